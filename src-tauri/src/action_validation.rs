@@ -0,0 +1,425 @@
+use crate::gemini::AiAction;
+use crate::obs_state::ObsState;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// What happens to a number outside its field's range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutOfRange {
+    Clamp,
+    Reject,
+}
+
+enum Kind {
+    Text,
+    Bool,
+    Object,
+    Number { min: f64, max: f64, default: OutOfRange },
+    OneOf(&'static [&'static str]),
+    /// A filter kind OBS reports as installed.
+    FilterKind,
+    /// The name of a media source or VLC input.
+    MediaInput,
+}
+
+struct Field {
+    key: &'static str,
+    kind: Kind,
+    required: bool,
+}
+
+const fn req(key: &'static str, kind: Kind) -> Field {
+    Field { key, kind, required: true }
+}
+
+const fn opt(key: &'static str, kind: Kind) -> Field {
+    Field { key, kind, required: false }
+}
+
+const fn num(min: f64, max: f64, default: OutOfRange) -> Kind {
+    Kind::Number { min, max, default }
+}
+
+const BLEND_MODES: &[&str] = &[
+    "OBS_BLEND_NORMAL",
+    "OBS_BLEND_ADDITIVE",
+    "OBS_BLEND_SUBTRACT",
+    "OBS_BLEND_SCREEN",
+    "OBS_BLEND_MULTIPLY",
+    "OBS_BLEND_LIGHTEN",
+    "OBS_BLEND_DARKEN",
+];
+
+const MONITOR_TYPES: &[&str] = &[
+    "OBS_MONITORING_TYPE_NONE",
+    "OBS_MONITORING_TYPE_MONITOR_ONLY",
+    "OBS_MONITORING_TYPE_MONITOR_AND_OUTPUT",
+];
+
+/// The OBS requests actions may send, with their parameters. Anything not
+/// listed here, or any parameter not listed for its request, is rejected.
+fn obs_request_schema(request_type: &str) -> Option<Vec<Field>> {
+    use Kind::*;
+    use OutOfRange::*;
+    const INPUT: Field = req("inputName", Text);
+    const SOURCE: Field = req("sourceName", Text);
+    const FILTER: Field = req("filterName", Text);
+    const SCENE: Field = opt("sceneName", Text);
+    const MEDIA: Field = req("inputName", MediaInput);
+    let fields = match request_type {
+        "SetInputVolume" => vec![INPUT, req("inputVolumeDb", num(-100.0, 26.0, Clamp))],
+        "SetInputMute" => vec![INPUT, req("inputMuted", Bool)],
+        "ToggleInputMute" => vec![INPUT],
+        "SetInputAudioMonitorType" => vec![INPUT, req("monitorType", OneOf(MONITOR_TYPES))],
+        "SetInputAudioBalance" => vec![INPUT, req("inputAudioBalance", num(0.0, 1.0, Clamp))],
+        "SetInputAudioSyncOffset" => vec![INPUT, req("inputAudioSyncOffset", num(-950.0, 20000.0, Reject))],
+        "SetInputAudioTracks" => vec![INPUT, req("inputAudioTracks", Object)],
+        "CreateSourceFilter" => vec![
+            SOURCE,
+            FILTER,
+            req("filterKind", FilterKind),
+            opt("filterSettings", Object),
+            opt("filterIndex", num(0.0, 100.0, Clamp)),
+        ],
+        "SetSourceFilterSettings" => vec![SOURCE, FILTER, req("filterSettings", Object), opt("overlay", Bool)],
+        "SetSourceFilterEnabled" => vec![SOURCE, FILTER, req("filterEnabled", Bool)],
+        "SetSourceFilterIndex" => vec![SOURCE, FILTER, req("filterIndex", num(0.0, 100.0, Clamp))],
+        "RemoveSourceFilter" => vec![SOURCE, FILTER],
+        "SetCurrentProgramScene" => vec![req("sceneName", Text)],
+        "SetCurrentSceneTransition" => vec![req("transitionName", Text)],
+        "SetCurrentSceneTransitionDuration" => vec![req("transitionDuration", num(50.0, 20000.0, Clamp))],
+        "SetSceneItemEnabled" => vec![SCENE, SOURCE, req("sceneItemEnabled", Bool)],
+        "SetSceneItemLocked" => vec![SCENE, SOURCE, req("sceneItemLocked", Bool)],
+        "SetSceneItemIndex" => vec![SCENE, SOURCE, req("sceneItemIndex", num(0.0, 1000.0, Clamp))],
+        "SetSceneItemTransform" => vec![SCENE, SOURCE, req("sceneItemTransform", Object)],
+        "SetSceneItemBlendMode" => vec![SCENE, SOURCE, req("sceneItemBlendMode", OneOf(BLEND_MODES))],
+        "RemoveSceneItem" => vec![SCENE, SOURCE],
+        "DuplicateSceneItem" => vec![SCENE, SOURCE, opt("destinationSceneName", Text)],
+        "StartStream" | "StopStream" | "StartRecord" | "StopRecord" | "PauseRecord" | "ResumeRecord" => Vec::new(),
+        "TriggerHotkeyByName" => vec![req("hotkeyName", Text)],
+        "TriggerMediaInputAction" => vec![MEDIA, req("mediaAction", OneOf(crate::media::MEDIA_ACTIONS))],
+        "SetMediaInputCursor" => vec![MEDIA, req("mediaCursor", num(0.0, 86_400_000.0, Clamp))],
+        _ => return None,
+    };
+    Some(fields)
+}
+
+fn windows_audio_schema(request_type: &str) -> Option<Vec<Field>> {
+    use Kind::*;
+    let fields = match request_type {
+        "set_volume" => vec![req("deviceId", Text), req("volume", num(0.0, 1.0, OutOfRange::Clamp))],
+        "set_mute" => vec![req("deviceId", Text), req("muted", Bool)],
+        _ => return None,
+    };
+    Some(fields)
+}
+
+/// Checks an action against its schema before it runs. Returns the action
+/// with out-of-range numbers clamped where that's the field's mode, or the
+/// reason it's rejected. `modes` overrides a field's default mode, keyed
+/// "RequestType.field".
+pub fn validate(
+    action: &AiAction,
+    obs_state: &ObsState,
+    modes: &HashMap<String, OutOfRange>,
+) -> Result<AiAction, String> {
+    let fields = match action.action_type.as_str() {
+        "obs_request" => obs_request_schema(&action.request_type)
+            .ok_or_else(|| format!("'{}' is not an allowed OBS request", action.request_type))?,
+        "windows_audio" => windows_audio_schema(&action.request_type)
+            .ok_or_else(|| format!("'{}' is not an allowed audio command", action.request_type))?,
+        "apply_preset" => {
+            if action.params["presetId"].as_str().map_or(true, str::is_empty) {
+                return Err("Missing presetId".into());
+            }
+            return Ok(action.clone());
+        }
+        "text" => {
+            if action.params["inputName"].as_str().map_or(true, |s| s.trim().is_empty()) {
+                return Err("Missing inputName".into());
+            }
+            if !action.params["text"].is_string() {
+                return Err("text must be a string".into());
+            }
+            return Ok(action.clone());
+        }
+        // Validated by their own handlers.
+        "layout" | "video_editor" | "video_edit" => return Ok(action.clone()),
+        other => return Err(format!("Unknown action_type: {}", other)),
+    };
+
+    let empty = serde_json::Map::new();
+    let params = match &action.params {
+        Value::Object(map) => map,
+        Value::Null => &empty,
+        _ => return Err("params must be an object".into()),
+    };
+    if let Some(unknown) = params.keys().find(|k| !fields.iter().any(|f| f.key == k.as_str())) {
+        return Err(format!("'{}' is not a parameter of {}", unknown, action.request_type));
+    }
+
+    let mut action = action.clone();
+    for field in &fields {
+        let Some(value) = params.get(field.key).filter(|v| !v.is_null()) else {
+            if field.required {
+                return Err(format!("Missing {}", field.key));
+            }
+            continue;
+        };
+        let fixed = check_field(field, value, obs_state, || {
+            modes
+                .get(&format!("{}.{}", action.request_type, field.key))
+                .copied()
+        })?;
+        if let Some(fixed) = fixed {
+            action.params[field.key] = fixed;
+        }
+    }
+    Ok(action)
+}
+
+/// Ok(Some) is a clamped replacement value.
+fn check_field(
+    field: &Field,
+    value: &Value,
+    obs_state: &ObsState,
+    mode: impl Fn() -> Option<OutOfRange>,
+) -> Result<Option<Value>, String> {
+    let key = field.key;
+    match &field.kind {
+        Kind::Text => match value.as_str() {
+            Some(s) if !s.trim().is_empty() => Ok(None),
+            _ => Err(format!("{} must be a non-empty string", key)),
+        },
+        Kind::Bool => value
+            .is_boolean()
+            .then_some(None)
+            .ok_or_else(|| format!("{} must be true or false", key)),
+        Kind::Object => value
+            .is_object()
+            .then_some(None)
+            .ok_or_else(|| format!("{} must be an object", key)),
+        Kind::Number { min, max, default } => {
+            let n = value
+                .as_f64()
+                .filter(|n| n.is_finite())
+                .ok_or_else(|| format!("{} must be a number", key))?;
+            if (*min..=*max).contains(&n) {
+                return Ok(None);
+            }
+            match mode().unwrap_or(*default) {
+                OutOfRange::Clamp => Ok(Some(json!(n.clamp(*min, *max)))),
+                OutOfRange::Reject => Err(format!("{} {} is outside {} to {}", key, n, min, max)),
+            }
+        }
+        Kind::OneOf(options) => match value.as_str() {
+            Some(s) if options.contains(&s) => Ok(None),
+            _ => Err(format!("{} must be one of {}", key, options.join(", "))),
+        },
+        Kind::FilterKind => {
+            let kind = value
+                .as_str()
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| format!("{} must be a non-empty string", key))?;
+            // An empty cache means OBS hasn't been asked yet; let OBS judge.
+            if obs_state.filter_kinds.is_empty() || obs_state.filter_kinds.iter().any(|k| k == kind) {
+                Ok(None)
+            } else {
+                Err(format!("Filter kind '{}' is not installed in OBS", kind))
+            }
+        }
+        Kind::MediaInput => {
+            let name = value
+                .as_str()
+                .filter(|s| !s.trim().is_empty())
+                .ok_or_else(|| format!("{} must be a non-empty string", key))?;
+            crate::media::check_media_input(obs_state, name).map(|_| None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obs_state::InputInfo;
+
+    fn request(request_type: &str, params: Value) -> AiAction {
+        AiAction {
+            safety: "safe".into(),
+            description: String::new(),
+            action_type: "obs_request".into(),
+            request_type: request_type.into(),
+            params,
+        }
+    }
+
+    fn check(request_type: &str, params: Value) -> Result<AiAction, String> {
+        validate(&request(request_type, params), &ObsState::default(), &HashMap::new())
+    }
+
+    fn action(action_type: &str, request_type: &str, params: Value) -> AiAction {
+        AiAction {
+            action_type: action_type.into(),
+            ..request(request_type, params)
+        }
+    }
+
+    #[test]
+    fn set_input_volume_clamps() {
+        let loud = check("SetInputVolume", json!({"inputName": "Mic", "inputVolumeDb": 40})).unwrap();
+        assert_eq!(loud.params["inputVolumeDb"], json!(26.0));
+        let quiet = check("SetInputVolume", json!({"inputName": "Mic", "inputVolumeDb": -300})).unwrap();
+        assert_eq!(quiet.params["inputVolumeDb"], json!(-100.0));
+        let in_range = check("SetInputVolume", json!({"inputName": "Mic", "inputVolumeDb": -6})).unwrap();
+        assert_eq!(in_range.params["inputVolumeDb"], json!(-6));
+        assert!(check("SetInputVolume", json!({"inputName": "Mic", "inputVolumeDb": "loud"})).is_err());
+    }
+
+    #[test]
+    fn set_input_audio_sync_offset_rejects() {
+        let params = json!({"inputName": "Mic", "inputAudioSyncOffset": 30000});
+        assert_eq!(
+            check("SetInputAudioSyncOffset", params).err().as_deref(),
+            Some("inputAudioSyncOffset 30000 is outside -950 to 20000")
+        );
+        assert!(check("SetInputAudioSyncOffset", json!({"inputName": "Mic", "inputAudioSyncOffset": 250})).is_ok());
+    }
+
+    #[test]
+    fn modes_override_the_field_default() {
+        let state = ObsState::default();
+        let mut modes = HashMap::new();
+        modes.insert("SetInputVolume.inputVolumeDb".to_string(), OutOfRange::Reject);
+        modes.insert("SetInputAudioSyncOffset.inputAudioSyncOffset".to_string(), OutOfRange::Clamp);
+
+        let loud = request("SetInputVolume", json!({"inputName": "Mic", "inputVolumeDb": 40}));
+        assert!(validate(&loud, &state, &modes).is_err());
+        let late = request("SetInputAudioSyncOffset", json!({"inputName": "Mic", "inputAudioSyncOffset": 30000}));
+        let clamped = validate(&late, &state, &modes).unwrap();
+        assert_eq!(clamped.params["inputAudioSyncOffset"], json!(20000.0));
+
+        // Keyed by request type: the same field name elsewhere keeps its default.
+        let balance = request("SetInputAudioBalance", json!({"inputName": "Mic", "inputAudioBalance": 2}));
+        assert_eq!(validate(&balance, &state, &modes).unwrap().params["inputAudioBalance"], json!(1.0));
+    }
+
+    #[test]
+    fn filter_kind_checks_the_cache() {
+        let params = |kind: &str| json!({"sourceName": "Mic", "filterName": "Gate", "filterKind": kind});
+        // Nothing cached yet: OBS decides.
+        assert!(check("CreateSourceFilter", params("made_up_filter")).is_ok());
+
+        let mut state = ObsState::default();
+        state.filter_kinds = vec!["noise_gate_filter".to_string(), "compressor_filter".to_string()];
+        let installed = request("CreateSourceFilter", params("noise_gate_filter"));
+        assert!(validate(&installed, &state, &HashMap::new()).is_ok());
+        let missing = request("CreateSourceFilter", params("made_up_filter"));
+        assert_eq!(
+            validate(&missing, &state, &HashMap::new()).err().as_deref(),
+            Some("Filter kind 'made_up_filter' is not installed in OBS")
+        );
+        assert!(validate(&request("CreateSourceFilter", params("")), &state, &HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn media_input_must_be_a_media_source() {
+        let mut state = ObsState::default();
+        state.inputs.insert("Intro".into(), InputInfo::placeholder("Intro", "ffmpeg_source"));
+        state.inputs.insert("Mic".into(), InputInfo::placeholder("Mic", "wasapi_input_capture"));
+        let play = |name: &str| {
+            request(
+                "TriggerMediaInputAction",
+                json!({"inputName": name, "mediaAction": "OBS_WEBSOCKET_MEDIA_INPUT_ACTION_PLAY"}),
+            )
+        };
+        assert!(validate(&play("Intro"), &state, &HashMap::new()).is_ok());
+        assert!(validate(&play("Mic"), &state, &HashMap::new()).is_err());
+        // Unknown inputs are left for OBS to report.
+        assert!(validate(&play("Outro"), &state, &HashMap::new()).is_ok());
+
+        let seek = request("SetMediaInputCursor", json!({"inputName": "Intro", "mediaCursor": -5}));
+        let seek = validate(&seek, &state, &HashMap::new()).unwrap();
+        assert_eq!(seek.params["mediaCursor"], json!(0.0));
+    }
+
+    #[test]
+    fn windows_audio() {
+        let state = ObsState::default();
+        let none = HashMap::new();
+        let set = action("windows_audio", "set_volume", json!({"deviceId": "{0.0.1}", "volume": 1.5}));
+        assert_eq!(validate(&set, &state, &none).unwrap().params["volume"], json!(1.0));
+        let mute = action("windows_audio", "set_mute", json!({"deviceId": "{0.0.1}", "muted": "yes"}));
+        assert!(validate(&mute, &state, &none).is_err());
+        let unknown = action("windows_audio", "set_default_device", json!({"deviceId": "{0.0.1}"}));
+        assert!(validate(&unknown, &state, &none).is_err());
+    }
+
+    #[test]
+    fn text() {
+        let state = ObsState::default();
+        let none = HashMap::new();
+        let ok = action("text", "", json!({"inputName": "Title", "text": "Back soon"}));
+        assert!(validate(&ok, &state, &none).is_ok());
+        let blank_input = action("text", "", json!({"inputName": "  ", "text": "Back soon"}));
+        assert_eq!(validate(&blank_input, &state, &none).err().as_deref(), Some("Missing inputName"));
+        let not_text = action("text", "", json!({"inputName": "Title", "text": 5}));
+        assert_eq!(validate(&not_text, &state, &none).err().as_deref(), Some("text must be a string"));
+    }
+
+    #[test]
+    fn apply_preset() {
+        let state = ObsState::default();
+        let none = HashMap::new();
+        assert!(validate(&action("apply_preset", "", json!({"presetId": "podcast"})), &state, &none).is_ok());
+        let missing = action("apply_preset", "", json!({"presetId": ""}));
+        assert_eq!(validate(&missing, &state, &none).err().as_deref(), Some("Missing presetId"));
+    }
+
+    #[test]
+    fn set_scene_item_transform() {
+        let params = json!({"sourceName": "Cam", "sceneItemTransform": {"positionX": 10}});
+        assert!(check("SetSceneItemTransform", params).is_ok());
+        let missing = check("SetSceneItemTransform", json!({"sourceName": "Cam"}));
+        assert_eq!(missing.err().as_deref(), Some("Missing sceneItemTransform"));
+        let not_object = check("SetSceneItemTransform", json!({"sourceName": "Cam", "sceneItemTransform": 3}));
+        assert!(not_object.is_err());
+    }
+
+    #[test]
+    fn set_scene_item_index_clamps() {
+        let action = check("SetSceneItemIndex", json!({"sourceName": "Cam", "sceneItemIndex": -4})).unwrap();
+        assert_eq!(action.params["sceneItemIndex"], json!(0.0));
+    }
+
+    #[test]
+    fn set_scene_item_blend_mode() {
+        let ok = json!({"sourceName": "Cam", "sceneItemBlendMode": "OBS_BLEND_SCREEN"});
+        assert!(check("SetSceneItemBlendMode", ok).is_ok());
+        let bad = json!({"sourceName": "Cam", "sceneItemBlendMode": "OBS_BLEND_GLOW"});
+        assert!(check("SetSceneItemBlendMode", bad).is_err());
+    }
+
+    #[test]
+    fn remove_scene_item() {
+        assert!(check("RemoveSceneItem", json!({"sceneName": "Main", "sourceName": "Cam"})).is_ok());
+        assert!(check("RemoveSceneItem", json!({"sceneName": "Main"})).is_err());
+    }
+
+    #[test]
+    fn duplicate_scene_item() {
+        let params = json!({"sourceName": "Cam", "destinationSceneName": "BRB"});
+        assert!(check("DuplicateSceneItem", params).is_ok());
+        let extra = json!({"sourceName": "Cam", "sceneItemId": 4});
+        assert_eq!(
+            check("DuplicateSceneItem", extra).err().as_deref(),
+            Some("'sceneItemId' is not a parameter of DuplicateSceneItem")
+        );
+    }
+
+    #[test]
+    fn unlisted_requests_are_rejected() {
+        assert!(check("CreateScene", json!({"sceneName": "X"})).is_err());
+    }
+}
@@ -1,15 +1,33 @@
-use crate::audio;
+use crate::action_validation::{self, OutOfRange};
+use crate::audio::{self, AudioDevice};
+use crate::device_matching;
 use crate::gemini::AiAction;
-use crate::obs_state::ObsState;
-use crate::obs_websocket::ObsConnection;
+use crate::i18n::t;
+use crate::layout;
+use crate::obs_state::{ObsState, SharedObsState};
+use crate::obs_websocket::{BatchExecution, ObsHandle};
 use crate::presets;
 use crate::store::LicenseState;
+use crate::text_sources;
+use crate::video_editor::{self, ExportRequest, Segment, SharedVideoEditorState};
+use crate::volume_guard::{self, VolumeGuard};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio::sync::{Mutex, RwLock};
 
-pub type SharedUndoStack = Arc<RwLock<Vec<UndoEntry>>>;
+pub type SharedUndoStack = Arc<RwLock<UndoStack>>;
+pub type SharedAiEditSession = Arc<Mutex<AiEditSession>>;
+
+pub const VIDEO_EDIT_ACTIONS: &[&str] = &["load_latest_recording", "cut_segment", "remove_silence", "export"];
+
+const SILENCE_PADDING: f64 = 0.15;
+/// Oldest undo entries fall off past this.
+const MAX_UNDO_ENTRIES: usize = 200;
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,6 +38,217 @@ pub struct UndoEntry {
     pub revert_params: Value,
 }
 
+impl UndoEntry {
+    /// Several reverts undone together as one step; `entries` are in the order
+    /// they were applied and get reverted last to first.
+    pub fn compound(description: String, entries: Vec<UndoEntry>) -> Self {
+        let reverts: Vec<Value> = entries
+            .into_iter()
+            .rev()
+            .filter_map(|e| serde_json::to_value(e).ok())
+            .collect();
+        Self {
+            description,
+            action_type: "compound".into(),
+            request_type: String::new(),
+            revert_params: Value::Array(reverts),
+        }
+    }
+}
+
+/// Ties together the undo entries created by one preset apply or chat turn
+/// so they can be undone as one step.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoGroup {
+    pub id: String,
+    pub label: String,
+}
+
+impl UndoGroup {
+    pub fn new(label: String) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            label,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StackEntry {
+    entry: UndoEntry,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<UndoGroup>,
+    at: u64,
+}
+
+/// The undo history, mirrored to disk on every change so it survives restarts.
+#[derive(Default)]
+pub struct UndoStack {
+    entries: Vec<StackEntry>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoHistoryItem {
+    pub group_id: Option<String>,
+    pub label: String,
+    pub entry_count: usize,
+    pub descriptions: Vec<String>,
+    pub at: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoGroupResult {
+    pub label: String,
+    pub undone: usize,
+    pub warnings: Vec<String>,
+}
+
+fn undo_file_path() -> std::path::PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("."))
+        .join("com.observe.app")
+        .join("undo_history.json")
+}
+
+impl UndoStack {
+    pub fn load() -> Self {
+        let Ok(content) = std::fs::read_to_string(undo_file_path()) else {
+            return Self::default();
+        };
+        match serde_json::from_str(&content) {
+            Ok(entries) => Self { entries },
+            Err(e) => {
+                log::warn!("Failed to parse undo history: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Snapshots the stack and writes it on a blocking thread, so callers
+    /// holding the stack's lock never wait on the disk. Writes are ordered;
+    /// one that finds a newer snapshot already on disk is dropped.
+    fn save(&self) {
+        static SAVE_SEQ: AtomicU64 = AtomicU64::new(0);
+        static WRITTEN_SEQ: std::sync::Mutex<u64> = std::sync::Mutex::new(0);
+
+        let entries = self.entries.clone();
+        let seq = SAVE_SEQ.fetch_add(1, Ordering::Relaxed) + 1;
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut written = WRITTEN_SEQ.lock().unwrap_or_else(|e| e.into_inner());
+            if *written > seq {
+                return;
+            }
+            let path = undo_file_path();
+            let result = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .map_err(|e| e.to_string())
+                .and_then(|()| serde_json::to_string(&entries).map_err(|e| e.to_string()))
+                .and_then(|json| {
+                    crate::settings::write_atomic(&path, json).map_err(|e| e.to_string())
+                });
+            match result {
+                Ok(()) => *written = seq,
+                Err(e) => log::warn!("Failed to save undo history: {}", e),
+            }
+        });
+    }
+
+    pub fn push(&mut self, entry: UndoEntry) {
+        self.push_in(entry, None);
+    }
+
+    pub fn push_in(&mut self, entry: UndoEntry, group: Option<&UndoGroup>) {
+        self.entries.push(StackEntry {
+            entry,
+            group: group.cloned(),
+            at: now_ms(),
+        });
+        if self.entries.len() > MAX_UNDO_ENTRIES {
+            let excess = self.entries.len() - MAX_UNDO_ENTRIES;
+            self.entries.drain(..excess);
+        }
+        self.save();
+    }
+
+    fn pop(&mut self) -> Option<UndoEntry> {
+        let entry = self.entries.pop()?.entry;
+        self.save();
+        Some(entry)
+    }
+
+    /// Removes every entry of a group (the newest one's when `group_id` is
+    /// None), newest first. An ungrouped newest entry comes back on its own.
+    fn pop_group(&mut self, group_id: Option<&str>) -> Option<(String, Vec<UndoEntry>)> {
+        let id = match group_id {
+            Some(id) => id.to_string(),
+            None => {
+                let last = self.entries.last()?;
+                match &last.group {
+                    Some(g) => g.id.clone(),
+                    None => {
+                        let last = self.entries.pop()?;
+                        self.save();
+                        return Some((last.entry.description.clone(), vec![last.entry]));
+                    }
+                }
+            }
+        };
+        let mut label = None;
+        let mut taken = Vec::new();
+        self.entries.retain(|e| match &e.group {
+            Some(g) if g.id == id => {
+                label.get_or_insert_with(|| g.label.clone());
+                taken.push(e.entry.clone());
+                false
+            }
+            _ => true,
+        });
+        let label = label?;
+        self.save();
+        taken.reverse();
+        Some((label, taken))
+    }
+
+    /// Newest first, with each group's entries folded into one item.
+    pub fn history(&self) -> Vec<UndoHistoryItem> {
+        let mut items: Vec<UndoHistoryItem> = Vec::new();
+        for e in self.entries.iter().rev() {
+            let group_id = e.group.as_ref().map(|g| g.id.clone());
+            if let Some(item) = items
+                .iter_mut()
+                .find(|i| group_id.is_some() && i.group_id == group_id)
+            {
+                item.entry_count += 1;
+                item.descriptions.push(e.entry.description.clone());
+                continue;
+            }
+            items.push(UndoHistoryItem {
+                label: e
+                    .group
+                    .as_ref()
+                    .map_or_else(|| e.entry.description.clone(), |g| g.label.clone()),
+                group_id,
+                entry_count: 1,
+                descriptions: vec![e.entry.description.clone()],
+                at: e.at,
+            });
+        }
+        items
+    }
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ActionResult {
@@ -31,10 +260,10 @@ pub struct ActionResult {
     pub pending_action: Option<AiAction>,
 }
 
-fn module_for_action(action: &AiAction) -> Option<&'static str> {
+pub(crate) fn module_for_action(action: &AiAction) -> Option<&'static str> {
     match action.action_type.as_str() {
         "apply_preset" => Some("presets"),
-        "video_editor" => Some("video-editor"),
+        "video_editor" | "video_edit" => Some("video-editor"),
         "obs_request" => {
             if action.params.get("filterKind")
                 .and_then(|v| v.as_str())
@@ -49,31 +278,87 @@ fn module_for_action(action: &AiAction) -> Option<&'static str> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_actions(
     actions: &[AiAction],
-    conn: &ObsConnection,
+    conn: &ObsHandle,
+    obs_state: &ObsState,
+    undo_stack: &SharedUndoStack,
+    license: &LicenseState,
+    guards: &HashMap<String, VolumeGuard>,
+    limits: &HashMap<String, OutOfRange>,
+    group: Option<&UndoGroup>,
+) -> Vec<ActionResult> {
+    run_actions(actions, conn, obs_state, undo_stack, license, guards, Some(limits), group).await
+}
+
+/// Like `execute_actions`, but for actions the user set up themselves (a
+/// timer's on_finish), which aren't held to the model's request allowlist.
+/// Volume guards, licensing and confirmation of dangerous actions still apply.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_user_actions(
+    actions: &[AiAction],
+    conn: &ObsHandle,
+    obs_state: &ObsState,
+    undo_stack: &SharedUndoStack,
+    license: &LicenseState,
+    guards: &HashMap<String, VolumeGuard>,
+    group: Option<&UndoGroup>,
+) -> Vec<ActionResult> {
+    run_actions(actions, conn, obs_state, undo_stack, license, guards, None, group).await
+}
+
+/// `limits` is None to skip schema validation.
+#[allow(clippy::too_many_arguments)]
+async fn run_actions(
+    actions: &[AiAction],
+    conn: &ObsHandle,
     obs_state: &ObsState,
     undo_stack: &SharedUndoStack,
     license: &LicenseState,
+    guards: &HashMap<String, VolumeGuard>,
+    limits: Option<&HashMap<String, OutOfRange>>,
+    group: Option<&UndoGroup>,
 ) -> Vec<ActionResult> {
-    let mut results = Vec::new();
+    let mut results: Vec<Option<ActionResult>> = Vec::with_capacity(actions.len());
+    // Consecutive plain OBS requests go out as one RequestBatch; anything
+    // else flushes the batch first so actions still apply in order.
+    let mut batch: Vec<(usize, Prepared)> = Vec::new();
 
     for action in actions {
-        if let Some(required_module) = module_for_action(action) {
-            if !license.owned_modules.contains(required_module) {
+        let slot = results.len();
+        results.push(None);
+        let validated = match limits {
+            Some(limits) => action_validation::validate(action, obs_state, limits),
+            None => Ok(action.clone()),
+        };
+        let action = match validated {
+            Ok(action) => action,
+            Err(reason) => {
+                results[slot] = Some(ActionResult {
+                    description: action.description.clone(),
+                    status: "rejected".into(),
+                    error: Some(format!("rejected: {}", reason)),
+                    undoable: false,
+                    pending_action: None,
+                });
+                continue;
+            }
+        };
+        let (guarded, clamp_note) = apply_volume_guard(&action, guards);
+        let action = guarded;
+        if let Some(required_module) = module_for_action(&action) {
+            if !license.has_module(required_module) {
                 let catalog = crate::store::get_module_catalog();
                 let module_name = catalog
                     .iter()
                     .find(|m| m.id == required_module)
                     .map(|m| m.name.as_str())
                     .unwrap_or(required_module);
-                results.push(ActionResult {
+                results[slot] = Some(ActionResult {
                     description: action.description.clone(),
                     status: "blocked".into(),
-                    error: Some(format!(
-                        "Requires '{}' module — purchase from the Store panel",
-                        module_name
-                    )),
+                    error: Some(t("error.module_required", &[("module", &module_name)])),
                     undoable: false,
                     pending_action: None,
                 });
@@ -81,79 +366,293 @@ pub async fn execute_actions(
             }
         }
 
-        let result = match action.safety.as_str() {
-            "dangerous" => ActionResult {
-                description: action.description.clone(),
-                status: "pending_confirmation".into(),
-                error: None,
-                undoable: false,
-                pending_action: Some(action.clone()),
-            },
-            "caution" => {
-                let undo = snapshot_for_undo(action, obs_state);
-                let exec_result = dispatch_action(action, conn).await;
-                if let (Ok(()), Some(undo_entry)) = (&exec_result, undo) {
-                    undo_stack.write().await.push(undo_entry);
-                }
-                match exec_result {
-                    Ok(()) => ActionResult {
-                        description: action.description.clone(),
-                        status: "executed".into(),
-                        error: None,
-                        undoable: true,
-                        pending_action: None,
-                    },
-                    Err(e) => ActionResult {
-                        description: action.description.clone(),
-                        status: "failed".into(),
-                        error: Some(e),
-                        undoable: false,
-                        pending_action: None,
-                    },
-                }
+        let undoable = match action.safety.as_str() {
+            "dangerous" => {
+                results[slot] = Some(ActionResult {
+                    description: action.description.clone(),
+                    status: "pending_confirmation".into(),
+                    error: None,
+                    undoable: false,
+                    pending_action: Some(action),
+                });
+                continue;
             }
-            _ => {
-                let exec_result = dispatch_action(action, conn).await;
-                match exec_result {
-                    Ok(()) => ActionResult {
-                        description: action.description.clone(),
-                        status: "executed".into(),
-                        error: None,
-                        undoable: false,
-                        pending_action: None,
-                    },
-                    Err(e) => ActionResult {
-                        description: action.description.clone(),
-                        status: "failed".into(),
-                        error: Some(e),
-                        undoable: false,
-                        pending_action: None,
-                    },
-                }
+            "caution" => true,
+            _ => false,
+        };
+
+        // OBS gets ids; the undo entry keeps the names the model used.
+        let named = action.clone();
+        let action = match resolve_ids(action, obs_state) {
+            Ok(action) => action,
+            Err(e) => {
+                results[slot] = Some(ActionResult {
+                    description: named.description,
+                    status: "failed".into(),
+                    error: Some(e),
+                    undoable: false,
+                    pending_action: None,
+                });
+                continue;
             }
         };
-        results.push(result);
+
+        if !is_batchable(&action) {
+            flush_batch(&mut batch, conn, undo_stack, group, &mut results).await;
+        }
+        let undo = match (undoable, action.action_type.as_str()) {
+            (false, _) => None,
+            (true, "layout") => layout::snapshot_for_undo(&action, conn).await,
+            (true, "text") => text_sources::snapshot_for_undo(&action, conn).await,
+            (true, _) => snapshot_for_undo(&named, obs_state),
+        };
+        let prepared = Prepared {
+            action,
+            clamp_note,
+            undo,
+            undoable,
+        };
+        if is_batchable(&prepared.action) {
+            batch.push((slot, prepared));
+        } else {
+            let outcome = dispatch_action(&prepared.action, conn).await;
+            results[slot] = Some(finish_action(prepared, outcome, undo_stack, group).await);
+        }
+    }
+    flush_batch(&mut batch, conn, undo_stack, group, &mut results).await;
+
+    results.into_iter().flatten().collect()
+}
+
+/// An action that passed the license and safety checks and is about to run.
+struct Prepared {
+    action: AiAction,
+    clamp_note: Option<String>,
+    undo: Option<UndoEntry>,
+    undoable: bool,
+}
+
+/// Plain requests that dispatch_action would send as-is.
+fn is_batchable(action: &AiAction) -> bool {
+    action.action_type == "obs_request"
+        && action.request_type != "TriggerHotkeyByName"
+        && !needs_scene_item_id(action)
+}
+
+/// Requests that address a scene item by id. The model only knows sources
+/// by name, so it sends sceneName + sourceName and the id is looked up.
+const SCENE_ITEM_REQUESTS: &[&str] = &[
+    "SetSceneItemEnabled",
+    "SetSceneItemLocked",
+    "SetSceneItemIndex",
+    "SetSceneItemTransform",
+    "SetSceneItemBlendMode",
+    "RemoveSceneItem",
+    "DuplicateSceneItem",
+];
+
+fn needs_scene_item_id(action: &AiAction) -> bool {
+    action.action_type == "obs_request"
+        && SCENE_ITEM_REQUESTS.contains(&action.request_type.as_str())
+        && action.params.get("sceneItemId").is_none()
+}
+
+/// Fills in ids the model can only name, from the cached state.
+fn resolve_ids(mut action: AiAction, obs_state: &ObsState) -> Result<AiAction, String> {
+    if needs_scene_item_id(&action) {
+        if let Some(params) = resolve_scene_item(&action.params, obs_state)? {
+            action.params = params;
+        }
+    }
+    Ok(action)
+}
+
+/// Replaces sourceName with the sceneItemId it has in the scene (the
+/// current scene when sceneName is left out). Ok(None) means the scene's
+/// items aren't cached; dispatch then asks OBS. A source that appears more
+/// than once in the scene is refused rather than guessed at.
+fn resolve_scene_item(params: &Value, state: &ObsState) -> Result<Option<Value>, String> {
+    let scene_name = params["sceneName"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .unwrap_or(state.current_scene.as_str());
+    if scene_name.is_empty() {
+        return Err("Missing sceneName".into());
+    }
+    let source_name = params["sourceName"].as_str().ok_or("Missing sourceName")?;
+    if !state.scenes.iter().any(|s| s.name == scene_name) {
+        return Err(format!("Scene \"{}\" not found", scene_name));
+    }
+    let Some(items) = state.scene_items.get(scene_name) else {
+        return Ok(None);
+    };
+    let ids: Vec<u64> = items
+        .iter()
+        .filter(|i| i.source_name == source_name)
+        .map(|i| i.scene_item_id)
+        .collect();
+    let id = match ids.as_slice() {
+        [] => return Err(format!("Source \"{}\" not found in scene \"{}\"", source_name, scene_name)),
+        [id] => *id,
+        _ => {
+            return Err(format!(
+                "\"{}\" appears {} times in scene \"{}\", so it's unclear which one is meant",
+                source_name,
+                ids.len(),
+                scene_name
+            ))
+        }
+    };
+    let mut params = params.clone();
+    params["sceneName"] = json!(scene_name);
+    params["sceneItemId"] = json!(id);
+    if let Some(obj) = params.as_object_mut() {
+        obj.remove("sourceName");
+    }
+    Ok(Some(params))
+}
+
+fn request_params(action: &AiAction) -> Option<Value> {
+    if action.params.as_object().map_or(true, |o| o.is_empty()) {
+        None
+    } else {
+        Some(action.params.clone())
+    }
+}
+
+async fn flush_batch(
+    batch: &mut Vec<(usize, Prepared)>,
+    conn: &ObsHandle,
+    undo_stack: &SharedUndoStack,
+    group: Option<&UndoGroup>,
+    results: &mut [Option<ActionResult>],
+) {
+    let outcomes: Vec<Result<(), String>> = match batch.as_slice() {
+        [] => return,
+        [(_, only)] => vec![dispatch_action(&only.action, conn).await],
+        _ => {
+            let requests = batch
+                .iter()
+                .map(|(_, p)| (p.action.request_type.clone(), request_params(&p.action)))
+                .collect();
+            match conn.send_batch(requests, BatchExecution::SerialRealtime, false).await {
+                Ok(outcomes) => outcomes.into_iter().map(|o| o.map(|_| ())).collect(),
+                Err(e) => vec![Err(e); batch.len()],
+            }
+        }
+    };
+    for ((slot, prepared), outcome) in batch.drain(..).zip(outcomes) {
+        results[slot] = Some(finish_action(prepared, outcome, undo_stack, group).await);
+    }
+}
+
+async fn finish_action(
+    prepared: Prepared,
+    outcome: Result<(), String>,
+    undo_stack: &SharedUndoStack,
+    group: Option<&UndoGroup>,
+) -> ActionResult {
+    let Prepared {
+        action,
+        clamp_note,
+        undo,
+        undoable,
+    } = prepared;
+    match outcome {
+        Ok(()) => {
+            if let Some(undo_entry) = undo {
+                undo_stack.write().await.push_in(undo_entry, group);
+            }
+            let description = match clamp_note {
+                Some(note) => format!("{} ({})", action.description, note),
+                None => action.description,
+            };
+            ActionResult {
+                description,
+                status: "executed".into(),
+                error: None,
+                undoable,
+                pending_action: None,
+            }
+        }
+        Err(e) => ActionResult {
+            description: action.description,
+            status: "failed".into(),
+            error: Some(e),
+            undoable: false,
+            pending_action: None,
+        },
     }
+}
 
-    results
+/// Caps AI-issued SetInputVolume requests at the input's volume guard.
+fn apply_volume_guard(action: &AiAction, guards: &HashMap<String, VolumeGuard>) -> (AiAction, Option<String>) {
+    let mut action = action.clone();
+    if action.action_type != "obs_request" || action.request_type != "SetInputVolume" {
+        return (action, None);
+    }
+    let (Some(input_name), Some(volume_db)) = (
+        action.params["inputName"].as_str().map(String::from),
+        action.params["inputVolumeDb"].as_f64(),
+    ) else {
+        return (action, None);
+    };
+    let clamp = volume_guard::clamp(guards, &input_name, volume_db);
+    if !clamp.clamped {
+        return (action, None);
+    }
+    action.params["inputVolumeDb"] = json!(clamp.applied_db);
+    let note = t(
+        "volume_guard.capped",
+        &[
+            ("applied", &format!("{:.1}", clamp.applied_db)),
+            ("requested", &format!("{:.1}", clamp.requested_db)),
+        ],
+    );
+    (action, Some(note))
 }
 
-fn dispatch_action<'a>(action: &'a AiAction, conn: &'a ObsConnection) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), String>> + Send + 'a>> {
+fn dispatch_action<'a>(action: &'a AiAction, conn: &'a ObsHandle) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), String>> + Send + 'a>> {
     Box::pin(dispatch_action_inner(action, conn))
 }
 
-async fn dispatch_action_inner(action: &AiAction, conn: &ObsConnection) -> Result<(), String> {
+/// Ok when every step of a preset applied, otherwise an error naming each
+/// step that failed and those that never ran after it.
+fn preset_outcome(name: &str, steps: &[AiAction], outcomes: Vec<Result<(), String>>) -> Result<(), String> {
+    let applied = outcomes.iter().filter(|o| o.is_ok()).count();
+    if applied == steps.len() {
+        return Ok(());
+    }
+    let mut outcomes = outcomes.into_iter();
+    let problems: Vec<String> = steps
+        .iter()
+        .filter_map(|step| match outcomes.next() {
+            Some(Ok(())) => None,
+            Some(Err(e)) => Some(format!("\"{}\" failed: {}", step.description, e)),
+            None => Some(format!("\"{}\" not run", step.description)),
+        })
+        .collect();
+    Err(format!(
+        "Preset \"{}\": {} of {} steps applied; {}",
+        name,
+        applied,
+        steps.len(),
+        problems.join("; ")
+    ))
+}
+
+async fn dispatch_action_inner(action: &AiAction, conn: &ObsHandle) -> Result<(), String> {
     match action.action_type.as_str() {
         "obs_request" => {
-            if action.request_type == "SetSceneItemEnabled" {
-                return dispatch_scene_item_enabled(action, conn).await;
+            if SCENE_ITEM_REQUESTS.contains(&action.request_type.as_str()) {
+                return dispatch_scene_item_request(action, conn).await;
             }
-            let params = if action.params.as_object().map_or(true, |o| o.is_empty()) {
-                None
-            } else {
-                Some(action.params.clone())
-            };
-            conn.send_request(&action.request_type, params).await?;
+            if action.request_type == "TriggerHotkeyByName" {
+                let name = action.params["hotkeyName"].as_str().ok_or("Missing hotkeyName")?;
+                let hotkeys = crate::obs_hotkeys::fetch(conn).await?;
+                return crate::obs_hotkeys::trigger(conn, &hotkeys, name).await;
+            }
+            conn.send_request(&action.request_type, request_params(action)).await?;
             Ok(())
         }
         "apply_preset" => {
@@ -170,46 +669,64 @@ async fn dispatch_action_inner(action: &AiAction, conn: &ObsConnection) -> Resul
             let preset = all_presets
                 .iter()
                 .find(|p| p.id == preset_id)
-                .ok_or_else(|| format!("Preset '{}' not found", preset_id))?;
+                .ok_or_else(|| t("error.preset_not_found", &[("id", &preset_id)]))?;
             let resolved = presets::resolve_preset_actions(&preset.actions, mic, desktop)?;
-            for a in &resolved {
-                dispatch_action(a, conn).await?;
-            }
-            Ok(())
+            let outcomes: Vec<Result<(), String>> = if resolved.len() > 1 && resolved.iter().all(is_batchable) {
+                let requests = resolved
+                    .iter()
+                    .map(|a| (a.request_type.clone(), request_params(a)))
+                    .collect();
+                conn.send_batch(requests, BatchExecution::SerialRealtime, true)
+                    .await?
+                    .into_iter()
+                    .map(|o| o.map(|_| ()))
+                    .collect()
+            } else {
+                let mut outcomes = Vec::with_capacity(resolved.len());
+                for a in &resolved {
+                    let outcome = dispatch_action(a, conn).await;
+                    let failed = outcome.is_err();
+                    outcomes.push(outcome);
+                    if failed {
+                        break;
+                    }
+                }
+                outcomes
+            };
+            preset_outcome(&preset.name, &resolved, outcomes)
         }
         "windows_audio" => {
             let params = action.params.clone();
             let request_type = action.request_type.clone();
             tokio::task::spawn_blocking(move || match request_type.as_str() {
                 "set_volume" => {
-                    let device_id = params["deviceId"]
-                        .as_str()
-                        .ok_or("Missing deviceId")?;
+                    let device_id = resolve_windows_device(&params)?;
                     let volume = params["volume"]
                         .as_f64()
                         .ok_or("Missing volume")? as f32;
-                    audio::set_device_volume(device_id, volume)
+                    audio::set_device_volume(&device_id, volume)
                 }
                 "set_mute" => {
-                    let device_id = params["deviceId"]
-                        .as_str()
-                        .ok_or("Missing deviceId")?;
+                    let device_id = resolve_windows_device(&params)?;
                     let muted = params["muted"]
                         .as_bool()
                         .ok_or("Missing muted")?;
-                    audio::set_device_mute(device_id, muted)
+                    audio::set_device_mute(&device_id, muted)
                 }
                 other => Err(format!("Unknown windows_audio command: {}", other)),
             })
             .await
             .map_err(|e| format!("Task failed: {}", e))?
         }
+        "layout" => layout::dispatch(action, conn).await,
+        "text" => text_sources::dispatch(action, conn).await,
         "video_editor" => Ok(()),
+        "video_edit" => Err("Video edit actions are only available from chat".into()),
         other => Err(format!("Unknown action_type: {}", other)),
     }
 }
 
-fn snapshot_for_undo(action: &AiAction, obs_state: &ObsState) -> Option<UndoEntry> {
+pub(crate) fn snapshot_for_undo(action: &AiAction, obs_state: &ObsState) -> Option<UndoEntry> {
     match action.action_type.as_str() {
         "obs_request" => match action.request_type.as_str() {
             "SetInputVolume" => {
@@ -285,6 +802,25 @@ fn snapshot_for_undo(action: &AiAction, obs_state: &ObsState) -> Option<UndoEntr
                     "sceneName": obs_state.current_scene
                 }),
             }),
+            "SetCurrentSceneTransition" => Some(UndoEntry {
+                description: format!("Revert to transition \"{}\"", obs_state.transition.current),
+                action_type: "obs_request".into(),
+                request_type: "SetCurrentSceneTransition".into(),
+                revert_params: json!({
+                    "transitionName": obs_state.transition.current
+                }),
+            }),
+            "SetCurrentSceneTransitionDuration" => {
+                let duration = obs_state.transition.duration_ms?;
+                Some(UndoEntry {
+                    description: format!("Revert transition duration to {}ms", duration),
+                    action_type: "obs_request".into(),
+                    request_type: "SetCurrentSceneTransitionDuration".into(),
+                    revert_params: json!({
+                        "transitionDuration": duration
+                    }),
+                })
+            }
             "CreateSourceFilter" => {
                 let source_name = action.params["sourceName"].as_str()?;
                 let filter_name = action.params["filterName"].as_str()?;
@@ -359,73 +895,466 @@ fn snapshot_for_undo(action: &AiAction, obs_state: &ObsState) -> Option<UndoEntr
     }
 }
 
-async fn dispatch_scene_item_enabled(action: &AiAction, conn: &ObsConnection) -> Result<(), String> {
-    let scene_name = action.params["sceneName"]
-        .as_str()
-        .ok_or("Missing sceneName")?;
-    let source_name = action.params["sourceName"]
-        .as_str()
-        .ok_or("Missing sourceName")?;
-    let enabled = action.params["sceneItemEnabled"]
-        .as_bool()
-        .ok_or("Missing sceneItemEnabled")?;
-
-    let items_data = conn
-        .send_request(
-            "GetSceneItemList",
-            Some(json!({"sceneName": scene_name})),
-        )
-        .await?;
-
-    let scene_item_id = items_data["sceneItems"]
-        .as_array()
-        .and_then(|arr| {
-            arr.iter().find_map(|item| {
-                if item["sourceName"].as_str() == Some(source_name) {
-                    item["sceneItemId"].as_u64()
-                } else {
-                    None
-                }
-            })
-        })
-        .ok_or_else(|| format!("Source \"{}\" not found in scene \"{}\"", source_name, scene_name))?;
-
-    conn.send_request(
-        "SetSceneItemEnabled",
-        Some(json!({
-            "sceneName": scene_name,
-            "sceneItemId": scene_item_id,
-            "sceneItemEnabled": enabled
-        })),
-    )
-    .await?;
+const DEVICE_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Endpoints from the last enumeration. A turn that adjusts several devices
+/// would otherwise enumerate them all again for every action.
+static DEVICE_CACHE: std::sync::Mutex<Option<(Instant, Vec<AudioDevice>)>> = std::sync::Mutex::new(None);
+
+/// The model often passes a device name instead of the endpoint id.
+fn resolve_windows_device(params: &Value) -> Result<String, String> {
+    let query = params["deviceId"].as_str().ok_or("Missing deviceId")?;
+    let cached = DEVICE_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .filter(|(at, _)| at.elapsed() < DEVICE_CACHE_TTL)
+        .map(|(_, devices)| devices.clone());
+    if let Some(devices) = cached {
+        if let Some(device) = device_matching::find_device_by_name(query, &devices) {
+            return Ok(device.id.clone());
+        }
+    }
+    // Not cached, expired, or no match: the device may have just appeared.
+    let devices = audio::enumerate_audio_devices()?;
+    let found = device_matching::find_device_by_name(query, &devices).map(|d| d.id.clone());
+    *DEVICE_CACHE.lock().unwrap_or_else(|e| e.into_inner()) = Some((Instant::now(), devices));
+    found.ok_or_else(|| format!("No unique audio device matches '{}'", query))
+}
+
+/// Sends a scene item request, looking the id up with GetSceneItemId when
+/// it wasn't resolved from state (undo, presets, uncached scenes).
+async fn dispatch_scene_item_request(action: &AiAction, conn: &ObsHandle) -> Result<(), String> {
+    let mut params = action.params.clone();
+    if params.get("sceneItemId").is_none() {
+        let scene_name = action.params["sceneName"]
+            .as_str()
+            .ok_or("Missing sceneName")?;
+        let source_name = action.params["sourceName"]
+            .as_str()
+            .ok_or("Missing sourceName")?;
+        let resp = conn
+            .send_request(
+                "GetSceneItemId",
+                Some(json!({"sceneName": scene_name, "sourceName": source_name})),
+            )
+            .await
+            .map_err(|_| format!("Source \"{}\" not found in scene \"{}\"", source_name, scene_name))?;
+        params["sceneItemId"] = resp["sceneItemId"]
+            .as_u64()
+            .map(|id| json!(id))
+            .ok_or("OBS returned no sceneItemId")?;
+        if let Some(obj) = params.as_object_mut() {
+            obj.remove("sourceName");
+        }
+    }
+    conn.send_request(&action.request_type, Some(params)).await?;
     Ok(())
 }
 
 pub async fn execute_single_action(
     action: &AiAction,
-    conn: &ObsConnection,
+    conn: &ObsHandle,
 ) -> Result<(), String> {
     dispatch_action(action, conn).await
 }
 
+/// Reverts one undo entry. App-side config goes back through its own module;
+/// everything else is an action `dispatch_action` understands.
+async fn revert(action: &AiAction, conn: &ObsHandle, app: &tauri::AppHandle) -> Result<(), String> {
+    match action.action_type.as_str() {
+        crate::ducking::CONFIG_UNDO_TYPE => crate::ducking::restore_config(app, &action.params).await,
+        _ => dispatch_action(action, conn).await,
+    }
+}
+
 pub async fn undo_last(
-    conn: &ObsConnection,
+    conn: &ObsHandle,
     undo_stack: &SharedUndoStack,
+    app: &tauri::AppHandle,
 ) -> Result<String, String> {
     let entry = {
         let mut stack = undo_stack.write().await;
-        stack.pop().ok_or_else(|| "Nothing to undo".to_string())?
+        stack.pop().ok_or_else(|| t("error.nothing_to_undo", &[]))?
     };
 
+    if entry.action_type == "compound" {
+        let reverts: Vec<UndoEntry> = serde_json::from_value(entry.revert_params.clone()).unwrap_or_default();
+        // Every part is tried; the ones that fail go back on the stack so
+        // undoing again can retry them.
+        let mut failed = Vec::new();
+        let mut errors = Vec::new();
+        for revert in reverts {
+            let undo_action = AiAction {
+                safety: "safe".into(),
+                description: revert.description.clone(),
+                action_type: revert.action_type.clone(),
+                request_type: revert.request_type.clone(),
+                params: revert.revert_params.clone(),
+            };
+            if let Err(e) = revert(&undo_action, conn, app).await {
+                errors.push(format!("{}: {}", revert.description, e));
+                failed.push(revert);
+            }
+        }
+        if errors.is_empty() {
+            return Ok(entry.description);
+        }
+        undo_stack.write().await.push(UndoEntry {
+            revert_params: json!(failed),
+            ..entry
+        });
+        return Err(errors.join("; "));
+    }
+
     let undo_action = AiAction {
         safety: "safe".into(),
         description: entry.description.clone(),
-        action_type: entry.action_type,
-        request_type: entry.request_type,
-        params: entry.revert_params,
+        action_type: entry.action_type.clone(),
+        request_type: entry.request_type.clone(),
+        params: entry.revert_params.clone(),
     };
 
-    dispatch_action(&undo_action, conn).await?;
+    if let Err(e) = revert(&undo_action, conn, app).await {
+        undo_stack.write().await.push(entry);
+        return Err(e);
+    }
     Ok(entry.description)
 }
+
+/// Reverts a whole group, newest entry first. Entries whose input, source
+/// or scene is gone, or whose revert fails, are skipped with a warning.
+pub async fn undo_group(
+    conn: &ObsHandle,
+    undo_stack: &SharedUndoStack,
+    obs_state: &SharedObsState,
+    app: &tauri::AppHandle,
+    group_id: Option<&str>,
+) -> Result<UndoGroupResult, String> {
+    let (label, entries) = undo_stack
+        .write()
+        .await
+        .pop_group(group_id)
+        .ok_or_else(|| t("error.nothing_to_undo", &[]))?;
+
+    let mut reverts = Vec::new();
+    for entry in entries {
+        if entry.action_type == "compound" {
+            let parts: Vec<UndoEntry> = serde_json::from_value(entry.revert_params).unwrap_or_default();
+            reverts.extend(parts);
+        } else {
+            reverts.push(entry);
+        }
+    }
+
+    let state = obs_state.read().await.clone();
+    let mut undone = 0;
+    let mut warnings = Vec::new();
+    for revert in reverts {
+        if let Some(missing) = missing_target(&revert.revert_params, &state) {
+            warnings.push(format!("Skipped \"{}\": '{}' no longer exists", revert.description, missing));
+            continue;
+        }
+        let undo_action = AiAction {
+            safety: "safe".into(),
+            description: revert.description.clone(),
+            action_type: revert.action_type,
+            request_type: revert.request_type,
+            params: revert.revert_params,
+        };
+        match revert(&undo_action, conn, app).await {
+            Ok(()) => undone += 1,
+            Err(e) => warnings.push(format!("Failed to revert \"{}\": {}", revert.description, e)),
+        }
+    }
+    Ok(UndoGroupResult {
+        label,
+        undone,
+        warnings,
+    })
+}
+
+/// The first input, source or scene named in `params` that OBS no longer has.
+fn missing_target(params: &Value, state: &ObsState) -> Option<String> {
+    let is_scene = |name: &str| state.scenes.iter().any(|s| s.name == name);
+    if let Some(name) = params["inputName"].as_str() {
+        if !state.inputs.contains_key(name) {
+            return Some(name.to_string());
+        }
+    }
+    if let Some(name) = params["sourceName"].as_str() {
+        if !state.inputs.contains_key(name) && !is_scene(name) {
+            return Some(name.to_string());
+        }
+    }
+    if let Some(name) = params["sceneName"].as_str() {
+        if !is_scene(name) {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+// --- Backend video editing ---
+
+/// Edit state the AI builds up across video_edit actions: the loaded file and
+/// the segments still kept.
+#[derive(Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiEditSession {
+    pub source_path: Option<String>,
+    pub duration: f64,
+    pub segments: Vec<Segment>,
+}
+
+impl AiEditSession {
+    fn kept_duration(&self) -> f64 {
+        self.segments.iter().map(|s| s.end - s.start).sum()
+    }
+
+    fn remove_range(&mut self, start: f64, end: f64) {
+        let mut kept = Vec::new();
+        for seg in self.segments.drain(..) {
+            if end <= seg.start || start >= seg.end {
+                kept.push(seg);
+                continue;
+            }
+            if start > seg.start {
+                kept.push(Segment { start: seg.start, end: start, deleted: false });
+            }
+            if end < seg.end {
+                kept.push(Segment { start: end, end: seg.end, deleted: false });
+            }
+        }
+        kept.retain(|s| s.end - s.start > 0.05);
+        self.segments = kept;
+    }
+}
+
+fn param_f64(params: &Value, key: &str) -> Option<f64> {
+    params.get(key).and_then(|v| v.as_f64())
+}
+
+/// Runs one backend video_edit action and returns a short human-readable result.
+pub async fn execute_video_edit_action(
+    action: &AiAction,
+    session: &SharedAiEditSession,
+    editor: &SharedVideoEditorState,
+    obs_state: &SharedObsState,
+    app: &tauri::AppHandle,
+) -> Result<String, String> {
+    let params = &action.params;
+    let detail = match action.request_type.as_str() {
+        "load_latest_recording" => {
+            let dir = obs_state.read().await.record_settings.record_directory.clone();
+            if dir.is_empty() {
+                return Err("No recording directory configured in OBS".into());
+            }
+            let latest = tokio::task::spawn_blocking(move || video_editor::list_video_files(&dir))
+                .await
+                .map_err(|e| format!("Task failed: {}", e))??
+                .into_iter()
+                .next()
+                .ok_or("No recordings found")?;
+            let ffprobe = editor
+                .lock()
+                .await
+                .ffprobe_path
+                .clone()
+                .ok_or("ffprobe not found. Run detect_ffmpeg first.")?;
+            let duration = video_editor::probe_duration(&ffprobe, &latest.path).await?;
+
+            let mut s = session.lock().await;
+            *s = AiEditSession {
+                source_path: Some(latest.path.clone()),
+                duration,
+                segments: vec![Segment { start: 0.0, end: duration, deleted: false }],
+            };
+            format!("Loaded {} ({:.0}s)", latest.name, duration)
+        }
+        "cut_segment" => {
+            let mut s = session.lock().await;
+            if s.source_path.is_none() {
+                return Err("No video loaded; use load_latest_recording first".into());
+            }
+            let start = param_f64(params, "start").unwrap_or(0.0).max(0.0);
+            let end = param_f64(params, "end").unwrap_or(s.duration).min(s.duration);
+            if end <= start {
+                return Err(format!("Invalid cut range {:.1}s-{:.1}s", start, end));
+            }
+            s.remove_range(start, end);
+            format!("Cut {:.1}s-{:.1}s, {:.0}s remaining", start, end, s.kept_duration())
+        }
+        "remove_silence" => {
+            let source = session
+                .lock()
+                .await
+                .source_path
+                .clone()
+                .ok_or("No video loaded; use load_latest_recording first")?;
+            let ffmpeg = editor
+                .lock()
+                .await
+                .ffmpeg_path
+                .clone()
+                .ok_or("FFmpeg not found. Run detect_ffmpeg first.")?;
+            let noise_db = param_f64(params, "noise_db").unwrap_or(-35.0);
+            let min_duration = param_f64(params, "min_duration").unwrap_or(1.0).max(0.2);
+            let ranges = video_editor::detect_silence_ranges(&ffmpeg, &source, noise_db, min_duration, None).await?;
+
+            let mut s = session.lock().await;
+            let before = s.kept_duration();
+            for (start, end) in &ranges {
+                let (start, end) = (start + SILENCE_PADDING, end - SILENCE_PADDING);
+                if end > start {
+                    s.remove_range(start, end);
+                }
+            }
+            format!(
+                "Removed {} silent section(s), {:.0}s shorter",
+                ranges.len(),
+                before - s.kept_duration()
+            )
+        }
+        "export" => {
+            let s = session.lock().await.clone();
+            let source_path = s.source_path.ok_or("No video loaded; use load_latest_recording first")?;
+            if s.segments.is_empty() {
+                return Err("Nothing left to export".into());
+            }
+            let recording_dir = obs_state.read().await.record_settings.record_directory.clone();
+
+            let preset = match params.get("preset").and_then(|v| v.as_str()) {
+                Some(name) => {
+                    let name = name.to_string();
+                    tokio::task::spawn_blocking(video_editor::load_export_presets)
+                        .await
+                        .map_err(|e| format!("Task failed: {}", e))?
+                        .into_iter()
+                        .find(|p| p.name == name)
+                        .ok_or_else(|| format!("Export preset '{}' not found", name))?
+                }
+                None => video_editor::ExportPreset {
+                    name: "ai".into(),
+                    format: "mp4".into(),
+                    video_codec: params["codec"].as_str().unwrap_or("libx264").to_string(),
+                    quality: params["quality"].as_str().unwrap_or("medium").to_string(),
+                    resolution: None,
+                    audio_track_mode: None,
+                    audio_tracks: Vec::new(),
+                    destination_template: String::new(),
+                },
+            };
+            let output = video_editor::expand_destination_template(
+                &preset.destination_template,
+                &recording_dir,
+                &source_path,
+                &preset,
+            );
+            if let Some(parent) = output.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create output folder: {}", e))?;
+            }
+            let output_path = output.to_string_lossy().to_string();
+            let request = ExportRequest {
+                source_path,
+                segments: s.segments,
+                overlays: Vec::new(),
+                output_path: output_path.clone(),
+                format: preset.format,
+                video_codec: preset.video_codec,
+                quality: preset.quality,
+                resolution: preset.resolution,
+                captions: None,
+                audio_narration: None,
+                audio_track_mode: preset.audio_track_mode,
+                audio_tracks: preset.audio_tracks,
+                aspect: None,
+                crop_keyframes: Vec::new(),
+                audio_normalize: None,
+                gif: None,
+                chapters: Vec::new(),
+                subtitle_path: None,
+            };
+            let job_id = video_editor::start_export(editor, app.clone(), request).await?;
+            format!("Export started, job id {} → {}", job_id, output_path)
+        }
+        other => return Err(format!("Unknown video_edit action: {}", other)),
+    };
+
+    let snapshot = session.lock().await.clone();
+    let _ = app.emit("video-editor://ai-session", &snapshot);
+    Ok(detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obs_state::{SceneInfo, SceneItemInfo};
+
+    fn item(source_name: &str, scene_item_id: u64) -> SceneItemInfo {
+        SceneItemInfo {
+            source_name: source_name.into(),
+            source_kind: "image_source".into(),
+            scene_item_id,
+            enabled: true,
+            transform: Value::Null,
+        }
+    }
+
+    fn state() -> ObsState {
+        let mut state = ObsState {
+            current_scene: "Main".into(),
+            scenes: vec![
+                SceneInfo { name: "Main".into(), index: 0 },
+                SceneInfo { name: "BRB".into(), index: 1 },
+                SceneInfo { name: "Uncached".into(), index: 2 },
+            ],
+            ..Default::default()
+        };
+        state
+            .scene_items
+            .insert("Main".into(), vec![item("Webcam", 3), item("Logo", 4), item("Logo", 7)]);
+        state.scene_items.insert("BRB".into(), vec![item("Logo", 9)]);
+        state
+    }
+
+    #[test]
+    fn resolves_in_the_current_scene_by_default() {
+        let params = resolve_scene_item(&json!({"sourceName": "Webcam", "sceneItemEnabled": false}), &state())
+            .unwrap()
+            .unwrap();
+        assert_eq!(params, json!({"sceneName": "Main", "sceneItemId": 3, "sceneItemEnabled": false}));
+    }
+
+    #[test]
+    fn resolves_in_a_named_scene() {
+        let params = resolve_scene_item(&json!({"sceneName": "BRB", "sourceName": "Logo"}), &state())
+            .unwrap()
+            .unwrap();
+        assert_eq!(params["sceneItemId"], json!(9));
+    }
+
+    #[test]
+    fn rejects_a_missing_scene() {
+        let err = resolve_scene_item(&json!({"sceneName": "Gone", "sourceName": "Logo"}), &state()).unwrap_err();
+        assert!(err.contains("Scene \"Gone\" not found"), "{}", err);
+    }
+
+    #[test]
+    fn rejects_a_missing_source() {
+        let err = resolve_scene_item(&json!({"sourceName": "Chat"}), &state()).unwrap_err();
+        assert!(err.contains("Source \"Chat\" not found in scene \"Main\""), "{}", err);
+    }
+
+    #[test]
+    fn refuses_to_guess_between_duplicate_names() {
+        let err = resolve_scene_item(&json!({"sourceName": "Logo"}), &state()).unwrap_err();
+        assert!(err.contains("appears 2 times"), "{}", err);
+    }
+
+    #[test]
+    fn defers_to_obs_when_the_scene_is_not_cached() {
+        let params = json!({"sceneName": "Uncached", "sourceName": "Logo"});
+        assert_eq!(resolve_scene_item(&params, &state()).unwrap(), None);
+    }
+}
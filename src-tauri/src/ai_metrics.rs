@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+pub type SharedAiMetrics = Arc<Mutex<AiMetrics>>;
+
+const MAX_SAMPLES: usize = 200;
+
+/// Where the time went in the model calls of one chat turn. Accumulates
+/// across the follow-up calls of a function-calling turn.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelTiming {
+    pub prompt_ms: u64,
+    pub network_ms: u64,
+    pub parse_ms: u64,
+    pub model_calls: u32,
+}
+
+impl ModelTiming {
+    pub fn thinking_ms(&self) -> u64 {
+        self.prompt_ms + self.network_ms + self.parse_ms
+    }
+}
+
+/// Payload of `ai://timing`, one per chat turn.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnTiming {
+    #[serde(flatten)]
+    pub model: ModelTiming,
+    pub thinking_ms: u64,
+    pub apply_ms: u64,
+    pub action_count: u32,
+    pub slowest_action_ms: u64,
+    pub total_ms: u64,
+}
+
+/// Times the OBS side of a turn. `Instant` pairs only; nothing allocates.
+#[derive(Default)]
+pub struct ApplyTimer {
+    total_ms: u64,
+    count: u32,
+    slowest_ms: u64,
+}
+
+impl ApplyTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, started: Instant, metrics: &SharedAiMetrics) {
+        let ms = started.elapsed().as_millis() as u64;
+        self.total_ms += ms;
+        self.count += 1;
+        self.slowest_ms = self.slowest_ms.max(ms);
+        if let Ok(mut m) = metrics.lock() {
+            push(&mut m.action, ms);
+        }
+    }
+
+    pub fn finish(&self, model: ModelTiming, turn_started: Instant) -> TurnTiming {
+        TurnTiming {
+            model,
+            thinking_ms: model.thinking_ms(),
+            apply_ms: self.total_ms,
+            action_count: self.count,
+            slowest_action_ms: self.slowest_ms,
+            total_ms: turn_started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Percentiles {
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AiPerformanceReport {
+    pub turns: usize,
+    pub last_turn: Option<TurnTiming>,
+    pub total: Percentiles,
+    pub thinking: Percentiles,
+    pub prompt: Percentiles,
+    pub network: Percentiles,
+    pub parse: Percentiles,
+    pub apply: Percentiles,
+    pub per_action: Percentiles,
+}
+
+#[derive(Default)]
+pub struct AiMetrics {
+    last_turn: Option<TurnTiming>,
+    total: VecDeque<u64>,
+    thinking: VecDeque<u64>,
+    prompt: VecDeque<u64>,
+    network: VecDeque<u64>,
+    parse: VecDeque<u64>,
+    apply: VecDeque<u64>,
+    action: VecDeque<u64>,
+}
+
+fn push(samples: &mut VecDeque<u64>, ms: u64) {
+    if samples.len() == MAX_SAMPLES {
+        samples.pop_front();
+    }
+    samples.push_back(ms);
+}
+
+fn percentiles(samples: &VecDeque<u64>) -> Percentiles {
+    let mut sorted: Vec<u64> = samples.iter().copied().collect();
+    sorted.sort_unstable();
+    let at = |p: f64| -> u64 {
+        if sorted.is_empty() {
+            return 0;
+        }
+        sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+    };
+    Percentiles {
+        p50_ms: at(0.5),
+        p95_ms: at(0.95),
+        max_ms: sorted.last().copied().unwrap_or(0),
+    }
+}
+
+impl AiMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_turn(&mut self, turn: &TurnTiming) {
+        self.last_turn = Some(*turn);
+        push(&mut self.total, turn.total_ms);
+        push(&mut self.thinking, turn.thinking_ms);
+        push(&mut self.prompt, turn.model.prompt_ms);
+        push(&mut self.network, turn.model.network_ms);
+        push(&mut self.parse, turn.model.parse_ms);
+        push(&mut self.apply, turn.apply_ms);
+    }
+
+    pub fn report(&self) -> AiPerformanceReport {
+        AiPerformanceReport {
+            turns: self.total.len(),
+            last_turn: self.last_turn,
+            total: percentiles(&self.total),
+            thinking: percentiles(&self.thinking),
+            prompt: percentiles(&self.prompt),
+            network: percentiles(&self.network),
+            parse: percentiles(&self.parse),
+            apply: percentiles(&self.apply),
+            per_action: percentiles(&self.action),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_ai_performance_metrics(
+    metrics: tauri::State<'_, SharedAiMetrics>,
+) -> Result<AiPerformanceReport, String> {
+    let m = metrics.lock().map_err(|e| format!("Metrics lock poisoned: {}", e))?;
+    Ok(m.report())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Adds a few real milliseconds to a timing field, the way the providers
+    /// accumulate theirs.
+    fn spend(field: &mut u64) {
+        let started = Instant::now();
+        std::thread::sleep(Duration::from_millis(3));
+        *field += started.elapsed().as_millis() as u64;
+    }
+
+    #[test]
+    fn timing_payload_covers_thinking_and_applying() {
+        let metrics: SharedAiMetrics = Arc::new(Mutex::new(AiMetrics::new()));
+        let turn_started = Instant::now();
+
+        // Thinking: a function-calling turn with a follow-up call.
+        let mut model = ModelTiming::default();
+        spend(&mut model.prompt_ms);
+        for _ in 0..2 {
+            model.model_calls += 1;
+            spend(&mut model.network_ms);
+            spend(&mut model.parse_ms);
+        }
+
+        // Applying: two actions against OBS.
+        let mut apply = ApplyTimer::new();
+        for _ in 0..2 {
+            let started = Instant::now();
+            std::thread::sleep(Duration::from_millis(3));
+            apply.record(started, &metrics);
+        }
+
+        let timing = apply.finish(model, turn_started);
+        metrics.lock().unwrap().record_turn(&timing);
+
+        let payload = serde_json::to_value(timing).unwrap();
+        let fields = [
+            "promptMs",
+            "networkMs",
+            "parseMs",
+            "modelCalls",
+            "thinkingMs",
+            "applyMs",
+            "actionCount",
+            "slowestActionMs",
+            "totalMs",
+        ];
+        assert_eq!(payload.as_object().unwrap().len(), fields.len());
+        for field in fields {
+            let value = payload[field].as_u64().unwrap_or_else(|| panic!("{} missing from {}", field, payload));
+            assert!(value > 0, "{} is zero in {}", field, payload);
+        }
+        assert_eq!(payload["modelCalls"], 2);
+        assert_eq!(payload["actionCount"], 2);
+        assert_eq!(timing.thinking_ms, model.prompt_ms + model.network_ms + model.parse_ms);
+        assert!(timing.slowest_action_ms <= timing.apply_ms);
+        assert!(timing.total_ms >= timing.thinking_ms + timing.apply_ms);
+
+        let report = metrics.lock().unwrap().report();
+        assert_eq!(report.turns, 1);
+        assert_eq!(report.per_action.max_ms, timing.slowest_action_ms);
+        assert_eq!(report.total.max_ms, timing.total_ms);
+    }
+}
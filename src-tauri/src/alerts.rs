@@ -0,0 +1,600 @@
+use crate::i18n::t;
+use crate::obs_state::SharedObsState;
+use crate::settings::SharedSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+pub const ALERT_KINDS: &[&str] = &[
+    "frame-drop",
+    "obs-disconnected",
+    "mic-lost",
+    "clipping",
+    "mic-silent",
+    "mic-clipping",
+    "record-stopped",
+    "stream-disconnected",
+];
+
+// Frame-drop and clipping conditions tend to persist; one alert per kind
+// within this window is enough.
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+static LAST_FIRED: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AlertRule {
+    pub enabled: bool,
+    pub sound: bool,
+    pub toast: bool,
+    pub speak: bool,
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sound: true,
+            toast: true,
+            speak: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AlertSettings {
+    pub do_not_disturb: bool,
+    pub only_when_hidden: bool,
+    pub output_device_id: Option<String>,
+    pub rules: HashMap<String, AlertRule>,
+}
+
+impl Default for AlertSettings {
+    fn default() -> Self {
+        Self {
+            do_not_disturb: false,
+            only_when_hidden: true,
+            output_device_id: None,
+            rules: HashMap::new(),
+        }
+    }
+}
+
+impl AlertSettings {
+    pub fn rule(&self, kind: &str) -> AlertRule {
+        self.rules.get(kind).cloned().unwrap_or_default()
+    }
+}
+
+struct Alert {
+    kind: String,
+    title: String,
+    message: String,
+}
+
+fn render(kind: &str, args: &[(&str, &dyn Display)]) -> Alert {
+    Alert {
+        kind: kind.to_string(),
+        title: t(&format!("alert.{}.title", kind), args),
+        message: t(&format!("alert.{}.message", kind), args),
+    }
+}
+
+fn window_hidden(app: &AppHandle) -> bool {
+    match app.get_webview_window("main") {
+        Some(w) => {
+            !w.is_visible().unwrap_or(false) || w.is_minimized().unwrap_or(false)
+        }
+        None => true,
+    }
+}
+
+fn cooled_down(kind: &str) -> bool {
+    let mut last = LAST_FIRED
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap();
+    let now = Instant::now();
+    match last.get(kind) {
+        Some(t) if now.duration_since(*t) < COOLDOWN => false,
+        _ => {
+            last.insert(kind.to_string(), now);
+            true
+        }
+    }
+}
+
+/// Output devices OBS is capturing as desktop audio. Alerts must never be
+/// played on these or they end up in the stream/recording.
+async fn captured_outputs(app: &AppHandle) -> Vec<String> {
+    let Some(obs_state) = app.try_state::<SharedObsState>() else {
+        return Vec::new();
+    };
+    let state = obs_state.read().await;
+    state
+        .inputs
+        .values()
+        .filter(|i| i.kind.contains("wasapi_output_capture"))
+        .map(|i| i.device_id.clone())
+        .collect()
+}
+
+/// Raises a native alert for `kind` if its rule is enabled, do-not-disturb is
+/// off and (by default) the main window is hidden. Text is rendered from
+/// `alert.<kind>.title` / `alert.<kind>.message`.
+pub fn notify(app: &AppHandle, kind: &str, args: &[(&str, &dyn Display)]) {
+    let alert = render(kind, args);
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let Some(settings) = app.try_state::<SharedSettings>() else {
+            return;
+        };
+        let config = settings.read().await.alerts.clone();
+        let rule = config.rule(&alert.kind);
+        if config.do_not_disturb || !rule.enabled {
+            return;
+        }
+        if config.only_when_hidden && !window_hidden(&app) {
+            return;
+        }
+        if !cooled_down(&alert.kind) {
+            return;
+        }
+        let captured = captured_outputs(&app).await;
+        let _ = tauri::async_runtime::spawn_blocking(move || {
+            for e in deliver(&alert, &rule, config.output_device_id.as_deref(), &captured) {
+                log::warn!("Alert '{}': {}", alert.kind, e);
+            }
+        })
+        .await;
+    });
+}
+
+fn deliver(
+    alert: &Alert,
+    rule: &AlertRule,
+    output_device_id: Option<&str>,
+    captured: &[String],
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    if rule.toast {
+        if let Err(e) = show_toast(&alert.title, &alert.message) {
+            errors.push(t("alert.delivery.toast_failed", &[("error", &e)]));
+        }
+    }
+    if rule.sound {
+        if let Err(e) = play_chime(&alert.kind, output_device_id, captured) {
+            errors.push(t("alert.delivery.sound_failed", &[("error", &e)]));
+        }
+    }
+    if rule.speak {
+        if let Err(e) = speak(&alert.message, output_device_id, captured) {
+            errors.push(t("alert.delivery.speech_failed", &[("error", &e)]));
+        }
+    }
+    errors
+}
+
+/// Short chime, distinct per alert kind, bundled as 16-bit mono WAV.
+fn chime(kind: &str) -> &'static [u8] {
+    match kind {
+        "obs-disconnected" => include_bytes!("../sounds/alert-obs-disconnected.wav"),
+        "mic-lost" => include_bytes!("../sounds/alert-mic-lost.wav"),
+        "clipping" => include_bytes!("../sounds/alert-clipping.wav"),
+        "mic-silent" => include_bytes!("../sounds/alert-mic-silent.wav"),
+        "mic-clipping" => include_bytes!("../sounds/alert-mic-clipping.wav"),
+        "record-stopped" => include_bytes!("../sounds/alert-record-stopped.wav"),
+        "stream-disconnected" => include_bytes!("../sounds/alert-stream-disconnected.wav"),
+        _ => include_bytes!("../sounds/alert-frame-drop.wav"),
+    }
+}
+
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let ratio = from as f64 / to as f64;
+    let len = (samples.len() as f64 / ratio) as usize;
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx.min(samples.len() - 1)];
+            let b = samples[(idx + 1).min(samples.len() - 1)];
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Minimal RIFF reader for the 16-bit PCM files SAPI writes and the bundled
+/// chimes use; returns mono samples and the sample rate.
+fn parse_wav(bytes: &[u8]) -> Result<(Vec<f32>, u32), String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("Not a WAV file".into());
+    }
+    let u16_at = |p: usize| u16::from_le_bytes([bytes[p], bytes[p + 1]]);
+    let u32_at = |p: usize| u32::from_le_bytes([bytes[p], bytes[p + 1], bytes[p + 2], bytes[p + 3]]);
+
+    let mut pos = 12;
+    let mut format: Option<(u16, u32, u16)> = None;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let size = u32_at(pos + 4) as usize;
+        let body = pos + 8;
+        let end = (body + size).min(bytes.len());
+        if id == b"fmt " && end >= body + 16 {
+            format = Some((u16_at(body + 2), u32_at(body + 4), u16_at(body + 14)));
+        } else if id == b"data" {
+            let (channels, rate, bits) = format.ok_or("WAV data before fmt chunk")?;
+            if bits != 16 || channels == 0 {
+                return Err(format!("Unsupported WAV format: {} bit, {} channel(s)", bits, channels));
+            }
+            let channels = channels as usize;
+            let samples = bytes[body..end]
+                .chunks_exact(2 * channels)
+                .map(|frame| {
+                    frame
+                        .chunks_exact(2)
+                        .map(|s| i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0)
+                        .sum::<f32>()
+                        / channels as f32
+                })
+                .collect();
+            return Ok((samples, rate));
+        }
+        pos = body + size + (size & 1);
+    }
+    Err("WAV file has no data chunk".into())
+}
+
+#[cfg(windows)]
+fn play_chime(kind: &str, output_device_id: Option<&str>, captured: &[String]) -> Result<(), String> {
+    let (samples, rate) = parse_wav(chime(kind))?;
+    play_mono(output_device_id, captured, move |mix_rate| resample(&samples, rate, mix_rate))
+}
+
+#[cfg(not(windows))]
+fn play_chime(_kind: &str, _output_device_id: Option<&str>, _captured: &[String]) -> Result<(), String> {
+    Err(t("alert.delivery.sound_unsupported", &[]))
+}
+
+#[cfg(windows)]
+fn speak(text: &str, output_device_id: Option<&str>, captured: &[String]) -> Result<(), String> {
+    // SAPI is rendered to a temp WAV and played through our own device
+    // selection; speaking directly would go to the default output, which OBS
+    // may be capturing.
+    let path = std::env::temp_dir().join(format!("observe_alert_{}.wav", uuid::Uuid::new_v4()));
+    let script = format!(
+        "Add-Type -AssemblyName System.Speech; $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; $s.SetOutputToWaveFile('{}'); $s.Speak('{}'); $s.Dispose()",
+        path.to_string_lossy().replace('\'', "''"),
+        text.replace('\'', "''")
+    );
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .output()
+        .map_err(|e| format!("PowerShell error: {}", e))?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Speech synthesis failed: {}", stderr.trim()));
+    }
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read speech: {}", e));
+    let _ = std::fs::remove_file(&path);
+    let (samples, rate) = parse_wav(&bytes?)?;
+    play_mono(output_device_id, captured, move |mix_rate| resample(&samples, rate, mix_rate))
+}
+
+#[cfg(not(windows))]
+fn speak(_text: &str, _output_device_id: Option<&str>, _captured: &[String]) -> Result<(), String> {
+    Err(t("alert.delivery.speech_unsupported", &[]))
+}
+
+#[cfg(windows)]
+fn show_toast(title: &str, message: &str) -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+    use windows::Win32::System::Com::*;
+
+    let xml = format!(
+        "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual></toast>",
+        xml_escape(title),
+        xml_escape(message)
+    );
+    unsafe {
+        CoInitializeEx(None, COINIT_MULTITHREADED)
+            .ok()
+            .map_err(|e| format!("COM init failed: {}", e))?;
+    }
+    let result = (|| -> windows::core::Result<()> {
+        let doc = XmlDocument::new()?;
+        doc.LoadXml(&HSTRING::from(xml))?;
+        let toast = ToastNotification::CreateToastNotification(&doc)?;
+        ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from("com.observe.app"))?.Show(&toast)
+    })()
+    .map_err(|e| format!("Toast failed: {}", e));
+    unsafe { CoUninitialize() };
+    result
+}
+
+#[cfg(windows)]
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(not(windows))]
+fn show_toast(_title: &str, _message: &str) -> Result<(), String> {
+    Err(t("alert.delivery.toast_unsupported", &[]))
+}
+
+/// Picks the render endpoint for alerts: the configured device, then the
+/// default communications device, then the default console device, then any
+/// other active output — skipping anything OBS captures.
+#[cfg(windows)]
+unsafe fn pick_render_device(
+    enumerator: &windows::Win32::Media::Audio::IMMDeviceEnumerator,
+    output_device_id: Option<&str>,
+    captured: &[String],
+) -> Result<windows::Win32::Media::Audio::IMMDevice, String> {
+    use windows::Win32::Media::Audio::*;
+
+    let id_of = |d: &IMMDevice| d.GetId().ok().map(|id| id.to_string().unwrap_or_default());
+    let console_id = enumerator
+        .GetDefaultAudioEndpoint(eRender, eConsole)
+        .ok()
+        .and_then(|d| id_of(&d));
+    let is_captured = |id: &str| {
+        captured
+            .iter()
+            .any(|c| c == id || (c == "default" && console_id.as_deref() == Some(id)))
+    };
+
+    let mut candidates: Vec<String> = Vec::new();
+    if let Some(id) = output_device_id {
+        candidates.push(id.to_string());
+    }
+    if let Some(id) = enumerator
+        .GetDefaultAudioEndpoint(eRender, eCommunications)
+        .ok()
+        .and_then(|d| id_of(&d))
+    {
+        candidates.push(id);
+    }
+    candidates.extend(console_id.clone());
+    if let Ok(collection) = enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE) {
+        for i in 0..collection.GetCount().unwrap_or(0) {
+            if let Some(id) = collection.Item(i).ok().and_then(|d| id_of(&d)) {
+                candidates.push(id);
+            }
+        }
+    }
+
+    for id in candidates.iter().filter(|id| !is_captured(id)) {
+        let wide: Vec<u16> = id.encode_utf16().chain(std::iter::once(0)).collect();
+        if let Ok(device) = enumerator.GetDevice(windows::core::PCWSTR(wide.as_ptr())) {
+            return Ok(device);
+        }
+    }
+    Err(t("alert.delivery.all_outputs_captured", &[]))
+}
+
+/// Renders mono audio produced by `make` (given the device mix rate) on the
+/// alert output device and blocks until playback finishes.
+#[cfg(windows)]
+fn play_mono(
+    output_device_id: Option<&str>,
+    captured: &[String],
+    make: impl FnOnce(u32) -> Vec<f32>,
+) -> Result<(), String> {
+    use windows::Win32::Media::Audio::*;
+    use windows::Win32::System::Com::*;
+
+    unsafe {
+        CoInitializeEx(None, COINIT_MULTITHREADED)
+            .ok()
+            .map_err(|e| format!("COM init failed: {}", e))?;
+    }
+    let result = unsafe {
+        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+            .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
+        pick_render_device(&enumerator, output_device_id, captured)
+            .and_then(|device| render_to(&device, make))
+    };
+    unsafe { CoUninitialize() };
+    result
+}
+
+/// How the shared-mode mix format lays out one sample.
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy)]
+enum SampleFormat {
+    F32,
+    I16,
+    I24,
+    I32,
+}
+
+#[cfg(windows)]
+impl SampleFormat {
+    fn width(self) -> usize {
+        match self {
+            SampleFormat::I16 => 2,
+            SampleFormat::I24 => 3,
+            SampleFormat::F32 | SampleFormat::I32 => 4,
+        }
+    }
+
+    /// Little-endian bytes for `sample`; only the first `width()` are used.
+    fn encode(self, sample: f32) -> [u8; 4] {
+        let s = sample.clamp(-1.0, 1.0) as f64;
+        match self {
+            SampleFormat::F32 => (s as f32).to_le_bytes(),
+            SampleFormat::I16 => {
+                let [a, b] = ((s * i16::MAX as f64) as i16).to_le_bytes();
+                [a, b, 0, 0]
+            }
+            SampleFormat::I24 => ((s * 8_388_607.0) as i32).to_le_bytes(),
+            SampleFormat::I32 => ((s * i32::MAX as f64) as i32).to_le_bytes(),
+        }
+    }
+}
+
+/// Reads the sample layout from a mix format, following the sub-format of a
+/// `WAVE_FORMAT_EXTENSIBLE` header. A 24-bit sample in a 32-bit container is
+/// written as a full-scale 32-bit sample.
+#[cfg(windows)]
+unsafe fn sample_format(
+    format: *const windows::Win32::Media::Audio::WAVEFORMATEX,
+) -> Result<SampleFormat, String> {
+    use windows::Win32::Media::Audio::WAVEFORMATEXTENSIBLE;
+    const WAVE_FORMAT_PCM: u16 = 1;
+    const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+    const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+    const SUBTYPE_PCM: windows::core::GUID =
+        windows::core::GUID::from_u128(0x00000001_0000_0010_8000_00aa00389b71);
+    const SUBTYPE_IEEE_FLOAT: windows::core::GUID =
+        windows::core::GUID::from_u128(0x00000003_0000_0010_8000_00aa00389b71);
+
+    let fmt = *format;
+    let tag = match fmt.wFormatTag {
+        WAVE_FORMAT_EXTENSIBLE => {
+            let ext = format as *const WAVEFORMATEXTENSIBLE;
+            let sub_format = std::ptr::addr_of!((*ext).SubFormat).read_unaligned();
+            if sub_format == SUBTYPE_IEEE_FLOAT {
+                WAVE_FORMAT_IEEE_FLOAT
+            } else if sub_format == SUBTYPE_PCM {
+                WAVE_FORMAT_PCM
+            } else {
+                return Err(format!("Unsupported output sub-format {:?}", sub_format));
+            }
+        }
+        tag => tag,
+    };
+    let container = fmt.nBlockAlign as usize / (fmt.nChannels as usize).max(1);
+    match (tag, container) {
+        (WAVE_FORMAT_IEEE_FLOAT, 4) => Ok(SampleFormat::F32),
+        (WAVE_FORMAT_PCM, 2) => Ok(SampleFormat::I16),
+        (WAVE_FORMAT_PCM, 3) => Ok(SampleFormat::I24),
+        (WAVE_FORMAT_PCM, 4) => Ok(SampleFormat::I32),
+        _ => Err(format!(
+            "Unsupported output format: tag {}, {}-bit samples",
+            fmt.wFormatTag, fmt.wBitsPerSample
+        )),
+    }
+}
+
+#[cfg(windows)]
+unsafe fn render_to(
+    device: &windows::Win32::Media::Audio::IMMDevice,
+    make: impl FnOnce(u32) -> Vec<f32>,
+) -> Result<(), String> {
+    use windows::Win32::Media::Audio::*;
+
+    let audio_client: IAudioClient = device
+        .Activate(windows::Win32::System::Com::CLSCTX_ALL, None)
+        .map_err(|e| format!("Activate IAudioClient: {}", e))?;
+    let mix_format = audio_client
+        .GetMixFormat()
+        .map_err(|e| format!("GetMixFormat: {}", e))?;
+    let fmt = *mix_format;
+    let format = sample_format(mix_format);
+    let initialized = audio_client.Initialize(AUDCLNT_SHAREMODE_SHARED, 0, 5_000_000, 0, mix_format, None);
+    windows::Win32::System::Com::CoTaskMemFree(Some(mix_format as *const _));
+    initialized.map_err(|e| format!("Initialize: {}", e))?;
+    let format = format?;
+    let channels = fmt.nChannels as usize;
+    let sample_rate = fmt.nSamplesPerSec;
+    let block_align = fmt.nBlockAlign as usize;
+    let container = block_align / channels.max(1);
+    let render_client: IAudioRenderClient = audio_client
+        .GetService()
+        .map_err(|e| format!("GetService IAudioRenderClient: {}", e))?;
+    let buffer_frames = audio_client
+        .GetBufferSize()
+        .map_err(|e| format!("GetBufferSize: {}", e))?;
+
+    let samples = make(sample_rate);
+    let mut written = 0usize;
+    let mut started = false;
+    while written < samples.len() {
+        let padding = audio_client.GetCurrentPadding().unwrap_or(buffer_frames);
+        let frames = ((buffer_frames - padding) as usize).min(samples.len() - written);
+        if frames > 0 {
+            let buf = render_client
+                .GetBuffer(frames as u32)
+                .map_err(|e| format!("GetBuffer: {}", e))?;
+            for (f, sample) in samples[written..written + frames].iter().enumerate() {
+                let bytes = format.encode(*sample);
+                for c in 0..channels {
+                    let dst = buf.add(f * block_align + c * container);
+                    std::ptr::copy_nonoverlapping(bytes.as_ptr(), dst, format.width());
+                }
+            }
+            render_client
+                .ReleaseBuffer(frames as u32, 0)
+                .map_err(|e| format!("ReleaseBuffer: {}", e))?;
+            written += frames;
+        }
+        if !started {
+            audio_client.Start().map_err(|e| format!("Start: {}", e))?;
+            started = true;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    while audio_client.GetCurrentPadding().unwrap_or(0) > 0 {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let _ = audio_client.Stop();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn test_alert(
+    settings: tauri::State<'_, SharedSettings>,
+    app_handle: AppHandle,
+    event_kind: String,
+) -> Result<(), String> {
+    if !ALERT_KINDS.contains(&event_kind.as_str()) {
+        return Err(format!(
+            "Unknown alert '{}'. Expected one of: {}",
+            event_kind,
+            ALERT_KINDS.join(", ")
+        ));
+    }
+    let config = settings.read().await.alerts.clone();
+    let rule = config.rule(&event_kind);
+    let device = t("alert.test.device", &[]);
+    let input = t("alert.test.input", &[]);
+    let alert = render(
+        &event_kind,
+        &[
+            ("frames", &50),
+            ("device", &device),
+            ("input", &input),
+            ("seconds", &30),
+            ("count", &6),
+            ("file", &"recording.mkv"),
+        ],
+    );
+    let captured = captured_outputs(&app_handle).await;
+    let errors = tokio::task::spawn_blocking(move || {
+        deliver(&alert, &rule, config.output_device_id.as_deref(), &captured)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
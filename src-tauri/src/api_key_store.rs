@@ -0,0 +1,190 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Where the AI provider's key in use came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    Env,
+    Disk,
+    None,
+}
+
+/// One file per provider, e.g. `gemini_key.bin`.
+fn key_file_path(provider: &str) -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join(format!("{}_key.bin", provider))
+}
+
+/// Saves the key encrypted for the current Windows user.
+pub fn save(provider: &str, key: &str) -> Result<(), String> {
+    save_at(&key_file_path(provider), key)
+}
+
+/// `Ok(None)` when nothing is stored; an error when the stored blob can't be
+/// decrypted (corrupted, or written by another Windows user).
+pub fn load(provider: &str) -> Result<Option<String>, String> {
+    load_at(&key_file_path(provider))
+}
+
+pub fn clear(provider: &str) -> Result<(), String> {
+    clear_at(&key_file_path(provider))
+}
+
+fn save_at(path: &Path, key: &str) -> Result<(), String> {
+    let blob = protect(key.as_bytes())?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
+    std::fs::write(path, blob).map_err(|e| format!("Failed to save API key: {}", e))
+}
+
+fn load_at(path: &Path) -> Result<Option<String>, String> {
+    let blob = match std::fs::read(path) {
+        Ok(blob) => blob,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read stored API key: {}", e)),
+    };
+    let bytes = unprotect(&blob)?;
+    let key = String::from_utf8(bytes).map_err(|_| "Stored API key is corrupted".to_string())?;
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("Stored API key is empty".into());
+    }
+    Ok(Some(key.to_string()))
+}
+
+fn clear_at(path: &Path) -> Result<(), String> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to remove stored API key: {}", e))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(windows)]
+fn protect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Security::Cryptography::{
+        CryptProtectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    unsafe {
+        CryptProtectData(
+            &input,
+            PCWSTR::null(),
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        )
+        .map_err(|e| format!("Failed to encrypt API key: {}", e))?;
+        Ok(take_blob(output))
+    }
+}
+
+#[cfg(windows)]
+fn unprotect(data: &[u8]) -> Result<Vec<u8>, String> {
+    use windows::Win32::Security::Cryptography::{
+        CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    unsafe {
+        CryptUnprotectData(
+            &input,
+            None,
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            &mut output,
+        )
+        .map_err(|e| format!("Stored API key could not be decrypted: {}", e))?;
+        Ok(take_blob(output))
+    }
+}
+
+/// Copies out a DPAPI result and frees the buffer it allocated.
+#[cfg(windows)]
+unsafe fn take_blob(blob: windows::Win32::Security::Cryptography::CRYPT_INTEGER_BLOB) -> Vec<u8> {
+    use windows::Win32::Foundation::{LocalFree, HLOCAL};
+
+    let bytes = std::slice::from_raw_parts(blob.pbData, blob.cbData as usize).to_vec();
+    let _ = LocalFree(HLOCAL(blob.pbData as _));
+    bytes
+}
+
+// No DPAPI elsewhere; the file relies on the user's data dir permissions.
+#[cfg(not(windows))]
+fn protect(data: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(data.to_vec())
+}
+
+#[cfg(not(windows))]
+fn unprotect(data: &[u8]) -> Result<Vec<u8>, String> {
+    Ok(data.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A key file path in a fresh temp dir, removed with the returned guard.
+    struct TempKey(PathBuf);
+
+    impl TempKey {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("observe-key-{}", uuid::Uuid::new_v4()));
+            TempKey(dir.join("gemini_key.bin"))
+        }
+    }
+
+    impl Drop for TempKey {
+        fn drop(&mut self) {
+            if let Some(dir) = self.0.parent() {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    #[test]
+    fn save_load_clear_round_trip() {
+        let file = TempKey::new();
+        assert_eq!(load_at(&file.0), Ok(None));
+
+        save_at(&file.0, "AIza-test-key").unwrap();
+        assert_ne!(std::fs::read(&file.0).unwrap().len(), 0);
+        assert_eq!(load_at(&file.0), Ok(Some("AIza-test-key".to_string())));
+
+        clear_at(&file.0).unwrap();
+        assert!(!file.0.exists());
+        assert_eq!(load_at(&file.0), Ok(None));
+        // Clearing twice is fine.
+        assert!(clear_at(&file.0).is_ok());
+    }
+
+    #[test]
+    fn garbage_key_file_is_an_error_not_a_key() {
+        let file = TempKey::new();
+        std::fs::create_dir_all(file.0.parent().unwrap()).unwrap();
+        std::fs::write(&file.0, [0xff, 0xfe, 0x00, 0x9c, 0x80, 0xc3]).unwrap();
+        assert!(load_at(&file.0).is_err());
+
+        std::fs::write(&file.0, b"").unwrap();
+        assert!(load_at(&file.0).is_err());
+    }
+}
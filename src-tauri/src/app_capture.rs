@@ -1,8 +1,26 @@
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::settings::{self, AppCaptureSettings, SharedSettings};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
 #[cfg(windows)]
 use windows_core::Interface;
 
+const APP_CAPTURE_KIND: &str = "wasapi_process_output_capture";
+const GAMING_TAG: &str = "gaming";
+const WATCH_INTERVAL: Duration = Duration::from_secs(3);
+
+/// A process to capture as soon as it shows up with an audio session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoCaptureRule {
+    pub process_name: String,
+    pub display_name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioProcess {
@@ -10,11 +28,18 @@ pub struct AudioProcess {
     pub name: String,
     pub exe_path: String,
     pub display_name: String,
+    /// Session volume, 0.0-1.0, as set in the Windows volume mixer.
+    #[serde(default)]
+    pub volume: f32,
+    #[serde(default)]
+    pub muted: bool,
+    /// Current peak, 0.0-1.0.
+    #[serde(default)]
+    pub peak: f32,
 }
 
 #[cfg(windows)]
 pub fn enumerate_audio_sessions() -> Result<Vec<AudioProcess>, String> {
-    use std::collections::HashMap;
     use sysinfo::{ProcessesToUpdate, System};
     use windows::Win32::Media::Audio::*;
     use windows::Win32::System::Com::*;
@@ -105,12 +130,16 @@ pub fn enumerate_audio_sessions() -> Result<Vec<AudioProcess>, String> {
                     } else {
                         clean_process_name(name)
                     };
+                    let (volume, muted, peak) = session_levels(&session);
 
                     results.push(AudioProcess {
                         pid,
                         name: name.clone(),
                         exe_path: exe_path.clone(),
                         display_name,
+                        volume,
+                        muted,
+                        peak,
                     });
                 }
             }
@@ -121,6 +150,112 @@ pub fn enumerate_audio_sessions() -> Result<Vec<AudioProcess>, String> {
     Ok(results)
 }
 
+/// (volume, muted, peak) of one session; a session that won't say reads
+/// as full volume, unmuted and silent.
+#[cfg(windows)]
+unsafe fn session_levels(
+    session: &windows::Win32::Media::Audio::IAudioSessionControl,
+) -> (f32, bool, f32) {
+    use windows::Win32::Media::Audio::Endpoints::IAudioMeterInformation;
+    use windows::Win32::Media::Audio::ISimpleAudioVolume;
+
+    let (volume, muted) = match session.cast::<ISimpleAudioVolume>() {
+        Ok(v) => (
+            v.GetMasterVolume().unwrap_or(1.0),
+            v.GetMute().map(|m| m.as_bool()).unwrap_or(false),
+        ),
+        Err(_) => (1.0, false),
+    };
+    let peak = session
+        .cast::<IAudioMeterInformation>()
+        .and_then(|m| m.GetPeakValue())
+        .unwrap_or(0.0);
+    (volume, muted, peak)
+}
+
+/// Runs `f` on every session `pid` has on any output device. Sessions come
+/// and go, so they're looked up fresh on each call; none found means the
+/// app has stopped playing audio.
+#[cfg(windows)]
+fn with_process_sessions(
+    pid: u32,
+    mut f: impl FnMut(&windows::Win32::Media::Audio::ISimpleAudioVolume) -> windows::core::Result<()>,
+) -> Result<(), String> {
+    use windows::Win32::Media::Audio::*;
+    use windows::Win32::System::Com::*;
+
+    let mut found = 0;
+    unsafe {
+        CoInitializeEx(None, COINIT_MULTITHREADED)
+            .ok()
+            .map_err(|e| format!("COM init failed: {}", e))?;
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(|e| format!("Failed to create device enumerator: {}", e))?;
+        let devices = enumerator
+            .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
+            .map_err(|e| format!("EnumAudioEndpoints failed: {}", e))?;
+
+        for i in 0..devices.GetCount().unwrap_or(0) {
+            let Ok(device) = devices.Item(i) else {
+                continue;
+            };
+            let Ok(mgr) = device.Activate::<IAudioSessionManager2>(CLSCTX_ALL, None) else {
+                continue;
+            };
+            let Ok(session_enum) = mgr.GetSessionEnumerator() else {
+                continue;
+            };
+            for j in 0..session_enum.GetCount().unwrap_or(0) {
+                let Ok(session) = session_enum.GetSession(j) else {
+                    continue;
+                };
+                if session.GetState().map_or(true, |s| s == AudioSessionStateExpired) {
+                    continue;
+                }
+                let Ok(session2) = session.cast::<IAudioSessionControl2>() else {
+                    continue;
+                };
+                if session2.GetProcessId().ok() != Some(pid) {
+                    continue;
+                }
+                let Ok(volume) = session.cast::<ISimpleAudioVolume>() else {
+                    continue;
+                };
+                // The session can expire between lookup and call.
+                if f(&volume).is_ok() {
+                    found += 1;
+                }
+            }
+        }
+    }
+    if found == 0 {
+        return Err(format!("Audio session for process {} is gone", pid));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+pub fn set_session_volume(pid: u32, volume: f32) -> Result<(), String> {
+    with_process_sessions(pid, |v| unsafe { v.SetMasterVolume(volume, std::ptr::null()) })
+}
+
+#[cfg(windows)]
+pub fn set_session_mute(pid: u32, muted: bool) -> Result<(), String> {
+    with_process_sessions(pid, |v| unsafe { v.SetMute(muted, std::ptr::null()) })
+}
+
+#[cfg(not(windows))]
+pub fn set_session_volume(_pid: u32, _volume: f32) -> Result<(), String> {
+    Err("Per-app volume is only supported on Windows".to_string())
+}
+
+#[cfg(not(windows))]
+pub fn set_session_mute(_pid: u32, _muted: bool) -> Result<(), String> {
+    Err("Per-app volume is only supported on Windows".to_string())
+}
+
 #[cfg(windows)]
 fn get_friendly_name(exe_path: &str) -> Option<String> {
     use windows::core::PCWSTR;
@@ -205,3 +340,356 @@ fn clean_process_name(name: &str) -> String {
 pub fn enumerate_audio_sessions() -> Result<Vec<AudioProcess>, String> {
     Ok(Vec::new())
 }
+
+/// Lowercased names of every running process, with or without audio.
+fn running_process_names() -> HashSet<String> {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    sys.processes()
+        .values()
+        .map(|p| p.name().to_string_lossy().to_lowercase())
+        .collect()
+}
+
+#[cfg(windows)]
+fn foreground_pid() -> Option<u32> {
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0.is_null() {
+            return None;
+        }
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        (pid != 0).then_some(pid)
+    }
+}
+
+#[cfg(not(windows))]
+fn foreground_pid() -> Option<u32> {
+    None
+}
+
+/// The foreground window's process, if it is currently playing audio.
+fn foreground_audio_process() -> Result<Option<AudioProcess>, String> {
+    let Some(pid) = foreground_pid() else {
+        return Ok(None);
+    };
+    Ok(enumerate_audio_sessions()?.into_iter().find(|p| p.pid == pid))
+}
+
+fn is_eligible(process_name: &str, config: &AppCaptureSettings) -> bool {
+    let listed = |list: &[String]| list.iter().any(|n| n.eq_ignore_ascii_case(process_name));
+    if !config.allow.is_empty() {
+        return listed(&config.allow);
+    }
+    !listed(&config.deny)
+}
+
+/// Whether an app capture input already targets `process_name`. OBS stores
+/// the target as "title:class:exe", older builds of ours just the exe.
+async fn already_captured(conn: &ObsHandle, obs_state: &SharedObsState, process_name: &str) -> bool {
+    let captures: Vec<String> = obs_state
+        .read()
+        .await
+        .inputs
+        .values()
+        .filter(|i| i.kind == APP_CAPTURE_KIND)
+        .map(|i| i.name.clone())
+        .collect();
+    let exe = process_name.to_lowercase();
+    for name in captures {
+        let Ok(resp) = conn
+            .send_request("GetInputSettings", Some(json!({"inputName": name})))
+            .await
+        else {
+            continue;
+        };
+        let window = resp["inputSettings"]["window"].as_str().unwrap_or("").to_lowercase();
+        if window == exe || window.ends_with(&format!(":{}", exe)) {
+            return true;
+        }
+    }
+    false
+}
+
+/// Auto-adds the foreground app's audio when OBS switches to a scene tagged
+/// "gaming". Runs off the event loop since it enumerates sessions and talks
+/// to OBS.
+pub fn on_scene_changed(app: &AppHandle, scene_name: &str) {
+    let app = app.clone();
+    let scene_name = scene_name.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = auto_add_for_scene(&app, &scene_name).await {
+            log::warn!("App capture auto-add failed: {}", e);
+        }
+    });
+}
+
+async fn auto_add_for_scene(app: &AppHandle, scene_name: &str) -> Result<(), String> {
+    let (Some(conn), Some(obs_state), Some(settings)) = (
+        app.try_state::<ObsHandle>(),
+        app.try_state::<SharedObsState>(),
+        app.try_state::<SharedSettings>(),
+    ) else {
+        return Ok(());
+    };
+    let (config, tagged) = {
+        let s = settings.read().await;
+        let tagged = s
+            .scene_tags
+            .get(scene_name)
+            .map(|tags| tags.iter().any(|t| t.eq_ignore_ascii_case(GAMING_TAG)))
+            .unwrap_or(false);
+        (s.app_capture.clone(), tagged)
+    };
+    if !config.auto_add || !tagged {
+        return Ok(());
+    }
+    if !config.also_when_idle {
+        let s = obs_state.read().await;
+        if !s.stream_status.active && !s.record_status.active {
+            return Ok(());
+        }
+    }
+
+    let process = tokio::task::spawn_blocking(foreground_audio_process)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    let Some(process) = process else {
+        return Ok(());
+    };
+    if !is_eligible(&process.name, &config) || already_captured(&conn, &obs_state, &process.name).await {
+        return Ok(());
+    }
+
+    let input_name = crate::commands::create_app_capture(
+        &conn,
+        &obs_state,
+        &process.name,
+        Some(process.display_name.clone()),
+        Some(scene_name.to_string()),
+    )
+    .await?;
+    log::info!("Auto-added app capture '{}' for {}", input_name, process.name);
+    let _ = app.emit(
+        "appcapture://auto-added",
+        json!({
+            "inputName": input_name,
+            "processName": process.name,
+            "displayName": process.display_name,
+            "sceneName": scene_name,
+        }),
+    );
+    Ok(())
+}
+
+/// Polls audio sessions for processes on the watch list and gives each one
+/// an app capture input while it runs.
+pub fn start_watcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // process name (lowercase) -> input we created for it
+        let mut added: HashMap<String, String> = HashMap::new();
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            if let Err(e) = watch_tick(&app, &mut added).await {
+                log::warn!("Auto-capture watch failed: {}", e);
+            }
+        }
+    });
+}
+
+async fn watch_tick(app: &AppHandle, added: &mut HashMap<String, String>) -> Result<(), String> {
+    let (Some(conn), Some(obs_state), Some(settings)) = (
+        app.try_state::<ObsHandle>(),
+        app.try_state::<SharedObsState>(),
+        app.try_state::<SharedSettings>(),
+    ) else {
+        return Ok(());
+    };
+    let config = settings.read().await.app_capture.clone();
+    if config.watch.is_empty() || !conn.is_connected() {
+        return Ok(());
+    }
+
+    let processes = tokio::task::spawn_blocking(enumerate_audio_sessions)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    // One entry per process name, however many sessions or instances it has.
+    let audible: HashSet<String> = processes.iter().map(|p| p.name.to_lowercase()).collect();
+
+    for rule in &config.watch {
+        let key = rule.process_name.to_lowercase();
+        if !audible.contains(&key) {
+            continue;
+        }
+        // Ours and still there; skips the GetInputSettings round trips.
+        if let Some(input) = added.get(&key) {
+            if obs_state.read().await.inputs.contains_key(input) {
+                continue;
+            }
+        }
+        let input_name = format!("App: {}", rule.display_name);
+        if obs_state.read().await.inputs.contains_key(&input_name)
+            || already_captured(&conn, &obs_state, &rule.process_name).await
+        {
+            continue;
+        }
+        let input_name = match crate::commands::create_app_capture(
+            &conn,
+            &obs_state,
+            &rule.process_name,
+            Some(rule.display_name.clone()),
+            None,
+        )
+        .await
+        {
+            Ok(name) => name,
+            Err(e) => {
+                log::warn!("Auto-capture of {} failed: {}", rule.process_name, e);
+                continue;
+            }
+        };
+        log::info!("Auto-captured {} as '{}'", rule.process_name, input_name);
+        let _ = app.emit(
+            "obs://auto-capture-added",
+            json!({
+                "inputName": input_name,
+                "processName": rule.process_name,
+                "displayName": rule.display_name,
+            }),
+        );
+        added.insert(key, input_name);
+    }
+
+    if added.is_empty() {
+        return Ok(());
+    }
+    // An app that merely went quiet drops its audio session but is still
+    // running; only a process that is gone counts as exited.
+    let running = tokio::task::spawn_blocking(running_process_names)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?;
+
+    // Only inputs this watcher created are ever removed.
+    let gone: Vec<String> = added.keys().filter(|k| !running.contains(*k)).cloned().collect();
+    for key in gone {
+        let Some(input_name) = added.remove(&key) else {
+            continue;
+        };
+        if !config.remove_on_exit || !obs_state.read().await.inputs.contains_key(&input_name) {
+            continue;
+        }
+        if let Err(e) = conn
+            .send_request("RemoveInput", Some(json!({"inputName": input_name})))
+            .await
+        {
+            // Keep tracking it so the next tick tries again.
+            log::warn!("Failed to remove auto-capture '{}': {}", input_name, e);
+            added.insert(key, input_name);
+            continue;
+        }
+        log::info!("Removed auto-capture '{}' after {} exited", input_name, key);
+        let _ = app.emit(
+            "obs://auto-capture-removed",
+            json!({"inputName": input_name, "processName": key}),
+        );
+    }
+    Ok(())
+}
+
+/// Carries a scene's tags over when it is renamed in OBS.
+pub async fn on_scene_renamed(app: &AppHandle, old_name: &str, new_name: &str) {
+    let Some(settings) = app.try_state::<SharedSettings>() else {
+        return;
+    };
+    // Moved in memory here, in event order; the file is written off the
+    // event loop.
+    {
+        let mut s = settings.write().await;
+        let Some(tags) = s.scene_tags.remove(old_name) else {
+            return;
+        };
+        s.scene_tags.insert(new_name.to_string(), tags);
+    }
+    settings::save_in_background(&settings);
+}
+
+#[tauri::command]
+pub async fn set_scene_tags(
+    settings: tauri::State<'_, SharedSettings>,
+    scene_name: String,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let mut tags: Vec<String> = tags
+        .into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    settings::update_section(&settings, |s| {
+        if tags.is_empty() {
+            s.scene_tags.remove(&scene_name);
+        } else {
+            s.scene_tags.insert(scene_name, tags);
+        }
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_scene_tags(
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    Ok(settings.read().await.scene_tags.clone())
+}
+
+#[tauri::command]
+pub async fn list_auto_capture_rules(
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<Vec<AutoCaptureRule>, String> {
+    Ok(settings.read().await.app_capture.watch.clone())
+}
+
+#[tauri::command]
+pub async fn add_auto_capture_rule(
+    settings: tauri::State<'_, SharedSettings>,
+    process_name: String,
+    display_name: Option<String>,
+) -> Result<Vec<AutoCaptureRule>, String> {
+    let process_name = process_name.trim().to_string();
+    if process_name.is_empty() {
+        return Err("Process name is required".to_string());
+    }
+    let display_name = display_name
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .unwrap_or_else(|| clean_process_name(&process_name));
+    settings::update_section(&settings, |s| {
+        s.app_capture
+            .watch
+            .retain(|r| !r.process_name.eq_ignore_ascii_case(&process_name));
+        s.app_capture.watch.push(AutoCaptureRule {
+            process_name,
+            display_name,
+        });
+    })
+    .await?;
+    Ok(settings.read().await.app_capture.watch.clone())
+}
+
+#[tauri::command]
+pub async fn remove_auto_capture_rule(
+    settings: tauri::State<'_, SharedSettings>,
+    process_name: String,
+) -> Result<Vec<AutoCaptureRule>, String> {
+    settings::update_section(&settings, |s| {
+        s.app_capture
+            .watch
+            .retain(|r| !r.process_name.eq_ignore_ascii_case(&process_name));
+    })
+    .await?;
+    Ok(settings.read().await.app_capture.watch.clone())
+}
@@ -0,0 +1,198 @@
+use crate::audio;
+use crate::audio_monitor::AudioMetrics;
+use crate::device_matching;
+use crate::obs_state::{InputInfo, SharedObsState};
+use crate::settings::{self, SharedSettings};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Mic-level problems worth telling the user about while live.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioAlertSettings {
+    pub enabled: bool,
+    /// Peak below this counts as silence.
+    pub silence_threshold_db: f64,
+    pub silence_seconds: u64,
+    /// More clipping bursts than this within the window raises the alert.
+    pub clipping_count: usize,
+    pub clipping_window_seconds: u64,
+}
+
+impl Default for AudioAlertSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            silence_threshold_db: -60.0,
+            silence_seconds: 30,
+            clipping_count: 5,
+            clipping_window_seconds: 10,
+        }
+    }
+}
+
+/// How often the primary mic's device id is re-resolved ("default" can move).
+const RESOLVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Per-condition state kept by the audio monitor loop. Each condition fires
+/// once and then stays quiet until it clears.
+#[derive(Default)]
+pub struct AudioAlertState {
+    device: Option<(String, String)>, // (input name, device id)
+    resolved_at: Option<Instant>,
+    silent_since: Option<Instant>,
+    silence_alerted: bool,
+    was_clipping: bool,
+    clip_events: VecDeque<Instant>,
+    clipping_alerted: bool,
+}
+
+impl AudioAlertState {
+    fn clear_silence(&mut self, app: &AppHandle, input: &str) {
+        self.silent_since = None;
+        if self.silence_alerted {
+            self.silence_alerted = false;
+            emit(app, "silence", input, false);
+            crate::tray::set_audio_alert(app, None);
+        }
+    }
+
+    fn clear_clipping(&mut self, app: &AppHandle, input: &str) {
+        self.clip_events.clear();
+        self.was_clipping = false;
+        if self.clipping_alerted {
+            self.clipping_alerted = false;
+            emit(app, "clipping", input, false);
+        }
+    }
+}
+
+fn emit(app: &AppHandle, kind: &str, input: &str, active: bool) {
+    let _ = app.emit(
+        "obs://audio-alert",
+        json!({"kind": kind, "inputName": input, "active": active}),
+    );
+}
+
+/// Keeps the (input, device) pair for special_inputs.mic1 current.
+async fn resolve_mic(state: &mut AudioAlertState, mic: &str, input: Option<&InputInfo>) {
+    let stale = state.resolved_at.map_or(true, |at| at.elapsed() >= RESOLVE_INTERVAL);
+    let renamed = state.device.as_ref().map_or(true, |(name, _)| name != mic);
+    if !stale && !renamed {
+        return;
+    }
+    state.resolved_at = Some(Instant::now());
+    let Some(input) = input else {
+        state.device = None;
+        return;
+    };
+    let devices = tokio::task::spawn_blocking(audio::enumerate_audio_devices)
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .unwrap_or_default();
+    let device_id = device_matching::resolve_input_device(input, &devices)
+        .map(|d| d.id.clone())
+        .unwrap_or_else(|| input.device_id.clone());
+    state.device = Some((mic.to_string(), device_id));
+}
+
+/// Called by the audio monitor after each metrics poll.
+pub async fn check(
+    app: &AppHandle,
+    state: &mut AudioAlertState,
+    obs_state: &SharedObsState,
+    metrics: &AudioMetrics,
+    config: &AudioAlertSettings,
+) {
+    let (live, mic, info) = {
+        let obs = obs_state.read().await;
+        let live = obs.stream_status.active
+            || (obs.record_status.active && !obs.record_status.paused);
+        let mic = obs.special_inputs.mic1.clone();
+        let info = obs.inputs.get(&mic).cloned();
+        (live, mic, info)
+    };
+    if !config.enabled || !live || mic.is_empty() {
+        if let Some((input, _)) = state.device.clone() {
+            state.clear_silence(app, &input);
+            state.clear_clipping(app, &input);
+        }
+        return;
+    }
+
+    resolve_mic(state, &mic, info.as_ref()).await;
+    let Some((input, device_id)) = state.device.clone() else {
+        return;
+    };
+    let Some(m) = device_matching::lookup_by_id(&metrics.devices, &device_id) else {
+        return;
+    };
+    let now = Instant::now();
+
+    // Muted in OBS counts as silent even though the device still has level.
+    let peak_db = if m.peak > 0.0 { 20.0 * (m.peak as f64).log10() } else { -100.0 };
+    let muted = info.as_ref().map_or(false, |i| i.muted);
+    if muted || peak_db < config.silence_threshold_db {
+        let since = *state.silent_since.get_or_insert(now);
+        if !state.silence_alerted && now.duration_since(since).as_secs() >= config.silence_seconds {
+            state.silence_alerted = true;
+            emit(app, "silence", &input, true);
+            crate::tray::set_audio_alert(app, Some(&input));
+            let seconds = config.silence_seconds;
+            crate::alerts::notify(app, "mic-silent", &[("input", &input), ("seconds", &seconds)]);
+        }
+    } else {
+        state.clear_silence(app, &input);
+    }
+
+    // Count bursts, not polls: a held clip is one event.
+    if m.clipping && !state.was_clipping {
+        state.clip_events.push_back(now);
+    }
+    state.was_clipping = m.clipping;
+    let window = Duration::from_secs(config.clipping_window_seconds);
+    while state.clip_events.front().map_or(false, |t| now.duration_since(*t) > window) {
+        state.clip_events.pop_front();
+    }
+    if state.clip_events.len() > config.clipping_count {
+        if !state.clipping_alerted {
+            state.clipping_alerted = true;
+            emit(app, "clipping", &input, true);
+            // With auto-rescue on, the volume guard turns the mic down and
+            // raises its own "clipping" alert; this one would repeat it.
+            let rescued = match app.try_state::<SharedSettings>() {
+                Some(s) => s.read().await.volume_guards.get(&input).is_some_and(|g| g.auto_rescue),
+                None => false,
+            };
+            if !rescued {
+                let count = state.clip_events.len();
+                crate::alerts::notify(app, "mic-clipping", &[("input", &input), ("count", &count)]);
+            }
+        }
+    } else if state.clip_events.is_empty() && state.clipping_alerted {
+        state.clipping_alerted = false;
+        emit(app, "clipping", &input, false);
+    }
+}
+
+#[tauri::command]
+pub async fn get_audio_alert_config(
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<AudioAlertSettings, String> {
+    Ok(settings.read().await.audio_alerts.clone())
+}
+
+#[tauri::command]
+pub async fn set_audio_alert_config(
+    settings: tauri::State<'_, SharedSettings>,
+    config: AudioAlertSettings,
+) -> Result<(), String> {
+    if config.silence_seconds == 0 || config.clipping_window_seconds == 0 {
+        return Err("Alert durations must be at least one second".to_string());
+    }
+    settings::update_section(&settings, |s| s.audio_alerts = config).await
+}
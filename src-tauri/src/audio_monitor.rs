@@ -1,9 +1,12 @@
 use crate::audio;
+use crate::audio_alerts::{self, AudioAlertState};
+use crate::loudness::{self, SharedLoudness};
 use crate::obs_state::SharedObsState;
+use crate::settings::{AudioHistorySettings, SharedSettings};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use tokio::sync::RwLock;
 
 #[derive(Debug, Clone, Serialize, Default)]
@@ -17,9 +20,58 @@ pub struct DeviceMetrics {
     pub rms: f32,
     pub noise_floor: f32,
     pub clipping: bool,
+    /// K-weighted loudness in LUFS; None for devices no OBS input uses.
+    pub lufs_momentary: Option<f64>,
+    pub lufs_short_term: Option<f64>,
+    pub lufs_integrated: Option<f64>,
 }
 
 pub type SharedAudioMetrics = Arc<RwLock<AudioMetrics>>;
+pub type SharedMetricsNotify = Arc<MetricsNotify>;
+
+/// Signalled after metrics updates so a consumer can wait for fresh levels
+/// instead of polling. Ungated, every update wakes it; gated, only a watched
+/// device peaking above the gate level does, so an idle consumer sleeps
+/// through quiet audio.
+#[derive(Default)]
+pub struct MetricsNotify {
+    notify: tokio::sync::Notify,
+    gate: std::sync::Mutex<Option<(Vec<String>, f32)>>,
+}
+
+impl MetricsNotify {
+    /// Only wake for `device_ids` peaking above `level` (linear). An empty
+    /// list sleeps until `wake`.
+    pub fn gate(&self, device_ids: Vec<String>, level: f32) {
+        *self.gate.lock().unwrap_or_else(|e| e.into_inner()) = Some((device_ids, level));
+    }
+
+    /// Wake on every update again.
+    pub fn ungate(&self) {
+        *self.gate.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Wakes the consumer now, e.g. after its configuration changed.
+    pub fn wake(&self) {
+        self.notify.notify_one();
+    }
+
+    pub async fn notified(&self) {
+        self.notify.notified().await
+    }
+
+    fn publish(&self, metrics: &AudioMetrics) {
+        let open = match &*self.gate.lock().unwrap_or_else(|e| e.into_inner()) {
+            None => true,
+            Some((ids, level)) => ids
+                .iter()
+                .any(|id| metrics.devices.get(id).is_some_and(|m| m.peak > *level)),
+        };
+        if open {
+            self.notify.notify_one();
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -44,29 +96,63 @@ pub struct ObsDeviceLostEvent {
     pub device_id: String,
     pub device_name: String,
     pub affected_inputs: Vec<String>,
+    /// The affected inputs that capture a microphone or line-in, as opposed to
+    /// desktop or application audio.
+    #[serde(skip)]
+    pub mic_inputs: Vec<String>,
 }
 
 pub async fn start_audio_monitor(
     app_handle: AppHandle,
     obs_state: SharedObsState,
     audio_metrics: SharedAudioMetrics,
+    metrics_notify: SharedMetricsNotify,
+    audio_history: SharedAudioHistory,
 ) -> Result<(), String> {
-    start_peak_meter_polling(app_handle.clone(), audio_metrics);
+    start_peak_meter_polling(
+        app_handle.clone(),
+        obs_state.clone(),
+        audio_metrics,
+        metrics_notify,
+        audio_history,
+    );
     start_device_hotplug(app_handle, obs_state);
     Ok(())
 }
 
+const POLL_INTERVAL_MS: u64 = 200;
 const RMS_WINDOW: usize = 5; // 5 samples at 200ms = 1 second
 const NOISE_FLOOR_WINDOW: usize = 50; // 50 samples at 200ms = 10 seconds
+/// How often the set of loudness-metered devices follows the OBS inputs.
+const LOUDNESS_SYNC_TICKS: u32 = 25; // 5 seconds
 
-fn start_peak_meter_polling(app_handle: AppHandle, audio_metrics: SharedAudioMetrics) {
+fn start_peak_meter_polling(
+    app_handle: AppHandle,
+    obs_state: SharedObsState,
+    audio_metrics: SharedAudioMetrics,
+    metrics_notify: SharedMetricsNotify,
+    audio_history: SharedAudioHistory,
+) {
     tokio::spawn(async move {
-        let mut interval = tokio::time::interval(std::time::Duration::from_millis(200));
+        let settings = app_handle.state::<SharedSettings>().inner().clone();
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_millis(POLL_INTERVAL_MS));
         let mut ring_buffers: HashMap<String, Vec<f32>> = HashMap::new();
         let mut noise_floor_history: HashMap<String, Vec<f32>> = HashMap::new();
+        let loudness = app_handle.state::<SharedLoudness>().inner().clone();
+        let mut ticks: u32 = 0;
+        let mut alert_state = AudioAlertState::default();
 
         loop {
             interval.tick().await;
+            if ticks % LOUDNESS_SYNC_TICKS == 0 {
+                let wanted = loudness::wanted_devices(&obs_state).await;
+                if let Ok(mut meters) = loudness.lock() {
+                    meters.sync(&wanted);
+                }
+            }
+            ticks = ticks.wrapping_add(1);
+            let loudness_readings = loudness.lock().map(|m| m.readings()).unwrap_or_default();
             let handle = app_handle.clone();
             let result = tokio::task::spawn_blocking(poll_all_peak_meters).await;
             if let Ok(Ok(levels)) = result {
@@ -104,6 +190,7 @@ fn start_peak_meter_polling(app_handle: AppHandle, audio_metrics: SharedAudioMet
                         .reduce(f32::min)
                         .unwrap_or(0.0);
 
+                    let lufs = crate::device_matching::lookup_by_id(&loudness_readings, &level.device_id);
                     metrics_snapshot.devices.insert(
                         level.device_id.clone(),
                         DeviceMetrics {
@@ -111,13 +198,40 @@ fn start_peak_meter_polling(app_handle: AppHandle, audio_metrics: SharedAudioMet
                             rms,
                             noise_floor,
                             clipping: level.peak >= 0.95,
+                            lufs_momentary: lufs.map(|l| l.momentary),
+                            lufs_short_term: lufs.map(|l| l.short_term),
+                            lufs_integrated: lufs.map(|l| l.integrated),
                         },
                     );
                 }
 
+                // Forget unplugged devices so their windows don't pile up.
+                ring_buffers.retain(|id, _| metrics_snapshot.devices.contains_key(id));
+                noise_floor_history.retain(|id, _| metrics_snapshot.devices.contains_key(id));
+
+                let alert_config = settings.read().await.audio_alerts.clone();
+                audio_alerts::check(&handle, &mut alert_state, &obs_state, &metrics_snapshot, &alert_config)
+                    .await;
+
                 {
                     let mut m = audio_metrics.write().await;
                     *m = metrics_snapshot;
+                    metrics_notify.publish(&m);
+                }
+
+                let capacity = history_capacity(settings.read().await.audio_history.hours);
+                let recording = {
+                    let s = obs_state.read().await;
+                    let status = &s.record_status;
+                    status.active.then_some((status.duration_ms, status.paused))
+                };
+                let rms: HashMap<String, f32> = loudness_readings
+                    .iter()
+                    .map(|(id, r)| (id.clone(), r.rms))
+                    .collect();
+                if let Ok(mut h) = audio_history.lock() {
+                    h.set_capacity(capacity);
+                    h.record_tick(unix_ms(), POLL_INTERVAL_MS, &levels, &rms, recording);
                 }
 
                 if levels.iter().any(|l| l.peak > 0.001) {
@@ -311,6 +425,7 @@ fn start_device_hotplug(app_handle: AppHandle, obs_state: SharedObsState) {
                     );
                 }
                 HotplugEvent::Removed(device_id) => {
+                    crate::endpoint_volume::device_removed(&app_handle, &device_id);
                     let lost = check_obs_device_lost(&device_id, &obs_state).await;
                     let _ = app_handle.emit(
                         "audio://device-removed",
@@ -321,6 +436,7 @@ fn start_device_hotplug(app_handle: AppHandle, obs_state: SharedObsState) {
                         },
                     );
                     if let Some(lost_event) = lost {
+                        notify_device_lost(&app_handle, &lost_event);
                         let _ = app_handle.emit("audio://obs-device-lost", lost_event);
                     }
                 }
@@ -336,6 +452,7 @@ fn start_device_hotplug(app_handle: AppHandle, obs_state: SharedObsState) {
                             },
                         );
                     } else {
+                        crate::endpoint_volume::device_removed(&app_handle, &device_id);
                         let lost = check_obs_device_lost(&device_id, &obs_state).await;
                         let _ = app_handle.emit(
                             "audio://device-removed",
@@ -346,11 +463,13 @@ fn start_device_hotplug(app_handle: AppHandle, obs_state: SharedObsState) {
                             },
                         );
                         if let Some(lost_event) = lost {
+                            notify_device_lost(&app_handle, &lost_event);
                             let _ = app_handle.emit("audio://obs-device-lost", lost_event);
                         }
                     }
                 }
                 HotplugEvent::DefaultChanged(flow, device_id) => {
+                    crate::endpoint_volume::refresh(&app_handle);
                     let info = resolve_device_info(&device_id);
                     let device_type = match flow {
                         0 => Some("output".to_string()),
@@ -382,15 +501,32 @@ fn resolve_device_info(device_id: &str) -> Option<(String, String)> {
         .map(|d| (d.name.clone(), d.device_type.clone()))
 }
 
+fn notify_device_lost(app_handle: &AppHandle, event: &ObsDeviceLostEvent) {
+    // Losing a speaker under a desktop-audio capture is not a "mic lost".
+    if event.mic_inputs.is_empty() {
+        return;
+    }
+    let inputs = event.mic_inputs.join(", ");
+    crate::alerts::notify(
+        app_handle,
+        "mic-lost",
+        &[("device", &event.device_name), ("input", &inputs)],
+    );
+}
+
 async fn check_obs_device_lost(
     device_id: &str,
     obs_state: &SharedObsState,
 ) -> Option<ObsDeviceLostEvent> {
     let state = obs_state.read().await;
     let mut affected = Vec::new();
+    let mut mic_inputs = Vec::new();
     for input in state.inputs.values() {
         if input.device_id == device_id {
             affected.push(input.name.clone());
+            if input.kind.contains("input_capture") {
+                mic_inputs.push(input.name.clone());
+            }
         }
     }
     if affected.is_empty() {
@@ -403,5 +539,381 @@ async fn check_obs_device_lost(
         device_id: device_id.to_string(),
         device_name: name,
         affected_inputs: affected,
+        mic_inputs,
     })
 }
+
+// --- Metrics History ---
+
+const HISTORY_MAX_HOURS: u32 = 12;
+const CLIPPING_LOG_CAP: usize = 5000;
+const CLIP_THRESHOLD: f32 = 0.95;
+const DEFAULT_HISTORY_POINTS: usize = 600;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct HistorySample {
+    secs: u64,
+    peak: f32,
+    /// None for devices without a loudness meter, where only peaks are known.
+    rms: Option<f32>,
+}
+
+#[derive(Debug)]
+struct OpenClip {
+    start_ms: u64,
+    last_ms: u64,
+    peak: f32,
+    recording_offset_ms: Option<u64>,
+}
+
+/// One device's 1-second samples. The ring is allocated once when the device
+/// first shows up, so recording a tick never allocates.
+#[derive(Debug)]
+struct DeviceHistory {
+    ring: Vec<HistorySample>,
+    head: usize,
+    len: usize,
+    acc_secs: u64,
+    acc_peak: f32,
+    acc_count: u32,
+    /// Mean-square sum over the readings that had an RMS.
+    acc_sum_sq: f32,
+    acc_rms_count: u32,
+    last_seen_ms: u64,
+    clip: Option<OpenClip>,
+}
+
+impl DeviceHistory {
+    fn new(capacity: usize) -> Self {
+        Self {
+            ring: vec![HistorySample::default(); capacity],
+            head: 0,
+            len: 0,
+            acc_secs: 0,
+            acc_peak: 0.0,
+            acc_count: 0,
+            acc_sum_sq: 0.0,
+            acc_rms_count: 0,
+            last_seen_ms: 0,
+            clip: None,
+        }
+    }
+
+    fn push(&mut self, sample: HistorySample) {
+        let cap = self.ring.len();
+        if cap == 0 {
+            return;
+        }
+        self.ring[self.head] = sample;
+        self.head = (self.head + 1) % cap;
+        self.len = (self.len + 1).min(cap);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &HistorySample> {
+        let cap = self.ring.len();
+        let start = (self.head + cap - self.len) % cap.max(1);
+        (0..self.len).map(move |i| &self.ring[(start + i) % cap])
+    }
+
+    fn flush_second(&mut self) {
+        if self.acc_count == 0 {
+            return;
+        }
+        let sample = HistorySample {
+            secs: self.acc_secs,
+            peak: self.acc_peak,
+            rms: (self.acc_rms_count > 0)
+                .then(|| (self.acc_sum_sq / self.acc_rms_count as f32).sqrt()),
+        };
+        self.push(sample);
+        self.acc_peak = 0.0;
+        self.acc_count = 0;
+        self.acc_sum_sq = 0.0;
+        self.acc_rms_count = 0;
+    }
+
+    fn resize(&mut self, capacity: usize) {
+        let kept: Vec<HistorySample> = self
+            .iter()
+            .skip(self.len.saturating_sub(capacity))
+            .copied()
+            .collect();
+        self.ring = vec![HistorySample::default(); capacity];
+        self.head = 0;
+        self.len = 0;
+        for s in kept {
+            self.push(s);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClippingEvent {
+    pub device_id: String,
+    /// Unix time in milliseconds.
+    pub start_ms: u64,
+    pub duration_ms: u64,
+    pub peak_db: f32,
+    /// Position in the recording when the clip started, if one was running.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recording_offset_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPoint {
+    /// Unix time in milliseconds.
+    pub timestamp: u64,
+    pub peak: f32,
+    /// Missing for devices only peak-metered (no OBS input uses them).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rms: Option<f32>,
+}
+
+#[derive(Debug)]
+pub struct AudioHistory {
+    devices: HashMap<String, DeviceHistory>,
+    clipping: VecDeque<ClippingEvent>,
+    capacity: usize,
+    recording: Option<RecordingClock>,
+}
+
+/// Recording position between GetRecordStatus polls: OBS's last reported
+/// duration plus the wall time since, frozen while paused.
+#[derive(Debug)]
+struct RecordingClock {
+    reported_ms: u64,
+    offset_ms: u64,
+    at_ms: u64,
+    paused: bool,
+}
+
+impl RecordingClock {
+    fn new(duration_ms: u64, paused: bool, now_ms: u64) -> Self {
+        Self {
+            reported_ms: duration_ms,
+            offset_ms: duration_ms,
+            at_ms: now_ms,
+            paused,
+        }
+    }
+
+    fn estimate(&self, now_ms: u64) -> u64 {
+        if self.paused {
+            self.offset_ms
+        } else {
+            self.offset_ms + now_ms.saturating_sub(self.at_ms)
+        }
+    }
+
+    fn update(&mut self, duration_ms: u64, paused: bool, now_ms: u64) -> u64 {
+        if duration_ms != self.reported_ms {
+            *self = Self::new(duration_ms, paused, now_ms);
+        } else if paused != self.paused {
+            self.offset_ms = self.estimate(now_ms);
+            self.at_ms = now_ms;
+            self.paused = paused;
+        }
+        self.estimate(now_ms)
+    }
+}
+
+pub type SharedAudioHistory = Arc<std::sync::Mutex<AudioHistory>>;
+
+impl Default for AudioHistory {
+    fn default() -> Self {
+        Self {
+            devices: HashMap::new(),
+            clipping: VecDeque::with_capacity(64),
+            capacity: history_capacity(AudioHistorySettings::default().hours),
+            recording: None,
+        }
+    }
+}
+
+fn history_capacity(hours: u32) -> usize {
+    hours.clamp(1, HISTORY_MAX_HOURS) as usize * 3600
+}
+
+fn to_db(linear: f32) -> f32 {
+    if linear <= 0.0 {
+        -100.0
+    } else {
+        (20.0 * linear.log10()).max(-100.0)
+    }
+}
+
+impl AudioHistory {
+    fn set_capacity(&mut self, capacity: usize) {
+        if capacity == self.capacity {
+            return;
+        }
+        self.capacity = capacity;
+        for dev in self.devices.values_mut() {
+            dev.resize(capacity);
+        }
+    }
+
+    /// `recording` is `(duration_ms, paused)` from the OBS record status
+    /// while a recording runs; `rms` holds the loudness meters' readings.
+    fn record_tick(
+        &mut self,
+        now_ms: u64,
+        tick_ms: u64,
+        levels: &[DevicePeakLevel],
+        rms: &HashMap<String, f32>,
+        recording: Option<(u64, bool)>,
+    ) {
+        let offset = match recording {
+            Some((duration_ms, paused)) => Some(match self.recording.as_mut() {
+                Some(clock) => clock.update(duration_ms, paused, now_ms),
+                None => self
+                    .recording
+                    .insert(RecordingClock::new(duration_ms, paused, now_ms))
+                    .estimate(now_ms),
+            }),
+            None => {
+                self.recording = None;
+                None
+            }
+        };
+
+        let secs = now_ms / 1000;
+        for level in levels {
+            if !self.devices.contains_key(&level.device_id) {
+                self.devices
+                    .insert(level.device_id.clone(), DeviceHistory::new(self.capacity));
+            }
+            let Some(dev) = self.devices.get_mut(&level.device_id) else {
+                continue;
+            };
+            dev.last_seen_ms = now_ms;
+
+            if dev.acc_count > 0 && dev.acc_secs != secs {
+                dev.flush_second();
+            }
+            dev.acc_secs = secs;
+            dev.acc_peak = dev.acc_peak.max(level.peak);
+            dev.acc_count += 1;
+            if let Some(r) = crate::device_matching::lookup_by_id(rms, &level.device_id) {
+                dev.acc_sum_sq += r * r;
+                dev.acc_rms_count += 1;
+            }
+
+            if level.peak >= CLIP_THRESHOLD {
+                match dev.clip.as_mut() {
+                    Some(clip) => {
+                        clip.last_ms = now_ms;
+                        clip.peak = clip.peak.max(level.peak);
+                    }
+                    None => {
+                        dev.clip = Some(OpenClip {
+                            start_ms: now_ms,
+                            last_ms: now_ms,
+                            peak: level.peak,
+                            recording_offset_ms: offset,
+                        });
+                    }
+                }
+            } else if let Some(clip) = dev.clip.take() {
+                push_clip(&mut self.clipping, &level.device_id, clip, tick_ms);
+            }
+        }
+
+        // Devices that vanished mid-clip still get their event closed.
+        for (id, dev) in self.devices.iter_mut() {
+            if dev.last_seen_ms != now_ms {
+                if let Some(clip) = dev.clip.take() {
+                    push_clip(&mut self.clipping, id, clip, tick_ms);
+                }
+            }
+        }
+        // Once a device's newest sample has aged out of the window there is
+        // nothing left to show for it.
+        let window_ms = self.capacity as u64 * 1000;
+        self.devices
+            .retain(|_, dev| now_ms.saturating_sub(dev.last_seen_ms) <= window_ms);
+    }
+
+    fn history(&self, device_id: &str, from: u64, to: u64, max_points: usize) -> Vec<HistoryPoint> {
+        let Some(dev) = self.devices.get(device_id) else {
+            return Vec::new();
+        };
+        let samples: Vec<&HistorySample> = dev
+            .iter()
+            .filter(|s| s.secs * 1000 >= from && s.secs * 1000 <= to)
+            .collect();
+        let bucket = samples.len().div_ceil(max_points.max(1)).max(1);
+        samples
+            .chunks(bucket)
+            .map(|chunk| {
+                let peak = chunk.iter().map(|s| s.peak).fold(0.0, f32::max);
+                let rms: Vec<f32> = chunk.iter().filter_map(|s| s.rms).collect();
+                HistoryPoint {
+                    timestamp: chunk[0].secs * 1000,
+                    peak,
+                    rms: (!rms.is_empty()).then(|| {
+                        (rms.iter().map(|r| r * r).sum::<f32>() / rms.len() as f32).sqrt()
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    /// Clipping events that started within `[from, to]`, oldest first.
+    pub fn clipping_events(&self, from: u64, to: u64) -> Vec<ClippingEvent> {
+        self.clipping
+            .iter()
+            .filter(|e| e.start_ms >= from && e.start_ms <= to)
+            .cloned()
+            .collect()
+    }
+}
+
+fn push_clip(log: &mut VecDeque<ClippingEvent>, device_id: &str, clip: OpenClip, tick_ms: u64) {
+    if log.len() >= CLIPPING_LOG_CAP {
+        log.pop_front();
+    }
+    log.push_back(ClippingEvent {
+        device_id: device_id.to_string(),
+        start_ms: clip.start_ms,
+        duration_ms: clip.last_ms - clip.start_ms + tick_ms,
+        peak_db: to_db(clip.peak),
+        recording_offset_ms: clip.recording_offset_ms,
+    });
+}
+
+fn unix_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[tauri::command]
+pub async fn get_audio_history(
+    history: tauri::State<'_, SharedAudioHistory>,
+    device_id: String,
+    from: Option<u64>,
+    to: Option<u64>,
+    max_points: Option<usize>,
+) -> Result<Vec<HistoryPoint>, String> {
+    let h = history.lock().map_err(|e| e.to_string())?;
+    Ok(h.history(
+        &device_id,
+        from.unwrap_or(0),
+        to.unwrap_or(u64::MAX),
+        max_points.unwrap_or(DEFAULT_HISTORY_POINTS),
+    ))
+}
+
+#[tauri::command]
+pub async fn get_clipping_events(
+    history: tauri::State<'_, SharedAudioHistory>,
+    from: Option<u64>,
+    to: Option<u64>,
+) -> Result<Vec<ClippingEvent>, String> {
+    let h = history.lock().map_err(|e| e.to_string())?;
+    Ok(h.clipping_events(from.unwrap_or(0), to.unwrap_or(u64::MAX)))
+}
@@ -0,0 +1,751 @@
+use crate::audio;
+use crate::device_matching;
+use crate::obs_config;
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::routing;
+use crate::spectrum::{self, MONITOR_AND_OUTPUT, MONITOR_ONLY};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Mutex;
+
+pub type SharedAudioRouter = Arc<Mutex<AudioRouter>>;
+
+/// Added latency above this is reported through `audio-route://latency`.
+pub const LATENCY_WARN_MS: u32 = 30;
+#[cfg(windows)]
+const RENDER_FILL_MS: usize = 20;
+#[cfg(windows)]
+const MAX_QUEUE_MS: usize = 60;
+#[cfg(windows)]
+const POLL_MS: u64 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioRoute {
+    pub input_name: String,
+    pub device_id: String,
+    pub device_name: String,
+    pub gain_db: f64,
+    pub keep_in_stream: bool,
+    pub latency_ms: u32,
+}
+
+struct RouteHandle {
+    info: AudioRoute,
+    stop: Arc<AtomicBool>,
+    latency_ms: Arc<AtomicU32>,
+    /// Monitor type to put back when the route ends.
+    previous_monitor: String,
+}
+
+#[derive(Default)]
+pub struct AudioRouter {
+    routes: HashMap<String, RouteHandle>,
+}
+
+/// A monitor type to put back, kept on disk while a route is live so a
+/// crash doesn't leave the input monitor-only.
+#[derive(Serialize, Deserialize)]
+struct PendingRestore {
+    input_name: String,
+    monitor_type: String,
+}
+
+fn routes_file_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("audio_routes.json")
+}
+
+fn save_pending(pending: &[PendingRestore]) {
+    let path = routes_file_path();
+    if pending.is_empty() {
+        let _ = std::fs::remove_file(&path);
+        return;
+    }
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| crate::settings::write_atomic(&path, serde_json::to_vec_pretty(pending).unwrap_or_default()));
+    if let Err(e) = result {
+        log::warn!("Failed to save audio route state: {}", e);
+    }
+}
+
+/// Writes the live routes' monitor types to disk. Called whenever they change.
+fn persist(router: &AudioRouter) {
+    let pending: Vec<PendingRestore> = router
+        .routes
+        .values()
+        .map(|h| PendingRestore {
+            input_name: h.info.input_name.clone(),
+            monitor_type: h.previous_monitor.clone(),
+        })
+        .collect();
+    save_pending(&pending);
+}
+
+/// Puts back monitor types left changed by routes that never ended (app
+/// crash or lost connection). Called after each (re)connect; routes still
+/// running are left alone.
+pub async fn restore_pending(conn: &ObsHandle, obs_state: &SharedObsState, app: &AppHandle) {
+    let Ok(content) = std::fs::read_to_string(routes_file_path()) else {
+        return;
+    };
+    let pending: Vec<PendingRestore> = match serde_json::from_str(&content) {
+        Ok(p) => p,
+        Err(e) => {
+            log::warn!("Discarding unreadable audio route state: {}", e);
+            save_pending(&[]);
+            return;
+        }
+    };
+    let live: Vec<String> = match app.try_state::<SharedAudioRouter>() {
+        Some(router) => router.lock().await.routes.keys().cloned().collect(),
+        None => Vec::new(),
+    };
+    let mut keep = Vec::new();
+    for entry in pending {
+        if live.contains(&entry.input_name) {
+            keep.push(entry);
+            continue;
+        }
+        log::info!("Restoring monitoring on '{}' from an interrupted audio route", entry.input_name);
+        if let Err(e) = spectrum::set_monitor_type(conn, obs_state, &entry.input_name, &entry.monitor_type).await {
+            log::warn!("Failed to restore monitoring on '{}': {}", entry.input_name, e);
+            keep.push(entry);
+        }
+    }
+    save_pending(&keep);
+}
+
+struct RouteSpec {
+    input_name: String,
+    capture_device_id: String,
+    render_device_id: String,
+    gain: f32,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RouteEndedPayload {
+    input_name: String,
+    reason: String,
+}
+
+/// Linear resampler with channel mapping, fed interleaved capture chunks.
+/// Keeps the last frame of the previous chunk so interpolation is continuous
+/// across packet boundaries.
+#[cfg(windows)]
+struct Resampler {
+    step: f64,
+    pos: f64,
+    last: Vec<f32>,
+    channels_in: usize,
+    channels_out: usize,
+    gain: f32,
+}
+
+#[cfg(windows)]
+impl Resampler {
+    fn new(rate_in: u32, rate_out: u32, channels_in: usize, channels_out: usize, gain: f32) -> Self {
+        Self {
+            step: rate_in as f64 / rate_out as f64,
+            pos: 0.0,
+            last: vec![0.0; channels_in],
+            channels_in,
+            channels_out,
+            gain,
+        }
+    }
+
+    fn process(&mut self, input: &[f32], out: &mut std::collections::VecDeque<f32>) {
+        let cin = self.channels_in;
+        let frames = input.len() / cin;
+        if frames == 0 {
+            return;
+        }
+        // Frame 0 is the carried-over last frame, 1..=frames the new chunk.
+        let sample = |i: usize, ch: usize| {
+            if i == 0 {
+                self.last[ch]
+            } else {
+                input[(i - 1) * cin + ch]
+            }
+        };
+        let mut pos = self.pos;
+        while pos + 1.0 <= frames as f64 {
+            let i = pos as usize;
+            let frac = (pos - i as f64) as f32;
+            let at = |ch: usize| {
+                let a = sample(i, ch);
+                a + (sample(i + 1, ch) - a) * frac
+            };
+            if self.channels_out == 1 && cin > 1 {
+                out.push_back((0..cin).map(at).sum::<f32>() / cin as f32 * self.gain);
+            } else {
+                for c in 0..self.channels_out {
+                    out.push_back(at(c % cin) * self.gain);
+                }
+            }
+            pos += self.step;
+        }
+        self.pos = pos - frames as f64;
+        self.last.copy_from_slice(&input[(frames - 1) * cin..frames * cin]);
+    }
+}
+
+fn route_list(router: &AudioRouter) -> Vec<AudioRoute> {
+    router
+        .routes
+        .values()
+        .map(|r| AudioRoute {
+            latency_ms: r.latency_ms.load(Ordering::Relaxed),
+            ..r.info.clone()
+        })
+        .collect()
+}
+
+/// Removes a route and puts the input's monitor type back. `only` limits the
+/// removal to the route owning that stop flag, so a thread that ends late
+/// can't tear down a newer route for the same input.
+async fn end_route(
+    router: &SharedAudioRouter,
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    input_name: &str,
+    only: Option<&Arc<AtomicBool>>,
+) -> bool {
+    let removed = {
+        let mut r = router.lock().await;
+        // By stop flag when given, so a rename since the route started
+        // doesn't lose it.
+        let key = match only {
+            Some(stop) => r
+                .routes
+                .iter()
+                .find(|(_, h)| Arc::ptr_eq(&h.stop, stop))
+                .map(|(name, _)| name.clone()),
+            None => r.routes.contains_key(input_name).then(|| input_name.to_string()),
+        };
+        let removed = key.and_then(|name| r.routes.remove(&name).map(|h| (name, h)));
+        if removed.is_some() {
+            persist(&r);
+        }
+        removed
+    };
+    let Some((input_name, handle)) = removed else {
+        return false;
+    };
+    handle.stop.store(true, Ordering::Relaxed);
+    if let Err(e) = spectrum::set_monitor_type(conn, obs_state, &input_name, &handle.previous_monitor).await {
+        log::warn!("Failed to restore monitoring on '{}': {}", input_name, e);
+    }
+    true
+}
+
+pub async fn on_input_renamed(app: AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let Some(router) = app.try_state::<SharedAudioRouter>() else {
+        return Vec::new();
+    };
+    let mut r = router.lock().await;
+    let Some(mut handle) = r.routes.remove(&old_name) else {
+        return Vec::new();
+    };
+    handle.info.input_name = new_name.clone();
+    r.routes.insert(new_name, handle);
+    persist(&r);
+    vec!["audio route".to_string()]
+}
+
+/// Stops every route and restores monitor types before the app exits.
+pub async fn shutdown(app: AppHandle) {
+    let (Some(conn), Some(obs), Some(router)) = (
+        app.try_state::<ObsHandle>(),
+        app.try_state::<SharedObsState>(),
+        app.try_state::<SharedAudioRouter>(),
+    ) else {
+        return;
+    };
+    let inputs: Vec<String> = router.lock().await.routes.keys().cloned().collect();
+    for input in inputs {
+        end_route(&router, &conn, &obs, &input, None).await;
+    }
+}
+
+fn spawn_route(
+    spec: RouteSpec,
+    stop: Arc<AtomicBool>,
+    latency_ms: Arc<AtomicU32>,
+    app_handle: AppHandle,
+) -> tokio::sync::oneshot::Receiver<Result<u32, String>> {
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+    std::thread::spawn(move || {
+        let reason = match run_route(&spec, &stop, &latency_ms, &app_handle, ready_tx) {
+            Ok(()) => "stopped".to_string(),
+            Err(e) => {
+                log::warn!("AudioRoute '{}' ended: {}", spec.input_name, e);
+                e
+            }
+        };
+        let _ = app_handle.emit(
+            "audio-route://ended",
+            RouteEndedPayload {
+                input_name: spec.input_name.clone(),
+                reason,
+            },
+        );
+        tauri::async_runtime::spawn(async move {
+            let router = app_handle.state::<SharedAudioRouter>().inner().clone();
+            let conn = app_handle.state::<ObsHandle>().inner().clone();
+            let obs_state = app_handle.state::<SharedObsState>().inner().clone();
+            end_route(&router, &conn, &obs_state, &spec.input_name, Some(&stop)).await;
+        });
+    });
+    ready_rx
+}
+
+#[cfg(windows)]
+fn run_route(
+    spec: &RouteSpec,
+    stop: &AtomicBool,
+    latency_ms: &AtomicU32,
+    app_handle: &AppHandle,
+    ready: tokio::sync::oneshot::Sender<Result<u32, String>>,
+) -> Result<(), String> {
+    use windows::Win32::System::Com::*;
+
+    unsafe {
+        if CoInitializeEx(None, COINIT_MULTITHREADED).ok().is_err() {
+            let _ = ready.send(Err("COM init failed".into()));
+            return Err("COM init failed".into());
+        }
+    }
+    let result = route_loop(spec, stop, latency_ms, app_handle, ready);
+    unsafe {
+        CoUninitialize();
+    }
+    result
+}
+
+#[cfg(windows)]
+struct Stream {
+    client: windows::Win32::Media::Audio::IAudioClient,
+    rate: u32,
+    channels: usize,
+    bits: u16,
+    block_align: usize,
+    latency_ms: u32,
+}
+
+#[cfg(windows)]
+fn open_stream(device_id: &str, loopback: bool) -> Result<Stream, String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Media::Audio::*;
+    use windows::Win32::System::Com::*;
+
+    const BUFFER_HNS: i64 = 1_000_000; // 100ms in 100ns units
+
+    unsafe {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(|e| format!("Enumerator: {}", e))?;
+        let wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+        let device = enumerator
+            .GetDevice(PCWSTR(wide.as_ptr()))
+            .map_err(|e| format!("GetDevice: {}", e))?;
+        let client: IAudioClient = device
+            .Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Activate IAudioClient: {}", e))?;
+        let mix_format = client.GetMixFormat().map_err(|e| format!("GetMixFormat: {}", e))?;
+        let fmt = *mix_format;
+        let flags = if loopback { AUDCLNT_STREAMFLAGS_LOOPBACK } else { 0 };
+        let initialized = client.Initialize(AUDCLNT_SHAREMODE_SHARED, flags, BUFFER_HNS, 0, mix_format, None);
+        CoTaskMemFree(Some(mix_format as *const _));
+        initialized.map_err(|e| format!("Initialize: {}", e))?;
+        let latency = client.GetStreamLatency().unwrap_or(0);
+        Ok(Stream {
+            rate: fmt.nSamplesPerSec,
+            channels: fmt.nChannels as usize,
+            bits: fmt.wBitsPerSample,
+            block_align: fmt.nBlockAlign as usize,
+            latency_ms: (latency / 10_000) as u32,
+            client,
+        })
+    }
+}
+
+#[cfg(windows)]
+fn route_loop(
+    spec: &RouteSpec,
+    stop: &AtomicBool,
+    latency_ms: &AtomicU32,
+    app_handle: &AppHandle,
+    ready: tokio::sync::oneshot::Sender<Result<u32, String>>,
+) -> Result<(), String> {
+    use windows::Win32::Media::Audio::{IAudioCaptureClient, IAudioRenderClient};
+
+    let capture = match open_stream(&spec.capture_device_id, true) {
+        Ok(s) => s,
+        Err(e) => {
+            let e = format!("Monitoring device: {}", e);
+            let _ = ready.send(Err(e.clone()));
+            return Err(e);
+        }
+    };
+    let render = match open_stream(&spec.render_device_id, false) {
+        Ok(s) if s.bits == 32 || s.bits == 16 => s,
+        Ok(s) => {
+            let e = format!("Output device uses unsupported {}-bit samples", s.bits);
+            let _ = ready.send(Err(e.clone()));
+            return Err(e);
+        }
+        Err(e) => {
+            let e = format!("Output device: {}", e);
+            let _ = ready.send(Err(e.clone()));
+            return Err(e);
+        }
+    };
+    let start = || -> windows::core::Result<(IAudioCaptureClient, IAudioRenderClient, u32)> {
+        unsafe {
+            let capture_client: IAudioCaptureClient = capture.client.GetService()?;
+            let render_client: IAudioRenderClient = render.client.GetService()?;
+            let buffer_frames = render.client.GetBufferSize()?;
+            render.client.Start()?;
+            capture.client.Start()?;
+            Ok((capture_client, render_client, buffer_frames))
+        }
+    };
+    let (capture_client, render_client, buffer_frames) = match start() {
+        Ok(c) => c,
+        Err(e) => {
+            let e = format!("Failed to start audio route: {}", e);
+            let _ = ready.send(Err(e.clone()));
+            return Err(e);
+        }
+    };
+
+    let base_latency = capture.latency_ms + render.latency_ms;
+    let estimate = base_latency + RENDER_FILL_MS as u32;
+    latency_ms.store(estimate, Ordering::Relaxed);
+    let _ = ready.send(Ok(estimate));
+
+    log::info!(
+        "AudioRoute '{}': {}Hz/{}ch -> {}Hz/{}ch, ~{}ms",
+        spec.input_name, capture.rate, capture.channels, render.rate, render.channels, estimate
+    );
+
+    let result = pump(
+        spec,
+        stop,
+        latency_ms,
+        app_handle,
+        (&capture, &capture_client),
+        (&render, &render_client, buffer_frames),
+        base_latency,
+    );
+    unsafe {
+        let _ = capture.client.Stop();
+        let _ = render.client.Stop();
+    }
+    result
+}
+
+/// Moves audio from the loopback capture to the output device until stopped
+/// or one of the devices goes away.
+#[cfg(windows)]
+fn pump(
+    spec: &RouteSpec,
+    stop: &AtomicBool,
+    latency_ms: &AtomicU32,
+    app_handle: &AppHandle,
+    (capture, capture_client): (&Stream, &windows::Win32::Media::Audio::IAudioCaptureClient),
+    (render, render_client, buffer_frames): (&Stream, &windows::Win32::Media::Audio::IAudioRenderClient, u32),
+    base_latency: u32,
+) -> Result<(), String> {
+    use std::collections::VecDeque;
+    use std::time::{Duration, Instant};
+
+    let frames_for = |ms: usize| render.rate as usize * ms / 1000;
+    let mut resampler = Resampler::new(capture.rate, render.rate, capture.channels, render.channels, spec.gain);
+    let mut queue: VecDeque<f32> = VecDeque::with_capacity(frames_for(MAX_QUEUE_MS * 2) * render.channels);
+    let mut peak_latency = 0u32;
+    let mut warned = false;
+    let mut last_report = Instant::now();
+
+    while !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(POLL_MS));
+
+        loop {
+            let packet = unsafe { capture_client.GetNextPacketSize() }
+                .map_err(|e| format!("Monitoring device lost: {}", e))?;
+            if packet == 0 {
+                break;
+            }
+            let mut buffer_ptr = std::ptr::null_mut();
+            let mut num_frames = 0u32;
+            let mut flags = 0u32;
+            unsafe { capture_client.GetBuffer(&mut buffer_ptr, &mut num_frames, &mut flags, None, None) }
+                .map_err(|e| format!("Monitoring device lost: {}", e))?;
+            let frame_count = num_frames as usize;
+            let samples = if flags & 0x2 != 0 {
+                vec![0.0f32; frame_count * capture.channels]
+            } else {
+                spectrum::extract_samples(buffer_ptr, frame_count, capture.channels, capture.bits, capture.block_align)
+            };
+            let _ = unsafe { capture_client.ReleaseBuffer(num_frames) };
+            resampler.process(&samples, &mut queue);
+        }
+
+        // Clock drift between the two devices slowly grows the queue; drop
+        // the oldest audio rather than let the delay creep up.
+        if queue.len() > frames_for(MAX_QUEUE_MS) * render.channels {
+            let excess = queue.len() - frames_for(RENDER_FILL_MS) * render.channels;
+            queue.drain(..excess - excess % render.channels);
+        }
+
+        let padding = unsafe { render.client.GetCurrentPadding() }
+            .map_err(|e| format!("Output device lost: {}", e))? as usize;
+        let target = frames_for(RENDER_FILL_MS).min(buffer_frames as usize);
+        let frames = target.saturating_sub(padding).min(queue.len() / render.channels);
+        if frames > 0 {
+            let ptr = unsafe { render_client.GetBuffer(frames as u32) }
+                .map_err(|e| format!("Output device lost: {}", e))?;
+            let count = frames * render.channels;
+            unsafe {
+                if render.bits == 32 {
+                    let out = std::slice::from_raw_parts_mut(ptr as *mut f32, count);
+                    for (dst, src) in out.iter_mut().zip(queue.drain(..count)) {
+                        *dst = src;
+                    }
+                } else {
+                    let out = std::slice::from_raw_parts_mut(ptr as *mut i16, count);
+                    for (dst, src) in out.iter_mut().zip(queue.drain(..count)) {
+                        *dst = (src.clamp(-1.0, 1.0) * 32767.0) as i16;
+                    }
+                }
+            }
+            unsafe { render_client.ReleaseBuffer(frames as u32, 0) }
+                .map_err(|e| format!("Output device lost: {}", e))?;
+        }
+
+        let buffered = (padding + frames + queue.len() / render.channels) * 1000 / render.rate as usize;
+        peak_latency = peak_latency.max(base_latency + buffered as u32);
+        if last_report.elapsed() >= Duration::from_secs(1) {
+            latency_ms.store(peak_latency, Ordering::Relaxed);
+            let high = peak_latency > LATENCY_WARN_MS;
+            if high != warned {
+                let _ = app_handle.emit(
+                    "audio-route://latency",
+                    serde_json::json!({"inputName": spec.input_name, "latencyMs": peak_latency, "high": high}),
+                );
+                warned = high;
+            }
+            peak_latency = 0;
+            last_report = Instant::now();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn run_route(
+    _spec: &RouteSpec,
+    _stop: &AtomicBool,
+    _latency_ms: &AtomicU32,
+    _app_handle: &AppHandle,
+    ready: tokio::sync::oneshot::Sender<Result<u32, String>>,
+) -> Result<(), String> {
+    let msg = "Audio routing is only supported on Windows".to_string();
+    let _ = ready.send(Err(msg.clone()));
+    Err(msg)
+}
+
+/// Plays an input on a second output device. OBS only renders audio to its
+/// one monitoring device, so the input is monitored there and that device is
+/// captured via loopback and re-rendered to `device_id` with `gain_db`.
+/// Loopback hears everything on that device, so routing is refused while
+/// other inputs are monitored. `keep_in_stream` uses monitor-and-output so
+/// the stream still has it.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn route_input_to_device(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    router: tauri::State<'_, SharedAudioRouter>,
+    app_handle: AppHandle,
+    input_name: String,
+    device_id: String,
+    gain_db: f64,
+    keep_in_stream: Option<bool>,
+) -> Result<AudioRoute, String> {
+    let keep_in_stream = keep_in_stream.unwrap_or(false);
+    let gain_db = gain_db.clamp(-60.0, 12.0);
+    {
+        let r = router.lock().await;
+        if let Some(existing) = r.routes.keys().find(|name| **name != input_name) {
+            // Every route would carry the whole monitoring mix, so a second
+            // input can't be kept apart from the first.
+            return Err(format!(
+                "'{}' is already routed. OBS has a single monitoring device, so only one input can be routed at a time.",
+                existing
+            ));
+        }
+    }
+    // Re-routing the same input starts from its original monitor type.
+    end_route(&router, &conn_state, &obs_state, &input_name, None).await;
+
+    let current_monitor = {
+        let state = obs_state.read().await;
+        let input = state
+            .inputs
+            .get(&input_name)
+            .ok_or_else(|| format!("Source '{}' not found", input_name))?;
+        if !input.audio_tracks.is_object() {
+            return Err(format!("'{}' has no audio", input_name));
+        }
+        input.monitor_type.clone()
+    };
+
+    let audio_config = tokio::task::spawn_blocking(obs_config::read_obs_audio_config)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    let devices = tokio::task::spawn_blocking(audio::enumerate_audio_devices)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    let monitor_device = device_matching::resolve_device(
+        "wasapi_output_capture",
+        &audio_config.monitoring_device_id,
+        &devices,
+    )
+    .ok_or("OBS monitoring device not found")?
+    .clone();
+    let target = devices
+        .iter()
+        .find(|d| d.device_type == "output" && device_matching::ids_match(&d.id, &device_id))
+        .ok_or_else(|| format!("Output device '{}' not found", device_id))?
+        .clone();
+    if target.id == monitor_device.id {
+        return Err(format!("{} is already OBS's monitoring device", target.name));
+    }
+
+    let (monitor_loops, target_loops, also_monitored) = {
+        let state = obs_state.read().await;
+        let also: Vec<String> = state
+            .inputs
+            .values()
+            .filter(|i| i.name != input_name && (i.monitor_type == MONITOR_ONLY || i.monitor_type == MONITOR_AND_OUTPUT))
+            .map(|i| i.name.clone())
+            .collect();
+        (
+            routing::monitoring_loop_inputs(&state, &devices, &audio_config.monitoring_device_id),
+            routing::monitoring_loop_inputs(&state, &devices, &target.id),
+            also,
+        )
+    };
+    if !monitor_loops.is_empty() {
+        return Err(format!(
+            "'{}' captures the monitoring device ({}), so monitoring '{}' would echo into the stream. \
+             Pick a different monitoring device in OBS Settings > Audio first.",
+            monitor_loops.join("', '"),
+            monitor_device.name,
+            input_name
+        ));
+    }
+    if !target_loops.is_empty() {
+        return Err(format!(
+            "'{}' captures {}, so routing '{}' there would put it back into the stream.",
+            target_loops.join("', '"),
+            target.name,
+            input_name
+        ));
+    }
+    if !also_monitored.is_empty() {
+        return Err(format!(
+            "'{}' is also monitored and would be heard on {} too. Turn its monitoring off first.",
+            also_monitored.join("', '"),
+            target.name
+        ));
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let latency_ms = Arc::new(AtomicU32::new(0));
+    let spec = RouteSpec {
+        input_name: input_name.clone(),
+        capture_device_id: monitor_device.id.clone(),
+        render_device_id: target.id.clone(),
+        gain: 10f64.powf(gain_db / 20.0) as f32,
+    };
+    let info = AudioRoute {
+        input_name: input_name.clone(),
+        device_id: target.id.clone(),
+        device_name: target.name.clone(),
+        gain_db,
+        keep_in_stream,
+        latency_ms: 0,
+    };
+    {
+        // Checked again under the same lock as the insert, so two routes
+        // started at once can't both get in.
+        let mut r = router.lock().await;
+        if let Some(existing) = r.routes.keys().next() {
+            return Err(format!("'{}' is already routed", existing));
+        }
+        r.routes.insert(
+            input_name.clone(),
+            RouteHandle {
+                info: info.clone(),
+                stop: stop.clone(),
+                latency_ms: latency_ms.clone(),
+                previous_monitor: current_monitor,
+            },
+        );
+        persist(&r);
+    }
+    let monitor_type = if keep_in_stream { MONITOR_AND_OUTPUT } else { MONITOR_ONLY };
+    if let Err(e) = spectrum::set_monitor_type(&conn_state, &obs_state, &input_name, monitor_type).await {
+        end_route(&router, &conn_state, &obs_state, &input_name, Some(&stop)).await;
+        return Err(e);
+    }
+
+    let ready = spawn_route(spec, stop, latency_ms, app_handle);
+    let latency = ready
+        .await
+        .map_err(|_| "Audio route thread exited unexpectedly".to_string())
+        .and_then(|r| r);
+    match latency {
+        Ok(latency_ms) => {
+            if latency_ms > LATENCY_WARN_MS {
+                log::warn!("Route for '{}' adds ~{}ms of latency", input_name, latency_ms);
+            }
+            Ok(AudioRoute { latency_ms, ..info })
+        }
+        Err(e) => {
+            end_route(&router, &conn_state, &obs_state, &input_name, None).await;
+            Err(e)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn stop_route(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    router: tauri::State<'_, SharedAudioRouter>,
+    input_name: String,
+) -> Result<(), String> {
+    if !end_route(&router, &conn_state, &obs_state, &input_name, None).await {
+        return Err(format!("'{}' is not routed", input_name));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_active_routes(router: tauri::State<'_, SharedAudioRouter>) -> Result<Vec<AudioRoute>, String> {
+    Ok(route_list(&router.lock().await))
+}
@@ -0,0 +1,130 @@
+use crate::obs_websocket::ObsHandle;
+use serde_json::json;
+
+const BROWSER_KIND: &str = "browser_source";
+const MAX_DIMENSION: u32 = 4096;
+
+/// Fails with a readable message unless `input_name` is a browser source.
+async fn ensure_browser(conn: &ObsHandle, input_name: &str) -> Result<(), String> {
+    let v = conn
+        .send_request("GetInputSettings", Some(json!({ "inputName": input_name })))
+        .await?;
+    match v["inputKind"].as_str().unwrap_or("") {
+        BROWSER_KIND => Ok(()),
+        other => Err(format!("\"{}\" is a {} input, not a browser source", input_name, other)),
+    }
+}
+
+fn check_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid URL '{}': {}", url, e))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(()),
+        other => Err(format!("Browser sources need an http or https URL, got {}:", other)),
+    }
+}
+
+/// Alert and overlay services that put the widget token in the path.
+const WIDGET_HOSTS: &[&str] = &["streamelements.com", "streamlabs.com", "tipeeestream.com"];
+
+/// A URL safe to show or send to the AI: no credentials, query string or
+/// fragment, which often carry widget tokens. For widget hosts only the first
+/// path segment is kept, e.g. https://streamelements.com/overlay/…
+pub fn redact_url(url: &str) -> String {
+    let Ok(mut parsed) = reqwest::Url::parse(url.trim()) else {
+        // A local file path.
+        return url.split(['?', '#']).next().unwrap_or(url).to_string();
+    };
+    let _ = parsed.set_username("");
+    let _ = parsed.set_password(None);
+    parsed.set_query(None);
+    parsed.set_fragment(None);
+    let widget = parsed.host_str().is_some_and(|host| {
+        WIDGET_HOSTS
+            .iter()
+            .any(|w| host == *w || host.ends_with(&format!(".{}", w)))
+    });
+    if widget {
+        let first = parsed
+            .path_segments()
+            .and_then(|mut s| s.next())
+            .unwrap_or("")
+            .to_string();
+        let truncated = parsed.path().len() > first.len() + 1;
+        parsed.set_path(&first);
+        if truncated {
+            return format!("{}/…", parsed.as_str().trim_end_matches('/'));
+        }
+    }
+    parsed.to_string()
+}
+
+/// Reloads the page, bypassing the browser cache. The usual fix for a
+/// stuck alert box.
+#[tauri::command]
+pub async fn refresh_browser_source(
+    conn_state: tauri::State<'_, ObsHandle>,
+    input_name: String,
+) -> Result<(), String> {
+    ensure_browser(&conn_state, &input_name).await?;
+    conn_state
+        .send_request(
+            "PressInputPropertiesButton",
+            Some(json!({ "inputName": input_name, "propertyName": "refreshnocache" })),
+        )
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_browser_source_url(
+    conn_state: tauri::State<'_, ObsHandle>,
+    input_name: String,
+    url: String,
+) -> Result<(), String> {
+    check_url(&url)?;
+    ensure_browser(&conn_state, &input_name).await?;
+    conn_state
+        .send_request(
+            "SetInputSettings",
+            Some(json!({
+                "inputName": input_name,
+                "inputSettings": { "url": url.trim(), "is_local_file": false },
+                "overlay": true
+            })),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Adds a browser source to `scene_name` and returns its scene item id.
+#[tauri::command]
+pub async fn create_browser_source(
+    conn_state: tauri::State<'_, ObsHandle>,
+    scene_name: String,
+    name: String,
+    url: String,
+    width: u32,
+    height: u32,
+) -> Result<u64, String> {
+    check_url(&url)?;
+    let created = conn_state
+        .send_request(
+            "CreateInput",
+            Some(json!({
+                "sceneName": scene_name,
+                "inputName": name,
+                "inputKind": BROWSER_KIND,
+                "inputSettings": {
+                    "url": url.trim(),
+                    "is_local_file": false,
+                    "width": width.clamp(1, MAX_DIMENSION),
+                    "height": height.clamp(1, MAX_DIMENSION)
+                },
+                "sceneItemEnabled": true
+            })),
+        )
+        .await?;
+    created["sceneItemId"]
+        .as_u64()
+        .ok_or_else(|| "OBS did not return the new scene item".to_string())
+}
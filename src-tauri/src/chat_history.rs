@@ -0,0 +1,126 @@
+use crate::gemini::ChatMessage;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Unix seconds of the launch that started this session, which is also its id.
+static SESSION_STARTED: OnceLock<u64> = OnceLock::new();
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSession {
+    pub id: String,
+    pub started_at: u64,
+    pub updated_at: u64,
+    pub messages: Vec<ChatMessage>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSessionInfo {
+    pub id: String,
+    pub started_at: u64,
+    pub updated_at: u64,
+    pub message_count: usize,
+    pub current: bool,
+}
+
+fn sessions_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("chat_sessions")
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Starts the session for this launch. Later calls keep the same one.
+pub fn start_session() -> String {
+    SESSION_STARTED.get_or_init(unix_now).to_string()
+}
+
+fn session_path(id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid chat session id: {}", id));
+    }
+    Ok(sessions_dir().join(format!("{}.json", id)))
+}
+
+fn read_session(path: &std::path::Path) -> Option<ChatSession> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(session) => Some(session),
+        Err(e) => {
+            log::warn!("Failed to parse chat session {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Every saved session, newest first.
+pub fn list_sessions() -> Vec<ChatSessionInfo> {
+    let current = start_session();
+    let Ok(entries) = std::fs::read_dir(sessions_dir()) else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<ChatSessionInfo> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| read_session(&e.path()))
+        .map(|s| ChatSessionInfo {
+            current: s.id == current,
+            message_count: s.messages.len(),
+            id: s.id,
+            started_at: s.started_at,
+            updated_at: s.updated_at,
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    sessions
+}
+
+pub fn load_session(id: &str) -> Result<ChatSession, String> {
+    let path = session_path(id)?;
+    if !path.exists() {
+        return Err(format!("Chat session {} not found", id));
+    }
+    read_session(&path).ok_or_else(|| format!("Chat session {} could not be read", id))
+}
+
+/// Writes this launch's history, then drops the oldest sessions beyond
+/// `keep`. An empty history removes the session's file.
+pub fn save_current(messages: &[ChatMessage], keep: usize) -> Result<(), String> {
+    let id = start_session();
+    let path = session_path(&id)?;
+    if messages.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path).map_err(|e| format!("Failed to remove chat session: {}", e))?;
+        }
+        return Ok(());
+    }
+
+    std::fs::create_dir_all(sessions_dir()).map_err(|e| format!("Failed to create dir: {}", e))?;
+    let session = ChatSession {
+        started_at: SESSION_STARTED.get().copied().unwrap_or_default(),
+        updated_at: unix_now(),
+        messages: messages.to_vec(),
+        id,
+    };
+    let json = serde_json::to_string_pretty(&session)
+        .map_err(|e| format!("Failed to serialize chat session: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to save chat session: {}", e))?;
+
+    for old in list_sessions().iter().filter(|s| !s.current).skip(keep.saturating_sub(1)) {
+        if let Ok(path) = session_path(&old.id) {
+            if let Err(e) = std::fs::remove_file(&path) {
+                log::warn!("Failed to remove old chat session {}: {}", old.id, e);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use crate::ai_metrics::ModelTiming;
+use crate::api_key_store::{self, KeySource};
+use crate::gemini::{ChatMessage, ChatResponse, GeminiProvider, PromptParams};
+use crate::i18n::t;
+use crate::openai_compat::OpenAiProvider;
+use crate::settings::AiSettings;
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const DEFAULT_HISTORY_LIMIT: usize = 10;
+/// Below this a turn can't keep its own question and answer.
+const MIN_HISTORY_LIMIT: usize = 2;
+pub const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProviderKind {
+    #[default]
+    Gemini,
+    /// Any OpenAI-compatible chat completions API: OpenAI itself, Ollama,
+    /// LM Studio, OpenRouter.
+    OpenAi,
+}
+
+impl AiProviderKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Gemini => "gemini",
+            Self::OpenAi => "openai",
+        }
+    }
+
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "gemini" => Ok(Self::Gemini),
+            "openai" => Ok(Self::OpenAi),
+            other => Err(format!("Unknown AI provider: {}", other)),
+        }
+    }
+
+    fn env_var(self) -> &'static str {
+        match self {
+            Self::Gemini => "GEMINI_API_KEY",
+            Self::OpenAi => "OPENAI_API_KEY",
+        }
+    }
+}
+
+/// What a streaming reply passes to its listener as it arrives.
+pub enum StreamEvent<'a> {
+    Token(&'a str),
+    /// Everything streamed so far is void; the reply is being retried and
+    /// streams again from the start.
+    Reset,
+}
+
+/// A chat backend. Each provider builds its own API's requests, including
+/// how the reply is held to the ChatResponse shape, so the rest of the app
+/// only sees ChatResponse.
+pub trait ChatProvider: Send + Sync {
+    fn kind(&self) -> AiProviderKind;
+
+    fn send_message<'a>(
+        &'a mut self,
+        user_text: &'a str,
+        params: PromptParams<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse, String>>;
+
+    /// Passes the reply's message text to `on_token` as it arrives.
+    /// Providers that can't stream pass it in one piece once it's in.
+    fn send_message_streaming<'a>(
+        &'a mut self,
+        user_text: &'a str,
+        params: PromptParams<'a>,
+        on_token: &'a mut (dyn FnMut(StreamEvent<'_>) + Send),
+    ) -> BoxFuture<'a, Result<ChatResponse, String>> {
+        Box::pin(async move {
+            let response = self.send_message(user_text, params).await?;
+            if !response.message.is_empty() {
+                on_token(StreamEvent::Token(&response.message));
+            }
+            Ok(response)
+        })
+    }
+
+    fn send_function_responses(&mut self, _results: Vec<Value>) -> BoxFuture<'_, Result<ChatResponse, String>> {
+        Box::pin(async { Err("No function calls awaiting responses".to_string()) })
+    }
+
+    /// `send_function_responses`, streaming the follow-up reply's text the
+    /// way `send_message_streaming` does.
+    fn send_function_responses_streaming<'a>(
+        &'a mut self,
+        results: Vec<Value>,
+        on_token: &'a mut (dyn FnMut(StreamEvent<'_>) + Send),
+    ) -> BoxFuture<'a, Result<ChatResponse, String>> {
+        Box::pin(async move {
+            let response = self.send_function_responses(results).await?;
+            if !response.message.is_empty() {
+                on_token(StreamEvent::Token(&response.message));
+            }
+            Ok(response)
+        })
+    }
+
+    fn function_calling(&self) -> bool {
+        false
+    }
+
+    fn set_function_calling(&mut self, _enabled: bool) {}
+
+    fn awaiting_function_responses(&self) -> bool {
+        false
+    }
+
+    fn abandon_tool_turns(&mut self) {}
+
+    fn set_model(&mut self, model: &str);
+
+    fn chat_history(&self) -> &ChatHistory;
+
+    fn chat_history_mut(&mut self) -> &mut ChatHistory;
+
+    /// Timing accumulated since the last call, for the turn just finished.
+    fn take_timing(&mut self) -> ModelTiming;
+
+    fn key_source(&self) -> KeySource;
+
+    fn history(&self) -> &[ChatMessage] {
+        self.chat_history().messages()
+    }
+
+    /// How many messages are kept and sent as context.
+    fn set_history_limit(&mut self, limit: usize) {
+        self.chat_history_mut().set_limit(limit);
+    }
+
+    fn clear_history(&mut self) {
+        self.abandon_tool_turns();
+        self.chat_history_mut().clear();
+    }
+}
+
+/// The conversation sent as context. Trimmed from the front a question and
+/// answer at a time, so a reply is never kept without its user message.
+pub struct ChatHistory {
+    messages: Vec<ChatMessage>,
+    limit: usize,
+}
+
+impl Default for ChatHistory {
+    fn default() -> Self {
+        Self {
+            messages: Vec::new(),
+            limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+}
+
+impl ChatHistory {
+    pub fn messages(&self) -> &[ChatMessage] {
+        &self.messages
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit.max(MIN_HISTORY_LIMIT);
+        self.trim();
+    }
+
+    pub fn push(&mut self, role: &str, text: &str) {
+        self.messages.push(ChatMessage {
+            role: role.into(),
+            text: text.into(),
+        });
+        self.trim();
+    }
+
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    /// At most the last `limit` messages, starting at a user message.
+    pub fn recent(&self, limit: usize) -> &[ChatMessage] {
+        &self.messages[self.pair_start(self.messages.len().saturating_sub(limit))..]
+    }
+
+    /// Removes the user message a failed exchange left without a reply, so
+    /// the next request doesn't resend it.
+    pub fn drop_unanswered(&mut self) {
+        if self.messages.last().map(|m| m.role == "user").unwrap_or(false) {
+            self.messages.pop();
+        }
+    }
+
+    fn trim(&mut self) {
+        if self.messages.len() > self.limit {
+            let drain_count = self.pair_start(self.messages.len() - self.limit);
+            self.messages.drain(..drain_count);
+        }
+    }
+
+    /// Moves `index` past any replies whose question comes before it.
+    fn pair_start(&self, mut index: usize) -> usize {
+        while self.messages.get(index).is_some_and(|m| m.role == "model") {
+            index += 1;
+        }
+        index
+    }
+}
+
+/// The key for `kind`. Its environment variable wins over a stored key.
+pub fn stored_key(kind: AiProviderKind) -> Result<Option<(String, KeySource)>, String> {
+    if let Some(key) = std::env::var(kind.env_var()).ok().filter(|k| !k.is_empty()) {
+        return Ok(Some((key, KeySource::Env)));
+    }
+    Ok(api_key_store::load(kind.as_str())?.map(|k| (k, KeySource::Disk)))
+}
+
+/// Builds the provider the settings select, configured from them.
+pub fn build(ai: &AiSettings, key: Option<(String, KeySource)>) -> Result<Box<dyn ChatProvider>, String> {
+    let mut provider: Box<dyn ChatProvider> = match ai.provider {
+        AiProviderKind::Gemini => {
+            let (key, source) = key.ok_or_else(|| t("error.gemini_key_missing", &[]))?;
+            Box::new(GeminiProvider::new(key, source))
+        }
+        AiProviderKind::OpenAi => {
+            if ai.model.trim().is_empty() {
+                return Err("An OpenAI-compatible provider needs a model name".into());
+            }
+            let base_url = match ai.base_url.trim() {
+                "" => DEFAULT_OPENAI_BASE_URL,
+                url => url,
+            };
+            Box::new(OpenAiProvider::new(base_url, key))
+        }
+    };
+    configure(provider.as_mut(), ai);
+    Ok(provider)
+}
+
+/// Applies the settings that don't need the provider rebuilt.
+pub fn configure(provider: &mut dyn ChatProvider, ai: &AiSettings) {
+    provider.set_model(&ai.model);
+    provider.set_function_calling(ai.function_calling);
+    provider.set_history_limit(ai.history_limit);
+}
+
+/// The provider the settings select with its stored key, or None when it
+/// can't be built (e.g. Gemini without a key).
+pub fn from_settings(ai: &AiSettings) -> Option<Box<dyn ChatProvider>> {
+    let key = stored_key(ai.provider).unwrap_or_else(|e| {
+        log::warn!("Ignoring stored {} API key: {}", ai.provider.as_str(), e);
+        None
+    });
+    if ai.provider == AiProviderKind::Gemini && key.is_none() {
+        return None;
+    }
+    match build(ai, key) {
+        Ok(provider) => Some(provider),
+        Err(e) => {
+            log::warn!("AI provider not configured: {}", e);
+            None
+        }
+    }
+}
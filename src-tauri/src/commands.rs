@@ -1,20 +1,43 @@
-use crate::ai_actions::{self, ActionResult, SharedUndoStack};
+use crate::ai_actions::{
+    self, ActionResult, SharedAiEditSession, SharedUndoStack, UndoGroup, UndoGroupResult, UndoHistoryItem,
+};
+use crate::ai_metrics::{ApplyTimer, SharedAiMetrics};
+use crate::api_key_store::{self, KeySource};
 use crate::app_capture::{self, AudioProcess};
 use crate::audio;
-use crate::audio_monitor::{AudioMetrics, SharedAudioMetrics};
+use crate::audio_monitor::{AudioMetrics, SharedAudioMetrics, SharedMetricsNotify};
+use crate::disk_headroom;
 use crate::video_devices;
-use crate::ducking::{DuckingConfig, SharedDuckingConfig};
-use crate::gemini::{AiAction, SharedGeminiClient};
+use crate::video_editor::SharedVideoEditorState;
+use crate::ducking::{self, DuckingConfig, SharedDuckingConfig};
+use crate::endpoint_volume::SharedEndpointVolumes;
+use crate::filter_history::{self, SharedFilterHistory};
+use crate::filter_schema::{self, SharedFilterDefaults};
+use crate::chat_history::{self, ChatSessionInfo};
+use crate::chat_provider::{self, AiProviderKind, StreamEvent};
+use crate::gemini::{AiAction, AiPreferences, ChatMessage, PromptParams, SharedGeminiClient};
+use crate::i18n::t;
+use crate::macros::{self, SharedMacroState};
+use crate::obs_health::{HealthReport, SharedObsHealth};
 use crate::obs_launcher::{self, ObsLaunchStatus};
-use crate::obs_config::{self, ObsAudioConfig};
-use crate::obs_state::{self, ObsState, SharedObsState};
-use crate::obs_websocket::{ObsConnection, ObsStatus};
-use crate::preflight::{self, PreflightReport};
-use crate::presets::{self, Preset};
+use crate::obs_config::{self, ObsAudioConfig, ObsOutputConfig};
+use crate::obs_state::{
+    self, FilterInfo, InputInfo, ObsState, RecordStatus, ReplayBufferStatus, SceneItemInfo,
+    SharedObsState, StreamServiceSettings,
+};
+use crate::obs_websocket::{BatchExecution, ConnectError, ObsConnection, ObsHandle, ObsStatus};
+use crate::plugin_check::{self, MissingPluginsReport};
+use crate::preflight::{self, PreflightReport, SpeedTestResult};
+use crate::presets::{self, ConflictPolicy, PlanStatus, Preset};
+use crate::settings::{self, SharedSettings};
+use crate::source_schemas::{self, FieldError, InputKindSchema};
+use crate::stream_service::{self, StreamDestinationTest, StreamPreset};
 use crate::routing::{self, RoutingRecommendation};
 use crate::store::SharedLicenseState;
 use crate::system_monitor::{self, DisplayInfo, SystemResources};
-use crate::vst_manager::{self, VstCatalogWithStatus, VstPluginInfo, VstStatus};
+use crate::volume_guard::{self, ClampResult};
+use crate::vst_manager::{self, UserVst, VstCatalogWithStatus, VstPluginInfo, VstRepairResult, VstStatus};
+use crate::ws_log::WsLogEntry;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
@@ -31,7 +54,23 @@ pub async fn connect_obs(
     host: String,
     port: u16,
     password: Option<String>,
+    use_detected: Option<bool>,
 ) -> Result<ObsStatus, String> {
+    // Port and password from OBS's own websocket config, for when the user
+    // doesn't know them.
+    let (port, password) = if use_detected.unwrap_or(false) {
+        let detected = tokio::task::spawn_blocking(|| obs_config::read_websocket_settings(true))
+            .await
+            .map_err(|e| format!("Task failed: {}", e))??;
+        if !detected.enabled {
+            return Err("The OBS websocket server is disabled. Enable it in Tools > WebSocket Server Settings.".to_string());
+        }
+        let auth_required = detected.auth_required;
+        let detected_password = detected.password.filter(|p| auth_required && !p.is_empty());
+        (detected.port, detected_password.or(password))
+    } else {
+        (port, password)
+    };
     let mut conn = conn_state.lock().await;
     conn.connect(
         &host,
@@ -41,15 +80,56 @@ pub async fn connect_obs(
         obs_state.inner().clone(),
     )
     .await?;
+    let status = conn.status();
+    let handle = conn.handle();
+    // Let disconnect_obs through while the state loads.
+    drop(conn);
+
+    sync_after_connect(&handle, obs_state.inner(), &app_handle).await;
+    Ok(status)
+}
 
-    if let Err(e) = obs_state::populate_initial_state(&conn, obs_state.inner()).await {
+/// Loads OBS state after a (re)connect and pushes it to the frontend.
+pub(crate) async fn sync_after_connect(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    app_handle: &tauri::AppHandle,
+) {
+    if let Err(e) = obs_state::populate_initial_state(conn, obs_state, Some(app_handle)).await {
         log::warn!("Failed to populate initial state: {}", e);
+        if !conn.is_connected() {
+            return;
+        }
     }
 
+    let state_snapshot = obs_state.read().await.clone();
+    match plugin_check::analyze(conn, &state_snapshot).await {
+        Ok(report) => {
+            if !report.missing.is_empty() {
+                log::warn!(
+                    "{} source(s) reference missing OBS plugins",
+                    report.affected_source_count()
+                );
+                let _ = app_handle.emit("obs://missing-plugins", &report);
+            }
+            obs_state.write().await.missing_plugins = report;
+        }
+        Err(e) => log::warn!("Missing plugin check failed: {}", e),
+    }
+
+    crate::track_preview::restore_pending(conn, obs_state, app_handle).await;
+    crate::spectrum::restore_pending(conn, obs_state, app_handle).await;
+    crate::audio_router::restore_pending(conn, obs_state, app_handle).await;
+
     let state_snapshot = obs_state.read().await.clone();
     let _ = app_handle.emit("obs://state-sync", &state_snapshot);
+}
 
-    Ok(conn.status())
+#[tauri::command]
+pub async fn get_missing_plugins_report(
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<MissingPluginsReport, String> {
+    Ok(obs_state.read().await.missing_plugins.clone())
 }
 
 #[tauri::command]
@@ -64,6 +144,16 @@ pub async fn disconnect_obs(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn set_auto_reconnect(
+    conn_state: tauri::State<'_, SharedObsConnection>,
+    settings: tauri::State<'_, SharedSettings>,
+    enabled: bool,
+) -> Result<(), String> {
+    conn_state.lock().await.set_auto_reconnect(enabled);
+    settings::update_section(&settings, |s| s.connection.auto_reconnect = enabled).await
+}
+
 #[tauri::command]
 pub async fn get_obs_status(
     state: tauri::State<'_, SharedObsConnection>,
@@ -72,6 +162,14 @@ pub async fn get_obs_status(
     Ok(conn.status())
 }
 
+#[tauri::command]
+pub async fn get_connection_health(
+    health: tauri::State<'_, SharedObsHealth>,
+) -> Result<HealthReport, String> {
+    let h = health.lock().map_err(|e| format!("Health lock poisoned: {}", e))?;
+    Ok(h.report())
+}
+
 #[tauri::command]
 pub async fn get_obs_state(
     state: tauri::State<'_, SharedObsState>,
@@ -96,45 +194,56 @@ pub async fn get_video_devices() -> Result<Vec<video_devices::VideoDevice>, Stri
 
 #[tauri::command]
 pub async fn get_scene_list(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
 ) -> Result<Value, String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     conn.send_request("GetSceneList", None).await
 }
 
 #[tauri::command]
 pub async fn get_stats(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
 ) -> Result<Value, String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     conn.send_request("GetStats", None).await
 }
 
 #[tauri::command]
 pub async fn set_input_volume(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+    settings: tauri::State<'_, SharedSettings>,
     input_name: String,
     volume_db: f64,
-) -> Result<(), String> {
-    let conn = state.lock().await;
+) -> Result<ClampResult, String> {
+    let clamp = volume_guard::clamp(&settings.read().await.volume_guards, &input_name, volume_db);
+    let conn = state.inner().clone();
     conn.send_request(
         "SetInputVolume",
         Some(json!({
             "inputName": input_name,
-            "inputVolumeDb": volume_db
+            "inputVolumeDb": clamp.applied_db
         })),
     )
     .await?;
-    Ok(())
+    macros::record_request(
+        &recorder,
+        "SetInputVolume",
+        json!({"inputName": input_name, "inputVolumeDb": clamp.applied_db}),
+        format!("Set \"{}\" volume to {:.1} dB", input_name, clamp.applied_db),
+    )
+    .await;
+    Ok(clamp)
 }
 
 #[tauri::command]
 pub async fn set_input_mute(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
     input_name: String,
     muted: bool,
 ) -> Result<(), String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     conn.send_request(
         "SetInputMute",
         Some(json!({
@@ -143,15 +252,22 @@ pub async fn set_input_mute(
         })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "SetInputMute",
+        json!({"inputName": input_name, "inputMuted": muted}),
+        format!("{} \"{}\"", if muted { "Mute" } else { "Unmute" }, input_name),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_input_audio_balance(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
     input_name: String,
 ) -> Result<f64, String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     let resp = conn
         .send_request(
             "GetInputAudioBalance",
@@ -163,11 +279,12 @@ pub async fn get_input_audio_balance(
 
 #[tauri::command]
 pub async fn set_input_audio_balance(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
     input_name: String,
     balance: f64,
 ) -> Result<(), String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     conn.send_request(
         "SetInputAudioBalance",
         Some(json!({
@@ -176,15 +293,22 @@ pub async fn set_input_audio_balance(
         })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "SetInputAudioBalance",
+        json!({"inputName": input_name, "inputAudioBalance": balance}),
+        format!("Set \"{}\" balance to {:.2}", input_name, balance),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_input_audio_sync_offset(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
     input_name: String,
 ) -> Result<i64, String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     let resp = conn
         .send_request(
             "GetInputAudioSyncOffset",
@@ -196,11 +320,12 @@ pub async fn get_input_audio_sync_offset(
 
 #[tauri::command]
 pub async fn set_input_audio_sync_offset(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
     input_name: String,
     offset_ms: i64,
 ) -> Result<(), String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     conn.send_request(
         "SetInputAudioSyncOffset",
         Some(json!({
@@ -209,15 +334,22 @@ pub async fn set_input_audio_sync_offset(
         })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "SetInputAudioSyncOffset",
+        json!({"inputName": input_name, "inputAudioSyncOffset": offset_ms}),
+        format!("Set \"{}\" sync offset to {} ms", input_name, offset_ms),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_input_audio_tracks(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
     input_name: String,
 ) -> Result<Value, String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     let resp = conn
         .send_request(
             "GetInputAudioTracks",
@@ -229,11 +361,12 @@ pub async fn get_input_audio_tracks(
 
 #[tauri::command]
 pub async fn set_input_audio_tracks(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
     input_name: String,
     tracks: Value,
 ) -> Result<(), String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     conn.send_request(
         "SetInputAudioTracks",
         Some(json!({
@@ -242,15 +375,23 @@ pub async fn set_input_audio_tracks(
         })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "SetInputAudioTracks",
+        json!({"inputName": input_name, "inputAudioTracks": tracks}),
+        format!("Set \"{}\" track routing", input_name),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn toggle_input_mute(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
     input_name: String,
 ) -> Result<(), String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     conn.send_request(
         "ToggleInputMute",
         Some(json!({
@@ -258,18 +399,59 @@ pub async fn toggle_input_mute(
         })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "ToggleInputMute",
+        json!({"inputName": input_name}),
+        format!("Toggle mute on \"{}\"", input_name),
+    )
+    .await;
     Ok(())
 }
 
+/// Reads a source's filters straight from OBS and refreshes the cached copy,
+/// for when edits made in OBS didn't arrive as events.
+#[tauri::command]
+pub async fn get_source_filters(
+    state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    source_name: String,
+) -> Result<Vec<FilterInfo>, String> {
+    let filters = obs_state::fetch_filters(&state, &source_name).await?;
+    if let Some(input) = obs_state.write().await.inputs.get_mut(&source_name) {
+        input.filters = filters.clone();
+    }
+    let _ = app_handle.emit("obs://filters-changed", json!({"sourceName": source_name}));
+    Ok(filters)
+}
+
+/// Re-reads volume, mute, balance, sync offset, tracks, monitoring and
+/// filters for one input and replaces its cached entry.
+#[tauri::command]
+pub async fn refresh_input(
+    state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    input_name: String,
+) -> Result<InputInfo, String> {
+    let input = obs_state::fetch_input(&state, &input_name).await?;
+    obs_state.write().await.inputs.insert(input_name.clone(), input.clone());
+    let _ = app_handle.emit("obs://filters-changed", json!({"sourceName": input_name}));
+    let _ = app_handle.emit("obs://input-refreshed", &input);
+    Ok(input)
+}
+
 #[tauri::command]
 pub async fn create_source_filter(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    history: tauri::State<'_, SharedFilterHistory>,
     source_name: String,
     filter_name: String,
     filter_kind: String,
     filter_settings: Option<Value>,
 ) -> Result<(), String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     let mut data = json!({
         "sourceName": source_name,
         "filterName": filter_name,
@@ -278,18 +460,20 @@ pub async fn create_source_filter(
     if let Some(settings) = filter_settings {
         data["filterSettings"] = settings;
     }
+    filter_history::expect(&history, &source_name, &filter_name, "ui");
     conn.send_request("CreateSourceFilter", Some(data)).await?;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn set_source_filter_enabled(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
     source_name: String,
     filter_name: String,
     enabled: bool,
 ) -> Result<(), String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
     conn.send_request(
         "SetSourceFilterEnabled",
         Some(json!({
@@ -299,16 +483,30 @@ pub async fn set_source_filter_enabled(
         })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "SetSourceFilterEnabled",
+        json!({"sourceName": source_name, "filterName": filter_name, "filterEnabled": enabled}),
+        format!(
+            "{} \"{}\" on \"{}\"",
+            if enabled { "Enable" } else { "Disable" },
+            filter_name,
+            source_name
+        ),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn remove_source_filter(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    history: tauri::State<'_, SharedFilterHistory>,
     source_name: String,
     filter_name: String,
 ) -> Result<(), String> {
-    let conn = state.lock().await;
+    let conn = state.inner().clone();
+    filter_history::expect(&history, &source_name, &filter_name, "ui");
     conn.send_request(
         "RemoveSourceFilter",
         Some(json!({
@@ -322,12 +520,15 @@ pub async fn remove_source_filter(
 
 #[tauri::command]
 pub async fn set_source_filter_settings(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+    history: tauri::State<'_, SharedFilterHistory>,
     source_name: String,
     filter_name: String,
     filter_settings: Value,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
+    filter_history::expect(&history, &source_name, &filter_name, "ui");
     conn.send_request(
         "SetSourceFilterSettings",
         Some(json!({
@@ -337,17 +538,28 @@ pub async fn set_source_filter_settings(
         })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "SetSourceFilterSettings",
+        json!({
+            "sourceName": source_name,
+            "filterName": filter_name,
+            "filterSettings": filter_settings
+        }),
+        format!("Update \"{}\" on \"{}\"", filter_name, source_name),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn set_source_filter_index(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     source_name: String,
     filter_name: String,
     filter_index: u32,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "SetSourceFilterIndex",
         Some(json!({
@@ -362,12 +574,14 @@ pub async fn set_source_filter_index(
 
 #[tauri::command]
 pub async fn set_source_filter_name(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    history: tauri::State<'_, SharedFilterHistory>,
     source_name: String,
     filter_name: String,
     new_filter_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
+    filter_history::expect(&history, &source_name, &filter_name, "ui");
     conn.send_request(
         "SetSourceFilterName",
         Some(json!({
@@ -380,13 +594,15 @@ pub async fn set_source_filter_name(
     Ok(())
 }
 
+/// The rename cascade runs off OBS's InputNameChanged event, which covers
+/// renames made in OBS too.
 #[tauri::command]
 pub async fn rename_input(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     input_name: String,
     new_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "SetInputName",
         Some(json!({
@@ -400,8 +616,12 @@ pub async fn rename_input(
 
 #[tauri::command]
 pub async fn get_windows_volume(
+    endpoint_volumes: tauri::State<'_, SharedEndpointVolumes>,
     device_id: String,
 ) -> Result<audio::DeviceVolume, String> {
+    if let Some(cached) = endpoint_volumes.lock().unwrap().get(&device_id) {
+        return Ok(cached);
+    }
     tokio::task::spawn_blocking(move || audio::get_device_volume(&device_id))
         .await
         .map_err(|e| format!("Task failed: {}", e))?
@@ -429,14 +649,65 @@ pub async fn set_windows_mute(
 
 #[tauri::command]
 pub async fn run_preflight(
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
+    settings: tauri::State<'_, SharedSettings>,
     mode: String,
 ) -> Result<PreflightReport, String> {
     let state_snapshot = obs_state.read().await.clone();
     let sys = tokio::task::spawn_blocking(system_monitor::get_system_resources)
         .await
         .map_err(|e| format!("Task failed: {}", e))?;
-    Ok(preflight::run_all_checks(&state_snapshot, &sys, &mode))
+    let destination = if mode == "stream" && !state_snapshot.stream_service.server.is_empty() {
+        Some(stream_service::test_destination(&state_snapshot.stream_service.server).await)
+    } else {
+        None
+    };
+    let headroom = if mode == "record" {
+        let record_settings = state_snapshot.record_settings.clone();
+        tokio::task::spawn_blocking(move || disk_headroom::measure(&record_settings))
+            .await
+            .map_err(|e| format!("Task failed: {}", e))?
+    } else {
+        None
+    };
+    let stream_bitrate = if mode == "stream" && conn_state.is_connected() {
+        Some(disk_headroom::fetch_stream_bitrate_kbps(&conn_state).await)
+    } else {
+        None
+    };
+    let encoder = if conn_state.is_connected() && (mode == "stream" || mode == "record") {
+        disk_headroom::fetch_encoder(&conn_state, mode == "record").await
+    } else {
+        None
+    };
+    let speed_test = settings.read().await.preflight.last_speed_test.clone();
+    Ok(preflight::run_all_checks(
+        &state_snapshot,
+        &sys,
+        &mode,
+        destination.as_ref(),
+        headroom.as_ref(),
+        stream_bitrate,
+        speed_test.as_ref(),
+        encoder.as_ref(),
+    ))
+}
+
+/// Measures upload bandwidth against the configured endpoint and keeps the
+/// result for the preflight bitrate check.
+#[tauri::command]
+pub async fn run_speed_test(
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<SpeedTestResult, String> {
+    let config = settings.read().await.preflight.clone();
+    if config.speed_test_url.is_empty() {
+        return Err("No speed test endpoint is configured".to_string());
+    }
+    let result = preflight::measure_upload(&config.speed_test_url, config.speed_test_megabytes).await?;
+    let stored = result.clone();
+    settings::update_section(&settings, |s| s.preflight.last_speed_test = Some(stored)).await?;
+    Ok(result)
 }
 
 #[tauri::command]
@@ -455,10 +726,10 @@ pub async fn get_displays() -> Result<Vec<DisplayInfo>, String> {
 
 #[tauri::command]
 pub async fn refresh_video_settings(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     let v = conn.send_request("GetVideoSettings", None).await?;
     let mut s = obs_state.write().await;
     s.video_settings = obs_state::VideoSettings {
@@ -474,11 +745,11 @@ pub async fn refresh_video_settings(
 
 #[tauri::command]
 pub async fn set_input_settings(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     input_name: String,
     input_settings: Value,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "SetInputSettings",
         Some(json!({
@@ -492,10 +763,10 @@ pub async fn set_input_settings(
 
 #[tauri::command]
 pub async fn get_input_settings(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     input_name: String,
 ) -> Result<Value, String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     let resp = conn
         .send_request(
             "GetInputSettings",
@@ -507,12 +778,13 @@ pub async fn get_input_settings(
 
 #[tauri::command]
 pub async fn set_input_audio_monitor_type(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
     obs_state: tauri::State<'_, SharedObsState>,
     input_name: String,
     monitor_type: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "SetInputAudioMonitorType",
         Some(json!({
@@ -521,6 +793,13 @@ pub async fn set_input_audio_monitor_type(
         })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "SetInputAudioMonitorType",
+        json!({"inputName": input_name, "monitorType": monitor_type}),
+        format!("Set \"{}\" monitoring", input_name),
+    )
+    .await;
     // Immediately sync the cache so UI reflects the change
     {
         let mut s = obs_state.write().await;
@@ -533,11 +812,11 @@ pub async fn set_input_audio_monitor_type(
 
 #[tauri::command]
 pub async fn get_input_audio_monitor_type(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
     input_name: String,
 ) -> Result<String, String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     let result = conn
         .send_request(
             "GetInputAudioMonitorType",
@@ -560,13 +839,20 @@ pub async fn get_input_audio_monitor_type(
 
 #[tauri::command]
 pub async fn create_input(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     scene_name: String,
     input_name: String,
     input_kind: String,
     input_settings: Option<Value>,
+    validate: Option<bool>,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let input_settings = if validate.unwrap_or(false) {
+        source_schemas::checked_settings(&input_kind, input_settings.as_ref())?
+    } else {
+        input_settings
+    };
+
+    let conn = conn_state.inner().clone();
     let mut data = json!({
         "sceneName": scene_name,
         "inputName": input_name,
@@ -588,13 +874,27 @@ pub async fn create_input(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_input_kind_schema(kind: String) -> Result<InputKindSchema, String> {
+    source_schemas::schema_for(&kind).ok_or_else(|| format!("No settings schema for input kind '{}'", kind))
+}
+
+#[tauri::command]
+pub async fn validate_input_settings(
+    kind: String,
+    settings: Value,
+) -> Result<Vec<FieldError>, String> {
+    let settings = source_schemas::normalize(&kind, &settings);
+    Ok(source_schemas::validate(&kind, &settings))
+}
+
 #[tauri::command]
 pub async fn create_scene_item(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     scene_name: String,
     source_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
 
     // Check if source already exists in this scene
     let scene_items = conn
@@ -646,28 +946,32 @@ pub async fn create_scene_item(
 
 #[tauri::command]
 pub async fn get_routing_recommendations(
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
 ) -> Result<Vec<RoutingRecommendation>, String> {
     let state_snapshot = obs_state.read().await.clone();
     let devices = tokio::task::spawn_blocking(audio::enumerate_audio_devices)
         .await
         .map_err(|e| format!("Task failed: {}", e))??;
-    Ok(routing::analyze(&state_snapshot, &devices))
+    let monitoring = obs_config::monitoring_device_id(&conn_state).await;
+    Ok(routing::analyze(&state_snapshot, &devices, monitoring.as_deref()))
 }
 
 #[tauri::command]
 pub async fn apply_recommended_setup(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
 ) -> Result<Vec<String>, String> {
     let state_snapshot = obs_state.read().await.clone();
     let devices = tokio::task::spawn_blocking(audio::enumerate_audio_devices)
         .await
         .map_err(|e| format!("Task failed: {}", e))??;
-    let recs = routing::analyze(&state_snapshot, &devices);
+    let monitoring = obs_config::monitoring_device_id(&conn_state).await;
+    let recs = routing::analyze(&state_snapshot, &devices, monitoring.as_deref());
 
-    let conn = conn_state.lock().await;
-    let mut applied = Vec::new();
+    let conn = conn_state.inner().clone();
+    let mut titles = Vec::new();
+    let mut requests = Vec::new();
 
     for rec in &recs {
         let action = match &rec.action {
@@ -675,30 +979,28 @@ pub async fn apply_recommended_setup(
             None => continue,
         };
 
-        let result = match action.action_type.as_str() {
+        let request = match action.action_type.as_str() {
             "set_device" => {
                 let device_id = action.params["device_id"].as_str().unwrap_or("default");
-                conn.send_request(
+                (
                     "SetInputSettings",
-                    Some(json!({
+                    json!({
                         "inputName": action.input_name,
                         "inputSettings": {"device_id": device_id},
-                    })),
+                    }),
                 )
-                .await
             }
             "set_monitor_type" => {
                 let monitor_type = action.params["monitorType"]
                     .as_str()
                     .unwrap_or("OBS_MONITORING_TYPE_MONITOR_ONLY");
-                conn.send_request(
+                (
                     "SetInputAudioMonitorType",
-                    Some(json!({
+                    json!({
                         "inputName": action.input_name,
                         "monitorType": monitor_type,
-                    })),
+                    }),
                 )
-                .await
             }
             "add_filter" => {
                 let filter_name = action.params["filterName"].as_str().unwrap_or("Filter");
@@ -712,20 +1014,35 @@ pub async fn apply_recommended_setup(
                 if let Some(settings) = filter_settings {
                     data["filterSettings"] = settings;
                 }
-                conn.send_request("CreateSourceFilter", Some(data)).await
+                ("CreateSourceFilter", data)
             }
+            "mute_input" => (
+                "SetInputMute",
+                json!({
+                    "inputName": action.input_name,
+                    "inputMuted": true,
+                }),
+            ),
             _ => continue,
         };
+        titles.push(rec.title.clone());
+        requests.push((request.0.to_string(), Some(request.1)));
+    }
 
-        if result.is_ok() {
-            applied.push(rec.title.clone());
+    // One round trip; a failed recommendation doesn't stop the rest.
+    let outcomes = conn
+        .send_batch(requests, BatchExecution::SerialRealtime, false)
+        .await?;
+    let mut applied = Vec::new();
+    for (title, outcome) in titles.into_iter().zip(outcomes) {
+        match outcome {
+            Ok(_) => applied.push(title),
+            Err(e) => log::warn!("Recommended setup '{}' failed: {}", title, e),
         }
     }
 
     // Re-populate state after applying changes
-    drop(conn);
-    let conn = conn_state.lock().await;
-    let _ = obs_state::populate_initial_state(&conn, obs_state.inner()).await;
+    let _ = obs_state::populate_initial_state(&conn, obs_state.inner(), None).await;
 
     Ok(applied)
 }
@@ -738,12 +1055,175 @@ pub async fn get_obs_audio_config() -> Result<ObsAudioConfig, String> {
 }
 
 #[tauri::command]
-pub async fn set_obs_audio_config(config: ObsAudioConfig) -> Result<(), String> {
+pub async fn set_obs_audio_config(
+    settings: tauri::State<'_, SharedSettings>,
+    config: ObsAudioConfig,
+) -> Result<(), String> {
+    crate::obs_backup::auto_backup(&settings, "audio-config").await?;
     tokio::task::spawn_blocking(move || obs_config::write_obs_audio_config(&config))
         .await
         .map_err(|e| format!("Task failed: {}", e))?
 }
 
+#[tauri::command]
+pub async fn get_obs_output_config() -> Result<ObsOutputConfig, String> {
+    tokio::task::spawn_blocking(obs_config::read_obs_output_config)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn validate_obs_output_config(config: ObsOutputConfig) -> Result<Vec<String>, String> {
+    tokio::task::spawn_blocking(move || obs_config::validate_output_config(&config))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+/// Saves the output config, returning what validation still objects to.
+/// With OBS closed the profile files are edited directly. With OBS open the
+/// ini keys go through the websocket, and OBS is made to reload the encoder
+/// JSON by switching profiles away and back, the same way
+/// ensure_virtual_cam_program reloads the scene collection.
+#[tauri::command]
+pub async fn set_obs_output_config(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    settings: tauri::State<'_, SharedSettings>,
+    config: ObsOutputConfig,
+) -> Result<Vec<String>, String> {
+    crate::obs_backup::auto_backup(&settings, "output-config").await?;
+    let conflicts = obs_config::validate_output_config(&config);
+
+    let running = tokio::task::spawn_blocking(obs_config::is_obs_running)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?;
+    if !running {
+        let to_write = config.clone();
+        tokio::task::spawn_blocking(move || obs_config::write_obs_output_config(&to_write))
+            .await
+            .map_err(|e| format!("Task failed: {}", e))??;
+        return Ok(conflicts);
+    }
+
+    let conn = conn_state.inner().clone();
+    if !conn.is_connected() {
+        return Err("OBS is running but not connected. Connect to OBS or close it first.".to_string());
+    }
+    {
+        let s = obs_state.read().await;
+        if s.stream_status.active || s.record_status.active {
+            return Err("Stop streaming and recording before changing output settings".to_string());
+        }
+    }
+    let resp = conn.send_request("GetProfileList", None).await?;
+    let mut current = resp["currentProfileName"].as_str().unwrap_or("").to_string();
+    if current == OUTPUT_TEMP_PROFILE && config.profile_name != OUTPUT_TEMP_PROFILE {
+        // An interrupted save left OBS on the temporary profile.
+        conn.send_request("SetCurrentProfile", Some(json!({"profileName": config.profile_name})))
+            .await?;
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        remove_output_temp_profile(&conn).await;
+        current = config.profile_name.clone();
+    }
+    if current != config.profile_name {
+        return Err(format!(
+            "OBS is using profile '{}', not '{}'",
+            current, config.profile_name
+        ));
+    }
+
+    for (category, name, value) in obs_config::output_ini_updates(&config) {
+        conn.send_request(
+            "SetProfileParameter",
+            Some(json!({
+                "parameterCategory": category,
+                "parameterName": name,
+                "parameterValue": value,
+            })),
+        )
+        .await?;
+    }
+    if config.mode != "Advanced" {
+        return Ok(conflicts);
+    }
+
+    if config.use_stream_encoder {
+        // No record encoder JSON to reload.
+        return Ok(conflicts);
+    }
+
+    // Switching away saves basic.ini; the encoder JSON is written while the
+    // profile is inactive and read back when switching back.
+    if conn
+        .send_request("CreateProfile", Some(json!({"profileName": OUTPUT_TEMP_PROFILE})))
+        .await
+        .is_err()
+    {
+        // Left over from an interrupted run.
+        conn.send_request("SetCurrentProfile", Some(json!({"profileName": OUTPUT_TEMP_PROFILE})))
+            .await?;
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+    let to_write = config.clone();
+    let written = tokio::task::spawn_blocking(move || obs_config::write_record_encoder(&to_write))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+        .and_then(|r| r);
+
+    // Whatever happened above, OBS must not be left on the temporary profile.
+    let mut switched_back = Err(String::new());
+    for _ in 0..3 {
+        switched_back = conn
+            .send_request("SetCurrentProfile", Some(json!({"profileName": current})))
+            .await;
+        if switched_back.is_ok() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+    if let Err(e) = switched_back {
+        return Err(format!(
+            "OBS was left on the temporary profile '{}'; switch back to '{}' in OBS ({})",
+            OUTPUT_TEMP_PROFILE, current, e
+        ));
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    remove_output_temp_profile(&conn).await;
+    written?;
+    Ok(conflicts)
+}
+
+/// Profile `set_obs_output_config` switches to while it rewrites the encoder JSON.
+const OUTPUT_TEMP_PROFILE: &str = "OBServe_output_temp";
+
+async fn remove_output_temp_profile(conn: &ObsHandle) {
+    if let Err(e) = conn
+        .send_request("RemoveProfile", Some(json!({"profileName": OUTPUT_TEMP_PROFILE})))
+        .await
+    {
+        log::warn!("Failed to remove temporary profile '{}': {}", OUTPUT_TEMP_PROFILE, e);
+    }
+}
+
+/// `reveal` includes the password itself rather than just whether one is set.
+#[tauri::command]
+pub async fn get_websocket_settings(reveal: Option<bool>) -> Result<obs_config::WebsocketSettings, String> {
+    let reveal = reveal.unwrap_or(false);
+    tokio::task::spawn_blocking(move || obs_config::read_websocket_settings(reveal))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Enables the websocket server with a password; returns the settings with
+/// the password revealed so the connection form can be filled in.
+#[tauri::command]
+pub async fn enable_websocket_server(port: Option<u16>) -> Result<obs_config::WebsocketSettings, String> {
+    tokio::task::spawn_blocking(move || obs_config::enable_websocket_server(port))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
 // --- AI Integration Commands ---
 
 #[derive(Serialize, Deserialize)]
@@ -758,83 +1238,234 @@ pub struct FullChatResponse {
 #[tauri::command]
 pub async fn send_chat_message(
     gemini: tauri::State<'_, SharedGeminiClient>,
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
     undo_stack: tauri::State<'_, SharedUndoStack>,
     audio_metrics_state: tauri::State<'_, SharedAudioMetrics>,
     license: tauri::State<'_, SharedLicenseState>,
+    editor_state: tauri::State<'_, SharedVideoEditorState>,
+    edit_session: tauri::State<'_, SharedAiEditSession>,
+    settings: tauri::State<'_, SharedSettings>,
+    filter_defaults: tauri::State<'_, SharedFilterDefaults>,
+    ai_metrics: tauri::State<'_, SharedAiMetrics>,
+    history: tauri::State<'_, SharedFilterHistory>,
+    app_handle: tauri::AppHandle,
     message: String,
     calibration_data: Option<String>,
 ) -> Result<FullChatResponse, String> {
-    let mut client_guard = gemini.write().await;
-    let client = client_guard
+    // One turn at a time, since tool turns carry state in the client. The
+    // client itself is only locked while talking to the model, not while
+    // actions run against OBS or ffmpeg.
+    static CHAT_TURN: std::sync::OnceLock<tokio::sync::Mutex<()>> = std::sync::OnceLock::new();
+    let _turn = CHAT_TURN.get_or_init(Default::default).lock().await;
+    let turn_started = std::time::Instant::now();
+    let undo_group = UndoGroup::new(format!("AI: {}", message.chars().take(60).collect::<String>()));
+    gemini
+        .write()
+        .await
         .as_mut()
-        .ok_or_else(|| "Gemini API key not configured. Set GEMINI_API_KEY environment variable.".to_string())?;
+        .ok_or_else(|| t("error.gemini_key_missing", &[]))?
+        .take_timing();
+    let mut apply_timer = ApplyTimer::new();
 
     let state_snapshot = obs_state.read().await.clone();
     let metrics_snapshot = audio_metrics_state.read().await.clone();
     let license_snapshot = license.read().await.clone();
+    let (volume_guards, out_of_range, ai_preferences, streaming, persist_history) = {
+        let s = settings.read().await;
+        let persist = s.ai.persist_history.then_some(s.ai.history_sessions);
+        (s.volume_guards.clone(), s.ai.out_of_range.clone(), s.ai.preferences.clone(), s.ai.streaming, persist)
+    };
     let devices = tokio::task::spawn_blocking(audio::enumerate_audio_devices)
         .await
         .map_err(|e| format!("Task failed: {}", e))??;
 
-    let chat_response = client
-        .send_message(
-            &message,
-            &state_snapshot,
-            &devices,
-            &metrics_snapshot,
-            calibration_data.as_deref(),
-            &license_snapshot,
-        )
-        .await?;
+    let prompt_params = PromptParams {
+        state: &state_snapshot,
+        devices: &devices,
+        audio_metrics: &metrics_snapshot,
+        calibration_json: calibration_data.as_deref(),
+        license: &license_snapshot,
+        preferences: &ai_preferences,
+    };
+    // Streaming emits the message as chat://token deltas while it arrives,
+    // and chat://reset when a retry voids what was sent so far;
+    // chat://complete then carries the final response, actions included.
+    let token_handle = app_handle.clone();
+    let mut emit_token = move |event: StreamEvent<'_>| match event {
+        StreamEvent::Token(text) => {
+            let _ = token_handle.emit("chat://token", json!({ "text": text }));
+        }
+        StreamEvent::Reset => {
+            let _ = token_handle.emit("chat://reset", ());
+        }
+    };
+    let mut chat_response = {
+        let mut guard = gemini.write().await;
+        let client = guard.as_mut().ok_or_else(|| t("error.gemini_key_missing", &[]))?;
+        if streaming {
+            client
+                .send_message_streaming(&message, prompt_params, &mut emit_token)
+                .await?
+        } else {
+            client.send_message(&message, prompt_params).await?
+        }
+    };
 
-    let frontend_actions: Vec<AiAction> = chat_response
-        .actions
-        .iter()
-        .filter(|a| a.action_type == "video_editor")
-        .cloned()
-        .collect();
+    let mut messages: Vec<String> = Vec::new();
+    let mut action_results: Vec<ActionResult> = Vec::new();
+    let mut pending: Vec<AiAction> = Vec::new();
+    let mut frontend_actions: Vec<AiAction> = Vec::new();
+    let mut tool_turns = 0;
 
-    let backend_actions: Vec<AiAction> = chat_response
-        .actions
-        .iter()
-        .filter(|a| a.action_type != "video_editor")
-        .cloned()
-        .collect();
+    loop {
+        if !chat_response.message.is_empty() {
+            messages.push(chat_response.message.clone());
+        }
 
-    let conn = conn_state.lock().await;
-    let results =
-        ai_actions::execute_actions(&backend_actions, &conn, &state_snapshot, &undo_stack, &license_snapshot)
-            .await;
+        let state_snapshot = obs_state.read().await.clone();
+        let mut function_results: Vec<Value> = Vec::new();
+        let conn = conn_state.inner().clone();
 
-    let pending: Vec<AiAction> = results
-        .iter()
-        .filter_map(|r| r.pending_action.clone())
-        .collect();
+        for action in &chat_response.actions {
+            match action.action_type.as_str() {
+                "video_editor" => {
+                    frontend_actions.push(action.clone());
+                    function_results.push(json!({"status": "sent_to_editor"}));
+                }
+                "video_edit" => {
+                    let result = match crate::store::require_module(&license, "video-editor").await {
+                        Ok(()) => {
+                            ai_actions::execute_video_edit_action(
+                                action,
+                                &edit_session,
+                                &editor_state,
+                                &obs_state,
+                                &app_handle,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    };
+                    let (status, description, error) = match result {
+                        Ok(detail) => ("executed", detail, None),
+                        Err(e) => ("failed", action.description.clone(), Some(e)),
+                    };
+                    function_results.push(json!({"status": status, "result": description, "error": error}));
+                    action_results.push(ActionResult {
+                        description,
+                        status: status.into(),
+                        error,
+                        undoable: false,
+                        pending_action: None,
+                    });
+                }
+                "obs_query" => {
+                    let data = action.params.get("requestData").cloned();
+                    let result = match conn.send_request(&action.request_type, data).await {
+                        Ok(data) => json!({"status": "ok", "data": data}),
+                        Err(e) => json!({"status": "failed", "error": e}),
+                    };
+                    function_results.push(result);
+                }
+                "filter_describe" => {
+                    let source = action.params["sourceName"].as_str().unwrap_or("");
+                    let filter = action.params["filterName"].as_str().unwrap_or("");
+                    let result = match filter_schema::describe_filter_with(
+                        &conn,
+                        &obs_state,
+                        &filter_defaults,
+                        source,
+                        filter,
+                    )
+                    .await
+                    {
+                        Ok(schema) => json!({"status": "ok", "data": schema}),
+                        Err(e) => json!({"status": "failed", "error": e}),
+                    };
+                    function_results.push(result);
+                }
+                _ => {
+                    let action_started = std::time::Instant::now();
+                    filter_history::expect_actions(&history, std::slice::from_ref(action), "ai");
+                    let results = ai_actions::execute_actions(
+                        std::slice::from_ref(action),
+                        &conn,
+                        &state_snapshot,
+                        &undo_stack,
+                        &license_snapshot,
+                        &volume_guards,
+                        &out_of_range,
+                        Some(&undo_group),
+                    )
+                    .await;
+                    apply_timer.record(action_started, &ai_metrics);
+                    for mut r in results {
+                        function_results.push(json!({"status": r.status.clone(), "result": r.description.clone(), "error": r.error.clone()}));
+                        if let Some(p) = r.pending_action.take() {
+                            pending.push(p);
+                        }
+                        action_results.push(r);
+                    }
+                }
+            }
+        }
 
-    let action_results: Vec<ActionResult> = results
-        .into_iter()
-        .map(|mut r| {
-            r.pending_action = None;
-            r
-        })
-        .collect();
+        let mut guard = gemini.write().await;
+        // A provider switch mid-turn leaves nothing awaiting responses.
+        let Some(client) = guard.as_mut().filter(|c| c.awaiting_function_responses()) else {
+            break;
+        };
+        tool_turns += 1;
+        if tool_turns > crate::gemini::MAX_TOOL_TURNS {
+            log::warn!("AI tool-use turn limit reached");
+            client.abandon_tool_turns();
+            break;
+        }
+        chat_response = if streaming {
+            client
+                .send_function_responses_streaming(function_results, &mut emit_token)
+                .await?
+        } else {
+            client.send_function_responses(function_results).await?
+        };
+    }
+
+    let (model_timing, chat_history) = {
+        let mut guard = gemini.write().await;
+        let client = guard.as_mut().ok_or_else(|| t("error.gemini_key_missing", &[]))?;
+        (client.take_timing(), persist_history.map(|_| client.history().to_vec()))
+    };
+    let timing = apply_timer.finish(model_timing, turn_started);
+    if let Ok(mut m) = ai_metrics.lock() {
+        m.record_turn(&timing);
+    }
+    let _ = app_handle.emit("ai://timing", timing);
+
+    if let (Some(keep), Some(chat_history)) = (persist_history, chat_history) {
+        save_chat_history(chat_history, keep).await;
+    }
 
-    Ok(FullChatResponse {
-        message: chat_response.message,
+    let response = FullChatResponse {
+        message: messages.join("\n\n"),
         action_results,
         pending_dangerous: pending,
         frontend_actions,
-    })
+    };
+    if streaming {
+        let _ = app_handle.emit("chat://complete", &response);
+    }
+    Ok(response)
 }
 
 #[tauri::command]
 pub async fn confirm_dangerous_action(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    history: tauri::State<'_, SharedFilterHistory>,
     action: AiAction,
 ) -> Result<ActionResult, String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
+    filter_history::expect_actions(&history, std::slice::from_ref(&action), "ai");
     match ai_actions::execute_single_action(&action, &conn).await {
         Ok(()) => Ok(ActionResult {
             description: action.description,
@@ -862,96 +1493,493 @@ pub async fn get_smart_presets(
 }
 
 #[tauri::command]
-pub async fn apply_preset(
+pub async fn save_user_preset(
     license: tauri::State<'_, SharedLicenseState>,
-    conn_state: tauri::State<'_, SharedObsConnection>,
-    obs_state: tauri::State<'_, SharedObsState>,
-    undo_stack: tauri::State<'_, SharedUndoStack>,
-    preset_id: String,
-    mic_source: Option<String>,
-    desktop_source: Option<String>,
-) -> Result<Vec<ActionResult>, String> {
+    preset: Preset,
+) -> Result<Preset, String> {
     crate::store::require_module(&license, "presets").await?;
-    let all_presets = presets::get_presets();
-    let preset = all_presets
-        .iter()
-        .find(|p| p.id == preset_id)
-        .ok_or_else(|| format!("Preset '{}' not found", preset_id))?;
+    tokio::task::spawn_blocking(move || presets::save_user_preset(preset))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
 
-    let state_snapshot = obs_state.read().await.clone();
+/// Builds (but doesn't save) a preset from an input's live volume and filters.
+#[tauri::command]
+pub async fn export_current_chain_as_preset(
+    license: tauri::State<'_, SharedLicenseState>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    input_name: String,
+    preset_name: String,
+) -> Result<Preset, String> {
+    crate::store::require_module(&license, "presets").await?;
+    if preset_name.trim().is_empty() {
+        return Err(t("error.preset_needs_name", &[]));
+    }
+    let input = obs_state::fetch_input(&conn_state, &input_name).await?;
+    Ok(presets::preset_from_chain(&input, &preset_name))
+}
 
+#[tauri::command]
+pub async fn delete_user_preset(
+    license: tauri::State<'_, SharedLicenseState>,
+    id: String,
+) -> Result<(), String> {
+    crate::store::require_module(&license, "presets").await?;
+    tokio::task::spawn_blocking(move || presets::delete_user_preset(&id))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Resolves a preset for the given (or default) sources and checks it
+/// against the current state.
+fn plan_preset(
+    preset: &Preset,
+    state: &ObsState,
+    mic_source: Option<String>,
+    desktop_source: Option<String>,
+    policy: ConflictPolicy,
+) -> Result<presets::PresetPlan, String> {
     let mic = mic_source.unwrap_or_else(|| {
-        let m = &state_snapshot.special_inputs.mic1;
+        let m = &state.special_inputs.mic1;
         if m.is_empty() { "Mic/Aux".into() } else { m.clone() }
     });
     let desktop = desktop_source.unwrap_or_else(|| {
-        let d = &state_snapshot.special_inputs.desktop1;
+        let d = &state.special_inputs.desktop1;
         if d.is_empty() { "Desktop Audio".into() } else { d.clone() }
     });
 
     let resolved = presets::resolve_preset_actions(&preset.actions, &mic, &desktop)?;
+    let actions = presets::plan_actions(&resolved, state, &preset.filter_prefix, policy);
+    let can_apply = actions.iter().all(|a| a.status != PlanStatus::WouldFail);
+    Ok(presets::PresetPlan {
+        preset_id: preset.id.clone(),
+        mic_source: mic,
+        desktop_source: desktop,
+        conflict_policy: policy,
+        actions,
+        can_apply,
+    })
+}
 
-    let license_snapshot = license.read().await.clone();
-    let conn = conn_state.lock().await;
-    let results =
-        ai_actions::execute_actions(&resolved, &conn, &state_snapshot, &undo_stack, &license_snapshot).await;
+fn find_preset(preset_id: &str) -> Result<Preset, String> {
+    presets::get_presets()
+        .into_iter()
+        .find(|p| p.id == preset_id)
+        .ok_or_else(|| t("error.preset_not_found", &[("id", &preset_id)]))
+}
+
+/// What apply_preset would do, without sending anything to OBS.
+#[tauri::command]
+pub async fn preview_preset(
+    license: tauri::State<'_, SharedLicenseState>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    preset_id: String,
+    mic_source: Option<String>,
+    desktop_source: Option<String>,
+    conflict_policy: Option<ConflictPolicy>,
+) -> Result<presets::PresetPlan, String> {
+    crate::store::require_module(&license, "presets").await?;
+    let preset = find_preset(&preset_id)?;
+    let state_snapshot = obs_state.read().await.clone();
+    plan_preset(
+        &preset,
+        &state_snapshot,
+        mic_source,
+        desktop_source,
+        conflict_policy.unwrap_or_default(),
+    )
+}
+
+/// An apply_preset step result with the plan branch it went down.
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetActionResult {
+    #[serde(flatten)]
+    pub result: ActionResult,
+    pub plan: PlanStatus,
+}
+
+#[tauri::command]
+pub async fn apply_preset(
+    license: tauri::State<'_, SharedLicenseState>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    undo_stack: tauri::State<'_, SharedUndoStack>,
+    settings: tauri::State<'_, SharedSettings>,
+    history: tauri::State<'_, SharedFilterHistory>,
+    preset_id: String,
+    mic_source: Option<String>,
+    desktop_source: Option<String>,
+    conflict_policy: Option<ConflictPolicy>,
+) -> Result<Vec<PresetActionResult>, String> {
+    crate::store::require_module(&license, "presets").await?;
+    let preset = find_preset(&preset_id)?;
+    let state_snapshot = obs_state.read().await.clone();
 
-    Ok(results.into_iter().map(|mut r| { r.pending_action = None; r }).collect())
+    // Refuse up front rather than leave half a chain behind.
+    let plan = plan_preset(
+        &preset,
+        &state_snapshot,
+        mic_source,
+        desktop_source,
+        conflict_policy.unwrap_or_default(),
+    )?;
+    let failures = plan.failures();
+    if !failures.is_empty() {
+        return Err(t("error.preset_apply_failed", &[("failures", &failures.join("; "))]));
+    }
+    let runnable: Vec<usize> = plan
+        .actions
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| a.status != PlanStatus::Skipped)
+        .map(|(i, _)| i)
+        .collect();
+    let resolved: Vec<AiAction> = runnable.iter().map(|&i| plan.actions[i].action.clone()).collect();
+
+    let license_snapshot = license.read().await.clone();
+    let (volume_guards, out_of_range) = {
+        let s = settings.read().await;
+        (s.volume_guards.clone(), s.ai.out_of_range.clone())
+    };
+    let conn = conn_state.inner().clone();
+    filter_history::expect_actions(&history, &resolved, "preset");
+    let undo_group = UndoGroup::new(format!("Preset: {}", preset.name));
+    let results = ai_actions::execute_actions(
+        &resolved,
+        &conn,
+        &state_snapshot,
+        &undo_stack,
+        &license_snapshot,
+        &volume_guards,
+        &out_of_range,
+        Some(&undo_group),
+    )
+    .await;
+
+    if results.len() != runnable.len() {
+        log::error!(
+            "Preset '{}' ran {} steps but got {} results",
+            preset.name,
+            runnable.len(),
+            results.len()
+        );
+    }
+    // execute_actions keeps order; pair each result with the step it ran.
+    let mut by_step: std::collections::HashMap<usize, ActionResult> = runnable.into_iter().zip(results).collect();
+    Ok(plan
+        .actions
+        .into_iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let result = match by_step.remove(&i) {
+                Some(mut r) => {
+                    r.pending_action = None;
+                    r
+                }
+                None if step.status == PlanStatus::Skipped => ActionResult {
+                    description: step.description,
+                    status: "skipped".into(),
+                    error: step.detail,
+                    undoable: false,
+                    pending_action: None,
+                },
+                None => ActionResult {
+                    description: step.description,
+                    status: "failed".into(),
+                    error: Some("No result was reported for this step".into()),
+                    undoable: false,
+                    pending_action: None,
+                },
+            };
+            PresetActionResult { result, plan: step.status }
+        })
+        .collect())
 }
 
 #[tauri::command]
 pub async fn undo_last_action(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     undo_stack: tauri::State<'_, SharedUndoStack>,
+    app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
-    let conn = conn_state.lock().await;
-    ai_actions::undo_last(&conn, &undo_stack).await
+    let conn = conn_state.inner().clone();
+    ai_actions::undo_last(&conn, &undo_stack, &app_handle).await
+}
+
+/// Undoes a whole preset apply or chat turn; the newest one when no id is given.
+#[tauri::command]
+pub async fn undo_action_group(
+    conn_state: tauri::State<'_, ObsHandle>,
+    undo_stack: tauri::State<'_, SharedUndoStack>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    group_id: Option<String>,
+) -> Result<UndoGroupResult, String> {
+    let conn = conn_state.inner().clone();
+    ai_actions::undo_group(&conn, &undo_stack, &obs_state, &app_handle, group_id.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn get_undo_history(
+    undo_stack: tauri::State<'_, SharedUndoStack>,
+) -> Result<Vec<UndoHistoryItem>, String> {
+    Ok(undo_stack.read().await.history())
+}
+
+/// Switches the AI backend. A given `api_key` is stored for that provider;
+/// without one the provider's stored or environment key is used. An
+/// OpenAI-compatible provider needs a model and may run without a key.
+/// A model name only carries over while the provider stays the same.
+#[tauri::command]
+pub async fn set_ai_provider(
+    gemini: tauri::State<'_, SharedGeminiClient>,
+    settings: tauri::State<'_, SharedSettings>,
+    provider: String,
+    api_key: Option<String>,
+    base_url: Option<String>,
+    model: Option<String>,
+) -> Result<AiStatus, String> {
+    let kind = AiProviderKind::parse(&provider)?;
+    let mut ai = settings.read().await.ai.clone();
+    let switching = ai.provider != kind;
+    ai.provider = kind;
+    if let Some(base_url) = base_url {
+        ai.base_url = base_url.trim().to_string();
+    }
+    match model.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()) {
+        Some(model) => ai.model = model,
+        None if switching && kind == AiProviderKind::OpenAi => {
+            return Err("Choose a model when switching to an OpenAI-compatible provider".into());
+        }
+        // Gemini's own default, not the other provider's model.
+        None if switching => ai.model.clear(),
+        None => {}
+    }
+
+    let api_key = api_key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty());
+    let key = tokio::task::spawn_blocking(move || match api_key {
+        Some(key) => {
+            api_key_store::save(kind.as_str(), &key)?;
+            Ok(Some((key, KeySource::Disk)))
+        }
+        None => chat_provider::stored_key(kind),
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))??;
+
+    let new_client = chat_provider::build(&ai, key)?;
+    let status = AiStatus {
+        ready: true,
+        provider: kind,
+        key_source: new_client.key_source(),
+    };
+    *gemini.write().await = Some(new_client);
+    settings::update_section(&settings, move |s| s.ai = ai).await?;
+    Ok(status)
+}
+
+async fn save_chat_history(messages: Vec<ChatMessage>, keep: usize) {
+    let result = tokio::task::spawn_blocking(move || chat_history::save_current(&messages, keep))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+        .and_then(|r| r);
+    if let Err(e) = result {
+        log::warn!("Failed to save chat history: {}", e);
+    }
+}
+
+/// The current conversation, or a saved session's when `session_id` is
+/// given, so the chat can be restored after a restart.
+#[tauri::command]
+pub async fn get_chat_history(
+    gemini: tauri::State<'_, SharedGeminiClient>,
+    session_id: Option<String>,
+) -> Result<Vec<ChatMessage>, String> {
+    if let Some(id) = session_id {
+        return tokio::task::spawn_blocking(move || chat_history::load_session(&id))
+            .await
+            .map_err(|e| format!("Task failed: {}", e))?
+            .map(|session| session.messages);
+    }
+    Ok(gemini
+        .read()
+        .await
+        .as_ref()
+        .map(|client| client.history().to_vec())
+        .unwrap_or_default())
 }
 
 #[tauri::command]
-pub async fn set_gemini_api_key(
+pub async fn list_chat_sessions() -> Result<Vec<ChatSessionInfo>, String> {
+    tokio::task::spawn_blocking(chat_history::list_sessions)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn clear_chat_history(
+    gemini: tauri::State<'_, SharedGeminiClient>,
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<(), String> {
+    if let Some(client) = gemini.write().await.as_mut() {
+        client.clear_history();
+    }
+    let ai = settings.read().await.ai.clone();
+    if ai.persist_history {
+        save_chat_history(Vec::new(), ai.history_sessions).await;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_ai_function_calling(
+    gemini: tauri::State<'_, SharedGeminiClient>,
+    settings: tauri::State<'_, SharedSettings>,
+    enabled: bool,
+) -> Result<(), String> {
+    if let Some(client) = gemini.write().await.as_mut() {
+        client.set_function_calling(enabled);
+    }
+    settings::update_section(&settings, |s| s.ai.function_calling = enabled).await
+}
+
+#[tauri::command]
+pub async fn set_ai_preferences(
+    settings: tauri::State<'_, SharedSettings>,
+    preferences: AiPreferences,
+) -> Result<AiPreferences, String> {
+    let preferences = preferences.sanitized();
+    let saved = preferences.clone();
+    settings::update_section(&settings, move |s| s.ai.preferences = saved).await?;
+    Ok(preferences)
+}
+
+#[tauri::command]
+pub async fn get_ai_preferences(
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<AiPreferences, String> {
+    Ok(settings.read().await.ai.preferences.clone())
+}
+
+/// Removes the selected provider's stored key.
+#[tauri::command]
+pub async fn clear_ai_api_key(
     gemini: tauri::State<'_, SharedGeminiClient>,
-    api_key: String,
+    settings: tauri::State<'_, SharedSettings>,
 ) -> Result<(), String> {
+    let kind = settings.read().await.ai.provider;
+    tokio::task::spawn_blocking(move || api_key_store::clear(kind.as_str()))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
     let mut client = gemini.write().await;
-    if api_key.is_empty() {
+    // A key from the environment was never stored, so it stays in use.
+    if client.as_ref().map(|c| c.key_source()) == Some(KeySource::Disk) {
         *client = None;
-    } else {
-        *client = Some(crate::gemini::GeminiClient::new(api_key));
     }
     Ok(())
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiStatus {
+    pub ready: bool,
+    pub provider: AiProviderKind,
+    pub key_source: KeySource,
+}
+
 #[tauri::command]
 pub async fn check_ai_status(
     gemini: tauri::State<'_, SharedGeminiClient>,
-) -> Result<bool, String> {
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<AiStatus, String> {
     let client = gemini.read().await;
-    Ok(client.is_some())
+    Ok(AiStatus {
+        ready: client.is_some(),
+        provider: client
+            .as_ref()
+            .map_or(settings.read().await.ai.provider, |c| c.kind()),
+        key_source: client.as_ref().map_or(KeySource::None, |c| c.key_source()),
+    })
 }
 
 // --- Scene & Output Control Commands ---
 
 #[tauri::command]
 pub async fn set_current_scene(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
     scene_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "SetCurrentProgramScene",
         Some(json!({ "sceneName": scene_name })),
     )
     .await?;
+    macros::record_request(
+        &recorder,
+        "SetCurrentProgramScene",
+        json!({"sceneName": scene_name}),
+        format!("Switch to \"{}\"", scene_name),
+    )
+    .await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_studio_mode_enabled(
+    conn_state: tauri::State<'_, ObsHandle>,
+) -> Result<bool, String> {
+    let resp = conn_state.send_request("GetStudioModeEnabled", None).await?;
+    Ok(resp["studioModeEnabled"].as_bool().unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn set_studio_mode_enabled(
+    conn_state: tauri::State<'_, ObsHandle>,
+    enabled: bool,
+) -> Result<(), String> {
+    conn_state
+        .send_request("SetStudioModeEnabled", Some(json!({ "studioModeEnabled": enabled })))
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_current_preview_scene(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    scene_name: String,
+) -> Result<(), String> {
+    if !obs_state.read().await.studio_mode {
+        return Err("Studio mode is off; there is no preview scene to set".to_string());
+    }
+    conn_state
+        .send_request("SetCurrentPreviewScene", Some(json!({ "sceneName": scene_name })))
+        .await?;
+    Ok(())
+}
+
+/// Moves the preview scene to program with the current transition.
+#[tauri::command]
+pub async fn trigger_studio_mode_transition(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<(), String> {
+    if !obs_state.read().await.studio_mode {
+        return Err("Studio mode is off; switch scenes directly instead".to_string());
+    }
+    conn_state.send_request("TriggerStudioModeTransition", None).await?;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn create_scene(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     scene_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request("CreateScene", Some(json!({ "sceneName": scene_name })))
         .await?;
     Ok(())
@@ -959,10 +1987,10 @@ pub async fn create_scene(
 
 #[tauri::command]
 pub async fn remove_scene(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     scene_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request("RemoveScene", Some(json!({ "sceneName": scene_name })))
         .await?;
     Ok(())
@@ -970,11 +1998,11 @@ pub async fn remove_scene(
 
 #[tauri::command]
 pub async fn rename_scene(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     scene_name: String,
     new_scene_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "SetSceneName",
         Some(json!({ "sceneName": scene_name, "newSceneName": new_scene_name })),
@@ -985,12 +2013,16 @@ pub async fn rename_scene(
 
 #[tauri::command]
 pub async fn get_scene_screenshot(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    health: tauri::State<'_, SharedObsHealth>,
     scene_name: String,
     width: u32,
     height: u32,
 ) -> Result<String, String> {
-    let conn = conn_state.lock().await;
+    if health.lock().map(|h| h.is_degraded()).unwrap_or(false) {
+        return Err("OBS is not responding; preview paused".to_string());
+    }
+    let conn = conn_state.inner().clone();
     let resp = conn
         .send_request(
             "GetSourceScreenshot",
@@ -1012,29 +2044,319 @@ pub async fn get_scene_screenshot(
 
 #[tauri::command]
 pub async fn toggle_stream(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request("ToggleStream", None).await?;
+    macros::record_request(
+        &recorder,
+        "ToggleStream",
+        json!({}),
+        "Toggle streaming".to_string(),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn toggle_record(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request("ToggleRecord", None).await?;
+    macros::record_request(
+        &recorder,
+        "ToggleRecord",
+        json!({}),
+        "Toggle recording".to_string(),
+    )
+    .await;
+    Ok(())
+}
+
+// --- Recording Commands ---
+
+async fn record_request(
+    conn: &ObsHandle,
+    recorder: &SharedMacroState,
+    request_type: &str,
+    label: &str,
+) -> Result<(), String> {
+    conn.send_request(request_type, None).await?;
+    macros::record_request(recorder, request_type, json!({}), label.to_string()).await;
     Ok(())
 }
 
+#[tauri::command]
+pub async fn pause_record(
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+) -> Result<(), String> {
+    record_request(&conn_state, &recorder, "PauseRecord", "Pause recording").await
+}
+
+#[tauri::command]
+pub async fn resume_record(
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+) -> Result<(), String> {
+    record_request(&conn_state, &recorder, "ResumeRecord", "Resume recording").await
+}
+
+/// Starts a new file without stopping; needs OBS 30+ and file splitting
+/// enabled in its output settings. The new path arrives via obs://record-file-changed.
+#[tauri::command]
+pub async fn split_record_file(
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+) -> Result<(), String> {
+    record_request(&conn_state, &recorder, "SplitRecordFile", "Split recording file").await
+}
+
+#[tauri::command]
+pub async fn get_record_status(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<RecordStatus, String> {
+    let conn = conn_state.inner().clone();
+    let resp = conn.send_request("GetRecordStatus", None).await?;
+    let mut s = obs_state.write().await;
+    s.record_status.update_from(&resp);
+    Ok(s.record_status.clone())
+}
+
+// --- Replay Buffer Commands ---
+
+async fn replay_buffer_request(
+    conn: &ObsHandle,
+    recorder: &SharedMacroState,
+    request_type: &str,
+    label: &str,
+) -> Result<(), String> {
+    conn.send_request(request_type, None).await.map_err(|e| {
+        if e.contains("604") || e.contains("not available") {
+            "The replay buffer is turned off in OBS (Settings > Output > Replay Buffer)".to_string()
+        } else {
+            e
+        }
+    })?;
+    macros::record_request(recorder, request_type, json!({}), label.to_string()).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn toggle_replay_buffer(
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+) -> Result<(), String> {
+    replay_buffer_request(&conn_state, &recorder, "ToggleReplayBuffer", "Toggle replay buffer").await
+}
+
+#[tauri::command]
+pub async fn start_replay_buffer(
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+) -> Result<(), String> {
+    replay_buffer_request(&conn_state, &recorder, "StartReplayBuffer", "Start replay buffer").await
+}
+
+#[tauri::command]
+pub async fn stop_replay_buffer(
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+) -> Result<(), String> {
+    replay_buffer_request(&conn_state, &recorder, "StopReplayBuffer", "Stop replay buffer").await
+}
+
+/// The saved path arrives separately through obs://replay-saved.
+#[tauri::command]
+pub async fn save_replay_buffer(
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
+) -> Result<(), String> {
+    replay_buffer_request(&conn_state, &recorder, "SaveReplayBuffer", "Save replay").await
+}
+
+#[tauri::command]
+pub async fn get_replay_buffer_status(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<ReplayBufferStatus, String> {
+    let conn = conn_state.inner().clone();
+    let status = obs_state::fetch_replay_buffer_status(&conn).await;
+    obs_state.write().await.replay_buffer_status = status.clone();
+    Ok(status)
+}
+
+// --- Stream Service Commands ---
+
+#[tauri::command]
+pub async fn get_stream_service_settings(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<StreamServiceSettings, String> {
+    let conn = conn_state.inner().clone();
+    let settings = stream_service::fetch(&conn).await?;
+    obs_state.write().await.stream_service = settings.clone();
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn get_stream_presets() -> Result<Vec<StreamPreset>, String> {
+    Ok(stream_service::presets())
+}
+
+#[tauri::command]
+pub async fn set_stream_service_settings(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    service: Option<String>,
+    server: Option<String>,
+    key: Option<String>,
+) -> Result<StreamServiceSettings, String> {
+    let (streaming, current_server) = {
+        let s = obs_state.read().await;
+        (s.stream_status.active, s.stream_service.server.clone())
+    };
+    if streaming {
+        return Err("Cannot change stream settings while streaming".to_string());
+    }
+
+    let server = match service.as_deref().filter(|s| !s.is_empty()) {
+        Some(id) => stream_service::find_preset(id)
+            .map(|p| p.server)
+            .ok_or_else(|| format!("Unknown stream preset: {}", id))?,
+        None => server.filter(|s| !s.is_empty()).unwrap_or(current_server),
+    };
+    if server.is_empty() {
+        return Err("No stream server specified".to_string());
+    }
+
+    let conn = conn_state.inner().clone();
+    stream_service::apply(&conn, &server, key.as_deref()).await?;
+    let settings = stream_service::fetch(&conn).await?;
+
+    obs_state.write().await.stream_service = settings.clone();
+    let _ = app_handle.emit("obs://stream-service-changed", &settings);
+    Ok(settings)
+}
+
+#[tauri::command]
+pub async fn test_stream_destination(
+    obs_state: tauri::State<'_, SharedObsState>,
+    server: Option<String>,
+) -> Result<StreamDestinationTest, String> {
+    let server = match server.filter(|s| !s.is_empty()) {
+        Some(s) => s,
+        None => obs_state.read().await.stream_service.server.clone(),
+    };
+    if server.is_empty() {
+        return Err("No stream server configured".to_string());
+    }
+    Ok(stream_service::test_destination(&server).await)
+}
+
 // --- OBS Launcher Commands ---
 
 #[tauri::command]
-pub async fn launch_obs(minimize: bool) -> Result<ObsLaunchStatus, String> {
-    tokio::task::spawn_blocking(move || Ok(obs_launcher::launch_obs(minimize)))
+pub async fn launch_obs(
+    minimize: bool,
+    profile: Option<String>,
+    scene_collection: Option<String>,
+) -> Result<ObsLaunchStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        Ok(obs_launcher::launch_obs(minimize, profile.as_deref(), scene_collection.as_deref()))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Launches OBS (unless it's already running as asked), waits for its
+/// websocket server and connects. The server comes up a few seconds after
+/// the window, so both the port and the handshake are retried until
+/// `timeout_secs` (default 30) runs out.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn launch_and_connect(
+    conn_state: tauri::State<'_, SharedObsConnection>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    host: String,
+    port: u16,
+    password: Option<String>,
+    minimize: bool,
+    profile: Option<String>,
+    scene_collection: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<ObsStatus, String> {
+    let launch = {
+        let (profile, collection) = (profile.clone(), scene_collection.clone());
+        tokio::task::spawn_blocking(move || {
+            obs_launcher::launch_obs(minimize, profile.as_deref(), collection.as_deref())
+        })
         .await
         .map_err(|e| format!("Task failed: {}", e))?
+    };
+    if let Some(e) = launch.error {
+        return Err(e);
+    }
+
+    let deadline =
+        std::time::Instant::now() + std::time::Duration::from_secs(timeout_secs.unwrap_or(30));
+    let mut delay = std::time::Duration::from_millis(250);
+    let handle = loop {
+        let last_error = match tokio::net::TcpStream::connect((host.as_str(), port)).await {
+            Ok(_) => {
+                let mut conn = conn_state.lock().await;
+                let result = conn
+                    .connect(
+                        &host,
+                        port,
+                        password.as_deref(),
+                        app_handle.clone(),
+                        obs_state.inner().clone(),
+                    )
+                    .await;
+                match result {
+                    Ok(()) => break conn.handle(),
+                    // A wrong password won't fix itself.
+                    Err(ConnectError::Auth(e)) => return Err(e),
+                    Err(ConnectError::Other(e)) => e,
+                }
+            }
+            Err(e) => format!("OBS websocket not reachable on {}:{}: {}", host, port, e),
+        };
+        if std::time::Instant::now() + delay > deadline {
+            return Err(format!(
+                "OBS did not accept a websocket connection within {} s ({})",
+                timeout_secs.unwrap_or(30),
+                last_error
+            ));
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(std::time::Duration::from_secs(2));
+    };
+
+    // The connection lock is already released, so disconnect_obs can get
+    // through while the state loads.
+    sync_after_connect(&handle, obs_state.inner(), &app_handle).await;
+
+    if launch.already_running {
+        // global.ini can lag behind a switch that hasn't been saved yet.
+        if let Some(wanted) = profile.filter(|p| !p.is_empty()) {
+            let resp = handle.send_request("GetProfileList", None).await?;
+            let current = resp["currentProfileName"].as_str().unwrap_or("");
+            if !current.eq_ignore_ascii_case(&wanted) {
+                // Don't leave the app driving an OBS the caller didn't ask for.
+                conn_state.lock().await.disconnect().await;
+                return Err(format!("OBS is already running with profile '{}'", current));
+            }
+        }
+    }
+    Ok(conn_state.lock().await.status())
 }
 
 #[tauri::command]
@@ -1073,6 +2395,60 @@ pub async fn install_vsts(
     vst_manager::install_vsts(&app_handle)
 }
 
+/// Restores any plugin that is missing or fails its hash check.
+#[tauri::command]
+pub async fn repair_vsts(
+    license: tauri::State<'_, SharedLicenseState>,
+    app_handle: tauri::AppHandle,
+) -> Result<VstRepairResult, String> {
+    crate::store::require_module(&license, "audio-fx").await?;
+    vst_manager::repair_vsts(&app_handle).await
+}
+
+/// Scans the standard VST2 folders plus the configured search paths.
+#[tauri::command]
+pub async fn scan_user_vsts(
+    license: tauri::State<'_, SharedLicenseState>,
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<Vec<UserVst>, String> {
+    crate::store::require_module(&license, "audio-fx").await?;
+    let search_paths = settings.read().await.vst.search_paths.clone();
+    tokio::task::spawn_blocking(move || vst_manager::scan_user_vsts(&search_paths))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn add_vst_search_path(
+    settings: tauri::State<'_, SharedSettings>,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let path = path.trim().trim_end_matches(['\\', '/']).to_string();
+    if !std::path::Path::new(&path).is_dir() {
+        return Err(format!("Folder not found: {}", path));
+    }
+    settings::update_section(&settings, move |s| {
+        if !s.vst.search_paths.iter().any(|p| p.eq_ignore_ascii_case(&path)) {
+            s.vst.search_paths.push(path);
+        }
+    })
+    .await?;
+    Ok(settings.read().await.vst.search_paths.clone())
+}
+
+#[tauri::command]
+pub async fn remove_vst_search_path(
+    settings: tauri::State<'_, SharedSettings>,
+    path: String,
+) -> Result<Vec<String>, String> {
+    let path = path.trim().trim_end_matches(['\\', '/']).to_string();
+    settings::update_section(&settings, move |s| {
+        s.vst.search_paths.retain(|p| !p.eq_ignore_ascii_case(&path));
+    })
+    .await?;
+    Ok(settings.read().await.vst.search_paths.clone())
+}
+
 #[tauri::command]
 pub async fn get_vst_catalog(
     license: tauri::State<'_, SharedLicenseState>,
@@ -1092,20 +2468,12 @@ pub async fn download_vst(
 
 #[tauri::command]
 pub async fn get_source_filter_kinds(
-    state: tauri::State<'_, SharedObsConnection>,
+    state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
 ) -> Result<Vec<String>, String> {
-    let conn = state.lock().await;
-    let resp = conn
-        .send_request("GetSourceFilterKindList", None)
-        .await?;
-    let kinds = resp["sourceFilterKinds"]
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(String::from))
-                .collect()
-        })
-        .unwrap_or_default();
+    let conn = state.inner().clone();
+    let kinds = obs_state::fetch_filter_kinds(&conn).await?;
+    obs_state.write().await.filter_kinds = kinds.clone();
     Ok(kinds)
 }
 
@@ -1125,12 +2493,29 @@ pub async fn get_ducking_config(
 pub async fn set_ducking_config(
     license: tauri::State<'_, SharedLicenseState>,
     state: tauri::State<'_, SharedDuckingConfig>,
+    settings: tauri::State<'_, SharedSettings>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    undo_stack: tauri::State<'_, SharedUndoStack>,
+    metrics_notify: tauri::State<'_, SharedMetricsNotify>,
     config: DuckingConfig,
-) -> Result<(), String> {
+) -> Result<Vec<String>, String> {
     crate::store::require_module(&license, "ducking").await?;
-    let mut current = state.write().await;
-    *current = config;
-    Ok(())
+    let previous = {
+        let mut current = state.write().await;
+        std::mem::replace(&mut *current, config.clone())
+    };
+    metrics_notify.wake();
+    // The volume loop restores its targets itself once the mode changes;
+    // the sidechain compressors have to be added or removed here.
+    let conn = conn_state.inner().clone();
+    let warnings = if conn.is_connected() {
+        ducking::sync_sidechain(&conn, &obs_state, &undo_stack, &previous, &config).await
+    } else {
+        Vec::new()
+    };
+    settings::update_section(&settings, |s| s.ducking = config).await?;
+    Ok(warnings)
 }
 
 // --- App Capture Commands ---
@@ -1142,13 +2527,41 @@ pub async fn get_audio_processes() -> Result<Vec<AudioProcess>, String> {
         .map_err(|e| format!("Task failed: {}", e))?
 }
 
+/// Sets an app's session volume (0.0-1.0), independent of OBS.
+#[tauri::command]
+pub async fn set_app_volume(process_id: u32, volume: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&volume) {
+        return Err(format!("Volume must be between 0 and 1, not {}", volume));
+    }
+    tokio::task::spawn_blocking(move || app_capture::set_session_volume(process_id, volume))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn set_app_mute(process_id: u32, muted: bool) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || app_capture::set_session_mute(process_id, muted))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
 #[tauri::command]
 pub async fn add_app_capture(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
     process_name: String,
     display_name: Option<String>,
     scene_name: Option<String>,
+) -> Result<String, String> {
+    create_app_capture(&conn_state, &obs_state, &process_name, display_name, scene_name).await
+}
+
+pub(crate) async fn create_app_capture(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    process_name: &str,
+    display_name: Option<String>,
+    scene_name: Option<String>,
 ) -> Result<String, String> {
     let label = display_name.unwrap_or_else(|| process_name.replace(".exe", ""));
     let input_name = format!("App: {}", label);
@@ -1172,7 +2585,6 @@ pub async fn add_app_capture(
         return Err("No scene available to add the capture source".to_string());
     }
 
-    let conn = conn_state.lock().await;
     conn.send_request(
         "CreateInput",
         Some(json!({
@@ -1191,10 +2603,10 @@ pub async fn add_app_capture(
 
 #[tauri::command]
 pub async fn remove_app_capture(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     input_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request("RemoveInput", Some(json!({"inputName": input_name})))
         .await?;
     Ok(())
@@ -1202,36 +2614,53 @@ pub async fn remove_app_capture(
 
 #[tauri::command]
 pub async fn start_virtual_cam(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request("StartVirtualCam", None).await?;
+    macros::record_request(
+        &recorder,
+        "StartVirtualCam",
+        json!({}),
+        "Start virtual camera".to_string(),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn stop_virtual_cam(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    recorder: tauri::State<'_, SharedMacroState>,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request("StopVirtualCam", None).await?;
+    macros::record_request(
+        &recorder,
+        "StopVirtualCam",
+        json!({}),
+        "Stop virtual camera".to_string(),
+    )
+    .await;
     Ok(())
 }
 
 #[tauri::command]
 pub async fn get_virtual_cam_status(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
 ) -> Result<bool, String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     let resp = conn.send_request("GetVirtualCamStatus", None).await?;
     Ok(resp["outputActive"].as_bool().unwrap_or(false))
 }
 
 #[tauri::command]
 pub async fn ensure_virtual_cam_program(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    settings: tauri::State<'_, SharedSettings>,
 ) -> Result<String, String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
 
     // Find OBS scene collection JSON
     let appdata = std::env::var("APPDATA")
@@ -1269,6 +2698,8 @@ pub async fn ensure_virtual_cam_program(
         return Ok("already_program".to_string());
     }
 
+    crate::obs_backup::auto_backup(&settings, "vcam-program").await?;
+
     // Set to Program (type2: 0) — need to stop vcam, update file, restart
     let was_active = conn.send_request("GetVirtualCamStatus", None).await
         .map(|r| r["outputActive"].as_bool().unwrap_or(false))
@@ -1341,12 +2772,12 @@ pub async fn ensure_virtual_cam_program(
 
 #[tauri::command]
 pub async fn set_scene_item_transform(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     scene_name: String,
     scene_item_id: u64,
     transform: Value,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "SetSceneItemTransform",
         Some(json!({
@@ -1359,6 +2790,83 @@ pub async fn set_scene_item_transform(
     Ok(())
 }
 
+/// Items of a scene, bottom to top, re-read from OBS so transforms are current.
+#[tauri::command]
+pub async fn get_scene_items(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    scene_name: String,
+) -> Result<Vec<SceneItemInfo>, String> {
+    let conn = conn_state.inner().clone();
+    obs_state::refresh_scene_items(&conn, &obs_state, &scene_name).await?;
+    Ok(obs_state
+        .read()
+        .await
+        .scene_items
+        .get(&scene_name)
+        .cloned()
+        .unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn set_scene_item_enabled(
+    conn_state: tauri::State<'_, ObsHandle>,
+    scene_name: String,
+    scene_item_id: u64,
+    enabled: bool,
+) -> Result<(), String> {
+    let conn = conn_state.inner().clone();
+    conn.send_request(
+        "SetSceneItemEnabled",
+        Some(json!({
+            "sceneName": scene_name,
+            "sceneItemId": scene_item_id,
+            "sceneItemEnabled": enabled,
+        })),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_scene_item(
+    conn_state: tauri::State<'_, ObsHandle>,
+    scene_name: String,
+    scene_item_id: u64,
+) -> Result<(), String> {
+    let conn = conn_state.inner().clone();
+    conn.send_request(
+        "RemoveSceneItem",
+        Some(json!({
+            "sceneName": scene_name,
+            "sceneItemId": scene_item_id,
+        })),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Moves an item in the scene's stacking order; index 0 is the bottom.
+#[tauri::command]
+pub async fn set_scene_item_index(
+    conn_state: tauri::State<'_, ObsHandle>,
+    scene_name: String,
+    scene_item_id: u64,
+    scene_item_index: u32,
+) -> Result<(), String> {
+    let conn = conn_state.inner().clone();
+    conn.send_request(
+        "SetSceneItemIndex",
+        Some(json!({
+            "sceneName": scene_name,
+            "sceneItemId": scene_item_id,
+            "sceneItemIndex": scene_item_index,
+        })),
+    )
+    .await?;
+    Ok(())
+}
+
 #[derive(serde::Serialize)]
 pub struct AutoCamResult {
     pub created: Vec<String>,
@@ -1368,7 +2876,7 @@ pub struct AutoCamResult {
 #[tauri::command]
 pub async fn auto_setup_cameras(
     license: tauri::State<'_, SharedLicenseState>,
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
 ) -> Result<AutoCamResult, String> {
     crate::store::require_module(&license, "camera").await?;
@@ -1394,7 +2902,7 @@ pub async fn auto_setup_cameras(
         }};
     }
 
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
 
     // Get canvas dimensions for fit-to-screen
     let (base_width, base_height) = {
@@ -1568,9 +3076,7 @@ pub async fn auto_setup_cameras(
                     "sceneName": &scene_name,
                     "inputName": &camera.name,
                     "inputKind": "dshow_input",
-                    "inputSettings": {
-                        "video_device_id": &camera.id,
-                    }
+                    "inputSettings": source_schemas::dshow_input(&camera.id, None),
                 })),
             )
             .await
@@ -1619,9 +3125,7 @@ pub async fn auto_setup_cameras(
 
     // Refresh state cache if we created anything
     if !created_scenes.is_empty() {
-        drop(conn);
-        let conn = conn_state.lock().await;
-        let _ = obs_state::populate_initial_state(&conn, obs_state.inner()).await;
+        let _ = obs_state::populate_initial_state(&conn, obs_state.inner(), None).await;
     }
 
     Ok(AutoCamResult { created: created_scenes, logs })
@@ -1639,10 +3143,10 @@ fn clean_camera_name(raw: &str) -> String {
 
 #[tauri::command]
 pub async fn open_source_properties(
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     source_name: String,
 ) -> Result<(), String> {
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "OpenInputPropertiesDialog",
         Some(json!({ "inputName": source_name })),
@@ -1655,3 +3159,49 @@ pub async fn open_source_properties(
 pub async fn open_devtools(window: tauri::WebviewWindow) {
     window.open_devtools();
 }
+
+// --- Developer Console Commands ---
+
+#[tauri::command]
+pub async fn set_ws_logging(
+    conn_state: tauri::State<'_, ObsHandle>,
+    enabled: bool,
+    max_entries: Option<usize>,
+    payload_limit: Option<usize>,
+) -> Result<(), String> {
+    let ws_log = conn_state.ws_log();
+    ws_log.lock().await.configure(enabled, max_entries, payload_limit);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_ws_log(
+    conn_state: tauri::State<'_, ObsHandle>,
+) -> Result<Vec<WsLogEntry>, String> {
+    let ws_log = conn_state.ws_log();
+    let entries = ws_log.lock().await.entries();
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn clear_ws_log(
+    conn_state: tauri::State<'_, ObsHandle>,
+) -> Result<(), String> {
+    let ws_log = conn_state.ws_log();
+    ws_log.lock().await.clear();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn export_ws_log(
+    conn_state: tauri::State<'_, ObsHandle>,
+    path: String,
+) -> Result<usize, String> {
+    let ws_log = conn_state.ws_log();
+    let replay = ws_log.lock().await.to_replay();
+    let count = replay["messages"].as_array().map_or(0, |m| m.len());
+    let json = serde_json::to_string_pretty(&replay)
+        .map_err(|e| format!("Failed to serialize log: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write log: {}", e))?;
+    Ok(count)
+}
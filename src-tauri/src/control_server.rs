@@ -0,0 +1,522 @@
+use crate::commands;
+use crate::i18n::t;
+use crate::obs_state::SharedObsState;
+use crate::settings::{self, ControlServerSettings, SharedSettings};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{oneshot, Mutex};
+
+pub type SharedControlServer = Arc<Mutex<Option<RunningServer>>>;
+
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const COMMANDS: &[&str] = &[
+    "apply_preset",
+    "set_input_volume",
+    "toggle_input_mute",
+    "set_current_scene",
+    "toggle_record",
+    "run_preflight",
+    "get_obs_state",
+];
+
+pub struct RunningServer {
+    port: u16,
+    token: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlServerStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    pub token: String,
+    pub commands: Vec<String>,
+}
+
+/// An HTTP status and the error message sent back with it.
+type Rejection = (u16, String);
+
+const BAD_REQUEST: u16 = 400;
+const UNAUTHORIZED: u16 = 401;
+const NOT_FOUND: u16 = 404;
+const METHOD_NOT_ALLOWED: u16 = 405;
+const PAYLOAD_TOO_LARGE: u16 = 413;
+const INTERNAL_ERROR: u16 = 500;
+
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+pub fn generate_token() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+/// Starts, stops or restarts the server so it matches the current settings.
+pub async fn sync(app: &tauri::AppHandle) -> Result<(), String> {
+    let config = app.state::<SharedSettings>().read().await.control_server.clone();
+    apply(app, config).await
+}
+
+/// Starts, stops or restarts the server for `config`, then saves it. A port
+/// that fails to bind leaves the saved settings as they were.
+pub async fn apply(app: &tauri::AppHandle, mut config: ControlServerSettings) -> Result<(), String> {
+    if config.enabled && config.token.is_empty() {
+        config.token = generate_token();
+    }
+    run(app, &config).await?;
+    persist(app, config).await
+}
+
+/// Starts, stops or restarts the server so it matches `config`.
+async fn run<R: tauri::Runtime>(app: &tauri::AppHandle<R>, config: &ControlServerSettings) -> Result<(), String> {
+    let server = app.state::<SharedControlServer>().inner().clone();
+    let mut running = server.lock().await;
+    let unchanged = running
+        .as_ref()
+        .map(|r| r.port == config.port && r.token == config.token)
+        .unwrap_or(false);
+    if config.enabled && unchanged {
+        return Ok(());
+    }
+    if let Some(old) = running.take() {
+        let _ = old.shutdown.send(());
+        log::info!("Control server on port {} stopped", old.port);
+    }
+    if !config.enabled {
+        return Ok(());
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", config.port))
+        .await
+        .map_err(|e| t("control_server.bind_failed", &[("port", &config.port), ("error", &e)]))?;
+    let port = listener
+        .local_addr()
+        .map(|a| a.port())
+        .unwrap_or(config.port);
+    let (tx, rx) = oneshot::channel();
+    tauri::async_runtime::spawn(serve(listener, app.clone(), config.token.clone(), rx));
+    log::info!("Control server listening on 127.0.0.1:{}", port);
+    *running = Some(RunningServer {
+        port,
+        token: config.token.clone(),
+        shutdown: tx,
+    });
+    Ok(())
+}
+
+async fn persist(app: &tauri::AppHandle, config: ControlServerSettings) -> Result<(), String> {
+    let settings = app.state::<SharedSettings>().inner().clone();
+    if settings.read().await.control_server == config {
+        return Ok(());
+    }
+    settings::update_section(&settings, move |s| s.control_server = config).await
+}
+
+async fn serve<R: tauri::Runtime>(listener: TcpListener, app: tauri::AppHandle<R>, token: String, mut shutdown: oneshot::Receiver<()>) {
+    let token = Arc::new(token);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let app = app.clone();
+                    let token = token.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = handle_connection(stream, &app, &token).await {
+                            log::debug!("Control server connection error: {}", e);
+                        }
+                    });
+                }
+                Err(e) => log::warn!("Control server accept failed: {}", e),
+            },
+        }
+    }
+}
+
+async fn handle_connection<R: tauri::Runtime>(mut stream: TcpStream, app: &tauri::AppHandle<R>, token: &str) -> Result<(), String> {
+    let (status, body) = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Err(_) => return Err("Timed out reading request".into()),
+        Ok(Err((status, e))) => (status, json!({"ok": false, "error": e})),
+        Ok(Ok(req)) => match route(app, token, req).await {
+            Ok(data) => (200, json!({"ok": true, "data": data})),
+            Err((status, e)) => (status, json!({"ok": false, "error": e})),
+        },
+    };
+    write_response(&mut stream, status, &body).await
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, Rejection> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err((PAYLOAD_TOO_LARGE, "Request headers too large".to_string()));
+        }
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| (BAD_REQUEST, format!("Read failed: {}", e)))?;
+        if n == 0 {
+            return Err((BAD_REQUEST, "Connection closed mid-request".to_string()));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("").to_uppercase();
+    let path = request_line.next().unwrap_or("").to_string();
+
+    let mut content_length = 0usize;
+    let mut authorization = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.trim().to_lowercase().as_str() {
+            "content-length" => {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| (BAD_REQUEST, "Invalid Content-Length".to_string()))?;
+            }
+            "authorization" => authorization = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err((PAYLOAD_TOO_LARGE, "Request body too large".to_string()));
+    }
+
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| (BAD_REQUEST, format!("Read failed: {}", e)))?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(Request {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, status: u16, body: &Value) -> Result<(), String> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+    let payload = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        payload.len(),
+        payload
+    );
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| format!("Write failed: {}", e))?;
+    let _ = stream.shutdown().await;
+    Ok(())
+}
+
+fn token_matches(header: Option<&str>, token: &str) -> bool {
+    let Some(presented) = header.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let (a, b) = (presented.trim().as_bytes(), token.as_bytes());
+    // Constant-time so the token can't be recovered byte by byte from timings.
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn route<R: tauri::Runtime>(app: &tauri::AppHandle<R>, token: &str, req: Request) -> Result<Value, Rejection> {
+    if !token_matches(req.authorization.as_deref(), token) {
+        return Err((UNAUTHORIZED, "Missing or invalid bearer token".to_string()));
+    }
+    let Some(command) = req.path.strip_prefix("/api/") else {
+        return Err((NOT_FOUND, format!("Unknown path: {}", req.path)));
+    };
+    if !COMMANDS.contains(&command) {
+        return Err((NOT_FOUND, format!("Unknown command: {}", command)));
+    }
+    if req.method != "POST" {
+        return Err((METHOD_NOT_ALLOWED, "Use POST".to_string()));
+    }
+    let args: Value = if req.body.is_empty() {
+        json!({})
+    } else {
+        serde_json::from_slice(&req.body)
+            .map_err(|e| (BAD_REQUEST, format!("Invalid JSON body: {}", e)))?
+    };
+    dispatch(app, command, &args).await
+}
+
+fn arg_str(args: &Value, key: &str) -> Result<String, Rejection> {
+    args[key]
+        .as_str()
+        .map(String::from)
+        .ok_or_else(|| (BAD_REQUEST, format!("Missing string field '{}'", key)))
+}
+
+fn to_value<T: Serialize>(result: Result<T, String>) -> Result<Value, Rejection> {
+    let data = result.map_err(|e| (INTERNAL_ERROR, e))?;
+    serde_json::to_value(data).map_err(|e| (INTERNAL_ERROR, format!("Failed to serialize result: {}", e)))
+}
+
+/// Runs the same Tauri command handlers the frontend invokes, against the same
+/// managed state.
+async fn dispatch<R: tauri::Runtime>(app: &tauri::AppHandle<R>, command: &str, args: &Value) -> Result<Value, Rejection> {
+    match command {
+        "apply_preset" => {
+            let preset_id = arg_str(args, "presetId")?;
+            to_value(
+                commands::apply_preset(
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    app.state(),
+                    preset_id,
+                    args["micSource"].as_str().map(String::from),
+                    args["desktopSource"].as_str().map(String::from),
+                    serde_json::from_value(args["conflictPolicy"].clone()).ok(),
+                )
+                .await,
+            )
+        }
+        "set_input_volume" => {
+            let input_name = arg_str(args, "inputName")?;
+            let volume_db = args["volumeDb"]
+                .as_f64()
+                .ok_or_else(|| (BAD_REQUEST, "Missing number field 'volumeDb'".to_string()))?;
+            to_value(commands::set_input_volume(app.state(), app.state(), app.state(), input_name, volume_db).await)
+        }
+        "toggle_input_mute" => {
+            let input_name = arg_str(args, "inputName")?;
+            to_value(commands::toggle_input_mute(app.state(), app.state(), input_name).await)
+        }
+        "set_current_scene" => {
+            let scene_name = arg_str(args, "sceneName")?;
+            to_value(commands::set_current_scene(app.state(), app.state(), scene_name).await)
+        }
+        "toggle_record" => to_value(commands::toggle_record(app.state(), app.state()).await),
+        "run_preflight" => {
+            let mode = args["mode"].as_str().unwrap_or("stream").to_string();
+            to_value(commands::run_preflight(app.state(), app.state(), app.state(), mode).await)
+        }
+        "get_obs_state" => Ok(state_summary(&app.state::<SharedObsState>()).await),
+        other => Err((NOT_FOUND, format!("Unknown command: {}", other))),
+    }
+}
+
+async fn state_summary(obs_state: &SharedObsState) -> Value {
+    let s = obs_state.read().await;
+    let mut inputs: Vec<Value> = s
+        .inputs
+        .values()
+        .map(|i| json!({"name": i.name, "kind": i.kind, "volumeDb": i.volume_db, "muted": i.muted}))
+        .collect();
+    inputs.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    json!({
+        "currentScene": s.current_scene,
+        "scenes": s.scenes.iter().map(|sc| sc.name.clone()).collect::<Vec<_>>(),
+        "inputs": inputs,
+        "streaming": s.stream_status.active,
+        "recording": s.record_status.active,
+        "recordingPaused": s.record_status.paused,
+    })
+}
+
+#[tauri::command]
+pub async fn get_control_server_status(
+    settings: tauri::State<'_, SharedSettings>,
+    server: tauri::State<'_, SharedControlServer>,
+) -> Result<ControlServerStatus, String> {
+    let config = settings.read().await.control_server.clone();
+    let running = server.lock().await.as_ref().map(|r| r.port);
+    Ok(ControlServerStatus {
+        enabled: config.enabled,
+        running: running.is_some(),
+        port: running.unwrap_or(config.port),
+        token: config.token,
+        commands: COMMANDS.iter().map(|c| c.to_string()).collect(),
+    })
+}
+
+#[tauri::command]
+pub async fn regenerate_control_token(
+    settings: tauri::State<'_, SharedSettings>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, String> {
+    let token = generate_token();
+    let stored = token.clone();
+    settings::update_section(&settings, move |s| s.control_server.token = stored).await?;
+    sync(&app_handle).await?;
+    Ok(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::macros::SharedMacroState;
+    use crate::obs_websocket::ObsHandle;
+    use std::sync::Mutex as StdMutex;
+
+    const TOKEN: &str = "test-token";
+
+    /// An app with a mocked OBS connection that records every request.
+    fn mock_app() -> (tauri::App<tauri::test::MockRuntime>, Arc<StdMutex<Vec<(String, Value)>>>) {
+        let requests = Arc::new(StdMutex::new(Vec::new()));
+        let seen = requests.clone();
+        let obs = ObsHandle::mock(move |request_type, data| {
+            seen.lock().unwrap().push((request_type.to_string(), data.clone()));
+            Ok(json!({}))
+        });
+        let app = tauri::test::mock_app();
+        app.manage(obs);
+        app.manage(SharedMacroState::default());
+        app.manage(SharedObsState::default());
+        app.manage(Arc::new(Mutex::new(None)) as SharedControlServer);
+        (app, requests)
+    }
+
+    fn config(enabled: bool, token: &str) -> ControlServerSettings {
+        ControlServerSettings {
+            enabled,
+            // Any free port.
+            port: 0,
+            token: token.into(),
+        }
+    }
+
+    async fn running_port<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Option<u16> {
+        app.state::<SharedControlServer>().lock().await.as_ref().map(|r| r.port)
+    }
+
+    /// POSTs `body` and returns the status and JSON body of the response.
+    async fn post(port: u16, path: &str, token: Option<&str>, body: &str) -> std::io::Result<(u16, Value)> {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+        let auth = token.map(|t| format!("Authorization: Bearer {}\r\n", t)).unwrap_or_default();
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            path,
+            auth,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+        let status = head.split_whitespace().nth(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+        Ok((status, serde_json::from_str(body).unwrap_or(Value::Null)))
+    }
+
+    #[test]
+    fn rejects_missing_or_wrong_token() {
+        let (app, requests) = mock_app();
+        tauri::async_runtime::block_on(async {
+            run(app.handle(), &config(true, TOKEN)).await.unwrap();
+            let port = running_port(app.handle()).await.unwrap();
+            let body = r#"{"sceneName": "BRB"}"#;
+            for token in [None, Some("wrong-token"), Some("")] {
+                let (status, reply) = post(port, "/api/set_current_scene", token, body).await.unwrap();
+                assert_eq!(status, 401, "token {:?}", token);
+                assert_eq!(reply["ok"], false);
+                assert_eq!(reply["error"], "Missing or invalid bearer token");
+                assert!(reply.get("data").is_none());
+            }
+        });
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn runs_an_authorized_command_against_obs() {
+        let (app, requests) = mock_app();
+        tauri::async_runtime::block_on(async {
+            run(app.handle(), &config(true, TOKEN)).await.unwrap();
+            let port = running_port(app.handle()).await.unwrap();
+            let (status, reply) = post(port, "/api/set_current_scene", Some(TOKEN), r#"{"sceneName": "BRB"}"#)
+                .await
+                .unwrap();
+            assert_eq!(status, 200);
+            assert_eq!(reply, json!({"ok": true, "data": null}));
+
+            let (status, reply) = post(port, "/api/set_current_scene", Some(TOKEN), "{}").await.unwrap();
+            assert_eq!(status, 400);
+            assert_eq!(reply["ok"], false);
+            assert_eq!(reply["error"], "Missing string field 'sceneName'");
+        });
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![("SetCurrentProgramScene".to_string(), json!({"sceneName": "BRB"}))]
+        );
+    }
+
+    #[test]
+    fn starts_and_stops_when_the_setting_toggles() {
+        let (app, _) = mock_app();
+        tauri::async_runtime::block_on(async {
+            let handle = app.handle();
+            run(handle, &config(false, TOKEN)).await.unwrap();
+            assert_eq!(running_port(handle).await, None);
+
+            run(handle, &config(true, TOKEN)).await.unwrap();
+            let port = running_port(handle).await.unwrap();
+            let (status, _) = post(port, "/api/get_obs_state", Some(TOKEN), "").await.unwrap();
+            assert_eq!(status, 200);
+
+            // A new token restarts the server; the old one stops working.
+            run(handle, &config(true, "rotated-token")).await.unwrap();
+            let port = running_port(handle).await.unwrap();
+            assert_eq!(post(port, "/api/get_obs_state", Some(TOKEN), "").await.unwrap().0, 401);
+            assert_eq!(post(port, "/api/get_obs_state", Some("rotated-token"), "").await.unwrap().0, 200);
+
+            run(handle, &config(false, "rotated-token")).await.unwrap();
+            assert_eq!(running_port(handle).await, None);
+            // The listener closes once the serve task sees the shutdown.
+            let mut closed = false;
+            for _ in 0..50 {
+                if TcpStream::connect(("127.0.0.1", port)).await.is_err() {
+                    closed = true;
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            assert!(closed, "port {} still accepting after disable", port);
+        });
+    }
+}
@@ -0,0 +1,208 @@
+use crate::audio::AudioDevice;
+use crate::obs_state::InputInfo;
+use std::collections::HashMap;
+
+/// Canonical form of a WASAPI endpoint id. OBS, the MMDevice API and the
+/// SWD device path all wrap the same `{guid}` in different prefixes
+/// (`{0.0.1.00000000}.`, `SWD\MMDEVAPI\`) and casing.
+pub fn normalize_id(id: &str) -> String {
+    let id = id.trim().to_lowercase();
+    let id = id.strip_prefix(r"swd\mmdevapi\").unwrap_or(&id);
+    let guid = match id.strip_prefix("{0.0.") {
+        Some(rest) => rest.split_once("}.").map(|(_, guid)| guid).unwrap_or(id),
+        None => id,
+    };
+    guid.to_string()
+}
+
+pub fn ids_match(a: &str, b: &str) -> bool {
+    !a.is_empty() && !b.is_empty() && normalize_id(a) == normalize_id(b)
+}
+
+/// "input" or "output" for WASAPI/Pulse/CoreAudio capture kinds.
+pub fn device_flow(kind: &str) -> Option<&'static str> {
+    if kind.contains("input_capture") {
+        Some("input")
+    } else if kind.contains("output_capture") {
+        Some("output")
+    } else {
+        None
+    }
+}
+
+/// OBS's placeholder ids that follow the system default. We don't enumerate
+/// the communications role separately, so it resolves to the default too.
+pub fn is_default_alias(device_id: &str) -> bool {
+    matches!(device_id, "" | "default" | "communications")
+}
+
+pub fn resolve_device<'a>(kind: &str, device_id: &str, devices: &'a [AudioDevice]) -> Option<&'a AudioDevice> {
+    let flow = device_flow(kind)?;
+    let mut candidates = devices.iter().filter(|d| d.device_type == flow);
+    if is_default_alias(device_id) {
+        candidates.find(|d| d.is_default)
+    } else {
+        let wanted = normalize_id(device_id);
+        candidates.find(|d| normalize_id(&d.id) == wanted)
+    }
+}
+
+/// The enumerated device an OBS audio input is actually capturing. `None`
+/// for non-audio inputs and for ids of hardware that is no longer present.
+pub fn resolve_input_device<'a>(input: &InputInfo, devices: &'a [AudioDevice]) -> Option<&'a AudioDevice> {
+    resolve_device(&input.kind, &input.device_id, devices)
+}
+
+/// Looks up a per-device map (e.g. live metrics) by any spelling of the id.
+pub fn lookup_by_id<'a, V>(map: &'a HashMap<String, V>, device_id: &str) -> Option<&'a V> {
+    map.get(device_id).or_else(|| {
+        let wanted = normalize_id(device_id);
+        map.iter().find(|(k, _)| normalize_id(k) == wanted).map(|(_, v)| v)
+    })
+}
+
+fn name_score(query: &str, device: &AudioDevice) -> u32 {
+    let q = query.trim().to_lowercase();
+    let name = device.name.to_lowercase();
+    if q.is_empty() {
+        return 0;
+    }
+    if ids_match(&q, &device.id) {
+        return 100;
+    }
+    if name == q {
+        return 90;
+    }
+    if name.starts_with(&q) {
+        return 70;
+    }
+    if name.contains(&q) {
+        return 60;
+    }
+    let words: Vec<&str> = q.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    if words.is_empty() {
+        return 0;
+    }
+    let hits = words.iter().filter(|w| name.contains(*w)).count();
+    (hits * 40 / words.len()) as u32
+}
+
+/// Best device for a free-form name or id, as the AI tends to produce. Ties
+/// between identically named devices go to the system default; otherwise an
+/// ambiguous query resolves to nothing rather than guessing.
+pub fn find_device_by_name<'a>(query: &str, devices: &'a [AudioDevice]) -> Option<&'a AudioDevice> {
+    let scored: Vec<(u32, &AudioDevice)> = devices
+        .iter()
+        .map(|d| (name_score(query, d), d))
+        .filter(|(score, _)| *score >= 20)
+        .collect();
+    let best = scored.iter().map(|(s, _)| *s).max()?;
+    let top: Vec<&AudioDevice> = scored.iter().filter(|(s, _)| *s == best).map(|(_, d)| *d).collect();
+    match top.as_slice() {
+        [only] => Some(only),
+        many => many.iter().find(|d| d.is_default).copied(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USB_A: &str = "{0.0.1.00000000}.{aaaaaaaa-0000-0000-0000-000000000001}";
+    const USB_B: &str = "{0.0.1.00000000}.{aaaaaaaa-0000-0000-0000-000000000002}";
+    const SPEAKERS: &str = "{0.0.0.00000000}.{bbbbbbbb-0000-0000-0000-000000000003}";
+
+    fn device(id: &str, name: &str, device_type: &str, is_default: bool) -> AudioDevice {
+        AudioDevice {
+            id: id.to_string(),
+            name: name.to_string(),
+            device_type: device_type.to_string(),
+            is_default,
+        }
+    }
+
+    /// Two identical USB mics, the second one the default, plus speakers.
+    fn devices() -> Vec<AudioDevice> {
+        vec![
+            device(USB_A, "Microphone (USB Audio Device)", "input", false),
+            device(USB_B, "Microphone (USB Audio Device)", "input", true),
+            device(SPEAKERS, "Speakers (Realtek Audio)", "output", true),
+        ]
+    }
+
+    fn input(kind: &str, device_id: &str) -> InputInfo {
+        let mut input = InputInfo::placeholder("Mic", kind);
+        input.device_id = device_id.to_string();
+        input
+    }
+
+    #[test]
+    fn normalizes_endpoint_prefixes_and_case() {
+        let guid = "{aaaaaaaa-0000-0000-0000-000000000001}";
+        assert_eq!(normalize_id(USB_A), guid);
+        assert_eq!(normalize_id(&format!(r"SWD\MMDEVAPI\{}", USB_A.to_uppercase())), guid);
+        assert!(ids_match(guid, USB_A));
+        assert!(!ids_match("", ""));
+    }
+
+    #[test]
+    fn default_aliases_resolve_to_the_default_of_the_right_flow() {
+        let devices = devices();
+        for alias in ["default", "communications", ""] {
+            let mic = resolve_input_device(&input("wasapi_input_capture", alias), &devices).unwrap();
+            assert_eq!(mic.id, USB_B);
+            let out = resolve_input_device(&input("wasapi_output_capture", alias), &devices).unwrap();
+            assert_eq!(out.id, SPEAKERS);
+        }
+    }
+
+    #[test]
+    fn duplicate_names_resolve_by_id() {
+        let devices = devices();
+        let a = resolve_input_device(&input("wasapi_input_capture", USB_A), &devices).unwrap();
+        assert_eq!(a.id, USB_A);
+        let b = resolve_input_device(&input("wasapi_input_capture", USB_B), &devices).unwrap();
+        assert_eq!(b.id, USB_B);
+    }
+
+    #[test]
+    fn duplicate_names_prefer_the_default_or_nothing() {
+        let devices = devices();
+        let found = find_device_by_name("USB Audio", &devices).unwrap();
+        assert_eq!(found.id, USB_B);
+
+        let no_default: Vec<AudioDevice> = devices
+            .into_iter()
+            .map(|mut d| {
+                d.is_default = false;
+                d
+            })
+            .collect();
+        assert!(find_device_by_name("USB Audio", &no_default).is_none());
+        assert_eq!(find_device_by_name(USB_A, &no_default).unwrap().id, USB_A);
+    }
+
+    #[test]
+    fn stale_ids_from_unplugged_hardware_resolve_to_nothing() {
+        let devices = devices();
+        let stale = "{0.0.1.00000000}.{cccccccc-0000-0000-0000-000000000009}";
+        assert!(resolve_input_device(&input("wasapi_input_capture", stale), &devices).is_none());
+        assert!(find_device_by_name(stale, &devices).is_none());
+    }
+
+    #[test]
+    fn non_audio_inputs_and_wrong_flow_do_not_resolve() {
+        let devices = devices();
+        assert!(resolve_input_device(&input("browser_source", USB_A), &devices).is_none());
+        assert!(resolve_input_device(&input("wasapi_output_capture", USB_A), &devices).is_none());
+    }
+
+    #[test]
+    fn lookup_by_id_accepts_any_spelling() {
+        let mut map = HashMap::new();
+        map.insert(USB_A.to_string(), 1);
+        assert_eq!(lookup_by_id(&map, USB_A), Some(&1));
+        assert_eq!(lookup_by_id(&map, &format!(r"SWD\MMDEVAPI\{}", USB_A)), Some(&1));
+        assert_eq!(lookup_by_id(&map, USB_B), None);
+    }
+}
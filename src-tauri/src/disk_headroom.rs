@@ -0,0 +1,303 @@
+use crate::obs_config;
+use crate::obs_state::{RecordSettings, SharedObsState};
+use crate::obs_websocket::ObsHandle;
+use crate::settings::SharedSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{Emitter, Manager};
+
+/// Used when the encoder targets quality (CQP/CRF/ICQ) instead of a bitrate.
+const QUALITY_MODE_FALLBACK_KBPS: u32 = 30_000;
+const QUALITY_MODES: &[&str] = &["CQP", "CRF", "ICQ", "QVBR", "LOSSLESS"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingHeadroom {
+    pub directory: String,
+    pub free_bytes: u64,
+    pub bitrate_kbps: u32,
+    pub bitrate_estimated: bool,
+    pub seconds_remaining: f64,
+}
+
+async fn profile_param(conn: &ObsHandle, category: &str, name: &str) -> Option<String> {
+    let resp = conn
+        .send_request(
+            "GetProfileParameter",
+            Some(json!({"parameterCategory": category, "parameterName": name})),
+        )
+        .await
+        .ok()?;
+    resp["parameterValue"]
+        .as_str()
+        .or_else(|| resp["defaultParameterValue"].as_str())
+        .map(String::from)
+}
+
+async fn profile_kbps(conn: &ObsHandle, category: &str, name: &str, default: u32) -> u32 {
+    profile_param(conn, category, name)
+        .await
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Fills in the bitrate fields of `settings` from the current profile's
+/// output configuration. Advanced mode keeps encoder settings in JSON files
+/// next to basic.ini, so those are read from disk.
+pub async fn fetch_encoder_settings(conn: &ObsHandle, settings: &mut RecordSettings) {
+    let mode = profile_param(conn, "Output", "Mode").await.unwrap_or_default();
+
+    if mode != "Advanced" {
+        let quality = profile_param(conn, "SimpleOutput", "RecQuality").await.unwrap_or_default();
+        if quality == "Stream" {
+            settings.rate_control = "CBR".into();
+            settings.video_bitrate_kbps = profile_kbps(conn, "SimpleOutput", "VBitrate", 2500).await;
+        } else {
+            settings.rate_control = "CQP".into();
+            settings.video_bitrate_kbps = 0;
+        }
+        settings.audio_bitrate_kbps = profile_kbps(conn, "SimpleOutput", "ABitrate", 160).await;
+        return;
+    }
+
+    if profile_param(conn, "AdvOut", "RecType").await.as_deref() == Some("FFmpeg") {
+        settings.rate_control = "CBR".into();
+        settings.video_bitrate_kbps = profile_kbps(conn, "AdvOut", "FFVBitrate", 2500).await;
+        settings.audio_bitrate_kbps = profile_kbps(conn, "AdvOut", "FFABitrate", 160).await;
+        return;
+    }
+
+    let profile = conn
+        .send_request("GetProfileList", None)
+        .await
+        .ok()
+        .and_then(|v| v["currentProfileName"].as_str().map(String::from))
+        .unwrap_or_default();
+    let rec_encoder = profile_param(conn, "AdvOut", "RecEncoder").await.unwrap_or_default();
+    let file = if rec_encoder == "none" { "streamEncoder.json" } else { "recordEncoder.json" };
+    let encoder = obs_config::read_profile_json(&profile, file).unwrap_or_default();
+    settings.rate_control = encoder["rate_control"].as_str().unwrap_or("CBR").to_uppercase();
+    settings.video_bitrate_kbps = encoder["bitrate"].as_u64().unwrap_or(2500) as u32;
+
+    let tracks = profile_kbps(conn, "AdvOut", "RecTracks", 1).await;
+    let mut audio = 0;
+    for track in (1..=6).filter(|n| tracks & (1 << (n - 1)) != 0) {
+        audio += profile_kbps(conn, "AdvOut", &format!("Track{}Bitrate", track), 160).await;
+    }
+    settings.audio_bitrate_kbps = audio;
+}
+
+/// The video encoder an output is set to use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfiguredEncoder {
+    /// OBS encoder id, e.g. "obs_x264" or "jim_nvenc" (simple mode: "x264", "nvenc").
+    pub id: String,
+    /// Preset for x264 only.
+    pub x264_preset: Option<String>,
+}
+
+impl ConfiguredEncoder {
+    /// "cpu", "nvidia", "amd", "intel", "apple", or "unknown".
+    pub fn family(&self) -> &'static str {
+        let id = self.id.to_lowercase();
+        if id.contains("x264") {
+            "cpu"
+        } else if id.contains("nvenc") {
+            "nvidia"
+        } else if id.contains("amf") || id.starts_with("amd") {
+            "amd"
+        } else if id.contains("qsv") {
+            "intel"
+        } else if id.contains("apple") || id.contains("videotoolbox") {
+            "apple"
+        } else {
+            "unknown"
+        }
+    }
+}
+
+/// Reads the video encoder for streaming, or for recording when `record`
+/// is set (which may defer to the stream encoder).
+pub async fn fetch_encoder(conn: &ObsHandle, record: bool) -> Option<ConfiguredEncoder> {
+    let mode = profile_param(conn, "Output", "Mode").await.unwrap_or_default();
+    if mode != "Advanced" {
+        let mut key = "StreamEncoder";
+        if record {
+            let quality = profile_param(conn, "SimpleOutput", "RecQuality").await.unwrap_or_default();
+            if quality != "Stream" {
+                key = "RecEncoder";
+            }
+        }
+        let id = profile_param(conn, "SimpleOutput", key).await?;
+        let x264_preset = if id.contains("x264") {
+            profile_param(conn, "SimpleOutput", "Preset").await
+        } else {
+            None
+        };
+        return Some(ConfiguredEncoder { id, x264_preset });
+    }
+
+    let mut key = "Encoder";
+    let mut file = "streamEncoder.json";
+    if record && profile_param(conn, "AdvOut", "RecEncoder").await.as_deref().unwrap_or("none") != "none" {
+        key = "RecEncoder";
+        file = "recordEncoder.json";
+    }
+    let id = profile_param(conn, "AdvOut", key).await?;
+    let x264_preset = if id.contains("x264") {
+        let profile = conn
+            .send_request("GetProfileList", None)
+            .await
+            .ok()
+            .and_then(|v| v["currentProfileName"].as_str().map(String::from))
+            .unwrap_or_default();
+        let encoder = obs_config::read_profile_json(&profile, file).unwrap_or_default();
+        // OBS leaves the file out of the default "veryfast".
+        Some(encoder["preset"].as_str().unwrap_or("veryfast").to_string())
+    } else {
+        None
+    };
+    Some(ConfiguredEncoder { id, x264_preset })
+}
+
+/// Configured streaming bitrate, video plus audio, from the current profile.
+pub async fn fetch_stream_bitrate_kbps(conn: &ObsHandle) -> u32 {
+    let mode = profile_param(conn, "Output", "Mode").await.unwrap_or_default();
+    if mode != "Advanced" {
+        return profile_kbps(conn, "SimpleOutput", "VBitrate", 2500).await
+            + profile_kbps(conn, "SimpleOutput", "ABitrate", 160).await;
+    }
+
+    let profile = conn
+        .send_request("GetProfileList", None)
+        .await
+        .ok()
+        .and_then(|v| v["currentProfileName"].as_str().map(String::from))
+        .unwrap_or_default();
+    let encoder = obs_config::read_profile_json(&profile, "streamEncoder.json").unwrap_or_default();
+    let video = encoder["bitrate"].as_u64().unwrap_or(2500) as u32;
+    let track = profile_kbps(conn, "AdvOut", "TrackIndex", 1).await;
+    video + profile_kbps(conn, "AdvOut", &format!("Track{}Bitrate", track), 160).await
+}
+
+/// Total recording bitrate and whether it is a guess rather than a setting.
+pub fn estimate_bitrate_kbps(settings: &RecordSettings) -> (u32, bool) {
+    let quality_mode = QUALITY_MODES.contains(&settings.rate_control.as_str());
+    if quality_mode || settings.video_bitrate_kbps == 0 {
+        (QUALITY_MODE_FALLBACK_KBPS, true)
+    } else {
+        (settings.video_bitrate_kbps + settings.audio_bitrate_kbps, false)
+    }
+}
+
+#[cfg(windows)]
+pub fn free_space(dir: &str) -> Option<u64> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = dir.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(wide.as_ptr()), Some(&mut available), None, None) }.ok()?;
+    Some(available)
+}
+
+#[cfg(not(windows))]
+pub fn free_space(dir: &str) -> Option<u64> {
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .iter()
+        .filter(|d| std::path::Path::new(dir).starts_with(d.mount_point()))
+        .max_by_key(|d| d.mount_point().as_os_str().len())
+        .map(|d| d.available_space())
+}
+
+pub fn measure(settings: &RecordSettings) -> Option<RecordingHeadroom> {
+    let dir = &settings.record_directory;
+    if dir.is_empty() {
+        return None;
+    }
+    let free_bytes = free_space(dir)?;
+    let (bitrate_kbps, bitrate_estimated) = estimate_bitrate_kbps(settings);
+    let bytes_per_second = bitrate_kbps as f64 * 1000.0 / 8.0;
+    Some(RecordingHeadroom {
+        directory: dir.clone(),
+        free_bytes,
+        bitrate_kbps,
+        bitrate_estimated,
+        seconds_remaining: free_bytes as f64 / bytes_per_second,
+    })
+}
+
+/// How often the recording drive is checked while recording.
+const CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Emits `recorder://low-disk-space` once each time the estimated recording
+/// time left crosses below the configured threshold; returns the new
+/// warned flag.
+async fn check_live(state: &SharedObsState, app: &tauri::AppHandle, warned: bool) -> bool {
+    let settings = {
+        let s = state.read().await;
+        if !s.record_status.active {
+            return false;
+        }
+        s.record_settings.clone()
+    };
+    let threshold_minutes = match app.try_state::<SharedSettings>() {
+        Some(s) => s.read().await.recording.low_disk_warning_minutes,
+        None => return warned,
+    };
+    if threshold_minutes == 0 {
+        return false;
+    }
+    let Some(headroom) = tokio::task::spawn_blocking(move || measure(&settings)).await.ok().flatten() else {
+        return warned;
+    };
+
+    let low = headroom.seconds_remaining < threshold_minutes as f64 * 60.0;
+    if low && !warned {
+        log::warn!(
+            "Recording drive low: {:.0} min left at {} kbps",
+            headroom.seconds_remaining / 60.0,
+            headroom.bitrate_kbps
+        );
+        let _ = app.emit(
+            "recorder://low-disk-space",
+            json!({
+                "directory": headroom.directory,
+                "freeBytes": headroom.free_bytes,
+                "bitrateKbps": headroom.bitrate_kbps,
+                "bitrateEstimated": headroom.bitrate_estimated,
+                "secondsRemaining": headroom.seconds_remaining,
+                "thresholdMinutes": threshold_minutes,
+            }),
+        );
+    }
+    low
+}
+
+/// Checks the recording drive on its own timer, so a slow disk query never
+/// holds up the websocket reader.
+pub fn start_watcher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<SharedObsState>().inner().clone();
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut warned = false;
+        loop {
+            interval.tick().await;
+            warned = check_live(&state, &app, warned).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_recording_headroom(
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<RecordingHeadroom, String> {
+    let settings = obs_state.read().await.record_settings.clone();
+    tokio::task::spawn_blocking(move || measure(&settings))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .ok_or_else(|| "Could not read free space for the record directory".to_string())
+}
@@ -1,21 +1,57 @@
+use crate::ai_actions::{SharedUndoStack, UndoEntry};
 use crate::audio;
-use crate::audio_monitor::SharedAudioMetrics;
-use crate::commands::SharedObsConnection;
+use crate::audio_monitor::{SharedAudioMetrics, SharedMetricsNotify};
+use crate::obs_health::SharedObsHealth;
 use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::settings::{self, SharedSettings};
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
-use tauri::Emitter;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
 use tokio::sync::RwLock;
 
+const FADE_STEP_MS: u64 = 25;
+/// Volumes closer than this are treated as equal; OBS round-trips dB values
+/// through a multiplier, so exact comparisons never match.
+const TOLERANCE_DB: f64 = 0.05;
+/// How long a rename's config change waits before it is written, so a burst
+/// of renames is saved once.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(1000);
+/// How often an enabled config whose inputs aren't in OBS yet is re-checked.
+const RESOLVE_RETRY: Duration = Duration::from_secs(5);
+
+/// How ducking lowers the targets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuckingMode {
+    /// Ramps the targets' input volume from the trigger meters.
+    #[default]
+    Volume,
+    /// Leaves the faders alone and lets a sidechained compressor_filter on
+    /// each target do the ducking inside OBS.
+    Sidechain,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DuckingConfig {
     pub enabled: bool,
+    #[serde(default)]
+    pub mode: DuckingMode,
     pub trigger_source: String,
     pub target_source: String,
+    /// Further triggers; any one of them speaking ducks every target.
+    #[serde(default)]
+    pub trigger_sources: Vec<String>,
+    /// Further targets, each ramped independently from its own volume.
+    #[serde(default)]
+    pub target_sources: Vec<String>,
     pub threshold_db: f64,
+    #[serde(default)]
+    pub close_threshold_db: Option<f64>,
     pub duck_amount_db: f64,
     pub attack_ms: u64,
     pub hold_ms: u64,
@@ -26,9 +62,13 @@ impl Default for DuckingConfig {
     fn default() -> Self {
         Self {
             enabled: false,
+            mode: DuckingMode::Volume,
             trigger_source: String::new(),
             target_source: String::new(),
+            trigger_sources: Vec::new(),
+            target_sources: Vec::new(),
             threshold_db: -40.0,
+            close_threshold_db: None,
             duck_amount_db: -14.0,
             attack_ms: 50,
             hold_ms: 500,
@@ -37,6 +77,37 @@ impl Default for DuckingConfig {
     }
 }
 
+const DEFAULT_HYSTERESIS_DB: f64 = 6.0;
+
+impl DuckingConfig {
+    /// The level speech has to fall below before the hold timer starts.
+    /// Never above the open threshold, so the two always form a band.
+    fn close_threshold(&self) -> f64 {
+        self.close_threshold_db
+            .unwrap_or(self.threshold_db - DEFAULT_HYSTERESIS_DB)
+            .min(self.threshold_db)
+    }
+
+    /// The single trigger field plus the list, without blanks or repeats.
+    pub fn triggers(&self) -> Vec<String> {
+        merged(&self.trigger_source, &self.trigger_sources)
+    }
+
+    pub fn targets(&self) -> Vec<String> {
+        merged(&self.target_source, &self.target_sources)
+    }
+}
+
+fn merged(single: &str, list: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in std::iter::once(single).chain(list.iter().map(String::as_str)) {
+        if !name.is_empty() && !names.iter().any(|n| n == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum DuckingStatus {
@@ -50,72 +121,271 @@ pub enum DuckingStatus {
 
 pub type SharedDuckingConfig = Arc<RwLock<DuckingConfig>>;
 
+/// Trigger-level state machine. Speech opens the duck above `threshold_db`,
+/// but only dropping below the close threshold starts the hold timer, so
+/// levels hovering around a single threshold don't flap.
+pub struct DuckMachine {
+    pub status: DuckingStatus,
+    entered: Instant,
+}
+
+impl DuckMachine {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            status: DuckingStatus::Idle,
+            entered: now,
+        }
+    }
+
+    fn enter(&mut self, status: DuckingStatus, now: Instant) -> Option<DuckingStatus> {
+        self.status = status;
+        self.entered = now;
+        Some(status)
+    }
+
+    /// Advances the machine with the current trigger level; returns the new
+    /// status when it changed.
+    pub fn step(&mut self, level_db: f64, now: Instant, config: &DuckingConfig) -> Option<DuckingStatus> {
+        let elapsed = now.duration_since(self.entered).as_millis() as u64;
+        let open = level_db > config.threshold_db;
+        let sustained = level_db >= config.close_threshold();
+        match self.status {
+            DuckingStatus::Disabled | DuckingStatus::Idle if open => {
+                self.enter(DuckingStatus::Attacking, now)
+            }
+            DuckingStatus::Disabled => self.enter(DuckingStatus::Idle, now),
+            // Speech that stops before the duck is fully down releases
+            // straight away instead of holding a half-finished duck.
+            DuckingStatus::Attacking if !sustained => self.enter(DuckingStatus::Releasing, now),
+            DuckingStatus::Attacking if elapsed >= config.attack_ms => {
+                self.enter(DuckingStatus::Ducking, now)
+            }
+            DuckingStatus::Ducking if !sustained => self.enter(DuckingStatus::Holding, now),
+            DuckingStatus::Holding if sustained => self.enter(DuckingStatus::Ducking, now),
+            DuckingStatus::Holding if elapsed >= config.hold_ms => {
+                self.enter(DuckingStatus::Releasing, now)
+            }
+            DuckingStatus::Releasing if open => self.enter(DuckingStatus::Attacking, now),
+            DuckingStatus::Releasing if elapsed >= config.release_ms => {
+                self.enter(DuckingStatus::Idle, now)
+            }
+            _ => None,
+        }
+    }
+
+    /// When the machine needs to be re-evaluated even without a new meter
+    /// frame (end of attack, hold or release).
+    pub fn deadline(&self, config: &DuckingConfig) -> Option<Instant> {
+        let ms = match self.status {
+            DuckingStatus::Attacking => config.attack_ms,
+            DuckingStatus::Holding => config.hold_ms,
+            DuckingStatus::Releasing => config.release_ms,
+            _ => return None,
+        };
+        Some(self.entered + Duration::from_millis(ms))
+    }
+}
+
+/// A volume ramp precomputed as (offset, dB) steps.
+struct Fade {
+    started: Instant,
+    steps: Vec<(Duration, f64)>,
+    next: usize,
+}
+
+impl Fade {
+    fn new(from_db: f64, to_db: f64, duration_ms: u64, now: Instant) -> Self {
+        let count = (duration_ms / FADE_STEP_MS).max(1);
+        let steps = (1..=count)
+            .map(|i| {
+                let t = i as f64 / count as f64;
+                (
+                    Duration::from_millis(duration_ms * i / count),
+                    from_db + (to_db - from_db) * t,
+                )
+            })
+            .collect();
+        Self {
+            started: now,
+            steps,
+            next: 0,
+        }
+    }
+
+    /// Latest step that is due; skipped intermediate steps are dropped.
+    fn due(&mut self, now: Instant) -> Option<f64> {
+        let elapsed = now.duration_since(self.started);
+        let mut value = None;
+        while self.next < self.steps.len() && self.steps[self.next].0 <= elapsed {
+            value = Some(self.steps[self.next].1);
+            self.next += 1;
+        }
+        value
+    }
+
+    fn next_at(&self) -> Option<Instant> {
+        self.steps.get(self.next).map(|(offset, _)| self.started + *offset)
+    }
+}
+
+/// Envelope state for one ducked input.
+#[derive(Default)]
+struct TargetDuck {
+    /// Volume before ducking started; restored on release.
+    original_db: Option<f64>,
+    current_db: Option<f64>,
+    fade: Option<Fade>,
+    last_self_set: Option<Instant>,
+}
+
+impl TargetDuck {
+    /// Puts the input back at its original volume and forgets the envelope.
+    async fn restore(&mut self, conn: &ObsHandle, name: &str) {
+        self.fade = None;
+        if let Some(orig) = self.original_db.take() {
+            if !self.current_db.is_some_and(|db| (db - orig).abs() <= TOLERANCE_DB) {
+                apply_volume(conn, name, orig, &mut self.last_self_set).await;
+            }
+        }
+        self.current_db = None;
+    }
+}
+
+type Targets = std::collections::HashMap<String, TargetDuck>;
+
+async fn restore_all(conn: &ObsHandle, targets: &mut Targets) {
+    for (name, duck) in targets.iter_mut() {
+        duck.restore(conn, name).await;
+    }
+    targets.clear();
+}
+
 pub async fn start_ducking_loop(
     app_handle: tauri::AppHandle,
-    obs_conn: SharedObsConnection,
+    obs_conn: ObsHandle,
+    obs_health: SharedObsHealth,
     obs_state: SharedObsState,
     audio_metrics: SharedAudioMetrics,
+    metrics_notify: SharedMetricsNotify,
     ducking_config: SharedDuckingConfig,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
 ) {
-    let mut interval = tokio::time::interval(std::time::Duration::from_millis(50));
-    let mut status = DuckingStatus::Disabled;
-    let mut original_volume_db: Option<f64> = None;
-    let mut state_entered_at = Instant::now();
-    let mut last_self_set: Option<Instant> = None;
+    let mut machine = DuckMachine::new(Instant::now());
+    machine.status = DuckingStatus::Disabled;
+    let mut targets: Targets = Targets::new();
+    let mut wake_at: Option<Instant> = None;
 
     loop {
-        interval.tick().await;
+        // Idle periods sleep until a trigger crosses the threshold (the gate
+        // set below); fades and hold/release timers add their own deadline.
+        match wake_at {
+            Some(at) => {
+                tokio::select! {
+                    _ = metrics_notify.notified() => {}
+                    _ = tokio::time::sleep_until(at.into()) => {}
+                    _ = shutdown.wait_for(|stop| *stop) => {}
+                }
+            }
+            None => {
+                tokio::select! {
+                    _ = metrics_notify.notified() => {}
+                    _ = shutdown.wait_for(|stop| *stop) => {}
+                }
+            }
+        }
+        wake_at = None;
+
+        // Never leave a target stuck at the ducked level when the app exits.
+        if *shutdown.borrow() {
+            restore_all(&obs_conn, &mut targets).await;
+            return;
+        }
+
+        // Queued volume changes would only pile up behind a hung OBS.
+        if obs_health.lock().map(|h| h.is_degraded()).unwrap_or(false) {
+            wake_at = Some(Instant::now() + RESOLVE_RETRY);
+            continue;
+        }
 
         let config = ducking_config.read().await.clone();
+        let trigger_names = config.triggers();
+        let target_names = config.targets();
 
         if !config.enabled
-            || config.trigger_source.is_empty()
-            || config.target_source.is_empty()
+            || config.mode != DuckingMode::Volume
+            || trigger_names.is_empty()
+            || target_names.is_empty()
         {
-            if status != DuckingStatus::Disabled {
-                if let Some(orig) = original_volume_db.take() {
-                    restore_volume(&obs_conn, &config.target_source, orig, &mut last_self_set)
-                        .await;
-                }
-                status = DuckingStatus::Disabled;
-                emit_status(&app_handle, status);
+            if machine.status != DuckingStatus::Disabled {
+                restore_all(&obs_conn, &mut targets).await;
+                machine.status = DuckingStatus::Disabled;
+                emit_status(&app_handle, machine.status, &[]);
             }
+            // Nothing to watch until the config changes and wakes us.
+            metrics_notify.gate(Vec::new(), 0.0);
             continue;
         }
 
-        let trigger_device_id = {
+        // Targets dropped from the config (or from OBS) go back to normal.
+        let (trigger_device_ids, live_targets) = {
             let state = obs_state.read().await;
-            match state.inputs.get(&config.trigger_source) {
-                Some(input) => {
-                    let did = input.device_id.clone();
-                    if did == "default" || did.is_empty() {
-                        resolve_default_input_device()
-                    } else {
-                        Some(did)
-                    }
-                }
-                None => None,
-            }
+            let devices: Vec<String> = trigger_names
+                .iter()
+                .filter_map(|name| state.inputs.get(name).map(|i| i.device_id.clone()))
+                .collect();
+            let live: Vec<String> = target_names
+                .iter()
+                .filter(|name| state.inputs.contains_key(*name))
+                .cloned()
+                .collect();
+            (devices, live)
         };
+        let stale: Vec<String> = targets
+            .keys()
+            .filter(|name| !live_targets.contains(name))
+            .cloned()
+            .collect();
+        for name in stale {
+            if let Some(mut duck) = targets.remove(&name) {
+                duck.restore(&obs_conn, &name).await;
+            }
+        }
 
-        let trigger_device_id = match trigger_device_id {
-            Some(id) => id,
-            None => {
-                if status != DuckingStatus::Idle {
-                    status = DuckingStatus::Idle;
-                    emit_status(&app_handle, status);
+        // Names are re-read from ObsState every cycle; renames are folded into
+        // the config by on_input_renamed via the rename cascade.
+        let mut default_device: Option<Option<String>> = None;
+        let trigger_device_ids: Vec<String> = trigger_device_ids
+            .into_iter()
+            .filter_map(|did| {
+                if did == "default" || did.is_empty() {
+                    default_device
+                        .get_or_insert_with(resolve_default_input_device)
+                        .clone()
+                } else {
+                    Some(did)
                 }
-                continue;
+            })
+            .collect();
+
+        if trigger_device_ids.is_empty() || live_targets.is_empty() {
+            if machine.status != DuckingStatus::Idle {
+                restore_all(&obs_conn, &mut targets).await;
+                machine = DuckMachine::new(Instant::now());
+                emit_status(&app_handle, machine.status, &[]);
             }
-        };
+            // The inputs may show up once OBS connects or they are created.
+            metrics_notify.gate(Vec::new(), 0.0);
+            wake_at = Some(Instant::now() + RESOLVE_RETRY);
+            continue;
+        }
 
+        // The loudest trigger drives the shared envelope.
         let peak = {
             let metrics = audio_metrics.read().await;
-            metrics
-                .devices
-                .get(&trigger_device_id)
-                .map(|m| m.peak)
-                .unwrap_or(0.0)
+            trigger_device_ids
+                .iter()
+                .filter_map(|id| metrics.devices.get(id).map(|m| m.peak))
+                .fold(0.0_f32, f32::max)
         };
 
         let peak_db = if peak > 0.0001 {
@@ -124,87 +394,291 @@ pub async fn start_ducking_loop(
             -100.0
         };
 
-        let voice_active = peak_db > config.threshold_db;
-        let elapsed = state_entered_at.elapsed().as_millis() as u64;
-
-        let new_status = match status {
-            DuckingStatus::Disabled => {
-                state_entered_at = Instant::now();
-                DuckingStatus::Idle
-            }
-            DuckingStatus::Idle => {
-                if voice_active {
-                    state_entered_at = Instant::now();
-                    if original_volume_db.is_none() {
-                        original_volume_db = get_current_volume(&obs_conn, &obs_state, &config.target_source, &last_self_set).await;
+        let now = Instant::now();
+        if let Some(new_status) = machine.step(peak_db, now, &config) {
+            match new_status {
+                DuckingStatus::Attacking => {
+                    for name in &live_targets {
+                        let duck = targets.entry(name.clone()).or_default();
+                        if duck.original_db.is_none() {
+                            duck.original_db =
+                                get_current_volume(&obs_conn, &obs_state, name, &duck.last_self_set)
+                                    .await;
+                        }
+                        duck.fade = duck.original_db.map(|orig| {
+                            let from = duck.current_db.unwrap_or(orig);
+                            Fade::new(from, orig + config.duck_amount_db, config.attack_ms, now)
+                        });
                     }
-                    DuckingStatus::Attacking
-                } else {
-                    DuckingStatus::Idle
                 }
-            }
-            DuckingStatus::Attacking => {
-                if elapsed >= config.attack_ms {
-                    if let Some(orig) = original_volume_db {
-                        let ducked = orig + config.duck_amount_db;
-                        apply_volume(&obs_conn, &config.target_source, ducked, &mut last_self_set)
-                            .await;
+                DuckingStatus::Releasing => {
+                    for duck in targets.values_mut() {
+                        duck.fade = duck.original_db.map(|orig| {
+                            let from = duck.current_db.unwrap_or(orig + config.duck_amount_db);
+                            Fade::new(from, orig, config.release_ms, now)
+                        });
                     }
-                    state_entered_at = Instant::now();
-                    DuckingStatus::Ducking
-                } else if !voice_active {
-                    original_volume_db = None;
-                    state_entered_at = Instant::now();
-                    DuckingStatus::Idle
-                } else {
-                    DuckingStatus::Attacking
                 }
+                DuckingStatus::Idle => restore_all(&obs_conn, &mut targets).await,
+                _ => {}
             }
-            DuckingStatus::Ducking => {
-                if voice_active {
-                    DuckingStatus::Ducking
-                } else {
-                    state_entered_at = Instant::now();
-                    DuckingStatus::Holding
-                }
-            }
-            DuckingStatus::Holding => {
-                if voice_active {
-                    state_entered_at = Instant::now();
-                    DuckingStatus::Ducking
-                } else if elapsed >= config.hold_ms {
-                    state_entered_at = Instant::now();
-                    DuckingStatus::Releasing
-                } else {
-                    DuckingStatus::Holding
-                }
+            emit_status(&app_handle, new_status, &live_targets);
+        }
+
+        let now = Instant::now();
+        for (name, duck) in targets.iter_mut() {
+            let Some(f) = duck.fade.as_mut() else { continue };
+            if let Some(db) = f.due(now) {
+                apply_volume(&obs_conn, name, db, &mut duck.last_self_set).await;
+                duck.current_db = Some(db);
             }
-            DuckingStatus::Releasing => {
-                if voice_active {
-                    state_entered_at = Instant::now();
-                    DuckingStatus::Ducking
-                } else if elapsed >= config.release_ms {
-                    if let Some(orig) = original_volume_db.take() {
-                        restore_volume(&obs_conn, &config.target_source, orig, &mut last_self_set)
-                            .await;
-                    }
-                    state_entered_at = Instant::now();
-                    DuckingStatus::Idle
-                } else {
-                    DuckingStatus::Releasing
-                }
+            if f.next_at().is_none() {
+                duck.fade = None;
             }
-        };
+        }
+
+        wake_at = targets
+            .values()
+            .filter_map(|d| d.fade.as_ref().and_then(|f| f.next_at()))
+            .chain(machine.deadline(&config))
+            .min();
 
-        if new_status != status {
-            status = new_status;
-            emit_status(&app_handle, status);
+        // Settled and idle: only a trigger going over the threshold matters.
+        if machine.status == DuckingStatus::Idle && wake_at.is_none() {
+            let level = 10f64.powf(config.threshold_db / 20.0) as f32;
+            metrics_notify.gate(trigger_device_ids, level);
+        } else {
+            metrics_notify.ungate();
         }
     }
 }
 
-fn emit_status(app: &tauri::AppHandle, status: DuckingStatus) {
+/// Keeps the ducking config pointing at an input after it is renamed.
+pub async fn on_input_renamed(app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let Some(ducking) = app.try_state::<SharedDuckingConfig>() else {
+        return Vec::new();
+    };
+    let mut changed = Vec::new();
+    {
+        let mut config = ducking.write().await;
+        if config.trigger_source == old_name {
+            config.trigger_source = new_name.clone();
+            changed.push("trigger source".to_string());
+        }
+        if config.target_source == old_name {
+            config.target_source = new_name.clone();
+            changed.push("target source".to_string());
+        }
+        for name in config.trigger_sources.iter_mut().filter(|n| **n == old_name) {
+            *name = new_name.clone();
+            changed.push("trigger source".to_string());
+        }
+        for name in config.target_sources.iter_mut().filter(|n| **n == old_name) {
+            *name = new_name.clone();
+            changed.push("target source".to_string());
+        }
+        changed.dedup();
+        if changed.is_empty() {
+            return changed;
+        }
+    }
+    save_soon(&app);
+    changed
+}
+
+static SAVE_GEN: AtomicU64 = AtomicU64::new(0);
+
+/// Writes the live ducking config to settings once renames have settled;
+/// each call restarts the wait.
+fn save_soon(app: &tauri::AppHandle) {
+    let gen = SAVE_GEN.fetch_add(1, Ordering::Relaxed) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+        if SAVE_GEN.load(Ordering::Relaxed) != gen {
+            return;
+        }
+        let (Some(ducking), Some(settings)) =
+            (app.try_state::<SharedDuckingConfig>(), app.try_state::<SharedSettings>())
+        else {
+            return;
+        };
+        let config = ducking.read().await.clone();
+        if let Err(e) = settings::update_section(&settings, |s| s.ducking = config).await {
+            log::warn!("Failed to save ducking config after rename: {}", e);
+        }
+    });
+}
+
+fn emit_status(app: &tauri::AppHandle, status: DuckingStatus, targets: &[String]) {
     let _ = app.emit("ducking://state-changed", json!({ "status": status }));
+    let active = !matches!(status, DuckingStatus::Disabled | DuckingStatus::Idle);
+    let targets: &[String] = if active { targets } else { &[] };
+    let _ = app.emit(
+        "obs://ducking-state",
+        json!({ "status": status, "active": active, "targets": targets }),
+    );
+}
+
+/// The compressor OBServe owns on each target in sidechain mode.
+pub const SIDECHAIN_FILTER_NAME: &str = "OBServe Duck";
+
+/// Level the trigger is assumed to reach above the threshold while talking,
+/// used to turn the dB duck amount into a compressor ratio.
+const SIDECHAIN_HEADROOM_DB: f64 = 20.0;
+
+fn sidechain_settings(config: &DuckingConfig, trigger: &str) -> Value {
+    let reduction = (-config.duck_amount_db).clamp(0.0, SIDECHAIN_HEADROOM_DB - 0.5);
+    let ratio = (SIDECHAIN_HEADROOM_DB / (SIDECHAIN_HEADROOM_DB - reduction)).clamp(1.0, 32.0);
+    json!({
+        "ratio": ratio,
+        "threshold": config.threshold_db,
+        "attack_time": config.attack_ms,
+        "release_time": config.hold_ms + config.release_ms,
+        "output_gain": 0.0,
+        "sidechain_source": trigger,
+    })
+}
+
+/// Brings the "OBServe Duck" compressors in line with `new`: created or
+/// updated on every target while sidechain ducking is on, removed everywhere
+/// else, including targets only `old` had. All changes go on the undo stack
+/// as one step. Returns per-target problems.
+pub async fn sync_sidechain(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    undo_stack: &SharedUndoStack,
+    old: &DuckingConfig,
+    new: &DuckingConfig,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    // The compressor takes a single sidechain; the first trigger wins.
+    let trigger = new.triggers().into_iter().next();
+    let wanted = new.enabled && new.mode == DuckingMode::Sidechain && trigger.is_some();
+    if new.enabled && new.mode == DuckingMode::Sidechain && new.triggers().len() > 1 {
+        warnings.push(format!(
+            "Sidechain ducking follows one trigger; using '{}'",
+            trigger.as_deref().unwrap_or("")
+        ));
+    }
+    let desired = if wanted { new.targets() } else { Vec::new() };
+
+    let mut names = old.targets();
+    for name in new.targets() {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    let mut undo_entries = Vec::new();
+    for target in names {
+        let existing = {
+            let state = obs_state.read().await;
+            match state.inputs.get(&target) {
+                Some(input) => input
+                    .filters
+                    .iter()
+                    .find(|f| f.name == SIDECHAIN_FILTER_NAME)
+                    .map(|f| (f.kind.clone(), f.settings.clone())),
+                None => continue,
+            }
+        };
+        let keep = desired.contains(&target);
+        let (request_type, params, undo) = match (existing, keep) {
+            (None, false) => continue,
+            (Some((kind, settings)), false) => (
+                "RemoveSourceFilter",
+                json!({"sourceName": target, "filterName": SIDECHAIN_FILTER_NAME}),
+                UndoEntry {
+                    description: format!("Re-add ducking compressor on \"{}\"", target),
+                    action_type: "obs_request".into(),
+                    request_type: "CreateSourceFilter".into(),
+                    revert_params: json!({
+                        "sourceName": target,
+                        "filterName": SIDECHAIN_FILTER_NAME,
+                        "filterKind": kind,
+                        "filterSettings": settings,
+                    }),
+                },
+            ),
+            (Some((kind, _)), true) if kind != "compressor_filter" => {
+                warnings.push(format!(
+                    "'{}' already has a filter named \"{}\" that isn't a compressor",
+                    target, SIDECHAIN_FILTER_NAME
+                ));
+                continue;
+            }
+            (Some((_, settings)), true) => (
+                "SetSourceFilterSettings",
+                json!({
+                    "sourceName": target,
+                    "filterName": SIDECHAIN_FILTER_NAME,
+                    "filterSettings": sidechain_settings(new, trigger.as_deref().unwrap_or("")),
+                }),
+                UndoEntry {
+                    description: format!("Restore ducking compressor on \"{}\"", target),
+                    action_type: "obs_request".into(),
+                    request_type: "SetSourceFilterSettings".into(),
+                    revert_params: json!({
+                        "sourceName": target,
+                        "filterName": SIDECHAIN_FILTER_NAME,
+                        "filterSettings": settings,
+                    }),
+                },
+            ),
+            (None, true) => (
+                "CreateSourceFilter",
+                json!({
+                    "sourceName": target,
+                    "filterName": SIDECHAIN_FILTER_NAME,
+                    "filterKind": "compressor_filter",
+                    "filterSettings": sidechain_settings(new, trigger.as_deref().unwrap_or("")),
+                }),
+                UndoEntry {
+                    description: format!("Remove ducking compressor from \"{}\"", target),
+                    action_type: "obs_request".into(),
+                    request_type: "RemoveSourceFilter".into(),
+                    revert_params: json!({
+                        "sourceName": target,
+                        "filterName": SIDECHAIN_FILTER_NAME,
+                    }),
+                },
+            ),
+        };
+        match conn.send_request(request_type, Some(params)).await {
+            Ok(_) => undo_entries.push(undo),
+            Err(e) => warnings.push(format!("'{}': {}", target, e)),
+        }
+    }
+
+    if !undo_entries.is_empty() {
+        // Reverted last, once the compressors are back the way `old` had them.
+        undo_entries.insert(
+            0,
+            UndoEntry {
+                description: "Restore ducking settings".into(),
+                action_type: CONFIG_UNDO_TYPE.into(),
+                request_type: String::new(),
+                revert_params: serde_json::to_value(old).unwrap_or_default(),
+            },
+        );
+        undo_stack
+            .write()
+            .await
+            .push(UndoEntry::compound("Undo sidechain ducking changes".into(), undo_entries));
+    }
+    warnings
+}
+
+/// Undo entry type that puts a saved ducking config back, live and on disk.
+pub const CONFIG_UNDO_TYPE: &str = "ducking_config";
+
+pub async fn restore_config(app: &tauri::AppHandle, params: &Value) -> Result<(), String> {
+    let config: DuckingConfig =
+        serde_json::from_value(params.clone()).map_err(|e| format!("Invalid ducking config: {}", e))?;
+    *app.state::<SharedDuckingConfig>().write().await = config.clone();
+    app.state::<SharedMetricsNotify>().wake();
+    settings::update_section(&app.state::<SharedSettings>(), |s| s.ducking = config).await
 }
 
 fn resolve_default_input_device() -> Option<String> {
@@ -216,7 +690,7 @@ fn resolve_default_input_device() -> Option<String> {
 }
 
 async fn get_current_volume(
-    conn: &SharedObsConnection,
+    conn: &ObsHandle,
     obs_state: &SharedObsState,
     target: &str,
     last_self_set: &Option<Instant>,
@@ -227,7 +701,6 @@ async fn get_current_volume(
             return state.inputs.get(target).map(|i| i.volume_db);
         }
     }
-    let conn = conn.lock().await;
     let resp = conn
         .send_request("GetInputVolume", Some(json!({"inputName": target})))
         .await
@@ -236,12 +709,11 @@ async fn get_current_volume(
 }
 
 async fn apply_volume(
-    conn: &SharedObsConnection,
+    conn: &ObsHandle,
     target: &str,
     volume_db: f64,
     last_self_set: &mut Option<Instant>,
 ) {
-    let conn = conn.lock().await;
     let _ = conn
         .send_request(
             "SetInputVolume",
@@ -251,11 +723,100 @@ async fn apply_volume(
     *last_self_set = Some(Instant::now());
 }
 
-async fn restore_volume(
-    conn: &SharedObsConnection,
-    target: &str,
-    volume_db: f64,
-    last_self_set: &mut Option<Instant>,
-) {
-    apply_volume(conn, target, volume_db, last_self_set).await;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> DuckingConfig {
+        DuckingConfig {
+            enabled: true,
+            threshold_db: -30.0,
+            close_threshold_db: Some(-40.0),
+            attack_ms: 50,
+            hold_ms: 500,
+            release_ms: 300,
+            ..DuckingConfig::default()
+        }
+    }
+
+    /// Feeds `(ms, level)` frames and returns the statuses the machine entered.
+    fn run(frames: &[(u64, f64)]) -> Vec<DuckingStatus> {
+        let config = config();
+        let start = Instant::now();
+        let mut machine = DuckMachine::new(start);
+        frames
+            .iter()
+            .filter_map(|(ms, level)| {
+                machine.step(*level, start + Duration::from_millis(*ms), &config)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn speech_ducks_then_holds_and_releases() {
+        let statuses = run(&[
+            (0, -20.0),
+            (60, -20.0),
+            (200, -50.0),
+            (800, -50.0),
+            (1200, -50.0),
+        ]);
+        assert_eq!(
+            statuses,
+            vec![
+                DuckingStatus::Attacking,
+                DuckingStatus::Ducking,
+                DuckingStatus::Holding,
+                DuckingStatus::Releasing,
+                DuckingStatus::Idle,
+            ]
+        );
+    }
+
+    #[test]
+    fn levels_inside_the_band_do_not_flap() {
+        // Hovering between the close and open thresholds keeps the duck.
+        let statuses = run(&[
+            (0, -20.0),
+            (60, -20.0),
+            (200, -35.0),
+            (400, -31.0),
+            (600, -38.0),
+            (800, -33.0),
+        ]);
+        assert_eq!(statuses, vec![DuckingStatus::Attacking, DuckingStatus::Ducking]);
+    }
+
+    #[test]
+    fn short_pause_returns_from_hold() {
+        let statuses = run(&[(0, -20.0), (60, -20.0), (200, -50.0), (400, -25.0)]);
+        assert_eq!(
+            statuses,
+            vec![
+                DuckingStatus::Attacking,
+                DuckingStatus::Ducking,
+                DuckingStatus::Holding,
+                DuckingStatus::Ducking,
+            ]
+        );
+    }
+
+    #[test]
+    fn attack_aborts_when_speech_stops() {
+        let statuses = run(&[(0, -20.0), (20, -60.0), (400, -60.0)]);
+        assert_eq!(
+            statuses,
+            vec![DuckingStatus::Attacking, DuckingStatus::Releasing, DuckingStatus::Idle]
+        );
+    }
+
+    #[test]
+    fn close_threshold_never_exceeds_open() {
+        let config = DuckingConfig {
+            threshold_db: -30.0,
+            close_threshold_db: Some(-10.0),
+            ..DuckingConfig::default()
+        };
+        assert_eq!(config.close_threshold(), -30.0);
+    }
 }
@@ -0,0 +1,232 @@
+use crate::audio::DeviceVolume;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub type SharedEndpointVolumes = Arc<Mutex<EndpointVolumes>>;
+
+#[derive(Debug)]
+enum WatchCommand {
+    Refresh,
+    Removed(String),
+    Shutdown,
+}
+
+/// Latest known volume of each watched endpoint, kept current by
+/// IAudioEndpointVolumeCallback so reads don't need a COM round trip.
+#[derive(Default)]
+pub struct EndpointVolumes {
+    snapshot: HashMap<String, DeviceVolume>,
+    control: Option<std::sync::mpsc::Sender<WatchCommand>>,
+    watcher: Option<std::thread::JoinHandle<()>>,
+}
+
+/// The snapshot is plain data, so a panic elsewhere mustn't take it down.
+fn lock(volumes: &Mutex<EndpointVolumes>) -> MutexGuard<'_, EndpointVolumes> {
+    volumes.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl EndpointVolumes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, device_id: &str) -> Option<DeviceVolume> {
+        self.snapshot.get(device_id).cloned()
+    }
+
+    fn send(&self, cmd: WatchCommand) {
+        if let Some(tx) = &self.control {
+            let _ = tx.send(cmd);
+        }
+    }
+}
+
+fn send(app: &AppHandle, cmd: WatchCommand) {
+    if let Some(state) = app.try_state::<SharedEndpointVolumes>() {
+        lock(&state).send(cmd);
+    }
+}
+
+/// Re-registers callbacks on the current default devices. Called when the
+/// device watcher reports a default change.
+pub fn refresh(app: &AppHandle) {
+    send(app, WatchCommand::Refresh);
+}
+
+pub fn device_removed(app: &AppHandle, device_id: &str) {
+    if let Some(state) = app.try_state::<SharedEndpointVolumes>() {
+        let mut s = lock(&state);
+        s.snapshot.remove(device_id);
+        s.send(WatchCommand::Removed(device_id.to_string()));
+    }
+}
+
+/// Unregisters every callback and waits for the watcher thread to exit.
+pub fn shutdown(app: &AppHandle) {
+    let Some(state) = app.try_state::<SharedEndpointVolumes>() else {
+        return;
+    };
+    let watcher = {
+        let mut s = lock(&state);
+        s.send(WatchCommand::Shutdown);
+        s.control = None;
+        s.watcher.take()
+    };
+    // Joined with the lock released; the thread takes it to seed volumes.
+    if let Some(watcher) = watcher {
+        let _ = watcher.join();
+    }
+}
+
+#[cfg(windows)]
+pub fn start(app: AppHandle, volumes: SharedEndpointVolumes) {
+    use windows::Win32::Media::Audio::Endpoints::{
+        IAudioEndpointVolume, IAudioEndpointVolumeCallback, IAudioEndpointVolumeCallback_Impl,
+    };
+    use windows::Win32::Media::Audio::*;
+    use windows::Win32::System::Com::*;
+
+    #[windows::core::implement(IAudioEndpointVolumeCallback)]
+    struct VolumeCallback {
+        device_id: String,
+        sender: tokio::sync::mpsc::UnboundedSender<(String, f32, bool)>,
+    }
+
+    impl IAudioEndpointVolumeCallback_Impl for VolumeCallback_Impl {
+        fn OnNotify(&self, pnotify: *mut AUDIO_VOLUME_NOTIFICATION_DATA) -> windows::core::Result<()> {
+            // Runs on an arbitrary COM worker thread; just hand the values off.
+            if let Some(data) = unsafe { pnotify.as_ref() } {
+                let _ = self.sender.send((
+                    self.device_id.clone(),
+                    data.fMasterVolume,
+                    data.bMuted.as_bool(),
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    struct Registration {
+        endpoint: IAudioEndpointVolume,
+        callback: IAudioEndpointVolumeCallback,
+    }
+
+    let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel::<(String, f32, bool)>();
+    let (control_tx, control_rx) = std::sync::mpsc::channel::<WatchCommand>();
+    lock(&volumes).control = Some(control_tx.clone());
+
+    let seed_volumes = volumes.clone();
+    let watcher = std::thread::spawn(move || unsafe {
+        if CoInitializeEx(None, COINIT_MULTITHREADED).ok().is_err() {
+            return;
+        }
+        let enumerator: IMMDeviceEnumerator =
+            match CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) {
+                Ok(e) => e,
+                Err(_) => {
+                    CoUninitialize();
+                    return;
+                }
+            };
+
+        let mut registered: HashMap<String, Registration> = HashMap::new();
+        let unregister = |reg: Registration| {
+            let _ = reg.endpoint.UnregisterControlChangeNotify(&reg.callback);
+        };
+
+        let _ = control_tx.send(WatchCommand::Refresh);
+        while let Ok(cmd) = control_rx.recv() {
+            match cmd {
+                WatchCommand::Refresh => {
+                    let defaults: Vec<(String, IMMDevice)> = [eRender, eCapture]
+                        .into_iter()
+                        .filter_map(|flow| enumerator.GetDefaultAudioEndpoint(flow, eConsole).ok())
+                        .filter_map(|d| {
+                            let id = d.GetId().ok()?.to_string().ok()?;
+                            Some((id, d))
+                        })
+                        .collect();
+
+                    let stale: Vec<String> = registered
+                        .keys()
+                        .filter(|id| !defaults.iter().any(|(d, _)| d == *id))
+                        .cloned()
+                        .collect();
+                    for id in stale {
+                        if let Some(reg) = registered.remove(&id) {
+                            unregister(reg);
+                        }
+                        lock(&seed_volumes).snapshot.remove(&id);
+                    }
+
+                    for (id, device) in defaults {
+                        if registered.contains_key(&id) {
+                            continue;
+                        }
+                        let Ok(endpoint) = device.Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None) else {
+                            continue;
+                        };
+                        let callback: IAudioEndpointVolumeCallback = VolumeCallback {
+                            device_id: id.clone(),
+                            sender: event_tx.clone(),
+                        }
+                        .into();
+                        if endpoint.RegisterControlChangeNotify(&callback).is_err() {
+                            continue;
+                        }
+                        if let Ok(current) = crate::audio::get_device_volume(&id) {
+                            lock(&seed_volumes).snapshot.insert(id.clone(), current);
+                        }
+                        registered.insert(id, Registration { endpoint, callback });
+                    }
+                }
+                WatchCommand::Removed(id) => {
+                    if let Some(reg) = registered.remove(&id) {
+                        unregister(reg);
+                    }
+                }
+                WatchCommand::Shutdown => break,
+            }
+        }
+
+        for (_, reg) in registered.drain() {
+            unregister(reg);
+        }
+        drop(enumerator);
+        CoUninitialize();
+    });
+    lock(&volumes).watcher = Some(watcher);
+
+    tauri::async_runtime::spawn(async move {
+        while let Some((device_id, volume, muted)) = event_rx.recv().await {
+            let known = lock(&volumes).snapshot.contains_key(&device_id);
+            if !known {
+                // The seed read failed; look the device up so the entry
+                // carries its name. A device that's gone is ignored.
+                let id = device_id.clone();
+                let Ok(Ok(current)) = tokio::task::spawn_blocking(move || crate::audio::get_device_volume(&id)).await
+                else {
+                    continue;
+                };
+                lock(&volumes).snapshot.entry(device_id.clone()).or_insert(current);
+            }
+            let update = {
+                let mut s = lock(&volumes);
+                let Some(entry) = s.snapshot.get_mut(&device_id) else {
+                    continue;
+                };
+                if (entry.volume - volume).abs() < f32::EPSILON && entry.muted == muted {
+                    continue;
+                }
+                entry.volume = volume;
+                entry.muted = muted;
+                entry.clone()
+            };
+            let _ = app.emit("windows-audio://volume-changed", update);
+        }
+    });
+}
+
+#[cfg(not(windows))]
+pub fn start(_app: AppHandle, _volumes: SharedEndpointVolumes) {}
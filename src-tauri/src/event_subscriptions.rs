@@ -0,0 +1,149 @@
+use crate::obs_websocket::ObsHandle;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+pub type SharedEventSubscriptions = Arc<Mutex<SubscriptionRegistry>>;
+
+// obs-websocket EventSubscription bits.
+pub const GENERAL: u64 = 1;
+pub const CONFIG: u64 = 1 << 1;
+pub const SCENES: u64 = 1 << 2;
+pub const INPUTS: u64 = 1 << 3;
+pub const TRANSITIONS: u64 = 1 << 4;
+pub const FILTERS: u64 = 1 << 5;
+pub const OUTPUTS: u64 = 1 << 6;
+pub const SCENE_ITEMS: u64 = 1 << 7;
+pub const MEDIA_INPUTS: u64 = 1 << 8;
+pub const VENDORS: u64 = 1 << 9;
+pub const UI: u64 = 1 << 10;
+pub const INPUT_VOLUME_METERS: u64 = 1 << 16;
+pub const INPUT_ACTIVE_STATE_CHANGED: u64 = 1 << 17;
+pub const INPUT_SHOW_STATE_CHANGED: u64 = 1 << 18;
+pub const SCENE_ITEM_TRANSFORM_CHANGED: u64 = 1 << 19;
+
+/// Groups ObsState tracking depends on; always subscribed.
+pub const BASE: u64 = GENERAL
+    | CONFIG
+    | SCENES
+    | INPUTS
+    | TRANSITIONS
+    | FILTERS
+    | OUTPUTS
+    | SCENE_ITEMS
+    | MEDIA_INPUTS
+    | UI;
+
+const GROUPS: &[(&str, u64)] = &[
+    ("general", GENERAL),
+    ("config", CONFIG),
+    ("scenes", SCENES),
+    ("inputs", INPUTS),
+    ("transitions", TRANSITIONS),
+    ("filters", FILTERS),
+    ("outputs", OUTPUTS),
+    ("sceneItems", SCENE_ITEMS),
+    ("mediaInputs", MEDIA_INPUTS),
+    ("vendors", VENDORS),
+    ("ui", UI),
+    ("inputVolumeMeters", INPUT_VOLUME_METERS),
+    ("inputActiveStateChanged", INPUT_ACTIVE_STATE_CHANGED),
+    ("inputShowStateChanged", INPUT_SHOW_STATE_CHANGED),
+    ("sceneItemTransformChanged", SCENE_ITEM_TRANSFORM_CHANGED),
+];
+
+pub fn parse_groups(names: &[String]) -> Result<u64, String> {
+    names.iter().try_fold(0, |mask, name| {
+        GROUPS
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, bit)| mask | bit)
+            .ok_or_else(|| format!("Unknown event group '{}'", name))
+    })
+}
+
+pub fn group_names(mask: u64) -> Vec<String> {
+    GROUPS
+        .iter()
+        .filter(|(_, bit)| mask & bit != 0)
+        .map(|(n, _)| n.to_string())
+        .collect()
+}
+
+/// Which high-volume event groups each feature currently needs. The
+/// connection subscribes to BASE plus the union of all declarations.
+pub struct SubscriptionRegistry {
+    needs: BTreeMap<String, u64>,
+}
+
+impl Default for SubscriptionRegistry {
+    fn default() -> Self {
+        let mut needs = BTreeMap::new();
+        // The mixer meters are on by default until the UI says otherwise.
+        needs.insert("meters".to_string(), INPUT_VOLUME_METERS);
+        Self { needs }
+    }
+}
+
+impl SubscriptionRegistry {
+    pub fn declare(&mut self, feature: &str, flags: u64) {
+        if flags == 0 {
+            self.needs.remove(feature);
+        } else {
+            self.needs.insert(feature.to_string(), flags);
+        }
+    }
+
+    pub fn mask(&self) -> u64 {
+        self.needs.values().fold(BASE, |mask, flags| mask | flags)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventSubscriptionStatus {
+    pub mask: u64,
+    pub groups: Vec<String>,
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+/// Records what `feature` needs and resubscribes if the union changed.
+pub async fn declare(conn: &ObsHandle, feature: &str, flags: u64) -> Result<(), String> {
+    let mask = {
+        let registry = conn.subscriptions();
+        let mut registry = registry.lock().unwrap();
+        registry.declare(feature, flags);
+        registry.mask()
+    };
+    conn.set_event_subscriptions(mask).await
+}
+
+#[tauri::command]
+pub async fn set_event_interest(
+    conn_state: tauri::State<'_, ObsHandle>,
+    feature: String,
+    groups: Vec<String>,
+) -> Result<EventSubscriptionStatus, String> {
+    let flags = parse_groups(&groups)?;
+    declare(&conn_state, &feature, flags).await?;
+    get_active_event_subscriptions(conn_state).await
+}
+
+#[tauri::command]
+pub async fn get_active_event_subscriptions(
+    conn_state: tauri::State<'_, ObsHandle>,
+) -> Result<EventSubscriptionStatus, String> {
+    let conn = conn_state.inner().clone();
+    let mask = conn.active_event_subscriptions();
+    let registry = conn.subscriptions();
+    let registry = registry.lock().unwrap();
+    Ok(EventSubscriptionStatus {
+        mask,
+        groups: group_names(mask),
+        features: registry
+            .needs
+            .iter()
+            .map(|(feature, flags)| (feature.clone(), group_names(*flags)))
+            .collect(),
+    })
+}
@@ -0,0 +1,276 @@
+use crate::i18n::t;
+use crate::obs_websocket::ObsHandle;
+use crate::obs_state::SharedObsState;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+use tokio::sync::Mutex;
+
+pub type SharedFilterBypass = Arc<Mutex<HashMap<String, BypassSession>>>;
+
+const MAX_BYPASS_SECONDS: f64 = 600.0;
+
+pub struct BypassSession {
+    pub saved: Vec<(String, bool)>,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BypassStatus {
+    pub source_name: String,
+    pub filters: Vec<String>,
+    pub duration_seconds: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub source_name: String,
+    pub restored: Vec<String>,
+    pub skipped: Vec<String>,
+    pub reason: String,
+}
+
+async fn set_filter_enabled(
+    conn: &ObsHandle,
+    source_name: &str,
+    filter_name: &str,
+    enabled: bool,
+) -> Result<(), String> {
+    conn.send_request(
+        "SetSourceFilterEnabled",
+        Some(json!({
+            "sourceName": source_name,
+            "filterName": filter_name,
+            "filterEnabled": enabled,
+        })),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn current_filters(obs: &SharedObsState, source_name: &str) -> Result<Vec<(String, bool)>, String> {
+    let s = obs.read().await;
+    let input = s
+        .inputs
+        .get(source_name)
+        .ok_or_else(|| t("filter_bypass.source_not_found", &[("source", &source_name)]))?;
+    Ok(input
+        .filters
+        .iter()
+        .map(|f| (f.name.clone(), f.enabled))
+        .collect())
+}
+
+async fn restore_session(
+    conn: &ObsHandle,
+    obs: &SharedObsState,
+    bypass: &SharedFilterBypass,
+    app: &tauri::AppHandle,
+    source_name: &str,
+    token: Option<&str>,
+    reason: &str,
+) -> Result<Option<RestoreResult>, String> {
+    let found = {
+        let mut sessions = bypass.lock().await;
+        // A timer finds its session by token, so a rename since it started
+        // doesn't lose it.
+        let key = match token {
+            Some(t) => sessions.iter().find(|(_, s)| s.token == t).map(|(k, _)| k.clone()),
+            None => sessions.contains_key(source_name).then(|| source_name.to_string()),
+        };
+        key.and_then(|k| sessions.remove(&k).map(|s| (k, s)))
+    };
+    let Some((source_name, session)) = found else {
+        return Ok(None);
+    };
+    let source_name = source_name.as_str();
+
+    let existing: Vec<String> = current_filters(obs, source_name)
+        .await
+        .map(|f| f.into_iter().map(|(name, _)| name).collect())
+        .unwrap_or_default();
+
+    let mut restored = Vec::new();
+    let mut skipped = Vec::new();
+    for (name, enabled) in session.saved {
+        if !existing.contains(&name) {
+            skipped.push(name);
+            continue;
+        }
+        match set_filter_enabled(conn, source_name, &name, enabled).await {
+            Ok(()) => restored.push(name),
+            Err(e) => {
+                log::warn!("Failed to restore filter '{}' on '{}': {}", name, source_name, e);
+                skipped.push(name);
+            }
+        }
+    }
+
+    let result = RestoreResult {
+        source_name: source_name.to_string(),
+        restored,
+        skipped,
+        reason: reason.to_string(),
+    };
+    let _ = app.emit("filters://bypass-ended", &result);
+    Ok(Some(result))
+}
+
+pub async fn on_input_renamed(app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let Some(bypass) = app.try_state::<SharedFilterBypass>() else {
+        return Vec::new();
+    };
+    let mut sessions = bypass.lock().await;
+    let Some(session) = sessions.remove(&old_name) else {
+        return Vec::new();
+    };
+    sessions.insert(new_name, session);
+    vec!["filter bypass".to_string()]
+}
+
+/// Re-enables every bypassed chain before the app exits.
+pub async fn shutdown(app: tauri::AppHandle) {
+    let (Some(conn), Some(obs), Some(bypass)) = (
+        app.try_state::<ObsHandle>(),
+        app.try_state::<SharedObsState>(),
+        app.try_state::<SharedFilterBypass>(),
+    ) else {
+        return;
+    };
+    let sources: Vec<String> = bypass.lock().await.keys().cloned().collect();
+    for source in sources {
+        if let Err(e) = restore_session(&conn, &obs, &bypass, &app, &source, None, "shutdown").await {
+            log::warn!("Filter bypass restore failed for '{}': {}", source, e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn bypass_filter_chain(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    bypass_state: tauri::State<'_, SharedFilterBypass>,
+    app_handle: tauri::AppHandle,
+    source_name: String,
+    duration_seconds: f64,
+) -> Result<BypassStatus, String> {
+    let duration = duration_seconds.clamp(0.5, MAX_BYPASS_SECONDS);
+    let filters = current_filters(&obs_state, &source_name).await?;
+    let token = uuid::Uuid::new_v4().to_string();
+
+    {
+        let mut sessions = bypass_state.lock().await;
+        match sessions.get_mut(&source_name) {
+            // Already bypassed: keep the original snapshot, just extend the window.
+            Some(existing) => existing.token = token.clone(),
+            None => {
+                sessions.insert(
+                    source_name.clone(),
+                    BypassSession {
+                        saved: filters.clone(),
+                        token: token.clone(),
+                    },
+                );
+            }
+        }
+    }
+
+    let mut bypassed = Vec::new();
+    for (name, enabled) in &filters {
+        if *enabled {
+            if let Err(e) = set_filter_enabled(&conn_state, &source_name, name, false).await {
+                // Put back whatever was already switched off before giving up.
+                if let Err(restore_err) = restore_session(
+                    &conn_state,
+                    &obs_state,
+                    &bypass_state,
+                    &app_handle,
+                    &source_name,
+                    Some(&token),
+                    "failed",
+                )
+                .await
+                {
+                    log::warn!("Filter bypass restore failed for '{}': {}", source_name, restore_err);
+                }
+                return Err(t(
+                    "filter_bypass.bypass_failed",
+                    &[("filter", &name), ("source", &source_name), ("error", &e)],
+                ));
+            }
+        }
+        bypassed.push(name.clone());
+    }
+
+    let status = BypassStatus {
+        source_name: source_name.clone(),
+        filters: bypassed,
+        duration_seconds: duration,
+    };
+    let _ = app_handle.emit("filters://bypass-started", &status);
+
+    let conn = conn_state.inner().clone();
+    let obs = obs_state.inner().clone();
+    let bypass = bypass_state.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs_f64(duration)).await;
+        if let Err(e) = restore_session(&conn, &obs, &bypass, &app_handle, &source_name, Some(&token), "timeout").await {
+            log::warn!("Filter bypass restore failed for '{}': {}", source_name, e);
+        }
+    });
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn restore_filter_chain(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    bypass_state: tauri::State<'_, SharedFilterBypass>,
+    app_handle: tauri::AppHandle,
+    source_name: String,
+) -> Result<Option<RestoreResult>, String> {
+    restore_session(&conn_state, &obs_state, &bypass_state, &app_handle, &source_name, None, "manual").await
+}
+
+#[tauri::command]
+pub async fn get_bypassed_sources(
+    bypass_state: tauri::State<'_, SharedFilterBypass>,
+) -> Result<Vec<String>, String> {
+    Ok(bypass_state.lock().await.keys().cloned().collect())
+}
+
+#[tauri::command]
+pub async fn toggle_filter_group(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    source_name: String,
+    prefix: String,
+    enabled: bool,
+) -> Result<Vec<String>, String> {
+    if prefix.is_empty() {
+        return Err(t("filter_bypass.empty_prefix", &[]));
+    }
+    let filters = current_filters(&obs_state, &source_name).await?;
+    let mut changed = Vec::new();
+    for (name, current) in filters {
+        if !name.starts_with(&prefix) {
+            continue;
+        }
+        if current != enabled {
+            set_filter_enabled(&conn_state, &source_name, &name, enabled).await?;
+        }
+        changed.push(name);
+    }
+    if changed.is_empty() {
+        return Err(t(
+            "filter_bypass.no_prefix_match",
+            &[("source", &source_name), ("prefix", &prefix)],
+        ));
+    }
+    Ok(changed)
+}
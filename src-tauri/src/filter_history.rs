@@ -0,0 +1,495 @@
+use crate::ai_actions::{SharedUndoStack, UndoEntry};
+use crate::gemini::AiAction;
+use crate::obs_state::{FilterInfo, SharedObsState};
+use crate::obs_websocket::ObsHandle;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+pub type SharedFilterHistory = Arc<Mutex<FilterHistory>>;
+
+const MAX_ENTRIES_PER_SOURCE: usize = 500;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(3);
+/// How long a change we made waits for its OBS event before the event is
+/// attributed to OBS itself.
+const ORIGIN_WINDOW: Duration = Duration::from_secs(3);
+const DEFAULT_LIMIT: usize = 50;
+
+/// Serializes reads and rewrites of the per-source journal files.
+static DISK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterHistoryEntry {
+    /// Unix ms, unique per entry so it can name a snapshot.
+    pub timestamp: u64,
+    pub source_name: String,
+    pub filter_name: String,
+    pub filter_kind: String,
+    /// "created", "updated", "renamed" or "removed".
+    pub change: String,
+    /// The filter's name before a rename.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_name: Option<String>,
+    pub settings: Value,
+    /// "obs", "ui", "ai" or "preset".
+    pub origin: String,
+    /// The source's whole filter chain after the change.
+    pub chain: Vec<FilterInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterRestoreResult {
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Entries not yet on disk, plus the changes we've just asked OBS for so
+/// their events can be attributed.
+#[derive(Default)]
+pub struct FilterHistory {
+    pending: HashMap<String, Vec<FilterHistoryEntry>>,
+    expected: Vec<(String, String, &'static str, Instant)>,
+    last_timestamp: u64,
+}
+
+impl FilterHistory {
+    pub fn expect(&mut self, source_name: &str, filter_name: &str, origin: &'static str) {
+        self.expected.retain(|(_, _, _, at)| at.elapsed() < ORIGIN_WINDOW);
+        self.expected.push((source_name.to_string(), filter_name.to_string(), origin, Instant::now()));
+    }
+
+    fn take_origin(&mut self, source_name: &str, filter_name: &str) -> &'static str {
+        self.expected.retain(|(_, _, _, at)| at.elapsed() < ORIGIN_WINDOW);
+        match self
+            .expected
+            .iter()
+            .position(|(s, f, _, _)| s == source_name && f == filter_name)
+        {
+            Some(i) => self.expected.remove(i).2,
+            None => "obs",
+        }
+    }
+
+    fn next_timestamp(&mut self) -> u64 {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        self.last_timestamp = now.max(self.last_timestamp + 1);
+        self.last_timestamp
+    }
+}
+
+/// Requests whose OBS events end up in the journal. Expecting any other
+/// would leave an expectation nothing consumes, and the next OBS-side change
+/// to that filter would be credited to us.
+pub fn is_journaled(request_type: &str) -> bool {
+    matches!(
+        request_type,
+        "CreateSourceFilter" | "RemoveSourceFilter" | "SetSourceFilterSettings" | "SetSourceFilterName"
+    )
+}
+
+/// Marks the filter requests in `actions` as ours before they're sent.
+pub fn expect_actions(history: &SharedFilterHistory, actions: &[AiAction], origin: &'static str) {
+    let Ok(mut h) = history.lock() else {
+        return;
+    };
+    for action in actions
+        .iter()
+        .filter(|a| a.action_type == "obs_request" && is_journaled(&a.request_type))
+    {
+        if let (Some(source), Some(filter)) = (
+            action.params["sourceName"].as_str(),
+            action.params["filterName"].as_str(),
+        ) {
+            h.expect(source, filter, origin);
+        }
+    }
+}
+
+pub fn expect(history: &SharedFilterHistory, source_name: &str, filter_name: &str, origin: &'static str) {
+    if let Ok(mut h) = history.lock() {
+        h.expect(source_name, filter_name, origin);
+    }
+}
+
+/// Called from the websocket event handler after the state is updated. Only
+/// touches memory; the flush task writes to disk.
+pub async fn record_event(
+    app: &tauri::AppHandle,
+    obs_state: &SharedObsState,
+    change: &str,
+    source_name: &str,
+    filter: FilterInfo,
+) {
+    record(app, obs_state, change, source_name, None, filter).await;
+}
+
+/// A rename is requested under the old name, so that's what its expectation
+/// is keyed by.
+pub async fn record_rename(
+    app: &tauri::AppHandle,
+    obs_state: &SharedObsState,
+    source_name: &str,
+    old_name: &str,
+    filter: FilterInfo,
+) {
+    record(app, obs_state, "renamed", source_name, Some(old_name), filter).await;
+}
+
+async fn record(
+    app: &tauri::AppHandle,
+    obs_state: &SharedObsState,
+    change: &str,
+    source_name: &str,
+    previous_name: Option<&str>,
+    filter: FilterInfo,
+) {
+    let Some(chain) = obs_state.read().await.inputs.get(source_name).map(|i| i.filters.clone()) else {
+        return;
+    };
+    let history = app.state::<SharedFilterHistory>();
+    let Ok(mut h) = history.lock() else {
+        return;
+    };
+    let origin = h.take_origin(source_name, previous_name.unwrap_or(&filter.name));
+    let entry = FilterHistoryEntry {
+        timestamp: h.next_timestamp(),
+        source_name: source_name.to_string(),
+        filter_name: filter.name,
+        filter_kind: filter.kind,
+        change: change.to_string(),
+        previous_name: previous_name.map(str::to_string),
+        settings: filter.settings,
+        origin: origin.to_string(),
+        chain,
+    };
+    h.pending.entry(source_name.to_string()).or_default().push(entry);
+}
+
+fn history_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("filter_history")
+}
+
+// Source names can contain anything, so files are named by hash.
+fn history_file(source_name: &str) -> PathBuf {
+    use sha2::{Digest, Sha256};
+    let hash = Sha256::digest(source_name.as_bytes());
+    let name: String = hash.iter().take(8).map(|b| format!("{:02x}", b)).collect();
+    history_dir().join(format!("{}.json", name))
+}
+
+fn load_entries(source_name: &str) -> Vec<FilterHistoryEntry> {
+    let Ok(content) = std::fs::read_to_string(history_file(source_name)) else {
+        return Vec::new();
+    };
+    match serde_json::from_str::<Vec<FilterHistoryEntry>>(&content) {
+        Ok(entries) => entries.into_iter().filter(|e| e.source_name == source_name).collect(),
+        Err(e) => {
+            log::warn!("Failed to parse filter history for '{}': {}", source_name, e);
+            Vec::new()
+        }
+    }
+}
+
+fn append_entries(batch: HashMap<String, Vec<FilterHistoryEntry>>) -> Result<(), String> {
+    let _disk = DISK.lock().map_err(|e| format!("Filter history lock poisoned: {}", e))?;
+    std::fs::create_dir_all(history_dir()).map_err(|e| format!("Failed to create dir: {}", e))?;
+    for (source_name, new_entries) in batch {
+        let mut entries = load_entries(&source_name);
+        entries.extend(new_entries);
+        write_entries(&source_name, entries)?;
+    }
+    Ok(())
+}
+
+fn write_entries(source_name: &str, mut entries: Vec<FilterHistoryEntry>) -> Result<(), String> {
+    if entries.len() > MAX_ENTRIES_PER_SOURCE {
+        entries.drain(..entries.len() - MAX_ENTRIES_PER_SOURCE);
+    }
+    let path = history_file(source_name);
+    let json = serde_json::to_string(&entries)
+        .map_err(|e| format!("Failed to serialize filter history: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json).map_err(|e| format!("Failed to write filter history: {}", e))?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to save filter history: {}", e))
+}
+
+/// Moves a source's journal over to its new name, merging with anything
+/// already recorded under that name.
+pub async fn on_input_renamed(app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let Some(history) = app.try_state::<SharedFilterHistory>() else {
+        return Vec::new();
+    };
+    if let Err(e) = flush(&history, Some(&old_name)).await {
+        log::warn!("Failed to write filter history: {}", e);
+        return Vec::new();
+    }
+    let moved = tokio::task::spawn_blocking(move || -> Result<bool, String> {
+        let _disk = DISK.lock().map_err(|e| format!("Filter history lock poisoned: {}", e))?;
+        let mut entries = load_entries(&old_name);
+        if entries.is_empty() {
+            return Ok(false);
+        }
+        for entry in &mut entries {
+            entry.source_name = new_name.clone();
+        }
+        let mut merged = load_entries(&new_name);
+        merged.extend(entries);
+        merged.sort_by_key(|e| e.timestamp);
+        write_entries(&new_name, merged)?;
+        let _ = std::fs::remove_file(history_file(&old_name));
+        Ok(true)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))
+    .and_then(|r| r);
+    match moved {
+        Ok(true) => vec!["filter history".to_string()],
+        Ok(false) => Vec::new(),
+        Err(e) => {
+            log::warn!("Failed to move filter history after rename: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Writes out pending entries, all of them or just one source's.
+async fn flush(history: &SharedFilterHistory, only: Option<&str>) -> Result<(), String> {
+    let batch: HashMap<String, Vec<FilterHistoryEntry>> = {
+        let mut h = history.lock().map_err(|e| format!("Filter history lock poisoned: {}", e))?;
+        match only {
+            Some(source) => h.pending.remove_entry(source).into_iter().collect(),
+            None => std::mem::take(&mut h.pending),
+        }
+    };
+    if batch.is_empty() {
+        return Ok(());
+    }
+    tokio::task::spawn_blocking(move || append_entries(batch))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+pub fn start_flush_task(history: SharedFilterHistory) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = flush(&history, None).await {
+                log::warn!("Failed to write filter history: {}", e);
+            }
+        }
+    });
+}
+
+/// Synchronous flush for app exit, when the runtime may already be gone.
+pub fn flush_blocking(history: &SharedFilterHistory) {
+    let batch = match history.lock() {
+        Ok(mut h) => std::mem::take(&mut h.pending),
+        Err(_) => return,
+    };
+    if !batch.is_empty() {
+        if let Err(e) = append_entries(batch) {
+            log::warn!("Failed to write filter history: {}", e);
+        }
+    }
+}
+
+async fn source_entries(history: &SharedFilterHistory, source_name: &str) -> Result<Vec<FilterHistoryEntry>, String> {
+    flush(history, Some(source_name)).await?;
+    let name = source_name.to_string();
+    tokio::task::spawn_blocking(move || {
+        let _disk = DISK.lock().map_err(|e| format!("Filter history lock poisoned: {}", e))?;
+        Ok(load_entries(&name))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn filter_request(request_type: &str, description: String, params: Value) -> UndoEntry {
+    UndoEntry {
+        description,
+        action_type: "obs_request".into(),
+        request_type: request_type.into(),
+        revert_params: params,
+    }
+}
+
+#[tauri::command]
+pub async fn get_filter_history(
+    history: tauri::State<'_, SharedFilterHistory>,
+    source_name: String,
+    limit: Option<usize>,
+) -> Result<Vec<FilterHistoryEntry>, String> {
+    let entries = source_entries(&history, &source_name).await?;
+    Ok(entries.into_iter().rev().take(limit.unwrap_or(DEFAULT_LIMIT)).collect())
+}
+
+/// Brings the source's filter chain back to how it was right after the
+/// journal entry at `timestamp`: filters are removed, created and updated to
+/// match, then reordered. The whole restore is one undo step.
+#[tauri::command]
+pub async fn restore_filter_snapshot(
+    conn_state: tauri::State<'_, ObsHandle>,
+    history: tauri::State<'_, SharedFilterHistory>,
+    undo_stack: tauri::State<'_, SharedUndoStack>,
+    source_name: String,
+    timestamp: u64,
+) -> Result<FilterRestoreResult, String> {
+    let target = source_entries(&history, &source_name)
+        .await?
+        .into_iter()
+        .find(|e| e.timestamp == timestamp)
+        .ok_or_else(|| format!("No filter history entry at {} for '{}'", timestamp, source_name))?
+        .chain;
+
+    let conn = conn_state.inner().clone();
+    let resp = conn
+        .send_request("GetSourceFilterList", Some(json!({"sourceName": source_name})))
+        .await?;
+    let current: Vec<FilterInfo> = resp["filters"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|f| FilterInfo {
+                    name: f["filterName"].as_str().unwrap_or("").to_string(),
+                    kind: f["filterKind"].as_str().unwrap_or("").to_string(),
+                    enabled: f["filterEnabled"].as_bool().unwrap_or(true),
+                    settings: f["filterSettings"].clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mut result = FilterRestoreResult::default();
+    // Applied in order; the compound undo entry reverts them last to first,
+    // so the original order is put back after everything else.
+    let mut reverts: Vec<UndoEntry> = current
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, f)| {
+            filter_request(
+                "SetSourceFilterIndex",
+                format!("Move \"{}\" back", f.name),
+                json!({"sourceName": source_name, "filterName": f.name, "filterIndex": i}),
+            )
+        })
+        .collect();
+    let outcome: Result<(), String> = async {
+        let send = |request_type: &'static str, params: Value| {
+            if is_journaled(request_type) {
+                expect(&history, &source_name, params["filterName"].as_str().unwrap_or(""), "ui");
+            }
+            let conn = conn.clone();
+            async move { conn.send_request(request_type, Some(params)).await }
+        };
+
+        for f in &current {
+            let keep = target.iter().any(|t| t.name == f.name && t.kind == f.kind);
+            if keep {
+                continue;
+            }
+            send("RemoveSourceFilter", json!({"sourceName": source_name, "filterName": f.name})).await?;
+            if !f.enabled {
+                reverts.push(filter_request(
+                    "SetSourceFilterEnabled",
+                    format!("Disable \"{}\"", f.name),
+                    json!({"sourceName": source_name, "filterName": f.name, "filterEnabled": false}),
+                ));
+            }
+            reverts.push(filter_request(
+                "CreateSourceFilter",
+                format!("Re-add \"{}\"", f.name),
+                json!({"sourceName": source_name, "filterName": f.name, "filterKind": f.kind, "filterSettings": f.settings}),
+            ));
+            result.removed.push(f.name.clone());
+        }
+
+        for t in &target {
+            match current.iter().find(|f| f.name == t.name && f.kind == t.kind) {
+                Some(f) => {
+                    let mut changed = false;
+                    if f.settings != t.settings {
+                        send(
+                            "SetSourceFilterSettings",
+                            json!({"sourceName": source_name, "filterName": t.name, "filterSettings": t.settings, "overlay": false}),
+                        )
+                        .await?;
+                        reverts.push(filter_request(
+                            "SetSourceFilterSettings",
+                            format!("Revert settings of \"{}\"", t.name),
+                            json!({"sourceName": source_name, "filterName": t.name, "filterSettings": f.settings, "overlay": false}),
+                        ));
+                        changed = true;
+                    }
+                    if f.enabled != t.enabled {
+                        send(
+                            "SetSourceFilterEnabled",
+                            json!({"sourceName": source_name, "filterName": t.name, "filterEnabled": t.enabled}),
+                        )
+                        .await?;
+                        reverts.push(filter_request(
+                            "SetSourceFilterEnabled",
+                            format!("{} \"{}\"", if f.enabled { "Enable" } else { "Disable" }, t.name),
+                            json!({"sourceName": source_name, "filterName": t.name, "filterEnabled": f.enabled}),
+                        ));
+                        changed = true;
+                    }
+                    if changed {
+                        result.updated.push(t.name.clone());
+                    }
+                }
+                None => {
+                    send(
+                        "CreateSourceFilter",
+                        json!({"sourceName": source_name, "filterName": t.name, "filterKind": t.kind, "filterSettings": t.settings}),
+                    )
+                    .await?;
+                    reverts.push(filter_request(
+                        "RemoveSourceFilter",
+                        format!("Remove \"{}\"", t.name),
+                        json!({"sourceName": source_name, "filterName": t.name}),
+                    ));
+                    if !t.enabled {
+                        send(
+                            "SetSourceFilterEnabled",
+                            json!({"sourceName": source_name, "filterName": t.name, "filterEnabled": false}),
+                        )
+                        .await?;
+                    }
+                    result.created.push(t.name.clone());
+                }
+            }
+        }
+
+        for (i, t) in target.iter().enumerate() {
+            conn.send_request(
+                "SetSourceFilterIndex",
+                Some(json!({"sourceName": source_name, "filterName": t.name, "filterIndex": i})),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+    .await;
+
+    // Push what was applied even if a step failed, so it can still be undone.
+    undo_stack.write().await.push(UndoEntry::compound(
+        format!("Revert filter restore on \"{}\"", source_name),
+        reverts,
+    ));
+    outcome.map(|()| result)
+}
@@ -0,0 +1,232 @@
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub type SharedFilterDefaults = Arc<Mutex<HashMap<String, Value>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterField {
+    pub key: String,
+    pub field_type: String,
+    pub default: Value,
+    pub value: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterSchema {
+    pub source_name: String,
+    pub filter_name: String,
+    pub filter_kind: String,
+    pub enabled: bool,
+    pub fields: Vec<FilterField>,
+}
+
+struct FieldOverride {
+    min: Option<f64>,
+    max: Option<f64>,
+    step: Option<f64>,
+    unit: Option<&'static str>,
+    options: &'static [&'static str],
+}
+
+const fn range(min: f64, max: f64, step: f64, unit: &'static str) -> FieldOverride {
+    FieldOverride {
+        min: Some(min),
+        max: Some(max),
+        step: Some(step),
+        unit: Some(unit),
+        options: &[],
+    }
+}
+
+const fn choice(options: &'static [&'static str]) -> FieldOverride {
+    FieldOverride {
+        min: None,
+        max: None,
+        step: None,
+        unit: None,
+        options,
+    }
+}
+
+// Defaults only tell us a field's type; ranges for the core OBS audio filters
+// come from the property definitions in obs-filters.
+const OVERRIDES: &[(&str, &str, FieldOverride)] = &[
+    ("noise_suppress_filter_v2", "suppress_level", range(-60.0, 0.0, 1.0, "dB")),
+    ("noise_suppress_filter_v2", "method", choice(&["speex", "rnnoise", "denoiser"])),
+    ("noise_suppress_filter_v2", "intensity", range(0.0, 1.0, 0.01, "")),
+    ("noise_gate_filter", "open_threshold", range(-96.0, 0.0, 0.1, "dB")),
+    ("noise_gate_filter", "close_threshold", range(-96.0, 0.0, 0.1, "dB")),
+    ("noise_gate_filter", "attack_time", range(0.0, 10000.0, 1.0, "ms")),
+    ("noise_gate_filter", "hold_time", range(0.0, 10000.0, 1.0, "ms")),
+    ("noise_gate_filter", "release_time", range(0.0, 10000.0, 1.0, "ms")),
+    ("gain_filter", "db", range(-30.0, 30.0, 0.1, "dB")),
+    ("compressor_filter", "ratio", range(1.0, 32.0, 0.5, ":1")),
+    ("compressor_filter", "threshold", range(-60.0, 0.0, 0.1, "dB")),
+    ("compressor_filter", "attack_time", range(1.0, 500.0, 1.0, "ms")),
+    ("compressor_filter", "release_time", range(1.0, 1000.0, 1.0, "ms")),
+    ("compressor_filter", "output_gain", range(-32.0, 32.0, 0.1, "dB")),
+    ("limiter_filter", "threshold", range(-60.0, 0.0, 0.1, "dB")),
+    ("limiter_filter", "release_time", range(1.0, 1000.0, 1.0, "ms")),
+    ("expander_filter", "presets", choice(&["expander", "gate"])),
+    ("expander_filter", "ratio", range(1.0, 20.0, 0.1, ":1")),
+    ("expander_filter", "threshold", range(-60.0, 0.0, 0.1, "dB")),
+    ("expander_filter", "attack_time", range(1.0, 100.0, 1.0, "ms")),
+    ("expander_filter", "release_time", range(1.0, 1000.0, 1.0, "ms")),
+    ("expander_filter", "output_gain", range(-32.0, 32.0, 0.1, "dB")),
+    ("expander_filter", "detector", choice(&["RMS", "peak"])),
+    ("upward_compressor_filter", "ratio", range(0.0, 1.0, 0.01, ":1")),
+    ("upward_compressor_filter", "threshold", range(-60.0, 0.0, 0.1, "dB")),
+    ("upward_compressor_filter", "attack_time", range(1.0, 100.0, 1.0, "ms")),
+    ("upward_compressor_filter", "release_time", range(1.0, 1000.0, 1.0, "ms")),
+    ("upward_compressor_filter", "output_gain", range(-32.0, 32.0, 0.1, "dB")),
+    ("upward_compressor_filter", "knee_width", range(0.0, 20.0, 1.0, "dB")),
+    ("basic_eq_filter", "low", range(-20.0, 20.0, 0.1, "dB")),
+    ("basic_eq_filter", "mid", range(-20.0, 20.0, 0.1, "dB")),
+    ("basic_eq_filter", "high", range(-20.0, 20.0, 0.1, "dB")),
+];
+
+fn find_override(kind: &str, key: &str) -> Option<&'static FieldOverride> {
+    OVERRIDES
+        .iter()
+        .find(|(k, f, _)| *k == kind && *f == key)
+        .map(|(_, _, o)| o)
+}
+
+fn infer_type(value: &Value) -> &'static str {
+    match value {
+        Value::Bool(_) => "bool",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+        Value::Null => "unknown",
+    }
+}
+
+/// Builds the field list from a kind's defaults and a filter's current
+/// settings. Keys present in only one of the two are still described.
+pub fn describe(kind: &str, defaults: &Value, current: &Value) -> Vec<FilterField> {
+    let empty = serde_json::Map::new();
+    let defaults = defaults.as_object().unwrap_or(&empty);
+    let current = current.as_object().unwrap_or(&empty);
+
+    let mut keys: Vec<&String> = defaults.keys().collect();
+    keys.extend(current.keys().filter(|k| !defaults.contains_key(*k)));
+
+    keys.into_iter()
+        .map(|key| {
+            let default = defaults.get(key).cloned().unwrap_or(Value::Null);
+            let value = current.get(key).cloned().unwrap_or_else(|| default.clone());
+            let typed = if default.is_null() { &value } else { &default };
+            let mut field_type = infer_type(typed).to_string();
+            let ov = find_override(kind, key);
+            if let Some(o) = ov {
+                if !o.options.is_empty() {
+                    field_type = "enum".into();
+                } else if field_type == "integer" && o.step.map(|s| s.fract() != 0.0).unwrap_or(false) {
+                    // OBS omits the decimal point for whole-number float defaults.
+                    field_type = "number".into();
+                }
+            }
+            FilterField {
+                key: key.clone(),
+                field_type,
+                default,
+                value,
+                min: ov.and_then(|o| o.min),
+                max: ov.and_then(|o| o.max),
+                step: ov.and_then(|o| o.step),
+                unit: ov.and_then(|o| o.unit).filter(|u| !u.is_empty()).map(String::from),
+                options: ov
+                    .map(|o| o.options.iter().map(|s| s.to_string()).collect())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+pub async fn kind_defaults(
+    conn: &ObsHandle,
+    cache: &SharedFilterDefaults,
+    kind: &str,
+) -> Result<Value, String> {
+    if let Some(cached) = cache.lock().await.get(kind) {
+        return Ok(cached.clone());
+    }
+    let resp = conn
+        .send_request("GetSourceFilterDefaultSettings", Some(json!({"filterKind": kind})))
+        .await?;
+    let defaults = resp["defaultFilterSettings"].clone();
+    cache.lock().await.insert(kind.to_string(), defaults.clone());
+    Ok(defaults)
+}
+
+pub async fn describe_filter_with(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    cache: &SharedFilterDefaults,
+    source_name: &str,
+    filter_name: &str,
+) -> Result<FilterSchema, String> {
+    let filter = {
+        let state = obs_state.read().await;
+        let input = state
+            .inputs
+            .get(source_name)
+            .ok_or_else(|| format!("Source '{}' not found", source_name))?;
+        input
+            .filters
+            .iter()
+            .find(|f| f.name == filter_name)
+            .cloned()
+            .ok_or_else(|| format!("Filter '{}' not found on '{}'", filter_name, source_name))?
+    };
+    let defaults = kind_defaults(conn, cache, &filter.kind).await?;
+    Ok(FilterSchema {
+        source_name: source_name.to_string(),
+        filter_name: filter.name,
+        fields: describe(&filter.kind, &defaults, &filter.settings),
+        filter_kind: filter.kind,
+        enabled: filter.enabled,
+    })
+}
+
+#[tauri::command]
+pub async fn get_filter_kind_defaults(
+    conn_state: tauri::State<'_, ObsHandle>,
+    cache: tauri::State<'_, SharedFilterDefaults>,
+    kind: String,
+) -> Result<Value, String> {
+    let conn = conn_state.inner().clone();
+    kind_defaults(&conn, &cache, &kind).await
+}
+
+#[tauri::command]
+pub async fn describe_filter(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    cache: tauri::State<'_, SharedFilterDefaults>,
+    source_name: String,
+    filter_name: String,
+) -> Result<FilterSchema, String> {
+    let conn = conn_state.inner().clone();
+    describe_filter_with(&conn, &obs_state, &cache, &source_name, &filter_name).await
+}
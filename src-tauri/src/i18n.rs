@@ -0,0 +1,170 @@
+use crate::settings::{self, SharedSettings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{OnceLock, RwLock};
+use tauri::Emitter;
+
+pub const FALLBACK_LOCALE: &str = "en";
+
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("es", include_str!("../locales/es.json")),
+];
+
+static PARSED: OnceLock<HashMap<&'static str, HashMap<String, String>>> = OnceLock::new();
+static CURRENT: RwLock<String> = RwLock::new(String::new());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleInfo {
+    pub code: String,
+    pub name: String,
+    pub missing_keys: usize,
+}
+
+fn catalogs() -> &'static HashMap<&'static str, HashMap<String, String>> {
+    PARSED.get_or_init(|| {
+        CATALOGS
+            .iter()
+            .map(|(code, src)| {
+                let catalog = serde_json::from_str(src).unwrap_or_else(|e| {
+                    log::error!("Failed to parse '{}' message catalog: {}", code, e);
+                    HashMap::new()
+                });
+                (*code, catalog)
+            })
+            .collect()
+    })
+}
+
+fn missing_keys(code: &str) -> Vec<&'static str> {
+    let all = catalogs();
+    let (Some(base), Some(catalog)) = (all.get(FALLBACK_LOCALE), all.get(code)) else {
+        return Vec::new();
+    };
+    let mut missing: Vec<&str> = base
+        .keys()
+        .filter(|k| !catalog.contains_key(*k))
+        .map(|k| k.as_str())
+        .collect();
+    missing.sort_unstable();
+    missing
+}
+
+/// Maps a requested tag ("es-MX", "es_ES.UTF-8", "ES") to a shipped catalog,
+/// falling back to English.
+pub fn resolve(requested: &str) -> &'static str {
+    shipped(requested).unwrap_or(FALLBACK_LOCALE)
+}
+
+pub fn is_available(requested: &str) -> bool {
+    shipped(requested).is_some()
+}
+
+fn shipped(requested: &str) -> Option<&'static str> {
+    let lang = requested
+        .split(['-', '_', '.'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    CATALOGS
+        .iter()
+        .map(|(code, _)| *code)
+        .find(|code| *code == lang)
+}
+
+pub fn apply_locale(requested: &str) -> &'static str {
+    let code = resolve(requested);
+    let missing = missing_keys(code);
+    if !missing.is_empty() {
+        log::debug!(
+            "Locale '{}' is missing {} key(s), falling back to English: {}",
+            code,
+            missing.len(),
+            missing.join(", ")
+        );
+    }
+    if let Ok(mut current) = CURRENT.write() {
+        *current = code.to_string();
+    }
+    code
+}
+
+pub fn current_locale() -> String {
+    match CURRENT.read() {
+        Ok(c) if !c.is_empty() => c.clone(),
+        _ => FALLBACK_LOCALE.to_string(),
+    }
+}
+
+pub fn available_locales() -> Vec<LocaleInfo> {
+    CATALOGS
+        .iter()
+        .map(|(code, _)| LocaleInfo {
+            code: code.to_string(),
+            name: lookup(code, "locale.name").unwrap_or_else(|| code.to_string()),
+            missing_keys: missing_keys(code).len(),
+        })
+        .collect()
+}
+
+fn lookup(code: &str, key: &str) -> Option<String> {
+    catalogs().get(code)?.get(key).cloned()
+}
+
+/// Translates `key` for the current locale, substituting `{name}` placeholders
+/// from `args`. Missing keys fall back to English, then to the key itself.
+pub fn t(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut text = lookup(&current_locale(), key)
+        .or_else(|| lookup(FALLBACK_LOCALE, key))
+        .unwrap_or_else(|| {
+            log::debug!("Missing message key: {}", key);
+            key.to_string()
+        });
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    text
+}
+
+#[cfg(target_os = "windows")]
+pub fn detect_system_locale() -> String {
+    use windows::Win32::Globalization::GetUserDefaultLocaleName;
+    let mut buf = [0u16; 85];
+    let len = unsafe { GetUserDefaultLocaleName(&mut buf) };
+    if len > 1 {
+        String::from_utf16_lossy(&buf[..(len as usize - 1)])
+    } else {
+        FALLBACK_LOCALE.to_string()
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn detect_system_locale() -> String {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.is_empty() && v != "C" && v != "POSIX")
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string())
+}
+
+#[tauri::command]
+pub async fn set_locale(
+    settings: tauri::State<'_, SharedSettings>,
+    app_handle: tauri::AppHandle,
+    locale: String,
+) -> Result<String, String> {
+    if !is_available(&locale) {
+        return Err(format!("Locale '{}' is not available", locale));
+    }
+    let code = apply_locale(&locale);
+    settings::update_section(&settings, |s| s.locale = code.to_string()).await?;
+    let _ = app_handle.emit("i18n://locale-changed", code);
+    Ok(code.to_string())
+}
+
+#[tauri::command]
+pub async fn get_available_locales() -> Result<Vec<LocaleInfo>, String> {
+    Ok(available_locales())
+}
@@ -0,0 +1,208 @@
+use crate::device_matching;
+use crate::obs_state::{InputInfo, SharedObsState};
+use crate::obs_websocket::ObsHandle;
+use crate::settings::{self, SharedSettings};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use tauri::{Emitter, Manager};
+
+const TRACK_COUNT: u8 = 6;
+
+/// Per-input mixer preferences, keyed by input name in settings. Kind and
+/// device id are kept so an entry can follow the input through a rename made
+/// in OBS while we weren't watching.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InputPreference {
+    pub kind: String,
+    pub device_id: String,
+    pub hidden: bool,
+    /// Track mask from before the input was taken out of the mix.
+    pub saved_tracks: Option<Value>,
+}
+
+impl InputPreference {
+    fn is_default(&self) -> bool {
+        !self.hidden && self.saved_tracks.is_none()
+    }
+
+    fn matches(&self, input: &InputInfo) -> bool {
+        self.kind == input.kind
+            && !device_matching::is_default_alias(&self.device_id)
+            && device_matching::ids_match(&self.device_id, &input.device_id)
+    }
+}
+
+/// Moves entries whose input no longer exists onto an unclaimed input with
+/// the same kind and device. Returns true if anything changed.
+fn reassociate(prefs: &mut HashMap<String, InputPreference>, inputs: &HashMap<String, InputInfo>) -> bool {
+    let orphans: Vec<String> = prefs.keys().filter(|name| !inputs.contains_key(*name)).cloned().collect();
+    let mut changed = false;
+    for old_name in orphans {
+        let new_name = inputs
+            .values()
+            .find(|input| !prefs.contains_key(&input.name) && prefs[&old_name].matches(input))
+            .map(|input| input.name.clone());
+        if let Some(new_name) = new_name {
+            log::info!("Input preferences for '{}' moved to '{}'", old_name, new_name);
+            if let Some(pref) = prefs.remove(&old_name) {
+                prefs.insert(new_name, pref);
+            }
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn all_tracks(enabled: bool) -> Value {
+    let mask: Map<String, Value> = (1..=TRACK_COUNT).map(|n| (n.to_string(), json!(enabled))).collect();
+    Value::Object(mask)
+}
+
+async fn current_preferences(
+    settings: &SharedSettings,
+    obs_state: &SharedObsState,
+) -> Result<HashMap<String, InputPreference>, String> {
+    let mut prefs = settings.read().await.input_preferences.clone();
+    let changed = {
+        let state = obs_state.read().await;
+        // Before the first state sync every entry would look orphaned.
+        !state.inputs.is_empty() && reassociate(&mut prefs, &state.inputs)
+    };
+    if changed {
+        let stored = prefs.clone();
+        settings::update_section(settings, move |s| s.input_preferences = stored).await?;
+    }
+    Ok(prefs)
+}
+
+async fn update_preference<F>(
+    settings: &SharedSettings,
+    app: &tauri::AppHandle,
+    input: &InputInfo,
+    f: F,
+) -> Result<HashMap<String, InputPreference>, String>
+where
+    F: FnOnce(&mut InputPreference),
+{
+    let name = input.name.clone();
+    let kind = input.kind.clone();
+    let device_id = input.device_id.clone();
+    settings::update_section(settings, move |s| {
+        let pref = s.input_preferences.entry(name.clone()).or_default();
+        pref.kind = kind;
+        pref.device_id = device_id;
+        f(pref);
+        if pref.is_default() {
+            s.input_preferences.remove(&name);
+        }
+    })
+    .await?;
+    let prefs = settings.read().await.input_preferences.clone();
+    let _ = app.emit("mixer://input-preferences-changed", &prefs);
+    Ok(prefs)
+}
+
+/// Moves mixer preferences to an input's new name.
+pub async fn on_input_renamed(app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let Some(settings) = app.try_state::<SharedSettings>() else {
+        return Vec::new();
+    };
+    if !settings.read().await.input_preferences.contains_key(&old_name) {
+        return Vec::new();
+    }
+    let result = settings::update_section(&settings, |s| {
+        if let Some(pref) = s.input_preferences.remove(&old_name) {
+            s.input_preferences.insert(new_name, pref);
+        }
+    })
+    .await;
+    if let Err(e) = result {
+        log::warn!("Failed to save input preferences after rename: {}", e);
+        return Vec::new();
+    }
+    let prefs = settings.read().await.input_preferences.clone();
+    let _ = app.emit("mixer://input-preferences-changed", &prefs);
+    vec!["mixer preferences".to_string()]
+}
+
+async fn input_info(obs_state: &SharedObsState, input_name: &str) -> Result<InputInfo, String> {
+    obs_state
+        .read()
+        .await
+        .inputs
+        .get(input_name)
+        .cloned()
+        .ok_or_else(|| format!("Input '{}' not found", input_name))
+}
+
+#[tauri::command]
+pub async fn get_input_preferences(
+    settings: tauri::State<'_, SharedSettings>,
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<HashMap<String, InputPreference>, String> {
+    current_preferences(&settings, &obs_state).await
+}
+
+#[tauri::command]
+pub async fn set_input_hidden(
+    settings: tauri::State<'_, SharedSettings>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    input_name: String,
+    hidden: bool,
+) -> Result<HashMap<String, InputPreference>, String> {
+    let input = input_info(&obs_state, &input_name).await?;
+    update_preference(&settings, &app_handle, &input, |p| p.hidden = hidden).await
+}
+
+/// Takes an input out of every track (or puts it back) without muting it.
+/// The previous mask is stored in settings so reactivation restores exactly
+/// the tracks it had, even after a restart.
+#[tauri::command]
+pub async fn set_input_active_in_mix(
+    conn_state: tauri::State<'_, ObsHandle>,
+    settings: tauri::State<'_, SharedSettings>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    input_name: String,
+    active: bool,
+) -> Result<HashMap<String, InputPreference>, String> {
+    let input = input_info(&obs_state, &input_name).await?;
+    let saved = current_preferences(&settings, &obs_state)
+        .await?
+        .get(&input_name)
+        .and_then(|p| p.saved_tracks.clone());
+
+    let tracks = match (active, saved) {
+        (true, Some(saved)) => saved,
+        (false, None) => {
+            let resp = conn_state
+                .send_request("GetInputAudioTracks", Some(json!({"inputName": input_name})))
+                .await?;
+            let current = resp["inputAudioTracks"].clone();
+            // Save first so a failure below can't lose the original mask.
+            update_preference(&settings, &app_handle, &input, |p| p.saved_tracks = Some(current)).await?;
+            all_tracks(false)
+        }
+        // Already in the requested state.
+        _ => return current_preferences(&settings, &obs_state).await,
+    };
+
+    conn_state
+        .send_request(
+            "SetInputAudioTracks",
+            Some(json!({"inputName": input_name, "inputAudioTracks": tracks})),
+        )
+        .await?;
+    if let Some(info) = obs_state.write().await.inputs.get_mut(&input_name) {
+        info.audio_tracks = tracks;
+    }
+
+    if active {
+        update_preference(&settings, &app_handle, &input, |p| p.saved_tracks = None).await
+    } else {
+        current_preferences(&settings, &obs_state).await
+    }
+}
@@ -0,0 +1,567 @@
+use crate::gemini::AiAction;
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+const ALIGN_LEFT: u64 = 1;
+const ALIGN_RIGHT: u64 = 2;
+const ALIGN_TOP: u64 = 4;
+const ALIGN_BOTTOM: u64 = 8;
+
+pub const ANCHORS: &[&str] = &[
+    "top-left",
+    "top-center",
+    "top-right",
+    "center-left",
+    "center",
+    "center-right",
+    "bottom-left",
+    "bottom-center",
+    "bottom-right",
+];
+pub const FIT_MODES: &[&str] = &["fit", "fill", "stretch"];
+pub const ALIGN_AXES: &[&str] = &["left", "center-x", "right", "top", "center-y", "bottom"];
+/// Gap from the canvas edge when a position request doesn't give one.
+pub const DEFAULT_MARGIN_PCT: f64 = 2.0;
+
+// Reported by GetSceneItemTransform but rejected or ignored by SetSceneItemTransform.
+const READ_ONLY_FIELDS: &[&str] = &["width", "height", "sourceWidth", "sourceHeight"];
+
+/// Horizontal and vertical anchor fractions: 0 = left/top, 0.5 = center, 1 = right/bottom.
+fn parse_anchor(anchor: &str) -> Result<(f64, f64), String> {
+    let (v, h) = match anchor {
+        "center" => ("center", "center"),
+        other => other
+            .split_once('-')
+            .ok_or_else(|| format!("Unknown anchor '{}', expected one of: {}", anchor, ANCHORS.join(", ")))?,
+    };
+    let frac = |s: &str, lo: &str, hi: &str| {
+        if s == lo {
+            Some(0.0)
+        } else if s == "center" {
+            Some(0.5)
+        } else if s == hi {
+            Some(1.0)
+        } else {
+            None
+        }
+    };
+    match (frac(h, "left", "right"), frac(v, "top", "bottom")) {
+        (Some(x), Some(y)) => Ok((x, y)),
+        _ => Err(format!("Unknown anchor '{}', expected one of: {}", anchor, ANCHORS.join(", "))),
+    }
+}
+
+fn alignment_flags(fx: f64, fy: f64) -> u64 {
+    let h = if fx < 0.25 {
+        ALIGN_LEFT
+    } else if fx > 0.75 {
+        ALIGN_RIGHT
+    } else {
+        0
+    };
+    let v = if fy < 0.25 {
+        ALIGN_TOP
+    } else if fy > 0.75 {
+        ALIGN_BOTTOM
+    } else {
+        0
+    };
+    h | v
+}
+
+fn alignment_fractions(flags: u64) -> (f64, f64) {
+    let fx = if flags & ALIGN_LEFT != 0 {
+        0.0
+    } else if flags & ALIGN_RIGHT != 0 {
+        1.0
+    } else {
+        0.5
+    };
+    let fy = if flags & ALIGN_TOP != 0 {
+        0.0
+    } else if flags & ALIGN_BOTTOM != 0 {
+        1.0
+    } else {
+        0.5
+    };
+    (fx, fy)
+}
+
+fn canvas_from_state(width: u32, height: u32) -> Result<(f64, f64), String> {
+    if width == 0 || height == 0 {
+        return Err("Canvas size unknown — video settings not loaded".into());
+    }
+    Ok((width as f64, height as f64))
+}
+
+pub(crate) async fn fetch_canvas(conn: &ObsHandle) -> Result<(f64, f64), String> {
+    let v = conn.send_request("GetVideoSettings", None).await?;
+    canvas_from_state(
+        v["baseWidth"].as_u64().unwrap_or(0) as u32,
+        v["baseHeight"].as_u64().unwrap_or(0) as u32,
+    )
+}
+
+pub async fn get_transform(conn: &ObsHandle, scene: &str, item_id: u64) -> Result<Value, String> {
+    let data = conn
+        .send_request(
+            "GetSceneItemTransform",
+            Some(json!({"sceneName": scene, "sceneItemId": item_id})),
+        )
+        .await?;
+    Ok(data["sceneItemTransform"].clone())
+}
+
+/// Writes `transform` back, dropping the read-only size fields. Returns what was sent.
+pub async fn apply_transform(conn: &ObsHandle, scene: &str, item_id: u64, transform: &Value) -> Result<Value, String> {
+    let mut settable = transform.clone();
+    if let Some(obj) = settable.as_object_mut() {
+        for key in READ_ONLY_FIELDS {
+            obj.remove(*key);
+        }
+    }
+    conn.send_request(
+        "SetSceneItemTransform",
+        Some(json!({
+            "sceneName": scene,
+            "sceneItemId": item_id,
+            "sceneItemTransform": settable,
+        })),
+    )
+    .await?;
+    Ok(settable)
+}
+
+/// Source size after crop, before scaling.
+fn cropped_source_size(t: &Value) -> (f64, f64) {
+    let f = |k: &str| t[k].as_f64().unwrap_or(0.0);
+    (
+        (f("sourceWidth") - f("cropLeft") - f("cropRight")).max(0.0),
+        (f("sourceHeight") - f("cropTop") - f("cropBottom")).max(0.0),
+    )
+}
+
+/// On-canvas box of an item as (left, top, width, height). Rotation is ignored,
+/// so rotated items line up by their unrotated box.
+pub(crate) fn item_box(t: &Value) -> (f64, f64, f64, f64) {
+    let f = |k: &str| t[k].as_f64().unwrap_or(0.0);
+    let has_bounds = t["boundsType"].as_str().map(|b| b != "OBS_BOUNDS_NONE").unwrap_or(false);
+    let (w, h) = if has_bounds {
+        (f("boundsWidth"), f("boundsHeight"))
+    } else {
+        (f("width"), f("height"))
+    };
+    let (fx, fy) = alignment_fractions(t["alignment"].as_u64().unwrap_or(ALIGN_LEFT | ALIGN_TOP));
+    (f("positionX") - fx * w, f("positionY") - fy * h, w, h)
+}
+
+/// Axis-aligned box around an item as it shows on the canvas, rotation
+/// included. OBS turns items clockwise about their position point.
+pub(crate) fn visible_box(t: &Value) -> (f64, f64, f64, f64) {
+    let (left, top, w, h) = item_box(t);
+    let rotation = t["rotation"].as_f64().unwrap_or(0.0);
+    if rotation % 360.0 == 0.0 {
+        return (left, top, w, h);
+    }
+    let (px, py) = (t["positionX"].as_f64().unwrap_or(0.0), t["positionY"].as_f64().unwrap_or(0.0));
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let corners = [(left, top), (left + w, top), (left, top + h), (left + w, top + h)].map(|(x, y)| {
+        let (dx, dy) = (x - px, y - py);
+        (px + dx * cos - dy * sin, py + dx * sin + dy * cos)
+    });
+    let (mut min_x, mut min_y) = (f64::MAX, f64::MAX);
+    let (mut max_x, mut max_y) = (f64::MIN, f64::MIN);
+    for (x, y) in corners {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    (min_x, min_y, max_x - min_x, max_y - min_y)
+}
+
+pub async fn position_item(
+    conn: &ObsHandle,
+    canvas: (f64, f64),
+    scene: &str,
+    item_id: u64,
+    anchor: &str,
+    margin_pct: f64,
+    size_pct: f64,
+) -> Result<Value, String> {
+    let (fx, fy) = parse_anchor(anchor)?;
+    if !(1.0..=100.0).contains(&size_pct) {
+        return Err(format!("Size must be between 1 and 100 percent, got {}", size_pct));
+    }
+    let margin_pct = margin_pct.clamp(0.0, 25.0);
+    let (cw, ch) = canvas;
+    let margin = cw.min(ch) * margin_pct / 100.0;
+
+    let mut t = get_transform(conn, scene, item_id).await?;
+    let (src_w, src_h) = cropped_source_size(&t);
+    let box_w = cw * size_pct / 100.0;
+    let box_h = if src_w > 0.0 && src_h > 0.0 {
+        box_w * src_h / src_w
+    } else {
+        ch * size_pct / 100.0
+    };
+
+    t["alignment"] = json!(alignment_flags(fx, fy));
+    t["positionX"] = json!(margin + fx * (cw - 2.0 * margin));
+    t["positionY"] = json!(margin + fy * (ch - 2.0 * margin));
+    t["boundsType"] = json!("OBS_BOUNDS_SCALE_INNER");
+    t["boundsAlignment"] = json!(0);
+    t["boundsWidth"] = json!(box_w);
+    t["boundsHeight"] = json!(box_h);
+    apply_transform(conn, scene, item_id, &t).await
+}
+
+pub async fn fit_item(
+    conn: &ObsHandle,
+    canvas: (f64, f64),
+    scene: &str,
+    item_id: u64,
+    mode: &str,
+) -> Result<Value, String> {
+    let bounds_type = match mode {
+        "fit" => "OBS_BOUNDS_SCALE_INNER",
+        "fill" => "OBS_BOUNDS_SCALE_OUTER",
+        "stretch" => "OBS_BOUNDS_STRETCH",
+        other => return Err(format!("Unknown fit mode '{}', expected fit, fill or stretch", other)),
+    };
+    let (cw, ch) = canvas;
+    let mut t = get_transform(conn, scene, item_id).await?;
+    t["alignment"] = json!(0);
+    t["positionX"] = json!(cw / 2.0);
+    t["positionY"] = json!(ch / 2.0);
+    t["boundsType"] = json!(bounds_type);
+    t["boundsAlignment"] = json!(0);
+    t["boundsWidth"] = json!(cw);
+    t["boundsHeight"] = json!(ch);
+    apply_transform(conn, scene, item_id, &t).await
+}
+
+/// Lines items up with the first one in `item_ids` along `axis`.
+pub async fn align_items(conn: &ObsHandle, scene: &str, item_ids: &[u64], axis: &str) -> Result<Vec<Value>, String> {
+    if item_ids.len() < 2 {
+        return Err("Select at least two scene items to align".into());
+    }
+    if !ALIGN_AXES.contains(&axis) {
+        return Err(format!("Unknown axis '{}', expected one of: {}", axis, ALIGN_AXES.join(", ")));
+    }
+    let mut transforms = Vec::with_capacity(item_ids.len());
+    for id in item_ids {
+        transforms.push(get_transform(conn, scene, *id).await?);
+    }
+
+    // Edge (or center) coordinate of a box on the chosen axis.
+    let edge = |(left, top, w, h): (f64, f64, f64, f64)| match axis {
+        "left" => left,
+        "center-x" => left + w / 2.0,
+        "right" => left + w,
+        "top" => top,
+        "center-y" => top + h / 2.0,
+        _ => top + h,
+    };
+    let vertical = matches!(axis, "top" | "center-y" | "bottom");
+    let target = edge(item_box(&transforms[0]));
+
+    let mut applied = Vec::with_capacity(item_ids.len());
+    for (id, mut t) in item_ids.iter().zip(transforms) {
+        let delta = target - edge(item_box(&t));
+        let key = if vertical { "positionY" } else { "positionX" };
+        t[key] = json!(t[key].as_f64().unwrap_or(0.0) + delta);
+        applied.push(apply_transform(conn, scene, *id, &t).await?);
+    }
+    Ok(applied)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicatedItem {
+    pub scene_item_id: u64,
+    pub input_name: String,
+}
+
+/// First free name of the form "{base}", "{base} 2", "{base} 3"... Scenes
+/// share the source namespace, so their names count as taken too.
+async fn unique_source_name(conn: &ObsHandle, base: &str) -> Result<String, String> {
+    let inputs = conn.send_request("GetInputList", None).await?;
+    let scenes = conn.send_request("GetSceneList", None).await?;
+    let mut taken: Vec<String> = Vec::new();
+    for (list, key) in [(&inputs["inputs"], "inputName"), (&scenes["scenes"], "sceneName")] {
+        if let Some(arr) = list.as_array() {
+            taken.extend(arr.iter().filter_map(|v| v[key].as_str().map(String::from)));
+        }
+    }
+    let mut candidate = base.to_string();
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{} {}", base, n);
+        n += 1;
+    }
+    Ok(candidate)
+}
+
+/// Places `item_id` from `from_scene` into `to_scene` with the same transform.
+/// A reference shows the same source; otherwise the input is duplicated as
+/// "{name} copy" with its settings, so the two can be changed independently.
+pub async fn duplicate_item(
+    conn: &ObsHandle,
+    from_scene: &str,
+    item_id: u64,
+    to_scene: &str,
+    as_reference: bool,
+) -> Result<DuplicatedItem, String> {
+    let source = conn
+        .send_request(
+            "GetSceneItemSource",
+            Some(json!({"sceneName": from_scene, "sceneItemId": item_id})),
+        )
+        .await?;
+    let source_name = source["sourceName"].as_str().ok_or("Scene item has no source")?.to_string();
+    let transform = get_transform(conn, from_scene, item_id).await?;
+
+    let (input_name, created) = if as_reference {
+        let created = conn
+            .send_request(
+                "CreateSceneItem",
+                Some(json!({"sceneName": to_scene, "sourceName": source_name})),
+            )
+            .await?;
+        (source_name.clone(), created)
+    } else {
+        if is_scene_or_group(conn, &source_name).await? {
+            return Err(format!("\"{}\" is a scene or group and can only be added as a reference", source_name));
+        }
+        let info = conn
+            .send_request("GetInputSettings", Some(json!({"inputName": source_name})))
+            .await?;
+        let input_name = unique_source_name(conn, &format!("{} copy", source_name)).await?;
+        let created = conn
+            .send_request(
+                "CreateInput",
+                Some(json!({
+                    "sceneName": to_scene,
+                    "inputName": input_name,
+                    "inputKind": info["inputKind"],
+                    "inputSettings": info["inputSettings"],
+                })),
+            )
+            .await?;
+        (input_name, created)
+    };
+
+    let placed = match created["sceneItemId"].as_u64() {
+        Some(id) => apply_transform(conn, to_scene, id, &transform).await.map(|_| id),
+        None => Err("OBS did not return a scene item id".to_string()),
+    };
+    match placed {
+        Ok(scene_item_id) => Ok(DuplicatedItem {
+            scene_item_id,
+            input_name,
+        }),
+        Err(e) => {
+            // Don't leave a half-made copy behind.
+            let cleanup = if as_reference {
+                created["sceneItemId"].as_u64().map(|id| {
+                    ("RemoveSceneItem", json!({"sceneName": to_scene, "sceneItemId": id}))
+                })
+            } else {
+                Some(("RemoveInput", json!({"inputName": input_name})))
+            };
+            if let Some((request, data)) = cleanup {
+                if let Err(cleanup_err) = conn.send_request(request, Some(data)).await {
+                    log::warn!("Failed to remove the partial duplicate of \"{}\": {}", source_name, cleanup_err);
+                }
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn is_scene_or_group(conn: &ObsHandle, name: &str) -> Result<bool, String> {
+    let scenes = conn.send_request("GetSceneList", None).await?;
+    let groups = conn.send_request("GetGroupList", None).await?;
+    let in_scenes = scenes["scenes"]
+        .as_array()
+        .is_some_and(|a| a.iter().any(|s| s["sceneName"].as_str() == Some(name)));
+    let in_groups = groups["groups"]
+        .as_array()
+        .is_some_and(|a| a.iter().any(|g| g.as_str() == Some(name)));
+    Ok(in_scenes || in_groups)
+}
+
+// --- AI actions ---
+
+async fn resolve_item_id(conn: &ObsHandle, scene: &str, params: &Value, key: &str) -> Result<u64, String> {
+    if let Some(id) = params["sceneItemId"].as_u64() {
+        return Ok(id);
+    }
+    let source = params[key].as_str().ok_or_else(|| format!("Missing {}", key))?;
+    lookup_item_id(conn, scene, source).await
+}
+
+async fn lookup_item_id(conn: &ObsHandle, scene: &str, source: &str) -> Result<u64, String> {
+    let data = conn
+        .send_request(
+            "GetSceneItemId",
+            Some(json!({"sceneName": scene, "sourceName": source})),
+        )
+        .await
+        .map_err(|e| format!("Source \"{}\" not found in scene \"{}\": {}", source, scene, e))?;
+    data["sceneItemId"]
+        .as_u64()
+        .ok_or_else(|| format!("Source \"{}\" not found in scene \"{}\"", source, scene))
+}
+
+async fn action_item_ids(conn: &ObsHandle, scene: &str, action: &AiAction) -> Result<Vec<u64>, String> {
+    if action.request_type != "align" {
+        return Ok(vec![resolve_item_id(conn, scene, &action.params, "sourceName").await?]);
+    }
+    let names = action.params["sourceNames"]
+        .as_array()
+        .ok_or("Missing sourceNames")?;
+    let mut ids = Vec::with_capacity(names.len());
+    for name in names {
+        let name = name.as_str().ok_or("sourceNames must be strings")?;
+        ids.push(lookup_item_id(conn, scene, name).await?);
+    }
+    Ok(ids)
+}
+
+pub async fn dispatch(action: &AiAction, conn: &ObsHandle) -> Result<(), String> {
+    let p = &action.params;
+    let scene = p["sceneName"].as_str().ok_or("Missing sceneName")?;
+    match action.request_type.as_str() {
+        "position" => {
+            let item_id = resolve_item_id(conn, scene, p, "sourceName").await?;
+            let canvas = fetch_canvas(conn).await?;
+            let anchor = p["anchor"].as_str().ok_or("Missing anchor")?;
+            let margin = p["marginPct"].as_f64().unwrap_or(DEFAULT_MARGIN_PCT);
+            let size = p["sizePct"].as_f64().ok_or("Missing sizePct")?;
+            position_item(conn, canvas, scene, item_id, anchor, margin, size).await?;
+        }
+        "fit" => {
+            let item_id = resolve_item_id(conn, scene, p, "sourceName").await?;
+            let canvas = fetch_canvas(conn).await?;
+            let mode = p["mode"].as_str().unwrap_or("fit");
+            fit_item(conn, canvas, scene, item_id, mode).await?;
+        }
+        "align" => {
+            let ids = action_item_ids(conn, scene, action).await?;
+            let axis = p["axis"].as_str().ok_or("Missing axis")?;
+            align_items(conn, scene, &ids, axis).await?;
+        }
+        "duplicate" => {
+            let item_id = resolve_item_id(conn, scene, p, "sourceName").await?;
+            let to_scene = p["toSceneName"].as_str().unwrap_or(scene);
+            let as_reference = p["asReference"].as_bool().unwrap_or(true);
+            let dup = duplicate_item(conn, scene, item_id, to_scene, as_reference).await?;
+            if let Some(anchor) = p["anchor"].as_str() {
+                let canvas = fetch_canvas(conn).await?;
+                let size = match p["sizePct"].as_f64() {
+                    Some(size) => size,
+                    // Keep the copy's current width when only a corner was asked for.
+                    None => {
+                        let t = get_transform(conn, to_scene, dup.scene_item_id).await?;
+                        (item_box(&t).2 / canvas.0 * 100.0).clamp(1.0, 100.0)
+                    }
+                };
+                let margin = p["marginPct"].as_f64().unwrap_or(DEFAULT_MARGIN_PCT);
+                position_item(conn, canvas, to_scene, dup.scene_item_id, anchor, margin, size).await?;
+            }
+        }
+        "restore" => {
+            let saved = p["transforms"].as_array().ok_or("Missing transforms")?;
+            for entry in saved {
+                let item_id = entry["sceneItemId"].as_u64().ok_or("Missing sceneItemId")?;
+                apply_transform(conn, scene, item_id, &entry["sceneItemTransform"]).await?;
+            }
+        }
+        other => return Err(format!("Unknown layout action: {}", other)),
+    }
+    Ok(())
+}
+
+/// Captures the current transform of every item the action will touch, as a
+/// "restore" layout action.
+pub async fn snapshot_for_undo(action: &AiAction, conn: &ObsHandle) -> Option<crate::ai_actions::UndoEntry> {
+    // A duplicate adds an item rather than moving one; there's nothing to restore.
+    if action.request_type == "duplicate" {
+        return None;
+    }
+    let scene = action.params["sceneName"].as_str()?;
+    let ids = action_item_ids(conn, scene, action).await.ok()?;
+    let mut transforms = Vec::with_capacity(ids.len());
+    for id in ids {
+        let t = get_transform(conn, scene, id).await.ok()?;
+        transforms.push(json!({"sceneItemId": id, "sceneItemTransform": t}));
+    }
+    Some(crate::ai_actions::UndoEntry {
+        description: format!("Revert layout change in \"{}\"", scene),
+        action_type: "layout".into(),
+        request_type: "restore".into(),
+        revert_params: json!({"sceneName": scene, "transforms": transforms}),
+    })
+}
+
+// --- Commands ---
+
+async fn state_canvas(obs_state: &SharedObsState) -> Result<(f64, f64), String> {
+    let s = obs_state.read().await;
+    canvas_from_state(s.video_settings.base_width, s.video_settings.base_height)
+}
+
+#[tauri::command]
+pub async fn position_scene_item(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    scene_name: String,
+    scene_item_id: u64,
+    anchor: String,
+    margin_pct: Option<f64>,
+    size_pct: f64,
+) -> Result<Value, String> {
+    let canvas = state_canvas(&obs_state).await?;
+    let conn = conn_state.inner().clone();
+    position_item(&conn, canvas, &scene_name, scene_item_id, &anchor, margin_pct.unwrap_or(DEFAULT_MARGIN_PCT), size_pct).await
+}
+
+#[tauri::command]
+pub async fn fit_scene_item(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    scene_name: String,
+    scene_item_id: u64,
+    mode: String,
+) -> Result<Value, String> {
+    let canvas = state_canvas(&obs_state).await?;
+    let conn = conn_state.inner().clone();
+    fit_item(&conn, canvas, &scene_name, scene_item_id, &mode).await
+}
+
+#[tauri::command]
+pub async fn align_scene_items(
+    conn_state: tauri::State<'_, ObsHandle>,
+    scene_name: String,
+    scene_item_ids: Vec<u64>,
+    axis: String,
+) -> Result<Vec<Value>, String> {
+    let conn = conn_state.inner().clone();
+    align_items(&conn, &scene_name, &scene_item_ids, &axis).await
+}
+
+#[tauri::command]
+pub async fn duplicate_scene_item(
+    conn_state: tauri::State<'_, ObsHandle>,
+    from_scene: String,
+    item_id: u64,
+    to_scene: String,
+    as_reference: bool,
+) -> Result<DuplicatedItem, String> {
+    let conn = conn_state.inner().clone();
+    duplicate_item(&conn, &from_scene, item_id, &to_scene, as_reference).await
+}
@@ -1,89 +1,200 @@
+mod action_validation;
 mod ai_actions;
+mod ai_metrics;
+mod api_key_store;
+mod alerts;
+mod audio_alerts;
 mod app_capture;
 mod audio;
 mod audio_monitor;
+mod audio_router;
+mod browser_sources;
+mod chat_history;
+mod chat_provider;
 mod commands;
+mod control_server;
+mod device_matching;
+mod disk_headroom;
 mod ducking;
+mod endpoint_volume;
+mod event_subscriptions;
+mod filter_bypass;
+mod filter_history;
+mod filter_schema;
 mod gemini;
+mod i18n;
+mod input_prefs;
+mod layout;
+mod loudness;
+mod macros;
+mod media;
 mod narration_capture;
 mod obs_config;
+mod openai_compat;
 mod pad_capture;
+mod obs_backup;
+mod obs_health;
+mod obs_hotkeys;
 mod obs_launcher;
 mod obs_state;
 mod obs_websocket;
+mod panic;
+mod plugin_check;
 mod preflight;
 mod presets;
+mod projectors;
+mod rename_cascade;
+mod screenshot_cache;
 mod routing;
+mod settings;
+mod shortcuts;
+mod shutdown;
 mod store;
 mod system_monitor;
+mod teleprompter;
+mod text_sources;
+mod timers;
+mod track_preview;
+mod transitions;
 mod tray;
+mod source_schemas;
 mod spectrum;
+mod stream_health;
+mod stream_service;
 mod video_devices;
 mod video_editor;
+mod voice;
+mod volume_guard;
 mod vst_manager;
+mod ws_log;
 
-use ai_actions::SharedUndoStack;
-use audio_monitor::SharedAudioMetrics;
+use ai_actions::{SharedAiEditSession, SharedUndoStack};
+use ai_metrics::SharedAiMetrics;
+use audio_monitor::{SharedAudioHistory, SharedAudioMetrics, SharedMetricsNotify};
+use audio_router::SharedAudioRouter;
 use commands::SharedObsConnection;
+use control_server::SharedControlServer;
 use ducking::SharedDuckingConfig;
+use endpoint_volume::SharedEndpointVolumes;
+use filter_bypass::SharedFilterBypass;
+use filter_history::SharedFilterHistory;
+use filter_schema::SharedFilterDefaults;
+use loudness::SharedLoudness;
+use track_preview::SharedTrackPreview;
 use gemini::SharedGeminiClient;
+use macros::SharedMacroState;
 use narration_capture::SharedNarrationCaptureState;
+use obs_health::SharedObsHealth;
 use obs_state::SharedObsState;
 use pad_capture::SharedPadCaptureState;
-use obs_websocket::ObsConnection;
+use panic::SharedPanicState;
+use rename_cascade::SharedRenameCascade;
+use screenshot_cache::SharedScreenshotCache;
+use settings::SharedSettings;
+use shortcuts::SharedShortcuts;
+use shutdown::SharedShutdownGuard;
+use obs_websocket::{ObsConnection, ObsHandle};
 use spectrum::SharedSpectrumState;
 use store::SharedLicenseState;
+use teleprompter::SharedTeleprompter;
+use timers::SharedTimers;
 use video_editor::SharedVideoEditorState;
+use voice::SharedVoiceCapture;
+use volume_guard::SharedVolumeGuardState;
 use std::sync::Arc;
-use tauri::{Emitter, Manager};
+use tauri::Manager;
 use tokio::sync::{Mutex, RwLock};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let gemini_client: Option<gemini::GeminiClient> =
-        std::env::var("GEMINI_API_KEY").ok().and_then(|key| {
-            if key.is_empty() {
-                None
-            } else {
-                Some(gemini::GeminiClient::new(key))
-            }
-        });
+    let mut app_settings = settings::load_settings_from_disk();
+    if app_settings.locale.is_empty() {
+        app_settings.locale = i18n::resolve(&i18n::detect_system_locale()).to_string();
+        if let Err(e) = settings::save_settings_to_disk(&app_settings) {
+            log::warn!("Failed to save detected locale: {}", e);
+        }
+    }
+    i18n::apply_locale(&app_settings.locale);
+
+    // The provider's env var (GEMINI_API_KEY, OPENAI_API_KEY) wins over a
+    // key saved from the settings panel.
+    let gemini_client = chat_provider::from_settings(&app_settings.ai);
+
+    let app_shortcuts = shortcuts::from_settings(&app_settings.hotkeys);
+    let panic_snapshot = panic::load();
+    let panic_engaged = panic_snapshot.is_some();
 
+    let obs_hotkey_bindings = app_settings.hotkeys.obs_bindings.clone();
+
+    store::record_launch();
     let license_state = store::load_license_from_disk();
     log::info!(
         "License loaded: {} modules owned",
         license_state.owned_modules.len()
     );
 
+    let obs_connection = ObsConnection::new();
+    let obs_handle = obs_connection.handle();
+    let obs_health = obs_connection.health();
+    obs_connection.set_auto_reconnect(app_settings.connection.auto_reconnect);
+    obs_connection.subscriptions().lock().unwrap().declare(
+        "volume-guard",
+        volume_guard::meter_flags(&app_settings.volume_guards),
+    );
+    // Keeps the transforms in ObsState current for the AI's layout context.
+    obs_connection.subscriptions().lock().unwrap().declare(
+        "scene-layout",
+        event_subscriptions::SCENE_ITEM_TRANSFORM_CHANGED,
+    );
+
     tauri::Builder::default()
-        .manage(Arc::new(Mutex::new(ObsConnection::new())) as SharedObsConnection)
+        .manage(Arc::new(Mutex::new(obs_connection)) as SharedObsConnection)
+        .manage(obs_handle)
+        .manage(obs_health as SharedObsHealth)
         .manage(Arc::new(RwLock::new(obs_state::ObsState::new())) as SharedObsState)
         .manage(Arc::new(RwLock::new(gemini_client)) as SharedGeminiClient)
-        .manage(Arc::new(RwLock::new(Vec::<ai_actions::UndoEntry>::new())) as SharedUndoStack)
+        .manage(Arc::new(RwLock::new(ai_actions::UndoStack::load())) as SharedUndoStack)
+        .manage(Arc::new(Mutex::new(ai_actions::AiEditSession::default())) as SharedAiEditSession)
+        .manage(Arc::new(std::sync::Mutex::new(ai_metrics::AiMetrics::new())) as SharedAiMetrics)
+        .manage(Arc::new(Mutex::new(macros::MacroState::default())) as SharedMacroState)
         .manage(Arc::new(RwLock::new(audio_monitor::AudioMetrics::default())) as SharedAudioMetrics)
-        .manage(Arc::new(RwLock::new(ducking::DuckingConfig::default())) as SharedDuckingConfig)
+        .manage(Arc::new(std::sync::Mutex::new(loudness::LoudnessMeters::default())) as SharedLoudness)
+        .manage(Arc::new(std::sync::Mutex::new(audio_monitor::AudioHistory::default())) as SharedAudioHistory)
+        .manage(Arc::new(audio_monitor::MetricsNotify::default()) as SharedMetricsNotify)
+        .manage(Arc::new(std::sync::Mutex::new(endpoint_volume::EndpointVolumes::new())) as SharedEndpointVolumes)
+        .manage(Arc::new(RwLock::new(app_settings.ducking.clone())) as SharedDuckingConfig)
         .manage(Arc::new(Mutex::new(spectrum::SpectrumState::new())) as SharedSpectrumState)
-        .manage(Arc::new(Mutex::new(video_editor::VideoEditorState::new())) as SharedVideoEditorState)
+        .manage(Arc::new(Mutex::new(audio_router::AudioRouter::default())) as SharedAudioRouter)
+        .manage(Arc::new(Mutex::new(
+            video_editor::VideoEditorState::new()
+                .with_saved_ffmpeg(app_settings.video_editor.ffmpeg_path.as_deref()),
+        )) as SharedVideoEditorState)
         .manage(Arc::new(RwLock::new(license_state)) as SharedLicenseState)
         .manage(Arc::new(Mutex::new(narration_capture::NarrationCaptureState::new())) as SharedNarrationCaptureState)
         .manage(Arc::new(Mutex::new(pad_capture::PadCaptureState::new())) as SharedPadCaptureState)
+        .manage(Arc::new(Mutex::new(None)) as SharedVoiceCapture)
+        .manage(Arc::new(RwLock::new(app_settings)) as SharedSettings)
+        .manage(Arc::new(Mutex::new(std::collections::HashMap::new())) as SharedFilterBypass)
+        .manage(Arc::new(Mutex::new(std::collections::HashMap::new())) as SharedFilterDefaults)
+        .manage(Arc::new(std::sync::Mutex::new(filter_history::FilterHistory::default())) as SharedFilterHistory)
+        .manage(Arc::new(Mutex::new(None)) as SharedTrackPreview)
+        .manage(Arc::new(Mutex::new(teleprompter::Teleprompter::default())) as SharedTeleprompter)
+        .manage(Arc::new(Mutex::new(timers::Timers::load())) as SharedTimers)
+        .manage(Arc::new(Mutex::new(panic_snapshot)) as SharedPanicState)
+        .manage(Arc::new(std::sync::Mutex::new(shutdown::ShutdownGuard::default())) as SharedShutdownGuard)
+        .manage(Arc::new(std::sync::Mutex::new(rename_cascade::RenameCascade::default())) as SharedRenameCascade)
+        .manage(Arc::new(std::sync::Mutex::new(app_shortcuts)) as SharedShortcuts)
+        .manage(Arc::new(std::sync::Mutex::new(volume_guard::GuardRuntime::new())) as SharedVolumeGuardState)
+        .manage(Arc::new(Mutex::new(None)) as SharedControlServer)
+        .manage(Arc::new(std::sync::Mutex::new(screenshot_cache::ScreenshotCache::default())) as SharedScreenshotCache)
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_process::init())
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(|app, shortcut, event| {
-                    use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut, ShortcutState};
-                    let ptt = Shortcut::new(Some(Modifiers::CONTROL), Code::Space);
-                    if shortcut == &ptt {
-                        match event.state() {
-                            ShortcutState::Pressed => {
-                                let _ = app.emit("voice://ptt-start", ());
-                            }
-                            ShortcutState::Released => {
-                                let _ = app.emit("voice://ptt-stop", ());
-                            }
-                        }
+                .with_handler(move |app, shortcut, event| {
+                    if !shortcuts::on_shortcut(app, shortcut, event.state()) {
+                        obs_hotkeys::on_shortcut(app, shortcut, event.state());
                     }
                 })
                 .build(),
@@ -91,12 +202,21 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             commands::connect_obs,
             commands::disconnect_obs,
+            commands::set_auto_reconnect,
+            commands::get_missing_plugins_report,
             commands::get_obs_status,
+            commands::get_connection_health,
             commands::get_obs_state,
             commands::get_audio_devices,
             commands::get_scene_list,
             commands::get_stats,
             commands::set_input_volume,
+            volume_guard::set_volume_guard,
+            volume_guard::remove_volume_guard,
+            volume_guard::get_volume_guards,
+            input_prefs::get_input_preferences,
+            input_prefs::set_input_hidden,
+            input_prefs::set_input_active_in_mix,
             commands::set_input_mute,
             commands::toggle_input_mute,
             commands::get_input_audio_balance,
@@ -105,13 +225,35 @@ pub fn run() {
             commands::set_input_audio_sync_offset,
             commands::get_input_audio_tracks,
             commands::set_input_audio_tracks,
+            commands::get_source_filters,
+            commands::refresh_input,
             commands::create_source_filter,
             commands::set_source_filter_enabled,
+            filter_bypass::bypass_filter_chain,
+            filter_bypass::restore_filter_chain,
+            filter_bypass::get_bypassed_sources,
+            filter_bypass::toggle_filter_group,
+            filter_schema::get_filter_kind_defaults,
+            filter_schema::describe_filter,
+            filter_history::get_filter_history,
+            filter_history::restore_filter_snapshot,
+            track_preview::solo_track_preview,
+            track_preview::stop_track_preview,
             commands::remove_source_filter,
             commands::get_windows_volume,
             commands::set_windows_volume,
             commands::set_windows_mute,
             commands::run_preflight,
+            commands::run_speed_test,
+            stream_health::get_stream_health,
+            shortcuts::get_shortcuts,
+            shortcuts::set_shortcut,
+            obs_hotkeys::get_obs_hotkey_list,
+            obs_hotkeys::trigger_obs_hotkey,
+            obs_hotkeys::get_obs_hotkey_bindings,
+            obs_hotkeys::bind_obs_hotkey,
+            obs_hotkeys::unbind_obs_hotkey,
+            disk_headroom::get_recording_headroom,
             commands::get_system_resources,
             commands::get_displays,
             commands::refresh_video_settings,
@@ -120,25 +262,94 @@ pub fn run() {
             commands::set_input_audio_monitor_type,
             commands::get_input_audio_monitor_type,
             commands::create_input,
+            commands::get_input_kind_schema,
+            commands::validate_input_settings,
             commands::get_routing_recommendations,
             commands::apply_recommended_setup,
             commands::get_obs_audio_config,
             commands::set_obs_audio_config,
+            commands::get_obs_output_config,
+            commands::set_obs_output_config,
+            commands::validate_obs_output_config,
+            commands::get_websocket_settings,
+            commands::enable_websocket_server,
+            obs_backup::backup_obs_config,
+            obs_backup::list_obs_backups,
+            obs_backup::restore_obs_config,
             commands::send_chat_message,
             commands::confirm_dangerous_action,
             commands::get_smart_presets,
+            commands::save_user_preset,
+            commands::export_current_chain_as_preset,
+            commands::delete_user_preset,
+            commands::preview_preset,
             commands::apply_preset,
             commands::undo_last_action,
-            commands::set_gemini_api_key,
+            commands::undo_action_group,
+            commands::get_undo_history,
+            macros::list_macros,
+            macros::save_macro,
+            macros::delete_macro,
+            macros::run_macro,
+            macros::cancel_macro,
+            macros::record_macro_start,
+            macros::record_macro_stop,
+            commands::set_ai_provider,
             commands::check_ai_status,
+            commands::clear_ai_api_key,
+            ai_metrics::get_ai_performance_metrics,
+            commands::set_ai_function_calling,
+            commands::get_chat_history,
+            commands::list_chat_sessions,
+            commands::clear_chat_history,
+            commands::set_ai_preferences,
+            commands::get_ai_preferences,
             commands::set_current_scene,
             commands::create_scene,
             commands::remove_scene,
             commands::rename_scene,
             commands::get_scene_screenshot,
+            commands::get_studio_mode_enabled,
+            commands::set_studio_mode_enabled,
+            commands::set_current_preview_scene,
+            commands::trigger_studio_mode_transition,
+            projectors::get_monitor_list,
+            projectors::open_projector,
+            transitions::get_transition_list,
+            transitions::set_current_transition,
+            transitions::set_transition_duration,
+            transitions::transition_to_scene,
+            transitions::get_transition_settings,
+            media::get_media_status,
+            media::media_play,
+            media::media_pause,
+            media::media_restart,
+            media::media_stop,
+            media::media_seek,
+            text_sources::get_text_source,
+            text_sources::set_text_source,
+            text_sources::create_text_source,
+            browser_sources::refresh_browser_source,
+            browser_sources::set_browser_source_url,
+            browser_sources::create_browser_source,
+            screenshot_cache::get_all_scene_screenshots,
             commands::toggle_stream,
             commands::toggle_record,
+            commands::pause_record,
+            commands::resume_record,
+            commands::split_record_file,
+            commands::get_record_status,
+            commands::toggle_replay_buffer,
+            commands::start_replay_buffer,
+            commands::stop_replay_buffer,
+            commands::save_replay_buffer,
+            commands::get_replay_buffer_status,
+            commands::get_stream_service_settings,
+            commands::get_stream_presets,
+            commands::set_stream_service_settings,
+            commands::test_stream_destination,
             commands::launch_obs,
+            commands::launch_and_connect,
             commands::is_obs_running,
             commands::set_source_filter_settings,
             commands::set_source_filter_index,
@@ -146,13 +357,21 @@ pub fn run() {
             commands::rename_input,
             commands::get_vst_status,
             commands::install_vsts,
+            commands::repair_vsts,
             commands::get_vst_catalog,
             commands::download_vst,
+            commands::scan_user_vsts,
+            commands::add_vst_search_path,
+            commands::remove_vst_search_path,
             commands::get_audio_metrics,
+            audio_monitor::get_audio_history,
+            audio_monitor::get_clipping_events,
             commands::get_source_filter_kinds,
             commands::get_ducking_config,
             commands::set_ducking_config,
             commands::get_audio_processes,
+            commands::set_app_volume,
+            commands::set_app_mute,
             commands::add_app_capture,
             commands::remove_app_capture,
             commands::get_video_devices,
@@ -162,17 +381,50 @@ pub fn run() {
             commands::get_virtual_cam_status,
             commands::ensure_virtual_cam_program,
             commands::set_scene_item_transform,
+            commands::get_scene_items,
+            commands::set_scene_item_enabled,
+            commands::remove_scene_item,
+            commands::set_scene_item_index,
+            layout::position_scene_item,
+            layout::fit_scene_item,
+            layout::align_scene_items,
+            layout::duplicate_scene_item,
             commands::auto_setup_cameras,
             commands::open_source_properties,
             commands::open_devtools,
+            commands::set_ws_logging,
+            commands::get_ws_log,
+            commands::clear_ws_log,
+            commands::export_ws_log,
             spectrum::start_spectrum,
             spectrum::stop_spectrum,
+            spectrum::get_spectrum_config,
+            spectrum::start_input_spectrum,
+            spectrum::stop_input_spectrum,
             spectrum::reset_lufs,
+            audio_router::route_input_to_device,
+            audio_router::stop_route,
+            audio_router::list_active_routes,
+            teleprompter::load_script,
+            teleprompter::start_teleprompter,
+            teleprompter::stop_teleprompter,
+            teleprompter::pause_teleprompter,
+            teleprompter::resume_teleprompter,
+            teleprompter::set_teleprompter_speed,
+            teleprompter::jump_to_marker,
+            timers::create_timer,
+            timers::delete_timer,
+            timers::start_timer,
+            timers::pause_timer,
+            timers::reset_timer,
+            timers::list_timers,
             video_editor::detect_ffmpeg,
             video_editor::list_recordings,
             video_editor::remux_to_mp4,
             video_editor::get_video_info,
             video_editor::get_video_thumbnail,
+            video_editor::request_thumbnails,
+            video_editor::cancel_thumbnail_requests,
             video_editor::open_file_location,
             video_editor::delete_recording,
             video_editor::preview_edit,
@@ -191,12 +443,26 @@ pub fn run() {
             video_editor::export_srt,
             video_editor::save_narration_audio,
             video_editor::save_pad_sample,
+            video_editor::create_clip,
+            video_editor::detect_silence,
+            video_editor::export_clips,
+            video_editor::export_chapters_file,
+            video_editor::generate_proxy,
+            video_editor::get_proxy_status,
+            video_editor::delete_proxies,
+            video_editor::save_export_preset,
+            video_editor::list_export_presets,
+            video_editor::delete_export_preset,
+            video_editor::export_with_preset,
             narration_capture::check_vb_cable,
             narration_capture::install_vb_cable,
             narration_capture::configure_obs_monitoring_for_vbcable,
             narration_capture::auto_configure_obs_monitoring,
             narration_capture::start_narration_capture,
             narration_capture::stop_narration_capture,
+            voice::start_voice_capture,
+            voice::stop_voice_capture,
+            voice::transcribe_voice,
             pad_capture::get_pad_capture_sources,
             pad_capture::start_pad_capture,
             pad_capture::stop_pad_capture,
@@ -216,16 +482,44 @@ pub fn run() {
             store::deactivate_license,
             store::get_device_fingerprint,
             store::get_stored_license_keys,
+            store::refresh_license,
+            store::start_trial,
+            settings::get_settings,
+            settings::update_settings,
+            control_server::get_control_server_status,
+            control_server::regenerate_control_token,
+            alerts::test_alert,
+            audio_alerts::get_audio_alert_config,
+            audio_alerts::set_audio_alert_config,
+            event_subscriptions::set_event_interest,
+            event_subscriptions::get_active_event_subscriptions,
+            i18n::set_locale,
+            i18n::get_available_locales,
+            shutdown::resolve_close_request,
+            app_capture::set_scene_tags,
+            app_capture::get_scene_tags,
+            app_capture::list_auto_capture_rules,
+            app_capture::add_auto_capture_rule,
+            app_capture::remove_auto_capture_rule,
+            panic::panic_mode,
+            panic::get_panic_status,
         ])
-        .setup(|app| {
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                if window.label() == "main" && shutdown::on_close_requested(window) {
+                    api.prevent_close();
+                }
+            }
+        })
+        .setup(move |app| {
             tray::setup_tray(app.handle())?;
+            if panic_engaged {
+                tray::set_panic(app.handle(), true);
+            }
 
             {
-                use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
-                let ptt = Shortcut::new(Some(Modifiers::CONTROL), Code::Space);
-                if let Err(e) = app.global_shortcut().register(ptt) {
-                    log::warn!("Failed to register PTT shortcut: {}", e);
-                }
+                shortcuts::register_all(app.handle());
+                obs_hotkeys::register_bindings(app.handle(), &obs_hotkey_bindings);
             }
 
             // Auto-install bundled VST plugins
@@ -237,37 +531,109 @@ pub fn run() {
                 Err(e) => log::warn!("VST auto-install failed (non-fatal): {}", e),
             }
 
+            filter_history::start_flush_task(app.state::<SharedFilterHistory>().inner().clone());
+            timers::start_ticker(app.handle().clone());
+            disk_headroom::start_watcher(app.handle().clone());
+            chat_history::start_session();
+            store::start_background_refresh(app.state::<SharedLicenseState>().inner().clone());
+            app_capture::start_watcher(app.handle().clone());
+
+            {
+                let server_app = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = control_server::sync(&server_app).await {
+                        log::warn!("Control server failed to start: {}", e);
+                    }
+                });
+            }
+
             let app_handle = app.handle().clone();
             let obs_state = app.state::<SharedObsState>().inner().clone();
             let audio_metrics = app.state::<SharedAudioMetrics>().inner().clone();
+            let metrics_notify = app.state::<SharedMetricsNotify>().inner().clone();
+            let audio_history = app.state::<SharedAudioHistory>().inner().clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) =
-                    audio_monitor::start_audio_monitor(app_handle, obs_state, audio_metrics).await
+                if let Err(e) = audio_monitor::start_audio_monitor(
+                    app_handle,
+                    obs_state,
+                    audio_metrics,
+                    metrics_notify,
+                    audio_history,
+                )
+                .await
                 {
                     log::error!("Audio monitor failed: {}", e);
                 }
             });
 
+            endpoint_volume::start(
+                app.handle().clone(),
+                app.state::<SharedEndpointVolumes>().inner().clone(),
+            );
+
             {
                 let duck_app = app.handle().clone();
-                let duck_conn = app.state::<SharedObsConnection>().inner().clone();
+                let duck_conn = app.state::<ObsHandle>().inner().clone();
                 let duck_state = app.state::<SharedObsState>().inner().clone();
                 let duck_metrics = app.state::<SharedAudioMetrics>().inner().clone();
+                let duck_notify = app.state::<SharedMetricsNotify>().inner().clone();
                 let duck_config = app.state::<SharedDuckingConfig>().inner().clone();
-                tauri::async_runtime::spawn(async move {
+                let duck_health = app.state::<SharedObsHealth>().inner().clone();
+                let duck_shutdown = app.state::<SharedShutdownGuard>().lock().unwrap().subscribe();
+                let duck_task = tauri::async_runtime::spawn(async move {
                     ducking::start_ducking_loop(
                         duck_app,
                         duck_conn,
+                        duck_health,
                         duck_state,
                         duck_metrics,
+                        duck_notify,
                         duck_config,
+                        duck_shutdown,
                     )
                     .await;
                 });
+                app.state::<SharedShutdownGuard>().lock().unwrap().register("ducking", |_| async move {
+                    let _ = duck_task.await;
+                });
             }
 
+            {
+                let guard = app.state::<SharedShutdownGuard>();
+                let mut guard = guard.lock().unwrap();
+                guard.register("filter-bypass", filter_bypass::shutdown);
+                guard.register("track-preview", track_preview::shutdown);
+                guard.register("audio-router", audio_router::shutdown);
+                guard.register("loudness", loudness::shutdown);
+                guard.register("teleprompter", teleprompter::shutdown);
+                guard.register("export", video_editor::shutdown);
+            }
+
+            {
+                let cascade = app.state::<SharedRenameCascade>();
+                let mut cascade = cascade.lock().unwrap();
+                cascade.register("ducking", ducking::on_input_renamed);
+                cascade.register("volume-guard", volume_guard::on_input_renamed);
+                cascade.register("input-preferences", input_prefs::on_input_renamed);
+                cascade.register("macros", macros::on_input_renamed);
+                cascade.register("timers", timers::on_input_renamed);
+                cascade.register("teleprompter", teleprompter::on_input_renamed);
+                cascade.register("filter-history", filter_history::on_input_renamed);
+                cascade.register("audio-router", audio_router::on_input_renamed);
+                cascade.register("track-preview", track_preview::on_input_renamed);
+                cascade.register("filter-bypass", filter_bypass::on_input_renamed);
+            }
+            rename_cascade::start(app.handle());
+
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                shutdown::run_blocking(app);
+                endpoint_volume::shutdown(app);
+                filter_history::flush_blocking(app.state::<SharedFilterHistory>().inner());
+            }
+        });
 }
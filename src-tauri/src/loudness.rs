@@ -0,0 +1,274 @@
+use crate::audio;
+use crate::device_matching;
+use crate::obs_state::SharedObsState;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// One ebur128 meter per audio device an OBS input uses, each on its own
+/// WASAPI capture thread (loopback for output devices).
+pub type SharedLoudness = Arc<Mutex<LoudnessMeters>>;
+
+/// Bumped by reset_lufs; every meter, the spectrum view's included, resets
+/// its integrated value when it sees a new generation.
+static RESET_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// What ebur128 reports for silence and for windows that aren't full yet.
+pub const LUFS_FLOOR: f64 = -70.0;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoudnessReading {
+    /// 400 ms window.
+    pub momentary: f64,
+    /// 3 s window.
+    pub short_term: f64,
+    /// Gated, since the meter started or was last reset.
+    pub integrated: f64,
+    /// Linear RMS of the samples since the previous reading, all channels.
+    pub rms: f32,
+}
+
+struct Meter {
+    stop: Arc<AtomicBool>,
+    /// None until the first reading, and again once the meter has failed.
+    reading: Arc<Mutex<Option<LoudnessReading>>>,
+}
+
+#[derive(Default)]
+pub struct LoudnessMeters {
+    meters: HashMap<String, Meter>,
+}
+
+impl LoudnessMeters {
+    /// Starts meters for new devices and stops the ones no longer wanted.
+    /// `wanted` is (device id, is an output device).
+    pub fn sync(&mut self, wanted: &[(String, bool)]) {
+        self.meters.retain(|id, meter| {
+            let keep = wanted.iter().any(|(w, _)| w == id);
+            if !keep {
+                meter.stop.store(true, Ordering::Relaxed);
+            }
+            keep
+        });
+        for (id, loopback) in wanted {
+            if self.meters.contains_key(id) {
+                continue;
+            }
+            let meter = Meter {
+                stop: Arc::new(AtomicBool::new(false)),
+                reading: Arc::new(Mutex::new(None)),
+            };
+            spawn_meter(id.clone(), *loopback, meter.stop.clone(), meter.reading.clone());
+            self.meters.insert(id.clone(), meter);
+        }
+    }
+
+    pub fn readings(&self) -> HashMap<String, LoudnessReading> {
+        self.meters
+            .iter()
+            .filter_map(|(id, m)| m.reading.lock().ok().and_then(|r| *r).map(|r| (id.clone(), r)))
+            .collect()
+    }
+
+    pub fn stop_all(&mut self) {
+        self.sync(&[]);
+    }
+}
+
+/// Stops every meter thread on exit.
+pub async fn shutdown(app: tauri::AppHandle) {
+    use tauri::Manager;
+    if let Some(meters) = app.try_state::<SharedLoudness>() {
+        if let Ok(mut meters) = meters.lock() {
+            meters.stop_all();
+        }
+    }
+}
+
+pub fn reset_all() {
+    RESET_GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn reset_generation() -> u64 {
+    RESET_GENERATION.load(Ordering::Relaxed)
+}
+
+/// Devices behind the current OBS audio inputs, with "default" resolved.
+pub async fn wanted_devices(obs_state: &SharedObsState) -> Vec<(String, bool)> {
+    let devices = match tokio::task::spawn_blocking(audio::enumerate_audio_devices).await {
+        Ok(Ok(d)) => d,
+        _ => return Vec::new(),
+    };
+    let state = obs_state.read().await;
+    let mut wanted: Vec<(String, bool)> = Vec::new();
+    for input in state.inputs.values() {
+        let Some(device) = device_matching::resolve_input_device(input, &devices) else {
+            continue;
+        };
+        if !wanted.iter().any(|(id, _)| *id == device.id) {
+            wanted.push((device.id.clone(), device.device_type == "output"));
+        }
+    }
+    wanted
+}
+
+#[cfg(windows)]
+fn clamp_lufs(value: Result<f64, ebur128::Error>) -> f64 {
+    value.ok().filter(|v| v.is_finite()).unwrap_or(LUFS_FLOOR).max(LUFS_FLOOR)
+}
+
+#[cfg(windows)]
+fn spawn_meter(
+    device_id: String,
+    loopback: bool,
+    stop: Arc<AtomicBool>,
+    reading: Arc<Mutex<Option<LoudnessReading>>>,
+) {
+    std::thread::spawn(move || {
+        use windows::Win32::System::Com::*;
+        unsafe {
+            if CoInitializeEx(None, COINIT_MULTITHREADED).ok().is_err() {
+                log::warn!("Loudness: COM init failed for {}", device_id);
+                return;
+            }
+        }
+        if let Err(e) = run_meter(&device_id, loopback, &stop, &reading) {
+            log::warn!("Loudness meter for {} stopped: {}", device_id, e);
+            // A dead meter reads as unknown, not as silence.
+            if let Ok(mut r) = reading.lock() {
+                *r = None;
+            }
+        }
+        unsafe {
+            CoUninitialize();
+        }
+    });
+}
+
+#[cfg(windows)]
+fn run_meter(
+    device_id: &str,
+    loopback: bool,
+    stop: &AtomicBool,
+    reading: &Mutex<Option<LoudnessReading>>,
+) -> Result<(), String> {
+    use std::time::{Duration, Instant};
+    use windows::core::PCWSTR;
+    use windows::Win32::Media::Audio::*;
+    use windows::Win32::System::Com::*;
+
+    let (audio_client, capture_client, channels, sample_rate, bits_per_sample, block_align) = unsafe {
+        let enumerator: IMMDeviceEnumerator = CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+            .map_err(|e| format!("enumerator: {}", e))?;
+        let wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+        let device = enumerator
+            .GetDevice(PCWSTR(wide.as_ptr()))
+            .map_err(|e| format!("GetDevice: {}", e))?;
+        let audio_client: IAudioClient = device
+            .Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Activate IAudioClient: {}", e))?;
+        let mix_format = audio_client
+            .GetMixFormat()
+            .map_err(|e| format!("GetMixFormat: {}", e))?;
+        let fmt = *mix_format;
+        let flags = if loopback { AUDCLNT_STREAMFLAGS_LOOPBACK } else { 0 };
+        let initialized = audio_client.Initialize(AUDCLNT_SHAREMODE_SHARED, flags, 2_000_000, 0, mix_format, None);
+        CoTaskMemFree(Some(mix_format as *const _));
+        initialized.map_err(|e| format!("Initialize: {}", e))?;
+        let capture_client: IAudioCaptureClient = audio_client
+            .GetService()
+            .map_err(|e| format!("GetService IAudioCaptureClient: {}", e))?;
+        audio_client.Start().map_err(|e| format!("Start: {}", e))?;
+        (
+            audio_client,
+            capture_client,
+            fmt.nChannels as usize,
+            fmt.nSamplesPerSec,
+            fmt.wBitsPerSample,
+            fmt.nBlockAlign as usize,
+        )
+    };
+
+    let mode = ebur128::Mode::M | ebur128::Mode::S | ebur128::Mode::I;
+    let mut meter = ebur128::EbuR128::new(channels as u32, sample_rate, mode)
+        .map_err(|e| format!("ebur128 init: {:?}", e))?;
+    let mut generation = reset_generation();
+    let mut last_publish = Instant::now();
+    let mut sum_sq = 0.0f64;
+    let mut sample_count = 0usize;
+
+    while !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(50));
+
+        if reset_generation() != generation {
+            generation = reset_generation();
+            meter.reset();
+        }
+
+        let mut fed = 0usize;
+        loop {
+            let packet_size = unsafe { capture_client.GetNextPacketSize() }.unwrap_or(0);
+            if packet_size == 0 {
+                break;
+            }
+            let mut buffer_ptr = std::ptr::null_mut();
+            let mut num_frames = 0u32;
+            let mut flags = 0u32;
+            if unsafe { capture_client.GetBuffer(&mut buffer_ptr, &mut num_frames, &mut flags, None, None) }
+                .is_err()
+            {
+                break;
+            }
+            let frame_count = num_frames as usize;
+            // Silent packets still count towards the windows.
+            let samples = if (flags & 0x2) != 0 {
+                vec![0.0f32; frame_count * channels]
+            } else {
+                crate::spectrum::extract_samples(buffer_ptr, frame_count, channels, bits_per_sample, block_align)
+            };
+            if let Err(e) = meter.add_frames_f32(&samples) {
+                log::warn!("Loudness feed error: {:?}", e);
+            }
+            sum_sq += samples.iter().map(|s| (*s as f64) * (*s as f64)).sum::<f64>();
+            sample_count += samples.len();
+            fed += frame_count;
+            let _ = unsafe { capture_client.ReleaseBuffer(num_frames) };
+        }
+        // Loopback delivers nothing while the device plays nothing; count
+        // that as silence so the windows fall instead of freezing.
+        if loopback && fed == 0 {
+            let frames = sample_rate as usize / 20;
+            let _ = meter.add_frames_f32(&vec![0.0f32; frames * channels]);
+        }
+
+        if last_publish.elapsed() >= Duration::from_millis(100) {
+            if let Ok(mut r) = reading.lock() {
+                *r = Some(LoudnessReading {
+                    momentary: clamp_lufs(meter.loudness_momentary()),
+                    short_term: clamp_lufs(meter.loudness_shortterm()),
+                    integrated: clamp_lufs(meter.loudness_global()),
+                    rms: if sample_count > 0 { (sum_sq / sample_count as f64).sqrt() as f32 } else { 0.0 },
+                });
+            }
+            sum_sq = 0.0;
+            sample_count = 0;
+            last_publish = Instant::now();
+        }
+    }
+
+    unsafe {
+        let _ = audio_client.Stop();
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn spawn_meter(
+    _device_id: String,
+    _loopback: bool,
+    _stop: Arc<AtomicBool>,
+    _reading: Arc<Mutex<Option<LoudnessReading>>>,
+) {
+}
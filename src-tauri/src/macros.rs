@@ -0,0 +1,434 @@
+use crate::ai_actions::{self, ActionResult, SharedUndoStack, UndoEntry};
+use crate::gemini::AiAction;
+use crate::i18n::t;
+use crate::layout;
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::store::SharedLicenseState;
+use crate::text_sources;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio::sync::Mutex;
+
+pub type SharedMacroState = Arc<Mutex<MacroState>>;
+
+/// Pauses shorter than this between recorded commands aren't kept as waits.
+const MIN_RECORDED_WAIT_MS: u64 = 1000;
+const WAIT_POLL_MS: u64 = 50;
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroStep {
+    Action(AiAction),
+    WaitMs(u64),
+    EmitEvent(String),
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Macro {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+    #[serde(default = "default_abort_on_error")]
+    pub abort_on_error: bool,
+}
+
+fn default_abort_on_error() -> bool {
+    true
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroRunResult {
+    pub name: String,
+    pub steps: Vec<ActionResult>,
+    pub completed: bool,
+    pub cancelled: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MacroStepEvent {
+    macro_name: String,
+    index: usize,
+    total: usize,
+    description: String,
+    status: String,
+    error: Option<String>,
+}
+
+struct Recording {
+    steps: Vec<MacroStep>,
+    last_step: Instant,
+}
+
+#[derive(Default)]
+pub struct MacroState {
+    running: HashMap<String, Arc<AtomicBool>>,
+    recording: Option<Recording>,
+}
+
+fn macros_file_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("macros.json")
+}
+
+fn load_macros() -> Vec<Macro> {
+    let Ok(content) = std::fs::read_to_string(macros_file_path()) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&content) {
+        Ok(macros) => macros,
+        Err(e) => {
+            log::warn!("Failed to parse macros file: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn save_macros(macros: &[Macro]) -> Result<(), String> {
+    let path = macros_file_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(macros)
+        .map_err(|e| format!("Failed to serialize macros: {}", e))?;
+    crate::settings::write_atomic(&path, json).map_err(|e| format!("Failed to save macros: {}", e))
+}
+
+/// Param keys that name an input or source in recorded actions.
+const NAME_KEYS: &[&str] = &["inputName", "sourceName", "input_name", "source_name"];
+
+fn rename_in_params(value: &mut Value, old_name: &str, new_name: &str) -> bool {
+    let mut changed = false;
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                if NAME_KEYS.contains(&key.as_str()) && v.as_str() == Some(old_name) {
+                    *v = json!(new_name);
+                    changed = true;
+                } else {
+                    changed |= rename_in_params(v, old_name, new_name);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for v in items {
+                changed |= rename_in_params(v, old_name, new_name);
+            }
+        }
+        _ => {}
+    }
+    changed
+}
+
+/// Rewrites saved macro steps that target a renamed input.
+pub async fn on_input_renamed(_app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    tokio::task::spawn_blocking(move || {
+        let mut macros = load_macros();
+        let mut touched = Vec::new();
+        for m in &mut macros {
+            let mut changed = false;
+            for step in &mut m.steps {
+                if let MacroStep::Action(action) = step {
+                    changed |= rename_in_params(&mut action.params, &old_name, &new_name);
+                }
+            }
+            if changed {
+                touched.push(format!("macro \"{}\"", m.name));
+            }
+        }
+        if !touched.is_empty() {
+            if let Err(e) = save_macros(&macros) {
+                log::warn!("Failed to save macros after rename: {}", e);
+                return Vec::new();
+            }
+        }
+        touched
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn upsert(m: Macro) -> Result<(), String> {
+    let mut macros = load_macros();
+    match macros.iter_mut().find(|existing| existing.name == m.name) {
+        Some(existing) => *existing = m,
+        None => macros.push(m),
+    }
+    save_macros(&macros)
+}
+
+/// Called by the mutating commands in commands.rs after they succeed, so a
+/// macro can be recorded by doing the steps in the normal UI.
+pub async fn record_request(state: &SharedMacroState, request_type: &str, params: Value, description: String) {
+    let mut state = state.lock().await;
+    let Some(recording) = state.recording.as_mut() else {
+        return;
+    };
+    let gap = recording.last_step.elapsed().as_millis() as u64;
+    if !recording.steps.is_empty() && gap >= MIN_RECORDED_WAIT_MS {
+        recording.steps.push(MacroStep::WaitMs(gap / 100 * 100));
+    }
+    recording.steps.push(MacroStep::Action(AiAction {
+        safety: "safe".into(),
+        description,
+        action_type: "obs_request".into(),
+        request_type: request_type.into(),
+        params,
+    }));
+    recording.last_step = Instant::now();
+}
+
+fn step_description(step: &MacroStep) -> String {
+    match step {
+        MacroStep::Action(action) => action.description.clone(),
+        MacroStep::WaitMs(ms) => format!("Wait {} ms", ms),
+        MacroStep::EmitEvent(name) => format!("Marker \"{}\"", name),
+    }
+}
+
+/// Sleeps in short slices so a cancel doesn't have to wait out a long pause.
+/// Returns false if cancelled.
+async fn wait(ms: u64, cancel: &AtomicBool) -> bool {
+    let deadline = Instant::now() + Duration::from_millis(ms);
+    while Instant::now() < deadline {
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        tokio::time::sleep(left.min(Duration::from_millis(WAIT_POLL_MS))).await;
+    }
+    !cancel.load(Ordering::Relaxed)
+}
+
+async fn run_action(
+    action: &AiAction,
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    license: &SharedLicenseState,
+) -> Result<Option<UndoEntry>, String> {
+    if let Some(module) = ai_actions::module_for_action(action) {
+        if !license.read().await.has_module(module) {
+            return Err(t("error.module_required", &[("module", &module)]));
+        }
+    }
+    let undo = match action.action_type.as_str() {
+        "layout" => layout::snapshot_for_undo(action, conn).await,
+        "text" => text_sources::snapshot_for_undo(action, conn).await,
+        _ => ai_actions::snapshot_for_undo(action, &*obs_state.read().await),
+    };
+    ai_actions::execute_single_action(action, conn).await?;
+    Ok(undo)
+}
+
+async fn run(
+    m: &Macro,
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    license: &SharedLicenseState,
+    undo_stack: &SharedUndoStack,
+    cancel: &AtomicBool,
+    app: &tauri::AppHandle,
+) -> MacroRunResult {
+    let total = m.steps.len();
+    let mut results = Vec::new();
+    let mut reverts = Vec::new();
+    let mut completed = true;
+    let mut cancelled = false;
+
+    for (index, step) in m.steps.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            cancelled = true;
+            completed = false;
+            break;
+        }
+        let description = step_description(step);
+        let emit = |status: &str, error: Option<String>| {
+            let _ = app.emit(
+                "macro://step",
+                MacroStepEvent {
+                    macro_name: m.name.clone(),
+                    index,
+                    total,
+                    description: description.clone(),
+                    status: status.to_string(),
+                    error,
+                },
+            );
+        };
+        emit("running", None);
+
+        let outcome = match step {
+            MacroStep::Action(action) => run_action(action, conn, obs_state, license).await.map(|undo| {
+                let undoable = undo.is_some();
+                reverts.extend(undo);
+                undoable
+            }),
+            MacroStep::WaitMs(ms) => {
+                if !wait(*ms, cancel).await {
+                    cancelled = true;
+                    completed = false;
+                    emit("cancelled", None);
+                    break;
+                }
+                Ok(false)
+            }
+            MacroStep::EmitEvent(name) => {
+                let _ = app.emit("macro://event", json!({"macroName": m.name, "event": name}));
+                Ok(false)
+            }
+        };
+
+        let (status, error, undoable) = match outcome {
+            Ok(undoable) => ("executed", None, undoable),
+            Err(e) => ("failed", Some(e), false),
+        };
+        emit(status, error.clone());
+        results.push(ActionResult {
+            description,
+            status: status.into(),
+            error,
+            undoable,
+            pending_action: None,
+        });
+        if status == "failed" && m.abort_on_error {
+            completed = false;
+            break;
+        }
+    }
+
+    if !reverts.is_empty() {
+        let entry = UndoEntry::compound(format!("Revert macro \"{}\"", m.name), reverts);
+        undo_stack.write().await.push(entry);
+    }
+
+    MacroRunResult {
+        name: m.name.clone(),
+        steps: results,
+        completed,
+        cancelled,
+    }
+}
+
+#[tauri::command]
+pub async fn list_macros() -> Result<Vec<Macro>, String> {
+    tokio::task::spawn_blocking(load_macros)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_macro(macro_def: Macro) -> Result<(), String> {
+    if macro_def.name.trim().is_empty() {
+        return Err("Macro name cannot be empty".to_string());
+    }
+    tokio::task::spawn_blocking(move || upsert(macro_def))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn delete_macro(name: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let mut macros = load_macros();
+        let before = macros.len();
+        macros.retain(|m| m.name != name);
+        if macros.len() == before {
+            return Err(format!("Macro '{}' not found", name));
+        }
+        save_macros(&macros)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn run_macro(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    license: tauri::State<'_, SharedLicenseState>,
+    undo_stack: tauri::State<'_, SharedUndoStack>,
+    macro_state: tauri::State<'_, SharedMacroState>,
+    app_handle: tauri::AppHandle,
+    name: String,
+) -> Result<MacroRunResult, String> {
+    let m = tokio::task::spawn_blocking(load_macros)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .into_iter()
+        .find(|m| m.name == name)
+        .ok_or_else(|| format!("Macro '{}' not found", name))?;
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut state = macro_state.lock().await;
+        if state.running.contains_key(&name) {
+            return Err(format!("Macro '{}' is already running", name));
+        }
+        state.running.insert(name.clone(), cancel.clone());
+    }
+
+    let result = run(&m, &conn_state, &obs_state, &license, &undo_stack, &cancel, &app_handle).await;
+    macro_state.lock().await.running.remove(&name);
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn cancel_macro(
+    macro_state: tauri::State<'_, SharedMacroState>,
+    name: String,
+) -> Result<(), String> {
+    let state = macro_state.lock().await;
+    let cancel = state
+        .running
+        .get(&name)
+        .ok_or_else(|| format!("Macro '{}' is not running", name))?;
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn record_macro_start(macro_state: tauri::State<'_, SharedMacroState>) -> Result<(), String> {
+    let mut state = macro_state.lock().await;
+    if state.recording.is_some() {
+        return Err("Already recording a macro".to_string());
+    }
+    state.recording = Some(Recording {
+        steps: Vec::new(),
+        last_step: Instant::now(),
+    });
+    Ok(())
+}
+
+/// Ends the recording and returns what was captured. Passing a name also
+/// saves it; otherwise the frontend can edit the steps and call `save_macro`.
+#[tauri::command]
+pub async fn record_macro_stop(
+    macro_state: tauri::State<'_, SharedMacroState>,
+    name: Option<String>,
+) -> Result<Macro, String> {
+    let recording = macro_state
+        .lock()
+        .await
+        .recording
+        .take()
+        .ok_or("Not recording a macro")?;
+    let m = Macro {
+        name: name.clone().unwrap_or_default(),
+        steps: recording.steps,
+        abort_on_error: true,
+    };
+    if name.is_some() {
+        save_macro(m.clone()).await?;
+    }
+    Ok(m)
+}
@@ -0,0 +1,141 @@
+use crate::obs_state::{ObsState, SharedObsState};
+use crate::obs_websocket::ObsHandle;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// Input kinds OBS can play back, pause and seek.
+pub const MEDIA_KINDS: &[&str] = &["ffmpeg_source", "vlc_source"];
+
+pub const MEDIA_ACTIONS: &[&str] = &[
+    "OBS_WEBSOCKET_MEDIA_INPUT_ACTION_PLAY",
+    "OBS_WEBSOCKET_MEDIA_INPUT_ACTION_PAUSE",
+    "OBS_WEBSOCKET_MEDIA_INPUT_ACTION_RESTART",
+    "OBS_WEBSOCKET_MEDIA_INPUT_ACTION_STOP",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaStatus {
+    pub input_name: String,
+    /// playing, paused, stopped, ended, opening, buffering, error or none.
+    pub state: String,
+    /// None while nothing is loaded, or for live inputs with no length.
+    pub duration_ms: Option<u64>,
+    pub cursor_ms: Option<u64>,
+}
+
+/// Rejects inputs that aren't media sources before OBS answers with a bare
+/// code 600. Inputs we don't know about yet are left for OBS to judge.
+pub fn check_media_input(state: &ObsState, input_name: &str) -> Result<(), String> {
+    match state.inputs.get(input_name) {
+        Some(input) if !MEDIA_KINDS.contains(&input.kind.as_str()) => Err(format!(
+            "\"{}\" is a {} input, not a media source; only Media Source and VLC inputs can be played",
+            input_name, input.kind
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// "OBS_MEDIA_STATE_PLAYING" → "playing".
+fn state_name(raw: &str) -> String {
+    raw.strip_prefix("OBS_MEDIA_STATE_").unwrap_or(raw).to_lowercase()
+}
+
+pub async fn fetch_status(conn: &ObsHandle, input_name: &str) -> Result<MediaStatus, String> {
+    let v: Value = conn
+        .send_request("GetMediaInputStatus", Some(json!({ "inputName": input_name })))
+        .await?;
+    Ok(MediaStatus {
+        input_name: input_name.to_string(),
+        state: state_name(v["mediaState"].as_str().unwrap_or("")),
+        duration_ms: v["mediaDuration"].as_u64(),
+        cursor_ms: v["mediaCursor"].as_u64(),
+    })
+}
+
+async fn trigger(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    input_name: &str,
+    action: &str,
+) -> Result<(), String> {
+    check_media_input(&obs_state.read().await, input_name)?;
+    conn.send_request(
+        "TriggerMediaInputAction",
+        Some(json!({
+            "inputName": input_name,
+            "mediaAction": format!("OBS_WEBSOCKET_MEDIA_INPUT_ACTION_{}", action)
+        })),
+    )
+    .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_media_status(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    input_name: String,
+) -> Result<MediaStatus, String> {
+    check_media_input(&obs_state.read().await, &input_name)?;
+    fetch_status(&conn_state, &input_name).await
+}
+
+#[tauri::command]
+pub async fn media_play(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    input_name: String,
+) -> Result<(), String> {
+    trigger(&conn_state, &obs_state, &input_name, "PLAY").await
+}
+
+#[tauri::command]
+pub async fn media_pause(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    input_name: String,
+) -> Result<(), String> {
+    trigger(&conn_state, &obs_state, &input_name, "PAUSE").await
+}
+
+#[tauri::command]
+pub async fn media_restart(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    input_name: String,
+) -> Result<(), String> {
+    trigger(&conn_state, &obs_state, &input_name, "RESTART").await
+}
+
+#[tauri::command]
+pub async fn media_stop(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    input_name: String,
+) -> Result<(), String> {
+    trigger(&conn_state, &obs_state, &input_name, "STOP").await
+}
+
+/// Jumps to `cursor_ms`, clamped to the media's length when OBS knows it.
+#[tauri::command]
+pub async fn media_seek(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    input_name: String,
+    cursor_ms: u64,
+) -> Result<(), String> {
+    check_media_input(&obs_state.read().await, &input_name)?;
+    let status = fetch_status(&conn_state, &input_name).await?;
+    let cursor_ms = match status.duration_ms {
+        Some(duration) => cursor_ms.min(duration),
+        None => cursor_ms,
+    };
+    conn_state
+        .send_request(
+            "SetMediaInputCursor",
+            Some(json!({ "inputName": input_name, "mediaCursor": cursor_ms })),
+        )
+        .await?;
+    Ok(())
+}
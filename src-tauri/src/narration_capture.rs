@@ -1,7 +1,7 @@
 use crate::audio;
-use crate::commands::SharedObsConnection;
 use crate::obs_config;
 use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
 use crate::store::SharedLicenseState;
 use crate::video_editor::SharedVideoEditorState;
 use serde::Serialize;
@@ -224,6 +224,7 @@ pub async fn install_vb_cable(
 #[tauri::command]
 pub async fn configure_obs_monitoring_for_vbcable(
     license: tauri::State<'_, SharedLicenseState>,
+    settings: tauri::State<'_, crate::settings::SharedSettings>,
 ) -> Result<String, String> {
     crate::store::require_module(&license, "narration-studio").await?;
 
@@ -249,6 +250,7 @@ pub async fn configure_obs_monitoring_for_vbcable(
         channel_setup: config.channel_setup,
     };
 
+    crate::obs_backup::auto_backup(&settings, "audio-config").await?;
     tokio::task::spawn_blocking(move || obs_config::write_obs_audio_config(&updated))
         .await
         .map_err(|e| format!("Task join error: {}", e))??;
@@ -259,7 +261,7 @@ pub async fn configure_obs_monitoring_for_vbcable(
 #[tauri::command]
 pub async fn auto_configure_obs_monitoring(
     license: tauri::State<'_, SharedLicenseState>,
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
 ) -> Result<String, String> {
     crate::store::require_module(&license, "narration-studio").await?;
 
@@ -282,7 +284,7 @@ pub async fn auto_configure_obs_monitoring(
         device_id
     );
 
-    let conn = conn_state.lock().await;
+    let conn = conn_state.inner().clone();
     conn.send_request(
         "SetProfileParameter",
         Some(serde_json::json!({
@@ -302,7 +304,6 @@ pub async fn auto_configure_obs_monitoring(
         })),
     )
     .await?;
-    drop(conn);
 
     log::info!("auto_configure_obs_monitoring: done");
     Ok(format!("OBS monitoring set to '{}'", device_name))
@@ -311,7 +312,7 @@ pub async fn auto_configure_obs_monitoring(
 #[tauri::command]
 pub async fn start_narration_capture(
     license: tauri::State<'_, SharedLicenseState>,
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
     ve_state: tauri::State<'_, SharedVideoEditorState>,
     capture_state: tauri::State<'_, SharedNarrationCaptureState>,
@@ -333,7 +334,7 @@ pub async fn start_narration_capture(
     let cable_device_id = cable_output.id.clone();
 
     {
-        let conn = conn_state.lock().await;
+        let conn = conn_state.inner().clone();
         conn.send_request(
             "SetInputAudioMonitorType",
             Some(serde_json::json!({
@@ -386,7 +387,7 @@ pub async fn start_narration_capture(
 #[tauri::command]
 pub async fn stop_narration_capture(
     license: tauri::State<'_, SharedLicenseState>,
-    conn_state: tauri::State<'_, SharedObsConnection>,
+    conn_state: tauri::State<'_, ObsHandle>,
     obs_state: tauri::State<'_, SharedObsState>,
     capture_state: tauri::State<'_, SharedNarrationCaptureState>,
     mic_source_name: String,
@@ -405,7 +406,7 @@ pub async fn stop_narration_capture(
     tokio::time::sleep(std::time::Duration::from_millis(300)).await;
 
     {
-        let conn = conn_state.lock().await;
+        let conn = conn_state.inner().clone();
         conn.send_request(
             "SetInputAudioMonitorType",
             Some(serde_json::json!({
@@ -0,0 +1,297 @@
+use crate::obs_config;
+use crate::settings::SharedSettings;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+/// Backup parts and the paths (relative to the OBS config dir) they cover.
+const PARTS: &[(&str, &[&str])] = &[
+    ("scenes", &["basic/scenes"]),
+    ("profiles", &["basic/profiles"]),
+    ("global", &["global.ini", "user.ini"]),
+];
+
+const AUTO_PREFIX: &str = "auto-";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsBackup {
+    pub path: String,
+    pub file_name: String,
+    pub created_at: u64,
+    pub size_bytes: u64,
+    pub automatic: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupResult {
+    pub path: String,
+    pub size_bytes: u64,
+    pub files: usize,
+    pub warning: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreResult {
+    pub restored_parts: Vec<String>,
+    pub files: usize,
+    pub pre_restore_dir: String,
+}
+
+fn backups_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("backups")
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn part_paths(part: &str) -> Option<&'static [&'static str]> {
+    PARTS.iter().find(|(name, _)| *name == part).map(|(_, paths)| *paths)
+}
+
+/// Every file under `dir`, as paths relative to `root`.
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            out.push(rel.to_path_buf());
+        }
+    }
+    Ok(())
+}
+
+fn zip_name(rel: &Path) -> String {
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn write_backup(destination: &Path) -> Result<BackupResult, String> {
+    let config_dir = obs_config::obs_config_dir().ok_or("OBS config directory not found")?;
+    let warning = obs_config::is_obs_running().then(|| {
+        "OBS is running; scene collections it hasn't saved yet may be missing or inconsistent".to_string()
+    });
+
+    let mut files = Vec::new();
+    for (_, paths) in PARTS {
+        for rel in paths.iter() {
+            let full = config_dir.join(rel);
+            if full.is_dir() {
+                collect_files(&config_dir, &full, &mut files)
+                    .map_err(|e| format!("Cannot read {}: {}", full.display(), e))?;
+            } else if full.is_file() {
+                files.push(PathBuf::from(rel));
+            }
+        }
+    }
+    if files.is_empty() {
+        return Err("No OBS configuration files found to back up".to_string());
+    }
+
+    if let Some(dir) = destination.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
+    let file = File::create(destination).map_err(|e| format!("Cannot create zip: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for rel in &files {
+        let mut src = File::open(config_dir.join(rel))
+            .map_err(|e| format!("Cannot open {}: {}", rel.display(), e))?;
+        zip.start_file(zip_name(rel), options)
+            .map_err(|e| format!("Zip write error: {}", e))?;
+        std::io::copy(&mut src, &mut zip).map_err(|e| format!("Zip write error: {}", e))?;
+    }
+    zip.finish().map_err(|e| format!("Zip finish error: {}", e))?;
+
+    let size_bytes = std::fs::metadata(destination).map(|m| m.len()).unwrap_or(0);
+    Ok(BackupResult {
+        path: destination.to_string_lossy().to_string(),
+        size_bytes,
+        files: files.len(),
+        warning,
+    })
+}
+
+fn list_backups() -> Vec<ObsBackup> {
+    let Ok(entries) = std::fs::read_dir(backups_dir()) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<ObsBackup> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().map(|x| x == "zip").unwrap_or(false))
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            let created_at = meta
+                .modified()
+                .ok()?
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            let file_name = e.file_name().to_string_lossy().to_string();
+            Some(ObsBackup {
+                path: e.path().to_string_lossy().to_string(),
+                automatic: file_name.starts_with(AUTO_PREFIX),
+                file_name,
+                created_at,
+                size_bytes: meta.len(),
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    backups
+}
+
+fn prune_auto_backups(keep: usize) {
+    for old in list_backups().into_iter().filter(|b| b.automatic).skip(keep) {
+        if let Err(e) = std::fs::remove_file(&old.path) {
+            log::warn!("Failed to remove old backup {}: {}", old.path, e);
+        }
+    }
+}
+
+fn restore_backup(backup_path: &Path, parts: &[String]) -> Result<RestoreResult, String> {
+    if obs_config::is_obs_running() {
+        return Err("OBS Studio is currently running. Close it before restoring a backup.".to_string());
+    }
+    let config_dir = obs_config::obs_config_dir().ok_or("OBS config directory not found")?;
+    let mut selected = Vec::new();
+    for part in parts {
+        let paths = part_paths(part).ok_or_else(|| format!("Unknown backup part '{}'", part))?;
+        selected.extend(paths.iter().map(|p| (part.as_str(), *p)));
+    }
+    if selected.is_empty() {
+        return Err("Nothing selected to restore".to_string());
+    }
+
+    let file = File::open(backup_path).map_err(|e| format!("Cannot open zip: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid zip: {}", e))?;
+    let under = |name: &str, prefix: &str| name == prefix || name.starts_with(&format!("{}/", prefix));
+    // Only paths the backup actually has are replaced; the rest of the
+    // current config stays where it is.
+    let names: Vec<String> = archive.file_names().map(String::from).collect();
+    selected.retain(|(_, p)| names.iter().any(|n| under(n, p)));
+    if selected.is_empty() {
+        return Err("The backup doesn't contain any of the selected parts".to_string());
+    }
+    let prefixes: Vec<&str> = selected.iter().map(|(_, p)| *p).collect();
+    let mut restored_parts: Vec<String> = Vec::new();
+    for (part, _) in &selected {
+        if !restored_parts.iter().any(|r| r == part) {
+            restored_parts.push(part.to_string());
+        }
+    }
+    let covers = |name: &str| prefixes.iter().any(|p| under(name, p));
+
+    // Move (not copy) the current files aside so the restore lands on a clean
+    // tree and the old state survives even if extraction fails halfway.
+    let pre_restore_dir = config_dir.join(format!("pre-restore-{}", unix_now()));
+    for rel in &prefixes {
+        let current = config_dir.join(rel);
+        if !current.exists() {
+            continue;
+        }
+        let target = pre_restore_dir.join(rel);
+        if let Some(dir) = target.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+        }
+        std::fs::rename(&current, &target)
+            .map_err(|e| format!("Cannot move {} aside: {}", current.display(), e))?;
+    }
+
+    let mut files = 0;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| format!("Zip entry error: {}", e))?;
+        if entry.is_dir() || !covers(entry.name()) {
+            continue;
+        }
+        let Some(rel) = entry.enclosed_name() else {
+            log::warn!("Skipping unsafe backup entry '{}'", entry.name());
+            continue;
+        };
+        let out_path = config_dir.join(rel);
+        if let Some(dir) = out_path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+        }
+        let mut out = File::create(&out_path).map_err(|e| {
+            format!(
+                "Extract error: {} (previous files are in {})",
+                e,
+                pre_restore_dir.display()
+            )
+        })?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| {
+            format!(
+                "Extract error: {} (previous files are in {})",
+                e,
+                pre_restore_dir.display()
+            )
+        })?;
+        files += 1;
+    }
+
+    Ok(RestoreResult {
+        restored_parts,
+        files,
+        pre_restore_dir: pre_restore_dir.to_string_lossy().to_string(),
+    })
+}
+
+/// Snapshots the OBS config before we rewrite it ourselves, if the user has
+/// turned that on. A failed snapshot aborts the caller's change.
+pub async fn auto_backup(settings: &SharedSettings, reason: &str) -> Result<(), String> {
+    let (enabled, keep) = {
+        let s = settings.read().await;
+        (s.backups.auto_backup, s.backups.keep_auto_backups)
+    };
+    if !enabled {
+        return Ok(());
+    }
+    let destination = backups_dir().join(format!("{}{}-{}.zip", AUTO_PREFIX, reason, unix_now()));
+    let result = tokio::task::spawn_blocking(move || {
+        let result = write_backup(&destination);
+        prune_auto_backups(keep);
+        result
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(|e| format!("Auto-backup failed, OBS config left unchanged: {}", e))?;
+    log::info!("Backed up OBS config before {} to {}", reason, result.path);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn backup_obs_config(destination_zip: Option<String>) -> Result<BackupResult, String> {
+    let destination = destination_zip
+        .map(PathBuf::from)
+        .unwrap_or_else(|| backups_dir().join(format!("obs-backup-{}.zip", unix_now())));
+    tokio::task::spawn_blocking(move || write_backup(&destination))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn list_obs_backups() -> Result<Vec<ObsBackup>, String> {
+    tokio::task::spawn_blocking(list_backups)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn restore_obs_config(backup_path: String, what: Vec<String>) -> Result<RestoreResult, String> {
+    tokio::task::spawn_blocking(move || restore_backup(Path::new(&backup_path), &what))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
@@ -1,3 +1,4 @@
+use crate::i18n::t;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -12,7 +13,54 @@ pub struct ObsAudioConfig {
     pub channel_setup: String,
 }
 
-fn obs_config_dir() -> Option<PathBuf> {
+/// Recording output settings from a profile. Simple mode has no keyframe
+/// interval or quality value; those read as 0 there and aren't written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsOutputConfig {
+    pub profile_name: String,
+    /// "Simple" or "Advanced".
+    pub mode: String,
+    /// RecFormat2: "mkv", "mp4", "hybrid_mp4", "mov", "flv", ...
+    pub recording_format: String,
+    pub recording_path: String,
+    /// Encoder id, e.g. "obs_x264" ("x264" in simple mode).
+    pub encoder: String,
+    /// "CBR", "VBR", "CQP", "CRF", ...
+    pub rate_control: String,
+    pub bitrate_kbps: u32,
+    /// CQP/CRF level.
+    pub quality_level: u32,
+    /// 0 lets the encoder pick.
+    pub keyframe_interval_sec: u32,
+    /// Bitmask, track 1 = bit 0.
+    pub audio_tracks: u32,
+    /// Advanced mode set to a custom FFmpeg output, which ignores all of the above.
+    #[serde(default)]
+    pub ffmpeg_output: bool,
+    /// Advanced mode recording with the stream encoder (RecEncoder=none).
+    /// Saving keeps it that way and leaves the stream's encoder settings alone.
+    #[serde(default)]
+    pub use_stream_encoder: bool,
+}
+
+/// obs-websocket's server settings. The password itself is only filled in
+/// when asked for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebsocketSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub auth_required: bool,
+    pub password_set: bool,
+    pub password: Option<String>,
+    /// Where it was read from: "json" (obs-websocket 5.5+) or "ini".
+    pub source: String,
+}
+
+const WEBSOCKET_DEFAULT_PORT: u16 = 4455;
+
+pub(crate) fn obs_config_dir() -> Option<PathBuf> {
     let appdata = std::env::var("APPDATA").ok()?;
     let path = PathBuf::from(appdata).join("obs-studio");
     if path.exists() {
@@ -22,6 +70,20 @@ fn obs_config_dir() -> Option<PathBuf> {
     }
 }
 
+/// Profile and scene collection OBS last had selected, from global.ini.
+/// OBS updates it on every switch, so while it runs this is what it uses.
+pub(crate) fn selected_profile_and_collection() -> (Option<String>, Option<String>) {
+    let Some(content) = obs_config_dir().and_then(|d| std::fs::read_to_string(d.join("global.ini")).ok()) else {
+        return (None, None);
+    };
+    let sections = parse_ini(&content);
+    let basic = sections.get("Basic");
+    (
+        basic.and_then(|b| b.get("Profile")).cloned(),
+        basic.and_then(|b| b.get("SceneCollection")).cloned(),
+    )
+}
+
 fn find_active_profile(config_dir: &Path) -> Option<String> {
     let global_ini = config_dir.join("global.ini");
     if global_ini.exists() {
@@ -84,7 +146,7 @@ pub fn read_obs_audio_config() -> Result<ObsAudioConfig, String> {
 
 pub fn write_obs_audio_config(config: &ObsAudioConfig) -> Result<(), String> {
     if is_obs_running() {
-        return Err("OBS Studio is currently running. Close it before modifying config.".to_string());
+        return Err(t("obs_config.obs_running", &[]));
     }
 
     let config_dir = obs_config_dir().ok_or("OBS config directory not found")?;
@@ -119,13 +181,356 @@ pub fn write_obs_audio_config(config: &ObsAudioConfig) -> Result<(), String> {
 
     let new_content = update_ini_section(&content, "Audio", &updates);
 
-    std::fs::write(&basic_ini_path, new_content)
+    crate::settings::write_atomic(&basic_ini_path, new_content)
         .map_err(|e| format!("Failed to write basic.ini: {}", e))?;
 
     Ok(())
 }
 
-fn is_obs_running() -> bool {
+fn profile_dir(profile: &str) -> Result<PathBuf, String> {
+    let config_dir = obs_config_dir().ok_or("OBS config directory not found")?;
+    let dir = config_dir.join("basic").join("profiles").join(profile);
+    if !dir.join("basic.ini").exists() {
+        return Err(format!("Profile config not found: {}", dir.join("basic.ini").display()));
+    }
+    Ok(dir)
+}
+
+fn read_json_file(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+pub fn read_obs_output_config() -> Result<ObsOutputConfig, String> {
+    let config_dir = obs_config_dir().ok_or("OBS config directory not found")?;
+    let profile = find_active_profile(&config_dir).ok_or("No OBS profile found")?;
+    let dir = profile_dir(&profile)?;
+    let content = std::fs::read_to_string(dir.join("basic.ini"))
+        .map_err(|e| format!("Failed to read basic.ini: {}", e))?;
+    let sections = parse_ini(&content);
+    let get = |section: &str, key: &str| sections.get(section).and_then(|s| s.get(key)).cloned();
+    let num = |section: &str, key: &str, default: u32| {
+        get(section, key).and_then(|v| v.parse().ok()).unwrap_or(default)
+    };
+
+    let mode = get("Output", "Mode").unwrap_or_else(|| "Simple".to_string());
+    if mode != "Advanced" {
+        // Simple mode records with the stream encoder at "Same as stream"
+        // quality and uses a quality preset otherwise.
+        let same_as_stream = get("SimpleOutput", "RecQuality").as_deref() == Some("Stream");
+        let encoder_key = if same_as_stream { "StreamEncoder" } else { "RecEncoder" };
+        return Ok(ObsOutputConfig {
+            profile_name: profile,
+            mode,
+            recording_format: get("SimpleOutput", "RecFormat2")
+                .or_else(|| get("SimpleOutput", "RecFormat"))
+                .unwrap_or_else(|| "mkv".to_string()),
+            recording_path: get("SimpleOutput", "FilePath").unwrap_or_default(),
+            encoder: get("SimpleOutput", encoder_key).unwrap_or_else(|| "x264".to_string()),
+            rate_control: if same_as_stream { "CBR" } else { "CQP" }.to_string(),
+            bitrate_kbps: num("SimpleOutput", "VBitrate", 2500),
+            quality_level: 0,
+            keyframe_interval_sec: 0,
+            audio_tracks: num("SimpleOutput", "RecTracks", 1),
+            ffmpeg_output: false,
+            use_stream_encoder: same_as_stream,
+        });
+    }
+
+    // "none" records with the stream encoder and its settings.
+    let rec_encoder = get("AdvOut", "RecEncoder").unwrap_or_else(|| "none".to_string());
+    let use_stream_encoder = rec_encoder == "none";
+    let (encoder, file) = if use_stream_encoder {
+        (get("AdvOut", "Encoder").unwrap_or_else(|| "obs_x264".to_string()), "streamEncoder.json")
+    } else {
+        (rec_encoder, "recordEncoder.json")
+    };
+    let settings = read_json_file(&dir.join(file)).unwrap_or_default();
+    let quality = if encoder.contains("x264") { "crf" } else { "cqp" };
+    Ok(ObsOutputConfig {
+        profile_name: profile,
+        mode,
+        recording_format: get("AdvOut", "RecFormat2")
+            .or_else(|| get("AdvOut", "RecFormat"))
+            .unwrap_or_else(|| "mkv".to_string()),
+        recording_path: get("AdvOut", "RecFilePath").unwrap_or_default(),
+        rate_control: settings["rate_control"].as_str().unwrap_or("CBR").to_uppercase(),
+        bitrate_kbps: settings["bitrate"].as_u64().unwrap_or(2500) as u32,
+        quality_level: settings[quality].as_u64().unwrap_or(23) as u32,
+        keyframe_interval_sec: settings["keyint_sec"].as_u64().unwrap_or(0) as u32,
+        encoder,
+        audio_tracks: num("AdvOut", "RecTracks", 1),
+        ffmpeg_output: get("AdvOut", "RecType").as_deref() == Some("FFmpeg"),
+        use_stream_encoder,
+    })
+}
+
+/// The basic.ini changes for `config`, as (section, key, value).
+pub fn output_ini_updates(config: &ObsOutputConfig) -> Vec<(&'static str, &'static str, String)> {
+    let mut updates = Vec::new();
+    let advanced = config.mode == "Advanced";
+    let section = if advanced { "AdvOut" } else { "SimpleOutput" };
+    if !config.recording_format.is_empty() {
+        updates.push((section, "RecFormat2", config.recording_format.clone()));
+    }
+    if !config.recording_path.is_empty() {
+        let key = if advanced { "RecFilePath" } else { "FilePath" };
+        updates.push((section, key, config.recording_path.clone()));
+    }
+    if config.audio_tracks > 0 {
+        updates.push((section, "RecTracks", config.audio_tracks.to_string()));
+    }
+    if advanced {
+        if !config.encoder.is_empty() && !config.use_stream_encoder {
+            updates.push(("AdvOut", "RecEncoder", config.encoder.clone()));
+        }
+    } else if config.bitrate_kbps > 0 {
+        updates.push(("SimpleOutput", "VBitrate", config.bitrate_kbps.to_string()));
+    }
+    updates
+}
+
+/// Writes the advanced-mode record encoder settings. When recording used
+/// the stream encoder until now, its settings are the starting point.
+pub fn write_record_encoder(config: &ObsOutputConfig) -> Result<(), String> {
+    if config.mode != "Advanced" || config.use_stream_encoder {
+        return Ok(());
+    }
+    let dir = profile_dir(&config.profile_name)?;
+    let mut settings = read_json_file(&dir.join("recordEncoder.json"))
+        .or_else(|| read_json_file(&dir.join("streamEncoder.json")))
+        .filter(|v| v.is_object())
+        .unwrap_or_else(|| serde_json::json!({}));
+    settings["rate_control"] = config.rate_control.clone().into();
+    settings["bitrate"] = config.bitrate_kbps.into();
+    settings["keyint_sec"] = config.keyframe_interval_sec.into();
+    let quality = if config.encoder.contains("x264") { "crf" } else { "cqp" };
+    settings[quality] = config.quality_level.into();
+    let json = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("JSON serialize failed: {}", e))?;
+    crate::settings::write_atomic(&dir.join("recordEncoder.json"), json)
+        .map_err(|e| format!("Failed to write recordEncoder.json: {}", e))
+}
+
+/// Writes straight to disk; OBS must be closed. With OBS open, the caller
+/// goes through the websocket instead.
+pub fn write_obs_output_config(config: &ObsOutputConfig) -> Result<(), String> {
+    if is_obs_running() {
+        return Err(t("obs_config.obs_running", &[]));
+    }
+    let path = profile_dir(&config.profile_name)?.join("basic.ini");
+    let mut content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read basic.ini: {}", e))?;
+    for section in ["SimpleOutput", "AdvOut"] {
+        let updates: HashMap<String, String> = output_ini_updates(config)
+            .into_iter()
+            .filter(|(s, _, _)| *s == section)
+            .map(|(_, k, v)| (k.to_string(), v))
+            .collect();
+        if !updates.is_empty() {
+            content = update_ini_section(&content, section, &updates);
+        }
+    }
+    crate::settings::write_atomic(&path, content).map_err(|e| format!("Failed to write basic.ini: {}", e))?;
+    write_record_encoder(config)
+}
+
+/// Problems with an output config that OBS would accept but that record
+/// badly or not as intended.
+pub fn validate_output_config(config: &ObsOutputConfig) -> Vec<String> {
+    let mut conflicts = Vec::new();
+    let format = config.recording_format.as_str();
+    let tracks = config.audio_tracks.count_ones();
+    if config.ffmpeg_output {
+        conflicts.push(t("obs_config.conflict.ffmpeg_output", &[]));
+    }
+    if config.mode == "Advanced" && config.use_stream_encoder {
+        conflicts.push(t("obs_config.conflict.stream_encoder", &[]));
+    }
+    if format == "flv" && tracks > 1 {
+        conflicts.push(t("obs_config.conflict.flv_tracks", &[("count", &tracks)]));
+    }
+    if format == "mp4" || format == "mov" {
+        conflicts.push(t(
+            "obs_config.conflict.fragile_container",
+            &[("format", &format.to_uppercase())],
+        ));
+    }
+    if config.recording_path.is_empty() {
+        conflicts.push(t("obs_config.conflict.no_path", &[]));
+    } else if !Path::new(&config.recording_path).is_dir() {
+        conflicts.push(t("obs_config.conflict.missing_path", &[("path", &config.recording_path)]));
+    }
+    if config.mode == "Advanced" {
+        let quality_mode = matches!(config.rate_control.as_str(), "CQP" | "CRF" | "ICQ");
+        if !quality_mode && config.bitrate_kbps == 0 {
+            conflicts.push(t("obs_config.conflict.zero_bitrate", &[("rate_control", &config.rate_control)]));
+        }
+        if quality_mode && config.quality_level > 51 {
+            conflicts.push(t("obs_config.conflict.quality_range", &[("level", &config.quality_level)]));
+        }
+        if config.keyframe_interval_sec > 10 {
+            conflicts.push(t(
+                "obs_config.conflict.keyframe_interval",
+                &[("seconds", &config.keyframe_interval_sec)],
+            ));
+        }
+    } else if config.quality_level > 0 || config.keyframe_interval_sec > 0 {
+        conflicts.push(t("obs_config.conflict.simple_mode_extras", &[]));
+    }
+    conflicts
+}
+
+fn websocket_json_path(config_dir: &Path) -> PathBuf {
+    config_dir.join("plugin_config").join("obs-websocket").join("config.json")
+}
+
+/// The ini holding the [OBSWebSocket] section: user.ini from OBS 31 on,
+/// global.ini before.
+fn websocket_ini_path(config_dir: &Path) -> PathBuf {
+    let user = config_dir.join("user.ini");
+    if user.exists() {
+        user
+    } else {
+        config_dir.join("global.ini")
+    }
+}
+
+pub fn read_websocket_settings(reveal: bool) -> Result<WebsocketSettings, String> {
+    let config_dir = obs_config_dir().ok_or("OBS config directory not found")?;
+
+    let (enabled, port, auth_required, password, source) =
+        if let Some(json) = read_json_file(&websocket_json_path(&config_dir)) {
+            (
+                json["server_enabled"].as_bool().unwrap_or(false),
+                json["server_port"].as_u64().map(|p| p as u16).unwrap_or(WEBSOCKET_DEFAULT_PORT),
+                json["auth_required"].as_bool().unwrap_or(true),
+                json["server_password"].as_str().unwrap_or("").to_string(),
+                "json",
+            )
+        } else {
+            let content = std::fs::read_to_string(websocket_ini_path(&config_dir)).unwrap_or_default();
+            let sections = parse_ini(&content);
+            let ws = sections.get("OBSWebSocket").cloned().unwrap_or_default();
+            let flag = |key: &str, default: bool| {
+                ws.get(key).map(|v| v.eq_ignore_ascii_case("true")).unwrap_or(default)
+            };
+            (
+                flag("ServerEnabled", false),
+                ws.get("ServerPort").and_then(|p| p.parse().ok()).unwrap_or(WEBSOCKET_DEFAULT_PORT),
+                flag("AuthRequired", true),
+                ws.get("ServerPassword").cloned().unwrap_or_default(),
+                "ini",
+            )
+        };
+
+    Ok(WebsocketSettings {
+        enabled,
+        port,
+        auth_required,
+        password_set: !password.is_empty(),
+        password: if reveal { Some(password) } else { None },
+        source: source.to_string(),
+    })
+}
+
+/// Turns the server on with authentication, keeping an existing password
+/// or generating one. OBS must be closed: it writes its own copy on exit.
+pub fn enable_websocket_server(port: Option<u16>) -> Result<WebsocketSettings, String> {
+    if is_obs_running() {
+        return Err(t("obs_config.obs_running_websocket", &[]));
+    }
+    let config_dir = obs_config_dir().ok_or("OBS config directory not found")?;
+    let current = read_websocket_settings(true)?;
+    let password = current
+        .password
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().simple().to_string()[..16].to_string());
+    let port = port.unwrap_or(current.port);
+
+    if current.source == "json" {
+        let path = websocket_json_path(&config_dir);
+        let mut json = read_json_file(&path)
+            .filter(|v| v.is_object())
+            .unwrap_or_else(|| serde_json::json!({}));
+        json["server_enabled"] = true.into();
+        json["server_port"] = port.into();
+        json["auth_required"] = true.into();
+        json["server_password"] = password.clone().into();
+        let out = serde_json::to_string_pretty(&json)
+            .map_err(|e| format!("JSON serialize failed: {}", e))?;
+        std::fs::write(&path, out).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    } else {
+        // Newer obs-websocket builds migrate the ini section to JSON themselves.
+        let path = websocket_ini_path(&config_dir);
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        let updates: HashMap<String, String> = [
+            ("ServerEnabled", "true".to_string()),
+            ("ServerPort", port.to_string()),
+            ("AuthRequired", "true".to_string()),
+            ("ServerPassword", password.clone()),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+        crate::settings::write_atomic(&path, update_ini_section(&content, "OBSWebSocket", &updates))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    Ok(WebsocketSettings {
+        enabled: true,
+        port,
+        auth_required: true,
+        password_set: true,
+        password: Some(password),
+        source: current.source,
+    })
+}
+
+/// OBS's monitoring device id, asked of OBS itself so it's current even
+/// while OBS holds unsaved settings; basic.ini is the fallback.
+pub async fn monitoring_device_id(conn: &crate::obs_websocket::ObsHandle) -> Option<String> {
+    if conn.is_connected() {
+        if let Ok(resp) = conn
+            .send_request(
+                "GetProfileParameter",
+                Some(serde_json::json!({
+                    "parameterCategory": "Audio",
+                    "parameterName": "MonitoringDeviceId",
+                })),
+            )
+            .await
+        {
+            // Unset means OBS's own default.
+            let id = resp["parameterValue"]
+                .as_str()
+                .or(resp["defaultParameterValue"].as_str())
+                .unwrap_or("default");
+            return Some(id.to_string());
+        }
+    }
+    tokio::task::spawn_blocking(read_obs_audio_config)
+        .await
+        .ok()
+        .and_then(Result::ok)
+        .map(|c| c.monitoring_device_id)
+        .map(|id| if id.is_empty() { "default".to_string() } else { id })
+}
+
+/// Reads a JSON file from a profile directory, e.g. `recordEncoder.json`.
+/// Falls back to the active profile from global.ini when `profile` is empty.
+pub fn read_profile_json(profile: &str, file: &str) -> Option<serde_json::Value> {
+    let config_dir = obs_config_dir()?;
+    let profile = if profile.is_empty() {
+        find_active_profile(&config_dir)?
+    } else {
+        profile.to_string()
+    };
+    let path = config_dir.join("basic").join("profiles").join(profile).join(file);
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub(crate) fn is_obs_running() -> bool {
     use sysinfo::System;
     let mut sys = System::new();
     sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
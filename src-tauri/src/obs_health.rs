@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+use std::sync::Mutex;
+use tauri::Emitter;
+
+pub type SharedObsHealth = Arc<Mutex<ConnectionHealth>>;
+
+const MAX_SAMPLES: usize = 200;
+const STATS_SLOW_MS: u64 = 5000;
+const DEGRADED_AFTER_FAILURES: u32 = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+    pub degraded: bool,
+    pub consecutive_stats_failures: u32,
+    pub last_stats_latency_ms: Option<u64>,
+    pub sample_count: usize,
+    pub timeouts: u64,
+    pub avg_ms: f64,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+#[derive(Default)]
+pub struct ConnectionHealth {
+    degraded: bool,
+    consecutive_stats_failures: u32,
+    stats_in_flight: Option<(String, Instant)>,
+    last_stats_latency_ms: Option<u64>,
+    samples: VecDeque<u64>,
+    timeouts: u64,
+}
+
+impl ConnectionHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.degraded
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn record_request(&mut self, latency_ms: u64, timed_out: bool) {
+        if timed_out {
+            self.timeouts += 1;
+        }
+        self.samples.push_back(latency_ms);
+        while self.samples.len() > MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Called on each stats tick before the next GetStats goes out. A request
+    /// still unanswered a full interval later counts as a timeout.
+    pub fn stats_sent(&mut self, request_id: String, app: &tauri::AppHandle) {
+        if let Some((_, sent_at)) = self.stats_in_flight.take() {
+            self.record_request(sent_at.elapsed().as_millis() as u64, true);
+            self.stats_failed(app);
+        }
+        self.stats_in_flight = Some((request_id, Instant::now()));
+    }
+
+    pub fn stats_received(&mut self, request_id: &str, app: &tauri::AppHandle) {
+        let latency = match self.stats_in_flight.take() {
+            Some((id, sent_at)) if id == request_id => sent_at.elapsed().as_millis() as u64,
+            // Late reply to a request already counted as a timeout.
+            other => {
+                self.stats_in_flight = other;
+                return;
+            }
+        };
+        self.last_stats_latency_ms = Some(latency);
+        self.record_request(latency, false);
+
+        if latency > STATS_SLOW_MS {
+            self.stats_failed(app);
+            return;
+        }
+        self.consecutive_stats_failures = 0;
+        if self.degraded {
+            self.degraded = false;
+            log::info!("OBS is responding again ({} ms)", latency);
+            let _ = app.emit("obs://connection-recovered", self.report());
+        }
+    }
+
+    fn stats_failed(&mut self, app: &tauri::AppHandle) {
+        self.consecutive_stats_failures += 1;
+        if !self.degraded && self.consecutive_stats_failures >= DEGRADED_AFTER_FAILURES {
+            self.degraded = true;
+            log::warn!(
+                "OBS stopped answering requests ({} stats requests failed)",
+                self.consecutive_stats_failures
+            );
+            let _ = app.emit("obs://connection-degraded", self.report());
+        }
+    }
+
+    pub fn report(&self) -> HealthReport {
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let percentile = |p: f64| -> u64 {
+            if sorted.is_empty() {
+                return 0;
+            }
+            let idx = ((sorted.len() - 1) as f64 * p).round() as usize;
+            sorted[idx]
+        };
+        let avg_ms = if sorted.is_empty() {
+            0.0
+        } else {
+            sorted.iter().sum::<u64>() as f64 / sorted.len() as f64
+        };
+        HealthReport {
+            degraded: self.degraded,
+            consecutive_stats_failures: self.consecutive_stats_failures,
+            last_stats_latency_ms: self.last_stats_latency_ms,
+            sample_count: sorted.len(),
+            timeouts: self.timeouts,
+            avg_ms,
+            p50_ms: percentile(0.5),
+            p90_ms: percentile(0.9),
+            p99_ms: percentile(0.99),
+            max_ms: sorted.last().copied().unwrap_or(0),
+        }
+    }
+}
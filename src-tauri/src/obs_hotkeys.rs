@@ -0,0 +1,206 @@
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::settings::{self, SharedSettings};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::Manager;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Hotkey lists longer than this are left out of the AI system prompt.
+pub const PROMPT_LIST_LIMIT: usize = 60;
+
+/// A system-wide shortcut that fires an OBS hotkey by name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObsHotkeyBinding {
+    pub shortcut: String,
+    pub hotkey_name: String,
+}
+
+pub async fn fetch(conn: &ObsHandle) -> Result<Vec<String>, String> {
+    let resp = conn.send_request("GetHotkeyList", None).await?;
+    Ok(resp["hotkeys"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|h| h.as_str().map(String::from)).collect())
+        .unwrap_or_default())
+}
+
+/// The list cached in ObsState, fetched on first use. It's repopulated on
+/// every connect along with the rest of the state.
+pub async fn cached(conn: &ObsHandle, obs_state: &SharedObsState) -> Result<Vec<String>, String> {
+    {
+        let state = obs_state.read().await;
+        if !state.hotkeys.is_empty() {
+            return Ok(state.hotkeys.clone());
+        }
+    }
+    let hotkeys = fetch(conn).await?;
+    obs_state.write().await.hotkeys = hotkeys.clone();
+    Ok(hotkeys)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb { prev } else { 1 + prev.min(cur).min(row[j]) };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+fn closest<'a>(name: &str, hotkeys: &'a [String]) -> Option<&'a String> {
+    let wanted = name.to_lowercase();
+    hotkeys
+        .iter()
+        .map(|h| (edit_distance(&wanted, &h.to_lowercase()), h))
+        .filter(|(d, h)| *d <= h.len().max(wanted.len()) / 2)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, h)| h)
+}
+
+pub async fn trigger(conn: &ObsHandle, hotkeys: &[String], name: &str) -> Result<(), String> {
+    if !hotkeys.iter().any(|h| h == name) {
+        return Err(match closest(name, hotkeys) {
+            Some(suggestion) => format!("Unknown OBS hotkey '{}'. Did you mean '{}'?", name, suggestion),
+            None => format!("Unknown OBS hotkey '{}'", name),
+        });
+    }
+    conn.send_request("TriggerHotkeyByName", Some(json!({"hotkeyName": name})))
+        .await?;
+    Ok(())
+}
+
+/// Registers the saved bindings with the global shortcut plugin at startup.
+pub fn register_bindings(app: &tauri::AppHandle, bindings: &[ObsHotkeyBinding]) {
+    for binding in bindings {
+        match binding.shortcut.parse::<Shortcut>() {
+            Ok(shortcut) => {
+                if let Err(e) = app.global_shortcut().register(shortcut) {
+                    log::warn!("Failed to register shortcut '{}': {}", binding.shortcut, e);
+                }
+            }
+            Err(e) => log::warn!("Invalid shortcut '{}': {}", binding.shortcut, e),
+        }
+    }
+}
+
+/// Called from the global shortcut handler for anything that isn't one of
+/// the app's own action shortcuts.
+pub fn on_shortcut(app: &tauri::AppHandle, shortcut: &Shortcut, state: ShortcutState) {
+    if !matches!(state, ShortcutState::Pressed) {
+        return;
+    }
+    let app = app.clone();
+    let shortcut = *shortcut;
+    tauri::async_runtime::spawn(async move {
+        let hotkey_name = {
+            let settings = app.state::<SharedSettings>();
+            let s = settings.read().await;
+            s.hotkeys
+                .obs_bindings
+                .iter()
+                .find(|b| b.shortcut.parse::<Shortcut>().map(|s| s == shortcut).unwrap_or(false))
+                .map(|b| b.hotkey_name.clone())
+        };
+        let Some(name) = hotkey_name else {
+            return;
+        };
+        let conn = app.state::<ObsHandle>().inner().clone();
+        let obs_state = app.state::<SharedObsState>().inner().clone();
+        let result = match cached(&conn, &obs_state).await {
+            Ok(hotkeys) => trigger(&conn, &hotkeys, &name).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            log::warn!("Shortcut for OBS hotkey '{}' failed: {}", name, e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_obs_hotkey_list(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<Vec<String>, String> {
+    cached(&conn_state, &obs_state).await
+}
+
+#[tauri::command]
+pub async fn trigger_obs_hotkey(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    name: String,
+) -> Result<(), String> {
+    let hotkeys = cached(&conn_state, &obs_state).await?;
+    trigger(&conn_state, &hotkeys, &name).await
+}
+
+#[tauri::command]
+pub async fn get_obs_hotkey_bindings(
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<Vec<ObsHotkeyBinding>, String> {
+    Ok(settings.read().await.hotkeys.obs_bindings.clone())
+}
+
+#[tauri::command]
+pub async fn bind_obs_hotkey(
+    app: tauri::AppHandle,
+    settings: tauri::State<'_, SharedSettings>,
+    shortcut: String,
+    hotkey_name: String,
+) -> Result<Vec<ObsHotkeyBinding>, String> {
+    let parsed = shortcut
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", shortcut, e))?;
+    if crate::shortcuts::action_for(&app, &parsed).is_some() {
+        return Err(format!("'{}' is already used by one of OBServe's own shortcuts", shortcut));
+    }
+
+    let previous = settings.read().await.hotkeys.obs_bindings.iter().any(|b| {
+        b.shortcut.parse::<Shortcut>().map(|s| s == parsed).unwrap_or(false)
+    });
+    if !previous {
+        app.global_shortcut()
+            .register(parsed)
+            .map_err(|e| format!("Failed to register shortcut: {}", e))?;
+    }
+
+    settings::update_section(&settings, |s| {
+        s.hotkeys
+            .obs_bindings
+            .retain(|b| b.shortcut.parse::<Shortcut>().map(|s| s != parsed).unwrap_or(true));
+        s.hotkeys.obs_bindings.push(ObsHotkeyBinding {
+            shortcut: shortcut.clone(),
+            hotkey_name,
+        });
+    })
+    .await?;
+    Ok(settings.read().await.hotkeys.obs_bindings.clone())
+}
+
+#[tauri::command]
+pub async fn unbind_obs_hotkey(
+    app: tauri::AppHandle,
+    settings: tauri::State<'_, SharedSettings>,
+    shortcut: String,
+) -> Result<Vec<ObsHotkeyBinding>, String> {
+    let parsed = shortcut
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", shortcut, e))?;
+    settings::update_section(&settings, |s| {
+        s.hotkeys
+            .obs_bindings
+            .retain(|b| b.shortcut.parse::<Shortcut>().map(|s| s != parsed).unwrap_or(true));
+    })
+    .await?;
+    if let Err(e) = app.global_shortcut().unregister(parsed) {
+        log::warn!("Failed to unregister shortcut '{}': {}", shortcut, e);
+    }
+    Ok(settings.read().await.hotkeys.obs_bindings.clone())
+}
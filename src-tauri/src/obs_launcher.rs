@@ -18,6 +18,11 @@ pub struct ObsLaunchStatus {
     pub already_running: bool,
     pub path: Option<String>,
     pub error: Option<String>,
+    /// What a running OBS has selected, when `already_running`.
+    #[serde(default)]
+    pub running_profile: Option<String>,
+    #[serde(default)]
+    pub running_collection: Option<String>,
 }
 
 pub fn is_obs_running() -> bool {
@@ -80,13 +85,37 @@ fn find_obs_via_registry() -> Option<PathBuf> {
     None
 }
 
-pub fn launch_obs(minimize: bool) -> ObsLaunchStatus {
+/// Starts OBS, optionally on a given profile and scene collection. A
+/// running OBS is left alone; if it has something else selected than was
+/// asked for, that's reported as the error, since OBS only takes these
+/// arguments at startup.
+pub fn launch_obs(minimize: bool, profile: Option<&str>, collection: Option<&str>) -> ObsLaunchStatus {
     if is_obs_running() {
+        let (running_profile, running_collection) = crate::obs_config::selected_profile_and_collection();
+        let differs = |wanted: Option<&str>, running: &Option<String>| match (wanted, running) {
+            (Some(w), Some(r)) => !w.eq_ignore_ascii_case(r),
+            _ => false,
+        };
+        let error = if differs(profile, &running_profile) {
+            Some(format!(
+                "OBS is already running with profile '{}'",
+                running_profile.as_deref().unwrap_or_default()
+            ))
+        } else if differs(collection, &running_collection) {
+            Some(format!(
+                "OBS is already running with scene collection '{}'",
+                running_collection.as_deref().unwrap_or_default()
+            ))
+        } else {
+            None
+        };
         return ObsLaunchStatus {
             launched: false,
             already_running: true,
             path: None,
-            error: None,
+            error,
+            running_profile,
+            running_collection,
         };
     }
 
@@ -98,6 +127,8 @@ pub fn launch_obs(minimize: bool) -> ObsLaunchStatus {
                 already_running: false,
                 path: None,
                 error: Some("OBS Studio not found. Install it or check the installation path.".into()),
+                running_profile: None,
+                running_collection: None,
             };
         }
     };
@@ -108,6 +139,12 @@ pub fn launch_obs(minimize: bool) -> ObsLaunchStatus {
     if minimize {
         cmd.arg("--minimize-to-tray");
     }
+    if let Some(profile) = profile.filter(|p| !p.is_empty()) {
+        cmd.args(["--profile", profile]);
+    }
+    if let Some(collection) = collection.filter(|c| !c.is_empty()) {
+        cmd.args(["--collection", collection]);
+    }
 
     match cmd.spawn() {
         Ok(_) => ObsLaunchStatus {
@@ -115,12 +152,16 @@ pub fn launch_obs(minimize: bool) -> ObsLaunchStatus {
             already_running: false,
             path: Some(path_str),
             error: None,
+            running_profile: None,
+            running_collection: None,
         },
         Err(e) => ObsLaunchStatus {
             launched: false,
             already_running: false,
             path: Some(path_str),
             error: Some(format!("Failed to launch OBS: {}", e)),
+            running_profile: None,
+            running_collection: None,
         },
     }
 }
@@ -1,8 +1,13 @@
-use crate::obs_websocket::ObsConnection;
+use crate::obs_websocket::{BatchExecution, ObsHandle};
+use crate::plugin_check::MissingPluginsReport;
+use crate::stream_service;
+use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
+use tauri::Emitter;
 use tokio::sync::RwLock;
 
 pub type SharedObsState = Arc<RwLock<ObsState>>;
@@ -12,15 +17,26 @@ pub type SharedObsState = Arc<RwLock<ObsState>>;
 pub struct ObsState {
     pub scenes: Vec<SceneInfo>,
     pub current_scene: String,
+    pub studio_mode: bool,
+    /// The scene in studio mode's preview; None outside studio mode.
+    pub preview_scene: Option<String>,
+    pub transition: TransitionState,
     pub inputs: HashMap<String, InputInfo>,
     pub stream_status: StreamRecordStatus,
-    pub record_status: StreamRecordStatus,
+    pub record_status: RecordStatus,
+    pub replay_buffer_status: ReplayBufferStatus,
     pub stats: ObsStats,
+    pub stream_health: crate::stream_health::StreamHealth,
     pub video_settings: VideoSettings,
     pub stream_service: StreamServiceSettings,
     pub record_settings: RecordSettings,
     pub special_inputs: SpecialInputs,
     pub scene_items: HashMap<String, Vec<SceneItemInfo>>,
+    pub missing_plugins: MissingPluginsReport,
+    pub last_replay_path: Option<String>,
+    pub hotkeys: Vec<String>,
+    /// Filter kinds OBS has installed, from GetSourceFilterKindList.
+    pub filter_kinds: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +53,10 @@ pub struct SceneItemInfo {
     pub source_kind: String,
     pub scene_item_id: u64,
     pub enabled: bool,
+    /// Last known sceneItemTransform; Null until GetSceneItemList or a
+    /// transform event has reported it.
+    #[serde(default)]
+    pub transform: Value,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,6 +73,29 @@ pub struct InputInfo {
     pub audio_balance: f64,
     pub audio_sync_offset: i64,
     pub audio_tracks: Value,
+    /// What a browser source shows, a URL or a local file; empty for other kinds.
+    #[serde(default)]
+    pub browser_url: String,
+}
+
+impl InputInfo {
+    /// OBS's defaults for a new input, until its real values are fetched.
+    pub fn placeholder(name: &str, kind: &str) -> Self {
+        InputInfo {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            volume_db: 0.0,
+            volume_mul: 1.0,
+            muted: false,
+            monitor_type: String::new(),
+            filters: vec![],
+            device_id: String::new(),
+            audio_balance: 0.5,
+            audio_sync_offset: 0,
+            audio_tracks: json!({"1":true,"2":true,"3":false,"4":false,"5":false,"6":false}),
+            browser_url: String::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -65,6 +108,22 @@ pub struct SpecialInputs {
     pub mic3: String,
 }
 
+impl SpecialInputs {
+    pub fn rename(&mut self, old_name: &str, new_name: &str) {
+        for slot in [
+            &mut self.desktop1,
+            &mut self.desktop2,
+            &mut self.mic1,
+            &mut self.mic2,
+            &mut self.mic3,
+        ] {
+            if slot == old_name {
+                *slot = new_name.to_string();
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterInfo {
@@ -81,6 +140,108 @@ pub struct StreamRecordStatus {
     pub paused: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordStatus {
+    pub active: bool,
+    pub paused: bool,
+    /// File currently being written; follows splits.
+    pub output_path: Option<String>,
+    /// Elapsed recording time, refreshed with the periodic stats poll.
+    pub duration_ms: u64,
+}
+
+impl RecordStatus {
+    /// Applies a GetRecordStatus response. It carries no path, so the one
+    /// learned from events is kept.
+    pub fn update_from(&mut self, v: &Value) {
+        self.active = v["outputActive"].as_bool().unwrap_or(false);
+        self.paused = v["outputPaused"].as_bool().unwrap_or(false);
+        self.duration_ms = v["outputDuration"].as_u64().unwrap_or(0);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayBufferStatus {
+    /// False when the replay buffer is turned off in OBS's output settings.
+    pub available: bool,
+    pub active: bool,
+}
+
+/// Asks OBS for the replay buffer state; OBS answers with an error when the
+/// buffer isn't enabled, which reads as unavailable.
+pub async fn fetch_replay_buffer_status(conn: &ObsHandle) -> ReplayBufferStatus {
+    match conn.send_request("GetReplayBufferStatus", None).await {
+        Ok(v) => ReplayBufferStatus {
+            available: true,
+            active: v["outputActive"].as_bool().unwrap_or(false),
+        },
+        Err(_) => ReplayBufferStatus::default(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionState {
+    pub current: String,
+    /// OBS's transition duration. OBS keeps it while a fixed-length
+    /// transition like Cut is current but won't report it then, so it's
+    /// None until a non-fixed transition has been current.
+    pub duration_ms: Option<u64>,
+    pub available: Vec<TransitionInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionInfo {
+    pub name: String,
+    pub kind: String,
+    /// Fixed transitions ignore the duration.
+    pub fixed: bool,
+    pub configurable: bool,
+}
+
+impl TransitionState {
+    pub fn is_fixed(&self, name: &str) -> bool {
+        self.available.iter().any(|t| t.name == name && t.fixed)
+    }
+}
+
+/// Stores a freshly fetched transition list, keeping the known duration
+/// when a fixed transition is current and OBS didn't report one.
+pub fn store_transitions(state: &mut ObsState, mut fetched: TransitionState) -> TransitionState {
+    if fetched.duration_ms.is_none() {
+        fetched.duration_ms = state.transition.duration_ms;
+    }
+    state.transition = fetched.clone();
+    fetched
+}
+
+/// The transition list and the current transition's duration.
+pub async fn fetch_transitions(conn: &ObsHandle) -> Result<TransitionState, String> {
+    let list = conn.send_request("GetSceneTransitionList", None).await?;
+    let available = list["transitions"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|t| TransitionInfo {
+                    name: t["transitionName"].as_str().unwrap_or("").to_string(),
+                    kind: t["transitionKind"].as_str().unwrap_or("").to_string(),
+                    fixed: t["transitionFixed"].as_bool().unwrap_or(false),
+                    configurable: t["transitionConfigurable"].as_bool().unwrap_or(false),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let current = conn.send_request("GetCurrentSceneTransition", None).await?;
+    Ok(TransitionState {
+        current: current["transitionName"].as_str().unwrap_or("").to_string(),
+        duration_ms: current["transitionDuration"].as_u64(),
+        available,
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct VideoSettings {
@@ -98,12 +259,16 @@ pub struct StreamServiceSettings {
     pub service_type: String,
     pub server: String,
     pub key_set: bool,
+    pub key_length: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct RecordSettings {
     pub record_directory: String,
+    pub video_bitrate_kbps: u32,
+    pub audio_bitrate_kbps: u32,
+    pub rate_control: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -126,15 +291,184 @@ impl ObsState {
     }
 }
 
+fn parse_scene_items(items_data: &Value) -> Vec<SceneItemInfo> {
+    items_data["sceneItems"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|item| SceneItemInfo {
+                    source_name: item["sourceName"]
+                        .as_str()
+                        .unwrap_or("")
+                        .to_string(),
+                    source_kind: item["inputKind"]
+                        .as_str()
+                        .or_else(|| item["sourceType"].as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    scene_item_id: item["sceneItemId"].as_u64().unwrap_or(0),
+                    enabled: item["sceneItemEnabled"].as_bool().unwrap_or(true),
+                    transform: item["sceneItemTransform"].clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Re-reads one scene's items, transforms included, for callers that can't
+/// rely on transform events having kept up.
+pub async fn refresh_scene_items(
+    conn: &ObsHandle,
+    state: &SharedObsState,
+    scene: &str,
+) -> Result<(), String> {
+    let items_data = conn
+        .send_request("GetSceneItemList", Some(json!({"sceneName": scene})))
+        .await?;
+    let items = parse_scene_items(&items_data);
+    state.write().await.scene_items.insert(scene.to_string(), items);
+    Ok(())
+}
+
+/// Filters from a GetSourceFilterList response.
+pub fn parse_filters(data: &Value) -> Vec<FilterInfo> {
+    data["filters"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|f| FilterInfo {
+                    name: f["filterName"].as_str().unwrap_or("").to_string(),
+                    kind: f["filterKind"].as_str().unwrap_or("").to_string(),
+                    enabled: f["filterEnabled"].as_bool().unwrap_or(true),
+                    settings: f["filterSettings"].clone(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The page a browser source shows, from its settings.
+pub fn browser_url(settings: &Value) -> String {
+    let key = if settings["is_local_file"].as_bool().unwrap_or(false) {
+        "local_file"
+    } else {
+        "url"
+    };
+    settings[key].as_str().unwrap_or("").to_string()
+}
+
+pub async fn fetch_filters(conn: &ObsHandle, source_name: &str) -> Result<Vec<FilterInfo>, String> {
+    let data = conn
+        .send_request("GetSourceFilterList", Some(json!({"sourceName": source_name})))
+        .await?;
+    Ok(parse_filters(&data))
+}
+
+/// Reads everything InputInfo holds for one input in a single RequestBatch.
+/// Fails only if the input itself is gone; other gaps fall back to defaults
+/// the way populate_initial_state does.
+pub async fn fetch_input(conn: &ObsHandle, input_name: &str) -> Result<InputInfo, String> {
+    let by_input = json!({"inputName": input_name});
+    let requests = [
+        "GetInputSettings",
+        "GetInputVolume",
+        "GetInputMute",
+        "GetInputAudioMonitorType",
+        "GetInputAudioBalance",
+        "GetInputAudioSyncOffset",
+        "GetInputAudioTracks",
+    ]
+    .iter()
+    .map(|r| (r.to_string(), Some(by_input.clone())))
+    .chain(std::iter::once((
+        "GetSourceFilterList".to_string(),
+        Some(json!({"sourceName": input_name})),
+    )))
+    .collect();
+    let mut results = conn
+        .send_batch(requests, BatchExecution::SerialRealtime, false)
+        .await?
+        .into_iter();
+    let mut next = || results.next().and_then(Result::ok);
+
+    let settings = next().ok_or_else(|| format!("Input '{}' not found", input_name))?;
+    let kind = settings["inputKind"].as_str().unwrap_or("").to_string();
+    let device_id = if kind.contains("wasapi_input_capture") || kind.contains("wasapi_output_capture") {
+        settings["inputSettings"]["device_id"]
+            .as_str()
+            .unwrap_or("default")
+            .to_string()
+    } else {
+        String::new()
+    };
+    let browser_url = if kind == "browser_source" {
+        browser_url(&settings["inputSettings"])
+    } else {
+        String::new()
+    };
+    let volume = next();
+    let muted = next();
+    let monitor = next();
+    let balance = next();
+    let sync_offset = next();
+    let tracks = next();
+    let filters = next();
+
+    Ok(InputInfo {
+        name: input_name.to_string(),
+        kind,
+        volume_db: volume.as_ref().and_then(|v| v["inputVolumeDb"].as_f64()).unwrap_or(0.0),
+        volume_mul: volume.as_ref().and_then(|v| v["inputVolumeMul"].as_f64()).unwrap_or(1.0),
+        muted: muted.and_then(|v| v["inputMuted"].as_bool()).unwrap_or(false),
+        monitor_type: monitor
+            .and_then(|v| v["monitorType"].as_str().map(String::from))
+            .unwrap_or_default(),
+        filters: filters.map(|v| parse_filters(&v)).unwrap_or_default(),
+        device_id,
+        audio_balance: balance.and_then(|v| v["inputAudioBalance"].as_f64()).unwrap_or(0.5),
+        audio_sync_offset: sync_offset
+            .and_then(|v| v["inputAudioSyncOffset"].as_i64())
+            .unwrap_or(0),
+        audio_tracks: tracks
+            .and_then(|v| v.get("inputAudioTracks").cloned())
+            .unwrap_or(json!({"1":true,"2":true,"3":false,"4":false,"5":false,"6":false})),
+        browser_url,
+    })
+}
+
+/// Inputs whose details are fetched at once while populating.
+const POPULATE_CONCURRENCY: usize = 8;
+
+/// Loads everything ObsState tracks. With `app`, an `obs://state-sync` goes
+/// out as soon as the scene and input lists are known, so the UI can draw
+/// them while volumes, filters and the rest are still on their way. A
+/// disconnect part-way leaves the state cleared rather than half-written.
 pub async fn populate_initial_state(
-    conn: &ObsConnection,
+    conn: &ObsHandle,
     state: &SharedObsState,
+    app: Option<&tauri::AppHandle>,
 ) -> Result<(), String> {
-    let scene_data = conn.send_request("GetSceneList", None).await?;
+    let started = Instant::now();
+    let (scene_data, input_data, special_data, studio_data) = tokio::join!(
+        conn.send_request("GetSceneList", None),
+        conn.send_request("GetInputList", None),
+        conn.send_request("GetSpecialInputs", None),
+        conn.send_request("GetStudioModeEnabled", None),
+    );
+    let scene_data = scene_data?;
     let current_scene = scene_data["currentProgramSceneName"]
         .as_str()
         .unwrap_or("")
         .to_string();
+    let studio_mode = studio_data
+        .ok()
+        .and_then(|v| v["studioModeEnabled"].as_bool())
+        .unwrap_or(false);
+    // Null outside studio mode
+    let preview_scene = scene_data["currentPreviewSceneName"]
+        .as_str()
+        .filter(|_| studio_mode)
+        .map(String::from);
     let scenes: Vec<SceneInfo> = scene_data["scenes"]
         .as_array()
         .map(|arr| {
@@ -148,9 +482,8 @@ pub async fn populate_initial_state(
         })
         .unwrap_or_default();
 
-    let special_data = conn.send_request("GetSpecialInputs", None).await.ok();
     let special_inputs = special_data
-        .as_ref()
+        .ok()
         .map(|v| SpecialInputs {
             desktop1: v["desktop1"].as_str().unwrap_or("").to_string(),
             desktop2: v["desktop2"].as_str().unwrap_or("").to_string(),
@@ -160,353 +493,245 @@ pub async fn populate_initial_state(
         })
         .unwrap_or_default();
 
-    let input_data = conn.send_request("GetInputList", None).await?;
-    let mut inputs = HashMap::new();
-
-    if let Some(input_list) = input_data["inputs"].as_array() {
-        for input in input_list {
-            let name = input["inputName"].as_str().unwrap_or("").to_string();
-            let kind = input["inputKind"].as_str().unwrap_or("").to_string();
-
-            let (volume_db, volume_mul) = conn
-                .send_request("GetInputVolume", Some(json!({"inputName": &name})))
-                .await
-                .ok()
-                .map(|v| {
+    // Listed inputs, then the global audio sources GetInputList can leave out.
+    let mut listed: Vec<(String, String)> = input_data?["inputs"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .map(|input| {
                     (
-                        v["inputVolumeDb"].as_f64().unwrap_or(0.0),
-                        v["inputVolumeMul"].as_f64().unwrap_or(1.0),
+                        input["inputName"].as_str().unwrap_or("").to_string(),
+                        input["inputKind"].as_str().unwrap_or("").to_string(),
                     )
                 })
-                .unwrap_or((0.0, 1.0));
-
-            let muted = conn
-                .send_request("GetInputMute", Some(json!({"inputName": &name})))
-                .await
-                .ok()
-                .and_then(|v| v["inputMuted"].as_bool())
-                .unwrap_or(false);
-
-            let monitor_type = conn
-                .send_request(
-                    "GetInputAudioMonitorType",
-                    Some(json!({"inputName": &name})),
-                )
-                .await
-                .ok()
-                .and_then(|v| v["monitorType"].as_str().map(String::from))
-                .unwrap_or_default();
-
-            let filters = conn
-                .send_request("GetSourceFilterList", Some(json!({"sourceName": &name})))
-                .await
-                .ok()
-                .and_then(|v| {
-                    v["filters"].as_array().map(|arr| {
-                        arr.iter()
-                            .map(|f| FilterInfo {
-                                name: f["filterName"].as_str().unwrap_or("").to_string(),
-                                kind: f["filterKind"].as_str().unwrap_or("").to_string(),
-                                enabled: f["filterEnabled"].as_bool().unwrap_or(true),
-                                settings: f["filterSettings"].clone(),
-                            })
-                            .collect()
-                    })
-                })
-                .unwrap_or_default();
-
-            let device_id = if kind.contains("wasapi_input_capture")
-                || kind.contains("wasapi_output_capture")
-            {
-                conn.send_request(
-                    "GetInputSettings",
-                    Some(json!({"inputName": &name})),
-                )
-                .await
-                .ok()
-                .and_then(|v| {
-                    v["inputSettings"]["device_id"]
-                        .as_str()
-                        .map(String::from)
-                })
-                .unwrap_or_else(|| "default".to_string())
-            } else {
-                String::new()
-            };
-
-            let audio_balance = conn
-                .send_request("GetInputAudioBalance", Some(json!({"inputName": &name})))
-                .await
-                .ok()
-                .and_then(|v| v["inputAudioBalance"].as_f64())
-                .unwrap_or(0.5);
-
-            let audio_sync_offset = conn
-                .send_request("GetInputAudioSyncOffset", Some(json!({"inputName": &name})))
-                .await
-                .ok()
-                .and_then(|v| v["inputAudioSyncOffset"].as_i64())
-                .unwrap_or(0);
-
-            let audio_tracks = conn
-                .send_request("GetInputAudioTracks", Some(json!({"inputName": &name})))
-                .await
-                .ok()
-                .and_then(|v| v.get("inputAudioTracks").cloned())
-                .unwrap_or(json!({"1":true,"2":true,"3":false,"4":false,"5":false,"6":false}));
-
-            inputs.insert(
-                name.clone(),
-                InputInfo {
-                    name,
-                    kind,
-                    volume_db,
-                    volume_mul,
-                    muted,
-                    monitor_type,
-                    filters,
-                    device_id,
-                    audio_balance,
-                    audio_sync_offset,
-                    audio_tracks,
-                },
-            );
-        }
-    }
-
-    // Also populate special inputs (global audio sources) that might not appear in GetInputList
-    let special_names = [
+                .collect()
+        })
+        .unwrap_or_default();
+    for name in [
         &special_inputs.desktop1,
         &special_inputs.desktop2,
         &special_inputs.mic1,
         &special_inputs.mic2,
         &special_inputs.mic3,
-    ];
-    for sname in special_names {
-        if sname.is_empty() || inputs.contains_key(sname) {
-            continue;
+    ] {
+        if !name.is_empty() && !listed.iter().any(|(n, _)| n == name) {
+            listed.push((name.clone(), String::new()));
         }
-        let (volume_db, volume_mul) = conn
-            .send_request("GetInputVolume", Some(json!({"inputName": sname})))
-            .await
-            .ok()
-            .map(|v| {
+    }
+    let lists_ms = started.elapsed().as_millis();
+
+    if let Some(app) = app {
+        let snapshot = {
+            let mut s = state.write().await;
+            if !conn.is_connected() {
+                return Err("Disconnected while loading OBS state".into());
+            }
+            s.scenes = scenes.clone();
+            s.current_scene = current_scene.clone();
+            s.studio_mode = studio_mode;
+            s.preview_scene = preview_scene.clone();
+            s.special_inputs = special_inputs.clone();
+            s.clone()
+        };
+        let _ = app.emit("obs://state-sync", &snapshot);
+    }
+
+    let details_started = Instant::now();
+    let inputs_future = futures_util::stream::iter(
+        listed
+            .iter()
+            .map(|(name, kind)| async move { (name, kind, fetch_input(conn, name).await) }),
+    )
+    .buffer_unordered(POPULATE_CONCURRENCY)
+    .then(|(name, kind, fetched)| async move {
+        // An input whose details failed still exists; show it with OBS's
+        // defaults rather than dropping it.
+        let input = fetched.unwrap_or_else(|e| {
+            log::warn!("Failed to load details of input \"{}\": {}", name, e);
+            InputInfo::placeholder(name, kind)
+        });
+        // Each input appears as soon as its details are in.
+        {
+            let mut s = state.write().await;
+            if conn.is_connected() {
+                s.inputs.insert(input.name.clone(), input.clone());
+            }
+        }
+        if let Some(app) = app {
+            let _ = app.emit("obs://input-loaded", &input);
+        }
+        (input.name.clone(), input)
+    })
+    .collect::<HashMap<String, InputInfo>>();
+
+    let scene_items_future = async {
+        let requests = scenes
+            .iter()
+            .map(|scene| {
                 (
-                    v["inputVolumeDb"].as_f64().unwrap_or(0.0),
-                    v["inputVolumeMul"].as_f64().unwrap_or(1.0),
+                    "GetSceneItemList".to_string(),
+                    Some(json!({"sceneName": &scene.name})),
                 )
             })
-            .unwrap_or((0.0, 1.0));
-
-        let muted = conn
-            .send_request("GetInputMute", Some(json!({"inputName": sname})))
+            .collect();
+        let outcomes = conn
+            .send_batch(requests, BatchExecution::SerialRealtime, false)
             .await
-            .ok()
-            .and_then(|v| v["inputMuted"].as_bool())
-            .unwrap_or(false);
-
-        let monitor_type = conn
-            .send_request(
-                "GetInputAudioMonitorType",
-                Some(json!({"inputName": sname})),
-            )
-            .await
-            .ok()
-            .and_then(|v| v["monitorType"].as_str().map(String::from))
             .unwrap_or_default();
+        scenes
+            .iter()
+            .zip(outcomes)
+            .filter_map(|(scene, outcome)| {
+                outcome
+                    .ok()
+                    .map(|items_data| (scene.name.clone(), parse_scene_items(&items_data)))
+            })
+            .collect::<HashMap<String, Vec<SceneItemInfo>>>()
+    };
 
-        let filters = conn
-            .send_request("GetSourceFilterList", Some(json!({"sourceName": sname})))
+    let stream_status_future = async {
+        conn.send_request("GetStreamStatus", None)
             .await
             .ok()
-            .and_then(|v| {
-                v["filters"].as_array().map(|arr| {
-                    arr.iter()
-                        .map(|f| FilterInfo {
-                            name: f["filterName"].as_str().unwrap_or("").to_string(),
-                            kind: f["filterKind"].as_str().unwrap_or("").to_string(),
-                            enabled: f["filterEnabled"].as_bool().unwrap_or(true),
-                            settings: f["filterSettings"].clone(),
-                        })
-                        .collect()
-                })
+            .map(|v| StreamRecordStatus {
+                active: v["outputActive"].as_bool().unwrap_or(false),
+                paused: false,
             })
-            .unwrap_or_default();
+            .unwrap_or_default()
+    };
 
-        let kind_result = conn
-            .send_request("GetInputSettings", Some(json!({"inputName": sname})))
-            .await
-            .ok();
-        let kind = kind_result
-            .as_ref()
-            .and_then(|v| v["inputKind"].as_str().map(String::from))
-            .unwrap_or_default();
-        let device_id = kind_result
-            .as_ref()
-            .and_then(|v| v["inputSettings"]["device_id"].as_str().map(String::from))
-            .unwrap_or_else(|| "default".to_string());
+    let record_status_future = async {
+        let mut record_status = RecordStatus::default();
+        if let Ok(v) = conn.send_request("GetRecordStatus", None).await {
+            record_status.update_from(&v);
+        }
+        record_status
+    };
 
-        let audio_balance = conn
-            .send_request("GetInputAudioBalance", Some(json!({"inputName": sname})))
+    let stats_future = async {
+        conn.send_request("GetStats", None)
             .await
             .ok()
-            .and_then(|v| v["inputAudioBalance"].as_f64())
-            .unwrap_or(0.5);
+            .map(|v| ObsStats {
+                active_fps: v["activeFps"].as_f64().unwrap_or(0.0),
+                cpu_usage: v["cpuUsage"].as_f64().unwrap_or(0.0),
+                memory_usage: v["memoryUsage"].as_f64().unwrap_or(0.0),
+                render_skipped_frames: v["renderSkippedFrames"].as_u64().unwrap_or(0),
+                output_skipped_frames: v["outputSkippedFrames"].as_u64().unwrap_or(0),
+            })
+            .unwrap_or_default()
+    };
 
-        let audio_sync_offset = conn
-            .send_request("GetInputAudioSyncOffset", Some(json!({"inputName": sname})))
+    let video_settings_future = async {
+        conn.send_request("GetVideoSettings", None)
             .await
             .ok()
-            .and_then(|v| v["inputAudioSyncOffset"].as_i64())
-            .unwrap_or(0);
+            .map(|v| VideoSettings {
+                base_width: v["baseWidth"].as_u64().unwrap_or(0) as u32,
+                base_height: v["baseHeight"].as_u64().unwrap_or(0) as u32,
+                output_width: v["outputWidth"].as_u64().unwrap_or(0) as u32,
+                output_height: v["outputHeight"].as_u64().unwrap_or(0) as u32,
+                fps_numerator: v["fpsNumerator"].as_u64().unwrap_or(0) as u32,
+                fps_denominator: v["fpsDenominator"].as_u64().unwrap_or(1) as u32,
+            })
+            .unwrap_or_default()
+    };
 
-        let audio_tracks = conn
-            .send_request("GetInputAudioTracks", Some(json!({"inputName": sname})))
+    let stream_service_future = async {
+        conn.send_request("GetStreamServiceSettings", None)
             .await
             .ok()
-            .and_then(|v| v.get("inputAudioTracks").cloned())
-            .unwrap_or(json!({"1":true,"2":true,"3":false,"4":false,"5":false,"6":false}));
-
-        inputs.insert(
-            sname.clone(),
-            InputInfo {
-                name: sname.clone(),
-                kind,
-                volume_db,
-                volume_mul,
-                muted,
-                monitor_type,
-                filters,
-                device_id,
-                audio_balance,
-                audio_sync_offset,
-                audio_tracks,
-            },
-        );
-    }
-
-    let stream_status = conn
-        .send_request("GetStreamStatus", None)
-        .await
-        .ok()
-        .map(|v| StreamRecordStatus {
-            active: v["outputActive"].as_bool().unwrap_or(false),
-            paused: false,
-        })
-        .unwrap_or_default();
-
-    let record_status = conn
-        .send_request("GetRecordStatus", None)
-        .await
-        .ok()
-        .map(|v| StreamRecordStatus {
-            active: v["outputActive"].as_bool().unwrap_or(false),
-            paused: v["outputPaused"].as_bool().unwrap_or(false),
-        })
-        .unwrap_or_default();
-
-    let stats_data = conn.send_request("GetStats", None).await.ok();
-    let stats = stats_data
-        .map(|v| ObsStats {
-            active_fps: v["activeFps"].as_f64().unwrap_or(0.0),
-            cpu_usage: v["cpuUsage"].as_f64().unwrap_or(0.0),
-            memory_usage: v["memoryUsage"].as_f64().unwrap_or(0.0),
-            render_skipped_frames: v["renderSkippedFrames"].as_u64().unwrap_or(0),
-            output_skipped_frames: v["outputSkippedFrames"].as_u64().unwrap_or(0),
-        })
-        .unwrap_or_default();
-
-    let video_settings = conn
-        .send_request("GetVideoSettings", None)
-        .await
-        .ok()
-        .map(|v| VideoSettings {
-            base_width: v["baseWidth"].as_u64().unwrap_or(0) as u32,
-            base_height: v["baseHeight"].as_u64().unwrap_or(0) as u32,
-            output_width: v["outputWidth"].as_u64().unwrap_or(0) as u32,
-            output_height: v["outputHeight"].as_u64().unwrap_or(0) as u32,
-            fps_numerator: v["fpsNumerator"].as_u64().unwrap_or(0) as u32,
-            fps_denominator: v["fpsDenominator"].as_u64().unwrap_or(1) as u32,
-        })
-        .unwrap_or_default();
+            .map(|v| stream_service::settings_from_response(&v))
+            .unwrap_or_default()
+    };
 
-    let stream_service = conn
-        .send_request("GetStreamServiceSettings", None)
-        .await
-        .ok()
-        .map(|v| {
-            let settings = &v["streamServiceSettings"];
-            StreamServiceSettings {
-                service_type: v["streamServiceType"]
+    let record_settings_future = async {
+        let mut record_settings = conn
+            .send_request("GetRecordDirectory", None)
+            .await
+            .ok()
+            .map(|v| RecordSettings {
+                record_directory: v["recordDirectory"]
                     .as_str()
                     .unwrap_or("")
                     .to_string(),
-                server: settings["server"].as_str().unwrap_or("").to_string(),
-                key_set: settings["key"]
-                    .as_str()
-                    .map(|k| !k.is_empty())
-                    .unwrap_or(false),
-            }
-        })
-        .unwrap_or_default();
-
-    let record_settings = conn
-        .send_request("GetRecordDirectory", None)
-        .await
-        .ok()
-        .map(|v| RecordSettings {
-            record_directory: v["recordDirectory"]
-                .as_str()
-                .unwrap_or("")
-                .to_string(),
-        })
-        .unwrap_or_default();
-
-    let mut scene_items = HashMap::new();
-    for scene in &scenes {
-        if let Ok(items_data) = conn
-            .send_request(
-                "GetSceneItemList",
-                Some(json!({"sceneName": &scene.name})),
-            )
-            .await
-        {
-            let items: Vec<SceneItemInfo> = items_data["sceneItems"]
-                .as_array()
-                .map(|arr| {
-                    arr.iter()
-                        .map(|item| SceneItemInfo {
-                            source_name: item["sourceName"]
-                                .as_str()
-                                .unwrap_or("")
-                                .to_string(),
-                            source_kind: item["inputKind"]
-                                .as_str()
-                                .or_else(|| item["sourceType"].as_str())
-                                .unwrap_or("")
-                                .to_string(),
-                            scene_item_id: item["sceneItemId"].as_u64().unwrap_or(0),
-                            enabled: item["sceneItemEnabled"].as_bool().unwrap_or(true),
-                        })
-                        .collect()
-                })
-                .unwrap_or_default();
-            scene_items.insert(scene.name.clone(), items);
+                ..Default::default()
+            })
+            .unwrap_or_default();
+        crate::disk_headroom::fetch_encoder_settings(conn, &mut record_settings).await;
+        record_settings
+    };
+
+    let (
+        inputs,
+        scene_items,
+        stream_status,
+        record_status,
+        replay_buffer_status,
+        stats,
+        video_settings,
+        stream_service,
+        record_settings,
+        transition,
+        hotkeys,
+        filter_kinds,
+    ) = tokio::join!(
+        inputs_future,
+        scene_items_future,
+        stream_status_future,
+        record_status_future,
+        fetch_replay_buffer_status(conn),
+        stats_future,
+        video_settings_future,
+        stream_service_future,
+        record_settings_future,
+        fetch_transitions(conn),
+        crate::obs_hotkeys::fetch(conn),
+        fetch_filter_kinds(conn),
+    );
+    let details_ms = details_started.elapsed().as_millis();
+
+    {
+        let mut s = state.write().await;
+        // The reader task clears the state when the link drops; writing now
+        // would bring back a stale copy.
+        if !conn.is_connected() {
+            return Err("Disconnected while loading OBS state".into());
         }
+        s.scenes = scenes;
+        s.current_scene = current_scene;
+        s.studio_mode = studio_mode;
+        s.preview_scene = preview_scene;
+        s.transition = transition.unwrap_or_default();
+        s.inputs = inputs;
+        s.stream_status = stream_status;
+        s.record_status = record_status;
+        s.replay_buffer_status = replay_buffer_status;
+        s.stats = stats;
+        s.video_settings = video_settings;
+        s.stream_service = stream_service;
+        s.record_settings = record_settings;
+        s.special_inputs = special_inputs;
+        s.scene_items = scene_items;
+        s.hotkeys = hotkeys.unwrap_or_default();
+        s.filter_kinds = filter_kinds.unwrap_or_default();
     }
 
-    let mut s = state.write().await;
-    s.scenes = scenes;
-    s.current_scene = current_scene;
-    s.inputs = inputs;
-    s.stream_status = stream_status;
-    s.record_status = record_status;
-    s.stats = stats;
-    s.video_settings = video_settings;
-    s.stream_service = stream_service;
-    s.record_settings = record_settings;
-    s.special_inputs = special_inputs;
-    s.scene_items = scene_items;
-
+    log::info!(
+        "OBS state loaded in {}ms ({} scenes, {} inputs; lists {}ms, details {}ms)",
+        started.elapsed().as_millis(),
+        scene_data["scenes"].as_array().map_or(0, Vec::len),
+        listed.len(),
+        lists_ms,
+        details_ms
+    );
     Ok(())
 }
+
+pub async fn fetch_filter_kinds(conn: &ObsHandle) -> Result<Vec<String>, String> {
+    let resp = conn.send_request("GetSourceFilterKindList", None).await?;
+    Ok(resp["sourceFilterKinds"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default())
+}
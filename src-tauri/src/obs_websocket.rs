@@ -1,53 +1,172 @@
 use base64::Engine;
+use futures_util::future::BoxFuture;
 use futures_util::{SinkExt, StreamExt};
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{mpsc, oneshot, Mutex};
 use tokio_tungstenite::tungstenite::Message;
 
+use crate::event_subscriptions::{SharedEventSubscriptions, SubscriptionRegistry};
+use crate::filter_history;
+use crate::i18n::t;
+use crate::obs_health::{ConnectionHealth, SharedObsHealth};
 use crate::obs_state::{FilterInfo, InputInfo, ObsStats, SharedObsState};
-use tauri::Emitter;
+use crate::ws_log::{SharedWsLog, WsLog};
+use tauri::{Emitter, Manager};
+
+/// Waits between reconnect attempts after the link drops; the last repeats.
+const RECONNECT_DELAYS_SECS: &[u64] = &[1, 2, 5, 10, 20, 30];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+type PendingRequests = Arc<Mutex<HashMap<String, oneshot::Sender<Value>>>>;
+
+/// obs-websocket RequestBatchExecutionType.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchExecution {
+    /// One after another, as fast as possible.
+    #[default]
+    SerialRealtime = 0,
+    /// One request per graphics frame; lets Sleep count frames.
+    SerialFrame = 1,
+    /// All at once on a thread pool; order is not guaranteed.
+    Parallel = 2,
+}
+
+/// Why `ObsConnection::connect` failed. A rejected password is split out
+/// because retrying it won't help.
+#[derive(Debug)]
+pub enum ConnectError {
+    Auth(String),
+    Other(String),
+}
+
+impl std::fmt::Display for ConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectError::Auth(msg) | ConnectError::Other(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl From<String> for ConnectError {
+    fn from(msg: String) -> Self {
+        ConnectError::Other(msg)
+    }
+}
+
+impl From<&str> for ConnectError {
+    fn from(msg: &str) -> Self {
+        ConnectError::Other(msg.to_string())
+    }
+}
+
+impl From<ConnectError> for String {
+    fn from(e: ConnectError) -> Self {
+        e.to_string()
+    }
+}
+
+/// obs-websocket WebSocketCloseCode::AuthenticationFailed.
+const CLOSE_AUTHENTICATION_FAILED: u16 = 4009;
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ObsStatus {
     pub connected: bool,
     pub obs_version: Option<String>,
     pub ws_version: Option<String>,
+    pub degraded: bool,
 }
 
+/// Cheap, cloneable handle for talking to OBS. Requests from any number of
+/// tasks go out concurrently and are matched to responses by requestId, so
+/// callers never need the connection mutex.
+#[derive(Clone)]
+pub struct ObsHandle {
+    sender: Arc<std::sync::RwLock<Option<mpsc::Sender<Message>>>>,
+    pending: PendingRequests,
+    connected_flag: Arc<AtomicBool>,
+    ws_log: SharedWsLog,
+    health: SharedObsHealth,
+    subscriptions: SharedEventSubscriptions,
+    active_subscriptions: Arc<AtomicU64>,
+}
+
+/// Owns the connection lifecycle (handshake, reader task, teardown). Only
+/// connect/disconnect go through SharedObsConnection's mutex; everything else
+/// uses the handle.
 pub struct ObsConnection {
-    sender: Option<mpsc::Sender<Message>>,
-    pending: Arc<Mutex<HashMap<String, oneshot::Sender<Value>>>>,
+    handle: ObsHandle,
     status: ObsStatus,
-    connected_flag: Arc<AtomicBool>,
+    /// Bumped by every connect/disconnect. A reader task that ends while its
+    /// session is still current lost the link; otherwise the user moved on.
+    session: Arc<AtomicU64>,
+    auto_reconnect: Arc<AtomicBool>,
+    stop: Option<oneshot::Sender<()>>,
+}
+
+/// Where the last successful connection went, for reconnecting.
+#[derive(Clone)]
+struct ConnectTarget {
+    host: String,
+    port: u16,
+    password: Option<String>,
+}
+
+impl std::ops::Deref for ObsConnection {
+    type Target = ObsHandle;
+
+    fn deref(&self) -> &ObsHandle {
+        &self.handle
+    }
 }
 
 impl ObsConnection {
     pub fn new() -> Self {
         Self {
-            sender: None,
-            pending: Arc::new(Mutex::new(HashMap::new())),
+            handle: ObsHandle {
+                sender: Arc::new(std::sync::RwLock::new(None)),
+                pending: Arc::new(Mutex::new(HashMap::new())),
+                connected_flag: Arc::new(AtomicBool::new(false)),
+                ws_log: Arc::new(Mutex::new(WsLog::new())),
+                health: Arc::new(std::sync::Mutex::new(ConnectionHealth::new())),
+                subscriptions: Arc::new(std::sync::Mutex::new(SubscriptionRegistry::default())),
+                active_subscriptions: Arc::new(AtomicU64::new(0)),
+            },
             status: ObsStatus {
                 connected: false,
                 obs_version: None,
                 ws_version: None,
+                degraded: false,
             },
-            connected_flag: Arc::new(AtomicBool::new(false)),
+            session: Arc::new(AtomicU64::new(0)),
+            auto_reconnect: Arc::new(AtomicBool::new(true)),
+            stop: None,
         }
     }
 
+    pub fn handle(&self) -> ObsHandle {
+        self.handle.clone()
+    }
+
     pub fn status(&self) -> ObsStatus {
         ObsStatus {
-            connected: self.connected_flag.load(Ordering::Relaxed),
+            connected: self.is_connected(),
+            degraded: self.is_degraded(),
             ..self.status.clone()
         }
     }
 
-    pub fn is_connected(&self) -> bool {
-        self.connected_flag.load(Ordering::Relaxed)
+    pub fn set_auto_reconnect(&self, enabled: bool) {
+        self.auto_reconnect.store(enabled, Ordering::Relaxed);
+    }
+
+    fn session(&self) -> u64 {
+        self.session.load(Ordering::Relaxed)
     }
 
     pub async fn connect(
@@ -57,10 +176,11 @@ impl ObsConnection {
         password: Option<&str>,
         app_handle: tauri::AppHandle,
         obs_state: SharedObsState,
-    ) -> Result<(), String> {
+    ) -> Result<(), ConnectError> {
         if self.is_connected() {
             self.disconnect().await;
         }
+        let session = self.session.fetch_add(1, Ordering::Relaxed) + 1;
 
         let url = format!("ws://{}:{}", host, port);
         let (ws_stream, _) = tokio_tungstenite::connect_async(&url)
@@ -68,6 +188,7 @@ impl ObsConnection {
             .map_err(|e| format!("Failed to connect to OBS: {}", e))?;
 
         let (mut write, mut read) = ws_stream.split();
+        self.ws_log.lock().await.set_app_handle(app_handle.clone());
 
         let hello: Value = loop {
             let msg = read
@@ -103,6 +224,8 @@ impl ObsConnection {
             }
         };
 
+        crate::ws_log::log_incoming(&self.ws_log, &hello).await;
+
         let op = hello["op"].as_u64().unwrap_or(0);
         if op != 0 {
             return Err(format!("Expected Hello (op 0), got op {}", op));
@@ -114,9 +237,8 @@ impl ObsConnection {
             .unwrap_or("unknown")
             .to_string();
 
-        // Event subscription bitmask:
-        // General(1) | Config(2) | Scenes(4) | Inputs(8) | Filters(32) | Outputs(64) | SceneItems(128) | InputVolumeMeters(1<<16)
-        let event_subscriptions: u64 = 1 | 2 | 4 | 8 | 32 | 64 | 128 | (1 << 16);
+        // Base groups plus whatever features have declared; see event_subscriptions.
+        let event_subscriptions = self.subscriptions.lock().unwrap().mask();
 
         let mut identify = json!({
             "op": 1,
@@ -127,8 +249,9 @@ impl ObsConnection {
         });
 
         if let Some(auth) = hello_data.get("authentication") {
-            let pw =
-                password.ok_or("OBS requires a password but none was provided")?;
+            let pw = password.ok_or_else(|| {
+                ConnectError::Auth("OBS requires a password but none was provided".to_string())
+            })?;
             let challenge = auth["challenge"]
                 .as_str()
                 .ok_or("Missing auth challenge")?;
@@ -137,6 +260,8 @@ impl ObsConnection {
             identify["d"]["authentication"] = json!(auth_string);
         }
 
+        crate::ws_log::log_outgoing(&self.ws_log, &identify).await;
+
         write
             .send(Message::Text(identify.to_string().into()))
             .await
@@ -165,21 +290,32 @@ impl ObsConnection {
                 }
                 Message::Ping(_) | Message::Pong(_) => continue,
                 Message::Close(frame) => {
-                    return Err(format!("OBS closed connection: {:?}", frame));
+                    let msg = format!("OBS closed connection: {:?}", frame);
+                    return Err(match frame {
+                        Some(f) if u16::from(f.code) == CLOSE_AUTHENTICATION_FAILED => {
+                            ConnectError::Auth(msg)
+                        }
+                        _ => ConnectError::Other(msg),
+                    });
                 }
                 _ => continue,
             }
         };
 
+        crate::ws_log::log_incoming(&self.ws_log, &identified).await;
+
         if identified["op"].as_u64().unwrap_or(0) != 2 {
-            return Err("Authentication failed".to_string());
+            return Err(ConnectError::Auth("Authentication failed".to_string()));
         }
 
         let negotiated_version = identified["d"]["negotiatedRpcVersion"]
             .as_u64()
             .unwrap_or(1);
 
-        let (tx, mut rx) = mpsc::channel::<Message>(32);
+        self.active_subscriptions
+            .store(event_subscriptions, Ordering::Relaxed);
+
+        let (tx, rx) = mpsc::channel::<Message>(32);
         let pending = self.pending.clone();
         let connected_flag = self.connected_flag.clone();
         connected_flag.store(true, Ordering::Relaxed);
@@ -187,123 +323,440 @@ impl ObsConnection {
         let loop_state = obs_state.clone();
         let loop_app = app_handle.clone();
         let loop_connected = connected_flag.clone();
-        let stats_sender = tx.clone();
+        let loop_subscriptions = self.active_subscriptions.clone();
+        let loop_ws_log = self.ws_log.clone();
+        let loop_health = self.health.clone();
+        if let Ok(mut h) = loop_health.lock() {
+            h.reset();
+        }
+        let loop_session = self.session.clone();
+        let loop_auto_reconnect = self.auto_reconnect.clone();
+        let target = ConnectTarget {
+            host: host.to_string(),
+            port,
+            password: password.map(str::to_string),
+        };
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
 
         tokio::spawn(async move {
-            let mut stats_interval =
-                tokio::time::interval(std::time::Duration::from_secs(5));
-            stats_interval.tick().await; // skip immediate first tick
-
-            let mut prev_render_skipped: u64 = 0;
-            let mut prev_output_skipped: u64 = 0;
-
-            loop {
-                tokio::select! {
-                    Some(msg) = rx.recv() => {
-                        if write.send(msg).await.is_err() {
-                            break;
-                        }
-                    }
-                    msg_result = read.next() => {
-                        match msg_result {
-                            Some(Ok(msg)) => {
-                                if let Message::Text(text) = msg {
-                                    if let Ok(parsed) = serde_json::from_str::<Value>(&text) {
-                                        let op = parsed["op"].as_u64().unwrap_or(0);
-                                        match op {
-                                            5 => {
-                                                handle_event(&parsed["d"], &loop_state, &loop_app).await;
-                                            }
-                                            7 => {
-                                                if let Some(request_id) = parsed["d"]["requestId"].as_str() {
-                                                    if request_id.starts_with("__stats_") {
-                                                        let (render, output) = handle_stats_response(&parsed["d"], &loop_state, &loop_app).await;
-                                                        let render_delta = render.saturating_sub(prev_render_skipped);
-                                                        let output_delta = output.saturating_sub(prev_output_skipped);
-                                                        if prev_render_skipped > 0 || prev_output_skipped > 0 {
-                                                            if render_delta >= 50 || output_delta >= 50 {
-                                                                let _ = loop_app.emit("obs://frame-drop-alert", serde_json::json!({
-                                                                    "renderDelta": render_delta,
-                                                                    "outputDelta": output_delta,
-                                                                }));
-                                                            }
-                                                        }
-                                                        prev_render_skipped = render;
-                                                        prev_output_skipped = output;
-                                                    } else {
-                                                        let mut pending_lock = pending.lock().await;
-                                                        if let Some(sender) = pending_lock.remove(request_id) {
-                                                            let _ = sender.send(parsed["d"].clone());
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                            _ => {}
-                                        }
-                                    }
-                                }
-                            }
-                            Some(Err(_)) | None => break,
-                        }
-                    }
-                    _ = stats_interval.tick() => {
-                        let request_id = format!("__stats_{}", uuid::Uuid::new_v4());
-                        let msg = json!({
-                            "op": 6,
-                            "d": {
-                                "requestType": "GetStats",
-                                "requestId": request_id,
-                            }
-                        });
-                        // Send directly — no pending entry needed, handled by prefix check
-                        let _ = stats_sender.send(Message::Text(msg.to_string().into())).await;
-                    }
-                }
+            let mut hooks = AppSession {
+                app: loop_app.clone(),
+                state: loop_state.clone(),
+                health: loop_health.clone(),
+                prev_render_skipped: 0,
+                prev_output_skipped: 0,
+                obs_exiting: false,
+                watchdog: crate::stream_health::HealthWatchdog::default(),
+            };
+            run_session(
+                write,
+                read,
+                rx,
+                stop_rx,
+                pending.clone(),
+                loop_ws_log,
+                STATS_INTERVAL,
+                &mut hooks,
+            )
+            .await;
+            // Set when the user quits OBS, so its closing the socket isn't
+            // reported as a lost connection.
+            let obs_exiting = hooks.obs_exiting;
+
+            // disconnect() (or a newer connect) already tore this session down.
+            if loop_session.load(Ordering::Relaxed) != session {
+                return;
             }
 
             loop_connected.store(false, Ordering::Relaxed);
+            loop_subscriptions.store(0, Ordering::Relaxed);
+            if let Ok(mut h) = loop_health.lock() {
+                h.reset();
+            }
             {
                 let mut s = loop_state.write().await;
                 s.clear();
             }
+            crate::screenshot_cache::clear(&loop_app);
             let _ = loop_app.emit("obs://disconnected", ());
+            if !obs_exiting {
+                crate::alerts::notify(&loop_app, "obs-disconnected", &[]);
+            }
             // Clean up any pending requests
-            let mut pending_lock = pending.lock().await;
-            pending_lock.clear();
+            pending.lock().await.clear();
+
+            if loop_auto_reconnect.load(Ordering::Relaxed) {
+                tokio::spawn(reconnect_loop(loop_app, loop_state, target, session));
+            }
         });
 
-        self.sender = Some(tx);
+        *self.handle.sender.write().unwrap() = Some(tx);
+        self.stop = Some(stop_tx);
         self.status = ObsStatus {
             connected: true,
             obs_version: Some(obs_version),
             ws_version: Some(format!("RPC v{}", negotiated_version)),
+            degraded: false,
         };
 
         Ok(())
     }
 
     pub async fn disconnect(&mut self) {
-        if let Some(sender) = self.sender.take() {
+        self.session.fetch_add(1, Ordering::Relaxed);
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        if let Some(sender) = self.handle.sender.write().unwrap().take() {
             drop(sender);
         }
         self.connected_flag.store(false, Ordering::Relaxed);
+        self.active_subscriptions.store(0, Ordering::Relaxed);
         self.status = ObsStatus {
             connected: false,
             obs_version: None,
             ws_version: None,
+            degraded: false,
         };
     }
+}
+
+/// Retries the last connection with backoff until it succeeds, the user
+/// connects or disconnects by hand, or auto-reconnect is turned off. A
+/// rejected password ends it with `obs://reconnect-failed`.
+/// Boxed because it calls back into connect(), which spawns it.
+fn reconnect_loop(
+    app: tauri::AppHandle,
+    obs_state: SharedObsState,
+    target: ConnectTarget,
+    mut session: u64,
+) -> BoxFuture<'static, ()> {
+    Box::pin(async move {
+        let Some(conn_state) = app
+            .try_state::<crate::commands::SharedObsConnection>()
+            .map(|s| s.inner().clone())
+        else {
+            return;
+        };
+        for attempt in 1usize.. {
+            let delay = RECONNECT_DELAYS_SECS[(attempt - 1).min(RECONNECT_DELAYS_SECS.len() - 1)];
+            let _ = app.emit(
+                "obs://reconnecting",
+                json!({"attempt": attempt, "delaySecs": delay}),
+            );
+            tokio::time::sleep(Duration::from_secs(delay)).await;
+
+            let mut conn = conn_state.lock().await;
+            if conn.session() != session || !conn.auto_reconnect.load(Ordering::Relaxed) {
+                return;
+            }
+            let result = conn
+                .connect(
+                    &target.host,
+                    target.port,
+                    target.password.as_deref(),
+                    app.clone(),
+                    obs_state.clone(),
+                )
+                .await;
+            session = conn.session();
+            match result {
+                Ok(()) => {
+                    log::info!("Reconnected to OBS after {} attempt(s)", attempt);
+                    crate::commands::sync_after_connect(&conn, &obs_state, &app).await;
+                    let _ = app.emit("obs://reconnected", json!({"attempts": attempt}));
+                    return;
+                }
+                // The saved password no longer works; retrying won't fix it.
+                Err(ConnectError::Auth(reason)) => {
+                    log::warn!("Stopped reconnecting to OBS: {}", reason);
+                    let _ = app.emit(
+                        "obs://reconnect-failed",
+                        json!({"attempts": attempt, "reason": reason}),
+                    );
+                    return;
+                }
+                Err(e) => log::debug!("Reconnect attempt {} failed: {}", attempt, e),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+impl ObsHandle {
+    /// A connected handle whose requests `respond` answers instead of OBS,
+    /// given the request type and its requestData.
+    pub(crate) fn mock<F>(respond: F) -> ObsHandle
+    where
+        F: Fn(&str, &Value) -> Result<Value, String> + Send + 'static,
+    {
+        let handle = ObsConnection::new().handle();
+        let (tx, mut rx) = mpsc::channel::<Message>(32);
+        *handle.sender.write().unwrap() = Some(tx);
+        handle.connected_flag.store(true, Ordering::Relaxed);
+        let pending = handle.pending.clone();
+        tauri::async_runtime::spawn(async move {
+            while let Some(Message::Text(text)) = rx.recv().await {
+                let Ok(msg) = serde_json::from_str::<Value>(text.as_str()) else {
+                    continue;
+                };
+                let d = &msg["d"];
+                let response = match respond(d["requestType"].as_str().unwrap_or(""), &d["requestData"]) {
+                    Ok(data) => json!({"requestStatus": {"result": true, "code": 100}, "responseData": data}),
+                    Err(comment) => json!({"requestStatus": {"result": false, "code": 600, "comment": comment}}),
+                };
+                if let Some(sender) = pending.lock().await.remove(d["requestId"].as_str().unwrap_or("")) {
+                    let _ = sender.send(response);
+                }
+            }
+        });
+        handle
+    }
+}
+
+/// What the session loop does with traffic other than replies to handle
+/// requests. The app's implementation updates ObsState and emits events;
+/// keeping it behind a trait lets the loop run against a mock server.
+trait SessionHooks: Send {
+    /// Polling requests for a stats tick. Ids must start with `__` so their
+    /// replies come back through `on_poll_response`.
+    fn poll_requests(&mut self) -> BoxFuture<'_, Vec<Value>>;
+    /// An OBS event (op 5).
+    fn on_event<'a>(&'a mut self, data: &'a Value) -> BoxFuture<'a, ()>;
+    fn on_poll_response<'a>(&'a mut self, request_id: &'a str, data: &'a Value) -> BoxFuture<'a, ()>;
+}
+
+/// Pumps one connection until it drops or `stop_rx` fires: handle requests
+/// out, replies to their pending senders, events and poll replies to
+/// `hooks`. Polls are written straight to the socket. This loop is the only
+/// reader of `rx`, so awaiting a send into it here would deadlock as soon
+/// as callers filled the channel.
+#[allow(clippy::too_many_arguments)]
+async fn run_session<W, R, H>(
+    mut write: W,
+    mut read: R,
+    mut rx: mpsc::Receiver<Message>,
+    mut stop_rx: oneshot::Receiver<()>,
+    pending: PendingRequests,
+    ws_log: SharedWsLog,
+    poll_every: Duration,
+    hooks: &mut H,
+) where
+    W: futures_util::Sink<Message> + Unpin,
+    R: futures_util::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    H: SessionHooks,
+{
+    let mut stats_interval = tokio::time::interval(poll_every);
+    stats_interval.tick().await; // skip immediate first tick
+
+    'session: loop {
+        tokio::select! {
+            _ = &mut stop_rx => {
+                let _ = write.close().await;
+                break;
+            }
+            Some(msg) = rx.recv() => {
+                if write.send(msg).await.is_err() {
+                    break;
+                }
+            }
+            msg_result = read.next() => {
+                let text = match msg_result {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(_)) => continue,
+                    Some(Err(_)) | None => break,
+                };
+                let Ok(parsed) = serde_json::from_str::<Value>(&text) else {
+                    continue;
+                };
+                crate::ws_log::log_incoming(&ws_log, &parsed).await;
+                match parsed["op"].as_u64().unwrap_or(0) {
+                    5 => hooks.on_event(&parsed["d"]).await,
+                    // 7 = RequestResponse, 9 = RequestBatchResponse
+                    7 | 9 => {
+                        let Some(request_id) = parsed["d"]["requestId"].as_str() else {
+                            continue;
+                        };
+                        if request_id.starts_with("__") {
+                            hooks.on_poll_response(request_id, &parsed["d"]).await;
+                        } else if let Some(sender) = pending.lock().await.remove(request_id) {
+                            let _ = sender.send(parsed["d"].clone());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ = stats_interval.tick() => {
+                for msg in hooks.poll_requests().await {
+                    crate::ws_log::log_outgoing(&ws_log, &msg).await;
+                    if write.send(Message::Text(msg.to_string().into())).await.is_err() {
+                        break 'session;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The app side of a live session: stats, stream and recording polling,
+/// and event handling.
+struct AppSession {
+    app: tauri::AppHandle,
+    state: SharedObsState,
+    health: SharedObsHealth,
+    prev_render_skipped: u64,
+    prev_output_skipped: u64,
+    obs_exiting: bool,
+    watchdog: crate::stream_health::HealthWatchdog,
+}
+
+impl SessionHooks for AppSession {
+    fn poll_requests(&mut self) -> BoxFuture<'_, Vec<Value>> {
+        Box::pin(async move {
+            let request = |request_type: &str, prefix: &str| {
+                json!({
+                    "op": 6,
+                    "d": {
+                        "requestType": request_type,
+                        "requestId": format!("{}{}", prefix, uuid::Uuid::new_v4()),
+                    }
+                })
+            };
+            let stats = request("GetStats", "__stats_");
+            if let Ok(mut h) = self.health.lock() {
+                h.stats_sent(stats["d"]["requestId"].as_str().unwrap_or("").to_string(), &self.app);
+            }
+            let mut requests = vec![stats];
+
+            let (streaming, recording) = {
+                let s = self.state.read().await;
+                (s.stream_status.active, s.record_status.active)
+            };
+            // Congestion and network drops for the health watchdog.
+            if streaming {
+                requests.push(request("GetStreamStatus", "__streamstatus_"));
+            } else {
+                self.watchdog.reset_stream();
+            }
+            // Recording duration rides along while a recording is running.
+            if recording {
+                requests.push(request("GetRecordStatus", "__recstatus_"));
+            }
+            requests
+        })
+    }
+
+    fn on_event<'a>(&'a mut self, data: &'a Value) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            if data["eventType"] == "ExitStarted" {
+                self.obs_exiting = true;
+            }
+            handle_event(data, &self.state, &self.app).await;
+        })
+    }
+
+    fn on_poll_response<'a>(&'a mut self, request_id: &'a str, data: &'a Value) -> BoxFuture<'a, ()> {
+        Box::pin(async move {
+            let (app, state) = (&self.app, &self.state);
+            if request_id.starts_with("__stats_") {
+                if let Ok(mut h) = self.health.lock() {
+                    h.stats_received(request_id, app);
+                }
+                let (render, output) = handle_stats_response(data, state, app).await;
+                crate::stream_health::on_stats(&mut self.watchdog, data, state, app).await;
+                let render_delta = render.saturating_sub(self.prev_render_skipped);
+                let output_delta = output.saturating_sub(self.prev_output_skipped);
+                if (self.prev_render_skipped > 0 || self.prev_output_skipped > 0)
+                    && (render_delta >= 50 || output_delta >= 50)
+                {
+                    let _ = app.emit(
+                        "obs://frame-drop-alert",
+                        json!({
+                            "renderDelta": render_delta,
+                            "outputDelta": output_delta,
+                        }),
+                    );
+                    crate::alerts::notify(app, "frame-drop", &[("frames", &render_delta.max(output_delta))]);
+                }
+                self.prev_render_skipped = render;
+                self.prev_output_skipped = output;
+            } else if request_id.starts_with("__streamstatus_") {
+                crate::stream_health::on_stream_status(&mut self.watchdog, data, state, app).await;
+            } else if request_id.starts_with("__recstatus_") {
+                if let Some(resp) = data.get("responseData") {
+                    let status = {
+                        let mut s = state.write().await;
+                        s.record_status.update_from(resp);
+                        s.record_status.clone()
+                    };
+                    let _ = app.emit("obs://record-status", &status);
+                }
+            }
+        })
+    }
+}
+
+impl ObsHandle {
+    pub fn ws_log(&self) -> SharedWsLog {
+        self.ws_log.clone()
+    }
+
+    pub fn health(&self) -> SharedObsHealth {
+        self.health.clone()
+    }
+
+    pub fn subscriptions(&self) -> SharedEventSubscriptions {
+        self.subscriptions.clone()
+    }
+
+    /// Event mask OBS is currently sending us; 0 while disconnected.
+    pub fn active_event_subscriptions(&self) -> u64 {
+        self.active_subscriptions.load(Ordering::Relaxed)
+    }
+
+    /// Changes the event mask on the live session with a Reidentify. Pending
+    /// requests are untouched, so in-flight responses still correlate. While
+    /// disconnected this is a no-op; connect applies the registry mask.
+    pub async fn set_event_subscriptions(&self, flags: u64) -> Result<(), String> {
+        let Some(sender) = self.current_sender().filter(|_| self.is_connected()) else {
+            return Ok(());
+        };
+        if self.active_event_subscriptions() == flags {
+            return Ok(());
+        }
+        let msg = json!({
+            "op": 3,
+            "d": {
+                "eventSubscriptions": flags
+            }
+        });
+        crate::ws_log::log_outgoing(&self.ws_log, &msg).await;
+        sender
+            .send(Message::Text(msg.to_string().into()))
+            .await
+            .map_err(|e| format!("Failed to send Reidentify: {}", e))?;
+        self.active_subscriptions.store(flags, Ordering::Relaxed);
+        log::info!("Event subscriptions updated to {:#x}", flags);
+        Ok(())
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.health.lock().map(|h| h.is_degraded()).unwrap_or(false)
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected_flag.load(Ordering::Relaxed)
+    }
+
+    fn current_sender(&self) -> Option<mpsc::Sender<Message>> {
+        self.sender.read().ok().and_then(|s| s.clone())
+    }
 
     pub async fn send_request(
         &self,
         request_type: &str,
         request_data: Option<Value>,
     ) -> Result<Value, String> {
-        let sender = self
-            .sender
-            .as_ref()
-            .ok_or("Not connected to OBS")?;
-
         let request_id = uuid::Uuid::new_v4().to_string();
 
         let mut msg = json!({
@@ -318,11 +771,87 @@ impl ObsConnection {
             msg["d"]["requestData"] = data;
         }
 
+        let response = self.round_trip(request_id, msg, REQUEST_TIMEOUT).await?;
+        request_outcome(&response)
+    }
+
+    /// Sends several requests as one RequestBatch. The outer error means the
+    /// batch itself failed; otherwise there is one result per request, in
+    /// order, and requests skipped by `halt_on_failure` come back as errors.
+    pub async fn send_batch(
+        &self,
+        requests: Vec<(String, Option<Value>)>,
+        execution: BatchExecution,
+        halt_on_failure: bool,
+    ) -> Result<Vec<Result<Value, String>>, String> {
+        let count = requests.len();
+        if count == 0 {
+            return Ok(Vec::new());
+        }
+
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let sub_requests: Vec<Value> = requests
+            .into_iter()
+            .enumerate()
+            .map(|(i, (request_type, request_data))| {
+                let mut request = json!({
+                    "requestType": request_type,
+                    "requestId": i.to_string(),
+                });
+                if let Some(data) = request_data {
+                    request["requestData"] = data;
+                }
+                request
+            })
+            .collect();
+        let msg = json!({
+            "op": 8,
+            "d": {
+                "requestId": batch_id,
+                "haltOnFailure": halt_on_failure,
+                "executionType": execution as i64,
+                "requests": sub_requests,
+            }
+        });
+
+        let timeout = REQUEST_TIMEOUT + Duration::from_millis(100 * count as u64);
+        let response = self.round_trip(batch_id, msg, timeout).await?;
+
+        let mut outcomes: Vec<Option<Result<Value, String>>> = vec![None; count];
+        for result in response["results"].as_array().into_iter().flatten() {
+            let index = result["requestId"]
+                .as_str()
+                .and_then(|id| id.parse::<usize>().ok())
+                .filter(|i| *i < count);
+            if let Some(i) = index {
+                outcomes[i] = Some(request_outcome(result));
+            }
+        }
+        Ok(outcomes
+            .into_iter()
+            .map(|o| {
+                o.unwrap_or_else(|| Err("Not run: an earlier request in the batch failed".to_string()))
+            })
+            .collect())
+    }
+
+    /// Sends `msg` and waits for the response carrying `request_id`.
+    async fn round_trip(
+        &self,
+        request_id: String,
+        msg: Value,
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        let sender = self
+            .current_sender()
+            .ok_or_else(|| t("error.not_connected", &[]))?;
+
         let (tx, rx) = oneshot::channel();
         {
             let mut pending = self.pending.lock().await;
             pending.insert(request_id.clone(), tx);
         }
+        crate::ws_log::log_outgoing(&self.ws_log, &msg).await;
 
         sender
             .send(Message::Text(msg.to_string().into()))
@@ -336,25 +865,31 @@ impl ObsConnection {
                 format!("Failed to send request: {}", e)
             })?;
 
-        let response =
-            tokio::time::timeout(std::time::Duration::from_secs(10), rx)
-                .await
-                .map_err(|_| "Request timed out".to_string())?
-                .map_err(|_| "Response channel closed".to_string())?;
-
-        let status = &response["requestStatus"];
-        let result = status["result"].as_bool().unwrap_or(false);
-        if !result {
-            let code = status["code"].as_u64().unwrap_or(0);
-            let comment = status["comment"].as_str().unwrap_or("Unknown error");
-            return Err(format!("OBS error {}: {}", code, comment));
+        let started = std::time::Instant::now();
+        let response = tokio::time::timeout(timeout, rx).await;
+        if let Ok(mut h) = self.health.lock() {
+            h.record_request(started.elapsed().as_millis() as u64, response.is_err());
         }
+        response
+            .map_err(|_| t("error.request_timed_out", &[]))?
+            .map_err(|_| "Response channel closed".to_string())
+    }
+}
 
-        Ok(response
-            .get("responseData")
-            .cloned()
-            .unwrap_or(json!({})))
+/// The responseData of a (batch sub-)response, or OBS's error for it.
+fn request_outcome(response: &Value) -> Result<Value, String> {
+    let status = &response["requestStatus"];
+    let result = status["result"].as_bool().unwrap_or(false);
+    if !result {
+        let code = status["code"].as_u64().unwrap_or(0);
+        let comment = status["comment"].as_str().unwrap_or("Unknown error");
+        return Err(t("error.obs_error", &[("code", &code), ("comment", &comment)]));
     }
+
+    Ok(response
+        .get("responseData")
+        .cloned()
+        .unwrap_or(json!({})))
 }
 
 async fn handle_event(
@@ -382,6 +917,7 @@ async fn handle_event(
                 "obs://input-volume-changed",
                 json!({"inputName": name, "inputVolumeDb": volume_db, "inputVolumeMul": volume_mul}),
             );
+            crate::volume_guard::on_volume_changed(app, name, volume_db).await;
         }
         "InputMuteStateChanged" => {
             let name = event_data["inputName"].as_str().unwrap_or("");
@@ -403,11 +939,125 @@ async fn handle_event(
                 let mut s = state.write().await;
                 s.current_scene = name.clone();
             }
+            crate::app_capture::on_scene_changed(app, &name);
+            crate::screenshot_cache::invalidate(app, &name).await;
             let _ = app.emit(
                 "obs://current-scene-changed",
                 json!({"sceneName": name}),
             );
         }
+        "CurrentPreviewSceneChanged" => {
+            let name = event_data["sceneName"].as_str().unwrap_or("").to_string();
+            {
+                let mut s = state.write().await;
+                s.preview_scene = Some(name.clone());
+            }
+            let _ = app.emit(
+                "obs://current-preview-scene-changed",
+                json!({"sceneName": name}),
+            );
+        }
+        "CurrentSceneTransitionChanged" => {
+            let name = event_data["transitionName"].as_str().unwrap_or("").to_string();
+            let fixed = {
+                let mut s = state.write().await;
+                s.transition.current = name.clone();
+                s.transition.is_fixed(&name)
+            };
+            let _ = app.emit(
+                "obs://current-transition-changed",
+                json!({"transitionName": name}),
+            );
+            // The event has no duration, and a fixed transition won't report
+            // one; otherwise ask, since it may never have been known.
+            if !fixed {
+                let app = app.clone();
+                let state = state.clone();
+                tauri::async_runtime::spawn(async move {
+                    let conn = app.state::<ObsHandle>().inner().clone();
+                    let Ok(current) = conn.send_request("GetCurrentSceneTransition", None).await else {
+                        return;
+                    };
+                    let Some(duration) = current["transitionDuration"].as_u64() else {
+                        return;
+                    };
+                    state.write().await.transition.duration_ms = Some(duration);
+                    let _ = app.emit(
+                        "obs://transition-duration-changed",
+                        json!({"transitionDuration": duration}),
+                    );
+                });
+            }
+        }
+        "CurrentSceneTransitionDurationChanged" => {
+            let duration = event_data["transitionDuration"].as_u64();
+            {
+                let mut s = state.write().await;
+                s.transition.duration_ms = duration;
+            }
+            let _ = app.emit(
+                "obs://transition-duration-changed",
+                json!({"transitionDuration": duration}),
+            );
+        }
+        "MediaInputPlaybackStarted" | "MediaInputPlaybackEnded" | "MediaInputActionTriggered" => {
+            let name = event_data["inputName"].as_str().unwrap_or("").to_string();
+            let fallback = match (event_type, event_data["mediaAction"].as_str()) {
+                ("MediaInputPlaybackStarted", _) => "playing",
+                ("MediaInputPlaybackEnded", _) => "ended",
+                (_, Some("OBS_WEBSOCKET_MEDIA_INPUT_ACTION_PAUSE")) => "paused",
+                (_, Some("OBS_WEBSOCKET_MEDIA_INPUT_ACTION_STOP")) => "stopped",
+                _ => "playing",
+            };
+            // The event carries no position; ask for it so the UI can show progress.
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let conn = app.state::<ObsHandle>().inner().clone();
+                let status = match crate::media::fetch_status(&conn, &name).await {
+                    Ok(status) => status,
+                    Err(_) => crate::media::MediaStatus {
+                        input_name: name,
+                        state: fallback.to_string(),
+                        duration_ms: None,
+                        cursor_ms: None,
+                    },
+                };
+                let _ = app.emit("obs://media-state", &status);
+            });
+        }
+        "SceneTransitionCreated" | "SceneTransitionRemoved" | "SceneTransitionNameChanged" => {
+            let app = app.clone();
+            let state = state.clone();
+            tauri::async_runtime::spawn(async move {
+                let conn = app.state::<ObsHandle>().inner().clone();
+                if let Ok(transition) = crate::obs_state::fetch_transitions(&conn).await {
+                    let transition =
+                        crate::obs_state::store_transitions(&mut *state.write().await, transition);
+                    let _ = app.emit("obs://transition-list-changed", &transition);
+                }
+            });
+        }
+        "StudioModeStateChanged" => {
+            let enabled = event_data["studioModeEnabled"].as_bool().unwrap_or(false);
+            {
+                let mut s = state.write().await;
+                s.studio_mode = enabled;
+                if !enabled {
+                    s.preview_scene = None;
+                }
+            }
+            let _ = app.emit(
+                "obs://studio-mode-changed",
+                json!({"studioModeEnabled": enabled}),
+            );
+        }
+        "SceneRemoved" => {
+            let name = event_data["sceneName"].as_str().unwrap_or("");
+            // Parents are found through the removed scene's own entry, so
+            // the cache goes first.
+            crate::screenshot_cache::invalidate(app, name).await;
+            state.write().await.scene_items.remove(name);
+        }
         "SceneListChanged" => {
             let scenes = event_data["scenes"]
                 .as_array()
@@ -440,22 +1090,25 @@ async fn handle_event(
                 .as_str()
                 .unwrap_or("")
                 .to_string();
+            let browser_url = if kind == "browser_source" {
+                let mut settings = event_data["defaultInputSettings"].clone();
+                if let (Some(merged), Some(set)) =
+                    (settings.as_object_mut(), event_data["inputSettings"].as_object())
+                {
+                    merged.extend(set.clone());
+                }
+                crate::obs_state::browser_url(&settings)
+            } else {
+                String::new()
+            };
             {
                 let mut s = state.write().await;
                 s.inputs.insert(
                     name.clone(),
                     InputInfo {
-                        name: name.clone(),
-                        kind,
-                        volume_db: 0.0,
-                        volume_mul: 1.0,
-                        muted: false,
-                        monitor_type: String::new(),
-                        filters: vec![],
                         device_id,
-                        audio_balance: 0.5,
-                        audio_sync_offset: 0,
-                        audio_tracks: serde_json::json!({"1":true,"2":true,"3":false,"4":false,"5":false,"6":false}),
+                        browser_url,
+                        ..InputInfo::placeholder(&name, &kind)
                     },
                 );
             }
@@ -479,7 +1132,9 @@ async fn handle_event(
                     input.name = new_name.clone();
                     s.inputs.insert(new_name.clone(), input);
                 }
+                s.special_inputs.rename(old_name, &new_name);
             }
+            crate::rename_cascade::input_renamed(app, old_name, &new_name);
             let _ = app.emit(
                 "obs://input-name-changed",
                 json!({"oldInputName": old_name, "inputName": new_name}),
@@ -495,6 +1150,13 @@ async fn handle_event(
                     }
                 }
             }
+            let settings = &event_data["inputSettings"];
+            if settings.get("url").is_some() || settings.get("local_file").is_some() {
+                let mut s = state.write().await;
+                if let Some(input) = s.inputs.get_mut(name).filter(|i| i.kind == "browser_source") {
+                    input.browser_url = crate::obs_state::browser_url(settings);
+                }
+            }
             let _ = app.emit(
                 "obs://input-settings-changed",
                 json!({"inputName": name}),
@@ -566,10 +1228,10 @@ async fn handle_event(
                 if let Some(input) = s.inputs.get_mut(&source) {
                     let idx = event_data["filterIndex"].as_u64().unwrap_or(input.filters.len() as u64) as usize;
                     let filter = FilterInfo {
-                        name: filter_name,
-                        kind: filter_kind,
+                        name: filter_name.clone(),
+                        kind: filter_kind.clone(),
                         enabled: true,
-                        settings: filter_settings,
+                        settings: filter_settings.clone(),
                     };
                     if idx <= input.filters.len() {
                         input.filters.insert(idx, filter);
@@ -578,16 +1240,27 @@ async fn handle_event(
                     }
                 }
             }
+            let filter = FilterInfo {
+                name: filter_name,
+                kind: filter_kind,
+                enabled: true,
+                settings: filter_settings,
+            };
+            filter_history::record_event(app, state, "created", &source, filter).await;
             let _ = app.emit("obs://filters-changed", json!({"sourceName": source}));
         }
         "SourceFilterRemoved" => {
             let source = event_data["sourceName"].as_str().unwrap_or("").to_string();
             let filter_name = event_data["filterName"].as_str().unwrap_or("");
-            {
+            let removed = {
                 let mut s = state.write().await;
-                if let Some(input) = s.inputs.get_mut(&source) {
-                    input.filters.retain(|f| f.name != filter_name);
-                }
+                s.inputs.get_mut(&source).and_then(|input| {
+                    let idx = input.filters.iter().position(|f| f.name == filter_name)?;
+                    Some(input.filters.remove(idx))
+                })
+            };
+            if let Some(filter) = removed {
+                filter_history::record_event(app, state, "removed", &source, filter).await;
             }
             let _ = app.emit("obs://filters-changed", json!({"sourceName": source}));
         }
@@ -609,13 +1282,16 @@ async fn handle_event(
             let source = event_data["sourceName"].as_str().unwrap_or("").to_string();
             let old_name = event_data["filterName"].as_str().unwrap_or("");
             let new_name = event_data["newFilterName"].as_str().unwrap_or("").to_string();
-            {
+            let renamed = {
                 let mut s = state.write().await;
-                if let Some(input) = s.inputs.get_mut(&source) {
-                    if let Some(f) = input.filters.iter_mut().find(|f| f.name == old_name) {
-                        f.name = new_name;
-                    }
-                }
+                s.inputs.get_mut(&source).and_then(|input| {
+                    let f = input.filters.iter_mut().find(|f| f.name == old_name)?;
+                    f.name = new_name;
+                    Some(f.clone())
+                })
+            };
+            if let Some(filter) = renamed {
+                filter_history::record_rename(app, state, &source, old_name, filter).await;
             }
             let _ = app.emit("obs://filters-changed", json!({"sourceName": source}));
         }
@@ -643,17 +1319,20 @@ async fn handle_event(
             let source = event_data["sourceName"].as_str().unwrap_or("").to_string();
             let filter_name = event_data["filterName"].as_str().unwrap_or("");
             let new_settings = &event_data["filterSettings"];
-            {
+            let updated = {
                 let mut s = state.write().await;
-                if let Some(input) = s.inputs.get_mut(&source) {
-                    if let Some(f) = input.filters.iter_mut().find(|f| f.name == filter_name) {
-                        if let (Some(existing), Some(incoming)) = (f.settings.as_object_mut(), new_settings.as_object()) {
-                            for (k, v) in incoming {
-                                existing.insert(k.clone(), v.clone());
-                            }
+                s.inputs.get_mut(&source).and_then(|input| {
+                    let f = input.filters.iter_mut().find(|f| f.name == filter_name)?;
+                    if let (Some(existing), Some(incoming)) = (f.settings.as_object_mut(), new_settings.as_object()) {
+                        for (k, v) in incoming {
+                            existing.insert(k.clone(), v.clone());
                         }
                     }
-                }
+                    Some(f.clone())
+                })
+            };
+            if let Some(filter) = updated {
+                filter_history::record_event(app, state, "updated", &source, filter).await;
             }
             let _ = app.emit("obs://filters-changed", json!({"sourceName": source}));
         }
@@ -664,6 +1343,9 @@ async fn handle_event(
                 let mut s = state.write().await;
                 s.stream_status.active = active;
             }
+            if event_data["outputState"] == "OBS_WEBSOCKET_OUTPUT_RECONNECTING" {
+                crate::alerts::notify(app, "stream-disconnected", &[]);
+            }
             let _ = app.emit(
                 "obs://stream-state-changed",
                 json!({"outputActive": active}),
@@ -672,34 +1354,92 @@ async fn handle_event(
         "RecordStateChanged" => {
             let active =
                 event_data["outputActive"].as_bool().unwrap_or(false);
+            let output_state = event_data["outputState"].as_str().unwrap_or("");
             let output_path = event_data["outputPath"]
                 .as_str()
                 .unwrap_or("")
                 .to_string();
-            {
+            let (paused, duration_ms) = {
                 let mut s = state.write().await;
-                s.record_status.active = active;
+                let status = &mut s.record_status;
+                status.active = active;
+                match output_state {
+                    "OBS_WEBSOCKET_OUTPUT_PAUSED" => status.paused = true,
+                    "OBS_WEBSOCKET_OUTPUT_RESUMED" => status.paused = false,
+                    "OBS_WEBSOCKET_OUTPUT_STARTING" | "OBS_WEBSOCKET_OUTPUT_STARTED" => {
+                        status.duration_ms = 0;
+                    }
+                    _ => {}
+                }
                 if !active {
-                    s.record_status.paused = false;
+                    status.paused = false;
+                }
+                if !output_path.is_empty() {
+                    status.output_path = Some(output_path.clone());
                 }
+                (status.paused, status.duration_ms)
+            };
+            if output_state == "OBS_WEBSOCKET_OUTPUT_STOPPED" {
+                crate::video_editor::spawn_auto_remux(app.clone(), output_path.clone());
+                let file = std::path::Path::new(&output_path)
+                    .file_name()
+                    .map(|f| f.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                crate::alerts::notify(app, "record-stopped", &[("file", &file)]);
             }
             let _ = app.emit(
                 "obs://record-state-changed",
-                json!({"outputActive": active, "outputPath": output_path}),
+                json!({
+                    "outputActive": active,
+                    "outputPaused": paused,
+                    "outputState": output_state,
+                    "outputPath": output_path,
+                    "durationMs": duration_ms,
+                }),
             );
         }
+        "RecordFileChanged" => {
+            let path = event_data["newOutputPath"].as_str().unwrap_or("").to_string();
+            state.write().await.record_status.output_path = Some(path.clone());
+            let _ = app.emit("obs://record-file-changed", json!({"outputPath": path}));
+        }
+        "ReplayBufferStateChanged" => {
+            let active = event_data["outputActive"].as_bool().unwrap_or(false);
+            {
+                let mut s = state.write().await;
+                s.replay_buffer_status.available = true;
+                s.replay_buffer_status.active = active;
+            }
+            let _ = app.emit(
+                "obs://replay-buffer-state-changed",
+                json!({"outputActive": active}),
+            );
+        }
+        "ReplayBufferSaved" => {
+            let path = event_data["savedReplayPath"]
+                .as_str()
+                .unwrap_or("")
+                .to_string();
+            if !path.is_empty() {
+                state.write().await.last_replay_path = Some(path.clone());
+            }
+            let _ = app.emit("obs://replay-saved", json!({"savedReplayPath": path}));
+        }
         "InputVolumeMeters" => {
             if let Some(inputs) = event_data["inputs"].as_array() {
                 let mut meters = Vec::new();
+                let mut peaks = Vec::new();
                 for input in inputs {
                     let name = input["inputName"].as_str().unwrap_or("");
                     if name.is_empty() { continue; }
                     let mut channels = Vec::new();
+                    let mut max_peak: f64 = 0.0;
                     if let Some(levels) = input["inputLevelsMul"].as_array() {
                         for ch in levels {
                             if let Some(ch_arr) = ch.as_array() {
                                 let mag = ch_arr.first().and_then(|v| v.as_f64()).unwrap_or(0.0);
                                 let peak = ch_arr.get(1).and_then(|v| v.as_f64()).unwrap_or(0.0);
+                                max_peak = max_peak.max(peak);
                                 let mag_db = if mag > 0.0 { (20.0 * mag.log10()).max(-100.0) } else { -100.0 };
                                 let peak_db = if peak > 0.0 { (20.0 * peak.log10()).max(-100.0) } else { -100.0 };
                                 channels.push(json!({"mag_db": mag_db, "peak_db": peak_db}));
@@ -708,29 +1448,91 @@ async fn handle_event(
                     }
                     if !channels.is_empty() {
                         meters.push(json!({"inputName": name, "channels": channels}));
+                        peaks.push((name.to_string(), max_peak));
                     }
                 }
                 if !meters.is_empty() {
                     let _ = app.emit("obs://input-volume-meters", json!({"inputs": meters}));
                 }
+                crate::volume_guard::on_meter_frame(app, &peaks).await;
             }
         }
+        "SceneItemTransformChanged" => {
+            let scene = event_data["sceneName"].as_str().unwrap_or("");
+            let item_id = event_data["sceneItemId"].as_u64().unwrap_or(0);
+            {
+                let mut s = state.write().await;
+                if let Some(items) = s.scene_items.get_mut(scene) {
+                    if let Some(item) = items.iter_mut().find(|i| i.scene_item_id == item_id) {
+                        item.transform = event_data["sceneItemTransform"].clone();
+                    }
+                }
+            }
+            crate::screenshot_cache::invalidate(app, scene).await;
+            let _ = app.emit(
+                "obs://scene-item-transform-changed",
+                json!({
+                    "sceneName": event_data["sceneName"],
+                    "sceneItemId": event_data["sceneItemId"],
+                    "sceneItemTransform": event_data["sceneItemTransform"],
+                }),
+            );
+        }
         "SceneItemCreated" => {
             let scene = event_data["sceneName"].as_str().unwrap_or("").to_string();
             let source = event_data["sourceName"].as_str().unwrap_or("").to_string();
             let item_id = event_data["sceneItemId"].as_u64().unwrap_or(0);
-            let source_kind = event_data["inputKind"].as_str().unwrap_or("").to_string();
+            let index = event_data["sceneItemIndex"].as_u64().map(|i| i as usize);
             {
                 let mut s = state.write().await;
-                let items = s.scene_items.entry(scene).or_insert_with(Vec::new);
-                items.push(crate::obs_state::SceneItemInfo {
+                // The event carries no kind; anything not an input is a nested scene,
+                // reported the way GetSceneItemList does.
+                let source_kind = s
+                    .inputs
+                    .get(&source)
+                    .map(|i| i.kind.clone())
+                    .unwrap_or_else(|| "OBS_SOURCE_TYPE_SCENE".into());
+                let items = s.scene_items.entry(scene.clone()).or_insert_with(Vec::new);
+                let item = crate::obs_state::SceneItemInfo {
                     source_name: source,
                     source_kind,
                     scene_item_id: item_id,
                     enabled: true,
+                    transform: Value::Null,
+                };
+                match index {
+                    Some(i) if i < items.len() => items.insert(i, item),
+                    _ => items.push(item),
+                }
+            }
+            // Creation sends no transform event, so ask for the starting one.
+            {
+                let app = app.clone();
+                let state = state.clone();
+                let scene = scene.clone();
+                tauri::async_runtime::spawn(async move {
+                    let conn = app.state::<ObsHandle>().inner().clone();
+                    let Ok(resp) = conn
+                        .send_request(
+                            "GetSceneItemTransform",
+                            Some(json!({"sceneName": scene, "sceneItemId": item_id})),
+                        )
+                        .await
+                    else {
+                        return;
+                    };
+                    let mut s = state.write().await;
+                    if let Some(item) = s
+                        .scene_items
+                        .get_mut(&scene)
+                        .and_then(|items| items.iter_mut().find(|i| i.scene_item_id == item_id))
+                    {
+                        item.transform = resp["sceneItemTransform"].clone();
+                    }
                 });
             }
-            let _ = app.emit("obs://scene-items-changed", json!({}));
+            crate::screenshot_cache::invalidate(app, &scene).await;
+            let _ = app.emit("obs://scene-items-changed", json!({"sceneName": scene}));
         }
         "SceneItemRemoved" => {
             let scene = event_data["sceneName"].as_str().unwrap_or("").to_string();
@@ -741,7 +1543,8 @@ async fn handle_event(
                     items.retain(|i| i.scene_item_id != item_id);
                 }
             }
-            let _ = app.emit("obs://scene-items-changed", json!({}));
+            crate::screenshot_cache::invalidate(app, &scene).await;
+            let _ = app.emit("obs://scene-items-changed", json!({"sceneName": scene}));
         }
         "SceneItemEnableStateChanged" => {
             let scene = event_data["sceneName"].as_str().unwrap_or("").to_string();
@@ -755,7 +1558,8 @@ async fn handle_event(
                     }
                 }
             }
-            let _ = app.emit("obs://scene-items-changed", json!({}));
+            crate::screenshot_cache::invalidate(app, &scene).await;
+            let _ = app.emit("obs://scene-items-changed", json!({"sceneName": scene}));
         }
         "SceneItemListReindexed" => {
             let scene = event_data["sceneName"].as_str().unwrap_or("").to_string();
@@ -774,7 +1578,8 @@ async fn handle_event(
                     }
                 }
             }
-            let _ = app.emit("obs://scene-items-changed", json!({}));
+            crate::screenshot_cache::invalidate(app, &scene).await;
+            let _ = app.emit("obs://scene-items-changed", json!({"sceneName": scene}));
         }
         "SceneNameChanged" => {
             let old_name = event_data["oldSceneName"].as_str().unwrap_or("").to_string();
@@ -787,7 +1592,13 @@ async fn handle_event(
                 if s.current_scene == old_name {
                     s.current_scene = new_name.clone();
                 }
+                if s.preview_scene.as_deref() == Some(old_name.as_str()) {
+                    s.preview_scene = Some(new_name.clone());
+                }
             }
+            crate::app_capture::on_scene_renamed(app, &old_name, &new_name).await;
+            crate::screenshot_cache::invalidate(app, &old_name).await;
+            crate::screenshot_cache::invalidate(app, &new_name).await;
             let _ = app.emit("obs://scene-name-changed", json!({"oldSceneName": old_name, "sceneName": new_name}));
         }
         _ => {}
@@ -837,3 +1648,128 @@ fn generate_auth_string(
         Sha256::digest(format!("{}{}", secret, challenge).as_bytes());
     base64::engine::general_purpose::STANDARD.encode(auth_hash)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Counts poll replies instead of touching app state.
+    struct CountingHooks {
+        polls_answered: Arc<AtomicUsize>,
+    }
+
+    impl SessionHooks for CountingHooks {
+        fn poll_requests(&mut self) -> BoxFuture<'_, Vec<Value>> {
+            Box::pin(async move {
+                vec![json!({
+                    "op": 6,
+                    "d": {
+                        "requestType": "GetStats",
+                        "requestId": format!("__stats_{}", uuid::Uuid::new_v4()),
+                    }
+                })]
+            })
+        }
+
+        fn on_event<'a>(&'a mut self, _data: &'a Value) -> BoxFuture<'a, ()> {
+            Box::pin(async {})
+        }
+
+        fn on_poll_response<'a>(&'a mut self, _request_id: &'a str, _data: &'a Value) -> BoxFuture<'a, ()> {
+            Box::pin(async move {
+                self.polls_answered.fetch_add(1, Ordering::Relaxed);
+            })
+        }
+    }
+
+    /// A stand-in OBS that answers every request after a short, uneven
+    /// delay, echoing requestData back as responseData.
+    async fn mock_obs() -> u16 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let (mut write, mut read) = ws.split();
+            let (out_tx, mut out_rx) = mpsc::unbounded_channel::<Message>();
+            tokio::spawn(async move {
+                while let Some(msg) = out_rx.recv().await {
+                    if write.send(msg).await.is_err() {
+                        break;
+                    }
+                }
+            });
+            let mut n = 0u64;
+            while let Some(Ok(Message::Text(text))) = read.next().await {
+                let request: Value = serde_json::from_str(text.as_str()).unwrap();
+                let d = &request["d"];
+                let reply = json!({
+                    "op": 7,
+                    "d": {
+                        "requestType": d["requestType"],
+                        "requestId": d["requestId"],
+                        "requestStatus": { "result": true, "code": 100 },
+                        "responseData": d.get("requestData").cloned().unwrap_or(json!({})),
+                    }
+                });
+                n += 1;
+                let delay = Duration::from_millis(50 + (n * 7) % 40);
+                let out_tx = out_tx.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    let _ = out_tx.send(Message::text(reply.to_string()));
+                });
+            }
+        });
+        port
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn parallel_requests_correlate_while_stats_polls_fire() {
+        let port = mock_obs().await;
+        let (ws, _) = tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}", port))
+            .await
+            .unwrap();
+        let (write, read) = ws.split();
+
+        let conn = ObsConnection::new();
+        let handle = conn.handle();
+        let (tx, rx) = mpsc::channel::<Message>(32);
+        let (_stop_tx, stop_rx) = oneshot::channel::<()>();
+        *handle.sender.write().unwrap() = Some(tx);
+        handle.connected_flag.store(true, Ordering::Relaxed);
+
+        let polls_answered = Arc::new(AtomicUsize::new(0));
+        let mut hooks = CountingHooks {
+            polls_answered: polls_answered.clone(),
+        };
+        let pending = handle.pending.clone();
+        let ws_log = handle.ws_log.clone();
+        tokio::spawn(async move {
+            // Polls every 5ms, so many ticks land while well over 32
+            // requests are queued in the channel the loop itself drains.
+            run_session(write, read, rx, stop_rx, pending, ws_log, Duration::from_millis(5), &mut hooks)
+                .await;
+        });
+
+        let requests = (0..200).map(|i| {
+            let handle = handle.clone();
+            async move {
+                handle
+                    .send_request("GetInputVolume", Some(json!({ "index": i })))
+                    .await
+            }
+        });
+        let results = tokio::time::timeout(Duration::from_secs(10), futures_util::future::join_all(requests))
+            .await
+            .expect("requests stalled; the session loop is blocked");
+
+        for (i, result) in results.into_iter().enumerate() {
+            let data = result.unwrap_or_else(|e| panic!("request {} failed: {}", i, e));
+            assert_eq!(data["index"], i, "request {} got another request's response", i);
+        }
+        assert!(handle.pending.lock().await.is_empty());
+        assert!(polls_answered.load(Ordering::Relaxed) > 0);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::ai_metrics::ModelTiming;
+use crate::api_key_store::KeySource;
+use crate::chat_provider::{AiProviderKind, ChatHistory, ChatProvider};
+use crate::gemini::{
+    self, api_error_message, build_system_prompt, ChatResponse, PromptParams, ResponseProblem,
+    CONCISE_RETRY_RULES,
+};
+use futures_util::future::BoxFuture;
+use serde_json::{json, Value};
+use std::time::Instant;
+
+/// Chat over an OpenAI-compatible `/chat/completions` API. The base URL
+/// picks the service, so this covers OpenAI, Ollama, LM Studio and
+/// OpenRouter. No function calling; actions come back in the JSON reply.
+pub struct OpenAiProvider {
+    base_url: String,
+    api_key: Option<String>,
+    key_source: KeySource,
+    http: reqwest::Client,
+    model: String,
+    history: ChatHistory,
+    timing: ModelTiming,
+}
+
+impl ChatProvider for OpenAiProvider {
+    fn kind(&self) -> AiProviderKind {
+        AiProviderKind::OpenAi
+    }
+
+    fn send_message<'a>(
+        &'a mut self,
+        user_text: &'a str,
+        params: PromptParams<'a>,
+    ) -> BoxFuture<'a, Result<ChatResponse, String>> {
+        Box::pin(self.send(user_text, params))
+    }
+
+    fn set_model(&mut self, model: &str) {
+        self.model = model.trim().to_string();
+    }
+
+    fn chat_history(&self) -> &ChatHistory {
+        &self.history
+    }
+
+    fn chat_history_mut(&mut self) -> &mut ChatHistory {
+        &mut self.history
+    }
+
+    fn take_timing(&mut self) -> ModelTiming {
+        std::mem::take(&mut self.timing)
+    }
+
+    fn key_source(&self) -> KeySource {
+        self.key_source
+    }
+}
+
+impl OpenAiProvider {
+    /// Local servers like Ollama take no key.
+    pub fn new(base_url: &str, key: Option<(String, KeySource)>) -> Self {
+        let (api_key, key_source) = match key {
+            Some((key, source)) => (Some(key), source),
+            None => (None, KeySource::None),
+        };
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            key_source,
+            http: reqwest::Client::new(),
+            model: String::new(),
+            history: ChatHistory::default(),
+            timing: ModelTiming::default(),
+        }
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}/chat/completions", self.base_url)
+    }
+
+    async fn send(&mut self, user_text: &str, params: PromptParams<'_>) -> Result<ChatResponse, String> {
+        self.history.push("user", user_text);
+
+        let prompt_started = Instant::now();
+        let system_prompt = build_system_prompt(params);
+        self.timing.prompt_ms += prompt_started.elapsed().as_millis() as u64;
+
+        let mut trimmed = false;
+        loop {
+            let (system_prompt, limit) = if trimmed {
+                (format!("{}{}", system_prompt, CONCISE_RETRY_RULES), 1)
+            } else {
+                (system_prompt.clone(), self.history.limit())
+            };
+            let body = json!({
+                "model": self.model,
+                "messages": self.messages(&system_prompt, limit),
+                "response_format": response_format()
+            });
+
+            let resp_json = self.post(&body).await?;
+            let problem = match reply_text(&resp_json) {
+                Ok(text) => {
+                    let parse_started = Instant::now();
+                    let parsed = serde_json::from_str::<ChatResponse>(strip_code_fence(&text));
+                    self.timing.parse_ms += parse_started.elapsed().as_millis() as u64;
+                    match parsed {
+                        Ok(chat_response) => {
+                            self.history.push("model", &text);
+                            return Ok(chat_response);
+                        }
+                        Err(e) if e.is_eof() => ResponseProblem::Truncated,
+                        Err(e) => ResponseProblem::Unparseable(e.to_string()),
+                    }
+                }
+                Err(problem) => problem,
+            };
+
+            if matches!(problem, ResponseProblem::Truncated) && !trimmed {
+                log::warn!("AI response truncated; retrying with a trimmed prompt");
+                trimmed = true;
+                continue;
+            }
+            log::warn!("AI provider returned no usable response: {:?}", problem);
+            self.history.drop_unanswered();
+            return Ok(problem.into_response());
+        }
+    }
+
+    fn messages(&self, system_prompt: &str, limit: usize) -> Vec<Value> {
+        let mut messages = vec![json!({"role": "system", "content": system_prompt})];
+        messages.extend(self.history.recent(limit).iter().map(|m| {
+            let role = if m.role == "model" { "assistant" } else { "user" };
+            json!({"role": role, "content": m.text})
+        }));
+        messages
+    }
+
+    async fn post(&mut self, body: &Value) -> Result<Value, String> {
+        self.timing.model_calls += 1;
+        let network_started = Instant::now();
+        let mut request = self.http.post(self.endpoint()).json(body);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| format!("AI request failed: {}", e))?;
+
+        let status = resp.status();
+        let resp_text = resp
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?;
+        self.timing.network_ms += network_started.elapsed().as_millis() as u64;
+
+        if !status.is_success() {
+            return Err(format!("AI API error ({}): {}", status, api_error_message(&resp_text)));
+        }
+
+        let parse_started = Instant::now();
+        let parsed = serde_json::from_str(&resp_text).map_err(|e| format!("Invalid JSON: {}", e));
+        self.timing.parse_ms += parse_started.elapsed().as_millis() as u64;
+        parsed
+    }
+}
+
+/// The first choice's text, or what went wrong with it.
+fn reply_text(resp_json: &Value) -> Result<String, ResponseProblem> {
+    let choice = &resp_json["choices"][0];
+    if choice.is_null() {
+        return Err(ResponseProblem::Empty);
+    }
+    if let Some(refusal) = choice["message"]["refusal"].as_str().filter(|r| !r.is_empty()) {
+        return Err(ResponseProblem::ResponseBlocked(refusal.to_string()));
+    }
+    match choice["finish_reason"].as_str().unwrap_or_default() {
+        "length" => return Err(ResponseProblem::Truncated),
+        "content_filter" => return Err(ResponseProblem::ResponseBlocked("content_filter".into())),
+        _ => {}
+    }
+    match choice["message"]["content"].as_str().map(str::trim) {
+        Some(text) if !text.is_empty() => Ok(text.to_string()),
+        _ => Err(ResponseProblem::Empty),
+    }
+}
+
+/// Local models sometimes wrap the JSON in a markdown code fence.
+fn strip_code_fence(text: &str) -> &str {
+    let Some(inner) = text.strip_prefix("```") else {
+        return text;
+    };
+    let inner = inner.strip_prefix("json").unwrap_or(inner);
+    inner.strip_suffix("```").unwrap_or(inner).trim()
+}
+
+/// The Gemini reply schema as a strict OpenAI `json_schema`: without the
+/// Gemini-only keywords, and with every object closed to extra keys.
+fn response_format() -> Value {
+    let mut schema = gemini::response_schema();
+    close_objects(&mut schema);
+    json!({
+        "type": "json_schema",
+        "json_schema": {"name": "observe_reply", "strict": true, "schema": schema}
+    })
+}
+
+fn close_objects(schema: &mut Value) {
+    let Some(obj) = schema.as_object_mut() else {
+        return;
+    };
+    obj.remove("propertyOrdering");
+    if obj.get("type").and_then(Value::as_str) == Some("object") {
+        obj.insert("additionalProperties".into(), json!(false));
+    }
+    if let Some(properties) = obj.get_mut("properties").and_then(Value::as_object_mut) {
+        properties.values_mut().for_each(close_objects);
+    }
+    if let Some(items) = obj.get_mut("items") {
+        close_objects(items);
+    }
+}
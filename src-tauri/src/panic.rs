@@ -0,0 +1,264 @@
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::settings::SharedSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+use tokio::sync::Mutex;
+
+/// The pre-panic snapshot while panic mode is engaged. Mirrored to disk so a
+/// restart in the middle of it can still put everything back.
+pub type SharedPanicState = Arc<Mutex<Option<PanicSnapshot>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PanicSnapshot {
+    pub scene: String,
+    /// Inputs we muted, with their mute state beforehand.
+    pub mutes: Vec<(String, bool)>,
+    /// True if panic mode paused a running recording.
+    pub paused_recording: bool,
+    pub engaged_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PanicStatus {
+    pub engaged: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<PanicSnapshot>,
+}
+
+fn panic_file_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("panic_state.json")
+}
+
+pub fn load() -> Option<PanicSnapshot> {
+    let content = std::fs::read_to_string(panic_file_path()).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            log::warn!("Discarding unreadable panic state: {}", e);
+            None
+        }
+    }
+}
+
+fn persist(snapshot: Option<&PanicSnapshot>) -> Result<(), String> {
+    let path = panic_file_path();
+    let Some(snapshot) = snapshot else {
+        return match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to clear panic state: {}", e))
+            }
+            _ => Ok(()),
+        };
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| format!("Failed to serialize panic state: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to save panic state: {}", e))
+}
+
+async fn set_mute(conn: &ObsHandle, input_name: &str, muted: bool) -> Result<(), String> {
+    conn.send_request(
+        "SetInputMute",
+        Some(json!({"inputName": input_name, "inputMuted": muted})),
+    )
+    .await
+    .map(|_| ())
+}
+
+async fn engage(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    settings: &SharedSettings,
+    panic: &SharedPanicState,
+) -> Result<(PanicSnapshot, Vec<String>), String> {
+    let config = settings.read().await.panic.clone();
+    // Without a scene to cut to, the stream keeps showing whatever it was.
+    if config.privacy_scene.is_empty() {
+        return Err("Choose a privacy scene before using panic mode".to_string());
+    }
+    if !obs_state.read().await.scenes.iter().any(|s| s.name == config.privacy_scene) {
+        return Err(format!("Privacy scene '{}' no longer exists in OBS", config.privacy_scene));
+    }
+    let mut current = panic.lock().await;
+
+    // Engaging again re-applies the panic state but keeps the original
+    // snapshot; otherwise release would "restore" the panic state.
+    let snapshot = match current.as_ref() {
+        Some(existing) => existing.clone(),
+        None => {
+            let (scene, mutes) = {
+                let s = obs_state.read().await;
+                let special = &s.special_inputs;
+                let mut names = vec![&special.mic1, &special.mic2, &special.mic3];
+                if config.mute_desktop {
+                    names.extend([&special.desktop1, &special.desktop2]);
+                }
+                let mutes: Vec<(String, bool)> = names
+                    .into_iter()
+                    .filter_map(|name| s.inputs.get(name).map(|i| (name.clone(), i.muted)))
+                    .collect();
+                (s.current_scene.clone(), mutes)
+            };
+            let paused_recording = if config.pause_recording {
+                let status = conn.send_request("GetRecordStatus", None).await?;
+                status["outputActive"].as_bool().unwrap_or(false)
+                    && !status["outputPaused"].as_bool().unwrap_or(false)
+            } else {
+                false
+            };
+            let snapshot = PanicSnapshot {
+                scene,
+                mutes,
+                paused_recording,
+                engaged_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0),
+            };
+            // Saved before touching OBS so a crash halfway can still be undone.
+            let saved = snapshot.clone();
+            tokio::task::spawn_blocking(move || persist(Some(&saved)))
+                .await
+                .map_err(|e| format!("Task failed: {}", e))??;
+            *current = Some(snapshot.clone());
+            snapshot
+        }
+    };
+
+    let mut errors = Vec::new();
+    for (name, _) in &snapshot.mutes {
+        if let Err(e) = set_mute(conn, name, true).await {
+            errors.push(format!("mute '{}': {}", name, e));
+        }
+    }
+    if let Err(e) = conn
+        .send_request(
+            "SetCurrentProgramScene",
+            Some(json!({"sceneName": config.privacy_scene})),
+        )
+        .await
+    {
+        errors.push(format!("switch to '{}': {}", config.privacy_scene, e));
+    }
+    if snapshot.paused_recording {
+        if let Err(e) = conn.send_request("PauseRecord", None).await {
+            errors.push(format!("pause recording: {}", e));
+        }
+    }
+    Ok((snapshot, errors))
+}
+
+async fn release(conn: &ObsHandle, obs_state: &SharedObsState, panic: &SharedPanicState) -> Result<PanicSnapshot, String> {
+    let mut current = panic.lock().await;
+    let snapshot = current.clone().ok_or("Panic mode is not engaged")?;
+
+    let mut errors = Vec::new();
+    let scene_exists = obs_state.read().await.scenes.iter().any(|s| s.name == snapshot.scene);
+    if scene_exists {
+        if let Err(e) = conn
+            .send_request(
+                "SetCurrentProgramScene",
+                Some(json!({"sceneName": snapshot.scene})),
+            )
+            .await
+        {
+            errors.push(format!("switch back to '{}': {}", snapshot.scene, e));
+        }
+    }
+    for (name, muted) in &snapshot.mutes {
+        if let Err(e) = set_mute(conn, name, *muted).await {
+            errors.push(format!("unmute '{}': {}", name, e));
+        }
+    }
+    if snapshot.paused_recording {
+        let status = conn.send_request("GetRecordStatus", None).await?;
+        if status["outputPaused"].as_bool().unwrap_or(false) {
+            if let Err(e) = conn.send_request("ResumeRecord", None).await {
+                errors.push(format!("resume recording: {}", e));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        // Keep the snapshot so the user can retry the release.
+        return Err(format!("Panic release incomplete: {}", errors.join("; ")));
+    }
+
+    tokio::task::spawn_blocking(|| persist(None))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    *current = None;
+    Ok(snapshot)
+}
+
+async fn set_panic(app: &tauri::AppHandle, enable: bool) -> Result<PanicStatus, String> {
+    let conn = app.state::<ObsHandle>().inner().clone();
+    let obs_state = app.state::<SharedObsState>().inner().clone();
+    let settings = app.state::<SharedSettings>().inner().clone();
+    let panic = app.state::<SharedPanicState>().inner().clone();
+    if !conn.is_connected() {
+        return Err("Not connected to OBS".to_string());
+    }
+
+    if enable {
+        let (snapshot, errors) = engage(&conn, &obs_state, &settings, &panic).await?;
+        // The snapshot is kept either way, so releasing undoes what did apply.
+        crate::tray::set_panic(app, true);
+        if !errors.is_empty() {
+            log::warn!("Panic mode only partly engaged: {}", errors.join("; "));
+            let _ = app.emit(
+                "panic://engage-failed",
+                json!({"snapshot": snapshot, "failed": errors}),
+            );
+            return Err(format!("Panic mode only partly engaged: {}", errors.join("; ")));
+        }
+        let _ = app.emit("panic://engaged", &snapshot);
+        Ok(PanicStatus {
+            engaged: true,
+            snapshot: Some(snapshot),
+        })
+    } else {
+        let snapshot = release(&conn, &obs_state, &panic).await?;
+        crate::tray::set_panic(app, false);
+        let _ = app.emit("panic://released", &snapshot);
+        Ok(PanicStatus {
+            engaged: false,
+            snapshot: None,
+        })
+    }
+}
+
+/// Called from the global shortcut handler; flips panic mode.
+pub fn toggle(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let engaged = app.state::<SharedPanicState>().lock().await.is_some();
+        if let Err(e) = set_panic(&app, !engaged).await {
+            log::warn!("Panic hotkey failed: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn panic_mode(app_handle: tauri::AppHandle, enable: bool) -> Result<PanicStatus, String> {
+    set_panic(&app_handle, enable).await
+}
+
+#[tauri::command]
+pub async fn get_panic_status(panic: tauri::State<'_, SharedPanicState>) -> Result<PanicStatus, String> {
+    let snapshot = panic.lock().await.clone();
+    Ok(PanicStatus {
+        engaged: snapshot.is_some(),
+        snapshot,
+    })
+}
@@ -0,0 +1,133 @@
+use crate::obs_state::ObsState;
+use crate::obs_websocket::ObsHandle;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingPluginsReport {
+    pub checked: bool,
+    pub missing: Vec<MissingKind>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingKind {
+    pub kind: String,
+    pub category: String,
+    pub plugin_name: String,
+    pub sources: Vec<String>,
+}
+
+impl MissingPluginsReport {
+    pub fn affected_source_count(&self) -> usize {
+        self.missing
+            .iter()
+            .flat_map(|m| m.sources.iter())
+            .collect::<HashSet<_>>()
+            .len()
+    }
+}
+
+const KNOWN_PLUGIN_KINDS: &[(&str, &str)] = &[
+    ("ndi_source", "DistroAV (NDI)"),
+    ("ndi_filter", "DistroAV (NDI)"),
+    ("ndi_audiofilter", "DistroAV (NDI)"),
+    ("move_source_filter", "Move Transition"),
+    ("move_value_filter", "Move Transition"),
+    ("move_audio_value_filter", "Move Transition"),
+    ("move_action_filter", "Move Transition"),
+    ("move_transition", "Move Transition"),
+    ("obs_shaderfilter", "obs-shaderfilter"),
+    ("shader_filter", "obs-shaderfilter"),
+    ("streamfx-", "StreamFX"),
+    ("background_removal", "Background Removal"),
+    ("obs_composite_blur", "Composite Blur"),
+    ("source_record_filter", "Source Record"),
+    ("spectralizer", "Spectralizer"),
+    ("waveform_source", "Waveform"),
+    ("advanced_masks_filter", "Advanced Masks"),
+    ("vertical_canvas_source", "Aitum Vertical"),
+    ("tuna_source", "Tuna"),
+    ("vlc_source", "VLC Video Source (requires VLC installed)"),
+];
+
+fn guess_plugin_name(kind: &str) -> String {
+    if kind.is_empty() {
+        return "Unknown plugin".into();
+    }
+    KNOWN_PLUGIN_KINDS
+        .iter()
+        .find(|(k, _)| kind == *k || (k.ends_with('-') && kind.starts_with(k)))
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("Unknown plugin providing '{}'", kind))
+}
+
+async fn fetch_kind_list(conn: &ObsHandle, request_type: &str, field: &str) -> Result<HashSet<String>, String> {
+    let data = conn
+        .send_request(request_type, Some(json!({"unversioned": false})))
+        .await?;
+    Ok(data[field]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+pub async fn analyze(conn: &ObsHandle, obs: &ObsState) -> Result<MissingPluginsReport, String> {
+    let input_kinds = fetch_kind_list(conn, "GetInputKindList", "inputKinds").await?;
+    let filter_kinds = fetch_kind_list(conn, "GetSourceFilterKindList", "sourceFilterKinds").await?;
+
+    let mut missing: BTreeMap<(String, String), BTreeSet<String>> = BTreeMap::new();
+
+    for input in obs.inputs.values() {
+        if input.kind.is_empty() || !input_kinds.contains(&input.kind) {
+            missing
+                .entry(("input".into(), input.kind.clone()))
+                .or_default()
+                .insert(input.name.clone());
+        }
+        for filter in &input.filters {
+            if filter.kind.is_empty() || !filter_kinds.contains(&filter.kind) {
+                missing
+                    .entry(("filter".into(), filter.kind.clone()))
+                    .or_default()
+                    .insert(format!("{} → {}", input.name, filter.name));
+            }
+        }
+    }
+
+    for items in obs.scene_items.values() {
+        for item in items {
+            if obs.inputs.contains_key(&item.source_name)
+                || item.source_kind.starts_with("OBS_SOURCE_TYPE_")
+            {
+                continue;
+            }
+            if item.source_kind.is_empty() || !input_kinds.contains(&item.source_kind) {
+                missing
+                    .entry(("input".into(), item.source_kind.clone()))
+                    .or_default()
+                    .insert(item.source_name.clone());
+            }
+        }
+    }
+
+    Ok(MissingPluginsReport {
+        checked: true,
+        missing: missing
+            .into_iter()
+            .map(|((category, kind), sources)| MissingKind {
+                plugin_name: guess_plugin_name(&kind),
+                kind,
+                category,
+                sources: sources.into_iter().collect(),
+            })
+            .collect(),
+    })
+}
@@ -1,6 +1,20 @@
+use crate::disk_headroom::{ConfiguredEncoder, RecordingHeadroom};
+use crate::i18n::t;
 use crate::obs_state::ObsState;
+use crate::stream_service::StreamDestinationTest;
 use crate::system_monitor::SystemResources;
 use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Stream bitrate should leave this much of the measured upload unused.
+const UPLOAD_HEADROOM: f64 = 1.5;
+/// Above this share of the measured upload the stream will almost surely drop frames.
+const UPLOAD_FAIL_RATIO: f64 = 0.8;
+const SPEED_TEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// x264 presets at or slower than medium need a strong CPU to keep up.
+const SLOW_X264_PRESETS: &[&str] = &["medium", "slow", "slower", "veryslow", "placebo"];
+const X264_MIN_CORES: u32 = 6;
+const X264_MAX_CPU_PERCENT: f32 = 60.0;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,6 +34,17 @@ pub struct CheckResult {
     pub detail: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedTestResult {
+    pub upload_kbps: u32,
+    pub bytes: u64,
+    pub duration_ms: u64,
+    pub endpoint: String,
+    /// Unix time in milliseconds.
+    pub tested_at: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum CheckStatus {
@@ -29,7 +54,16 @@ pub enum CheckStatus {
     Skip,
 }
 
-pub fn run_all_checks(obs: &ObsState, sys: &SystemResources, mode: &str) -> PreflightReport {
+pub fn run_all_checks(
+    obs: &ObsState,
+    sys: &SystemResources,
+    mode: &str,
+    destination: Option<&StreamDestinationTest>,
+    headroom: Option<&RecordingHeadroom>,
+    stream_bitrate_kbps: Option<u32>,
+    speed_test: Option<&SpeedTestResult>,
+    encoder: Option<&ConfiguredEncoder>,
+) -> PreflightReport {
     let mut checks = vec![
         check_audio_inputs(obs),
         check_audio_mute(obs),
@@ -43,11 +77,20 @@ pub fn run_all_checks(obs: &ObsState, sys: &SystemResources, mode: &str) -> Pref
 
     if mode == "stream" {
         checks.push(check_stream_service(obs));
+        if let Some(dest) = destination {
+            checks.push(check_stream_destination(dest));
+        }
+        checks.push(check_upload_bandwidth(stream_bitrate_kbps, speed_test));
     }
     if mode == "record" {
         checks.push(check_record_directory(obs));
+        checks.push(check_record_headroom(headroom));
     }
 
+    if mode == "stream" || mode == "record" {
+        checks.push(check_encoder(encoder, sys));
+    }
+    checks.push(check_missing_plugins(obs));
     checks.push(check_dropped_frames(obs));
 
     let pass_count = checks.iter().filter(|c| c.status == CheckStatus::Pass).count() as u32;
@@ -81,16 +124,16 @@ fn check_audio_inputs(obs: &ObsState) -> CheckResult {
     if audio_inputs.is_empty() {
         CheckResult {
             id: "audio_inputs".into(),
-            label: "Audio Inputs".into(),
+            label: t("preflight.audio_inputs.label", &[]),
             status: CheckStatus::Fail,
-            detail: "No audio inputs found in OBS".into(),
+            detail: t("preflight.audio_inputs.none", &[]),
         }
     } else {
         CheckResult {
             id: "audio_inputs".into(),
-            label: "Audio Inputs".into(),
+            label: t("preflight.audio_inputs.label", &[]),
             status: CheckStatus::Pass,
-            detail: format!("{} audio source(s)", audio_inputs.len()),
+            detail: t("preflight.audio_inputs.count", &[("count", &audio_inputs.len())]),
         }
     }
 }
@@ -106,16 +149,16 @@ fn check_audio_mute(obs: &ObsState) -> CheckResult {
     if muted.is_empty() {
         CheckResult {
             id: "audio_mute".into(),
-            label: "Audio Mute".into(),
+            label: t("preflight.audio_mute.label", &[]),
             status: CheckStatus::Pass,
-            detail: "No inputs muted".into(),
+            detail: t("preflight.audio_mute.none", &[]),
         }
     } else {
         CheckResult {
             id: "audio_mute".into(),
-            label: "Audio Mute".into(),
+            label: t("preflight.audio_mute.label", &[]),
             status: CheckStatus::Warn,
-            detail: format!("Muted: {}", muted.join(", ")),
+            detail: t("preflight.audio_mute.muted", &[("inputs", &muted.join(", "))]),
         }
     }
 }
@@ -124,14 +167,14 @@ fn check_active_scene(obs: &ObsState) -> CheckResult {
     if obs.current_scene.is_empty() {
         CheckResult {
             id: "active_scene".into(),
-            label: "Active Scene".into(),
+            label: t("preflight.active_scene.label", &[]),
             status: CheckStatus::Fail,
-            detail: "No active scene selected".into(),
+            detail: t("preflight.active_scene.none", &[]),
         }
     } else {
         CheckResult {
             id: "active_scene".into(),
-            label: "Active Scene".into(),
+            label: t("preflight.active_scene.label", &[]),
             status: CheckStatus::Pass,
             detail: obs.current_scene.clone(),
         }
@@ -145,25 +188,25 @@ fn check_video_resolution(obs: &ObsState) -> CheckResult {
     if w == 0 || h == 0 {
         return CheckResult {
             id: "video_resolution".into(),
-            label: "Video Resolution".into(),
+            label: t("preflight.video_resolution.label", &[]),
             status: CheckStatus::Skip,
-            detail: "Could not read video settings".into(),
+            detail: t("preflight.video_resolution.unknown", &[]),
         };
     }
 
     if h >= 720 {
         CheckResult {
             id: "video_resolution".into(),
-            label: "Video Resolution".into(),
+            label: t("preflight.video_resolution.label", &[]),
             status: CheckStatus::Pass,
             detail: format!("{}x{}", w, h),
         }
     } else {
         CheckResult {
             id: "video_resolution".into(),
-            label: "Video Resolution".into(),
+            label: t("preflight.video_resolution.label", &[]),
             status: CheckStatus::Warn,
-            detail: format!("{}x{} (below 720p)", w, h),
+            detail: t("preflight.video_resolution.low", &[("width", &w), ("height", &h)]),
         }
     }
 }
@@ -175,9 +218,9 @@ fn check_frame_rate(obs: &ObsState) -> CheckResult {
     if num == 0 || den == 0 {
         return CheckResult {
             id: "frame_rate".into(),
-            label: "Frame Rate".into(),
+            label: t("preflight.frame_rate.label", &[]),
             status: CheckStatus::Skip,
-            detail: "Could not read FPS settings".into(),
+            detail: t("preflight.frame_rate.unknown", &[]),
         };
     }
 
@@ -185,16 +228,16 @@ fn check_frame_rate(obs: &ObsState) -> CheckResult {
     if fps >= 24.0 {
         CheckResult {
             id: "frame_rate".into(),
-            label: "Frame Rate".into(),
+            label: t("preflight.frame_rate.label", &[]),
             status: CheckStatus::Pass,
             detail: format!("{:.0} FPS", fps),
         }
     } else {
         CheckResult {
             id: "frame_rate".into(),
-            label: "Frame Rate".into(),
+            label: t("preflight.frame_rate.label", &[]),
             status: CheckStatus::Warn,
-            detail: format!("{:.0} FPS (below 24)", fps),
+            detail: t("preflight.frame_rate.low", &[("fps", &format!("{:.0}", fps))]),
         }
     }
 }
@@ -202,16 +245,16 @@ fn check_frame_rate(obs: &ObsState) -> CheckResult {
 fn check_cpu_usage(sys: &SystemResources) -> CheckResult {
     let cpu = sys.cpu_usage_percent;
     let (status, detail) = if cpu > 90.0 {
-        (CheckStatus::Fail, format!("{:.0}% — very high", cpu))
+        (CheckStatus::Fail, t("preflight.usage.very_high", &[("percent", &format!("{:.0}", cpu))]))
     } else if cpu > 75.0 {
-        (CheckStatus::Warn, format!("{:.0}% — elevated", cpu))
+        (CheckStatus::Warn, t("preflight.usage.elevated", &[("percent", &format!("{:.0}", cpu))]))
     } else {
         (CheckStatus::Pass, format!("{:.0}%", cpu))
     };
 
     CheckResult {
         id: "cpu_usage".into(),
-        label: "CPU Usage".into(),
+        label: t("preflight.cpu_usage.label", &[]),
         status,
         detail,
     }
@@ -222,12 +265,12 @@ fn check_memory_usage(sys: &SystemResources) -> CheckResult {
     let (status, detail) = if pct > 90.0 {
         (
             CheckStatus::Fail,
-            format!("{:.0}% — critical", pct),
+            t("preflight.usage.critical", &[("percent", &format!("{:.0}", pct))]),
         )
     } else if pct > 80.0 {
         (
             CheckStatus::Warn,
-            format!("{:.0}% — elevated", pct),
+            t("preflight.usage.elevated", &[("percent", &format!("{:.0}", pct))]),
         )
     } else {
         (CheckStatus::Pass, format!("{:.0}%", pct))
@@ -235,7 +278,7 @@ fn check_memory_usage(sys: &SystemResources) -> CheckResult {
 
     CheckResult {
         id: "memory_usage".into(),
-        label: "Memory Usage".into(),
+        label: t("preflight.memory_usage.label", &[]),
         status,
         detail,
     }
@@ -244,16 +287,16 @@ fn check_memory_usage(sys: &SystemResources) -> CheckResult {
 fn check_disk_space(sys: &SystemResources) -> CheckResult {
     let free = sys.disk_free_gb;
     let (status, detail) = if free < 2.0 {
-        (CheckStatus::Fail, format!("{:.1} GB free — critical", free))
+        (CheckStatus::Fail, t("preflight.disk_space.critical", &[("gb", &format!("{:.1}", free))]))
     } else if free < 10.0 {
-        (CheckStatus::Warn, format!("{:.1} GB free — low", free))
+        (CheckStatus::Warn, t("preflight.disk_space.low", &[("gb", &format!("{:.1}", free))]))
     } else {
-        (CheckStatus::Pass, format!("{:.1} GB free", free))
+        (CheckStatus::Pass, t("preflight.disk_space.free", &[("gb", &format!("{:.1}", free))]))
     };
 
     CheckResult {
         id: "disk_space".into(),
-        label: "Disk Space".into(),
+        label: t("preflight.disk_space.label", &[]),
         status,
         detail,
     }
@@ -264,25 +307,185 @@ fn check_stream_service(obs: &ObsState) -> CheckResult {
     if svc.service_type.is_empty() {
         CheckResult {
             id: "stream_service".into(),
-            label: "Stream Service".into(),
+            label: t("preflight.stream_service.label", &[]),
             status: CheckStatus::Fail,
-            detail: "No stream service configured".into(),
+            detail: t("preflight.stream_service.none", &[]),
         }
     } else if !svc.key_set {
         CheckResult {
             id: "stream_service".into(),
-            label: "Stream Service".into(),
+            label: t("preflight.stream_service.label", &[]),
             status: CheckStatus::Fail,
-            detail: format!("{} — no stream key set", svc.service_type),
+            detail: t("preflight.stream_service.no_key", &[("service", &svc.service_type)]),
         }
     } else {
         CheckResult {
             id: "stream_service".into(),
-            label: "Stream Service".into(),
+            label: t("preflight.stream_service.label", &[]),
+            status: CheckStatus::Pass,
+            detail: t("preflight.stream_service.key_set", &[("service", &svc.service_type)]),
+        }
+    }
+}
+
+fn check_stream_destination(dest: &StreamDestinationTest) -> CheckResult {
+    if dest.reachable {
+        CheckResult {
+            id: "stream_destination".into(),
+            label: t("preflight.stream_destination.label", &[]),
             status: CheckStatus::Pass,
-            detail: format!("{} — key set", svc.service_type),
+            detail: t(
+                "preflight.stream_destination.reachable",
+                &[
+                    ("host", &dest.host),
+                    ("port", &dest.port),
+                    ("ms", &dest.latency_ms.unwrap_or(0)),
+                ],
+            ),
+        }
+    } else {
+        CheckResult {
+            id: "stream_destination".into(),
+            label: t("preflight.stream_destination.label", &[]),
+            status: CheckStatus::Fail,
+            detail: t(
+                "preflight.stream_destination.unreachable",
+                &[
+                    ("host", &dest.host),
+                    ("port", &dest.port),
+                    (
+                        "error",
+                        &dest
+                            .error
+                            .clone()
+                            .unwrap_or_else(|| t("preflight.stream_destination.unknown_error", &[])),
+                    ),
+                ],
+            ),
+        }
+    }
+}
+
+/// Uploads `megabytes` of zeros to `endpoint` and times it.
+pub async fn measure_upload(endpoint: &str, megabytes: u32) -> Result<SpeedTestResult, String> {
+    let bytes = megabytes.max(1) as usize * 1024 * 1024;
+    let client = reqwest::Client::builder()
+        .timeout(SPEED_TEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("HTTP client error: {}", e))?;
+    let started = Instant::now();
+    let resp = client
+        .post(endpoint)
+        .header("Content-Type", "application/octet-stream")
+        .body(vec![0u8; bytes])
+        .send()
+        .await
+        .map_err(|e| format!("Speed test upload failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("Speed test endpoint returned {}", resp.status()));
+    }
+    let elapsed = started.elapsed();
+    let tested_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    Ok(SpeedTestResult {
+        upload_kbps: (bytes as f64 * 8.0 / 1000.0 / elapsed.as_secs_f64().max(0.001)) as u32,
+        bytes: bytes as u64,
+        duration_ms: elapsed.as_millis() as u64,
+        endpoint: endpoint.to_string(),
+        tested_at,
+    })
+}
+
+fn check_upload_bandwidth(bitrate_kbps: Option<u32>, speed_test: Option<&SpeedTestResult>) -> CheckResult {
+    let label = t("preflight.upload_bandwidth.label", &[]);
+    let (Some(bitrate), Some(test)) = (bitrate_kbps.filter(|b| *b > 0), speed_test) else {
+        return CheckResult {
+            id: "upload_bandwidth".into(),
+            label,
+            status: CheckStatus::Skip,
+            detail: t("preflight.upload_bandwidth.unknown", &[]),
+        };
+    };
+
+    let bitrate_f = bitrate as f64;
+    let upload = test.upload_kbps as f64;
+    let status = if bitrate_f > upload * UPLOAD_FAIL_RATIO {
+        CheckStatus::Fail
+    } else if bitrate_f * UPLOAD_HEADROOM > upload {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    CheckResult {
+        id: "upload_bandwidth".into(),
+        label,
+        status,
+        detail: t(
+            "preflight.upload_bandwidth.compare",
+            &[
+                ("bitrate", &format!("{:.1}", bitrate_f / 1000.0)),
+                ("upload", &format!("{:.1}", upload / 1000.0)),
+                ("percent", &format!("{:.0}", bitrate_f / upload.max(1.0) * 100.0)),
+            ],
+        ),
+    }
+}
+
+fn check_encoder(encoder: Option<&ConfiguredEncoder>, sys: &SystemResources) -> CheckResult {
+    let label = t("preflight.encoder.label", &[]);
+    let Some(encoder) = encoder else {
+        return CheckResult {
+            id: "encoder".into(),
+            label,
+            status: CheckStatus::Skip,
+            detail: t("preflight.encoder.unknown", &[]),
+        };
+    };
+
+    let family = encoder.family();
+    let needs_gpu = matches!(family, "nvidia" | "amd" | "intel");
+    // No adapters listed means we couldn't look, not that there are none.
+    if needs_gpu && !sys.gpus.is_empty() && !sys.gpus.iter().any(|g| g.vendor == family) {
+        let gpus: Vec<&str> = sys.gpus.iter().map(|g| g.model.as_str()).collect();
+        return CheckResult {
+            id: "encoder".into(),
+            label,
+            status: CheckStatus::Fail,
+            detail: t(
+                "preflight.encoder.no_hardware",
+                &[("encoder", &encoder.id), ("gpus", &gpus.join(", "))],
+            ),
+        };
+    }
+
+    if let Some(preset) = encoder.x264_preset.as_deref() {
+        let slow = SLOW_X264_PRESETS.contains(&preset.to_lowercase().as_str());
+        let weak_cpu = sys.physical_cores > 0 && sys.physical_cores < X264_MIN_CORES;
+        if slow && (weak_cpu || sys.cpu_usage_percent > X264_MAX_CPU_PERCENT) {
+            return CheckResult {
+                id: "encoder".into(),
+                label,
+                status: CheckStatus::Warn,
+                detail: t(
+                    "preflight.encoder.slow_x264",
+                    &[
+                        ("preset", &preset),
+                        ("cores", &sys.physical_cores),
+                        ("percent", &format!("{:.0}", sys.cpu_usage_percent)),
+                    ],
+                ),
+            };
         }
     }
+
+    CheckResult {
+        id: "encoder".into(),
+        label,
+        status: CheckStatus::Pass,
+        detail: encoder.id.clone(),
+    }
 }
 
 fn check_record_directory(obs: &ObsState) -> CheckResult {
@@ -290,40 +493,117 @@ fn check_record_directory(obs: &ObsState) -> CheckResult {
     if dir.is_empty() {
         CheckResult {
             id: "record_directory".into(),
-            label: "Record Directory".into(),
+            label: t("preflight.record_directory.label", &[]),
             status: CheckStatus::Warn,
-            detail: "No record directory set".into(),
+            detail: t("preflight.record_directory.none", &[]),
         }
     } else if !std::path::Path::new(dir).exists() {
         CheckResult {
             id: "record_directory".into(),
-            label: "Record Directory".into(),
+            label: t("preflight.record_directory.label", &[]),
             status: CheckStatus::Fail,
-            detail: format!("{} — does not exist", dir),
+            detail: t("preflight.record_directory.missing", &[("path", dir)]),
         }
     } else {
         CheckResult {
             id: "record_directory".into(),
-            label: "Record Directory".into(),
+            label: t("preflight.record_directory.label", &[]),
             status: CheckStatus::Pass,
             detail: dir.clone(),
         }
     }
 }
 
+fn format_duration(seconds: f64) -> String {
+    let minutes = (seconds / 60.0) as u64;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+fn check_record_headroom(headroom: Option<&RecordingHeadroom>) -> CheckResult {
+    let Some(h) = headroom else {
+        return CheckResult {
+            id: "record_headroom".into(),
+            label: t("preflight.record_headroom.label", &[]),
+            status: CheckStatus::Skip,
+            detail: t("preflight.record_headroom.unknown", &[]),
+        };
+    };
+
+    let status = if h.seconds_remaining < 30.0 * 60.0 {
+        CheckStatus::Fail
+    } else if h.seconds_remaining < 2.0 * 3600.0 {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    let key = if h.bitrate_estimated {
+        "preflight.record_headroom.estimated"
+    } else {
+        "preflight.record_headroom.remaining"
+    };
+
+    CheckResult {
+        id: "record_headroom".into(),
+        label: t("preflight.record_headroom.label", &[]),
+        status,
+        detail: t(
+            key,
+            &[
+                ("time", &format_duration(h.seconds_remaining)),
+                ("mbps", &format!("{:.1}", h.bitrate_kbps as f64 / 1000.0)),
+                ("gb", &format!("{:.1}", h.free_bytes as f64 / 1_073_741_824.0)),
+            ],
+        ),
+    }
+}
+
+fn check_missing_plugins(obs: &ObsState) -> CheckResult {
+    let report = &obs.missing_plugins;
+    let (status, detail) = if !report.checked {
+        (CheckStatus::Skip, t("preflight.missing_plugins.not_checked", &[]))
+    } else if report.missing.is_empty() {
+        (CheckStatus::Pass, t("preflight.missing_plugins.ok", &[]))
+    } else {
+        let plugins: Vec<&str> = report
+            .missing
+            .iter()
+            .map(|m| m.plugin_name.as_str())
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        (
+            CheckStatus::Warn,
+            t(
+                "preflight.missing_plugins.broken",
+                &[
+                    ("count", &report.affected_source_count()),
+                    ("plugins", &plugins.join(", ")),
+                ],
+            ),
+        )
+    };
+
+    CheckResult {
+        id: "missing_plugins".into(),
+        label: t("preflight.missing_plugins.label", &[]),
+        status,
+        detail,
+    }
+}
+
 fn check_dropped_frames(obs: &ObsState) -> CheckResult {
     let total = obs.stats.render_skipped_frames + obs.stats.output_skipped_frames;
     let (status, detail) = if total >= 100 {
-        (CheckStatus::Fail, format!("{} total dropped frames", total))
+        (CheckStatus::Fail, t("preflight.dropped_frames.total", &[("count", &total)]))
     } else if total > 0 {
-        (CheckStatus::Warn, format!("{} dropped frames", total))
+        (CheckStatus::Warn, t("preflight.dropped_frames.some", &[("count", &total)]))
     } else {
-        (CheckStatus::Pass, "0 dropped frames".into())
+        (CheckStatus::Pass, t("preflight.dropped_frames.none", &[]))
     };
 
     CheckResult {
         id: "dropped_frames".into(),
-        label: "Dropped Frames".into(),
+        label: t("preflight.dropped_frames.label", &[]),
         status,
         detail,
     }
@@ -1,7 +1,28 @@
 use crate::gemini::AiAction;
+use crate::i18n::t;
+use crate::obs_state::{InputInfo, ObsState};
 use crate::vst_manager;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// User preset ids carry this prefix so they never collide with built-ins.
+const USER_PRESET_PREFIX: &str = "user:";
+
+/// What a user preset may do: shape audio on the mic/desktop inputs.
+const USER_PRESET_REQUESTS: &[&str] = &[
+    "CreateSourceFilter",
+    "SetSourceFilterSettings",
+    "SetSourceFilterEnabled",
+    "SetSourceFilterIndex",
+    "SetInputVolume",
+    "SetInputMute",
+    "SetInputAudioMonitorType",
+    "SetInputAudioBalance",
+    "SetInputAudioSyncOffset",
+    "SetInputAudioTracks",
+];
 
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -32,7 +53,12 @@ pub fn resolve_preset_actions(actions: &[AiAction], mic: &str, desktop: &str) ->
             match vst_manager::get_vst_path(plugin_name) {
                 Some(path) => path.replace('\\', "\\\\"),
                 None => {
-                    vst_error = Some(format!("VST plugin '{}' not installed", plugin_name));
+                    vst_error = Some(match vst_manager::plugin_state(plugin_name) {
+                        vst_manager::PluginState::Corrupt => {
+                            format!("VST plugin '{}' is corrupted, run repair", plugin_name)
+                        }
+                        _ => format!("VST plugin '{}' not installed", plugin_name),
+                    });
                     caps[0].to_string()
                 }
             }
@@ -56,33 +82,401 @@ pub fn resolve_preset_actions(actions: &[AiAction], mic: &str, desktop: &str) ->
     Ok(resolved_actions)
 }
 
+/// What to do when a filter the preset creates already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// Leave existing filters alone; volume and mute steps still run.
+    Skip,
+    /// Remove every filter starting with the preset's prefix first.
+    Replace,
+    /// Refuse to apply the preset at all.
+    #[default]
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanStatus {
+    WouldCreate,
+    WouldRemove,
+    WouldUpdate,
+    Skipped,
+    WouldFail,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedAction {
+    pub description: String,
+    pub safety: String,
+    pub status: PlanStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub action: AiAction,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetPlan {
+    pub preset_id: String,
+    pub mic_source: String,
+    pub desktop_source: String,
+    pub conflict_policy: ConflictPolicy,
+    pub actions: Vec<PlannedAction>,
+    pub can_apply: bool,
+}
+
+impl PresetPlan {
+    /// Every would_fail step, for refusing to apply before anything is sent.
+    pub fn failures(&self) -> Vec<String> {
+        self.actions
+            .iter()
+            .filter(|a| a.status == PlanStatus::WouldFail)
+            .map(|a| format!("{}: {}", a.description, a.detail.as_deref().unwrap_or("would fail")))
+            .collect()
+    }
+}
+
+fn action_source(action: &AiAction) -> &str {
+    action.params["sourceName"]
+        .as_str()
+        .or_else(|| action.params["inputName"].as_str())
+        .unwrap_or("")
+}
+
+/// Checks resolved preset actions against the cached OBS state without
+/// sending anything. Under `Replace` the plan starts with the removals.
+pub fn plan_actions(
+    actions: &[AiAction],
+    state: &ObsState,
+    filter_prefix: &str,
+    policy: ConflictPolicy,
+) -> Vec<PlannedAction> {
+    let source_exists =
+        |name: &str| state.inputs.contains_key(name) || state.scenes.iter().any(|s| s.name == name);
+    let mut plan = Vec::new();
+    // Filters removed up front by `Replace`.
+    let mut removed: Vec<(String, String)> = Vec::new();
+
+    if policy == ConflictPolicy::Replace && !filter_prefix.is_empty() {
+        let mut sources: Vec<&str> = Vec::new();
+        for a in actions {
+            let source = action_source(a);
+            if a.request_type == "CreateSourceFilter" && !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+        for source in sources {
+            let Some(input) = state.inputs.get(source) else { continue };
+            for f in input.filters.iter().filter(|f| f.name.starts_with(filter_prefix)) {
+                removed.push((source.to_string(), f.name.clone()));
+                plan.push(PlannedAction {
+                    description: format!("Remove filter \"{}\" from \"{}\"", f.name, source),
+                    safety: "caution".into(),
+                    status: PlanStatus::WouldRemove,
+                    detail: Some("replaced by the preset".into()),
+                    action: AiAction {
+                        safety: "caution".into(),
+                        description: format!("Remove filter \"{}\" from \"{}\"", f.name, source),
+                        action_type: "obs_request".into(),
+                        request_type: "RemoveSourceFilter".into(),
+                        params: json!({"sourceName": source, "filterName": f.name}),
+                    },
+                });
+            }
+        }
+    }
+
+    let existing_kind = |source: &str, filter: &str| {
+        if removed.iter().any(|(s, f)| s == source && f == filter) {
+            return None;
+        }
+        state
+            .inputs
+            .get(source)
+            .and_then(|i| i.filters.iter().find(|f| f.name == filter))
+            .map(|f| f.kind.clone())
+    };
+    // Filters created by earlier steps, and existing ones `Skip` left alone.
+    let mut created: Vec<(String, String)> = Vec::new();
+    let mut skipped: Vec<(String, String)> = Vec::new();
+
+    for a in actions {
+        let source = action_source(a);
+        let filter = a.params["filterName"].as_str().unwrap_or("");
+        let key = (source.to_string(), filter.to_string());
+        let (status, detail) = if a.action_type != "obs_request" {
+            (PlanStatus::WouldUpdate, None)
+        } else if !source.is_empty() && !source_exists(source) {
+            (PlanStatus::WouldFail, Some(format!("source '{}' not found", source)))
+        } else if a.request_type == "CreateSourceFilter" {
+            let wanted_kind = a.params["filterKind"].as_str().unwrap_or("");
+            match existing_kind(source, filter) {
+                _ if created.contains(&key) => (
+                    PlanStatus::WouldFail,
+                    Some(format!("filter '{}' is created twice on {}", filter, source)),
+                ),
+                None => {
+                    created.push(key);
+                    (PlanStatus::WouldCreate, None)
+                }
+                Some(kind) => {
+                    let conflict = if kind == wanted_kind {
+                        format!("filter '{}' already exists on {}", filter, source)
+                    } else {
+                        format!(
+                            "filter '{}' already exists on {} as {}, not {}",
+                            filter, source, kind, wanted_kind
+                        )
+                    };
+                    if policy == ConflictPolicy::Skip {
+                        skipped.push(key);
+                        (PlanStatus::Skipped, Some(conflict))
+                    } else {
+                        // Replace only clears the preset's own prefix, so
+                        // this is a foreign filter with a clashing name.
+                        (PlanStatus::WouldFail, Some(conflict))
+                    }
+                }
+            }
+        } else if skipped.contains(&key) {
+            (PlanStatus::Skipped, Some(format!("filter '{}' was left as it was", filter)))
+        } else if !filter.is_empty() && existing_kind(source, filter).is_none() && !created.contains(&key) {
+            (PlanStatus::WouldFail, Some(format!("filter '{}' not found on {}", filter, source)))
+        } else {
+            (PlanStatus::WouldUpdate, None)
+        };
+        plan.push(PlannedAction {
+            description: a.description.clone(),
+            safety: a.safety.clone(),
+            status,
+            detail,
+            action: a.clone(),
+        });
+    }
+    plan
+}
+
+/// Built-in presets with names and descriptions resolved for the current
+/// locale, followed by the user's own.
 pub fn get_presets() -> Vec<Preset> {
+    preset_catalog()
+        .into_iter()
+        .map(|mut p| {
+            p.name = t(&p.name, &[]);
+            p.description = t(&p.description, &[]);
+            for a in &mut p.actions {
+                a.description = t(&a.description, &[]);
+            }
+            p
+        })
+        .chain(load_user_presets())
+        .collect()
+}
+
+fn user_presets_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("presets_user.json")
+}
+
+pub fn load_user_presets() -> Vec<Preset> {
+    let Ok(content) = std::fs::read_to_string(user_presets_path()) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&content) {
+        Ok(presets) => presets,
+        Err(e) => {
+            log::warn!("Failed to parse user presets: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Held across every read-modify-write of the user presets file, so two
+/// saves can't each drop the other's preset.
+static USER_PRESETS_LOCK: Mutex<()> = Mutex::new(());
+
+fn write_user_presets(presets: &[Preset]) -> Result<(), String> {
+    let failed = |e: &dyn std::fmt::Display| t("error.preset_save_failed", &[("error", e)]);
+    let path = user_presets_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| failed(&e))?;
+    }
+    let json = serde_json::to_string_pretty(presets).map_err(|e| failed(&e))?;
+    crate::settings::write_atomic(&path, json).map_err(|e| failed(&e))
+}
+
+fn validate_user_preset(preset: &Preset) -> Result<(), String> {
+    if preset.name.trim().is_empty() {
+        return Err(t("error.preset_needs_name", &[]));
+    }
+    // The Signal Chain groups a preset's filters by this prefix.
+    if preset.filter_prefix.trim().is_empty() {
+        return Err(t("error.preset_needs_prefix", &[]));
+    }
+    if preset.actions.is_empty() {
+        return Err(t("error.preset_no_actions", &[]));
+    }
+    for (i, a) in preset.actions.iter().enumerate() {
+        if a.action_type != "obs_request" || !USER_PRESET_REQUESTS.contains(&a.request_type.as_str()) {
+            return Err(t(
+                "error.preset_step_not_allowed",
+                &[
+                    ("step", &(i + 1)),
+                    ("description", &a.description),
+                    ("request", &format!("{} {}", a.action_type, a.request_type)),
+                ],
+            ));
+        }
+        if a.safety == "dangerous" {
+            return Err(t(
+                "error.preset_step_dangerous",
+                &[("step", &(i + 1)), ("description", &a.description)],
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A preset that recreates `input`'s volume and filter chain on "{mic}".
+/// Filters are renamed under `preset_name` so the Signal Chain groups them,
+/// and catalog VST paths become {vst:Name} so the preset travels.
+pub fn preset_from_chain(input: &InputInfo, preset_name: &str) -> Preset {
+    let prefix = preset_name.trim().to_string();
+    let mut actions = vec![AiAction {
+        safety: "safe".into(),
+        description: format!("Set mic volume to {:.1} dB", input.volume_db),
+        action_type: "obs_request".into(),
+        request_type: "SetInputVolume".into(),
+        params: json!({"inputName": "{mic}", "inputVolumeDb": (input.volume_db * 10.0).round() / 10.0}),
+    }];
+
+    for filter in &input.filters {
+        let filter_name = if filter.name.starts_with(&prefix) {
+            filter.name.clone()
+        } else {
+            format!("{} {}", prefix, filter.name)
+        };
+        let mut settings = filter.settings.clone();
+        if filter.kind == "vst_filter" {
+            let placeholder = settings["plugin_path"]
+                .as_str()
+                .and_then(vst_manager::catalog_name_for_path)
+                .map(|name| format!("{{vst:{}}}", name));
+            if let Some(placeholder) = placeholder {
+                settings["plugin_path"] = json!(placeholder);
+            }
+        }
+        actions.push(AiAction {
+            safety: "caution".into(),
+            description: format!("Add {} to mic", filter_name),
+            action_type: "obs_request".into(),
+            request_type: "CreateSourceFilter".into(),
+            params: json!({
+                "sourceName": "{mic}",
+                "filterName": filter_name,
+                "filterKind": filter.kind,
+                "filterSettings": settings,
+            }),
+        });
+        if !filter.enabled {
+            actions.push(AiAction {
+                safety: "caution".into(),
+                description: format!("Disable {}", filter_name),
+                action_type: "obs_request".into(),
+                request_type: "SetSourceFilterEnabled".into(),
+                params: json!({"sourceName": "{mic}", "filterName": filter_name, "filterEnabled": false}),
+            });
+        }
+    }
+
+    Preset {
+        id: String::new(),
+        name: prefix.clone(),
+        description: format!("Saved from \"{}\"", input.name),
+        icon: "🎛️".into(),
+        filter_prefix: prefix,
+        pro: input.filters.iter().any(|f| f.kind == "vst_filter"),
+        actions,
+    }
+}
+
+/// Adds or replaces a user preset. Ids without the user prefix get one, so
+/// saving an edited built-in makes a user copy instead of shadowing it.
+pub fn save_user_preset(mut preset: Preset) -> Result<Preset, String> {
+    validate_user_preset(&preset)?;
+    let base = if preset.id.trim().is_empty() {
+        preset
+            .name
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect()
+    } else {
+        preset.id.trim().to_string()
+    };
+    if !base.starts_with(USER_PRESET_PREFIX) {
+        preset.id = format!("{}{}", USER_PRESET_PREFIX, base);
+    } else {
+        preset.id = base;
+    }
+    preset.pro = preset
+        .actions
+        .iter()
+        .any(|a| a.params["filterKind"].as_str() == Some("vst_filter"));
+
+    let _lock = USER_PRESETS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut presets = load_user_presets();
+    match presets.iter_mut().find(|p| p.id == preset.id) {
+        Some(existing) => *existing = preset.clone(),
+        None => presets.push(preset.clone()),
+    }
+    write_user_presets(&presets)?;
+    Ok(preset)
+}
+
+pub fn delete_user_preset(id: &str) -> Result<(), String> {
+    let _lock = USER_PRESETS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut presets = load_user_presets();
+    let before = presets.len();
+    presets.retain(|p| p.id != id);
+    if presets.len() == before {
+        return Err(t("error.preset_not_found", &[("id", &id)]));
+    }
+    write_user_presets(&presets)
+}
+
+fn preset_catalog() -> Vec<Preset> {
     vec![
         Preset {
             id: "tutorial".into(),
-            name: "Tutorial Recording".into(),
-            description: "Screen recording with voiceover. Mic priority, desktop audio low, noise gate + compressor on mic.".into(),
+            name: "preset.tutorial.name".into(),
+            description: "preset.tutorial.description".into(),
             icon: "🎓".into(),
             filter_prefix: "Tutorial".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to -3dB".into(),
+                    description: "preset.action.set_mic_volume_to_3db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": -3.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set desktop audio to -20dB".into(),
+                    description: "preset.action.set_desktop_audio_to_20db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{desktop}", "inputVolumeDb": -20.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise gate to mic".into(),
+                    description: "preset.action.add_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -100,7 +494,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -120,29 +514,29 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "gaming".into(),
-            name: "Game Streaming".into(),
-            description: "Balanced game + voice mix. Noise suppression on mic, game audio at -10dB.".into(),
+            name: "preset.gaming.name".into(),
+            description: "preset.gaming.description".into(),
             icon: "🎮".into(),
             filter_prefix: "Gaming".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to -5dB".into(),
+                    description: "preset.action.set_mic_volume_to_5db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": -5.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set desktop/game audio to -10dB".into(),
+                    description: "preset.action.set_desktop_game_audio_to_10db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{desktop}", "inputVolumeDb": -10.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise suppression to mic".into(),
+                    description: "preset.action.add_noise_suppression_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -159,29 +553,29 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "podcast".into(),
-            name: "Podcast".into(),
-            description: "Voice-only setup. Mic at 0dB, desktop audio muted, full vocal chain (gate + compressor + limiter).".into(),
+            name: "preset.podcast.name".into(),
+            description: "preset.podcast.description".into(),
             icon: "🎙️".into(),
             filter_prefix: "Podcast".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Mute desktop audio".into(),
+                    description: "preset.action.mute_desktop_audio".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputMute".into(),
                     params: json!({"inputName": "{desktop}", "inputMuted": true}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise gate to mic".into(),
+                    description: "preset.action.add_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -199,7 +593,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -217,7 +611,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -234,29 +628,29 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "music".into(),
-            name: "Music / DJ Stream".into(),
-            description: "Music priority with voice ducking. Music at 0dB, mic at -8dB, limiter on master.".into(),
+            name: "preset.music.name".into(),
+            description: "preset.music.description".into(),
             icon: "🎵".into(),
             filter_prefix: "Music".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set desktop/music audio to 0dB".into(),
+                    description: "preset.action.set_desktop_music_audio_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{desktop}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to -8dB".into(),
+                    description: "preset.action.set_mic_volume_to_8db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": -8.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -273,22 +667,22 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "broadcast".into(),
-            name: "Broadcast Voice".into(),
-            description: "Radio-quality vocal chain. Suppression + gate + compressor (high ratio) + gain + limiter for a polished, consistent sound.".into(),
+            name: "preset.broadcast.name".into(),
+            description: "preset.broadcast.description".into(),
             icon: "📻".into(),
             filter_prefix: "Broadcast".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise suppression to mic".into(),
+                    description: "preset.action.add_noise_suppression_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -302,7 +696,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise gate to mic".into(),
+                    description: "preset.action.add_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -320,7 +714,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -338,7 +732,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gain to mic".into(),
+                    description: "preset.action.add_gain_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -352,7 +746,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -369,29 +763,29 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "asmr".into(),
-            name: "ASMR / Whisper".into(),
-            description: "Preserve quiet detail and intimacy. Light gate (very low threshold), gentle compression, gain boost, soft limiter.".into(),
+            name: "preset.asmr.name".into(),
+            description: "preset.asmr.description".into(),
             icon: "🤫".into(),
             filter_prefix: "ASMR".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Mute desktop audio".into(),
+                    description: "preset.action.mute_desktop_audio".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputMute".into(),
                     params: json!({"inputName": "{desktop}", "inputMuted": true}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gentle noise gate to mic".into(),
+                    description: "preset.action.add_gentle_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -409,7 +803,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add soft compressor to mic".into(),
+                    description: "preset.action.add_soft_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -427,7 +821,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gain boost to mic".into(),
+                    description: "preset.action.add_gain_boost_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -441,7 +835,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -458,22 +852,22 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "noisy-room".into(),
-            name: "Noisy Room".into(),
-            description: "Maximum noise fighting. Aggressive suppression + tight gate + expander + compressor + limiter for loud environments.".into(),
+            name: "preset.noisy-room.name".into(),
+            description: "preset.noisy-room.description".into(),
             icon: "🔇".into(),
             filter_prefix: "Noisy Room".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add aggressive noise suppression to mic".into(),
+                    description: "preset.action.add_aggressive_noise_suppression_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -487,7 +881,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add tight noise gate to mic".into(),
+                    description: "preset.action.add_tight_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -505,7 +899,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add expander to mic".into(),
+                    description: "preset.action.add_expander_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -522,7 +916,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -540,7 +934,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -557,29 +951,29 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "just-chatting".into(),
-            name: "Just Chatting".into(),
-            description: "Balanced IRL/chatting stream. Suppression + compressor on mic, desktop audio at comfortable background level.".into(),
+            name: "preset.just-chatting.name".into(),
+            description: "preset.just-chatting.description".into(),
             icon: "💬".into(),
             filter_prefix: "Just Chatting".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to -3dB".into(),
+                    description: "preset.action.set_mic_volume_to_3db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": -3.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set desktop audio to -14dB".into(),
+                    description: "preset.action.set_desktop_audio_to_14db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{desktop}", "inputVolumeDb": -14.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise suppression to mic".into(),
+                    description: "preset.action.add_noise_suppression_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -593,7 +987,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -611,7 +1005,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -628,29 +1022,29 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "singing".into(),
-            name: "Singing / Karaoke".into(),
-            description: "Preserve vocal dynamics for singing. Light compressor, gain boost, limiter. Desktop at -6dB for backing track.".into(),
+            name: "preset.singing.name".into(),
+            description: "preset.singing.description".into(),
             icon: "🎤".into(),
             filter_prefix: "Singing".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set desktop/backing track to -6dB".into(),
+                    description: "preset.action.set_desktop_backing_track_to_6db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{desktop}", "inputVolumeDb": -6.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add light compressor to mic".into(),
+                    description: "preset.action.add_light_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -668,7 +1062,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gain to mic".into(),
+                    description: "preset.action.add_gain_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -682,7 +1076,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -699,29 +1093,29 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "interview".into(),
-            name: "Interview".into(),
-            description: "Two-person interview. Gate + compressor + limiter on both mic and aux for consistent levels.".into(),
+            name: "preset.interview.name".into(),
+            description: "preset.interview.description".into(),
             icon: "\u{1f399}".into(),
             filter_prefix: "Interview".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set desktop audio to -20dB".into(),
+                    description: "preset.action.set_desktop_audio_to_20db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{desktop}", "inputVolumeDb": -20.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise gate to mic".into(),
+                    description: "preset.action.add_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -739,7 +1133,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -757,7 +1151,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -772,7 +1166,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise gate to aux/guest".into(),
+                    description: "preset.action.add_noise_gate_to_aux_guest".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -790,7 +1184,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to aux/guest".into(),
+                    description: "preset.action.add_compressor_to_aux_guest".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -808,7 +1202,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to aux/guest".into(),
+                    description: "preset.action.add_limiter_to_aux_guest".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -825,29 +1219,29 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "voiceover".into(),
-            name: "Voiceover / Narration".into(),
-            description: "Clean narration voice. Tight gate, 4:1 compressor, gain boost, limiter for broadcast-ready VO.".into(),
+            name: "preset.voiceover.name".into(),
+            description: "preset.voiceover.description".into(),
             icon: "\u{1f3ac}".into(),
             filter_prefix: "Voiceover".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "safe".into(),
-                    description: "Mute desktop audio".into(),
+                    description: "preset.action.mute_desktop_audio".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputMute".into(),
                     params: json!({"inputName": "{desktop}", "inputMuted": true}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add tight noise gate to mic".into(),
+                    description: "preset.action.add_tight_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -865,7 +1259,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -883,7 +1277,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gain boost to mic".into(),
+                    description: "preset.action.add_gain_boost_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -897,7 +1291,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -914,22 +1308,22 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "lofi".into(),
-            name: "Lo-Fi / Retro".into(),
-            description: "Vintage lo-fi character. Gain reduction, heavy 8:1 compression for saturated warmth, gain boost.".into(),
+            name: "preset.lofi.name".into(),
+            description: "preset.lofi.description".into(),
             icon: "\u{1f4fc}".into(),
             filter_prefix: "Lo-Fi".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gain reduction to mic".into(),
+                    description: "preset.action.add_gain_reduction_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -943,7 +1337,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add heavy compressor to mic".into(),
+                    description: "preset.action.add_heavy_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -961,7 +1355,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gain boost to mic".into(),
+                    description: "preset.action.add_gain_boost_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -977,22 +1371,22 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "outdoor".into(),
-            name: "Outdoor / IRL Stream".into(),
-            description: "Wind and noise fighting for outdoor streams. Aggressive suppression, tight gate, compressor, limiter.".into(),
+            name: "preset.outdoor.name".into(),
+            description: "preset.outdoor.description".into(),
             icon: "\u{1f333}".into(),
             filter_prefix: "Outdoor".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to 0dB".into(),
+                    description: "preset.action.set_mic_volume_to_0db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": 0.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add aggressive noise suppression to mic".into(),
+                    description: "preset.action.add_aggressive_noise_suppression_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1006,7 +1400,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add tight noise gate to mic".into(),
+                    description: "preset.action.add_tight_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1024,7 +1418,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1042,7 +1436,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1059,22 +1453,22 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "conference".into(),
-            name: "Conference / Zoom".into(),
-            description: "Clean meeting audio. Moderate suppression, gate, 3:1 compressor, limiter for consistent call volume.".into(),
+            name: "preset.conference.name".into(),
+            description: "preset.conference.description".into(),
             icon: "\u{1f4bc}".into(),
             filter_prefix: "Conference".into(),
             pro: false,
             actions: vec![
                 AiAction {
                     safety: "safe".into(),
-                    description: "Set mic volume to -3dB".into(),
+                    description: "preset.action.set_mic_volume_to_3db".into(),
                     action_type: "obs_request".into(),
                     request_type: "SetInputVolume".into(),
                     params: json!({"inputName": "{mic}", "inputVolumeDb": -3.0}),
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise suppression to mic".into(),
+                    description: "preset.action.add_noise_suppression_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1088,7 +1482,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add noise gate to mic".into(),
+                    description: "preset.action.add_noise_gate_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1106,7 +1500,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add compressor to mic".into(),
+                    description: "preset.action.add_compressor_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1124,7 +1518,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add limiter to mic".into(),
+                    description: "preset.action.add_limiter_to_mic".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1142,15 +1536,15 @@ pub fn get_presets() -> Vec<Preset> {
         // --- Pro Presets (Airwindows VST) ---
         Preset {
             id: "pro-broadcast".into(),
-            name: "Pro Broadcast".into(),
-            description: "Professional broadcast voice. Console emulation, de-essing, smooth compression, brick-wall limiting.".into(),
+            name: "preset.pro-broadcast.name".into(),
+            description: "preset.pro-broadcast.description".into(),
             icon: "📡".into(),
             filter_prefix: "Pro Broadcast".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add console channel strip to mic (VST)".into(),
+                    description: "preset.action.add_console_channel_strip_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1164,7 +1558,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add de-esser to mic (VST)".into(),
+                    description: "preset.action.add_de_esser_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1178,7 +1572,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add smooth compressor to mic (VST)".into(),
+                    description: "preset.action.add_smooth_compressor_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1192,7 +1586,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add brick-wall limiter to mic (VST)".into(),
+                    description: "preset.action.add_brick_wall_limiter_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1208,15 +1602,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "pro-podcast".into(),
-            name: "Pro Podcast".into(),
-            description: "Warm, intimate podcast voice. Console strip, gate/envelope, density compression, brick-wall limiting.".into(),
+            name: "preset.pro-podcast.name".into(),
+            description: "preset.pro-podcast.description".into(),
             icon: "🎧".into(),
             filter_prefix: "Pro Podcast".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add console channel strip to mic (VST)".into(),
+                    description: "preset.action.add_console_channel_strip_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1230,7 +1624,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gate/envelope to mic (VST)".into(),
+                    description: "preset.action.add_gate_envelope_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1244,7 +1638,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add density compression to mic (VST)".into(),
+                    description: "preset.action.add_density_compression_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1258,7 +1652,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add brick-wall limiter to mic (VST)".into(),
+                    description: "preset.action.add_brick_wall_limiter_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1274,15 +1668,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "pro-music".into(),
-            name: "Pro Music".into(),
-            description: "Enhanced vocal/instrument sound. Air EQ, warm drive, smooth compression, vinyl tone, natural reverb.".into(),
+            name: "preset.pro-music.name".into(),
+            description: "preset.pro-music.description".into(),
             icon: "🎶".into(),
             filter_prefix: "Pro Music".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add air EQ to mic (VST)".into(),
+                    description: "preset.action.add_air_eq_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1296,7 +1690,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add warm saturation to mic (VST)".into(),
+                    description: "preset.action.add_warm_saturation_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1310,7 +1704,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add smooth compressor to mic (VST)".into(),
+                    description: "preset.action.add_smooth_compressor_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1324,7 +1718,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add vinyl tone shaping to mic (VST)".into(),
+                    description: "preset.action.add_vinyl_tone_shaping_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1338,7 +1732,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add natural reverb to mic (VST)".into(),
+                    description: "preset.action.add_natural_reverb_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1354,15 +1748,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "streamer-safety".into(),
-            name: "Streamer Safety".into(),
-            description: "Protection chain. De-essing, noise gating, brick-wall limiting — prevents sibilance, noise, and clipping.".into(),
+            name: "preset.streamer-safety.name".into(),
+            description: "preset.streamer-safety.description".into(),
             icon: "🛡️".into(),
             filter_prefix: "Streamer Safety".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add de-esser to mic (VST)".into(),
+                    description: "preset.action.add_de_esser_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1376,7 +1770,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gate/envelope to mic (VST)".into(),
+                    description: "preset.action.add_gate_envelope_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1390,7 +1784,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add brick-wall limiter to mic (VST)".into(),
+                    description: "preset.action.add_brick_wall_limiter_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1406,15 +1800,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "pro-radio".into(),
-            name: "Pro Radio Voice".into(),
-            description: "Punchy radio-style voice. Gate/envelope shaping, console warmth, smooth compression, brick-wall limiting.".into(),
+            name: "preset.pro-radio.name".into(),
+            description: "preset.pro-radio.description".into(),
             icon: "\u{1f4fb}".into(),
             filter_prefix: "Pro Radio".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gate/envelope to mic (VST)".into(),
+                    description: "preset.action.add_gate_envelope_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1428,7 +1822,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add console channel strip to mic (VST)".into(),
+                    description: "preset.action.add_console_channel_strip_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1442,7 +1836,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add smooth compressor to mic (VST)".into(),
+                    description: "preset.action.add_smooth_compressor_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1456,7 +1850,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add brick-wall limiter to mic (VST)".into(),
+                    description: "preset.action.add_brick_wall_limiter_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1472,15 +1866,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "pro-asmr".into(),
-            name: "Pro ASMR Detail".into(),
-            description: "Ultra-detailed ASMR. Gentle gate/envelope, airy high-frequency lift, density for micro-detail.".into(),
+            name: "preset.pro-asmr.name".into(),
+            description: "preset.pro-asmr.description".into(),
             icon: "\u{2728}".into(),
             filter_prefix: "Pro ASMR".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add gate/envelope to mic (VST)".into(),
+                    description: "preset.action.add_gate_envelope_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1494,7 +1888,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add air EQ to mic (VST)".into(),
+                    description: "preset.action.add_air_eq_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1508,7 +1902,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add density compression to mic (VST)".into(),
+                    description: "preset.action.add_density_compression_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1524,15 +1918,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "pro-lofi-warmth".into(),
-            name: "Pro Lo-Fi Warmth".into(),
-            description: "Analog warmth and vinyl character. Tube-style saturation, vinyl tone shaping, natural reverb.".into(),
+            name: "preset.pro-lofi-warmth.name".into(),
+            description: "preset.pro-lofi-warmth.description".into(),
             icon: "\u{1f3b8}".into(),
             filter_prefix: "Pro Lo-Fi".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add warm saturation to mic (VST)".into(),
+                    description: "preset.action.add_warm_saturation_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1546,7 +1940,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add vinyl tone shaping to mic (VST)".into(),
+                    description: "preset.action.add_vinyl_tone_shaping_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1560,7 +1954,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add natural reverb to mic (VST)".into(),
+                    description: "preset.action.add_natural_reverb_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1576,15 +1970,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "pro-channel-strip".into(),
-            name: "Pro Channel Strip".into(),
-            description: "Full channel strip processing. Console saturation, channel strip EQ/compression/gate, tape warmth.".into(),
+            name: "preset.pro-channel-strip.name".into(),
+            description: "preset.pro-channel-strip.description".into(),
             icon: "\u{1f39b}".into(),
             filter_prefix: "Pro Strip".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add console channel saturation to mic (VST)".into(),
+                    description: "preset.action.add_console_channel_saturation_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1598,7 +1992,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add channel strip to mic (VST)".into(),
+                    description: "preset.action.add_channel_strip_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1612,7 +2006,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add tape warmth to mic (VST)".into(),
+                    description: "preset.action.add_tape_warmth_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1628,15 +2022,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "pro-loudness".into(),
-            name: "Pro Loudness Max".into(),
-            description: "Competitive streaming loudness. Acceleration edge taming, NC-17 loudness maximizer, brick-wall limiter.".into(),
+            name: "preset.pro-loudness.name".into(),
+            description: "preset.pro-loudness.description".into(),
             icon: "\u{1f4e2}".into(),
             filter_prefix: "Pro Loud".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add edge taming to mic (VST)".into(),
+                    description: "preset.action.add_edge_taming_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1650,7 +2044,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add loudness maximizer to mic (VST)".into(),
+                    description: "preset.action.add_loudness_maximizer_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1664,7 +2058,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add brick-wall limiter to mic (VST)".into(),
+                    description: "preset.action.add_brick_wall_limiter_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1680,15 +2074,15 @@ pub fn get_presets() -> Vec<Preset> {
         },
         Preset {
             id: "pro-clarity".into(),
-            name: "Pro Vocal Clarity".into(),
-            description: "Crystal-clear vocal presence. Capacitor filter for articulation, de-essing, console warmth, acceleration smoothing.".into(),
+            name: "preset.pro-clarity.name".into(),
+            description: "preset.pro-clarity.description".into(),
             icon: "\u{1f4a0}".into(),
             filter_prefix: "Pro Clarity".into(),
             pro: true,
             actions: vec![
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add capacitor filter to mic (VST)".into(),
+                    description: "preset.action.add_capacitor_filter_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1702,7 +2096,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add de-esser to mic (VST)".into(),
+                    description: "preset.action.add_de_esser_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1716,7 +2110,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add console warmth to mic (VST)".into(),
+                    description: "preset.action.add_console_warmth_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
@@ -1730,7 +2124,7 @@ pub fn get_presets() -> Vec<Preset> {
                 },
                 AiAction {
                     safety: "caution".into(),
-                    description: "Add edge smoothing to mic (VST)".into(),
+                    description: "preset.action.add_edge_smoothing_to_mic_vst".into(),
                     action_type: "obs_request".into(),
                     request_type: "CreateSourceFilter".into(),
                     params: json!({
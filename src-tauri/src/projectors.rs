@@ -0,0 +1,123 @@
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::system_monitor::{self, DisplayInfo};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+/// A monitor as OBS numbers it, named the way Windows does.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorEntry {
+    /// What open_projector's monitor_index takes.
+    pub index: i64,
+    pub obs_name: String,
+    /// e.g. "Display 2 (2560x1440 @ 144Hz, primary)".
+    pub label: String,
+    pub width: u32,
+    pub height: u32,
+    pub x: i64,
+    pub y: i64,
+    pub is_primary: bool,
+    pub refresh_rate: Option<u32>,
+    pub adapter: Option<String>,
+}
+
+/// OBS's monitor list with each entry matched to a system display, by
+/// device name (`\\.\DISPLAY2`) and otherwise by resolution.
+fn merge_monitors(obs_monitors: &[Value], displays: &[DisplayInfo]) -> Vec<MonitorEntry> {
+    let mut unmatched: Vec<&DisplayInfo> = displays.iter().collect();
+    obs_monitors
+        .iter()
+        .map(|m| {
+            let index = m["monitorIndex"].as_i64().unwrap_or(0);
+            let obs_name = m["monitorName"].as_str().unwrap_or("").to_string();
+            let width = m["monitorWidth"].as_u64().unwrap_or(0) as u32;
+            let height = m["monitorHeight"].as_u64().unwrap_or(0) as u32;
+            let x = m["monitorPositionX"].as_i64().unwrap_or(0);
+            let y = m["monitorPositionY"].as_i64().unwrap_or(0);
+
+            let by_name = unmatched
+                .iter()
+                .position(|d| !d.name.is_empty() && obs_name.starts_with(&d.name));
+            let by_size = || {
+                unmatched
+                    .iter()
+                    .position(|d| d.width == width && d.height == height)
+            };
+            let display = by_name.or_else(by_size).map(|i| unmatched.remove(i));
+
+            let number = display
+                .and_then(|d| d.name.trim_start_matches(r"\\.\DISPLAY").parse::<u32>().ok())
+                .unwrap_or(index as u32 + 1);
+            let mut details = format!("{}x{}", width, height);
+            if let Some(d) = display.filter(|d| d.refresh_rate > 1) {
+                details.push_str(&format!(" @ {}Hz", d.refresh_rate));
+            }
+            // OBS puts the primary monitor at the origin
+            let is_primary = display.map(|d| d.is_primary).unwrap_or(x == 0 && y == 0);
+            if is_primary {
+                details.push_str(", primary");
+            }
+
+            MonitorEntry {
+                index,
+                label: format!("Display {} ({})", number, details),
+                obs_name,
+                width,
+                height,
+                x,
+                y,
+                is_primary,
+                refresh_rate: display.map(|d| d.refresh_rate),
+                adapter: display.map(|d| d.adapter.clone()),
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_monitor_list(
+    conn_state: tauri::State<'_, ObsHandle>,
+) -> Result<Vec<MonitorEntry>, String> {
+    let resp = conn_state.send_request("GetMonitorList", None).await?;
+    let displays = tokio::task::spawn_blocking(system_monitor::enumerate_displays)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?;
+    let obs_monitors = resp["monitors"].as_array().cloned().unwrap_or_default();
+    Ok(merge_monitors(&obs_monitors, &displays))
+}
+
+/// Opens a projector. `projector_type` is "program", "preview", "multiview",
+/// or "source" (a scene or input named by `source_or_scene_name`). Without a
+/// monitor index it opens windowed.
+#[tauri::command]
+pub async fn open_projector(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    projector_type: String,
+    monitor_index: Option<i64>,
+    source_or_scene_name: Option<String>,
+) -> Result<(), String> {
+    let monitor_index = monitor_index.unwrap_or(-1);
+    let (request_type, mut data) = match projector_type.as_str() {
+        "program" | "preview" | "multiview" => {
+            if projector_type == "preview" && !obs_state.read().await.studio_mode {
+                return Err("The preview projector needs studio mode on".to_string());
+            }
+            let mix = format!("OBS_WEBSOCKET_VIDEO_MIX_TYPE_{}", projector_type.to_uppercase());
+            ("OpenVideoMixProjector", json!({ "videoMixType": mix }))
+        }
+        "source" | "scene" => {
+            let name = source_or_scene_name
+                .filter(|n| !n.trim().is_empty())
+                .ok_or("A source projector needs a source or scene name")?;
+            ("OpenSourceProjector", json!({ "sourceName": name }))
+        }
+        other => return Err(format!("Unknown projector type: {}", other)),
+    };
+    if monitor_index >= 0 {
+        data["monitorIndex"] = json!(monitor_index);
+    }
+    conn_state.send_request(request_type, Some(data)).await?;
+    Ok(())
+}
@@ -0,0 +1,158 @@
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use serde_json::json;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+
+type Updater = Box<dyn Fn(AppHandle, String, String) -> BoxFuture<'static, Vec<String>> + Send + Sync>;
+
+/// Modules that store input names register an updater here. Renames are
+/// queued and applied one at a time in arrival order, so A→B followed by
+/// B→C always ends up pointing at C.
+#[derive(Default)]
+pub struct RenameCascade {
+    updaters: Vec<(&'static str, Updater)>,
+    queue: Option<mpsc::UnboundedSender<(String, String)>>,
+}
+
+pub type SharedRenameCascade = Arc<Mutex<RenameCascade>>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CascadeUpdate {
+    pub module: String,
+    pub reference: String,
+}
+
+impl RenameCascade {
+    /// `updater` gets (old, new) and returns a short label for each stored
+    /// reference it rewrote.
+    pub fn register<F, Fut>(&mut self, module: &'static str, updater: F)
+    where
+        F: Fn(AppHandle, String, String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Vec<String>> + Send + 'static,
+    {
+        self.updaters
+            .push((module, Box::new(move |app, old, new| Box::pin(updater(app, old, new)))));
+    }
+}
+
+/// Renames not yet applied everywhere, so a crash mid-cascade finishes them
+/// on the next launch.
+fn pending_file_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("rename_cascade.json")
+}
+
+fn load_pending() -> Vec<(String, String)> {
+    let Ok(content) = std::fs::read_to_string(pending_file_path()) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("Discarding unreadable pending input renames: {}", e);
+        Vec::new()
+    })
+}
+
+async fn save_pending(pending: &[(String, String)]) {
+    let pending = pending.to_vec();
+    let result = tokio::task::spawn_blocking(move || {
+        let path = pending_file_path();
+        if pending.is_empty() {
+            return match std::fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(()),
+            };
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string(&pending).map_err(|e| e.to_string())?;
+        crate::settings::write_atomic(&path, json).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())
+    .and_then(|r| r);
+    if let Err(e) = result {
+        log::warn!("Failed to save pending input renames: {}", e);
+    }
+}
+
+async fn apply(app: &AppHandle, updaters: &[(&'static str, Updater)], old_name: String, new_name: String) {
+    let mut updated = Vec::new();
+    for (module, updater) in updaters {
+        for reference in updater(app.clone(), old_name.clone(), new_name.clone()).await {
+            updated.push(CascadeUpdate {
+                module: module.to_string(),
+                reference,
+            });
+        }
+    }
+    if updated.is_empty() {
+        return;
+    }
+    log::info!(
+        "Input rename '{}' -> '{}' updated {} reference(s)",
+        old_name,
+        new_name,
+        updated.len()
+    );
+    let _ = app.emit(
+        "app://input-renamed-cascade",
+        json!({"oldInputName": old_name, "inputName": new_name, "updated": updated}),
+    );
+}
+
+/// Starts the worker, first finishing renames a previous run left pending.
+/// Updaters registered afterwards are never run.
+pub fn start(app: &AppHandle) {
+    let Some(cascade) = app.try_state::<SharedRenameCascade>() else {
+        return;
+    };
+    let (tx, mut rx) = mpsc::unbounded_channel::<(String, String)>();
+    let updaters = {
+        let mut c = cascade.lock().unwrap();
+        c.queue = Some(tx);
+        std::mem::take(&mut c.updaters)
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut pending = tokio::task::spawn_blocking(load_pending).await.unwrap_or_default();
+        loop {
+            if pending.is_empty() {
+                match rx.recv().await {
+                    Some(rename) => pending.push(rename),
+                    None => break,
+                }
+            }
+            while let Ok(rename) = rx.try_recv() {
+                pending.push(rename);
+            }
+            save_pending(&pending).await;
+            let (old_name, new_name) = pending.remove(0);
+            apply(&app, &updaters, old_name, new_name).await;
+            if pending.is_empty() {
+                save_pending(&pending).await;
+            }
+        }
+    });
+}
+
+/// Queues a rename reported by OBS's InputNameChanged, which covers renames
+/// made from this app and from OBS alike.
+pub fn input_renamed(app: &AppHandle, old_name: &str, new_name: &str) {
+    if old_name.is_empty() || new_name.is_empty() || old_name == new_name {
+        return;
+    }
+    let Some(cascade) = app.try_state::<SharedRenameCascade>() else {
+        return;
+    };
+    if let Some(queue) = &cascade.lock().unwrap().queue {
+        let _ = queue.send((old_name.to_string(), new_name.to_string()));
+    }
+}
@@ -1,4 +1,6 @@
 use crate::audio::AudioDevice;
+use crate::device_matching;
+use crate::i18n::t;
 use crate::obs_state::ObsState;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -21,16 +23,44 @@ pub struct RoutingAction {
     pub params: Value,
 }
 
-pub fn analyze(obs: &ObsState, devices: &[AudioDevice]) -> Vec<RoutingRecommendation> {
+/// `monitoring_device_id` is OBS's monitoring device; without it the
+/// feedback check is skipped.
+pub fn analyze(
+    obs: &ObsState,
+    devices: &[AudioDevice],
+    monitoring_device_id: Option<&str>,
+) -> Vec<RoutingRecommendation> {
     let mut recs = Vec::new();
     check_mic_captured(obs, devices, &mut recs);
     check_desktop_audio_captured(obs, devices, &mut recs);
     check_disconnected_devices(obs, devices, &mut recs);
+    if let Some(id) = monitoring_device_id {
+        check_monitor_feedback(obs, devices, id, &mut recs);
+    }
+    check_duplicate_desktop_capture(obs, devices, &mut recs);
     check_monitoring_config(obs, &mut recs);
     check_noise_suppression(obs, &mut recs);
     recs
 }
 
+/// Output-capture inputs that record the OBS monitoring device. Anything
+/// monitored to that device is captured again and fed back into the mix.
+pub fn monitoring_loop_inputs(obs: &ObsState, devices: &[AudioDevice], monitoring_device_id: &str) -> Vec<String> {
+    let Some(monitor) = device_matching::resolve_device("wasapi_output_capture", monitoring_device_id, devices) else {
+        return Vec::new();
+    };
+    obs.inputs
+        .values()
+        .filter(|input| {
+            input.kind.contains("wasapi_output_capture")
+                && device_matching::resolve_input_device(input, devices)
+                    .map(|d| d.id == monitor.id)
+                    .unwrap_or(false)
+        })
+        .map(|input| input.name.clone())
+        .collect()
+}
+
 fn check_mic_captured(
     obs: &ObsState,
     devices: &[AudioDevice],
@@ -43,10 +73,10 @@ fn check_mic_captured(
     };
 
     let mic_captured = obs.inputs.values().any(|input| {
-        if !input.kind.contains("wasapi_input_capture") {
-            return false;
-        }
-        input.device_id == "default" || input.device_id == default_mic.id
+        input.kind.contains("wasapi_input_capture")
+            && device_matching::resolve_input_device(input, devices)
+                .map(|d| d.id == default_mic.id)
+                .unwrap_or(false)
     });
 
     if !mic_captured {
@@ -73,11 +103,8 @@ fn check_mic_captured(
         recs.push(RoutingRecommendation {
             id: "mic_not_captured".to_string(),
             severity: "warning".to_string(),
-            title: "Microphone not captured".to_string(),
-            detail: format!(
-                "Default mic '{}' is not assigned to any OBS audio input",
-                default_mic.name
-            ),
+            title: t("routing.mic_not_captured.title", &[]),
+            detail: t("routing.mic_not_captured.detail", &[("device", &default_mic.name)]),
             action,
         });
     }
@@ -95,10 +122,10 @@ fn check_desktop_audio_captured(
     };
 
     let desktop_captured = obs.inputs.values().any(|input| {
-        if !input.kind.contains("wasapi_output_capture") {
-            return false;
-        }
-        input.device_id == "default" || input.device_id == default_output.id
+        input.kind.contains("wasapi_output_capture")
+            && device_matching::resolve_input_device(input, devices)
+                .map(|d| d.id == default_output.id)
+                .unwrap_or(false)
     });
 
     if !desktop_captured {
@@ -125,11 +152,8 @@ fn check_desktop_audio_captured(
         recs.push(RoutingRecommendation {
             id: "desktop_not_captured".to_string(),
             severity: "warning".to_string(),
-            title: "Desktop audio not captured".to_string(),
-            detail: format!(
-                "Default output '{}' is not assigned to any OBS desktop audio source",
-                default_output.name
-            ),
+            title: t("routing.desktop_not_captured.title", &[]),
+            detail: t("routing.desktop_not_captured.detail", &[("device", &default_output.name)]),
             action,
         });
     }
@@ -140,10 +164,8 @@ fn check_disconnected_devices(
     devices: &[AudioDevice],
     recs: &mut Vec<RoutingRecommendation>,
 ) {
-    let device_ids: Vec<&str> = devices.iter().map(|d| d.id.as_str()).collect();
-
     for input in obs.inputs.values() {
-        if input.device_id.is_empty() || input.device_id == "default" {
+        if device_matching::is_default_alias(&input.device_id) {
             continue;
         }
         if !input.kind.contains("wasapi_input_capture")
@@ -151,12 +173,12 @@ fn check_disconnected_devices(
         {
             continue;
         }
-        if !device_ids.contains(&input.device_id.as_str()) {
+        if device_matching::resolve_input_device(input, devices).is_none() {
             recs.push(RoutingRecommendation {
                 id: format!("disconnected_{}", input.name),
                 severity: "error".to_string(),
-                title: format!("'{}' — device disconnected", input.name),
-                detail: "The assigned audio device is not connected to the system".to_string(),
+                title: t("routing.disconnected.title", &[("input", &input.name)]),
+                detail: t("routing.disconnected.detail", &[]),
                 action: Some(RoutingAction {
                     action_type: "set_device".to_string(),
                     input_name: input.name.clone(),
@@ -167,22 +189,97 @@ fn check_disconnected_devices(
     }
 }
 
+fn is_monitored(monitor_type: &str) -> bool {
+    monitor_type == "OBS_MONITORING_TYPE_MONITOR_ONLY"
+        || monitor_type == "OBS_MONITORING_TYPE_MONITOR_AND_OUTPUT"
+}
+
+/// A monitored mic (or a monitored capture of the monitoring device itself)
+/// while some output capture records the monitoring device: what's
+/// monitored is captured again, and with speakers it howls.
+fn check_monitor_feedback(
+    obs: &ObsState,
+    devices: &[AudioDevice],
+    monitoring_device_id: &str,
+    recs: &mut Vec<RoutingRecommendation>,
+) {
+    let loops = monitoring_loop_inputs(obs, devices, monitoring_device_id);
+    let Some(capture) = loops.first() else {
+        return;
+    };
+    let mut inputs: Vec<_> = obs
+        .inputs
+        .values()
+        .filter(|i| is_monitored(&i.monitor_type))
+        .filter(|i| i.kind.contains("wasapi_input_capture") || loops.contains(&i.name))
+        .collect();
+    inputs.sort_by(|a, b| a.name.cmp(&b.name));
+    for input in inputs {
+        recs.push(RoutingRecommendation {
+            id: format!("monitor_feedback_{}", input.name),
+            severity: "error".to_string(),
+            title: t("routing.monitor_feedback.title", &[("input", &input.name)]),
+            detail: t("routing.monitor_feedback.detail", &[("capture", capture)]),
+            action: Some(RoutingAction {
+                action_type: "set_monitor_type".to_string(),
+                input_name: input.name.clone(),
+                params: json!({"monitorType": "OBS_MONITORING_TYPE_NONE"}),
+            }),
+        });
+    }
+}
+
+/// Unmuted output captures sharing a device put desktop audio in the mix
+/// twice. The first by name is kept.
+fn check_duplicate_desktop_capture(
+    obs: &ObsState,
+    devices: &[AudioDevice],
+    recs: &mut Vec<RoutingRecommendation>,
+) {
+    let mut captures: Vec<(&str, String)> = obs
+        .inputs
+        .values()
+        .filter(|i| i.kind.contains("wasapi_output_capture") && !i.muted)
+        .filter_map(|i| {
+            device_matching::resolve_input_device(i, devices).map(|d| (i.name.as_str(), d.id.clone()))
+        })
+        .collect();
+    captures.sort();
+    for (idx, (name, device_id)) in captures.iter().enumerate() {
+        let Some((kept, _)) = captures[..idx].iter().find(|(_, id)| id == device_id) else {
+            continue;
+        };
+        recs.push(RoutingRecommendation {
+            id: format!("duplicate_desktop_{}", name),
+            severity: "warning".to_string(),
+            title: t("routing.duplicate_desktop.title", &[("input", name)]),
+            detail: t("routing.duplicate_desktop.detail", &[("kept", kept)]),
+            action: Some(RoutingAction {
+                action_type: "mute_input".to_string(),
+                input_name: name.to_string(),
+                params: json!({}),
+            }),
+        });
+    }
+}
+
 fn check_monitoring_config(obs: &ObsState, recs: &mut Vec<RoutingRecommendation>) {
     // Warn if mic/input sources have monitoring enabled (causes "I hear myself" feedback)
     for input in obs.inputs.values() {
         if !input.kind.contains("wasapi_input_capture") {
             continue;
         }
-        if input.monitor_type == "OBS_MONITORING_TYPE_MONITOR_ONLY"
-            || input.monitor_type == "OBS_MONITORING_TYPE_MONITOR_AND_OUTPUT"
-        {
+        // Already flagged, more urgently, as a feedback loop.
+        let feedback_id = format!("monitor_feedback_{}", input.name);
+        if recs.iter().any(|r| r.id == feedback_id) {
+            continue;
+        }
+        if is_monitored(&input.monitor_type) {
             recs.push(RoutingRecommendation {
                 id: format!("mic_monitoring_{}", input.name),
                 severity: "warning".to_string(),
-                title: format!("'{}' has monitoring enabled", input.name),
-                detail: "Monitoring a microphone input causes you to hear yourself with delay. \
-                         This should almost always be set to Monitor Off."
-                    .to_string(),
+                title: t("routing.mic_monitoring.title", &[("input", &input.name)]),
+                detail: t("routing.mic_monitoring.detail", &[]),
                 action: Some(RoutingAction {
                     action_type: "set_monitor_type".to_string(),
                     input_name: input.name.clone(),
@@ -195,10 +292,7 @@ fn check_monitoring_config(obs: &ObsState, recs: &mut Vec<RoutingRecommendation>
     let monitored: Vec<&str> = obs
         .inputs
         .values()
-        .filter(|i| {
-            i.monitor_type == "OBS_MONITORING_TYPE_MONITOR_ONLY"
-                || i.monitor_type == "OBS_MONITORING_TYPE_MONITOR_AND_OUTPUT"
-        })
+        .filter(|i| is_monitored(&i.monitor_type))
         .map(|i| i.name.as_str())
         .collect();
 
@@ -206,9 +300,8 @@ fn check_monitoring_config(obs: &ObsState, recs: &mut Vec<RoutingRecommendation>
         recs.push(RoutingRecommendation {
             id: "no_monitoring".to_string(),
             severity: "info".to_string(),
-            title: "No audio monitoring enabled".to_string(),
-            detail: "Consider enabling monitoring on desktop audio to hear it through headphones"
-                .to_string(),
+            title: t("routing.no_monitoring.title", &[]),
+            detail: t("routing.no_monitoring.detail", &[]),
             action: None,
         });
     }
@@ -232,8 +325,8 @@ fn check_noise_suppression(obs: &ObsState, recs: &mut Vec<RoutingRecommendation>
         recs.push(RoutingRecommendation {
             id: format!("no_noise_suppress_{}", name),
             severity: "info".to_string(),
-            title: format!("'{}' has no noise suppression", name),
-            detail: "Adding a noise suppression filter can improve audio quality".to_string(),
+            title: t("routing.no_noise_suppression.title", &[("input", &name)]),
+            detail: t("routing.no_noise_suppression.detail", &[]),
             action: Some(RoutingAction {
                 action_type: "add_filter".to_string(),
                 input_name: name.to_string(),
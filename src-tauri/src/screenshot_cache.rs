@@ -0,0 +1,271 @@
+use crate::obs_health::SharedObsHealth;
+use crate::obs_state::{ObsState, SharedObsState};
+use crate::obs_websocket::{BatchExecution, ObsHandle};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+pub type SharedScreenshotCache = Arc<Mutex<ScreenshotCache>>;
+
+const MAX_CACHED_SHOTS: usize = 64;
+/// Base64 length; a larger thumbnail is returned but not kept.
+const MAX_IMAGE_BYTES: usize = 256 * 1024;
+const MAX_DIMENSION: u32 = 960;
+const DEFAULT_MAX_AGE_MS: u64 = 5000;
+const JPEG_QUALITY: u32 = 25;
+
+struct CachedShot {
+    data: String,
+    taken_at: Instant,
+}
+
+/// Scene thumbnails by scene name and size, so callers asking for different
+/// sizes don't evict each other. Entries are dropped when something in the
+/// scene changes, and the oldest go first once the cache is full.
+#[derive(Default)]
+pub struct ScreenshotCache {
+    entries: HashMap<(String, u32, u32), CachedShot>,
+}
+
+fn key(scene: &str, width: u32, height: u32) -> (String, u32, u32) {
+    (scene.to_string(), width, height)
+}
+
+impl ScreenshotCache {
+    fn get(&self, scene: &str, width: u32, height: u32) -> Option<&CachedShot> {
+        self.entries.get(&key(scene, width, height))
+    }
+
+    fn fresh(&self, scene: &str, width: u32, height: u32, max_age_ms: u64) -> Option<&CachedShot> {
+        self.get(scene, width, height)
+            .filter(|shot| shot.taken_at.elapsed().as_millis() as u64 <= max_age_ms)
+    }
+
+    fn insert(&mut self, scene: &str, data: &str, width: u32, height: u32) {
+        let key = key(scene, width, height);
+        if data.is_empty() || data.len() > MAX_IMAGE_BYTES {
+            self.entries.remove(&key);
+            return;
+        }
+        if !self.entries.contains_key(&key) && self.entries.len() >= MAX_CACHED_SHOTS {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, shot)| shot.taken_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(
+            key,
+            CachedShot {
+                data: data.to_string(),
+                taken_at: Instant::now(),
+            },
+        );
+    }
+
+    fn remove_scenes(&mut self, scenes: &HashSet<String>) {
+        self.entries.retain(|(scene, _, _), _| !scenes.contains(scene));
+    }
+}
+
+/// `scene` plus every scene that shows it, directly or through other
+/// nested scenes.
+fn with_parents(state: &ObsState, scene: &str) -> HashSet<String> {
+    let mut affected = HashSet::from([scene.to_string()]);
+    let mut queue = vec![scene.to_string()];
+    while let Some(child) = queue.pop() {
+        for (parent, items) in &state.scene_items {
+            if !affected.contains(parent) && items.iter().any(|i| i.source_name == child) {
+                affected.insert(parent.clone());
+                queue.push(parent.clone());
+            }
+        }
+    }
+    affected
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneScreenshot {
+    pub scene_name: String,
+    pub image_data: String,
+    /// True when served from the cache rather than taken just now.
+    pub cached: bool,
+    pub age_ms: u64,
+    /// Why a fresh screenshot couldn't be taken; any older one is still returned.
+    pub error: Option<String>,
+}
+
+/// Drops a scene's thumbnails after something in it changed, along with
+/// those of the scenes it's nested in.
+pub async fn invalidate(app: &AppHandle, scene_name: &str) {
+    let (Some(cache), Some(obs_state)) = (
+        app.try_state::<SharedScreenshotCache>(),
+        app.try_state::<SharedObsState>(),
+    ) else {
+        return;
+    };
+    let affected = with_parents(&*obs_state.read().await, scene_name);
+    cache.lock().unwrap().remove_scenes(&affected);
+}
+
+pub fn clear(app: &AppHandle) {
+    if let Some(cache) = app.try_state::<SharedScreenshotCache>() {
+        cache.lock().unwrap().entries.clear();
+    }
+}
+
+/// Thumbnails of every scene. Ones older than `max_age_ms` (5s by default)
+/// are retaken together in one batch; the rest come from the cache.
+#[tauri::command]
+pub async fn get_all_scene_screenshots(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    health: tauri::State<'_, SharedObsHealth>,
+    cache: tauri::State<'_, SharedScreenshotCache>,
+    width: u32,
+    height: u32,
+    max_age_ms: Option<u64>,
+) -> Result<Vec<SceneScreenshot>, String> {
+    let width = width.clamp(1, MAX_DIMENSION);
+    let height = height.clamp(1, MAX_DIMENSION);
+    let max_age_ms = max_age_ms.unwrap_or(DEFAULT_MAX_AGE_MS);
+    let scenes: Vec<String> = obs_state
+        .read()
+        .await
+        .scenes
+        .iter()
+        .map(|s| s.name.clone())
+        .collect();
+
+    let stale: Vec<String> = {
+        let cache = cache.lock().unwrap();
+        scenes
+            .iter()
+            .filter(|name| cache.fresh(name, width, height, max_age_ms).is_none())
+            .cloned()
+            .collect()
+    };
+
+    let degraded = health.lock().map(|h| h.is_degraded()).unwrap_or(false);
+    let mut taken: HashMap<String, String> = HashMap::new();
+    let mut errors: HashMap<String, String> = HashMap::new();
+    if !stale.is_empty() && degraded {
+        for name in &stale {
+            errors.insert(name.clone(), "OBS is not responding; preview paused".to_string());
+        }
+    } else if !stale.is_empty() {
+        let requests = stale
+            .iter()
+            .map(|name| {
+                (
+                    "GetSourceScreenshot".to_string(),
+                    Some(json!({
+                        "sourceName": name,
+                        "imageFormat": "jpg",
+                        "imageWidth": width,
+                        "imageHeight": height,
+                        "imageCompressionQuality": JPEG_QUALITY
+                    })),
+                )
+            })
+            .collect();
+        let outcomes = conn_state
+            .send_batch(requests, BatchExecution::SerialRealtime, false)
+            .await
+            .unwrap_or_else(|e| stale.iter().map(|_| Err(e.clone())).collect());
+        let mut cache = cache.lock().unwrap();
+        for (name, outcome) in stale.iter().zip(outcomes) {
+            match outcome {
+                Ok(resp) => {
+                    let data = resp["imageData"].as_str().unwrap_or("").to_string();
+                    cache.insert(name, &data, width, height);
+                    taken.insert(name.clone(), data);
+                }
+                Err(e) => {
+                    errors.insert(name.clone(), e);
+                }
+            }
+        }
+    }
+
+    let cache = cache.lock().unwrap();
+    Ok(scenes
+        .into_iter()
+        .map(|name| {
+            if let Some(data) = taken.remove(&name) {
+                return SceneScreenshot {
+                    scene_name: name,
+                    image_data: data,
+                    cached: false,
+                    age_ms: 0,
+                    error: None,
+                };
+            }
+            let error = errors.remove(&name);
+            match cache.get(&name, width, height) {
+                Some(shot) => SceneScreenshot {
+                    image_data: shot.data.clone(),
+                    cached: true,
+                    age_ms: shot.taken_at.elapsed().as_millis() as u64,
+                    scene_name: name,
+                    error,
+                },
+                _ => SceneScreenshot {
+                    scene_name: name,
+                    image_data: String::new(),
+                    cached: false,
+                    age_ms: 0,
+                    error,
+                },
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obs_state::SceneItemInfo;
+
+    fn item(source_name: &str) -> SceneItemInfo {
+        SceneItemInfo {
+            source_name: source_name.to_string(),
+            source_kind: "OBS_SOURCE_TYPE_SCENE".to_string(),
+            scene_item_id: 1,
+            enabled: true,
+            transform: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn sizes_are_cached_separately() {
+        let mut cache = ScreenshotCache::default();
+        cache.insert("Main", "small", 160, 90);
+        cache.insert("Main", "large", 640, 360);
+        assert_eq!(cache.get("Main", 160, 90).unwrap().data, "small");
+        assert_eq!(cache.get("Main", 640, 360).unwrap().data, "large");
+
+        cache.remove_scenes(&HashSet::from(["Main".to_string()]));
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn nested_scene_changes_reach_every_parent() {
+        let mut state = ObsState::default();
+        state.scene_items.insert("Main".into(), vec![item("Overlay")]);
+        state.scene_items.insert("Overlay".into(), vec![item("Lower Third")]);
+        state.scene_items.insert("Lower Third".into(), vec![item("Webcam")]);
+        state.scene_items.insert("Other".into(), vec![item("Webcam")]);
+
+        let affected = with_parents(&state, "Lower Third");
+        let expected: HashSet<String> = ["Lower Third", "Overlay", "Main"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(affected, expected);
+    }
+}
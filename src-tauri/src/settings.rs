@@ -0,0 +1,655 @@
+use crate::action_validation::OutOfRange;
+use crate::alerts::AlertSettings;
+use crate::app_capture::AutoCaptureRule;
+use crate::audio_alerts::AudioAlertSettings;
+use crate::audio_monitor::SharedMetricsNotify;
+use crate::chat_provider::{self, AiProviderKind};
+use crate::ducking::{DuckingConfig, SharedDuckingConfig};
+use crate::gemini::{AiPreferences, SharedGeminiClient};
+use crate::input_prefs::InputPreference;
+use crate::obs_hotkeys::ObsHotkeyBinding;
+use crate::preflight::SpeedTestResult;
+use crate::volume_guard::VolumeGuard;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::RwLock;
+
+pub type SharedSettings = Arc<RwLock<AppSettings>>;
+
+pub const SETTINGS_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    pub schema_version: u64,
+    pub locale: String,
+    pub connection: ConnectionSettings,
+    pub ducking: DuckingConfig,
+    pub hotkeys: HotkeySettings,
+    pub video_editor: VideoEditorSettings,
+    pub recording: RecordingSettings,
+    pub volume_guards: HashMap<String, VolumeGuard>,
+    pub input_preferences: HashMap<String, InputPreference>,
+    pub control_server: ControlServerSettings,
+    pub alerts: AlertSettings,
+    pub audio_alerts: AudioAlertSettings,
+    pub ai: AiSettings,
+    pub startup: StartupSettings,
+    pub backups: BackupSettings,
+    pub audio_history: AudioHistorySettings,
+    /// Free-form tags per scene name, e.g. "gaming".
+    pub scene_tags: HashMap<String, Vec<String>>,
+    pub app_capture: AppCaptureSettings,
+    pub panic: PanicSettings,
+    pub preflight: PreflightSettings,
+    pub voice: VoiceSettings,
+    pub vst: VstSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConnectionSettings {
+    pub host: String,
+    pub port: u16,
+    pub auto_connect: bool,
+    /// Retry with backoff when the link to OBS drops.
+    pub auto_reconnect: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HotkeySettings {
+    pub push_to_talk: String,
+    /// Toggles panic mode; empty disables the shortcut.
+    pub panic: String,
+    /// The remaining action shortcuts; empty means unbound.
+    pub toggle_stream: String,
+    pub toggle_record: String,
+    pub toggle_mic_mute: String,
+    pub save_replay: String,
+    pub obs_bindings: Vec<ObsHotkeyBinding>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VideoEditorSettings {
+    pub ffmpeg_path: Option<String>,
+    /// Most ffmpeg processes the recordings browser runs at once for thumbnails.
+    pub thumbnail_concurrency: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordingSettings {
+    pub auto_remux_on_record_stop: bool,
+    pub remux_destination: Option<String>,
+    pub delete_original_after_remux: bool,
+    /// Warn while recording once the drive has less than this many minutes
+    /// left at the estimated bitrate. 0 disables the warning.
+    pub low_disk_warning_minutes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ControlServerSettings {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AiSettings {
+    pub provider: AiProviderKind,
+    /// OpenAI-compatible providers only; empty means api.openai.com.
+    pub base_url: String,
+    pub model: String,
+    pub streaming: bool,
+    pub function_calling: bool,
+    /// Chat messages kept as context; trimmed a question/answer pair at a time.
+    pub history_limit: usize,
+    /// Saves each launch's chat to disk so it can be shown after a restart.
+    pub persist_history: bool,
+    /// How many saved chat sessions to keep.
+    pub history_sessions: usize,
+    pub preferences: AiPreferences,
+    /// Overrides whether an out-of-range action parameter is clamped or
+    /// rejected, keyed "RequestType.field" (e.g. "SetInputVolume.inputVolumeDb").
+    pub out_of_range: HashMap<String, OutOfRange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StartupSettings {
+    pub launch_obs: bool,
+    pub minimize_obs: bool,
+    pub start_minimized: bool,
+    /// What closing the main window does: hide to tray, quit, or (unset) ask.
+    pub close_to_tray: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupSettings {
+    /// Snapshot the OBS config before we rewrite scene collections or
+    /// profile audio settings ourselves.
+    pub auto_backup: bool,
+    pub keep_auto_backups: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioHistorySettings {
+    /// How many hours of per-second peak/RMS history to keep per device.
+    pub hours: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppCaptureSettings {
+    /// Add the foreground app's audio when switching to a "gaming" scene.
+    pub auto_add: bool,
+    /// Also auto-add while neither streaming nor recording.
+    pub also_when_idle: bool,
+    /// Process names (e.g. "game.exe"). A non-empty allow list limits
+    /// auto-add to those processes and overrides the deny list.
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+    /// Apps captured whenever they start playing audio while OBS is connected.
+    pub watch: Vec<AutoCaptureRule>,
+    /// Remove a watched app's input again when the app goes away.
+    pub remove_on_exit: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PanicSettings {
+    /// Scene to cut to while panic mode is engaged; empty leaves the scene alone.
+    pub privacy_scene: String,
+    pub pause_recording: bool,
+    pub mute_desktop: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PreflightSettings {
+    /// Accepts a POST body and discards it; used to measure upload speed.
+    pub speed_test_url: String,
+    pub speed_test_megabytes: u32,
+    pub last_speed_test: Option<SpeedTestResult>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VoiceSettings {
+    /// Push-to-talk microphone; empty uses the Windows default.
+    pub device_id: String,
+    /// whisper.cpp CLI to transcribe locally; empty sends clips to Gemini.
+    pub whisper_path: String,
+    /// ggml model file passed to whisper.cpp with -m.
+    pub whisper_model: String,
+    /// Spoken language hint, e.g. "en"; empty lets the backend detect it.
+    pub language: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VstSettings {
+    /// Scanned for VST2 plugins alongside the standard folders.
+    pub search_paths: Vec<String>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            schema_version: SETTINGS_VERSION,
+            locale: String::new(),
+            connection: ConnectionSettings::default(),
+            ducking: DuckingConfig::default(),
+            hotkeys: HotkeySettings::default(),
+            video_editor: VideoEditorSettings::default(),
+            recording: RecordingSettings::default(),
+            volume_guards: HashMap::new(),
+            input_preferences: HashMap::new(),
+            control_server: ControlServerSettings::default(),
+            alerts: AlertSettings::default(),
+            audio_alerts: AudioAlertSettings::default(),
+            ai: AiSettings::default(),
+            startup: StartupSettings::default(),
+            backups: BackupSettings::default(),
+            audio_history: AudioHistorySettings::default(),
+            scene_tags: HashMap::new(),
+            app_capture: AppCaptureSettings::default(),
+            panic: PanicSettings::default(),
+            preflight: PreflightSettings::default(),
+            voice: VoiceSettings::default(),
+            vst: VstSettings::default(),
+        }
+    }
+}
+
+impl Default for ConnectionSettings {
+    fn default() -> Self {
+        Self {
+            host: "localhost".into(),
+            port: 4455,
+            auto_connect: false,
+            auto_reconnect: true,
+        }
+    }
+}
+
+impl Default for HotkeySettings {
+    fn default() -> Self {
+        Self {
+            push_to_talk: "Ctrl+Space".into(),
+            panic: String::new(),
+            toggle_stream: String::new(),
+            toggle_record: String::new(),
+            toggle_mic_mute: String::new(),
+            save_replay: String::new(),
+            obs_bindings: Vec::new(),
+        }
+    }
+}
+
+impl Default for VideoEditorSettings {
+    fn default() -> Self {
+        Self {
+            ffmpeg_path: None,
+            thumbnail_concurrency: 3,
+        }
+    }
+}
+
+impl Default for RecordingSettings {
+    fn default() -> Self {
+        Self {
+            auto_remux_on_record_stop: false,
+            remux_destination: None,
+            delete_original_after_remux: false,
+            low_disk_warning_minutes: 20,
+        }
+    }
+}
+
+impl Default for PanicSettings {
+    fn default() -> Self {
+        Self {
+            privacy_scene: String::new(),
+            pause_recording: true,
+            mute_desktop: true,
+        }
+    }
+}
+
+impl Default for PreflightSettings {
+    fn default() -> Self {
+        Self {
+            speed_test_url: "https://speed.cloudflare.com/__up".to_string(),
+            speed_test_megabytes: 10,
+            last_speed_test: None,
+        }
+    }
+}
+
+impl Default for AppCaptureSettings {
+    fn default() -> Self {
+        // Browsers and voice chat carry audio that should not silently land
+        // on stream.
+        let deny = [
+            "chrome.exe", "msedge.exe", "firefox.exe", "opera.exe", "brave.exe", "vivaldi.exe",
+            "discord.exe", "teams.exe", "ms-teams.exe", "slack.exe", "zoom.exe", "skype.exe",
+            "ts3client_win64.exe", "teamspeak.exe", "mumble.exe", "obs64.exe",
+        ];
+        Self {
+            auto_add: false,
+            also_when_idle: false,
+            allow: Vec::new(),
+            deny: deny.iter().map(|s| s.to_string()).collect(),
+            watch: Vec::new(),
+            remove_on_exit: false,
+        }
+    }
+}
+
+impl Default for AudioHistorySettings {
+    fn default() -> Self {
+        Self { hours: 4 }
+    }
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            auto_backup: false,
+            keep_auto_backups: 10,
+        }
+    }
+}
+
+impl Default for ControlServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 4456,
+            token: String::new(),
+        }
+    }
+}
+
+impl Default for AiSettings {
+    fn default() -> Self {
+        Self {
+            provider: AiProviderKind::Gemini,
+            base_url: String::new(),
+            model: "gemini-2.5-flash".into(),
+            streaming: false,
+            function_calling: true,
+            history_limit: chat_provider::DEFAULT_HISTORY_LIMIT,
+            persist_history: false,
+            history_sessions: 10,
+            preferences: AiPreferences::default(),
+            out_of_range: HashMap::new(),
+        }
+    }
+}
+
+fn settings_file_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("settings.json")
+}
+
+pub fn load_settings_from_disk() -> AppSettings {
+    let path = settings_file_path();
+    let content = match std::fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(_) => return AppSettings::default(),
+    };
+
+    let raw: Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            log::warn!("Failed to parse settings file: {}", e);
+            return AppSettings::default();
+        }
+    };
+
+    let from_version = raw["schemaVersion"].as_u64().unwrap_or(0);
+    if from_version == 0 {
+        crate::store::import_legacy_keys(&raw);
+    }
+    let settings = match migrate(raw).and_then(|v| {
+        serde_json::from_value::<AppSettings>(v).map_err(|e| format!("Invalid settings: {}", e))
+    }) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Failed to load settings: {}", e);
+            return AppSettings::default();
+        }
+    };
+
+    if from_version < SETTINGS_VERSION {
+        log::info!("Migrated settings from v{} to v{}", from_version, SETTINGS_VERSION);
+        if let Err(e) = save_settings_to_disk(&settings) {
+            log::warn!("Failed to save migrated settings: {}", e);
+        }
+    }
+
+    settings
+}
+
+pub fn save_settings_to_disk(settings: &AppSettings) -> Result<(), String> {
+    let path = settings_file_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json).map_err(|e| format!("Failed to write settings: {}", e))?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        format!("Failed to save settings: {}", e)
+    })
+}
+
+/// Writes through a temp file next to `path`, so a crash mid-write leaves
+/// the old file intact.
+pub(crate) fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp_path);
+    })
+}
+
+pub fn migrate(mut raw: Value) -> Result<Value, String> {
+    if !raw.is_object() {
+        return Err("Settings file is not a JSON object".into());
+    }
+    let mut version = raw["schemaVersion"].as_u64().unwrap_or(0);
+    if version > SETTINGS_VERSION {
+        return Err(format!(
+            "Settings schema v{} is newer than supported v{}",
+            version, SETTINGS_VERSION
+        ));
+    }
+
+    while version < SETTINGS_VERSION {
+        raw = match version {
+            0 => migrate_v0_to_v1(raw),
+            _ => unreachable!(),
+        };
+        version += 1;
+        raw["schemaVersion"] = json!(version);
+    }
+
+    Ok(raw)
+}
+
+// v0 files predate the settings store and only carried the license, in
+// either the multi-key or the older single-key layout. load_settings_from_disk
+// has already copied the keys into license.json, which now owns them.
+fn migrate_v0_to_v1(mut raw: Value) -> Value {
+    if let Some(obj) = raw.as_object_mut() {
+        for legacy in [
+            "keys",
+            "key",
+            "email",
+            "modules",
+            "activated_at",
+            "activatedAt",
+            "owned_modules",
+            "ownedModules",
+        ] {
+            obj.remove(legacy);
+        }
+    }
+    raw
+}
+
+pub fn merge_json(target: &mut Value, patch: &Value) {
+    match (target, patch) {
+        (Value::Object(t), Value::Object(p)) => {
+            for (k, v) in p {
+                if v.is_null() {
+                    t.remove(k);
+                } else {
+                    merge_json(t.entry(k.clone()).or_insert(Value::Null), v);
+                }
+            }
+        }
+        (t, p) => *t = p.clone(),
+    }
+}
+
+pub async fn update_section<F>(settings: &SharedSettings, f: F) -> Result<(), String>
+where
+    F: FnOnce(&mut AppSettings),
+{
+    let snapshot = {
+        let mut s = settings.write().await;
+        f(&mut s);
+        s.clone()
+    };
+    tokio::task::spawn_blocking(move || save_settings_to_disk(&snapshot))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Writes the current settings from a background task, for callers that must
+/// not wait on the disk, such as the OBS event handler. Saves run one at a
+/// time and each snapshots the settings when it starts, so the file always
+/// ends up at the latest state.
+pub fn save_in_background(settings: &SharedSettings) {
+    let settings = settings.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = save_latest(&settings).await {
+            log::warn!("Failed to save settings: {}", e);
+        }
+    });
+}
+
+async fn save_latest(settings: &SharedSettings) -> Result<(), String> {
+    static SAVING: OnceLock<tokio::sync::Mutex<()>> = OnceLock::new();
+    let _saving = SAVING.get_or_init(Default::default).lock().await;
+    let snapshot = settings.read().await.clone();
+    tokio::task::spawn_blocking(move || save_settings_to_disk(&snapshot))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn get_settings(
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<AppSettings, String> {
+    Ok(settings.read().await.clone())
+}
+
+#[tauri::command]
+pub async fn update_settings(
+    settings: tauri::State<'_, SharedSettings>,
+    ducking: tauri::State<'_, SharedDuckingConfig>,
+    metrics_notify: tauri::State<'_, SharedMetricsNotify>,
+    gemini: tauri::State<'_, SharedGeminiClient>,
+    app_handle: tauri::AppHandle,
+    partial: Value,
+) -> Result<AppSettings, String> {
+    // Merged under the write lock, so concurrent updates and update_section
+    // calls can't be lost between the read and the write.
+    let (current, updated, control_server) = {
+        let mut s = settings.write().await;
+        let mut merged = serde_json::to_value(&*s)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        merge_json(&mut merged, &partial);
+        merged["schemaVersion"] = json!(SETTINGS_VERSION);
+        let mut updated: AppSettings =
+            serde_json::from_value(merged).map_err(|e| format!("Invalid settings: {}", e))?;
+        if partial.get("locale").is_some() && !crate::i18n::is_available(&updated.locale) {
+            return Err(format!("Locale '{}' is not available", updated.locale));
+        }
+        // The server saves its own section once the new port has bound.
+        let control_server = partial
+            .get("controlServer")
+            .map(|_| std::mem::replace(&mut updated.control_server, s.control_server.clone()));
+        let current = std::mem::replace(&mut *s, updated.clone());
+        (current, updated, control_server)
+    };
+    save_latest(&settings).await?;
+
+    if partial.get("ducking").is_some() {
+        *ducking.write().await = updated.ducking.clone();
+        metrics_notify.wake();
+    }
+    if partial.get("locale").is_some() {
+        crate::i18n::apply_locale(&updated.locale);
+    }
+    if partial.get("hotkeys").is_some() {
+        crate::shortcuts::rebind_all(&app_handle, &updated.hotkeys);
+    }
+    if partial.get("ai").is_some() {
+        let mut client = gemini.write().await;
+        if updated.ai.provider != current.ai.provider || updated.ai.base_url != current.ai.base_url {
+            let ai = updated.ai.clone();
+            *client = tokio::task::spawn_blocking(move || chat_provider::from_settings(&ai))
+                .await
+                .map_err(|e| format!("Task failed: {}", e))?;
+        } else if let Some(client) = client.as_mut() {
+            chat_provider::configure(client.as_mut(), &updated.ai);
+        }
+    }
+
+    if let Some(config) = control_server {
+        crate::control_server::apply(&app_handle, config).await?;
+        return Ok(settings.read().await.clone());
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrates_a_v0_license_only_file() {
+        let v0 = json!({
+            "keys": [{"key": "abc.def", "email": "a@b.c", "modules": ["spectrum"], "activated_at": 1700000000}],
+            "key": "old.key",
+            "email": "a@b.c",
+            "modules": ["spectrum"],
+            "activated_at": 1690000000
+        });
+        let migrated = migrate(v0).unwrap();
+        assert_eq!(migrated, json!({"schemaVersion": SETTINGS_VERSION}));
+        let settings: AppSettings = serde_json::from_value(migrated).unwrap();
+        assert_eq!(settings.schema_version, SETTINGS_VERSION);
+        assert_eq!(settings.hotkeys.push_to_talk, "Ctrl+Space");
+        assert_eq!(settings.connection.port, 4455);
+    }
+
+    #[test]
+    fn migration_keeps_sections_already_present() {
+        let v0 = json!({"key": "old.key", "connection": {"host": "studio-pc", "port": 4460}});
+        let settings: AppSettings = serde_json::from_value(migrate(v0).unwrap()).unwrap();
+        assert_eq!(settings.connection.host, "studio-pc");
+        assert_eq!(settings.connection.port, 4460);
+    }
+
+    #[test]
+    fn current_files_are_left_alone() {
+        let v1 = json!({"schemaVersion": 1, "locale": "es"});
+        assert_eq!(migrate(v1.clone()).unwrap(), v1);
+    }
+
+    #[test]
+    fn rejects_newer_and_malformed_files() {
+        assert!(migrate(json!({"schemaVersion": SETTINGS_VERSION + 1})).is_err());
+        assert!(migrate(json!(["not", "an", "object"])).is_err());
+    }
+
+    #[test]
+    fn merge_updates_one_field_without_clobbering_its_section() {
+        let mut target = serde_json::to_value(AppSettings::default()).unwrap();
+        merge_json(&mut target, &json!({"connection": {"port": 4456}}));
+        let settings: AppSettings = serde_json::from_value(target).unwrap();
+        assert_eq!(settings.connection.port, 4456);
+        assert_eq!(settings.connection.host, "localhost");
+        assert!(settings.connection.auto_reconnect);
+    }
+
+    #[test]
+    fn merge_null_resets_a_field_to_its_default() {
+        let mut target = json!({"videoEditor": {"ffmpegPath": "C:/ffmpeg.exe", "thumbnailConcurrency": 4}});
+        merge_json(&mut target, &json!({"videoEditor": {"ffmpegPath": null}}));
+        assert_eq!(target, json!({"videoEditor": {"thumbnailConcurrency": 4}}));
+    }
+}
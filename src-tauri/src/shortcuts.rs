@@ -0,0 +1,281 @@
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::settings::{self, HotkeySettings, SharedSettings};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// The app's own global shortcuts as currently registered. Read from the
+/// plugin handler, which is synchronous, hence the std mutex.
+pub type SharedShortcuts = Arc<std::sync::Mutex<Vec<(Shortcut, ShortcutAction)>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    PushToTalk,
+    Panic,
+    ToggleStream,
+    ToggleRecord,
+    ToggleMicMute,
+    SaveReplay,
+}
+
+const ACTIONS: [ShortcutAction; 6] = [
+    ShortcutAction::PushToTalk,
+    ShortcutAction::Panic,
+    ShortcutAction::ToggleStream,
+    ShortcutAction::ToggleRecord,
+    ShortcutAction::ToggleMicMute,
+    ShortcutAction::SaveReplay,
+];
+
+impl ShortcutAction {
+    fn label(self) -> &'static str {
+        match self {
+            Self::PushToTalk => "push-to-talk",
+            Self::Panic => "panic mode",
+            Self::ToggleStream => "toggle stream",
+            Self::ToggleRecord => "toggle recording",
+            Self::ToggleMicMute => "toggle mic mute",
+            Self::SaveReplay => "save replay",
+        }
+    }
+
+    fn accelerator(self, h: &HotkeySettings) -> &str {
+        match self {
+            Self::PushToTalk => &h.push_to_talk,
+            Self::Panic => &h.panic,
+            Self::ToggleStream => &h.toggle_stream,
+            Self::ToggleRecord => &h.toggle_record,
+            Self::ToggleMicMute => &h.toggle_mic_mute,
+            Self::SaveReplay => &h.save_replay,
+        }
+    }
+
+    fn accelerator_mut(self, h: &mut HotkeySettings) -> &mut String {
+        match self {
+            Self::PushToTalk => &mut h.push_to_talk,
+            Self::Panic => &mut h.panic,
+            Self::ToggleStream => &mut h.toggle_stream,
+            Self::ToggleRecord => &mut h.toggle_record,
+            Self::ToggleMicMute => &mut h.toggle_mic_mute,
+            Self::SaveReplay => &mut h.save_replay,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub action: ShortcutAction,
+    /// Empty when the action has no shortcut.
+    pub accelerator: String,
+    pub registered: bool,
+}
+
+/// Parses the saved accelerators; empty ones are unbound, invalid ones are
+/// logged and skipped.
+pub fn from_settings(h: &HotkeySettings) -> Vec<(Shortcut, ShortcutAction)> {
+    ACTIONS
+        .iter()
+        .filter_map(|&action| {
+            let accel = action.accelerator(h);
+            if accel.is_empty() {
+                return None;
+            }
+            accel
+                .parse::<Shortcut>()
+                .map_err(|e| log::warn!("Invalid {} shortcut '{}': {}", action.label(), accel, e))
+                .ok()
+                .map(|s| (s, action))
+        })
+        .collect()
+}
+
+/// The bindings, even if a panic poisoned the lock; the list is replaced
+/// wholesale, so it is never left half-updated.
+fn lock(bound: &SharedShortcuts) -> std::sync::MutexGuard<'_, Vec<(Shortcut, ShortcutAction)>> {
+    bound.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Registers the saved shortcuts at startup. Ones another application
+/// already holds are dropped from the bindings, so `get_shortcuts` reports
+/// what is really active.
+pub fn register_all(app: &tauri::AppHandle) {
+    let bound = app.state::<SharedShortcuts>();
+    let saved = lock(&bound).clone();
+    let registered = saved
+        .into_iter()
+        .filter(|(shortcut, action)| match app.global_shortcut().register(*shortcut) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("Failed to register {} shortcut: {}", action.label(), e);
+                false
+            }
+        })
+        .collect();
+    *lock(&bound) = registered;
+}
+
+/// Swaps every registration for the ones in `hotkeys`, app actions and OBS
+/// hotkey bindings alike, when update_settings replaces the section.
+pub fn rebind_all(app: &tauri::AppHandle, hotkeys: &HotkeySettings) {
+    if let Err(e) = app.global_shortcut().unregister_all() {
+        log::warn!("Failed to unregister shortcuts: {}", e);
+    }
+    *lock(&app.state::<SharedShortcuts>()) = from_settings(hotkeys);
+    register_all(app);
+    crate::obs_hotkeys::register_bindings(app, &hotkeys.obs_bindings);
+}
+
+/// The action bound to `shortcut`, if it's one of ours.
+pub fn action_for(app: &tauri::AppHandle, shortcut: &Shortcut) -> Option<ShortcutAction> {
+    let bound = app.state::<SharedShortcuts>();
+    let bound = lock(&bound);
+    bound.iter().find(|(s, _)| s == shortcut).map(|(_, a)| *a)
+}
+
+/// Called from the global shortcut handler; false if the shortcut isn't one
+/// of the app's actions.
+pub fn on_shortcut(app: &tauri::AppHandle, shortcut: &Shortcut, state: ShortcutState) -> bool {
+    let Some(action) = action_for(app, shortcut) else {
+        return false;
+    };
+    let pressed = state == ShortcutState::Pressed;
+    if action != ShortcutAction::PushToTalk && !pressed {
+        return true;
+    }
+    let _ = app.emit(
+        "shortcut://fired",
+        json!({"action": action, "pressed": pressed}),
+    );
+    match action {
+        ShortcutAction::PushToTalk => {
+            let _ = app.emit(if pressed { "voice://ptt-start" } else { "voice://ptt-stop" }, ());
+            crate::teleprompter::on_ptt(app, pressed);
+        }
+        ShortcutAction::Panic => crate::panic::toggle(app),
+        _ => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = run_obs_action(&app, action).await {
+                    log::warn!("Shortcut for {} failed: {}", action.label(), e);
+                    let _ = app.emit(
+                        "shortcut://failed",
+                        json!({"action": action, "error": e}),
+                    );
+                }
+            });
+        }
+    }
+    true
+}
+
+async fn run_obs_action(app: &tauri::AppHandle, action: ShortcutAction) -> Result<(), String> {
+    let conn = app.state::<ObsHandle>().inner().clone();
+    if !conn.is_connected() {
+        return Err("Not connected to OBS".to_string());
+    }
+    match action {
+        ShortcutAction::ToggleStream => conn.send_request("ToggleStream", None).await?,
+        ShortcutAction::ToggleRecord => conn.send_request("ToggleRecord", None).await?,
+        ShortcutAction::SaveReplay => conn.send_request("SaveReplayBuffer", None).await?,
+        ShortcutAction::ToggleMicMute => {
+            let mic = app.state::<SharedObsState>().read().await.special_inputs.mic1.clone();
+            if mic.is_empty() {
+                return Err("No primary mic is set in OBS".to_string());
+            }
+            conn.send_request("ToggleInputMute", Some(json!({"inputName": mic})))
+                .await?
+        }
+        ShortcutAction::PushToTalk | ShortcutAction::Panic => return Ok(()),
+    };
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_shortcuts(
+    settings: tauri::State<'_, SharedSettings>,
+    bound: tauri::State<'_, SharedShortcuts>,
+) -> Result<Vec<ShortcutBinding>, String> {
+    let hotkeys = settings.read().await.hotkeys.clone();
+    let bound = lock(&bound).clone();
+    Ok(ACTIONS
+        .iter()
+        .map(|&action| ShortcutBinding {
+            action,
+            accelerator: action.accelerator(&hotkeys).to_string(),
+            registered: bound.iter().any(|(_, a)| *a == action),
+        })
+        .collect())
+}
+
+/// Rebinds one action; an empty accelerator unbinds it. The old shortcut
+/// stays registered if the new one can't be.
+#[tauri::command]
+pub async fn set_shortcut(
+    app: tauri::AppHandle,
+    settings: tauri::State<'_, SharedSettings>,
+    bound: tauri::State<'_, SharedShortcuts>,
+    action: ShortcutAction,
+    accelerator: String,
+) -> Result<Vec<ShortcutBinding>, String> {
+    let accelerator = accelerator.trim().to_string();
+    let parsed = if accelerator.is_empty() {
+        None
+    } else {
+        Some(
+            accelerator
+                .parse::<Shortcut>()
+                .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?,
+        )
+    };
+
+    if let Some(new) = parsed {
+        if let Some(other) = action_for(&app, &new).filter(|a| *a != action) {
+            return Err(format!("'{}' is already the {} shortcut", accelerator, other.label()));
+        }
+        let obs_bound = settings.read().await.hotkeys.obs_bindings.iter().any(|b| {
+            b.shortcut.parse::<Shortcut>().map(|s| s == new).unwrap_or(false)
+        });
+        if obs_bound {
+            return Err(format!("'{}' is already bound to an OBS hotkey", accelerator));
+        }
+    }
+
+    let old = lock(&bound)
+        .iter()
+        .find(|(_, a)| *a == action)
+        .map(|(s, _)| *s);
+    if old != parsed {
+        if let Some(old) = old {
+            if let Err(e) = app.global_shortcut().unregister(old) {
+                log::warn!("Failed to unregister {} shortcut: {}", action.label(), e);
+            }
+        }
+        if let Some(new) = parsed {
+            if let Err(e) = app.global_shortcut().register(new) {
+                if let Some(old) = old {
+                    let _ = app.global_shortcut().register(old);
+                }
+                return Err(format!(
+                    "Couldn't register '{}'; another application is probably using it ({})",
+                    accelerator, e
+                ));
+            }
+        }
+        let mut bound = lock(&bound);
+        bound.retain(|(_, a)| *a != action);
+        if let Some(new) = parsed {
+            bound.push((new, action));
+        }
+    }
+
+    settings::update_section(&settings, |s| {
+        *action.accelerator_mut(&mut s.hotkeys) = accelerator.clone();
+    })
+    .await?;
+    get_shortcuts(settings, bound).await
+}
@@ -0,0 +1,138 @@
+use crate::settings::{self, SharedSettings};
+use futures_util::future::{join_all, BoxFuture};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::watch;
+
+/// Everything registered gets this long in total before the process exits.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+type Cleanup = Box<dyn FnOnce(AppHandle) -> BoxFuture<'static, ()> + Send>;
+
+/// Cleanups that put OBS back the way we found it (ducked volumes, bypassed
+/// filters, borrowed monitor types) and stop background work on exit.
+pub struct ShutdownGuard {
+    cleanups: Vec<(String, Cleanup)>,
+    signal: watch::Sender<bool>,
+    quitting: bool,
+}
+
+pub type SharedShutdownGuard = Arc<Mutex<ShutdownGuard>>;
+
+impl Default for ShutdownGuard {
+    fn default() -> Self {
+        Self {
+            cleanups: Vec::new(),
+            signal: watch::channel(false).0,
+            quitting: false,
+        }
+    }
+}
+
+impl ShutdownGuard {
+    pub fn register<F, Fut>(&mut self, name: &str, cleanup: F)
+    where
+        F: FnOnce(AppHandle) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.cleanups
+            .push((name.to_string(), Box::new(move |app| Box::pin(cleanup(app)))));
+    }
+
+    /// Flips to true once shutdown starts; loops select on it to stop.
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.signal.subscribe()
+    }
+}
+
+/// Signals loops, then runs every cleanup concurrently under one deadline.
+/// Only the first call does anything.
+pub async fn run(app: &AppHandle) {
+    let Some(guard) = app.try_state::<SharedShutdownGuard>() else {
+        return;
+    };
+    let cleanups = {
+        // A panicked cleanup registration shouldn't stop the rest running.
+        let mut g = guard.lock().unwrap_or_else(|e| e.into_inner());
+        if g.quitting {
+            return;
+        }
+        g.quitting = true;
+        g.signal.send_replace(true);
+        std::mem::take(&mut g.cleanups)
+    };
+
+    let tasks = cleanups.into_iter().map(|(name, cleanup)| {
+        let fut = cleanup(app.clone());
+        async move {
+            fut.await;
+            log::info!("Shutdown: {} cleaned up", name);
+        }
+    });
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, join_all(tasks)).await.is_err() {
+        log::warn!(
+            "Shutdown cleanup did not finish within {}s; exiting anyway",
+            SHUTDOWN_TIMEOUT.as_secs()
+        );
+    }
+}
+
+pub fn run_blocking(app: &AppHandle) {
+    tauri::async_runtime::block_on(run(app));
+}
+
+/// Closing the main window usually means "get out of my way", not "quit".
+/// `startup.closeToTray` decides; unset asks the frontend. Returns whether
+/// the close should be prevented.
+pub fn on_close_requested(window: &tauri::Window) -> bool {
+    let app = window.app_handle();
+    let quitting = app
+        .try_state::<SharedShutdownGuard>()
+        .map(|g| g.lock().map(|g| g.quitting).unwrap_or(false))
+        .unwrap_or(false);
+    if quitting {
+        return false;
+    }
+    let close_to_tray = app
+        .try_state::<SharedSettings>()
+        .and_then(|s| s.try_read().ok().map(|s| s.startup.close_to_tray))
+        .flatten();
+    match close_to_tray {
+        Some(true) => {
+            let _ = window.hide();
+            true
+        }
+        Some(false) => false,
+        None => {
+            let _ = app.emit("app://close-requested", ());
+            true
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn resolve_close_request(
+    settings: tauri::State<'_, SharedSettings>,
+    app_handle: AppHandle,
+    action: String,
+    remember: bool,
+) -> Result<(), String> {
+    let to_tray = match action.as_str() {
+        "tray" => true,
+        "quit" => false,
+        other => return Err(format!("Unknown close action '{}'", other)),
+    };
+    if remember {
+        settings::update_section(&settings, |s| s.startup.close_to_tray = Some(to_tray)).await?;
+    }
+    if to_tray {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            window.hide().map_err(|e| e.to_string())?;
+        }
+    } else {
+        app_handle.exit(0);
+    }
+    Ok(())
+}
@@ -0,0 +1,406 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldSchema {
+    pub key: String,
+    pub field_type: String,
+    pub required: bool,
+    pub description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputKindSchema {
+    pub kind: String,
+    pub label: String,
+    pub fields: Vec<FieldSchema>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+const AUDIO_CAPTURE_KINDS: &[&str] = &["wasapi_input_capture", "wasapi_output_capture"];
+const GAME_CAPTURE_MODES: &[&str] = &["any_fullscreen", "window", "hotkey"];
+
+fn field(key: &str, field_type: &str, required: bool, description: &str) -> FieldSchema {
+    FieldSchema {
+        key: key.into(),
+        field_type: field_type.into(),
+        required,
+        description: description.into(),
+        options: Vec::new(),
+    }
+}
+
+fn enum_field(key: &str, required: bool, description: &str, options: &[&str]) -> FieldSchema {
+    FieldSchema {
+        options: options.iter().map(|o| o.to_string()).collect(),
+        ..field(key, "enum", required, description)
+    }
+}
+
+pub fn schema_for(kind: &str) -> Option<InputKindSchema> {
+    let (label, fields) = match kind {
+        "wasapi_input_capture" => (
+            "Audio Input Capture",
+            vec![field("device_id", "string", true, "Device ID, or \"default\"")],
+        ),
+        "wasapi_output_capture" => (
+            "Audio Output Capture",
+            vec![field("device_id", "string", true, "Device ID, or \"default\"")],
+        ),
+        "dshow_input" => (
+            "Video Capture Device",
+            vec![
+                field("video_device_id", "string", true, "Device ID in the form \"Name:\\\\?\\usb#...\""),
+                field("resolution", "string", false, "Custom resolution as WIDTHxHEIGHT"),
+                field("res_type", "int", false, "0 = device default, 1 = custom resolution"),
+            ],
+        ),
+        "game_capture" => (
+            "Game Capture",
+            vec![
+                enum_field("capture_mode", true, "What to capture", GAME_CAPTURE_MODES),
+                field("window", "string", false, "Window as \"title:class:executable\" (window mode)"),
+            ],
+        ),
+        "monitor_capture" => (
+            "Display Capture",
+            vec![field("monitor_id", "string", true, "Display device name from enumerate_displays")],
+        ),
+        "window_capture" => (
+            "Window Capture",
+            vec![
+                field("window", "string", true, "Window as \"title:class:executable\""),
+                field("method", "int", false, "0 = automatic, 1 = BitBlt, 2 = Windows 10+"),
+            ],
+        ),
+        "image_source" => (
+            "Image",
+            vec![field("file", "path", true, "Absolute path to an image file")],
+        ),
+        "ffmpeg_source" => (
+            "Media Source",
+            vec![
+                field("is_local_file", "bool", true, "true for a local file, false for a URL"),
+                field("local_file", "path", false, "Absolute path (local files)"),
+                field("input", "string", false, "Stream URL (network sources)"),
+                field("looping", "bool", false, "Loop playback"),
+            ],
+        ),
+        _ => return None,
+    };
+    Some(InputKindSchema {
+        kind: kind.into(),
+        label: label.into(),
+        fields,
+    })
+}
+
+// --- Builders ---
+
+pub fn audio_capture(device_id: &str) -> Value {
+    json!({"device_id": device_id})
+}
+
+pub fn dshow_input(video_device_id: &str, resolution: Option<&str>) -> Value {
+    match resolution {
+        Some(res) => json!({"video_device_id": video_device_id, "res_type": 1, "resolution": res}),
+        None => json!({"video_device_id": video_device_id}),
+    }
+}
+
+pub fn game_capture(capture_mode: &str, window: Option<&str>) -> Value {
+    match window {
+        Some(w) => json!({"capture_mode": capture_mode, "window": w}),
+        None => json!({"capture_mode": capture_mode}),
+    }
+}
+
+pub fn monitor_capture(monitor_id: &str) -> Value {
+    json!({"monitor_id": monitor_id})
+}
+
+pub fn window_capture(window: &str, method: Option<u8>) -> Value {
+    json!({"window": window, "method": method.unwrap_or(0)})
+}
+
+pub fn image_source(file: &str) -> Value {
+    json!({"file": file})
+}
+
+pub fn media_source(location: &str, looping: bool) -> Value {
+    let is_local = !location.contains("://");
+    if is_local {
+        json!({"is_local_file": true, "local_file": location, "looping": looping})
+    } else {
+        json!({"is_local_file": false, "input": location, "looping": looping})
+    }
+}
+
+/// Rebuilds raw frontend settings through the typed builder for `kind`, so
+/// shorthand like a bare resolution picks up the companion keys OBS expects.
+pub fn normalize(kind: &str, settings: &Value) -> Value {
+    let s = |key: &str| settings[key].as_str();
+    let rebuilt = match kind {
+        k if AUDIO_CAPTURE_KINDS.contains(&k) => s("device_id").map(audio_capture),
+        "dshow_input" => s("video_device_id").map(|id| dshow_input(id, s("resolution"))),
+        "game_capture" => s("capture_mode").map(|mode| game_capture(mode, s("window"))),
+        "monitor_capture" => s("monitor_id").map(monitor_capture),
+        "window_capture" => s("window").map(|w| window_capture(w, settings["method"].as_u64().map(|m| m as u8))),
+        "image_source" => s("file").map(image_source),
+        "ffmpeg_source" => s("local_file")
+            .or(s("input"))
+            .map(|loc| media_source(loc, settings["looping"].as_bool().unwrap_or(false))),
+        _ => None,
+    };
+    match (rebuilt, settings) {
+        (Some(Value::Object(mut built)), Value::Object(raw)) => {
+            // Keep any extra keys the caller passed that the builder doesn't know about.
+            for (k, v) in raw {
+                built.entry(k.clone()).or_insert_with(|| v.clone());
+            }
+            Value::Object(built)
+        }
+        _ => settings.clone(),
+    }
+}
+
+// --- Validation ---
+
+fn is_window_spec(value: &str) -> bool {
+    value.split(':').count() >= 3
+}
+
+fn is_resolution(value: &str) -> bool {
+    match value.split_once('x') {
+        Some((w, h)) => w.parse::<u32>().is_ok_and(|w| w > 0) && h.parse::<u32>().is_ok_and(|h| h > 0),
+        None => false,
+    }
+}
+
+pub fn validate(kind: &str, settings: &Value) -> Vec<FieldError> {
+    let Some(schema) = schema_for(kind) else {
+        return Vec::new();
+    };
+    let mut errors = Vec::new();
+    let mut err = |field: &str, message: String| {
+        errors.push(FieldError {
+            field: field.into(),
+            message,
+        })
+    };
+
+    if !settings.is_object() && !settings.is_null() {
+        err("", "Settings must be an object".into());
+        return errors;
+    }
+
+    for f in &schema.fields {
+        let value = &settings[f.key.as_str()];
+        if value.is_null() {
+            if f.required {
+                err(&f.key, "Required".into());
+            }
+            continue;
+        }
+        let type_ok = match f.field_type.as_str() {
+            "string" | "path" | "enum" => value.is_string(),
+            "int" => value.is_u64() || value.is_i64(),
+            "bool" => value.is_boolean(),
+            _ => true,
+        };
+        if !type_ok {
+            err(&f.key, format!("Expected {}", f.field_type));
+            continue;
+        }
+        let text = value.as_str().unwrap_or("");
+        if f.required && (f.field_type == "string" || f.field_type == "path") && text.trim().is_empty() {
+            err(&f.key, "Must not be empty".into());
+        }
+        if f.field_type == "enum" && !f.options.iter().any(|o| o == text) {
+            err(&f.key, format!("Must be one of: {}", f.options.join(", ")));
+        }
+        if f.field_type == "path" && !text.is_empty() && !std::path::Path::new(text).exists() {
+            err(&f.key, format!("File not found: {}", text));
+        }
+    }
+
+    let str_of = |key: &str| settings[key].as_str().unwrap_or("");
+    match kind {
+        "dshow_input" => {
+            let res = str_of("resolution");
+            if !res.is_empty() && !is_resolution(res) {
+                err("resolution", "Expected WIDTHxHEIGHT, e.g. 1920x1080".into());
+            }
+            if !res.is_empty() && settings["res_type"].as_u64() != Some(1) {
+                err("res_type", "Must be 1 when a custom resolution is set".into());
+            }
+        }
+        "game_capture" => {
+            if str_of("capture_mode") == "window" && !is_window_spec(str_of("window")) {
+                err("window", "Window mode needs \"title:class:executable\"".into());
+            }
+        }
+        "window_capture" => {
+            if !str_of("window").is_empty() && !is_window_spec(str_of("window")) {
+                err("window", "Expected \"title:class:executable\"".into());
+            }
+            if let Some(method) = settings["method"].as_u64() {
+                if method > 2 {
+                    err("method", "Must be 0, 1 or 2".into());
+                }
+            }
+        }
+        "ffmpeg_source" => {
+            let is_local = settings["is_local_file"].as_bool().unwrap_or(true);
+            if is_local && str_of("local_file").is_empty() {
+                err("local_file", "Required for local files".into());
+            }
+            if !is_local && !str_of("input").contains("://") {
+                err("input", "Expected a URL such as rtmp:// or https://".into());
+            }
+        }
+        _ => {}
+    }
+
+    errors
+}
+
+/// Normalizes and validates settings for CreateInput; `None` if there are
+/// none to send.
+pub fn checked_settings(kind: &str, settings: Option<&Value>) -> Result<Option<Value>, String> {
+    let settings = normalize(kind, settings.unwrap_or(&Value::Null));
+    let errors = validate(kind, &settings);
+    if !errors.is_empty() {
+        return Err(format_errors(kind, &errors));
+    }
+    Ok(Some(settings).filter(|s| !s.is_null()))
+}
+
+pub fn format_errors(kind: &str, errors: &[FieldError]) -> String {
+    let details: Vec<String> = errors
+        .iter()
+        .map(|e| format!("{}: {}", e.field, e.message))
+        .collect();
+    format!("Invalid settings for {}: {}", kind, details.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(errors: &[FieldError]) -> Vec<&str> {
+        errors.iter().map(|e| e.field.as_str()).collect()
+    }
+
+    #[test]
+    fn audio_capture_settings() {
+        assert_eq!(audio_capture("default"), json!({"device_id": "default"}));
+    }
+
+    #[test]
+    fn dshow_input_settings() {
+        let id = "Cam Link 4K:\\\\?\\usb#vid_0fd9";
+        assert_eq!(dshow_input(id, None), json!({"video_device_id": id}));
+        assert_eq!(
+            dshow_input(id, Some("1920x1080")),
+            json!({"video_device_id": id, "res_type": 1, "resolution": "1920x1080"})
+        );
+    }
+
+    #[test]
+    fn game_capture_settings() {
+        assert_eq!(game_capture("any_fullscreen", None), json!({"capture_mode": "any_fullscreen"}));
+        assert_eq!(
+            game_capture("window", Some("Minecraft:GLFW30:javaw.exe")),
+            json!({"capture_mode": "window", "window": "Minecraft:GLFW30:javaw.exe"})
+        );
+    }
+
+    #[test]
+    fn monitor_capture_settings() {
+        assert_eq!(monitor_capture("\\\\.\\DISPLAY1"), json!({"monitor_id": "\\\\.\\DISPLAY1"}));
+    }
+
+    #[test]
+    fn window_capture_settings() {
+        let window = "Untitled - Notepad:Notepad:notepad.exe";
+        assert_eq!(window_capture(window, None), json!({"window": window, "method": 0}));
+        assert_eq!(window_capture(window, Some(2)), json!({"window": window, "method": 2}));
+    }
+
+    #[test]
+    fn image_source_settings() {
+        assert_eq!(image_source("C:\\overlays\\brb.png"), json!({"file": "C:\\overlays\\brb.png"}));
+    }
+
+    #[test]
+    fn media_source_settings() {
+        assert_eq!(
+            media_source("C:\\clips\\intro.mp4", true),
+            json!({"is_local_file": true, "local_file": "C:\\clips\\intro.mp4", "looping": true})
+        );
+        assert_eq!(
+            media_source("rtmp://example.com/live", false),
+            json!({"is_local_file": false, "input": "rtmp://example.com/live", "looping": false})
+        );
+    }
+
+    #[test]
+    fn normalize_adds_companion_keys_and_keeps_extras() {
+        let raw = json!({"video_device_id": "Cam", "resolution": "1280x720", "fps": 30});
+        assert_eq!(
+            normalize("dshow_input", &raw),
+            json!({"video_device_id": "Cam", "res_type": 1, "resolution": "1280x720", "fps": 30})
+        );
+    }
+
+    #[test]
+    fn validate_flags_each_bad_field() {
+        assert_eq!(fields(&validate("wasapi_input_capture", &json!({}))), ["device_id"]);
+        assert_eq!(fields(&validate("game_capture", &json!({"capture_mode": "fullscreen"}))), ["capture_mode"]);
+        assert_eq!(
+            fields(&validate("game_capture", &json!({"capture_mode": "window", "window": "Minecraft"}))),
+            ["window"]
+        );
+        assert_eq!(
+            fields(&validate("window_capture", &json!({"window": "a:b:c.exe", "method": 7}))),
+            ["method"]
+        );
+        assert_eq!(
+            fields(&validate("dshow_input", &json!({"video_device_id": "Cam", "resolution": "wide"}))),
+            ["resolution", "res_type"]
+        );
+        assert_eq!(fields(&validate("ffmpeg_source", &json!({"is_local_file": false, "input": "example.com"}))), ["input"]);
+        assert_eq!(fields(&validate("image_source", &json!({"file": "/no/such/observe.png"}))), ["file"]);
+        assert_eq!(fields(&validate("monitor_capture", &json!("DISPLAY1"))), [""]);
+        // Kinds without a schema are left for OBS to judge.
+        assert!(validate("browser_source", &json!({"url": 5})).is_empty());
+    }
+
+    #[test]
+    fn checked_settings_rejects_bad_settings() {
+        let err = checked_settings("window_capture", Some(&json!({"window": "Notepad", "method": 1}))).unwrap_err();
+        assert_eq!(err, "Invalid settings for window_capture: window: Expected \"title:class:executable\"");
+        assert!(checked_settings("monitor_capture", None).is_err());
+    }
+
+    #[test]
+    fn checked_settings_passes_normalized_settings() {
+        let settings = checked_settings("dshow_input", Some(&json!({"video_device_id": "Cam", "resolution": "1920x1080"})));
+        assert_eq!(
+            settings.unwrap(),
+            Some(json!({"video_device_id": "Cam", "res_type": 1, "resolution": "1920x1080"}))
+        );
+        assert_eq!(checked_settings("browser_source", None).unwrap(), None);
+    }
+}
@@ -1,27 +1,97 @@
+use crate::audio;
+use crate::device_matching;
+use crate::obs_config;
 use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::routing;
 use crate::store::SharedLicenseState;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use tokio::sync::Mutex;
 
 pub struct SpectrumState {
     control_tx: Option<std::sync::mpsc::Sender<SpectrumCommand>>,
-    is_running: bool,
+    /// What the capture thread is analyzing; None while stopped.
+    config: Option<SpectrumConfig>,
+    /// Input being analyzed through the monitoring device, with the monitor
+    /// type to put back when the view closes (None if it was already monitored).
+    input_monitor: Option<(String, Option<String>)>,
 }
 
 pub type SharedSpectrumState = Arc<Mutex<SpectrumState>>;
 
+/// The one capture thread takes these in order; a Start while running
+/// replaces the current capture rather than spawning another thread.
 enum SpectrumCommand {
-    Start(String, bool), // device_id, is_loopback
+    Start(SpectrumConfig),
     Stop,
 }
 
+const FFT_SIZES: [usize; 3] = [1024, 2048, 4096];
+const DEFAULT_FFT_SIZE: usize = 2048;
+const DEFAULT_BANDS: usize = 64;
+const DEFAULT_UPDATE_HZ: u32 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectrumConfig {
+    pub fft_size: usize,
+    /// Log-spaced output bands between 20 Hz and 20 kHz.
+    pub bands: usize,
+    pub update_hz: u32,
+    pub device_id: String,
+    pub is_loopback: bool,
+    /// OBS input being analyzed, when there is one.
+    pub input_name: Option<String>,
+}
+
+impl SpectrumConfig {
+    fn new(
+        device_id: String,
+        is_loopback: bool,
+        input_name: Option<String>,
+        fft_size: Option<usize>,
+        bands: Option<usize>,
+        update_hz: Option<u32>,
+    ) -> Result<Self, String> {
+        let fft_size = fft_size.unwrap_or(DEFAULT_FFT_SIZE);
+        if !FFT_SIZES.contains(&fft_size) {
+            return Err(format!("FFT size must be 1024, 2048 or 4096, not {}", fft_size));
+        }
+        let bands = bands.unwrap_or(DEFAULT_BANDS);
+        if !(8..=256).contains(&bands) {
+            return Err(format!("Band count must be between 8 and 256, not {}", bands));
+        }
+        let update_hz = update_hz.unwrap_or(DEFAULT_UPDATE_HZ);
+        if !(1..=60).contains(&update_hz) {
+            return Err(format!("Update rate must be between 1 and 60 Hz, not {}", update_hz));
+        }
+        Ok(Self {
+            fft_size,
+            bands,
+            update_hz,
+            device_id,
+            is_loopback,
+            input_name,
+        })
+    }
+}
+
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct FftPayload {
     bins: Vec<f32>,
     sample_rate: u32,
+    input_name: Option<String>,
+    /// Log-spaced bands in dB, low to high.
+    bands: Vec<f32>,
+    /// Per-band peak hold: holds for a second, then falls.
+    peaks: Vec<f32>,
+    /// Center frequency of each band in Hz.
+    band_frequencies: Vec<f32>,
 }
 
 #[derive(Clone, Serialize)]
@@ -31,64 +101,193 @@ struct LufsPayload {
     short_term: f64,
     integrated: f64,
     true_peak: f64,
+    input_name: Option<String>,
 }
 
+pub(crate) const MONITOR_ONLY: &str = "OBS_MONITORING_TYPE_MONITOR_ONLY";
+pub(crate) const MONITOR_AND_OUTPUT: &str = "OBS_MONITORING_TYPE_MONITOR_AND_OUTPUT";
+
 impl SpectrumState {
     pub fn new() -> Self {
         Self {
             control_tx: None,
-            is_running: false,
+            config: None,
+            input_monitor: None,
         }
     }
 }
 
-#[tauri::command]
-pub async fn start_spectrum(
-    license: tauri::State<'_, SharedLicenseState>,
-    source_name: String,
-    app_handle: AppHandle,
-    obs_state: tauri::State<'_, SharedObsState>,
-    spectrum_state: tauri::State<'_, SharedSpectrumState>,
-) -> Result<(), String> {
-    crate::store::require_module(&license, "spectrum").await?;
-    let state = obs_state.read().await;
-    let input = state
-        .inputs
-        .get(&source_name)
-        .ok_or_else(|| format!("Source '{}' not found", source_name))?;
-
-    let device_id = input.device_id.clone();
-    let is_loopback = input.kind.contains("wasapi_output_capture");
-    drop(state);
-
-    let mut spec = spectrum_state.lock().await;
-
+fn send_start(spec: &mut SpectrumState, app_handle: AppHandle, config: SpectrumConfig) {
+    spec.config = Some(config.clone());
     if let Some(tx) = &spec.control_tx {
-        if spec.is_running {
-            let _ = tx.send(SpectrumCommand::Stop);
-            std::thread::sleep(std::time::Duration::from_millis(100));
+        // A send only fails if the thread has exited (e.g. COM init failed);
+        // fall through and start a fresh one.
+        if tx.send(SpectrumCommand::Start(config.clone())).is_ok() {
+            return;
         }
-        let _ = tx.send(SpectrumCommand::Start(device_id, is_loopback));
-        spec.is_running = true;
-        return Ok(());
     }
 
     let (tx, rx) = std::sync::mpsc::channel::<SpectrumCommand>();
-    let _ = tx.send(SpectrumCommand::Start(device_id, is_loopback));
+    let _ = tx.send(SpectrumCommand::Start(config));
     spec.control_tx = Some(tx);
-    spec.is_running = true;
-    drop(spec);
 
     std::thread::spawn(move || {
         capture_thread(rx, app_handle);
     });
+}
+
+pub(crate) async fn set_monitor_type(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    input_name: &str,
+    monitor_type: &str,
+) -> Result<(), String> {
+    conn.send_request(
+        "SetInputAudioMonitorType",
+        Some(json!({"inputName": input_name, "monitorType": monitor_type})),
+    )
+    .await?;
+    if let Some(input) = obs_state.write().await.inputs.get_mut(input_name) {
+        input.monitor_type = monitor_type.to_string();
+    }
+    Ok(())
+}
+
+/// A monitor type changed for the input analyzer, kept on disk until it is
+/// put back so a crash mid-view doesn't leave the input monitored.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MonitorSession {
+    input_name: String,
+    monitor_type: String,
+}
+
+fn session_file_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("spectrum_monitor.json")
+}
+
+fn persist(session: &MonitorSession) -> Result<(), String> {
+    let path = session_file_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(session)
+        .map_err(|e| format!("Failed to serialize monitor state: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to save monitor state: {}", e))
+}
+
+fn clear_persisted() {
+    let _ = std::fs::remove_file(session_file_path());
+}
+
+/// Puts back the monitor type changed by `start_input_spectrum`, if any.
+async fn restore_input_monitor(conn: &ObsHandle, obs_state: &SharedObsState, spec: &mut SpectrumState) {
+    let Some((input_name, previous)) = spec.input_monitor.take() else {
+        return;
+    };
+    let Some(previous) = previous else {
+        return;
+    };
+    match set_monitor_type(conn, obs_state, &input_name, &previous).await {
+        Ok(()) => clear_persisted(),
+        Err(e) => log::warn!("Failed to restore monitoring on '{}': {}", input_name, e),
+    }
+}
+
+/// Puts back a monitor type left changed by an analyzer view that never
+/// closed (app crash or lost connection). Called after each (re)connect.
+pub async fn restore_pending(conn: &ObsHandle, obs_state: &SharedObsState, app: &AppHandle) {
+    // A reconnect while the view is open keeps the input monitored.
+    if let Some(spec) = app.try_state::<SharedSpectrumState>() {
+        if spec.lock().await.input_monitor.is_some() {
+            return;
+        }
+    }
+    let Ok(content) = std::fs::read_to_string(session_file_path()) else {
+        return;
+    };
+    let session: MonitorSession = match serde_json::from_str(&content) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Discarding unreadable spectrum monitor state: {}", e);
+            clear_persisted();
+            return;
+        }
+    };
+    log::info!("Restoring monitoring on '{}' from an interrupted spectrum view", session.input_name);
+    match set_monitor_type(conn, obs_state, &session.input_name, &session.monitor_type).await {
+        Ok(()) => clear_persisted(),
+        Err(e) => log::warn!("Failed to restore monitoring on '{}': {}", session.input_name, e),
+    }
+}
+
+/// Analyzes a device directly: `device_id` if given, else `source_name`'s
+/// device, else the primary mic's. Calling it while running restarts the
+/// capture with the new settings.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_spectrum(
+    license: tauri::State<'_, SharedLicenseState>,
+    source_name: Option<String>,
+    device_id: Option<String>,
+    fft_size: Option<usize>,
+    bands: Option<usize>,
+    update_hz: Option<u32>,
+    app_handle: AppHandle,
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    spectrum_state: tauri::State<'_, SharedSpectrumState>,
+) -> Result<(), String> {
+    crate::store::require_module(&license, "spectrum").await?;
+    let (device_id, is_loopback) = match device_id {
+        Some(id) => {
+            let devices = tokio::task::spawn_blocking(audio::enumerate_audio_devices)
+                .await
+                .map_err(|e| format!("Task failed: {}", e))??;
+            let is_loopback = devices
+                .iter()
+                .find(|d| d.id == id)
+                .map_or(false, |d| d.device_type == "output");
+            (id, is_loopback)
+        }
+        None => {
+            let state = obs_state.read().await;
+            let name = source_name
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| state.special_inputs.mic1.clone());
+            if name.is_empty() {
+                return Err("No source given and no Mic/Aux input is set in OBS".to_string());
+            }
+            let input = state
+                .inputs
+                .get(&name)
+                .ok_or_else(|| format!("Source '{}' not found", name))?;
+            (input.device_id.clone(), input.kind.contains("wasapi_output_capture"))
+        }
+    };
+    let config = SpectrumConfig::new(device_id, is_loopback, None, fft_size, bands, update_hz)?;
 
+    let mut spec = spectrum_state.lock().await;
+    restore_input_monitor(&conn_state, &obs_state, &mut spec).await;
+    send_start(&mut spec, app_handle, config);
     Ok(())
 }
 
+#[tauri::command]
+pub async fn get_spectrum_config(
+    spectrum_state: tauri::State<'_, SharedSpectrumState>,
+) -> Result<Option<SpectrumConfig>, String> {
+    Ok(spectrum_state.lock().await.config.clone())
+}
+
 #[tauri::command]
 pub async fn stop_spectrum(
     license: tauri::State<'_, SharedLicenseState>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
     spectrum_state: tauri::State<'_, SharedSpectrumState>,
 ) -> Result<(), String> {
     crate::store::require_module(&license, "spectrum").await?;
@@ -96,10 +295,100 @@ pub async fn stop_spectrum(
     if let Some(tx) = &spec.control_tx {
         let _ = tx.send(SpectrumCommand::Stop);
     }
-    spec.is_running = false;
+    spec.config = None;
+    restore_input_monitor(&conn_state, &obs_state, &mut spec).await;
+    Ok(())
+}
+
+/// Analyzes an OBS input after its filter chain. OBS doesn't stream per-input
+/// audio over the websocket, so the input is monitored and the monitoring
+/// device is captured via loopback instead.
+#[tauri::command]
+pub async fn start_input_spectrum(
+    license: tauri::State<'_, SharedLicenseState>,
+    input_name: String,
+    app_handle: AppHandle,
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    spectrum_state: tauri::State<'_, SharedSpectrumState>,
+) -> Result<(), String> {
+    crate::store::require_module(&license, "spectrum").await?;
+    let current_monitor = {
+        let state = obs_state.read().await;
+        let input = state
+            .inputs
+            .get(&input_name)
+            .ok_or_else(|| format!("Source '{}' not found", input_name))?;
+        if !input.audio_tracks.is_object() {
+            return Err(format!("'{}' has no audio", input_name));
+        }
+        input.monitor_type.clone()
+    };
+
+    let audio_config = tokio::task::spawn_blocking(obs_config::read_obs_audio_config)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    let devices = tokio::task::spawn_blocking(audio::enumerate_audio_devices)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??;
+    let monitor_device = device_matching::resolve_device(
+        "wasapi_output_capture",
+        &audio_config.monitoring_device_id,
+        &devices,
+    )
+    .ok_or("OBS monitoring device not found")?
+    .clone();
+
+    let loops = {
+        let state = obs_state.read().await;
+        routing::monitoring_loop_inputs(&state, &devices, &audio_config.monitoring_device_id)
+    };
+    if !loops.is_empty() {
+        return Err(format!(
+            "'{}' captures the monitoring device ({}), so monitoring '{}' would echo into the stream. \
+             Pick a different monitoring device in OBS Settings > Audio first.",
+            loops.join("', '"),
+            monitor_device.name,
+            input_name
+        ));
+    }
+
+    let mut spec = spectrum_state.lock().await;
+    let same_input = spec.input_monitor.as_ref().map(|(n, _)| n == &input_name).unwrap_or(false);
+    if !same_input {
+        restore_input_monitor(&conn_state, &obs_state, &mut spec).await;
+        // Already-monitored inputs are left alone. Others get monitor-and-output
+        // so they stay in the stream and recording while the view is open.
+        let previous = if current_monitor == MONITOR_ONLY || current_monitor == MONITOR_AND_OUTPUT {
+            None
+        } else {
+            persist(&MonitorSession {
+                input_name: input_name.clone(),
+                monitor_type: current_monitor.clone(),
+            })?;
+            if let Err(e) = set_monitor_type(&conn_state, &obs_state, &input_name, MONITOR_AND_OUTPUT).await {
+                clear_persisted();
+                return Err(e);
+            }
+            Some(current_monitor)
+        };
+        spec.input_monitor = Some((input_name.clone(), previous));
+    }
+    let config = SpectrumConfig::new(monitor_device.id, true, Some(input_name), None, None, None)?;
+    send_start(&mut spec, app_handle, config);
     Ok(())
 }
 
+#[tauri::command]
+pub async fn stop_input_spectrum(
+    license: tauri::State<'_, SharedLicenseState>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    spectrum_state: tauri::State<'_, SharedSpectrumState>,
+) -> Result<(), String> {
+    stop_spectrum(license, conn_state, obs_state, spectrum_state).await
+}
+
 #[tauri::command]
 pub async fn reset_lufs(
     license: tauri::State<'_, SharedLicenseState>,
@@ -107,8 +396,8 @@ pub async fn reset_lufs(
 ) -> Result<(), String> {
     crate::store::require_module(&license, "spectrum").await?;
     let _ = spectrum_state.lock().await;
-    // LUFS reset is handled via the capture thread recreating the ebur128 instance
-    // For now this is a no-op placeholder; the frontend can restart the spectrum
+    // Capture threads pick this up on their next pass and reset their meters.
+    crate::loudness::reset_all();
     Ok(())
 }
 
@@ -135,25 +424,20 @@ fn capture_thread(rx: std::sync::mpsc::Receiver<SpectrumCommand>, app_handle: Ap
         }
     };
 
+    let mut next: Option<SpectrumConfig> = None;
     loop {
-        let cmd = match rx.recv() {
-            Ok(c) => c,
-            Err(_) => break,
-        };
-
-        let (device_id, is_loopback) = match cmd {
-            SpectrumCommand::Start(id, lb) => (id, lb),
-            SpectrumCommand::Stop => continue,
+        let config = match next.take() {
+            Some(c) => c,
+            None => match rx.recv() {
+                Ok(SpectrumCommand::Start(c)) => c,
+                Ok(SpectrumCommand::Stop) => continue,
+                Err(_) => break,
+            },
         };
 
-        if let Err(e) = run_capture(
-            &enumerator,
-            &device_id,
-            is_loopback,
-            &rx,
-            &app_handle,
-        ) {
-            log::error!("Spectrum capture error: {}", e);
+        match run_capture(&enumerator, &config, &rx, &app_handle) {
+            Ok(restart) => next = restart,
+            Err(e) => log::error!("Spectrum capture error: {}", e),
         }
     }
 
@@ -162,21 +446,91 @@ fn capture_thread(rx: std::sync::mpsc::Receiver<SpectrumCommand>, app_handle: Ap
     }
 }
 
+#[cfg(windows)]
+const PEAK_HOLD: std::time::Duration = std::time::Duration::from_secs(1);
+#[cfg(windows)]
+const PEAK_FALL_DB_PER_SEC: f32 = 20.0;
+
+/// Folds FFT bins into log-spaced bands and tracks each band's peak hold.
+#[cfg(windows)]
+struct BandMapper {
+    ranges: Vec<(usize, usize)>,
+    centers: Vec<f32>,
+    peaks: Vec<f32>,
+    peak_at: Vec<std::time::Instant>,
+    last: std::time::Instant,
+}
+
+#[cfg(windows)]
+impl BandMapper {
+    fn new(bands: usize, fft_size: usize, sample_rate: u32) -> Self {
+        let half = fft_size / 2;
+        let bin_hz = sample_rate as f32 / fft_size as f32;
+        let low = 20.0f32;
+        let high = 20_000.0f32.min(sample_rate as f32 / 2.0);
+        let ratio = (high / low).powf(1.0 / bands as f32);
+        let mut ranges = Vec::with_capacity(bands);
+        let mut centers = Vec::with_capacity(bands);
+        for b in 0..bands {
+            let f0 = low * ratio.powi(b as i32);
+            let f1 = f0 * ratio;
+            // Low bands narrower than a bin share the bin they fall in.
+            let start = ((f0 / bin_hz).floor() as usize).min(half - 1);
+            let end = ((f1 / bin_hz).ceil() as usize).clamp(start + 1, half);
+            ranges.push((start, end));
+            centers.push((f0 * f1).sqrt());
+        }
+        let now = std::time::Instant::now();
+        Self {
+            ranges,
+            centers,
+            peaks: vec![-100.0; bands],
+            peak_at: vec![now; bands],
+            last: now,
+        }
+    }
+
+    fn fold(&mut self, bins: &[f32]) -> Vec<f32> {
+        let now = std::time::Instant::now();
+        let fall = PEAK_FALL_DB_PER_SEC * now.duration_since(self.last).as_secs_f32();
+        self.last = now;
+        let bands: Vec<f32> = self
+            .ranges
+            .iter()
+            .map(|&(s, e)| bins[s..e].iter().copied().fold(-100.0f32, f32::max))
+            .collect();
+        for (i, &level) in bands.iter().enumerate() {
+            if level >= self.peaks[i] {
+                self.peaks[i] = level;
+                self.peak_at[i] = now;
+            } else if now.duration_since(self.peak_at[i]) > PEAK_HOLD {
+                self.peaks[i] = (self.peaks[i] - fall).max(level);
+            }
+        }
+        bands
+    }
+}
+
+/// Captures until told to stop. Returns the config to restart with when a
+/// new Start arrives, so the caller can switch without a new thread.
 #[cfg(windows)]
 fn run_capture(
     enumerator: &windows::Win32::Media::Audio::IMMDeviceEnumerator,
-    device_id: &str,
-    is_loopback: bool,
+    config: &SpectrumConfig,
     rx: &std::sync::mpsc::Receiver<SpectrumCommand>,
     app_handle: &AppHandle,
-) -> Result<(), String> {
+) -> Result<Option<SpectrumConfig>, String> {
     use rustfft::{num_complex::Complex, FftPlanner};
     use std::time::Instant;
     use tauri::Emitter;
     use windows::Win32::Media::Audio::*;
     use windows::core::PCWSTR;
 
-    const FFT_SIZE: usize = 2048;
+    let fft_size = config.fft_size;
+    let device_id = config.device_id.as_str();
+    let is_loopback = config.is_loopback;
+    let input_name = config.input_name.clone();
+    let emit_interval_ms = 1000 / config.update_hz.max(1) as u128;
 
     let device = unsafe {
         if device_id == "default" {
@@ -244,14 +598,14 @@ fn run_capture(
     }
 
     let mut planner = FftPlanner::<f32>::new();
-    let fft = planner.plan_fft_forward(FFT_SIZE);
-    let mut hann_window = vec![0.0f32; FFT_SIZE];
-    for i in 0..FFT_SIZE {
+    let fft = planner.plan_fft_forward(fft_size);
+    let mut hann_window = vec![0.0f32; fft_size];
+    for i in 0..fft_size {
         hann_window[i] =
-            0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / FFT_SIZE as f32).cos());
+            0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / fft_size as f32).cos());
     }
 
-    let mut ring_buffer: Vec<f32> = Vec::with_capacity(FFT_SIZE);
+    let mut ring_buffer: Vec<f32> = Vec::with_capacity(fft_size);
     let mut last_fft_emit = Instant::now();
     let mut last_lufs_emit = Instant::now();
 
@@ -260,20 +614,52 @@ fn run_capture(
         ebur128::EbuR128::new(channels as u32, sample_rate, lufs_mode)
             .map_err(|e| format!("ebur128 init: {:?}", e))?;
 
-    let mut smoothed_bins: Vec<f32> = vec![-90.0; FFT_SIZE / 2];
+    let mut lufs_generation = crate::loudness::reset_generation();
+
+    let mut smoothed_bins: Vec<f32> = vec![-90.0; fft_size / 2];
     let smooth_alpha = 0.3f32;
+    let mut band_mapper = BandMapper::new(config.bands, fft_size, sample_rate);
+    let mut restart: Option<SpectrumConfig> = None;
 
     loop {
-        std::thread::sleep(std::time::Duration::from_millis(15));
+        // Wait out the packet period, but wake at once for a command.
+        let mut received = rx
+            .recv_timeout(std::time::Duration::from_millis(15))
+            .map_err(|e| match e {
+                std::sync::mpsc::RecvTimeoutError::Timeout => std::sync::mpsc::TryRecvError::Empty,
+                std::sync::mpsc::RecvTimeoutError::Disconnected => {
+                    std::sync::mpsc::TryRecvError::Disconnected
+                }
+            });
 
-        match rx.try_recv() {
-            Ok(SpectrumCommand::Stop) | Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                break;
-            }
-            Ok(SpectrumCommand::Start(..)) => {
-                break; // will restart with new device
+        // Drain everything queued; the last command wins.
+        let mut stop = false;
+        loop {
+            match received {
+                Ok(SpectrumCommand::Start(next)) => {
+                    restart = Some(next);
+                    stop = true;
+                }
+                Ok(SpectrumCommand::Stop) => {
+                    restart = None;
+                    stop = true;
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    restart = None;
+                    stop = true;
+                    break;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => break,
             }
-            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            received = rx.try_recv();
+        }
+        if stop {
+            break;
+        }
+
+        if crate::loudness::reset_generation() != lufs_generation {
+            lufs_generation = crate::loudness::reset_generation();
+            lufs_meter.reset();
         }
 
         loop {
@@ -328,10 +714,10 @@ fn run_capture(
             let _ = unsafe { capture_client.ReleaseBuffer(num_frames) };
 
             // FFT processing when we have enough samples
-            while ring_buffer.len() >= FFT_SIZE {
+            while ring_buffer.len() >= fft_size {
                 let now = Instant::now();
-                if now.duration_since(last_fft_emit).as_millis() >= 33 {
-                    let mut fft_input: Vec<Complex<f32>> = ring_buffer[..FFT_SIZE]
+                if now.duration_since(last_fft_emit).as_millis() >= emit_interval_ms {
+                    let mut fft_input: Vec<Complex<f32>> = ring_buffer[..fft_size]
                         .iter()
                         .enumerate()
                         .map(|(i, &s)| Complex {
@@ -342,8 +728,8 @@ fn run_capture(
 
                     fft.process(&mut fft_input);
 
-                    let n_sqrt = (FFT_SIZE as f32).sqrt();
-                    let half = FFT_SIZE / 2;
+                    let n_sqrt = (fft_size as f32).sqrt();
+                    let half = fft_size / 2;
                     let bins: Vec<f32> = fft_input[..half]
                         .iter()
                         .enumerate()
@@ -360,17 +746,22 @@ fn run_capture(
                         })
                         .collect();
 
+                    let bands = band_mapper.fold(&bins);
                     let _ = app_handle.emit(
                         "audio://fft-data",
                         FftPayload {
                             bins,
                             sample_rate,
+                            input_name: input_name.clone(),
+                            bands,
+                            peaks: band_mapper.peaks.clone(),
+                            band_frequencies: band_mapper.centers.clone(),
                         },
                     );
                     last_fft_emit = now;
                 }
                 // Remove first half to slide window
-                ring_buffer.drain(..FFT_SIZE / 2);
+                ring_buffer.drain(..fft_size / 2);
             }
 
             // LUFS emit
@@ -388,6 +779,7 @@ fn run_capture(
                         short_term,
                         integrated,
                         true_peak: if true_peak > 0.0 { 20.0 * true_peak.log10() } else { -100.0 },
+                        input_name: input_name.clone(),
                     },
                 );
                 last_lufs_emit = now;
@@ -399,7 +791,7 @@ fn run_capture(
         let _ = audio_client.Stop();
     }
 
-    Ok(())
+    Ok(restart)
 }
 
 #[cfg(windows)]
@@ -1,3 +1,4 @@
+use crate::i18n::t;
 use base64::Engine;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -9,6 +10,21 @@ pub type SharedLicenseState = Arc<RwLock<LicenseState>>;
 
 const LICENSE_PUBLIC_KEY_B64: &str = "VLnMNE9WY3KsKicAniGG/hCSE4GzwYNSd21K9PVya6w=";
 
+const LICENSE_API_BASE: &str = "https://observe-api.smythmyke.workers.dev";
+
+/// A key past its refresh-by date keeps working this long, so a stream
+/// without internet doesn't lock paid modules.
+const REFRESH_GRACE_SECS: u64 = 14 * 24 * 60 * 60;
+/// Keys this close to their refresh-by date are refreshed in the background.
+const REFRESH_AHEAD_SECS: u64 = 3 * 24 * 60 * 60;
+/// How far a key's issue time may be ahead of this machine's clock.
+const CLOCK_SKEW_SECS: u64 = 10 * 60;
+
+/// Modules that can be tried once per machine before buying.
+pub const TRIAL_MODULES: &[&str] = &["presets", "ducking", "audio-fx"];
+const TRIAL_SECS: u64 = 7 * DAY_SECS;
+const DAY_SECS: u64 = 24 * 60 * 60;
+
 const B64_URL: base64::engine::general_purpose::GeneralPurpose =
     base64::engine::general_purpose::URL_SAFE_NO_PAD;
 
@@ -22,21 +38,57 @@ pub struct ModuleInfo {
     pub panels: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LicenseState {
     pub owned_modules: HashSet<String>,
     pub email: Option<String>,
     pub activated_at: Option<u64>,
+    /// Modules whose keys are past their grace period. A refresh brings them back.
+    #[serde(default)]
+    pub expired_modules: HashSet<String>,
+    /// The earliest refresh-by date among the keys in use.
+    #[serde(default)]
+    pub refresh_by: Option<u64>,
+    /// A key in use is past its refresh-by date and running on grace.
+    #[serde(default)]
+    pub needs_refresh: bool,
+    /// One entry per trial module, tried or not.
+    #[serde(default)]
+    pub trials: Vec<TrialStatus>,
 }
 
-impl Default for LicenseState {
-    fn default() -> Self {
-        Self {
-            owned_modules: HashSet::new(),
-            email: None,
-            activated_at: None,
-        }
+impl LicenseState {
+    /// Owned outright or on a running trial.
+    pub fn has_module(&self, module_id: &str) -> bool {
+        self.owned_modules.contains(module_id) || self.active_trial(module_id).is_some()
     }
+
+    pub fn active_trial(&self, module_id: &str) -> Option<&TrialStatus> {
+        let now = unix_now();
+        self.trials.iter().find(|t| {
+            t.module_id == module_id
+                && t.state == TrialState::Active
+                && t.started_at.is_some_and(|s| now.saturating_add(CLOCK_SKEW_SECS) >= s)
+                && t.ends_at.is_some_and(|e| now < e)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrialState {
+    Available,
+    Active,
+    Used,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialStatus {
+    pub module_id: String,
+    pub state: TrialState,
+    pub started_at: Option<u64>,
+    pub ends_at: Option<u64>,
+    pub days_remaining: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +96,17 @@ struct LicensePayload {
     modules: Vec<String>,
     email: String,
     ts: u64,
+    /// When the key should be refreshed online. Keys issued before refreshes
+    /// existed have none and never lapse.
+    #[serde(default)]
+    refresh_by: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyStatus {
+    Current,
+    Grace,
+    Expired,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,9 +118,30 @@ struct StoredLicenseKey {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredTrial {
+    module: String,
+    started_at: u64,
+    /// The launch count when the trial started.
+    started_launch: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 struct StoredLicense {
     #[serde(default)]
     keys: Vec<StoredLicenseKey>,
+    #[serde(default)]
+    trials: Vec<StoredTrial>,
+    /// Only ever counts up, one per launch.
+    #[serde(default)]
+    launches: u64,
+    /// The latest clock reading seen, so setting the clock back doesn't give
+    /// a trial its days back.
+    #[serde(default)]
+    last_seen: u64,
+    /// HMAC of the trial records keyed on this machine, so they can't be
+    /// edited or copied from another machine.
+    #[serde(default)]
+    trial_seal: Option<String>,
     // Legacy single-key fields for backward compatibility on load
     #[serde(default)]
     key: Option<String>,
@@ -69,6 +153,22 @@ struct StoredLicense {
     activated_at: Option<u64>,
 }
 
+impl StoredLicense {
+    fn migrate_legacy_key(&mut self) {
+        if !self.keys.is_empty() {
+            return;
+        }
+        if let Some(legacy_key) = self.key.take() {
+            self.keys.push(StoredLicenseKey {
+                key: legacy_key,
+                email: self.email.take(),
+                modules: self.modules.take().unwrap_or_default(),
+                activated_at: self.activated_at.take().unwrap_or(0),
+            });
+        }
+    }
+}
+
 pub fn get_module_catalog() -> Vec<ModuleInfo> {
     vec![
         ModuleInfo {
@@ -172,106 +272,220 @@ fn license_file_path() -> PathBuf {
     license_dir().join("license.json")
 }
 
-pub fn load_license_from_disk() -> LicenseState {
-    let path = license_file_path();
-    match std::fs::read_to_string(&path) {
-        Ok(content) => match serde_json::from_str::<StoredLicense>(&content) {
-            Ok(stored) => {
-                let mut modules = HashSet::new();
-                let mut email = None;
-                let mut latest_ts: u64 = 0;
-
-                // Load from multi-key format
-                for k in &stored.keys {
-                    modules.extend(k.modules.iter().cloned());
-                    if k.activated_at > latest_ts {
-                        latest_ts = k.activated_at;
-                        email = k.email.clone();
-                    }
-                }
-
-                // Backward compat: load legacy single-key fields
-                if stored.keys.is_empty() {
-                    if let Some(legacy_modules) = stored.modules {
-                        modules.extend(legacy_modules);
-                    }
-                    if let Some(ts) = stored.activated_at {
-                        latest_ts = ts;
-                    }
-                    email = stored.email;
-                }
-
-                LicenseState {
-                    owned_modules: modules,
-                    email,
-                    activated_at: if latest_ts > 0 { Some(latest_ts) } else { None },
-                }
-            }
-            Err(e) => {
-                log::warn!("Failed to parse license file: {}", e);
-                LicenseState::default()
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// HMAC-SHA256 over the trial bookkeeping, keyed on the device.
+fn trial_seal(stored: &StoredLicense, device_secret: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let mut key = [0u8; 64];
+    key[..32].copy_from_slice(&Sha256::digest(format!("observe-trials:{}", device_secret)));
+    let message = serde_json::to_vec(&(&stored.trials, stored.launches, stored.last_seen))
+        .unwrap_or_default();
+    let inner = Sha256::new()
+        .chain_update(key.map(|b| b ^ 0x36))
+        .chain_update(&message)
+        .finalize();
+    let outer = Sha256::new()
+        .chain_update(key.map(|b| b ^ 0x5c))
+        .chain_update(inner)
+        .finalize();
+    B64_URL.encode(outer)
+}
+
+/// Trial records that don't carry a valid seal are replaced by used-up
+/// trials, so editing the file never hands out days.
+fn check_trial_seal(stored: &mut StoredLicense) {
+    if stored.trials.is_empty() && stored.trial_seal.is_none() {
+        return;
+    }
+    let valid = match (device_secret(false), &stored.trial_seal) {
+        (Some(secret), Some(seal)) => *seal == trial_seal(stored, &secret),
+        _ => false,
+    };
+    if !valid {
+        log::warn!("Trial records failed verification; treating all trials as used");
+        stored.trials = used_trials();
+    }
+}
+
+fn used_trials() -> Vec<StoredTrial> {
+    TRIAL_MODULES
+        .iter()
+        .map(|m| StoredTrial {
+            module: m.to_string(),
+            started_at: 0,
+            started_launch: 0,
+        })
+        .collect()
+}
+
+/// The license file, with the legacy single-key format migrated.
+fn read_stored() -> StoredLicense {
+    let Ok(content) = std::fs::read_to_string(license_file_path()) else {
+        return StoredLicense::default();
+    };
+    match serde_json::from_str::<StoredLicense>(&content) {
+        Ok(mut stored) => {
+            stored.migrate_legacy_key();
+            check_trial_seal(&mut stored);
+            stored
+        }
+        Err(e) => {
+            log::warn!("Failed to parse license file: {}", e);
+            // Breaking the file mustn't be a way to get the trials back.
+            StoredLicense {
+                trials: used_trials(),
+                ..StoredLicense::default()
             }
-        },
-        Err(_) => LicenseState::default(),
+        }
     }
 }
 
-fn save_license(new_key: &str, new_email: Option<&str>, new_modules: &[String], new_ts: u64) -> Result<(), String> {
+fn write_stored(stored: &StoredLicense) -> Result<(), String> {
     let dir = license_dir();
     std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create dir: {}", e))?;
-    let path = license_file_path();
-
-    // Load existing keys to preserve them
-    let mut keys: Vec<StoredLicenseKey> = if let Ok(content) = std::fs::read_to_string(&path) {
-        if let Ok(existing) = serde_json::from_str::<StoredLicense>(&content) {
-            let mut k = existing.keys;
-            // Migrate legacy single-key format
-            if k.is_empty() {
-                if let Some(legacy_key) = existing.key {
-                    k.push(StoredLicenseKey {
-                        key: legacy_key,
-                        email: existing.email,
-                        modules: existing.modules.unwrap_or_default(),
-                        activated_at: existing.activated_at.unwrap_or(0),
-                    });
-                }
+    let secret = device_secret(true).ok_or("Failed to read the device id")?;
+    let mut sealed = stored.clone();
+    sealed.trial_seal = Some(trial_seal(stored, &secret));
+    let json = serde_json::to_string_pretty(&sealed)
+        .map_err(|e| format!("Failed to serialize license: {}", e))?;
+    crate::settings::write_atomic(&license_file_path(), json)
+        .map_err(|e| format!("Failed to write license: {}", e))
+}
+
+/// Copies the keys a pre-v1 settings file carried into license.json, which
+/// has held them since. Keys already there are left alone.
+pub fn import_legacy_keys(raw: &serde_json::Value) {
+    let Ok(mut legacy) = serde_json::from_value::<StoredLicense>(raw.clone()) else {
+        return;
+    };
+    legacy.migrate_legacy_key();
+    let mut stored = read_stored();
+    let before = stored.keys.len();
+    for key in legacy.keys {
+        if !stored.keys.iter().any(|k| k.key == key.key) {
+            stored.keys.push(key);
+        }
+    }
+    if stored.keys.len() == before {
+        return;
+    }
+    match write_stored(&stored) {
+        Ok(()) => log::info!("Moved {} license key(s) from the old settings file", stored.keys.len() - before),
+        Err(e) => log::warn!("Failed to move license keys from the old settings file: {}", e),
+    }
+}
+
+/// Counts this launch and notes the clock, for the trial rollback checks.
+pub fn record_launch() {
+    let mut stored = read_stored();
+    let now = unix_now();
+    if now.saturating_add(CLOCK_SKEW_SECS) < stored.last_seen {
+        log::warn!("System clock is behind the last launch");
+    }
+    stored.launches += 1;
+    stored.last_seen = stored.last_seen.max(now);
+    if let Err(e) = write_stored(&stored) {
+        log::warn!("Failed to record launch: {}", e);
+    }
+}
+
+/// Builds the license from the stored keys. Each key's signature is checked
+/// again, so the modules listed in the file itself are never trusted.
+fn license_state(stored_license: &StoredLicense, now: u64) -> LicenseState {
+    let mut state = LicenseState::default();
+    let mut latest_ts: u64 = 0;
+
+    for stored in &stored_license.keys {
+        let payload = match verify_key(&stored.key) {
+            Ok(payload) => payload,
+            Err(e) => {
+                log::warn!("Ignoring stored license key: {}", e);
+                continue;
+            }
+        };
+        match key_status(&payload, now, stored_license.last_seen) {
+            KeyStatus::Current => {}
+            KeyStatus::Grace => state.needs_refresh = true,
+            KeyStatus::Expired => {
+                state.expired_modules.extend(payload.modules);
+                continue;
             }
-            k
-        } else {
-            Vec::new()
         }
-    } else {
-        Vec::new()
-    };
+        if let Some(by) = payload.refresh_by {
+            state.refresh_by = Some(state.refresh_by.map_or(by, |r| r.min(by)));
+        }
+        if payload.ts > latest_ts {
+            latest_ts = payload.ts;
+            state.email = Some(payload.email);
+        }
+        state.owned_modules.extend(payload.modules);
+    }
 
-    // Replace if same key exists, otherwise append
-    let existing_idx = keys.iter().position(|k| k.key == new_key);
-    let entry = StoredLicenseKey {
-        key: new_key.to_string(),
-        email: new_email.map(String::from),
-        modules: new_modules.to_vec(),
-        activated_at: new_ts,
-    };
-    if let Some(idx) = existing_idx {
-        keys[idx] = entry;
+    state.expired_modules.retain(|m| !state.owned_modules.contains(m));
+    state.activated_at = if latest_ts > 0 { Some(latest_ts) } else { None };
+    state.trials = trial_statuses(stored_license, now);
+    state
+}
+
+pub fn load_license_from_disk() -> LicenseState {
+    license_state(&read_stored(), unix_now())
+}
+
+fn trial_statuses(stored: &StoredLicense, now: u64) -> Vec<TrialStatus> {
+    let clock = now.max(stored.last_seen);
+    TRIAL_MODULES
+        .iter()
+        .map(|&module| {
+            let Some(trial) = stored.trials.iter().find(|t| t.module == module) else {
+                return TrialStatus {
+                    module_id: module.to_string(),
+                    state: TrialState::Available,
+                    started_at: None,
+                    ends_at: None,
+                    days_remaining: 0,
+                };
+            };
+            let ends_at = trial.started_at.saturating_add(TRIAL_SECS);
+            // The clock went back past the start, or the file's launch count did
+            let rolled_back = now.saturating_add(CLOCK_SKEW_SECS) < trial.started_at
+                || stored.launches < trial.started_launch;
+            let active = !rolled_back && clock < ends_at;
+            TrialStatus {
+                module_id: module.to_string(),
+                state: if active { TrialState::Active } else { TrialState::Used },
+                started_at: Some(trial.started_at),
+                ends_at: Some(ends_at),
+                days_remaining: if active {
+                    (ends_at - clock).div_ceil(DAY_SECS) as u32
+                } else {
+                    0
+                },
+            }
+        })
+        .collect()
+}
+
+/// Stores `entry`, replacing the key `replaces` (or one equal to the new key)
+/// and keeping the rest.
+fn save_license(entry: StoredLicenseKey, replaces: Option<&str>) -> Result<(), String> {
+    let mut stored = read_stored();
+    let old_key = replaces.unwrap_or(&entry.key).to_string();
+    if let Some(idx) = stored.keys.iter().position(|k| k.key == old_key) {
+        stored.keys[idx] = entry;
     } else {
-        keys.push(entry);
+        stored.keys.push(entry);
     }
-
-    let stored = StoredLicense {
-        keys,
-        key: None,
-        email: None,
-        modules: None,
-        activated_at: None,
-    };
-    let json = serde_json::to_string_pretty(&stored)
-        .map_err(|e| format!("Failed to serialize license: {}", e))?;
-    std::fs::write(&path, json).map_err(|e| format!("Failed to write license: {}", e))?;
-    Ok(())
+    write_stored(&stored)
 }
 
-pub fn activate_license(key: &str) -> Result<LicenseState, String> {
+/// Checks a key's signature and returns what it grants.
+fn verify_key(key: &str) -> Result<LicensePayload, String> {
     let parts: Vec<&str> = key.split('.').collect();
     if parts.len() != 2 {
         return Err("Invalid license key format".into());
@@ -312,14 +526,42 @@ pub fn activate_license(key: &str) -> Result<LicenseState, String> {
         .verify(&payload_bytes, &signature)
         .map_err(|_| "Invalid license key: signature verification failed")?;
 
-    let payload: LicensePayload = serde_json::from_slice(&payload_bytes)
-        .map_err(|_| "Invalid license key: bad payload")?;
+    serde_json::from_slice(&payload_bytes).map_err(|_| "Invalid license key: bad payload".into())
+}
 
-    Ok(LicenseState {
-        owned_modules: payload.modules.into_iter().collect(),
-        email: Some(payload.email),
-        activated_at: Some(payload.ts),
-    })
+/// `last_seen` is the latest clock reading on record; setting the clock
+/// back to before it doesn't buy a key more time.
+fn key_status(payload: &LicensePayload, now: u64, last_seen: u64) -> KeyStatus {
+    // A clock set before the key was issued would otherwise stretch the
+    // grace period forever.
+    if payload.ts > now.saturating_add(CLOCK_SKEW_SECS) {
+        return KeyStatus::Expired;
+    }
+    let clock = now.max(last_seen);
+    match payload.refresh_by {
+        None => KeyStatus::Current,
+        Some(by) if clock <= by => KeyStatus::Current,
+        Some(by) if clock <= by.saturating_add(REFRESH_GRACE_SECS) => KeyStatus::Grace,
+        Some(_) => KeyStatus::Expired,
+    }
+}
+
+/// Verifies a new key and stores it in place of `replaces`, or alongside the
+/// existing keys.
+fn install_key(key: &str, replaces: Option<&str>) -> Result<(), String> {
+    let payload = verify_key(key)?;
+    if key_status(&payload, unix_now(), read_stored().last_seen) == KeyStatus::Expired {
+        return Err(t("license.expired", &[]));
+    }
+    save_license(
+        StoredLicenseKey {
+            key: key.to_string(),
+            email: Some(payload.email),
+            modules: payload.modules,
+            activated_at: payload.ts,
+        },
+        replaces,
+    )
 }
 
 #[allow(dead_code)]
@@ -332,17 +574,119 @@ pub async fn require_module(
     module_id: &str,
 ) -> Result<(), String> {
     let state = license.read().await;
-    if state.owned_modules.contains(module_id) {
-        Ok(())
+    if state.has_module(module_id) {
+        return Ok(());
+    }
+    let name = module_name(module_id);
+    if state.expired_modules.contains(module_id) {
+        Err(t("license.needs_refresh", &[("module", &name)]))
+    } else if state.trials.iter().any(|t| t.module_id == module_id && t.state != TrialState::Available) {
+        Err(t("license.trial_ended", &[("module", &name)]))
     } else {
-        let catalog = get_module_catalog();
-        let name = catalog
-            .iter()
-            .find(|m| m.id == module_id)
-            .map(|m| m.name.as_str())
-            .unwrap_or(module_id);
-        Err(format!("Module '{}' not purchased", name))
+        Err(t("license.not_purchased", &[("module", &name)]))
+    }
+}
+
+fn module_name(module_id: &str) -> String {
+    get_module_catalog()
+        .into_iter()
+        .find(|m| m.id == module_id)
+        .map(|m| m.name)
+        .unwrap_or_else(|| module_id.to_string())
+}
+
+// --- Refresh ---
+
+#[derive(Deserialize)]
+struct RefreshResponse {
+    #[serde(default)]
+    key: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+async fn fetch_refreshed_key(
+    client: &reqwest::Client,
+    key: &str,
+    fingerprint: &str,
+) -> Result<String, String> {
+    let resp = client
+        .post(format!("{}/refresh-key", LICENSE_API_BASE))
+        .json(&serde_json::json!({ "key": key, "fingerprint": fingerprint }))
+        .timeout(std::time::Duration::from_secs(15))
+        .send()
+        .await
+        .map_err(|e| t("license.server_unreachable", &[("error", &e)]))?;
+    let status = resp.status();
+    let body: RefreshResponse = resp
+        .json()
+        .await
+        .map_err(|e| t("license.invalid_response", &[("error", &e)]))?;
+    if !status.is_success() {
+        return Err(body
+            .error
+            .unwrap_or_else(|| t("license.refresh_failed", &[("status", &status)])));
     }
+    body.key.ok_or_else(|| t("license.no_key_returned", &[]))
+}
+
+/// Trades each stored key for a freshly signed one. Keys the server doesn't
+/// renew are kept as they are.
+pub async fn refresh_keys(license: &SharedLicenseState) -> Result<LicenseState, String> {
+    let keys = read_stored().keys;
+    if keys.is_empty() {
+        return Err(t("license.no_keys", &[]));
+    }
+    let fingerprint = get_device_fingerprint()?;
+    let client = reqwest::Client::new();
+
+    let mut refreshed = 0;
+    let mut last_error = None;
+    for stored in &keys {
+        let result = match fetch_refreshed_key(&client, &stored.key, &fingerprint).await {
+            Ok(new_key) => install_key(&new_key, Some(&stored.key)),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => refreshed += 1,
+            Err(e) => {
+                log::warn!("License key refresh failed: {}", e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    let new_state = load_license_from_disk();
+    *license.write().await = new_state.clone();
+    match last_error {
+        Some(e) if refreshed == 0 => Err(e),
+        _ => Ok(new_state),
+    }
+}
+
+fn refresh_due(state: &LicenseState) -> bool {
+    state.needs_refresh
+        || !state.expired_modules.is_empty()
+        || state
+            .refresh_by
+            .is_some_and(|by| by <= unix_now().saturating_add(REFRESH_AHEAD_SECS))
+}
+
+/// Refreshes at launch when a key is due. Being offline only logs; the
+/// grace period covers it.
+pub fn start_background_refresh(license: SharedLicenseState) {
+    tauri::async_runtime::spawn(async move {
+        if !refresh_due(&*license.read().await) {
+            return;
+        }
+        match refresh_keys(&license).await {
+            Ok(state) => log::info!(
+                "License refreshed: {} modules owned",
+                state.owned_modules.len()
+            ),
+            Err(e) => log::warn!("License refresh skipped: {}", e),
+        }
+    });
 }
 
 // --- Device Fingerprint ---
@@ -404,7 +748,7 @@ fn read_machine_guid() -> Option<String> {
     None
 }
 
-fn get_or_create_fallback_device_id() -> Result<String, String> {
+fn get_or_create_fallback_device_id(create: bool) -> Result<String, String> {
     let dir = license_dir();
     let path = dir.join("device_id");
     if let Ok(id) = std::fs::read_to_string(&path) {
@@ -413,17 +757,26 @@ fn get_or_create_fallback_device_id() -> Result<String, String> {
             return Ok(id);
         }
     }
+    if !create {
+        return Err("No device id yet".into());
+    }
     let id = uuid::Uuid::new_v4().to_string();
     std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create dir: {}", e))?;
     std::fs::write(&path, &id).map_err(|e| format!("Failed to write device_id: {}", e))?;
     Ok(id)
 }
 
+/// The machine GUID, or a generated id where there is none. With `create`
+/// false nothing is written, and a missing id is None.
+fn device_secret(create: bool) -> Option<String> {
+    read_machine_guid().or_else(|| get_or_create_fallback_device_id(create).ok())
+}
+
 #[tauri::command]
 pub fn get_device_fingerprint() -> Result<String, String> {
     let source = match read_machine_guid() {
         Some(guid) => guid,
-        None => get_or_create_fallback_device_id()?,
+        None => get_or_create_fallback_device_id(true)?,
     };
 
     use sha2::{Digest, Sha256};
@@ -471,7 +824,48 @@ pub async fn get_store_catalog(
 pub async fn get_license_state(
     license: tauri::State<'_, SharedLicenseState>,
 ) -> Result<LicenseState, String> {
-    let state = license.read().await;
+    let stored = tokio::task::spawn_blocking(read_stored)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?;
+    let mut state = license.read().await.clone();
+    // Days remaining count down while the app runs
+    state.trials = trial_statuses(&stored, unix_now());
+    Ok(state)
+}
+
+/// Grants a trial module for 7 days, once per machine.
+#[tauri::command]
+pub async fn start_trial(
+    license: tauri::State<'_, SharedLicenseState>,
+    module: String,
+) -> Result<LicenseState, String> {
+    let name = module_name(&module);
+    if !TRIAL_MODULES.contains(&module.as_str()) {
+        return Err(format!("No trial is offered for '{}'", name));
+    }
+
+    let mut state = license.write().await;
+    if state.owned_modules.contains(&module) {
+        return Err(format!("Module '{}' is already owned", name));
+    }
+    let mut stored = read_stored();
+    if stored.trials.iter().any(|t| t.module == module) {
+        return Err(format!("The '{}' trial was already used on this machine", name));
+    }
+    let now = unix_now();
+    if now.saturating_add(CLOCK_SKEW_SECS) < stored.last_seen {
+        return Err("The system clock is behind the last launch. Correct it before starting a trial.".into());
+    }
+
+    stored.last_seen = stored.last_seen.max(now);
+    stored.trials.push(StoredTrial {
+        module,
+        started_at: now,
+        started_launch: stored.launches,
+    });
+    write_stored(&stored)?;
+
+    *state = license_state(&stored, now);
     Ok(state.clone())
 }
 
@@ -480,56 +874,91 @@ pub async fn activate_license_key(
     license: tauri::State<'_, SharedLicenseState>,
     key: String,
 ) -> Result<LicenseState, String> {
-    let new_state = activate_license(&key)?;
-
     // Save the new key (preserves existing keys on disk)
-    let new_modules: Vec<String> = new_state.owned_modules.iter().cloned().collect();
-    save_license(&key, new_state.email.as_deref(), &new_modules, new_state.activated_at.unwrap_or(0))?;
+    install_key(&key, None)?;
 
-    // Merge new modules into existing in-memory state
     let mut state = license.write().await;
-    state.owned_modules.extend(new_state.owned_modules);
-    if new_state.email.is_some() {
-        state.email = new_state.email;
-    }
-    if new_state.activated_at > state.activated_at {
-        state.activated_at = new_state.activated_at;
-    }
-
+    *state = load_license_from_disk();
     Ok(state.clone())
 }
 
+/// Renews the stored keys online. Offline, keys past their refresh-by date
+/// keep working for the grace period.
+#[tauri::command]
+pub async fn refresh_license(
+    license: tauri::State<'_, SharedLicenseState>,
+) -> Result<LicenseState, String> {
+    refresh_keys(&license).await
+}
+
 #[tauri::command]
 pub fn get_stored_license_keys() -> Result<Vec<String>, String> {
-    let path = license_file_path();
-    match std::fs::read_to_string(&path) {
-        Ok(content) => match serde_json::from_str::<StoredLicense>(&content) {
-            Ok(stored) => {
-                let mut keys: Vec<String> = stored.keys.iter().map(|k| k.key.clone()).collect();
-                if keys.is_empty() {
-                    if let Some(legacy_key) = stored.key {
-                        keys.push(legacy_key);
-                    }
-                }
-                Ok(keys)
-            }
-            Err(_) => Ok(Vec::new()),
-        },
-        Err(_) => Ok(Vec::new()),
-    }
+    Ok(read_stored().keys.into_iter().map(|k| k.key).collect())
 }
 
 #[tauri::command]
 pub async fn deactivate_license(
     license: tauri::State<'_, SharedLicenseState>,
 ) -> Result<(), String> {
-    let path = license_file_path();
-    if path.exists() {
-        std::fs::remove_file(&path).map_err(|e| format!("Failed to remove license: {}", e))?;
-    }
+    // Trials stay on record so deactivating can't reset them
+    let mut stored = read_stored();
+    stored.keys.clear();
+    write_stored(&stored)?;
 
     let mut state = license.write().await;
-    *state = LicenseState::default();
+    *state = license_state(&stored, unix_now());
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISSUED: u64 = 1_700_000_000;
+
+    fn payload(refresh_by: Option<u64>) -> LicensePayload {
+        LicensePayload {
+            modules: vec!["presets".into()],
+            email: "a@example.com".into(),
+            ts: ISSUED,
+            refresh_by,
+        }
+    }
+
+    fn encode_key(payload_json: &str) -> String {
+        format!("{}.{}", B64_URL.encode(payload_json), B64_URL.encode([7u8; 64]))
+    }
+
+    #[test]
+    fn tampered_payload_is_rejected() {
+        let key = encode_key(r#"{"modules":["presets","ducking","audio-fx"],"email":"a@example.com","ts":1700000000}"#);
+        assert_eq!(
+            verify_key(&key).err().as_deref(),
+            Some("Invalid license key: signature verification failed")
+        );
+        assert!(verify_key("not-a-key").is_err());
+    }
+
+    #[test]
+    fn grace_period_then_expiry() {
+        let by = ISSUED + 30 * DAY_SECS;
+        let p = payload(Some(by));
+        assert_eq!(key_status(&p, by, 0), KeyStatus::Current);
+        assert_eq!(key_status(&p, by + 1, 0), KeyStatus::Grace);
+        assert_eq!(key_status(&p, by + REFRESH_GRACE_SECS, 0), KeyStatus::Grace);
+        assert_eq!(key_status(&p, by + REFRESH_GRACE_SECS + 1, 0), KeyStatus::Expired);
+        assert_eq!(key_status(&payload(None), u64::MAX / 2, 0), KeyStatus::Current);
+    }
+
+    #[test]
+    fn clock_skew() {
+        let p = payload(Some(ISSUED + 30 * DAY_SECS));
+        // A little behind the issuer is fine, further is not.
+        assert_eq!(key_status(&p, ISSUED - CLOCK_SKEW_SECS, 0), KeyStatus::Current);
+        assert_eq!(key_status(&p, ISSUED - CLOCK_SKEW_SECS - 1, 0), KeyStatus::Expired);
+        // Winding the clock back below the last reading doesn't undo expiry.
+        let expired_at = ISSUED + 30 * DAY_SECS + REFRESH_GRACE_SECS + 1;
+        assert_eq!(key_status(&p, ISSUED + DAY_SECS, expired_at), KeyStatus::Expired);
+    }
+}
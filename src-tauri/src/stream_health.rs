@@ -0,0 +1,215 @@
+use crate::obs_state::SharedObsState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use tauri::Emitter;
+
+/// Stats arrive every 5 s, so this is the last minute.
+const WINDOW: usize = 12;
+
+const DEGRADED_DROP_PERCENT: f64 = 1.0;
+const BAD_DROP_PERCENT: f64 = 5.0;
+const DEGRADED_CONGESTION: f64 = 0.2;
+const BAD_CONGESTION: f64 = 0.5;
+/// Share of the frame budget average render time may use before it counts
+/// as degraded; over the full budget is bad.
+const DEGRADED_RENDER_SHARE: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthLevel {
+    #[default]
+    Good,
+    Degraded,
+    Bad,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamHealth {
+    pub level: HealthLevel,
+    /// Frames missed by rendering or encoding over the window.
+    pub dropped_percent: f64,
+    /// Frames the stream output dropped for network reasons.
+    pub network_dropped_percent: f64,
+    /// Average of OBS's outputCongestion (0-1) over the window.
+    pub congestion: f64,
+    pub avg_render_ms: f64,
+    pub frame_budget_ms: f64,
+    pub sample_count: usize,
+}
+
+#[derive(Clone, Copy)]
+struct StatsSample {
+    render_skipped: u64,
+    render_total: u64,
+    output_skipped: u64,
+    output_total: u64,
+    render_ms: f64,
+}
+
+#[derive(Clone, Copy)]
+struct StreamSample {
+    skipped: u64,
+    total: u64,
+    congestion: f64,
+}
+
+/// Rolling window of GetStats and GetStreamStatus samples, owned by the
+/// websocket loop.
+#[derive(Default)]
+pub struct HealthWatchdog {
+    stats: VecDeque<StatsSample>,
+    stream: VecDeque<StreamSample>,
+}
+
+fn push<T>(window: &mut VecDeque<T>, sample: T) {
+    window.push_back(sample);
+    while window.len() > WINDOW {
+        window.pop_front();
+    }
+}
+
+fn percent(skipped: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        skipped as f64 / total as f64 * 100.0
+    }
+}
+
+impl HealthWatchdog {
+    fn push_stats(&mut self, resp: &Value) {
+        let sample = StatsSample {
+            render_skipped: resp["renderSkippedFrames"].as_u64().unwrap_or(0),
+            render_total: resp["renderTotalFrames"].as_u64().unwrap_or(0),
+            output_skipped: resp["outputSkippedFrames"].as_u64().unwrap_or(0),
+            output_total: resp["outputTotalFrames"].as_u64().unwrap_or(0),
+            render_ms: resp["averageFrameRenderTime"].as_f64().unwrap_or(0.0),
+        };
+        // Counters restart with OBS; start the window over.
+        if self.stats.back().map_or(false, |l| sample.render_total < l.render_total) {
+            self.stats.clear();
+        }
+        push(&mut self.stats, sample);
+    }
+
+    fn push_stream_status(&mut self, resp: &Value) {
+        let sample = StreamSample {
+            skipped: resp["outputSkippedFrames"].as_u64().unwrap_or(0),
+            total: resp["outputTotalFrames"].as_u64().unwrap_or(0),
+            congestion: resp["outputCongestion"].as_f64().unwrap_or(0.0),
+        };
+        if self.stream.back().map_or(false, |l| sample.total < l.total) {
+            self.stream.clear();
+        }
+        push(&mut self.stream, sample);
+    }
+
+    /// Called while not streaming so the next stream starts clean.
+    pub fn reset_stream(&mut self) {
+        self.stream.clear();
+    }
+
+    fn evaluate(&self, frame_budget_ms: f64) -> StreamHealth {
+        let mut health = StreamHealth {
+            frame_budget_ms,
+            sample_count: self.stats.len(),
+            ..Default::default()
+        };
+        if let (Some(first), Some(last)) = (self.stats.front(), self.stats.back()) {
+            let render = percent(
+                last.render_skipped.saturating_sub(first.render_skipped),
+                last.render_total.saturating_sub(first.render_total),
+            );
+            let output = percent(
+                last.output_skipped.saturating_sub(first.output_skipped),
+                last.output_total.saturating_sub(first.output_total),
+            );
+            health.dropped_percent = render.max(output);
+            health.avg_render_ms =
+                self.stats.iter().map(|s| s.render_ms).sum::<f64>() / self.stats.len() as f64;
+        }
+        if let (Some(first), Some(last)) = (self.stream.front(), self.stream.back()) {
+            health.network_dropped_percent = percent(
+                last.skipped.saturating_sub(first.skipped),
+                last.total.saturating_sub(first.total),
+            );
+            health.congestion =
+                self.stream.iter().map(|s| s.congestion).sum::<f64>() / self.stream.len() as f64;
+        }
+
+        let dropped = health.dropped_percent.max(health.network_dropped_percent);
+        let render_share = if frame_budget_ms > 0.0 {
+            health.avg_render_ms / frame_budget_ms
+        } else {
+            0.0
+        };
+        health.level = if dropped >= BAD_DROP_PERCENT
+            || health.congestion >= BAD_CONGESTION
+            || render_share > 1.0
+        {
+            HealthLevel::Bad
+        } else if dropped >= DEGRADED_DROP_PERCENT
+            || health.congestion >= DEGRADED_CONGESTION
+            || render_share > DEGRADED_RENDER_SHARE
+        {
+            HealthLevel::Degraded
+        } else {
+            HealthLevel::Good
+        };
+        health
+    }
+}
+
+async fn publish(watchdog: &HealthWatchdog, state: &SharedObsState, app: &tauri::AppHandle) {
+    let (health, changed) = {
+        let mut s = state.write().await;
+        let v = &s.video_settings;
+        let budget = if v.fps_numerator > 0 {
+            1000.0 * v.fps_denominator as f64 / v.fps_numerator as f64
+        } else {
+            0.0
+        };
+        let health = watchdog.evaluate(budget);
+        let changed = health.level != s.stream_health.level;
+        s.stream_health = health.clone();
+        (health, changed)
+    };
+    if changed {
+        let _ = app.emit("obs://stream-health", &health);
+    }
+}
+
+/// Feeds one GetStats response.
+pub async fn on_stats(
+    watchdog: &mut HealthWatchdog,
+    data: &Value,
+    state: &SharedObsState,
+    app: &tauri::AppHandle,
+) {
+    if let Some(resp) = data.get("responseData") {
+        watchdog.push_stats(resp);
+        publish(watchdog, state, app).await;
+    }
+}
+
+/// Feeds one GetStreamStatus response.
+pub async fn on_stream_status(
+    watchdog: &mut HealthWatchdog,
+    data: &Value,
+    state: &SharedObsState,
+    app: &tauri::AppHandle,
+) {
+    if let Some(resp) = data.get("responseData") {
+        watchdog.push_stream_status(resp);
+        publish(watchdog, state, app).await;
+    }
+}
+
+#[tauri::command]
+pub async fn get_stream_health(
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<StreamHealth, String> {
+    Ok(obs_state.read().await.stream_health.clone())
+}
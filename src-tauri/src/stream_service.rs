@@ -0,0 +1,153 @@
+use crate::obs_state::StreamServiceSettings;
+use crate::obs_websocket::ObsHandle;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamPreset {
+    pub id: String,
+    pub platform: String,
+    pub label: String,
+    pub server: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamDestinationTest {
+    pub server: String,
+    pub host: String,
+    pub port: u16,
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+const PRESETS: &[(&str, &str, &str, &str)] = &[
+    ("twitch-auto", "Twitch", "Auto (recommended)", "rtmp://live.twitch.tv/app"),
+    ("twitch-global-rtmps", "Twitch", "Global (RTMPS)", "rtmps://ingest.global-contribute.live-video.net:443/app"),
+    ("twitch-us-east", "Twitch", "US East: Ashburn, VA", "rtmp://iad05.contribute.live-video.net/app"),
+    ("twitch-us-west", "Twitch", "US West: San Francisco, CA", "rtmp://sfo06.contribute.live-video.net/app"),
+    ("twitch-eu-central", "Twitch", "Europe: Frankfurt, DE", "rtmp://fra05.contribute.live-video.net/app"),
+    ("twitch-eu-west", "Twitch", "Europe: London, UK", "rtmp://lhr08.contribute.live-video.net/app"),
+    ("youtube-primary", "YouTube", "Primary", "rtmp://a.rtmp.youtube.com/live2"),
+    ("youtube-backup", "YouTube", "Backup", "rtmp://b.rtmp.youtube.com/live2?backup=1"),
+    ("youtube-rtmps", "YouTube", "Primary (RTMPS)", "rtmps://a.rtmps.youtube.com:443/live2"),
+    ("kick", "Kick", "Global (RTMPS)", "rtmps://fa723fc1b171.global-contribute.live-video.net:443/app"),
+];
+
+pub fn presets() -> Vec<StreamPreset> {
+    PRESETS
+        .iter()
+        .map(|(id, platform, label, server)| StreamPreset {
+            id: id.to_string(),
+            platform: platform.to_string(),
+            label: label.to_string(),
+            server: server.to_string(),
+        })
+        .collect()
+}
+
+pub fn find_preset(id: &str) -> Option<StreamPreset> {
+    presets().into_iter().find(|p| p.id == id)
+}
+
+pub fn settings_from_response(v: &Value) -> StreamServiceSettings {
+    let settings = &v["streamServiceSettings"];
+    let key_length = settings["key"].as_str().map(|k| k.chars().count()).unwrap_or(0);
+    StreamServiceSettings {
+        service_type: v["streamServiceType"].as_str().unwrap_or("").to_string(),
+        server: settings["server"].as_str().unwrap_or("").to_string(),
+        key_set: key_length > 0,
+        key_length,
+    }
+}
+
+pub async fn fetch(conn: &ObsHandle) -> Result<StreamServiceSettings, String> {
+    let data = conn.send_request("GetStreamServiceSettings", None).await?;
+    Ok(settings_from_response(&data))
+}
+
+/// Applies a custom RTMP destination. When `key` is None the current key is kept.
+pub async fn apply(conn: &ObsHandle, server: &str, key: Option<&str>) -> Result<(), String> {
+    if parse_host_port(server).is_none() {
+        return Err(format!("Invalid RTMP server URL: {}", server));
+    }
+    let key = match key {
+        Some(k) => k.to_string(),
+        None => {
+            let current = conn.send_request("GetStreamServiceSettings", None).await?;
+            current["streamServiceSettings"]["key"]
+                .as_str()
+                .unwrap_or("")
+                .to_string()
+        }
+    };
+    conn.send_request(
+        "SetStreamServiceSettings",
+        Some(json!({
+            "streamServiceType": "rtmp_custom",
+            "streamServiceSettings": {
+                "server": server,
+                "key": key,
+                "use_auth": false,
+            },
+        })),
+    )
+    .await?;
+    Ok(())
+}
+
+pub fn parse_host_port(server: &str) -> Option<(String, u16)> {
+    let (scheme, rest) = server.trim().split_once("://")?;
+    let default_port = match scheme.to_lowercase().as_str() {
+        "rtmp" => 1935,
+        "rtmps" => 443,
+        _ => return None,
+    };
+    let authority = rest.split(['/', '?']).next()?;
+    if authority.is_empty() {
+        return None;
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) => Some((host.to_string(), port.parse().ok()?)),
+        None => Some((authority.to_string(), default_port)),
+    }
+}
+
+pub async fn test_destination(server: &str) -> StreamDestinationTest {
+    let Some((host, port)) = parse_host_port(server) else {
+        return StreamDestinationTest {
+            server: server.to_string(),
+            host: String::new(),
+            port: 0,
+            reachable: false,
+            latency_ms: None,
+            error: Some("Not an RTMP/RTMPS URL".into()),
+        };
+    };
+
+    let start = Instant::now();
+    let result = tokio::time::timeout(
+        CONNECT_TIMEOUT,
+        tokio::net::TcpStream::connect((host.as_str(), port)),
+    )
+    .await;
+    let (reachable, latency_ms, error) = match result {
+        Ok(Ok(_)) => (true, Some(start.elapsed().as_millis() as u64), None),
+        Ok(Err(e)) => (false, None, Some(format!("Connection failed: {}", e))),
+        Err(_) => (false, None, Some(format!("Timed out after {}s", CONNECT_TIMEOUT.as_secs()))),
+    };
+
+    StreamDestinationTest {
+        server: server.to_string(),
+        host,
+        port,
+        reachable,
+        latency_ms,
+        error,
+    }
+}
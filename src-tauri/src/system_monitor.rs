@@ -9,6 +9,85 @@ pub struct SystemResources {
     pub memory_usage_percent: f32,
     pub disk_free_gb: f64,
     pub disk_total_gb: f64,
+    /// 0 when sysinfo can't tell.
+    pub physical_cores: u32,
+    pub gpus: Vec<GpuInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuInfo {
+    /// "nvidia", "amd", "intel", or "other".
+    pub vendor: String,
+    pub model: String,
+}
+
+#[cfg(windows)]
+fn gpu_vendor(model: &str) -> &'static str {
+    let lower = model.to_lowercase();
+    if lower.contains("nvidia") || lower.contains("geforce") || lower.contains("quadro") {
+        "nvidia"
+    } else if lower.contains("amd") || lower.contains("radeon") || lower.contains("ati ") {
+        "amd"
+    } else if lower.contains("intel") {
+        "intel"
+    } else {
+        "other"
+    }
+}
+
+/// PCI vendor ids, which are more reliable than the adapter's name.
+#[cfg(windows)]
+fn pci_vendor(vendor_id: u32) -> Option<&'static str> {
+    match vendor_id {
+        0x10DE => Some("nvidia"),
+        0x1002 | 0x1022 => Some("amd"),
+        0x8086 => Some("intel"),
+        _ => None,
+    }
+}
+
+/// Every hardware adapter DXGI knows of, driving a monitor or not, so a
+/// laptop's discrete GPU shows up too. Software renderers are left out.
+#[cfg(windows)]
+pub fn enumerate_gpus() -> Vec<GpuInfo> {
+    use windows::Win32::Graphics::Dxgi::{CreateDXGIFactory1, IDXGIFactory1, DXGI_ADAPTER_FLAG_SOFTWARE};
+
+    let factory: IDXGIFactory1 = match unsafe { CreateDXGIFactory1() } {
+        Ok(factory) => factory,
+        Err(e) => {
+            log::warn!("Failed to create DXGI factory: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut gpus: Vec<GpuInfo> = Vec::new();
+    // EnumAdapters1 fails with DXGI_ERROR_NOT_FOUND past the last adapter.
+    for idx in 0u32.. {
+        let Ok(adapter) = (unsafe { factory.EnumAdapters1(idx) }) else {
+            break;
+        };
+        let Ok(desc) = (unsafe { adapter.GetDesc1() }) else {
+            continue;
+        };
+        if desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0 {
+            continue;
+        }
+        let len = desc.Description.iter().position(|&c| c == 0).unwrap_or(desc.Description.len());
+        let model = String::from_utf16_lossy(&desc.Description[..len]);
+        if model.is_empty() || gpus.iter().any(|g| g.model == model) {
+            continue;
+        }
+        gpus.push(GpuInfo {
+            vendor: pci_vendor(desc.VendorId).unwrap_or_else(|| gpu_vendor(&model)).to_string(),
+            model,
+        });
+    }
+    gpus
+}
+
+#[cfg(not(windows))]
+pub fn enumerate_gpus() -> Vec<GpuInfo> {
+    Vec::new()
 }
 
 pub fn get_system_resources() -> SystemResources {
@@ -53,6 +132,8 @@ pub fn get_system_resources() -> SystemResources {
         memory_usage_percent: mem_percent,
         disk_free_gb: disk_free,
         disk_total_gb: disk_total,
+        physical_cores: System::physical_core_count().unwrap_or(0) as u32,
+        gpus: enumerate_gpus(),
     }
 }
 
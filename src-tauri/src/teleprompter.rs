@@ -0,0 +1,529 @@
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tokio::sync::Mutex;
+
+pub type SharedTeleprompter = Arc<Mutex<Teleprompter>>;
+
+/// One text update per tick at most, so OBS sees no more than ~4 a second.
+const TICK: Duration = Duration::from_millis(250);
+const DEFAULT_VISIBLE_LINES: usize = 5;
+const WRAP_COLUMNS: usize = 40;
+const MIN_WPM: f64 = 40.0;
+const MAX_WPM: f64 = 400.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Marker {
+    pub index: usize,
+    pub label: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptInfo {
+    pub lines: usize,
+    pub words: usize,
+    pub markers: Vec<Marker>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct ProgressPayload {
+    source_name: String,
+    line: usize,
+    total_lines: usize,
+    word: usize,
+    total_words: usize,
+    percent: f64,
+    marker: Option<usize>,
+    paused: bool,
+    finished: bool,
+}
+
+struct Script {
+    lines: Vec<String>,
+    /// Index of the first word of each line.
+    line_starts: Vec<usize>,
+    total_words: usize,
+    markers: Vec<Marker>,
+}
+
+impl Script {
+    /// Wraps the text to the prompter width. A `# Heading` line is a marker:
+    /// it's shown without the `#` and can be jumped to. Other lines starting
+    /// with `#` ("#1", "#hashtag") are ordinary text.
+    fn parse(text: &str) -> Self {
+        let mut lines: Vec<String> = Vec::new();
+        let mut markers = Vec::new();
+        for raw in text.lines() {
+            let mut raw = raw.trim_end();
+            if let Some(label) = raw.strip_prefix("# ").map(str::trim).filter(|l| !l.is_empty()) {
+                markers.push(Marker {
+                    index: markers.len(),
+                    label: label.to_string(),
+                    line: lines.len(),
+                });
+                raw = label;
+            }
+            let mut current = String::new();
+            for word in raw.split_whitespace() {
+                if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > WRAP_COLUMNS {
+                    lines.push(std::mem::take(&mut current));
+                }
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.push_str(word);
+            }
+            lines.push(current);
+        }
+
+        let mut line_starts = Vec::with_capacity(lines.len());
+        let mut total_words = 0;
+        for line in &lines {
+            line_starts.push(total_words);
+            total_words += line.split_whitespace().count();
+        }
+        Self {
+            lines,
+            line_starts,
+            total_words,
+            markers,
+        }
+    }
+
+    fn info(&self) -> ScriptInfo {
+        ScriptInfo {
+            lines: self.lines.len(),
+            words: self.total_words,
+            markers: self.markers.clone(),
+        }
+    }
+
+    fn line_at(&self, word: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= word).saturating_sub(1)
+    }
+
+    fn marker_at(&self, line: usize) -> Option<usize> {
+        self.markers.iter().rev().find(|m| m.line <= line).map(|m| m.index)
+    }
+
+    /// The current line sits second from the top, with one line of context
+    /// above it.
+    fn window(&self, line: usize, visible: usize) -> String {
+        let start = line.saturating_sub(1);
+        let end = (start + visible).min(self.lines.len());
+        self.lines[start.min(end)..end].join("\n")
+    }
+}
+
+struct Run {
+    id: u64,
+    source_name: String,
+    wpm: f64,
+    visible_lines: usize,
+    pause_on_ptt: bool,
+    /// Position in words; fractional so slow speeds still advance.
+    position: f64,
+    paused: bool,
+    ptt_held: bool,
+    /// Reached the end of the script. The last lines stay up until the
+    /// prompter is stopped or sent back with a marker jump.
+    finished: bool,
+    last_tick: Instant,
+    original_text: Option<String>,
+}
+
+impl Run {
+    fn is_paused(&self) -> bool {
+        self.paused || (self.pause_on_ptt && self.ptt_held)
+    }
+}
+
+#[derive(Default)]
+pub struct Teleprompter {
+    script: Option<Script>,
+    run: Option<Run>,
+    next_id: u64,
+}
+
+/// Rejects NaN and infinities, which `clamp` would pass straight through.
+fn checked_wpm(words_per_minute: f64) -> Result<f64, String> {
+    if words_per_minute.is_finite() {
+        Ok(words_per_minute.clamp(MIN_WPM, MAX_WPM))
+    } else {
+        Err(format!("Invalid speed: {} words per minute", words_per_minute))
+    }
+}
+
+fn is_text_source(kind: &str) -> bool {
+    kind.starts_with("text_gdiplus") || kind == "text_ft2_source" || kind == "text_ft2_source_v2"
+}
+
+async fn set_text(conn: &ObsHandle, source_name: &str, text: &str) -> Result<(), String> {
+    conn.send_request(
+        "SetInputSettings",
+        Some(json!({"inputName": source_name, "inputSettings": {"text": text}})),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Ends the run if it's still the one with `id`, returning it.
+async fn take_run(tele: &SharedTeleprompter, id: u64) -> Option<Run> {
+    let mut t = tele.lock().await;
+    if t.run.as_ref().map(|r| r.id) != Some(id) {
+        return None;
+    }
+    t.run.take()
+}
+
+fn emit_ended(app: &tauri::AppHandle, source_name: &str, reason: &str) {
+    let _ = app.emit(
+        "teleprompter://ended",
+        json!({"sourceName": source_name, "reason": reason}),
+    );
+}
+
+async fn run_loop(app: tauri::AppHandle, id: u64) {
+    let tele = app.state::<SharedTeleprompter>().inner().clone();
+    let conn = app.state::<ObsHandle>().inner().clone();
+    let obs_state = app.state::<SharedObsState>().inner().clone();
+    let mut interval = tokio::time::interval(TICK);
+    let mut shown: Option<String> = None;
+    let mut last_progress: Option<ProgressPayload> = None;
+
+    loop {
+        interval.tick().await;
+        let (source_name, text, progress, just_finished) = {
+            let mut t = tele.lock().await;
+            let Teleprompter { script, run, .. } = &mut *t;
+            let (Some(script), Some(run)) = (script.as_ref(), run.as_mut()) else {
+                return;
+            };
+            if run.id != id {
+                return;
+            }
+            let now = Instant::now();
+            let total = script.total_words as f64;
+            if !run.is_paused() && !run.finished {
+                let advanced = now.duration_since(run.last_tick).as_secs_f64() * run.wpm / 60.0;
+                if advanced.is_finite() {
+                    run.position = (run.position + advanced).min(total);
+                }
+            }
+            run.last_tick = now;
+            let just_finished = !run.finished && run.position >= total;
+            run.finished |= just_finished;
+            let word = run.position as usize;
+            let line = script.line_at(word);
+            let progress = ProgressPayload {
+                source_name: run.source_name.clone(),
+                line,
+                total_lines: script.lines.len(),
+                word,
+                total_words: script.total_words,
+                percent: if script.total_words == 0 {
+                    100.0
+                } else {
+                    run.position / total * 100.0
+                },
+                marker: script.marker_at(line),
+                paused: run.is_paused(),
+                finished: run.finished,
+            };
+            (
+                run.source_name.clone(),
+                script.window(line, run.visible_lines),
+                progress,
+                just_finished,
+            )
+        };
+
+        if !obs_state.read().await.inputs.contains_key(&source_name) {
+            if take_run(&tele, id).await.is_some() {
+                emit_ended(&app, &source_name, "source_removed");
+            }
+            return;
+        }
+        if shown.as_deref() != Some(text.as_str()) {
+            if let Err(e) = set_text(&conn, &source_name, &text).await {
+                log::warn!("Teleprompter update of '{}' failed: {}", source_name, e);
+                if take_run(&tele, id).await.is_some() {
+                    emit_ended(&app, &source_name, &e);
+                }
+                return;
+            }
+            shown = Some(text);
+        }
+        // Rounded so the event only fires when something visible changed.
+        let rounded = ProgressPayload {
+            percent: (progress.percent * 10.0).round() / 10.0,
+            ..progress
+        };
+        if last_progress.as_ref() != Some(&rounded) {
+            let _ = app.emit("teleprompter://progress", &rounded);
+            last_progress = Some(rounded);
+        }
+        if just_finished {
+            emit_ended(&app, &source_name, "finished");
+        }
+    }
+}
+
+/// Keeps a running prompter attached to its text source after a rename.
+pub async fn on_input_renamed(app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let Some(tele) = app.try_state::<SharedTeleprompter>() else {
+        return Vec::new();
+    };
+    let mut t = tele.lock().await;
+    match t.run.as_mut() {
+        Some(run) if run.source_name == old_name => {
+            run.source_name = new_name;
+            vec!["teleprompter source".to_string()]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Called from the push-to-talk shortcut handler.
+pub fn on_ptt(app: &tauri::AppHandle, held: bool) {
+    let tele = app.state::<SharedTeleprompter>().inner().clone();
+    tauri::async_runtime::spawn(async move {
+        if let Some(run) = tele.lock().await.run.as_mut() {
+            run.ptt_held = held;
+        }
+    });
+}
+
+async fn update_run<F>(tele: &SharedTeleprompter, f: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Run, &Script) -> Result<(), String>,
+{
+    let mut t = tele.lock().await;
+    let Teleprompter { script, run, .. } = &mut *t;
+    match (run.as_mut(), script.as_ref()) {
+        (Some(run), Some(script)) => f(run, script),
+        _ => Err("Teleprompter is not running".to_string()),
+    }
+}
+
+/// Loads a script from `text`, or from the file at `path`. A running
+/// teleprompter restarts from the top of the new script.
+#[tauri::command]
+pub async fn load_script(
+    tele: tauri::State<'_, SharedTeleprompter>,
+    text: Option<String>,
+    path: Option<String>,
+) -> Result<ScriptInfo, String> {
+    let text = match (text, path) {
+        (Some(text), _) => text,
+        (None, Some(path)) => tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| format!("Failed to read script: {}", e))?,
+        (None, None) => return Err("Provide the script text or a file path".to_string()),
+    };
+    let script = Script::parse(&text);
+    if script.total_words == 0 {
+        return Err("Script is empty".to_string());
+    }
+    let info = script.info();
+    let mut t = tele.lock().await;
+    t.script = Some(script);
+    if let Some(run) = t.run.as_mut() {
+        run.position = 0.0;
+        run.finished = false;
+    }
+    Ok(info)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_teleprompter(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    tele: tauri::State<'_, SharedTeleprompter>,
+    app_handle: tauri::AppHandle,
+    source_name: String,
+    words_per_minute: f64,
+    visible_lines: Option<usize>,
+    pause_on_ptt: Option<bool>,
+) -> Result<(), String> {
+    let kind = obs_state
+        .read()
+        .await
+        .inputs
+        .get(&source_name)
+        .map(|i| i.kind.clone())
+        .ok_or_else(|| format!("Source '{}' not found", source_name))?;
+    if !is_text_source(&kind) {
+        return Err(format!("'{}' is not a text source", source_name));
+    }
+    if tele.lock().await.script.is_none() {
+        return Err("Load a script first".to_string());
+    }
+    let wpm = checked_wpm(words_per_minute)?;
+
+    let current = conn_state
+        .send_request("GetInputSettings", Some(json!({"inputName": source_name})))
+        .await?;
+    let mut original_text = current["inputSettings"]["text"].as_str().map(String::from);
+
+    let previous = {
+        let mut t = tele.lock().await;
+        t.next_id += 1;
+        let id = t.next_id;
+        let previous = t.run.take();
+        // Restarting on the same source keeps the text from before the first run.
+        if let Some(prev) = previous.as_ref().filter(|p| p.source_name == source_name) {
+            original_text = prev.original_text.clone();
+        }
+        t.run = Some(Run {
+            id,
+            source_name: source_name.clone(),
+            wpm,
+            visible_lines: visible_lines.unwrap_or(DEFAULT_VISIBLE_LINES).max(1),
+            pause_on_ptt: pause_on_ptt.unwrap_or(false),
+            position: 0.0,
+            paused: false,
+            ptt_held: false,
+            finished: false,
+            last_tick: Instant::now(),
+            original_text,
+        });
+        tauri::async_runtime::spawn(run_loop(app_handle.clone(), id));
+        previous
+    };
+    // Restarting on another source hands the old one its text back.
+    if let Some(prev) = previous.filter(|p| p.source_name != source_name) {
+        if let Some(text) = prev.original_text {
+            let _ = set_text(&conn_state, &prev.source_name, &text).await;
+        }
+        emit_ended(&app_handle, &prev.source_name, "replaced");
+    }
+    Ok(())
+}
+
+/// Puts the prompter source's original text back before the app exits.
+pub async fn shutdown(app: tauri::AppHandle) {
+    let (Some(conn), Some(tele)) = (app.try_state::<ObsHandle>(), app.try_state::<SharedTeleprompter>()) else {
+        return;
+    };
+    let Some(run) = tele.lock().await.run.take() else {
+        return;
+    };
+    if let Some(text) = &run.original_text {
+        if let Err(e) = set_text(&conn, &run.source_name, text).await {
+            log::warn!("Failed to restore text on '{}': {}", run.source_name, e);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn stop_teleprompter(
+    conn_state: tauri::State<'_, ObsHandle>,
+    tele: tauri::State<'_, SharedTeleprompter>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let run = tele.lock().await.run.take().ok_or("Teleprompter is not running")?;
+    if let Some(text) = &run.original_text {
+        set_text(&conn_state, &run.source_name, text).await?;
+    }
+    emit_ended(&app_handle, &run.source_name, "stopped");
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn pause_teleprompter(tele: tauri::State<'_, SharedTeleprompter>) -> Result<(), String> {
+    update_run(&tele, |run, _| {
+        run.paused = true;
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn resume_teleprompter(tele: tauri::State<'_, SharedTeleprompter>) -> Result<(), String> {
+    update_run(&tele, |run, _| {
+        run.paused = false;
+        run.last_tick = Instant::now();
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn set_teleprompter_speed(
+    tele: tauri::State<'_, SharedTeleprompter>,
+    words_per_minute: f64,
+) -> Result<(), String> {
+    let wpm = checked_wpm(words_per_minute)?;
+    update_run(&tele, |run, _| {
+        run.wpm = wpm;
+        Ok(())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn jump_to_marker(tele: tauri::State<'_, SharedTeleprompter>, marker: usize) -> Result<(), String> {
+    update_run(&tele, |run, script| {
+        let m = script
+            .markers
+            .get(marker)
+            .ok_or_else(|| format!("Marker {} not found; the script has {}", marker, script.markers.len()))?;
+        run.position = script.line_starts.get(m.line).copied().unwrap_or(script.total_words) as f64;
+        run.finished = false;
+        run.last_tick = Instant::now();
+        Ok(())
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heading_lines_are_shown_and_marked() {
+        let script = Script::parse("# Intro\nHello there\n# Outro\nBye");
+        assert_eq!(script.lines, vec!["Intro", "Hello there", "Outro", "Bye"]);
+        assert_eq!(script.markers.len(), 2);
+        assert_eq!(script.markers[0].label, "Intro");
+        assert_eq!(script.markers[0].line, 0);
+        assert_eq!(script.markers[1].line, 2);
+        assert_eq!(script.total_words, 5);
+    }
+
+    #[test]
+    fn other_hash_lines_are_plain_text() {
+        let script = Script::parse("#1 rule\n#hashtag\n#\nend");
+        assert_eq!(script.lines, vec!["#1 rule", "#hashtag", "#", "end"]);
+        assert!(script.markers.is_empty());
+    }
+
+    #[test]
+    fn long_lines_wrap_and_words_map_to_lines() {
+        let text = "word ".repeat(20);
+        let script = Script::parse(&text);
+        assert!(script.lines.iter().all(|l| l.chars().count() <= WRAP_COLUMNS));
+        assert_eq!(script.total_words, 20);
+        assert_eq!(script.line_at(0), 0);
+        assert_eq!(script.line_at(script.total_words), script.lines.len() - 1);
+    }
+
+    #[test]
+    fn speed_rejects_non_finite_values() {
+        assert!(checked_wpm(f64::NAN).is_err());
+        assert!(checked_wpm(f64::INFINITY).is_err());
+        assert_eq!(checked_wpm(0.0), Ok(MIN_WPM));
+        assert_eq!(checked_wpm(1000.0), Ok(MAX_WPM));
+        assert_eq!(checked_wpm(150.0), Ok(150.0));
+    }
+}
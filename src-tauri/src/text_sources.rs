@@ -0,0 +1,252 @@
+use crate::gemini::AiAction;
+use crate::layout;
+use crate::obs_websocket::ObsHandle;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Kinds to create, best first. v3 is the GDI+ text on OBS 30+.
+const CREATE_KINDS: &[&str] = &["text_gdiplus_v3", "text_gdiplus_v2", "text_ft2_source_v2"];
+
+const DEFAULT_FONT_FACE: &str = "Arial";
+const DEFAULT_FONT_SIZE: u32 = 72;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextKind {
+    GdiPlus,
+    FreeType,
+}
+
+impl TextKind {
+    fn of(input_kind: &str) -> Option<TextKind> {
+        if input_kind.starts_with("text_gdiplus") {
+            Some(TextKind::GdiPlus)
+        } else if input_kind.starts_with("text_ft2_source") {
+            Some(TextKind::FreeType)
+        } else {
+            None
+        }
+    }
+
+    /// The settings that make the source read its text from a file instead.
+    fn file_keys(self) -> (&'static str, &'static str) {
+        match self {
+            TextKind::GdiPlus => ("read_from_file", "file"),
+            TextKind::FreeType => ("from_file", "text_file"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextSource {
+    pub input_name: String,
+    pub input_kind: String,
+    pub text: String,
+    /// Set when the source shows a file's contents rather than `text`.
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStyle {
+    pub font_face: Option<String>,
+    pub font_size: Option<u32>,
+    /// "#RRGGBB"; white when left out.
+    pub color: Option<String>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub outline: bool,
+}
+
+async fn read_text(conn: &ObsHandle, input_name: &str) -> Result<(TextSource, TextKind), String> {
+    let v = conn
+        .send_request("GetInputSettings", Some(json!({ "inputName": input_name })))
+        .await?;
+    let input_kind = v["inputKind"].as_str().unwrap_or("").to_string();
+    let kind = TextKind::of(&input_kind)
+        .ok_or_else(|| format!("\"{}\" is a {} input, not a text source", input_name, input_kind))?;
+    let settings = &v["inputSettings"];
+    let (from_file, file_key) = kind.file_keys();
+    let file = settings[from_file]
+        .as_bool()
+        .unwrap_or(false)
+        .then(|| settings[file_key].as_str().unwrap_or("").to_string());
+    let source = TextSource {
+        input_name: input_name.to_string(),
+        input_kind,
+        text: settings["text"].as_str().unwrap_or("").to_string(),
+        file,
+    };
+    Ok((source, kind))
+}
+
+/// Sets the text, switching off "read from file" unless `from_file` asks
+/// to keep it (undo puts a file-backed source back that way).
+async fn write_text(conn: &ObsHandle, input_name: &str, text: &str, from_file: bool) -> Result<(), String> {
+    let (_, kind) = read_text(conn, input_name).await?;
+    let (file_flag, _) = kind.file_keys();
+    conn.send_request(
+        "SetInputSettings",
+        Some(json!({
+            "inputName": input_name,
+            "inputSettings": { "text": text, file_flag: from_file },
+            "overlay": true
+        })),
+    )
+    .await?;
+    Ok(())
+}
+
+/// "#RRGGBB" as the 0xAABBGGRR OBS stores colors in.
+fn obs_color(hex: &str) -> Result<u32, String> {
+    let digits = hex.trim().trim_start_matches('#');
+    let rgb = u32::from_str_radix(digits, 16)
+        .ok()
+        .filter(|_| digits.len() == 6)
+        .ok_or_else(|| format!("Color must look like #RRGGBB, got '{}'", hex))?;
+    let (r, g, b) = (rgb >> 16, (rgb >> 8) & 0xFF, rgb & 0xFF);
+    Ok(0xFF00_0000 | (b << 16) | (g << 8) | r)
+}
+
+fn text_settings(kind: TextKind, text: &str, style: &TextStyle) -> Result<Value, String> {
+    let color = obs_color(style.color.as_deref().unwrap_or("#FFFFFF"))?;
+    let mut settings = json!({
+        "text": text,
+        "font": {
+            "face": style.font_face.as_deref().unwrap_or(DEFAULT_FONT_FACE),
+            "size": style.font_size.unwrap_or(DEFAULT_FONT_SIZE).clamp(8, 512),
+            "style": if style.bold { "Bold" } else { "Regular" },
+            "flags": u32::from(style.bold)
+        },
+        "outline": style.outline
+    });
+    match kind {
+        TextKind::GdiPlus => {
+            settings["color"] = json!(color);
+            if style.outline {
+                settings["outline_size"] = json!(4);
+                settings["outline_color"] = json!(0xFF00_0000u32);
+            }
+        }
+        TextKind::FreeType => {
+            settings["color1"] = json!(color);
+            settings["color2"] = json!(color);
+        }
+    }
+    Ok(settings)
+}
+
+async fn create_text(
+    conn: &ObsHandle,
+    scene_name: &str,
+    name: &str,
+    text: &str,
+    style: &TextStyle,
+) -> Result<u64, String> {
+    let kinds = conn
+        .send_request("GetInputKindList", Some(json!({ "unversioned": false })))
+        .await?;
+    let available: Vec<&str> = kinds["inputKinds"]
+        .as_array()
+        .map(|a| a.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    let input_kind = CREATE_KINDS
+        .iter()
+        .find(|k| available.contains(k))
+        .ok_or("OBS has no text source kind installed")?;
+    let kind = TextKind::of(input_kind).ok_or("OBS has no text source kind installed")?;
+
+    let created = conn
+        .send_request(
+            "CreateInput",
+            Some(json!({
+                "sceneName": scene_name,
+                "inputName": name,
+                "inputKind": input_kind,
+                "inputSettings": text_settings(kind, text, style)?,
+                "sceneItemEnabled": true
+            })),
+        )
+        .await?;
+    let item_id = created["sceneItemId"]
+        .as_u64()
+        .ok_or("OBS did not return the new scene item")?;
+
+    // Centered, and shrunk to fit only when the text is wider than the canvas.
+    let (cw, ch) = layout::fetch_canvas(conn).await?;
+    let mut t = layout::get_transform(conn, scene_name, item_id).await?;
+    t["alignment"] = json!(0);
+    t["positionX"] = json!(cw / 2.0);
+    t["positionY"] = json!(ch / 2.0);
+    t["boundsType"] = json!("OBS_BOUNDS_MAX_ONLY");
+    t["boundsAlignment"] = json!(0);
+    t["boundsWidth"] = json!(cw);
+    t["boundsHeight"] = json!(ch);
+    layout::apply_transform(conn, scene_name, item_id, &t).await?;
+    Ok(item_id)
+}
+
+// --- AI actions ---
+
+pub async fn dispatch(action: &AiAction, conn: &ObsHandle) -> Result<(), String> {
+    let p = &action.params;
+    match action.request_type.as_str() {
+        "set" => {
+            let name = p["inputName"].as_str().ok_or("Missing inputName")?;
+            let text = p["text"].as_str().ok_or("Missing text")?;
+            let from_file = p["readFromFile"].as_bool().unwrap_or(false);
+            write_text(conn, name, text, from_file).await
+        }
+        other => Err(format!("Unknown text action: {}", other)),
+    }
+}
+
+/// The source's current text as a "set" action, keeping it file-backed if
+/// it was.
+pub async fn snapshot_for_undo(action: &AiAction, conn: &ObsHandle) -> Option<crate::ai_actions::UndoEntry> {
+    let name = action.params["inputName"].as_str()?;
+    let (source, _) = read_text(conn, name).await.ok()?;
+    Some(crate::ai_actions::UndoEntry {
+        description: format!("Revert text of \"{}\"", name),
+        action_type: "text".into(),
+        request_type: "set".into(),
+        revert_params: json!({
+            "inputName": name,
+            "text": source.text,
+            "readFromFile": source.file.is_some()
+        }),
+    })
+}
+
+// --- Commands ---
+
+#[tauri::command]
+pub async fn get_text_source(
+    conn_state: tauri::State<'_, ObsHandle>,
+    input_name: String,
+) -> Result<TextSource, String> {
+    Ok(read_text(&conn_state, &input_name).await?.0)
+}
+
+#[tauri::command]
+pub async fn set_text_source(
+    conn_state: tauri::State<'_, ObsHandle>,
+    input_name: String,
+    text: String,
+) -> Result<(), String> {
+    write_text(&conn_state, &input_name, &text, false).await
+}
+
+/// Adds a text source to `scene_name` with readable defaults (72pt white
+/// Arial) and returns its scene item id.
+#[tauri::command]
+pub async fn create_text_source(
+    conn_state: tauri::State<'_, ObsHandle>,
+    scene_name: String,
+    name: String,
+    text: String,
+    style: Option<TextStyle>,
+) -> Result<u64, String> {
+    create_text(&conn_state, &scene_name, &name, &text, &style.unwrap_or_default()).await
+}
@@ -0,0 +1,416 @@
+use crate::ai_actions::{self, ActionResult, SharedUndoStack};
+use crate::gemini::AiAction;
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use crate::settings::SharedSettings;
+use crate::store::SharedLicenseState;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+use tokio::sync::Mutex;
+
+pub type SharedTimers = Arc<Mutex<Timers>>;
+
+const TEXT_SOURCE_KIND: &str = "text_gdiplus_v3";
+const DEFAULT_FORMAT: &str = "{mm}:{ss}";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerMode {
+    Countdown,
+    CountUp,
+}
+
+/// What gets persisted. Running state is deliberately not: a restart brings
+/// timers back reset.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerConfig {
+    pub name: String,
+    pub mode: TimerMode,
+    /// Countdown length, or the point a count-up finishes at (0 = never).
+    pub duration_secs: u64,
+    /// Text with `{h}`, `{hh}`, `{m}`, `{mm}`, `{s}`, `{ss}` and `{M}` (total
+    /// minutes) placeholders.
+    pub format: String,
+    pub text_source: String,
+    pub on_finish: Option<AiAction>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerStatus {
+    #[serde(flatten)]
+    pub config: TimerConfig,
+    pub running: bool,
+    pub finished: bool,
+    /// Seconds shown: remaining for a countdown, elapsed for a count-up.
+    pub display_secs: u64,
+    pub text: String,
+}
+
+struct Timer {
+    config: TimerConfig,
+    elapsed_before: Duration,
+    started_at: Option<Instant>,
+    finished: bool,
+    /// Finished while OBS was unreachable; the action runs on reconnect.
+    finish_pending: bool,
+    shown: Option<String>,
+}
+
+impl Timer {
+    fn new(config: TimerConfig) -> Self {
+        Self {
+            config,
+            elapsed_before: Duration::ZERO,
+            started_at: None,
+            finished: false,
+            finish_pending: false,
+            shown: None,
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.elapsed_before + self.started_at.map(|s| s.elapsed()).unwrap_or_default()
+    }
+
+    fn is_due(&self) -> bool {
+        self.started_at.is_some()
+            && self.config.duration_secs > 0
+            && self.elapsed() >= Duration::from_secs(self.config.duration_secs)
+    }
+
+    fn display_secs(&self) -> u64 {
+        let elapsed = self.elapsed().as_secs();
+        match self.config.mode {
+            // Rounded up, so a countdown shows 0:00 only when it's done.
+            TimerMode::Countdown => {
+                let remaining_ms = (self.config.duration_secs * 1000).saturating_sub(self.elapsed().as_millis() as u64);
+                remaining_ms.div_ceil(1000)
+            }
+            TimerMode::CountUp if self.config.duration_secs > 0 => elapsed.min(self.config.duration_secs),
+            TimerMode::CountUp => elapsed,
+        }
+    }
+
+    fn status(&self) -> TimerStatus {
+        let secs = self.display_secs();
+        TimerStatus {
+            config: self.config.clone(),
+            running: self.started_at.is_some(),
+            finished: self.finished,
+            display_secs: secs,
+            text: format_time(&self.config.format, secs),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Timers {
+    timers: Vec<Timer>,
+}
+
+impl Timers {
+    pub fn load() -> Self {
+        Self {
+            timers: load_configs().into_iter().map(Timer::new).collect(),
+        }
+    }
+
+    fn get_mut(&mut self, name: &str) -> Result<&mut Timer, String> {
+        self.timers
+            .iter_mut()
+            .find(|t| t.config.name == name)
+            .ok_or_else(|| format!("Timer '{}' not found", name))
+    }
+
+    fn configs(&self) -> Vec<TimerConfig> {
+        self.timers.iter().map(|t| t.config.clone()).collect()
+    }
+}
+
+pub fn format_time(format: &str, secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    format
+        .replace("{hh}", &format!("{:02}", h))
+        .replace("{mm}", &format!("{:02}", m))
+        .replace("{ss}", &format!("{:02}", s))
+        .replace("{h}", &h.to_string())
+        .replace("{m}", &m.to_string())
+        .replace("{s}", &s.to_string())
+        .replace("{M}", &(secs / 60).to_string())
+}
+
+fn timers_file_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("timers.json")
+}
+
+fn load_configs() -> Vec<TimerConfig> {
+    let Ok(content) = std::fs::read_to_string(timers_file_path()) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&content) {
+        Ok(configs) => configs,
+        Err(e) => {
+            log::warn!("Failed to parse timers file: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+async fn save_configs(configs: Vec<TimerConfig>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let path = timers_file_path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(&configs)
+            .map_err(|e| format!("Failed to serialize timers: {}", e))?;
+        crate::settings::write_atomic(&path, json).map_err(|e| format!("Failed to save timers: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+/// Follows timer text sources through a rename.
+pub async fn on_input_renamed(app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let Some(timers) = app.try_state::<SharedTimers>() else {
+        return Vec::new();
+    };
+    let (touched, configs) = {
+        let mut t = timers.lock().await;
+        let mut touched = Vec::new();
+        for timer in t.timers.iter_mut().filter(|timer| timer.config.text_source == old_name) {
+            timer.config.text_source = new_name.clone();
+            touched.push(format!("timer \"{}\"", timer.config.name));
+        }
+        (touched, t.configs())
+    };
+    if !touched.is_empty() {
+        if let Err(e) = save_configs(configs).await {
+            log::warn!("Failed to save timers after rename: {}", e);
+        }
+    }
+    touched
+}
+
+async fn run_finish_action(app: &tauri::AppHandle, action: AiAction) -> Vec<ActionResult> {
+    let conn = app.state::<ObsHandle>().inner().clone();
+    let obs_state = app.state::<SharedObsState>().read().await.clone();
+    let license = app.state::<SharedLicenseState>().read().await.clone();
+    let guards = app.state::<SharedSettings>().read().await.volume_guards.clone();
+    let undo_stack = app.state::<SharedUndoStack>().inner().clone();
+    // The user picked this action when setting up the timer, so it isn't
+    // limited to what the model may send.
+    ai_actions::execute_user_actions(&[action], &conn, &obs_state, &undo_stack, &license, &guards, None).await
+}
+
+/// One pass of the ticker: pushes changed text to OBS and handles timers
+/// that reached their end. Text updates stop while OBS is unreachable and
+/// catch up on reconnect, since each pass renders the current value.
+async fn tick(app: &tauri::AppHandle, timers: &SharedTimers, conn: &ObsHandle) {
+    let connected = conn.is_connected();
+    let mut updates = Vec::new();
+    let mut finished = Vec::new();
+    {
+        let mut t = timers.lock().await;
+        for timer in t.timers.iter_mut() {
+            if timer.is_due() {
+                timer.elapsed_before = Duration::from_secs(timer.config.duration_secs);
+                timer.started_at = None;
+                timer.finished = true;
+                timer.finish_pending = true;
+            }
+            let status = timer.status();
+            if status.running {
+                let _ = app.emit("timer://tick", &status);
+            }
+            if connected && timer.shown.as_deref() != Some(status.text.as_str()) {
+                updates.push((timer.config.name.clone(), timer.config.text_source.clone(), status.text));
+            }
+            if connected && timer.finish_pending {
+                timer.finish_pending = false;
+                finished.push((timer.config.name.clone(), timer.config.on_finish.clone()));
+            }
+        }
+    }
+
+    for (name, source, text) in updates {
+        let result = conn
+            .send_request(
+                "SetInputSettings",
+                Some(json!({"inputName": source, "inputSettings": {"text": text}})),
+            )
+            .await;
+        match result {
+            Ok(_) => {
+                if let Ok(timer) = timers.lock().await.get_mut(&name) {
+                    timer.shown = Some(text);
+                }
+            }
+            Err(e) => log::debug!("Timer '{}' update failed: {}", name, e),
+        }
+    }
+
+    for (name, action) in finished {
+        let results = match action {
+            Some(action) => run_finish_action(app, action).await,
+            None => Vec::new(),
+        };
+        for result in &results {
+            match result.status.as_str() {
+                "pending_confirmation" => log::info!(
+                    "Timer '{}' finished; '{}' is waiting for confirmation",
+                    name,
+                    result.description
+                ),
+                "failed" | "blocked" | "rejected" => log::warn!(
+                    "Timer '{}' finish action '{}' {}: {}",
+                    name,
+                    result.description,
+                    result.status,
+                    result.error.as_deref().unwrap_or("")
+                ),
+                _ => {}
+            }
+        }
+        let _ = app.emit(
+            "timer://finished",
+            json!({"name": name, "actionResults": results}),
+        );
+    }
+}
+
+pub fn start_ticker(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let timers = app.state::<SharedTimers>().inner().clone();
+        let conn = app.state::<ObsHandle>().inner().clone();
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            tick(&app, &timers, &conn).await;
+        }
+    });
+}
+
+/// Creates or replaces a timer. The text source is created in `scene_name`
+/// (default: the current program scene) if it doesn't exist yet.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_timer(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    timers: tauri::State<'_, SharedTimers>,
+    name: String,
+    mode: TimerMode,
+    duration_secs: u64,
+    format: Option<String>,
+    target_text_source: String,
+    scene_name: Option<String>,
+    on_finish: Option<AiAction>,
+) -> Result<TimerStatus, String> {
+    if name.trim().is_empty() {
+        return Err("Timer name cannot be empty".to_string());
+    }
+    if mode == TimerMode::Countdown && duration_secs == 0 {
+        return Err("A countdown needs a duration".to_string());
+    }
+    let config = TimerConfig {
+        name: name.clone(),
+        mode,
+        duration_secs,
+        format: format.filter(|f| !f.is_empty()).unwrap_or_else(|| DEFAULT_FORMAT.to_string()),
+        text_source: target_text_source.clone(),
+        on_finish,
+    };
+    let timer = Timer::new(config);
+    let status = timer.status();
+
+    let (exists, current_scene) = {
+        let s = obs_state.read().await;
+        (s.inputs.contains_key(&target_text_source), s.current_scene.clone())
+    };
+    if !exists {
+        conn_state
+            .send_request(
+                "CreateInput",
+                Some(json!({
+                    "sceneName": scene_name.unwrap_or(current_scene),
+                    "inputName": target_text_source,
+                    "inputKind": TEXT_SOURCE_KIND,
+                    "inputSettings": {"text": status.text},
+                })),
+            )
+            .await?;
+    }
+
+    let configs = {
+        let mut t = timers.lock().await;
+        t.timers.retain(|existing| existing.config.name != name);
+        t.timers.push(timer);
+        t.configs()
+    };
+    save_configs(configs).await?;
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn delete_timer(timers: tauri::State<'_, SharedTimers>, name: String) -> Result<(), String> {
+    let configs = {
+        let mut t = timers.lock().await;
+        let before = t.timers.len();
+        t.timers.retain(|existing| existing.config.name != name);
+        if t.timers.len() == before {
+            return Err(format!("Timer '{}' not found", name));
+        }
+        t.configs()
+    };
+    save_configs(configs).await
+}
+
+#[tauri::command]
+pub async fn start_timer(timers: tauri::State<'_, SharedTimers>, name: String) -> Result<TimerStatus, String> {
+    let mut t = timers.lock().await;
+    let timer = t.get_mut(&name)?;
+    if timer.finished {
+        timer.elapsed_before = Duration::ZERO;
+        timer.finished = false;
+    }
+    if timer.started_at.is_none() {
+        timer.started_at = Some(Instant::now());
+    }
+    Ok(timer.status())
+}
+
+#[tauri::command]
+pub async fn pause_timer(timers: tauri::State<'_, SharedTimers>, name: String) -> Result<TimerStatus, String> {
+    let mut t = timers.lock().await;
+    let timer = t.get_mut(&name)?;
+    timer.elapsed_before = timer.elapsed();
+    timer.started_at = None;
+    Ok(timer.status())
+}
+
+#[tauri::command]
+pub async fn reset_timer(timers: tauri::State<'_, SharedTimers>, name: String) -> Result<TimerStatus, String> {
+    let mut t = timers.lock().await;
+    let timer = t.get_mut(&name)?;
+    timer.elapsed_before = Duration::ZERO;
+    timer.started_at = timer.started_at.map(|_| Instant::now());
+    timer.finished = false;
+    timer.finish_pending = false;
+    Ok(timer.status())
+}
+
+#[tauri::command]
+pub async fn list_timers(timers: tauri::State<'_, SharedTimers>) -> Result<Vec<TimerStatus>, String> {
+    Ok(timers.lock().await.timers.iter().map(Timer::status).collect())
+}
@@ -0,0 +1,340 @@
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+use tokio::sync::Mutex;
+
+pub type SharedTrackPreview = Arc<Mutex<Option<PreviewSession>>>;
+
+const MONITOR_ONLY: &str = "OBS_MONITORING_TYPE_MONITOR_ONLY";
+const MONITOR_NONE: &str = "OBS_MONITORING_TYPE_NONE";
+const MAX_PREVIEW_SECONDS: f64 = 300.0;
+
+/// Monitor types from before the preview, written to disk before anything is
+/// changed so a crash or restart mid-preview can still put them back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewSession {
+    pub track: u8,
+    pub saved: Vec<(String, String)>,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackPreviewStatus {
+    pub track: u8,
+    pub soloed: Vec<String>,
+    pub silenced: Vec<String>,
+    pub duration_seconds: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackPreviewEnded {
+    pub track: u8,
+    pub restored: Vec<String>,
+    pub skipped: Vec<String>,
+    pub reason: String,
+}
+
+fn session_file_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("track_preview.json")
+}
+
+fn persist(session: &PreviewSession) -> Result<(), String> {
+    let path = session_file_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+    }
+    let json = serde_json::to_string_pretty(session)
+        .map_err(|e| format!("Failed to serialize preview state: {}", e))?;
+    std::fs::write(&path, json).map_err(|e| format!("Failed to save preview state: {}", e))
+}
+
+fn read_persisted() -> Option<PreviewSession> {
+    let content = std::fs::read_to_string(session_file_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn clear_persisted() {
+    let _ = std::fs::remove_file(session_file_path());
+}
+
+async fn set_monitor_type(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    input_name: &str,
+    monitor_type: &str,
+) -> Result<(), String> {
+    conn.send_request(
+        "SetInputAudioMonitorType",
+        Some(json!({
+            "inputName": input_name,
+            "monitorType": monitor_type,
+        })),
+    )
+    .await?;
+    if let Some(input) = obs_state.write().await.inputs.get_mut(input_name) {
+        input.monitor_type = monitor_type.to_string();
+    }
+    Ok(())
+}
+
+async fn restore(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    app: &tauri::AppHandle,
+    session: PreviewSession,
+    reason: &str,
+) -> TrackPreviewEnded {
+    let mut restored = Vec::new();
+    let mut skipped = Vec::new();
+    let mut failed = Vec::new();
+    for (name, monitor_type) in session.saved {
+        let exists = obs_state.read().await.inputs.contains_key(&name);
+        if !exists {
+            skipped.push(name);
+            continue;
+        }
+        match set_monitor_type(conn, obs_state, &name, &monitor_type).await {
+            Ok(()) => restored.push(name),
+            Err(e) => {
+                log::warn!("Failed to restore monitoring on '{}': {}", name, e);
+                skipped.push(name.clone());
+                failed.push((name, monitor_type));
+            }
+        }
+    }
+    // Whatever OBS refused stays on disk for the next connect to retry.
+    if failed.is_empty() {
+        clear_persisted();
+    } else if let Err(e) = persist(&PreviewSession {
+        track: session.track,
+        saved: failed,
+        token: session.token,
+    }) {
+        log::warn!("Failed to save unrestored monitor types: {}", e);
+    }
+    let ended = TrackPreviewEnded {
+        track: session.track,
+        restored,
+        skipped,
+        reason: reason.to_string(),
+    };
+    let _ = app.emit("audio://track-preview-ended", &ended);
+    ended
+}
+
+/// Points the saved monitor types at an input's new name, live or persisted.
+pub async fn on_input_renamed(app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let rename = |session: &mut PreviewSession| {
+        let mut renamed = false;
+        for (name, _) in session.saved.iter_mut().filter(|(name, _)| *name == old_name) {
+            *name = new_name.clone();
+            renamed = true;
+        }
+        renamed
+    };
+    if let Some(preview) = app.try_state::<SharedTrackPreview>() {
+        let mut current = preview.lock().await;
+        if let Some(session) = current.as_mut() {
+            if !rename(session) {
+                return Vec::new();
+            }
+            if let Err(e) = persist(session) {
+                log::warn!("Failed to save track preview state after rename: {}", e);
+            }
+            return vec!["track preview".to_string()];
+        }
+    }
+    let Some(mut session) = read_persisted() else {
+        return Vec::new();
+    };
+    if !rename(&mut session) {
+        return Vec::new();
+    }
+    if let Err(e) = persist(&session) {
+        log::warn!("Failed to save track preview state after rename: {}", e);
+    }
+    vec!["interrupted track preview".to_string()]
+}
+
+async fn end_session(
+    conn: &ObsHandle,
+    obs_state: &SharedObsState,
+    preview: &SharedTrackPreview,
+    app: &tauri::AppHandle,
+    token: Option<&str>,
+    reason: &str,
+) -> Option<TrackPreviewEnded> {
+    let session = {
+        let mut current = preview.lock().await;
+        match current.as_ref() {
+            Some(s) if token.map(|t| t == s.token).unwrap_or(true) => current.take(),
+            _ => None,
+        }
+    }?;
+    Some(restore(conn, obs_state, app, session, reason).await)
+}
+
+/// Ends a live preview before the app exits. If OBS is unreachable the
+/// persisted session is left for `restore_pending` on the next connect.
+pub async fn shutdown(app: tauri::AppHandle) {
+    let (Some(conn), Some(obs), Some(preview)) = (
+        app.try_state::<ObsHandle>(),
+        app.try_state::<SharedObsState>(),
+        app.try_state::<SharedTrackPreview>(),
+    ) else {
+        return;
+    };
+    if !conn.is_connected() {
+        return;
+    }
+    end_session(&conn, &obs, &preview, &app, None, "shutdown").await;
+}
+
+/// Puts back monitor types left over from a preview interrupted by a crash or
+/// restart. Called once OBS state has been populated after connecting.
+pub async fn restore_pending(conn: &ObsHandle, obs_state: &SharedObsState, app: &tauri::AppHandle) {
+    // A reconnect during a live preview leaves it to its own timer.
+    if let Some(preview) = app.try_state::<SharedTrackPreview>() {
+        if preview.lock().await.is_some() {
+            return;
+        }
+    }
+    let Ok(content) = std::fs::read_to_string(session_file_path()) else {
+        return;
+    };
+    let session: PreviewSession = match serde_json::from_str(&content) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("Discarding unreadable track preview state: {}", e);
+            clear_persisted();
+            return;
+        }
+    };
+    log::info!("Restoring monitor types from an interrupted track {} preview", session.track);
+    restore(conn, obs_state, app, session, "recovered").await;
+}
+
+#[tauri::command]
+pub async fn solo_track_preview(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    preview_state: tauri::State<'_, SharedTrackPreview>,
+    app_handle: tauri::AppHandle,
+    track_number: u8,
+    duration_seconds: f64,
+) -> Result<TrackPreviewStatus, String> {
+    if !(1..=6).contains(&track_number) {
+        return Err(format!("Track must be between 1 and 6, got {}", track_number));
+    }
+    let duration = duration_seconds.clamp(1.0, MAX_PREVIEW_SECONDS);
+    let key = track_number.to_string();
+
+    // Retry anything an earlier preview couldn't put back, and keep the
+    // original monitor type for whatever still isn't, so the new session
+    // doesn't save the soloed one in its place.
+    restore_pending(&conn_state, &obs_state, &app_handle).await;
+    let leftover = read_persisted().map(|s| s.saved).unwrap_or_default();
+
+    let (saved, soloed, silenced) = {
+        let state = obs_state.read().await;
+        let mut saved = Vec::new();
+        let mut soloed = Vec::new();
+        let mut silenced = Vec::new();
+        for input in state.inputs.values().filter(|i| i.audio_tracks.is_object()) {
+            let original = leftover
+                .iter()
+                .find(|(name, _)| *name == input.name)
+                .map(|(_, monitor_type)| monitor_type.clone())
+                .unwrap_or_else(|| input.monitor_type.clone());
+            saved.push((input.name.clone(), original));
+            if input.audio_tracks[&key].as_bool().unwrap_or(false) {
+                soloed.push(input.name.clone());
+            } else {
+                silenced.push(input.name.clone());
+            }
+        }
+        for entry in leftover {
+            if !saved.iter().any(|(name, _)| *name == entry.0) {
+                saved.push(entry);
+            }
+        }
+        (saved, soloed, silenced)
+    };
+    if soloed.is_empty() {
+        return Err(format!("No audio inputs are routed to track {}", track_number));
+    }
+
+    let token = uuid::Uuid::new_v4().to_string();
+    {
+        let mut current = preview_state.lock().await;
+        if let Some(active) = current.as_ref() {
+            return Err(format!(
+                "A preview of track {} is already running; stop it first",
+                active.track
+            ));
+        }
+        let session = PreviewSession {
+            track: track_number,
+            saved,
+            token: token.clone(),
+        };
+        persist(&session)?;
+        *current = Some(session);
+    }
+
+    let applied = {
+        let conn = conn_state.inner().clone();
+        let mut result = Ok(());
+        'apply: for (names, monitor_type) in [(&soloed, MONITOR_ONLY), (&silenced, MONITOR_NONE)] {
+            for name in names {
+                if let Err(e) = set_monitor_type(&conn, &obs_state, name, monitor_type).await {
+                    result = Err(e);
+                    break 'apply;
+                }
+            }
+        }
+        result
+    };
+    if let Err(e) = applied {
+        end_session(&conn_state, &obs_state, &preview_state, &app_handle, Some(&token), "failed").await;
+        return Err(e);
+    }
+
+    let status = TrackPreviewStatus {
+        track: track_number,
+        soloed,
+        silenced,
+        duration_seconds: duration,
+    };
+    let _ = app_handle.emit("audio://track-preview-started", &status);
+
+    let conn = conn_state.inner().clone();
+    let obs = obs_state.inner().clone();
+    let preview = preview_state.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs_f64(duration)).await;
+        end_session(&conn, &obs, &preview, &app_handle, Some(&token), "timeout").await;
+    });
+
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn stop_track_preview(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    preview_state: tauri::State<'_, SharedTrackPreview>,
+    app_handle: tauri::AppHandle,
+) -> Result<Option<TrackPreviewEnded>, String> {
+    Ok(end_session(&conn_state, &obs_state, &preview_state, &app_handle, None, "manual").await)
+}
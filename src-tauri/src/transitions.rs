@@ -0,0 +1,162 @@
+use crate::obs_state::{self, SharedObsState, TransitionState};
+use crate::obs_websocket::ObsHandle;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// OBS's own limits for a transition duration.
+const MIN_DURATION_MS: u64 = 50;
+const MAX_DURATION_MS: u64 = 20000;
+
+#[tauri::command]
+pub async fn get_transition_list(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+) -> Result<TransitionState, String> {
+    let transition = obs_state::fetch_transitions(&conn_state).await?;
+    Ok(obs_state::store_transitions(&mut *obs_state.write().await, transition))
+}
+
+#[tauri::command]
+pub async fn set_current_transition(
+    conn_state: tauri::State<'_, ObsHandle>,
+    transition_name: String,
+) -> Result<(), String> {
+    conn_state
+        .send_request(
+            "SetCurrentSceneTransition",
+            Some(json!({ "transitionName": transition_name })),
+        )
+        .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_transition_duration(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    duration_ms: u64,
+) -> Result<(), String> {
+    let transition = obs_state.read().await.transition.clone();
+    if transition.is_fixed(&transition.current) {
+        return Err(format!("\"{}\" has a fixed length", transition.current));
+    }
+    conn_state
+        .send_request(
+            "SetCurrentSceneTransitionDuration",
+            Some(json!({ "transitionDuration": duration_ms.clamp(MIN_DURATION_MS, MAX_DURATION_MS) })),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Switches to `scene_name`, using `transition_name` and `duration_ms` for
+/// this switch only; the previous transition is put back once it's done.
+/// In studio mode the scene goes through preview.
+#[tauri::command]
+pub async fn transition_to_scene(
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    scene_name: String,
+    transition_name: Option<String>,
+    duration_ms: Option<u64>,
+) -> Result<(), String> {
+    let conn = conn_state.inner().clone();
+    let (previous, studio_mode) = {
+        let s = obs_state.read().await;
+        (s.transition.clone(), s.studio_mode)
+    };
+    let name = transition_name.unwrap_or_else(|| previous.current.clone());
+    let fixed = previous.is_fixed(&name);
+    let duration_ms = duration_ms
+        .filter(|_| !fixed)
+        .map(|ms| ms.clamp(MIN_DURATION_MS, MAX_DURATION_MS));
+
+    // Whichever step fails, anything already changed is put back below.
+    let mut result = Ok(());
+    if name != previous.current {
+        result = conn
+            .send_request("SetCurrentSceneTransition", Some(json!({ "transitionName": name })))
+            .await
+            .map(|_| ());
+    }
+    if let (Ok(()), Some(ms)) = (&result, duration_ms) {
+        result = conn
+            .send_request(
+                "SetCurrentSceneTransitionDuration",
+                Some(json!({ "transitionDuration": ms })),
+            )
+            .await
+            .map(|_| ());
+    }
+    if result.is_ok() {
+        result = if studio_mode {
+            match conn
+                .send_request("SetCurrentPreviewScene", Some(json!({ "sceneName": scene_name })))
+                .await
+            {
+                Ok(_) => conn.send_request("TriggerStudioModeTransition", None).await,
+                Err(e) => Err(e),
+            }
+        } else {
+            conn.send_request("SetCurrentProgramScene", Some(json!({ "sceneName": scene_name })))
+                .await
+        }
+        .map(|_| ());
+    }
+
+    let changed = name != previous.current || duration_ms.is_some();
+    if changed {
+        // Swapping the transition mid-way would cut it short.
+        let wait = if result.is_ok() {
+            duration_ms.or(previous.duration_ms).unwrap_or(0) + 250
+        } else {
+            0
+        };
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(wait)).await;
+            restore_transition(&conn, &previous).await;
+        });
+    }
+    result
+}
+
+async fn restore_transition(conn: &ObsHandle, previous: &TransitionState) {
+    if let Err(e) = conn
+        .send_request(
+            "SetCurrentSceneTransition",
+            Some(json!({ "transitionName": previous.current })),
+        )
+        .await
+    {
+        log::warn!("Failed to restore transition \"{}\": {}", previous.current, e);
+        return;
+    }
+    if let Some(ms) = previous.duration_ms {
+        if let Err(e) = conn
+            .send_request(
+                "SetCurrentSceneTransitionDuration",
+                Some(json!({ "transitionDuration": ms })),
+            )
+            .await
+        {
+            log::warn!("Failed to restore transition duration: {}", e);
+        }
+    }
+}
+
+/// A transition's settings, e.g. a stinger's media file and transition
+/// point. OBS only reports them for the current transition.
+#[tauri::command]
+pub async fn get_transition_settings(
+    conn_state: tauri::State<'_, ObsHandle>,
+    transition_name: String,
+) -> Result<Value, String> {
+    let current = conn_state.send_request("GetCurrentSceneTransition", None).await?;
+    if current["transitionName"].as_str() != Some(transition_name.as_str()) {
+        return Err(format!(
+            "OBS only reports settings for the current transition; make \"{}\" current first",
+            transition_name
+        ));
+    }
+    Ok(current["transitionSettings"].clone())
+}
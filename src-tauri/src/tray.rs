@@ -1,16 +1,78 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
 use tauri::{
-    AppHandle, Manager, Runtime,
+    AppHandle, Listener, Manager, Runtime,
     image::Image,
-    menu::{MenuBuilder, MenuItemBuilder},
+    menu::{MenuBuilder, MenuItem, MenuItemBuilder},
     tray::TrayIconBuilder,
 };
 
+use crate::obs_state::SharedObsState;
+use crate::obs_websocket::ObsHandle;
+
+const TRAY_ID: &str = "main";
+
+/// Events that change what the tray menu shows.
+const STATUS_EVENTS: &[&str] = &[
+    "obs://stream-state-changed",
+    "obs://record-state-changed",
+    "obs://input-mute-changed",
+    "obs://state-sync",
+    "obs://disconnected",
+    "obs://reconnected",
+];
+
+static PANIC: AtomicBool = AtomicBool::new(false);
+static LIVE: AtomicBool = AtomicBool::new(false);
+static SILENT_INPUT: Mutex<Option<String>> = Mutex::new(None);
+
+/// The menu items that follow OBS's state.
+struct TrayMenu<R: Runtime> {
+    status: MenuItem<R>,
+    stream: MenuItem<R>,
+    record: MenuItem<R>,
+    mic: MenuItem<R>,
+    view: Mutex<TrayView>,
+    live_since: Mutex<Option<Instant>>,
+}
+
+#[derive(Default, Clone, PartialEq)]
+struct TrayView {
+    connected: bool,
+    status: String,
+    live: bool,
+    recording: bool,
+    /// mic1's name and whether it's muted.
+    mic: Option<(String, bool)>,
+}
+
 pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::error::Error>> {
+    let status = MenuItemBuilder::with_id("status", "OBS: Disconnected")
+        .enabled(false)
+        .build(app)?;
+    let stream = MenuItemBuilder::with_id("toggle_stream", "Start Streaming")
+        .enabled(false)
+        .build(app)?;
+    let record = MenuItemBuilder::with_id("toggle_record", "Start Recording")
+        .enabled(false)
+        .build(app)?;
+    let mic = MenuItemBuilder::with_id("toggle_mic", "Mute Mic")
+        .enabled(false)
+        .build(app)?;
     let show = MenuItemBuilder::with_id("show", "Show OBServe").build(app)?;
+    let save_replay = MenuItemBuilder::with_id("save_replay", "Save Replay").build(app)?;
     let quit = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
 
     let menu = MenuBuilder::new(app)
-        .text("status", "OBS: Disconnected")
+        .item(&status)
+        .separator()
+        .item(&stream)
+        .item(&record)
+        .item(&mic)
+        .item(&save_replay)
         .separator()
         .item(&show)
         .item(&quit)
@@ -18,7 +80,7 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::err
 
     let icon = Image::from_bytes(include_bytes!("../icons/icon.png"))?;
 
-    TrayIconBuilder::new()
+    TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
         .tooltip("OBServe - Disconnected")
         .menu(&menu)
@@ -29,6 +91,27 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::err
                     let _ = window.set_focus();
                 }
             }
+            // Clicking the item is the confirmation, so these skip the
+            // dangerous-action prompt the AI's versions go through.
+            "toggle_stream" => send_quick_action(app, "ToggleStream"),
+            "toggle_record" => send_quick_action(app, "ToggleRecord"),
+            "save_replay" => send_quick_action(app, "SaveReplayBuffer"),
+            "toggle_mic" => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    let mic = app.state::<SharedObsState>().read().await.special_inputs.mic1.clone();
+                    if mic.is_empty() {
+                        return;
+                    }
+                    let conn = app.state::<ObsHandle>().inner().clone();
+                    if let Err(e) = conn
+                        .send_request("ToggleInputMute", Some(json!({"inputName": mic})))
+                        .await
+                    {
+                        log::warn!("Tray mic toggle failed: {}", e);
+                    }
+                });
+            }
             "quit" => {
                 app.exit(0);
             }
@@ -49,5 +132,221 @@ pub fn setup_tray<R: Runtime>(app: &AppHandle<R>) -> Result<(), Box<dyn std::err
         })
         .build(app)?;
 
+    app.manage(TrayMenu {
+        status,
+        stream,
+        record,
+        mic,
+        view: Mutex::new(TrayView::default()),
+        live_since: Mutex::new(None),
+    });
+
+    for event in STATUS_EVENTS {
+        let handle = app.clone();
+        app.listen_any(*event, move |_| {
+            let app = handle.clone();
+            tauri::async_runtime::spawn(async move { refresh(&app).await });
+        });
+    }
+
+    // Keeps the LIVE clock ticking between events.
+    let ticker_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            refresh(&ticker_app).await;
+        }
+    });
+
     Ok(())
 }
+
+fn send_quick_action<R: Runtime>(app: &AppHandle<R>, request_type: &'static str) {
+    let conn = app.state::<ObsHandle>().inner().clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = conn.send_request(request_type, None).await {
+            log::warn!("Tray {} failed: {}", request_type, e);
+        }
+    });
+}
+
+/// Brings the status line, quick actions and icon in line with OBS.
+async fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let Some(menu) = app.try_state::<TrayMenu<R>>() else {
+        return;
+    };
+    let conn = app.state::<ObsHandle>().inner().clone();
+    let connected = conn.is_connected();
+    let (live, recording, record_paused, mic) = {
+        let s = app.state::<SharedObsState>().read().await;
+        let mic = s
+            .inputs
+            .get(&s.special_inputs.mic1)
+            .map(|input| (input.name.clone(), input.muted));
+        (s.stream_status.active, s.record_status.active, s.record_status.paused, mic)
+    };
+    let live = connected && live;
+    let recording = connected && recording;
+
+    let newly_live = {
+        let mut since = menu.live_since.lock().unwrap();
+        let newly_live = live && since.is_none();
+        if !live {
+            *since = None;
+        } else if newly_live {
+            *since = Some(Instant::now());
+        }
+        newly_live
+    };
+    // Started before we connected: count from OBS's own duration.
+    if newly_live {
+        if let Ok(v) = conn.send_request("GetStreamStatus", None).await {
+            let elapsed = Duration::from_millis(v["outputDuration"].as_u64().unwrap_or(0));
+            if let Some(started) = Instant::now().checked_sub(elapsed) {
+                *menu.live_since.lock().unwrap() = Some(started);
+            }
+        }
+    }
+    let live_secs = menu
+        .live_since
+        .lock()
+        .unwrap()
+        .map(|since| since.elapsed().as_secs());
+
+    let mut parts = Vec::new();
+    if let Some(secs) = live_secs {
+        parts.push(format!("● LIVE {}", clock(secs)));
+    }
+    if recording {
+        parts.push(if record_paused { "Recording paused" } else { "● Recording" }.to_string());
+    }
+    let status = if !connected {
+        "OBS: Disconnected".to_string()
+    } else if parts.is_empty() {
+        "OBS: Connected".to_string()
+    } else {
+        parts.join("  ·  ")
+    };
+
+    let view = TrayView {
+        connected,
+        status,
+        live,
+        recording,
+        mic: if connected { mic } else { None },
+    };
+    {
+        let mut last = menu.view.lock().unwrap();
+        if *last == view {
+            return;
+        }
+        *last = view.clone();
+    }
+
+    let _ = menu.status.set_text(&view.status);
+    let _ = menu
+        .stream
+        .set_text(if view.live { "Stop Streaming" } else { "Start Streaming" });
+    let _ = menu.stream.set_enabled(view.connected);
+    let _ = menu
+        .record
+        .set_text(if view.recording { "Stop Recording" } else { "Start Recording" });
+    let _ = menu.record.set_enabled(view.connected);
+    match &view.mic {
+        Some((name, muted)) => {
+            let verb = if *muted { "Unmute" } else { "Mute" };
+            let _ = menu.mic.set_text(format!("{} {}", verb, name));
+            let _ = menu.mic.set_enabled(true);
+        }
+        None => {
+            let _ = menu.mic.set_text("Mute Mic");
+            let _ = menu.mic.set_enabled(false);
+        }
+    }
+
+    if LIVE.swap(view.live, Ordering::Relaxed) != view.live {
+        apply_icon(app);
+    }
+}
+
+fn clock(secs: u64) -> String {
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Redraws the icon for the panic and live flags.
+fn apply_icon<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let Ok(icon) = Image::from_bytes(include_bytes!("../icons/icon.png")) else {
+        return;
+    };
+    let (width, height) = (icon.width(), icon.height());
+    let mut rgba = icon.rgba().to_vec();
+    if PANIC.load(Ordering::Relaxed) {
+        for px in rgba.chunks_exact_mut(4) {
+            let luma = (px[0] as u16 + px[1] as u16 + px[2] as u16) / 3;
+            px[0] = 160 + (luma * 95 / 255) as u8;
+            px[1] = (luma / 4) as u8;
+            px[2] = (luma / 4) as u8;
+        }
+    }
+    if LIVE.load(Ordering::Relaxed) {
+        draw_live_dot(&mut rgba, width, height);
+    }
+    let _ = tray.set_icon(Some(Image::new_owned(rgba, width, height)));
+}
+
+/// A white-ringed red dot in the bottom-right corner.
+fn draw_live_dot(rgba: &mut [u8], width: u32, height: u32) {
+    let radius = width.min(height) as f32 / 6.0;
+    let ring = (radius / 4.0).max(1.0);
+    let cx = width as f32 - radius - ring;
+    let cy = height as f32 - radius - ring;
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            let dist = (dx * dx + dy * dy).sqrt();
+            if dist > radius + ring {
+                continue;
+            }
+            let i = ((y * width + x) * 4) as usize;
+            let color = if dist <= radius { [230, 30, 30, 255] } else { [255, 255, 255, 255] };
+            rgba[i..i + 4].copy_from_slice(&color);
+        }
+    }
+}
+
+/// Tints the tray icon red and says so in the tooltip while panic mode is on.
+pub fn set_panic<R: Runtime>(app: &AppHandle<R>, engaged: bool) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    PANIC.store(engaged, Ordering::Relaxed);
+    apply_icon(app);
+    let _ = tray.set_tooltip(Some(tooltip()));
+}
+
+/// Names the silent mic in the tooltip until the audio comes back.
+pub fn set_audio_alert<R: Runtime>(app: &AppHandle<R>, silent_input: Option<&str>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    *SILENT_INPUT.lock().unwrap_or_else(|e| e.into_inner()) = silent_input.map(str::to_string);
+    let _ = tray.set_tooltip(Some(tooltip()));
+}
+
+/// Panic mode and a silent mic can overlap; the tooltip names both.
+fn tooltip() -> String {
+    let mut tooltip = "OBServe".to_string();
+    if PANIC.load(Ordering::Relaxed) {
+        tooltip.push_str(" - PANIC MODE");
+    }
+    if let Some(input) = SILENT_INPUT.lock().unwrap_or_else(|e| e.into_inner()).as_deref() {
+        tooltip.push_str(&format!(" - No audio from {}", input));
+    }
+    tooltip
+}
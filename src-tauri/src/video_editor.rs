@@ -1,9 +1,10 @@
 use crate::obs_launcher;
 use crate::obs_state::SharedObsState;
+use crate::settings::{self, SharedSettings};
 use crate::store::SharedLicenseState;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -16,8 +17,12 @@ pub struct VideoEditorState {
     pub ffprobe_path: Option<PathBuf>,
     pub temp_dir: PathBuf,
     pub thumbnail_cache: HashMap<String, String>,
+    pub thumbnail_queue: ThumbnailQueue,
     pub export_progress: ExportProgress,
     pub export_cancel: Arc<AtomicBool>,
+    pub remux_in_flight: HashSet<PathBuf>,
+    /// Proxy jobs by source path, kept after they finish for get_proxy_status.
+    pub proxy_jobs: HashMap<String, ProxyJob>,
 }
 
 impl VideoEditorState {
@@ -26,15 +31,45 @@ impl VideoEditorState {
         let _ = std::fs::create_dir_all(&temp_dir);
         let _ = std::fs::create_dir_all(temp_dir.join("thumbnails"));
         let _ = std::fs::create_dir_all(temp_dir.join("remuxed"));
+        let _ = std::fs::create_dir_all(temp_dir.join("proxies"));
         Self {
             ffmpeg_path: None,
             ffprobe_path: None,
             temp_dir,
             thumbnail_cache: HashMap::new(),
+            thumbnail_queue: ThumbnailQueue::default(),
             export_progress: ExportProgress::default(),
             export_cancel: Arc::new(AtomicBool::new(false)),
+            remux_in_flight: HashSet::new(),
+            proxy_jobs: HashMap::new(),
         }
     }
+
+    pub fn with_saved_ffmpeg(mut self, path: Option<&str>) -> Self {
+        if let Some(ffmpeg) = path.map(PathBuf::from).filter(|p| p.exists()) {
+            self.ffprobe_path = ffmpeg
+                .parent()
+                .map(|d| d.join("ffprobe.exe"))
+                .filter(|p| p.exists());
+            self.ffmpeg_path = Some(ffmpeg);
+        }
+        self
+    }
+}
+
+struct ThumbnailJob {
+    key: String,
+    path: String,
+    timestamp: f64,
+}
+
+/// Pending thumbnail work for the recordings browser. Jobs are taken newest
+/// first, since the latest batch is what the user is looking at.
+#[derive(Default)]
+pub struct ThumbnailQueue {
+    pending: Vec<ThumbnailJob>,
+    in_flight: HashSet<String>,
+    workers: usize,
 }
 
 // ---- Serializable Types ----
@@ -66,6 +101,27 @@ pub struct VideoInfo {
     pub fps: f64,
     pub video_codec: String,
     pub audio_codec: String,
+    #[serde(default)]
+    pub audio_streams: Vec<AudioStreamInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioStreamInfo {
+    pub index: u32,
+    pub stream_index: u32,
+    pub codec: String,
+    pub channels: u32,
+    pub language: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioTrackGain {
+    pub index: u32,
+    #[serde(default)]
+    pub gain_db: f64,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -116,6 +172,28 @@ pub struct ExportRequest {
     pub captions: Option<CaptionExportRequest>,
     #[serde(default)]
     pub audio_narration: Option<AudioNarrationRequest>,
+    #[serde(default)]
+    pub audio_track_mode: Option<String>,
+    #[serde(default)]
+    pub audio_tracks: Vec<AudioTrackGain>,
+    /// "9:16" crops and scales to 1080x1920; None keeps the source frame.
+    #[serde(default)]
+    pub aspect: Option<String>,
+    /// Pans the 9:16 crop; empty means a centered crop.
+    #[serde(default)]
+    pub crop_keyframes: Vec<CropKeyframe>,
+    /// Two-pass loudnorm on the final mix; None leaves levels alone.
+    #[serde(default)]
+    pub audio_normalize: Option<AudioNormalize>,
+    /// Frame rate and size for "gif" exports; None uses the defaults.
+    #[serde(default)]
+    pub gif: Option<GifOptions>,
+    /// Chapter marks in source time; remapped through the kept segments.
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+    /// An .ass or .srt in source time to burn into the video.
+    #[serde(default)]
+    pub subtitle_path: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -125,6 +203,25 @@ pub struct ExportProgress {
     pub eta_seconds: f64,
     pub status: String,
     pub error: Option<String>,
+    /// "splitting", "concatenating", "encoding" or "finalizing".
+    #[serde(default)]
+    pub stage: String,
+    #[serde(default)]
+    pub stage_percent: f64,
+    /// 1-based clip being written by export_clips; 0 for single exports.
+    #[serde(default)]
+    pub clip_index: usize,
+    #[serde(default)]
+    pub clip_count: usize,
+    #[serde(default)]
+    pub clip_percent: f64,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Integrated loudness of the edit before normalization, when measured.
+    #[serde(default)]
+    pub input_lufs: Option<f64>,
+    #[serde(default)]
+    pub target_lufs: Option<f64>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -142,6 +239,10 @@ pub struct EditProjectSave {
     pub narration_audio_path: Option<String>,
     #[serde(default)]
     pub narration_takes: Option<Vec<NarrationTake>>,
+    #[serde(default)]
+    pub last_export_preset: Option<String>,
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -317,12 +418,31 @@ fn detect_ffmpeg_inner() -> (Option<PathBuf>, Option<PathBuf>) {
 pub async fn detect_ffmpeg(
     license: tauri::State<'_, SharedLicenseState>,
     state: tauri::State<'_, SharedVideoEditorState>,
+    settings: tauri::State<'_, SharedSettings>,
 ) -> Result<FfmpegStatus, String> {
     crate::store::require_module(&license, "video-editor").await?;
+    {
+        let s = state.lock().await;
+        if let Some(ffmpeg) = s.ffmpeg_path.as_ref().filter(|p| p.exists()) {
+            return Ok(FfmpegStatus {
+                found: true,
+                path: Some(ffmpeg.to_string_lossy().to_string()),
+                ffprobe_path: s.ffprobe_path.as_ref().map(|p| p.to_string_lossy().to_string()),
+            });
+        }
+    }
+
     let (ffmpeg, ffprobe) = tokio::task::spawn_blocking(detect_ffmpeg_inner)
         .await
         .map_err(|e| format!("Task failed: {}", e))?;
 
+    if let Some(p) = &ffmpeg {
+        let saved = p.to_string_lossy().to_string();
+        if let Err(e) = settings::update_section(&settings, |s| s.video_editor.ffmpeg_path = Some(saved)).await {
+            log::warn!("Failed to save ffmpeg path: {}", e);
+        }
+    }
+
     let mut s = state.lock().await;
     s.ffmpeg_path = ffmpeg.clone();
     s.ffprobe_path = ffprobe.clone();
@@ -358,43 +478,54 @@ pub async fn list_recordings(
         .map_err(|e| format!("Task failed: {}", e))?
 }
 
-fn list_video_files(dir: &str) -> Result<Vec<VideoFileInfo>, String> {
+pub(crate) fn list_video_files(dir: &str) -> Result<Vec<VideoFileInfo>, String> {
     let path = Path::new(dir);
     if !path.exists() {
         return Err(format!("Directory not found: {}", dir));
     }
 
-    let extensions = ["mkv", "mp4", "flv", "mov", "ts", "webm"];
     let mut files: Vec<VideoFileInfo> = std::fs::read_dir(path)
         .map_err(|e| format!("Cannot read directory: {}", e))?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            let ext = path.extension()?.to_str()?.to_lowercase();
-            if !extensions.contains(&ext.as_str()) {
-                return None;
-            }
-            let meta = entry.metadata().ok()?;
-            let modified = meta
-                .modified()
-                .ok()?
-                .duration_since(std::time::UNIX_EPOCH)
-                .ok()?
-                .as_secs();
-            Some(VideoFileInfo {
-                path: path.to_string_lossy().to_string(),
-                name: path.file_name()?.to_string_lossy().to_string(),
-                size_bytes: meta.len(),
-                modified,
-                extension: ext,
-            })
-        })
+        .filter_map(|entry| video_file_info(&entry.ok()?.path()))
         .collect();
 
     files.sort_by(|a, b| b.modified.cmp(&a.modified));
     Ok(files)
 }
 
+const VIDEO_EXTENSIONS: &[&str] = &["mkv", "mp4", "flv", "mov", "ts", "webm"];
+
+fn video_file_info(path: &Path) -> Option<VideoFileInfo> {
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    if !VIDEO_EXTENSIONS.contains(&ext.as_str()) {
+        return None;
+    }
+    let meta = std::fs::metadata(path).ok()?;
+    if !meta.is_file() {
+        return None;
+    }
+    let modified = meta
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some(VideoFileInfo {
+        path: path.to_string_lossy().to_string(),
+        name: path.file_name()?.to_string_lossy().to_string(),
+        size_bytes: meta.len(),
+        modified,
+        extension: ext,
+    })
+}
+
+fn new_job_dir(temp_dir: &Path) -> Result<(String, PathBuf), String> {
+    let job_id = uuid::Uuid::new_v4().to_string();
+    let dir = temp_dir.join("jobs").join(&job_id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create job dir: {}", e))?;
+    Ok((job_id, dir))
+}
+
 #[tauri::command]
 pub async fn remux_to_mp4(
     license: tauri::State<'_, SharedLicenseState>,
@@ -415,14 +546,25 @@ pub async fn remux_to_mp4(
         return Ok(source_path);
     }
 
-    let s = state.lock().await;
+    let mut s = state.lock().await;
     let ffmpeg = s
         .ffmpeg_path
         .clone()
         .ok_or("FFmpeg not found. Run detect_ffmpeg first.")?;
     let remux_dir = s.temp_dir.join("remuxed");
+    if !s.remux_in_flight.insert(src.clone()) {
+        return Err(format!("{} is already being remuxed", source_path));
+    }
     drop(s);
 
+    let result = remux_cached(&ffmpeg, &src, &remux_dir).await;
+    state.lock().await.remux_in_flight.remove(&src);
+    result
+}
+
+async fn remux_cached(ffmpeg: &Path, src: &Path, remux_dir: &Path) -> Result<String, String> {
+    let source_path = src.to_string_lossy().to_string();
+
     let stem = src
         .file_stem()
         .map(|s| s.to_string_lossy().to_string())
@@ -430,7 +572,7 @@ pub async fn remux_to_mp4(
     let output = remux_dir.join(format!("{}.mp4", stem));
 
     if output.exists() {
-        let src_modified = std::fs::metadata(&src)
+        let src_modified = std::fs::metadata(src)
             .and_then(|m| m.modified())
             .ok();
         let out_modified = std::fs::metadata(&output)
@@ -444,11 +586,13 @@ pub async fn remux_to_mp4(
     }
 
     let output_str = output.to_string_lossy().to_string();
-    let result = tokio::process::Command::new(&ffmpeg)
+    let result = tokio::process::Command::new(ffmpeg)
         .args([
             "-y",
             "-i",
             &source_path,
+            "-map",
+            "0",
             "-c",
             "copy",
             "-movflags",
@@ -506,6 +650,7 @@ pub async fn get_video_info(
 
     let mut video_codec = String::new();
     let mut audio_codec = String::new();
+    let mut audio_streams: Vec<AudioStreamInfo> = Vec::new();
     let mut width = 0u32;
     let mut height = 0u32;
     let mut fps = 0.0f64;
@@ -532,10 +677,21 @@ pub async fn get_video_info(
                     }
                 }
                 Some("audio") => {
-                    audio_codec = stream["codec_name"]
+                    let codec = stream["codec_name"]
                         .as_str()
                         .unwrap_or("")
                         .to_string();
+                    if audio_streams.is_empty() {
+                        audio_codec = codec.clone();
+                    }
+                    audio_streams.push(AudioStreamInfo {
+                        index: audio_streams.len() as u32,
+                        stream_index: stream["index"].as_u64().unwrap_or(0) as u32,
+                        codec,
+                        channels: stream["channels"].as_u64().unwrap_or(0) as u32,
+                        language: stream["tags"]["language"].as_str().map(String::from),
+                        title: stream["tags"]["title"].as_str().map(String::from),
+                    });
                 }
                 _ => {}
             }
@@ -554,22 +710,27 @@ pub async fn get_video_info(
         fps,
         video_codec,
         audio_codec,
+        audio_streams,
     })
 }
 
 // ---- Phase 2: Browser + File Management Commands ----
 
+fn thumbnail_key(path: &str, timestamp: f64) -> String {
+    format!(
+        "{}_{:.0}",
+        path.replace(['\\', '/', ':', '.'], "_"),
+        timestamp * 1000.0
+    )
+}
+
 #[tauri::command]
 pub async fn get_video_thumbnail(
     state: tauri::State<'_, SharedVideoEditorState>,
     path: String,
     timestamp: f64,
 ) -> Result<String, String> {
-    let cache_key = format!(
-        "{}_{:.0}",
-        path.replace(['\\', '/', ':', '.'], "_"),
-        timestamp * 1000.0
-    );
+    let cache_key = thumbnail_key(&path, timestamp);
 
     {
         let s = state.lock().await;
@@ -586,11 +747,46 @@ pub async fn get_video_thumbnail(
     let thumb_dir = s.temp_dir.join("thumbnails");
     drop(s);
 
+    let base64 = extract_thumbnail(&ffmpeg, &thumb_dir, &path, timestamp, &cache_key).await?;
+
+    let mut s = state.lock().await;
+    s.thumbnail_cache.insert(cache_key, base64.clone());
+
+    Ok(base64)
+}
+
+/// Renders one thumbnail, reusing the PNG on disk when it is newer than the
+/// video so reopening the browser skips ffmpeg.
+async fn extract_thumbnail(
+    ffmpeg: &Path,
+    thumb_dir: &Path,
+    path: &str,
+    timestamp: f64,
+    cache_key: &str,
+) -> Result<String, String> {
     let thumb_path = thumb_dir.join(format!("{}.png", cache_key));
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    let fresh = match (modified(&thumb_path), modified(Path::new(path))) {
+        (Some(thumb), Some(video)) => thumb >= video,
+        _ => false,
+    };
+    if !fresh {
+        render_thumbnail(ffmpeg, &thumb_path, path, timestamp).await?;
+    }
+
+    let png_data = std::fs::read(&thumb_path)
+        .map_err(|e| format!("Failed to read thumbnail: {}", e))?;
+    Ok(format!(
+        "data:image/png;base64,{}",
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &png_data)
+    ))
+}
+
+async fn render_thumbnail(ffmpeg: &Path, thumb_path: &Path, path: &str, timestamp: f64) -> Result<(), String> {
     let thumb_str = thumb_path.to_string_lossy().to_string();
     let ts_str = format!("{:.3}", timestamp);
 
-    let result = tokio::process::Command::new(&ffmpeg)
+    let result = tokio::process::Command::new(ffmpeg)
         .args([
             "-y",
             "-ss",
@@ -610,18 +806,110 @@ pub async fn get_video_thumbnail(
     if !result.status.success() {
         return Err("Failed to extract thumbnail".to_string());
     }
+    Ok(())
+}
 
-    let png_data = std::fs::read(&thumb_path)
-        .map_err(|e| format!("Failed to read thumbnail: {}", e))?;
-    let base64 = format!(
-        "data:image/png;base64,{}",
-        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &png_data)
-    );
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ThumbnailReady {
+    path: String,
+    timestamp: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn emit_thumbnail(app: &tauri::AppHandle, ready: ThumbnailReady) {
+    let _ = tauri::Emitter::emit(app, "video-editor://thumbnail-ready", &ready);
+}
 
+/// Queues thumbnails for the recordings browser. Cached ones are sent back
+/// right away; the rest arrive as `video-editor://thumbnail-ready` events.
+#[tauri::command]
+pub async fn request_thumbnails(
+    state: tauri::State<'_, SharedVideoEditorState>,
+    settings: tauri::State<'_, SharedSettings>,
+    app_handle: tauri::AppHandle,
+    batch: Vec<(String, f64)>,
+) -> Result<(), String> {
+    let max_workers = settings.read().await.video_editor.thumbnail_concurrency.max(1);
     let mut s = state.lock().await;
-    s.thumbnail_cache.insert(cache_key, base64.clone());
+    if s.ffmpeg_path.is_none() {
+        return Err("FFmpeg not found".to_string());
+    }
+    // Pushed in reverse so the first entry of the batch is taken first.
+    for (path, timestamp) in batch.into_iter().rev() {
+        let key = thumbnail_key(&path, timestamp);
+        if let Some(cached) = s.thumbnail_cache.get(&key) {
+            emit_thumbnail(&app_handle, ThumbnailReady {
+                path,
+                timestamp,
+                thumbnail: Some(cached.clone()),
+                error: None,
+            });
+            continue;
+        }
+        let queue = &mut s.thumbnail_queue;
+        if queue.in_flight.contains(&key) {
+            continue;
+        }
+        queue.pending.retain(|job| job.key != key);
+        queue.pending.push(ThumbnailJob { key, path, timestamp });
+    }
 
-    Ok(base64)
+    let wanted = s.thumbnail_queue.pending.len().min(max_workers);
+    while s.thumbnail_queue.workers < wanted {
+        s.thumbnail_queue.workers += 1;
+        tauri::async_runtime::spawn(thumbnail_worker(state.inner().clone(), app_handle.clone()));
+    }
+    Ok(())
+}
+
+async fn thumbnail_worker(state: SharedVideoEditorState, app_handle: tauri::AppHandle) {
+    loop {
+        let (job, ffmpeg, thumb_dir) = {
+            let mut s = state.lock().await;
+            let (Some(job), Some(ffmpeg)) = (s.thumbnail_queue.pending.pop(), s.ffmpeg_path.clone()) else {
+                s.thumbnail_queue.workers -= 1;
+                return;
+            };
+            s.thumbnail_queue.in_flight.insert(job.key.clone());
+            (job, ffmpeg, s.temp_dir.join("thumbnails"))
+        };
+
+        let result = extract_thumbnail(&ffmpeg, &thumb_dir, &job.path, job.timestamp, &job.key).await;
+
+        {
+            let mut s = state.lock().await;
+            s.thumbnail_queue.in_flight.remove(&job.key);
+            if let Ok(thumbnail) = &result {
+                s.thumbnail_cache.insert(job.key.clone(), thumbnail.clone());
+            }
+        }
+        let (thumbnail, error) = match result {
+            Ok(t) => (Some(t), None),
+            Err(e) => (None, Some(e)),
+        };
+        emit_thumbnail(&app_handle, ThumbnailReady {
+            path: job.path,
+            timestamp: job.timestamp,
+            thumbnail,
+            error,
+        });
+    }
+}
+
+/// Drops queued thumbnails, e.g. when the user leaves the browser. Ones
+/// already rendering still finish and land in the cache.
+#[tauri::command]
+pub async fn cancel_thumbnail_requests(
+    state: tauri::State<'_, SharedVideoEditorState>,
+) -> Result<usize, String> {
+    let mut s = state.lock().await;
+    let dropped = s.thumbnail_queue.pending.len();
+    s.thumbnail_queue.pending.clear();
+    Ok(dropped)
 }
 
 #[tauri::command]
@@ -864,20 +1152,42 @@ pub async fn export_video(
     state: tauri::State<'_, SharedVideoEditorState>,
     app_handle: tauri::AppHandle,
     request: ExportRequest,
-) -> Result<(), String> {
+) -> Result<Vec<String>, String> {
     crate::store::require_module(&license, "video-editor").await?;
+    start_export(state.inner(), app_handle, request).await?;
+    let warnings = state.lock().await.export_progress.warnings.clone();
+    Ok(warnings)
+}
+
+pub(crate) async fn start_export(
+    state: &SharedVideoEditorState,
+    app_handle: tauri::AppHandle,
+    mut request: ExportRequest,
+) -> Result<String, String> {
+    let format = ExportFormat::parse(&request.format)?;
     let s = state.lock().await;
     let ffmpeg = s
         .ffmpeg_path
         .clone()
         .ok_or("FFmpeg not found")?;
+    let ffprobe = s.ffprobe_path.clone();
     let temp_dir = s.temp_dir.clone();
     let cancel_flag = s.export_cancel.clone();
     drop(s);
 
+    let reframe = Reframe::for_request(ffprobe.as_deref(), &request).await?;
+    let mut warnings = reframe
+        .as_ref()
+        .map(|r| r.overlay_warnings(&request.overlays))
+        .unwrap_or_default();
+    if let Some(corrected) = correct_extension(&request.output_path, format) {
+        warnings.push(format!("Saving as {} to match the {} format", corrected, format.extension()));
+        request.output_path = corrected;
+    }
+
     cancel_flag.store(false, Ordering::SeqCst);
 
-    let state_clone = state.inner().clone();
+    let state_clone = state.clone();
     let active_segments: Vec<Segment> = request
         .segments
         .iter()
@@ -888,6 +1198,7 @@ pub async fn export_video(
     if active_segments.is_empty() {
         return Err("No segments to export".to_string());
     }
+    let (job_id, job_dir) = new_job_dir(&temp_dir)?;
 
     {
         let mut s = state_clone.lock().await;
@@ -896,20 +1207,31 @@ pub async fn export_video(
             eta_seconds: 0.0,
             status: "starting".to_string(),
             error: None,
+            stage: String::new(),
+            stage_percent: 0.0,
+            warnings,
+            ..Default::default()
         };
     }
 
     tauri::async_runtime::spawn(async move {
+        let started = std::time::Instant::now();
         let result = run_export(
             &ffmpeg,
-            &temp_dir,
+            &job_dir,
             &request,
             &active_segments,
+            reframe.as_ref(),
             &state_clone,
             &cancel_flag,
             &app_handle,
+            started,
         )
         .await;
+        if result.is_ok() {
+            report_export_progress(&state_clone, &app_handle, &ExportStage::FINALIZE, 0.0, started).await;
+        }
+        let _ = tokio::fs::remove_dir_all(&job_dir).await;
 
         let mut s = state_clone.lock().await;
         match result {
@@ -931,7 +1253,7 @@ pub async fn export_video(
         let _ = tauri::Emitter::emit(&app_handle, "video-editor://export-progress", &progress);
     });
 
-    Ok(())
+    Ok(job_id)
 }
 
 fn escape_ass_path_for_ffmpeg(path: &Path) -> String {
@@ -940,27 +1262,126 @@ fn escape_ass_path_for_ffmpeg(path: &Path) -> String {
         .replace(':', "\\:")
 }
 
+fn audio_track_mode(request: &ExportRequest) -> &'static str {
+    match request.audio_track_mode.as_deref() {
+        Some("single") if !request.audio_tracks.is_empty() => "single",
+        Some("mix") if !request.audio_tracks.is_empty() => "mix",
+        Some("all") => "all",
+        _ => "first",
+    }
+}
+
+fn audio_map_args(request: &ExportRequest) -> Vec<String> {
+    match audio_track_mode(request) {
+        "single" => vec![
+            "-map".into(),
+            "0:v:0".into(),
+            "-map".into(),
+            format!("0:a:{}", request.audio_tracks[0].index),
+        ],
+        "all" => vec!["-map".into(), "0:v:0".into(), "-map".into(), "0:a?".into()],
+        _ => Vec::new(),
+    }
+}
+
+fn audio_filter_sources(request: &ExportRequest, segment_count: usize) -> (String, Vec<String>) {
+    match audio_track_mode(request) {
+        "single" => {
+            let label = format!("0:a:{}", request.audio_tracks[0].index);
+            (String::new(), vec![label; segment_count])
+        }
+        "mix" => {
+            let mut prelude = String::new();
+            for (i, track) in request.audio_tracks.iter().enumerate() {
+                prelude.push_str(&format!(
+                    "[0:a:{}]volume={:.2}dB[amt{i}]; ",
+                    track.index, track.gain_db
+                ));
+            }
+            let n = request.audio_tracks.len();
+            let labels: String = (0..n).map(|i| format!("[amt{i}]")).collect();
+            prelude.push_str(&format!(
+                "{labels}amix=inputs={n}:duration=longest:normalize=0[amixed]; "
+            ));
+            if segment_count > 1 {
+                let outs: String = (0..segment_count).map(|i| format!("[asrc{i}]")).collect();
+                prelude.push_str(&format!("[amixed]asplit={segment_count}{outs}; "));
+                (prelude, (0..segment_count).map(|i| format!("asrc{i}")).collect())
+            } else {
+                (prelude, vec!["amixed".to_string()])
+            }
+        }
+        _ => (String::new(), vec!["0:a".to_string(); segment_count]),
+    }
+}
+
+/// `-i` args for the source followed by each image overlay, in the input
+/// order build_filter_complex numbers them.
+fn overlay_inputs(source_path: &str, overlays: &[Overlay]) -> Vec<String> {
+    let mut inputs = vec!["-i".to_string(), source_path.to_string()];
+    for overlay in overlays {
+        if overlay.overlay_type == "image" && !overlay.content.is_empty() {
+            inputs.extend(["-i".to_string(), overlay.content.clone()]);
+        }
+    }
+    inputs
+}
+
+/// Encodes the edit, then adds chapters if the request has any.
+#[allow(clippy::too_many_arguments)]
 async fn run_export(
     ffmpeg: &Path,
     temp_dir: &Path,
     request: &ExportRequest,
     segments: &[Segment],
+    reframe: Option<&Reframe>,
     state: &SharedVideoEditorState,
     cancel: &AtomicBool,
     app_handle: &tauri::AppHandle,
+    started: std::time::Instant,
 ) -> Result<(), String> {
-    let has_overlays = !request.overlays.is_empty();
-    let crf = match request.quality.as_str() {
-        "high" => "18",
-        "low" => "28",
-        _ => "23",
+    encode_export(ffmpeg, temp_dir, request, segments, reframe, state, cancel, app_handle, started).await?;
+    if request.chapters.is_empty() {
+        return Ok(());
+    }
+    let chapters = remap_chapters(&request.chapters, segments);
+    let duration: f64 = segments.iter().map(|s| s.end - s.start).sum();
+    embed_chapters(ffmpeg, temp_dir, request, &chapters, duration, started, state, cancel, app_handle).await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn encode_export(
+    ffmpeg: &Path,
+    temp_dir: &Path,
+    request: &ExportRequest,
+    segments: &[Segment],
+    reframe: Option<&Reframe>,
+    state: &SharedVideoEditorState,
+    cancel: &AtomicBool,
+    app_handle: &tauri::AppHandle,
+    started: std::time::Instant,
+) -> Result<(), String> {
+    let overlays = match reframe {
+        Some(r) => r.map_overlays(&request.overlays),
+        None => request.overlays.clone(),
     };
+    let reframe_filter = reframe.map(Reframe::filter);
+    let has_overlays = !overlays.is_empty();
+    let format = ExportFormat::parse(&request.format)?;
+    let crf = x264_crf(&request.quality);
 
     let total_duration: f64 = segments.iter().map(|s| s.end - s.start).sum();
 
     let ass_path = if let Some(ref cap_req) = request.captions {
         if !cap_req.captions.is_empty() {
-            let content = generate_ass_content(cap_req);
+            let content = match reframe {
+                Some(_) => generate_ass_content(&CaptionExportRequest {
+                    video_width: VERTICAL_WIDTH,
+                    video_height: VERTICAL_HEIGHT,
+                    ..cap_req.clone()
+                }),
+                None => generate_ass_content(cap_req),
+            };
             let p = temp_dir.join("export_captions.ass");
             std::fs::write(&p, &content)
                 .map_err(|e| format!("Write ASS failed: {}", e))?;
@@ -972,63 +1393,102 @@ async fn run_export(
         None
     };
     let has_captions = ass_path.is_some();
+    let subtitle_burn = match request.subtitle_path.as_deref() {
+        Some(path) => Some(prepare_subtitles(path, segments, temp_dir)?),
+        None => None,
+    };
 
     let audio_mode = request.audio_narration.as_ref().map(|a| a.audio_mode.as_str());
     let needs_narration_mix = matches!(audio_mode, Some("narration_replaces") | Some("duck"));
+    let track_mode = audio_track_mode(request);
+    let map_args = audio_map_args(request);
+    let (audio_prelude, audio_labels) = audio_filter_sources(request, segments.len());
+    let is_mute_all = matches!(audio_mode, Some("mute_all"));
+    // Muted exports and GIFs have nothing to normalize.
+    let normalize = request
+        .audio_normalize
+        .as_ref()
+        .filter(|_| !is_mute_all && format.has_audio());
+    let encode_stage = match normalize {
+        Some(_) => ExportStage::encode_after_measure(total_duration),
+        None => ExportStage::encode(total_duration),
+    };
+    // Only the filter-graph paths below use this, and filtered video can't
+    // be stream copied.
+    let video_codec = if request.video_codec == "copy" {
+        "libx264"
+    } else {
+        request.video_codec.as_str()
+    };
+
+    // Reframing, normalization, subtitle burn-in and the non-copy formats
+    // only exist in the filter graph.
+    let needs_filter = reframe.is_some()
+        || normalize.is_some()
+        || subtitle_burn.is_some()
+        || !format.can_stream_copy();
+    // The filter graph carries a single audio stream, so "all" only works
+    // on the paths that map the source tracks directly.
+    let filtered = (needs_narration_mix && format.has_audio())
+        || has_overlays
+        || needs_filter
+        || (has_captions && segments.len() > 1);
+    if track_mode == "all" && filtered && format.has_audio() && !is_mute_all {
+        if let Some(ref p) = ass_path { let _ = std::fs::remove_file(p); }
+        return Err("Keeping all audio tracks needs a plain cut; pick one track or mix them to use narration, overlays, captions, reframing or normalization".into());
+    }
 
-    if needs_narration_mix {
+    if needs_narration_mix && format.has_audio() {
         let ass_ref = ass_path.as_deref();
         let narr = request.audio_narration.as_ref().unwrap();
         let mut narr_input_idx: usize = 1;
-        for overlay in &request.overlays {
+        for overlay in &overlays {
             if overlay.overlay_type == "image" && !overlay.content.is_empty() {
                 narr_input_idx += 1;
             }
         }
-        let filter = build_filter_complex(segments, &request.overlays, &request.source_path, ass_ref, Some(narr), Some(narr_input_idx));
-        let mut cmd = tokio::process::Command::new(ffmpeg);
-        cmd.args(["-y", "-progress", "pipe:1", "-i", &request.source_path]);
-
-        for overlay in &request.overlays {
-            if overlay.overlay_type == "image" && !overlay.content.is_empty() {
-                cmd.args(["-i", &overlay.content]);
-            }
-        }
-
+        let mut filter = format!(
+            "{}{}",
+            audio_prelude,
+            build_filter_complex(segments, &overlays, &request.source_path, ass_ref, subtitle_burn.as_deref(), Some(narr), Some(narr_input_idx), &audio_labels, reframe_filter.as_deref())
+        );
+        let mut inputs = overlay_inputs(&request.source_path, &overlays);
         if !narr.narration_takes.is_empty() {
             for take in &narr.narration_takes {
-                cmd.args(["-i", &take.audio_path]);
+                inputs.extend(["-i".to_string(), take.audio_path.clone()]);
             }
         } else if let Some(ref path) = narr.narration_audio_path {
-            cmd.args(["-i", path]);
+            inputs.extend(["-i".to_string(), path.clone()]);
         }
+        let audio_out = match normalize {
+            Some(n) => {
+                apply_normalize(ffmpeg, n, &inputs, &mut filter, total_duration, started, state, cancel, app_handle).await?
+            }
+            None => "[afinal]",
+        };
 
+        let mut cmd = tokio::process::Command::new(ffmpeg);
+        cmd.args(["-y", "-progress", "pipe:1"]);
+        cmd.args(&inputs);
         cmd.args([
             "-filter_complex",
             &filter,
             "-map",
             "[vfinal]",
             "-map",
-            "[afinal]",
-            "-c:v",
-            &request.video_codec,
-            "-crf",
-            crf,
-            "-c:a",
-            "aac",
-            "-movflags",
-            "+faststart",
-            &request.output_path,
+            audio_out,
         ]);
+        cmd.args(format.video_args(video_codec, &request.quality));
+        cmd.args(format.audio_args());
+        cmd.args(format.mux_args());
+        cmd.arg(&request.output_path);
 
-        let result = run_ffmpeg_with_progress(cmd, total_duration, state, cancel, app_handle).await;
+        let result = run_ffmpeg_with_progress(cmd, &encode_stage, started, state, cancel, app_handle).await;
         if let Some(ref p) = ass_path { let _ = std::fs::remove_file(p); }
         return result;
     }
 
-    let is_mute_all = matches!(audio_mode, Some("mute_all"));
-
-    if !has_overlays && !has_captions && segments.len() == 1 {
+    if !has_overlays && !has_captions && !needs_filter && segments.len() == 1 && track_mode != "mix" {
         let seg = &segments[0];
         let is_copy = request.video_codec == "copy";
         let mut cmd = tokio::process::Command::new(ffmpeg);
@@ -1043,6 +1503,7 @@ async fn run_export(
             "-i",
             &request.source_path,
         ]);
+        cmd.args(&map_args);
 
         if is_copy {
             cmd.args(["-c", "copy"]);
@@ -1050,17 +1511,16 @@ async fn run_export(
             cmd.args(["-c:v", &request.video_codec, "-crf", crf]);
         }
         if is_mute_all {
-            cmd.args(["-an", "-movflags", "+faststart", &request.output_path]);
+            cmd.arg("-an");
         } else if !is_copy {
-            cmd.args(["-c:a", "aac", "-movflags", "+faststart", &request.output_path]);
-        } else {
-            cmd.args(["-movflags", "+faststart", &request.output_path]);
+            cmd.args(["-c:a", "aac"]);
         }
+        cmd.args(format.mux_args()).arg(&request.output_path);
 
-        return run_ffmpeg_with_progress(cmd, total_duration, state, cancel, app_handle).await;
+        return run_ffmpeg_with_progress(cmd, &encode_stage, started, state, cancel, app_handle).await;
     }
 
-    if !has_overlays && has_captions && segments.len() == 1 {
+    if !has_overlays && has_captions && !needs_filter && segments.len() == 1 && track_mode != "mix" {
         let seg = &segments[0];
         let escaped = escape_ass_path_for_ffmpeg(ass_path.as_ref().unwrap());
         let vf = format!("ass='{}'", escaped);
@@ -1082,19 +1542,34 @@ async fn run_export(
             "-crf",
             crf,
         ]);
+        cmd.args(&map_args);
         if is_mute_all {
-            cmd.args(["-an", "-movflags", "+faststart", &request.output_path]);
+            cmd.arg("-an");
         } else {
-            cmd.args(["-c:a", "aac", "-movflags", "+faststart", &request.output_path]);
+            cmd.args(["-c:a", "aac"]);
         }
+        cmd.args(format.mux_args()).arg(&request.output_path);
 
-        let result = run_ffmpeg_with_progress(cmd, total_duration, state, cancel, app_handle).await;
+        let result = run_ffmpeg_with_progress(cmd, &encode_stage, started, state, cancel, app_handle).await;
         if let Some(ref p) = ass_path { let _ = std::fs::remove_file(p); }
         return result;
     }
 
-    if !has_overlays && !has_captions {
-        let seg_files = split_segments(ffmpeg, temp_dir, &request.source_path, segments, cancel).await?;
+    if !has_overlays && !has_captions && !needs_filter && track_mode != "mix" {
+        let (split_stages, concat_stage) = ExportStage::split_and_concat(segments);
+        let seg_files = split_segments(
+            ffmpeg,
+            temp_dir,
+            &request.source_path,
+            segments,
+            &map_args,
+            &split_stages,
+            started,
+            state,
+            cancel,
+            app_handle,
+        )
+        .await?;
         let concat_file = temp_dir.join("export_concat.txt");
         let concat_content: String = seg_files
             .iter()
@@ -1116,13 +1591,17 @@ async fn run_export(
             "-i",
             &concat_file.to_string_lossy(),
         ]);
+        if track_mode == "all" {
+            cmd.args(["-map", "0"]);
+        }
         if is_mute_all {
-            cmd.args(["-c:v", "copy", "-an", "-movflags", "+faststart", &request.output_path]);
+            cmd.args(["-c:v", "copy", "-an"]);
         } else {
-            cmd.args(["-c", "copy", "-movflags", "+faststart", &request.output_path]);
+            cmd.args(["-c", "copy"]);
         }
+        cmd.args(format.mux_args()).arg(&request.output_path);
 
-        let result = run_ffmpeg_with_progress(cmd, total_duration, state, cancel, app_handle).await;
+        let result = run_ffmpeg_with_progress(cmd, &concat_stage, started, state, cancel, app_handle).await;
 
         for f in &seg_files {
             let _ = std::fs::remove_file(f);
@@ -1132,113 +1611,114 @@ async fn run_export(
     }
 
     let ass_ref = ass_path.as_deref();
-    let filter = build_filter_complex(segments, &request.overlays, &request.source_path, ass_ref, None, None);
-    let mut cmd = tokio::process::Command::new(ffmpeg);
-    cmd.args(["-y", "-progress", "pipe:1", "-i", &request.source_path]);
-
-    for overlay in &request.overlays {
-        if overlay.overlay_type == "image" && !overlay.content.is_empty() {
-            cmd.args(["-i", &overlay.content]);
+    let mut filter = format!(
+        "{}{}",
+        audio_prelude,
+        build_filter_complex(segments, &overlays, &request.source_path, ass_ref, subtitle_burn.as_deref(), None, None, &audio_labels, reframe_filter.as_deref())
+    );
+    let inputs = overlay_inputs(&request.source_path, &overlays);
+    if format == ExportFormat::Gif {
+        let gif = request.gif.clone().unwrap_or_default();
+        let result = run_gif_export(
+            ffmpeg,
+            temp_dir,
+            &request.output_path,
+            &inputs,
+            &filter,
+            &gif,
+            &encode_stage,
+            started,
+            state,
+            cancel,
+            app_handle,
+        )
+        .await;
+        if let Some(ref p) = ass_path { let _ = std::fs::remove_file(p); }
+        return result;
+    }
+    let audio_out = match normalize {
+        Some(n) => {
+            apply_normalize(ffmpeg, n, &inputs, &mut filter, total_duration, started, state, cancel, app_handle).await?
         }
+        None => "[afinal]",
+    };
+    let mut cmd = tokio::process::Command::new(ffmpeg);
+    cmd.args(["-y", "-progress", "pipe:1"]);
+    cmd.args(&inputs);
+    cmd.args(["-filter_complex", &filter, "-map", "[vfinal]"]);
+    cmd.args(format.video_args(video_codec, &request.quality));
+    if is_mute_all {
+        cmd.arg("-an");
+    } else {
+        cmd.args(["-map", audio_out]);
+        cmd.args(format.audio_args());
     }
+    cmd.args(format.mux_args());
+    cmd.arg(&request.output_path);
 
-    if is_mute_all {
+    let result = run_ffmpeg_with_progress(cmd, &encode_stage, started, state, cancel, app_handle).await;
+    if let Some(ref p) = ass_path { let _ = std::fs::remove_file(p); }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn split_segments(
+    ffmpeg: &Path,
+    temp_dir: &Path,
+    source: &str,
+    segments: &[Segment],
+    map_args: &[String],
+    stages: &[ExportStage],
+    started: std::time::Instant,
+    state: &SharedVideoEditorState,
+    cancel: &AtomicBool,
+    app_handle: &tauri::AppHandle,
+) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for (i, (seg, stage)) in segments.iter().zip(stages).enumerate() {
+        let seg_file = temp_dir.join(format!("export_seg_{}.mp4", i));
+        let seg_str = seg_file.to_string_lossy().to_string();
+        let mut cmd = tokio::process::Command::new(ffmpeg);
         cmd.args([
-            "-filter_complex",
-            &filter,
-            "-map",
-            "[vfinal]",
-            "-an",
-            "-c:v",
-            &request.video_codec,
-            "-crf",
-            crf,
-            "-movflags",
-            "+faststart",
-            &request.output_path,
-        ]);
-    } else {
-        cmd.args([
-            "-filter_complex",
-            &filter,
-            "-map",
-            "[vfinal]",
-            "-map",
-            "[afinal]",
-            "-c:v",
-            &request.video_codec,
-            "-crf",
-            crf,
-            "-c:a",
-            "aac",
-            "-movflags",
-            "+faststart",
-            &request.output_path,
-        ]);
-    }
-
-    let result = run_ffmpeg_with_progress(cmd, total_duration, state, cancel, app_handle).await;
-    if let Some(ref p) = ass_path { let _ = std::fs::remove_file(p); }
-    result
-}
-
-async fn split_segments(
-    ffmpeg: &Path,
-    temp_dir: &Path,
-    source: &str,
-    segments: &[Segment],
-    cancel: &AtomicBool,
-) -> Result<Vec<PathBuf>, String> {
-    let mut files = Vec::new();
-    for (i, seg) in segments.iter().enumerate() {
-        if cancel.load(Ordering::SeqCst) {
-            for f in &files {
-                let _ = std::fs::remove_file(f);
-            }
-            return Err("Export cancelled".to_string());
-        }
-        let seg_file = temp_dir.join(format!("export_seg_{}.mp4", i));
-        let seg_str = seg_file.to_string_lossy().to_string();
-        let result = tokio::process::Command::new(ffmpeg)
-            .args([
-                "-y",
-                "-ss",
-                &format!("{:.3}", seg.start),
-                "-to",
-                &format!("{:.3}", seg.end),
-                "-i",
-                source,
-                "-c",
-                "copy",
-                &seg_str,
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Segment split failed: {}", e))?;
+            "-y",
+            "-progress",
+            "pipe:1",
+            "-ss",
+            &format!("{:.3}", seg.start),
+            "-to",
+            &format!("{:.3}", seg.end),
+            "-i",
+            source,
+        ])
+        .args(map_args)
+        .args(["-c", "copy", &seg_str]);
 
-        if !result.status.success() {
+        if let Err(e) = run_ffmpeg_with_progress(cmd, stage, started, state, cancel, app_handle).await {
+            let _ = std::fs::remove_file(&seg_file);
             for f in &files {
                 let _ = std::fs::remove_file(f);
             }
-            return Err(format!(
-                "Segment {} export failed: {}",
-                i,
-                String::from_utf8_lossy(&result.stderr)
-            ));
+            return Err(if cancel.load(Ordering::SeqCst) {
+                e
+            } else {
+                format!("Segment {} export failed: {}", i, e)
+            });
         }
         files.push(seg_file);
     }
     Ok(files)
 }
 
-fn build_filter_complex(segments: &[Segment], overlays: &[Overlay], _source: &str, captions_ass_path: Option<&Path>, narration: Option<&AudioNarrationRequest>, narration_input_idx: Option<usize>) -> String {
+#[allow(clippy::too_many_arguments)]
+fn build_filter_complex(segments: &[Segment], overlays: &[Overlay], _source: &str, captions_ass_path: Option<&Path>, subtitle_burn: Option<&str>, narration: Option<&AudioNarrationRequest>, narration_input_idx: Option<usize>, audio_labels: &[String], reframe_filter: Option<&str>) -> String {
     let mut filter = String::new();
     let n = segments.len();
 
     for (i, seg) in segments.iter().enumerate() {
+        let alabel = audio_labels.get(i).map(String::as_str).unwrap_or("0:a");
         filter.push_str(&format!(
             "[0:v]trim=start={:.3}:end={:.3},setpts=PTS-STARTPTS[v{i}]; \
-             [0:a]atrim=start={:.3}:end={:.3},asetpts=PTS-STARTPTS[a{i}]; ",
+             [{alabel}]atrim=start={:.3}:end={:.3},asetpts=PTS-STARTPTS[a{i}]; ",
             seg.start, seg.end, seg.start, seg.end
         ));
     }
@@ -1257,6 +1737,10 @@ fn build_filter_complex(segments: &[Segment], overlays: &[Overlay], _source: &st
     }
 
     let mut current_v = "vconcat".to_string();
+    if let Some(chain) = reframe_filter {
+        filter.push_str(&format!("[vconcat]{chain}[vreframed]; "));
+        current_v = "vreframed".to_string();
+    }
     let mut input_idx = 1;
     let mut time_offset = 0.0_f64;
 
@@ -1319,6 +1803,11 @@ fn build_filter_complex(segments: &[Segment], overlays: &[Overlay], _source: &st
         current_v = out.to_string();
     }
 
+    if let Some(burn) = subtitle_burn {
+        filter.push_str(&format!("[{current_v}]{burn}[vsub]; "));
+        current_v = "vsub".to_string();
+    }
+
     filter.push_str(&format!("[{current_v}]copy[vfinal]; "));
 
     if let (Some(narr), Some(narr_idx)) = (narration, narration_input_idx) {
@@ -1385,13 +1874,208 @@ fn build_filter_complex(segments: &[Segment], overlays: &[Overlay], _source: &st
     filter
 }
 
+/// Export share reserved for concatenating split segments (stream copy).
+const CONCAT_SHARE: f64 = 10.0;
+/// Export share reserved for cleanup after the last ffmpeg run.
+const FINALIZE_SHARE: f64 = 2.0;
+/// Export share of the loudnorm measuring pass when normalizing.
+const MEASURE_SHARE: f64 = 40.0;
+
+/// One ffmpeg run's slice of the overall export bar: it moves the bar from
+/// `offset` to `offset + weight` as it works through `duration` seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ExportStage {
+    name: &'static str,
+    offset: f64,
+    weight: f64,
+    duration: f64,
+}
+
+impl ExportStage {
+    const FINALIZE: ExportStage = ExportStage {
+        name: "finalizing",
+        offset: 100.0 - FINALIZE_SHARE,
+        weight: FINALIZE_SHARE,
+        duration: 0.0,
+    };
+
+    fn encode(duration: f64) -> Self {
+        Self {
+            name: "encoding",
+            offset: 0.0,
+            weight: 100.0 - FINALIZE_SHARE,
+            duration,
+        }
+    }
+
+    /// palettegen only outputs at the end, so it gets no share and the bar
+    /// follows the paletteuse pass.
+    fn palette(duration: f64) -> Self {
+        Self {
+            name: "palette",
+            offset: 0.0,
+            weight: 0.0,
+            duration,
+        }
+    }
+
+    fn measure(duration: f64) -> Self {
+        Self {
+            name: "measuring",
+            offset: 0.0,
+            weight: MEASURE_SHARE,
+            duration,
+        }
+    }
+
+    fn encode_after_measure(duration: f64) -> Self {
+        Self {
+            name: "encoding",
+            offset: MEASURE_SHARE,
+            weight: 100.0 - MEASURE_SHARE - FINALIZE_SHARE,
+            duration,
+        }
+    }
+
+    /// Split stages get the non-fixed share in proportion to each segment's
+    /// length, followed by a fixed share for the concat.
+    fn split_and_concat(segments: &[Segment]) -> (Vec<Self>, Self) {
+        let total: f64 = segments.iter().map(|s| s.end - s.start).sum();
+        let split_share = 100.0 - CONCAT_SHARE - FINALIZE_SHARE;
+        let mut offset = 0.0;
+        let stages = segments
+            .iter()
+            .map(|s| {
+                let duration = s.end - s.start;
+                let weight = if total > 0.0 {
+                    split_share * duration / total
+                } else {
+                    split_share / segments.len() as f64
+                };
+                let stage = Self {
+                    name: "splitting",
+                    offset,
+                    weight,
+                    duration,
+                };
+                offset += weight;
+                stage
+            })
+            .collect();
+        let concat = Self {
+            name: "concatenating",
+            offset: split_share,
+            weight: CONCAT_SHARE,
+            duration: total,
+        };
+        (stages, concat)
+    }
+
+    fn overall(&self, stage_percent: f64) -> f64 {
+        self.offset + self.weight * stage_percent.clamp(0.0, 100.0) / 100.0
+    }
+}
+
+/// The export bar for `stage` at `stage_percent`, and the current clip's
+/// own percent. `progress` is the last report: its clip fields spread a
+/// batch over one bar, and its percent is a floor, so the bar never moves
+/// backwards even if ffmpeg's out_time briefly does. Stays below 100 until
+/// the export actually finishes.
+fn export_percent(progress: &ExportProgress, stage: &ExportStage, stage_percent: f64) -> (f64, f64) {
+    let clip_percent = stage.overall(stage_percent);
+    let overall = if progress.clip_count > 0 {
+        let done = progress.clip_index.saturating_sub(1) as f64;
+        (done * 100.0 + clip_percent) / progress.clip_count as f64
+    } else {
+        clip_percent
+    };
+    (progress.percent.max(overall).min(99.9), clip_percent)
+}
+
+async fn report_export_progress(
+    state: &SharedVideoEditorState,
+    app_handle: &tauri::AppHandle,
+    stage: &ExportStage,
+    stage_percent: f64,
+    started: std::time::Instant,
+) {
+    let mut s = state.lock().await;
+    let (percent, clip_percent) = export_percent(&s.export_progress, stage, stage_percent);
+    if s.export_progress.clip_count > 0 {
+        s.export_progress.clip_percent = clip_percent;
+    }
+    let elapsed = started.elapsed().as_secs_f64();
+    s.export_progress.percent = percent;
+    s.export_progress.eta_seconds = if percent > 0.0 {
+        (elapsed / percent * (100.0 - percent)).max(0.0)
+    } else {
+        0.0
+    };
+    s.export_progress.status = "encoding".to_string();
+    s.export_progress.stage = stage.name.to_string();
+    s.export_progress.stage_percent = stage_percent;
+    let progress = s.export_progress.clone();
+    drop(s);
+    let _ = tauri::Emitter::emit(app_handle, "video-editor://export-progress", &progress);
+}
+
 async fn run_ffmpeg_with_progress(
-    mut cmd: tokio::process::Command,
-    total_duration: f64,
+    cmd: tokio::process::Command,
+    stage: &ExportStage,
+    started: std::time::Instant,
     state: &SharedVideoEditorState,
     cancel: &AtomicBool,
     app_handle: &tauri::AppHandle,
 ) -> Result<(), String> {
+    run_ffmpeg_capturing(cmd, ProgressSlot::Export(stage), started, state, cancel, app_handle)
+        .await
+        .map(|_| ())
+}
+
+/// Which progress a tracked ffmpeg run updates, so proxy jobs don't
+/// clobber a running export.
+#[derive(Clone, Copy)]
+enum ProgressSlot<'a> {
+    Export(&'a ExportStage),
+    Proxy { source: &'a str, duration: f64 },
+}
+
+impl ProgressSlot<'_> {
+    fn duration(&self) -> f64 {
+        match self {
+            ProgressSlot::Export(stage) => stage.duration,
+            ProgressSlot::Proxy { duration, .. } => *duration,
+        }
+    }
+
+    async fn report(
+        &self,
+        state: &SharedVideoEditorState,
+        app_handle: &tauri::AppHandle,
+        percent: f64,
+        started: std::time::Instant,
+    ) {
+        match self {
+            ProgressSlot::Export(stage) => {
+                report_export_progress(state, app_handle, stage, percent, started).await
+            }
+            ProgressSlot::Proxy { source, .. } => {
+                report_proxy_progress(state, app_handle, source, percent).await
+            }
+        }
+    }
+}
+
+/// run_ffmpeg_with_progress, handing back stderr for filters that report
+/// there (loudnorm).
+async fn run_ffmpeg_capturing(
+    mut cmd: tokio::process::Command,
+    slot: ProgressSlot<'_>,
+    started: std::time::Instant,
+    state: &SharedVideoEditorState,
+    cancel: &AtomicBool,
+    app_handle: &tauri::AppHandle,
+) -> Result<String, String> {
     use tokio::io::{AsyncBufReadExt, BufReader};
 
     cmd.stdout(std::process::Stdio::piped());
@@ -1402,7 +2086,7 @@ async fn run_ffmpeg_with_progress(
         .map_err(|e| format!("FFmpeg spawn failed: {}", e))?;
 
     let stdout = child.stdout.take();
-    let start_time = std::time::Instant::now();
+    slot.report(state, app_handle, 0.0, started).await;
 
     if let Some(stdout) = stdout {
         let mut reader = BufReader::new(stdout).lines();
@@ -1415,25 +2099,13 @@ async fn run_ffmpeg_with_progress(
             if line.starts_with("out_time_us=") {
                 if let Ok(us) = line.trim_start_matches("out_time_us=").parse::<f64>() {
                     let current = us / 1_000_000.0;
-                    let percent = if total_duration > 0.0 {
-                        (current / total_duration * 100.0).min(99.9)
-                    } else {
-                        0.0
-                    };
-                    let elapsed = start_time.elapsed().as_secs_f64();
-                    let eta = if percent > 0.0 {
-                        (elapsed / percent * (100.0 - percent)).max(0.0)
+                    let duration = slot.duration();
+                    let stage_percent = if duration > 0.0 {
+                        (current / duration * 100.0).min(100.0)
                     } else {
                         0.0
                     };
-
-                    let mut s = state.lock().await;
-                    s.export_progress.percent = percent;
-                    s.export_progress.eta_seconds = eta;
-                    s.export_progress.status = "encoding".to_string();
-                    let progress = s.export_progress.clone();
-                    drop(s);
-                    let _ = tauri::Emitter::emit(app_handle, "video-editor://export-progress", &progress);
+                    slot.report(state, app_handle, stage_percent, started).await;
                 }
             }
         }
@@ -1444,12 +2116,12 @@ async fn run_ffmpeg_with_progress(
         .await
         .map_err(|e| format!("FFmpeg wait failed: {}", e))?;
 
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(format!("FFmpeg export failed: {}", stderr));
     }
 
-    Ok(())
+    Ok(stderr)
 }
 
 #[tauri::command]
@@ -1460,6 +2132,29 @@ pub async fn get_export_progress(
     Ok(s.export_progress.clone())
 }
 
+/// Cancels a running export and waits for its ffmpeg to be killed, so no
+/// encoder is left running after the app exits.
+pub async fn shutdown(app: tauri::AppHandle) {
+    use tauri::Manager;
+    let Some(state) = app.try_state::<SharedVideoEditorState>() else {
+        return;
+    };
+    {
+        let s = state.lock().await;
+        s.export_cancel.store(true, Ordering::SeqCst);
+        for job in s.proxy_jobs.values() {
+            job.cancel.store(true, Ordering::SeqCst);
+        }
+    }
+    loop {
+        let status = state.lock().await.export_progress.status.clone();
+        if status != "starting" && status != "encoding" {
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+}
+
 #[tauri::command]
 pub async fn cancel_export(
     state: tauri::State<'_, SharedVideoEditorState>,
@@ -1470,113 +2165,438 @@ pub async fn cancel_export(
 }
 
 #[tauri::command]
-pub async fn save_edit_project(project: EditProjectSave, path: String) -> Result<(), String> {
-    let json = serde_json::to_string_pretty(&project)
+pub async fn save_edit_project(mut project: EditProjectSave, path: String) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        // The editor doesn't track the export preset; keep the one
+        // export_with_preset recorded.
+        if project.last_export_preset.is_none() {
+            project.last_export_preset = read_edit_project(&path)
+                .ok()
+                .and_then(|saved| saved.last_export_preset);
+        }
+        write_edit_project(&path, &project)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+fn read_edit_project(path: &str) -> Result<EditProjectSave, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Read failed: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Parse failed: {}", e))
+}
+
+fn write_edit_project(path: &str, project: &EditProjectSave) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(project)
         .map_err(|e| format!("Serialize failed: {}", e))?;
-    std::fs::write(&path, json).map_err(|e| format!("Write failed: {}", e))
+    crate::settings::write_atomic(Path::new(path), json).map_err(|e| format!("Write failed: {}", e))
 }
 
 #[tauri::command]
 pub async fn load_edit_project(path: String) -> Result<EditProjectSave, String> {
-    let content = std::fs::read_to_string(&path)
-        .map_err(|e| format!("Read failed: {}", e))?;
-    serde_json::from_str(&content).map_err(|e| format!("Parse failed: {}", e))
+    tokio::task::spawn_blocking(move || read_edit_project(&path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
 }
 
-// ---- Caption / Subtitle Generation ----
+// ---- Export Presets ----
 
-fn hex_to_ass_color(hex: &str) -> String {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() >= 6 {
-        let r = &hex[0..2];
-        let g = &hex[2..4];
-        let b = &hex[4..6];
-        let a = if hex.len() >= 8 { &hex[6..8] } else { "00" };
-        format!("&H{}{}{}{}", a.to_uppercase(), b.to_uppercase(), g.to_uppercase(), r.to_uppercase())
-    } else {
-        "&H00FFFFFF".to_string()
-    }
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportPreset {
+    pub name: String,
+    pub format: String,
+    pub video_codec: String,
+    pub quality: String,
+    pub resolution: Option<String>,
+    #[serde(default)]
+    pub audio_track_mode: Option<String>,
+    #[serde(default)]
+    pub audio_tracks: Vec<AudioTrackGain>,
+    pub destination_template: String,
 }
 
-fn format_ass_time(seconds: f64) -> String {
-    let total_cs = (seconds * 100.0).round() as u64;
-    let cs = total_cs % 100;
-    let total_s = total_cs / 100;
-    let s = total_s % 60;
-    let total_m = total_s / 60;
-    let m = total_m % 60;
-    let h = total_m / 60;
-    format!("{}:{:02}:{:02}.{:02}", h, m, s, cs)
-}
+const DEFAULT_DESTINATION_TEMPLATE: &str = "{recording_dir}/exports/{name}_{date}.{ext}";
 
-fn generate_ass_content(req: &CaptionExportRequest) -> String {
-    let style = &req.style;
-    let mut ass = String::new();
+fn export_presets_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("com.observe.app")
+        .join("export_presets.json")
+}
 
-    ass.push_str("[Script Info]\r\n");
-    ass.push_str("ScriptType: v4.00+\r\n");
-    ass.push_str(&format!("PlayResX: {}\r\n", req.video_width));
-    ass.push_str(&format!("PlayResY: {}\r\n", req.video_height));
-    ass.push_str("WrapStyle: 0\r\n");
-    ass.push_str("ScaledBorderAndShadow: yes\r\n");
-    ass.push_str("\r\n");
+pub(crate) fn load_export_presets() -> Vec<ExportPreset> {
+    std::fs::read_to_string(export_presets_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
 
-    ass.push_str("[V4+ Styles]\r\n");
-    ass.push_str("Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\r\n");
-    let bold_flag = if style.bold { -1 } else { 0 };
-    let italic_flag = if style.italic { -1 } else { 0 };
-    ass.push_str(&format!(
-        "Style: Default,{},{},{},{},{},{},{},{},0,0,100,100,0,0,1,{},{},{},20,20,{},1\r\n",
-        style.font_name,
-        style.font_size,
-        hex_to_ass_color(&style.primary_color),
-        "&H00FFFFFF",
-        hex_to_ass_color(&style.outline_color),
-        hex_to_ass_color(&style.shadow_color),
-        bold_flag,
-        italic_flag,
-        style.outline_width,
-        style.shadow_depth,
-        style.alignment,
-        style.margin_v,
-    ));
-    ass.push_str("\r\n");
+/// Held across every read-modify-write of the export presets file.
+static EXPORT_PRESETS_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
-    ass.push_str("[Events]\r\n");
-    ass.push_str("Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\r\n");
-    for cap in &req.captions {
-        let start = format_ass_time(cap.video_start);
-        let end = format_ass_time(cap.video_end);
-        let text = cap.text.replace('\n', "\\N");
-        ass.push_str(&format!(
-            "Dialogue: 0,{},{},Default,,0,0,0,,{}\r\n",
-            start, end, text
-        ));
+fn save_export_presets(presets: &[ExportPreset]) -> Result<(), String> {
+    let path = export_presets_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create dir: {}", e))?;
     }
+    let json = serde_json::to_string_pretty(presets)
+        .map_err(|e| format!("Serialize failed: {}", e))?;
+    crate::settings::write_atomic(&path, json).map_err(|e| format!("Write failed: {}", e))
+}
 
-    ass
+/// UTC (year, month, day, hour, minute, second) for a unix timestamp.
+fn civil_from_unix(secs: u64) -> (i64, u32, u32, u32, u32, u32) {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (
+        year,
+        month,
+        day,
+        (rem / 3_600) as u32,
+        (rem % 3_600 / 60) as u32,
+        (rem % 60) as u32,
+    )
 }
 
-fn format_srt_time(seconds: f64) -> String {
-    let total_ms = (seconds * 1000.0).round() as u64;
-    let ms = total_ms % 1000;
-    let total_s = total_ms / 1000;
-    let s = total_s % 60;
-    let total_m = total_s / 60;
-    let m = total_m % 60;
-    let h = total_m / 60;
-    format!("{:02}:{:02}:{:02},{:03}", h, m, s, ms)
+/// Where an export with `preset` goes: the preset's destination template
+/// with {recording_dir}, {name} (the source's file stem), {preset}, {date},
+/// {time} and {ext} filled in, made unique if the file already exists.
+/// {date} and {time} are UTC, so names sort the same on every machine.
+pub(crate) fn expand_destination_template(
+    template: &str,
+    recording_dir: &str,
+    source_path: &str,
+    preset: &ExportPreset,
+) -> PathBuf {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    unique_output_path(&fill_destination_template(template, recording_dir, source_path, preset, now))
 }
 
-#[tauri::command]
-pub async fn generate_ass_file(
-    state: tauri::State<'_, SharedVideoEditorState>,
-    request: CaptionExportRequest,
-) -> Result<String, String> {
-    let content = generate_ass_content(&request);
-    let s = state.lock().await;
-    let ass_path = s.temp_dir.join("captions.ass");
-    drop(s);
+fn fill_destination_template(
+    template: &str,
+    recording_dir: &str,
+    source_path: &str,
+    preset: &ExportPreset,
+    now: u64,
+) -> PathBuf {
+    let (y, mo, d, h, mi, s) = civil_from_unix(now);
+    let source = Path::new(source_path);
+    let name = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "export".to_string());
+    let recording_dir = if recording_dir.is_empty() {
+        source
+            .parent()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default()
+    } else {
+        recording_dir.to_string()
+    };
+    let template = if template.is_empty() { DEFAULT_DESTINATION_TEMPLATE } else { template };
+
+    let expanded = template
+        .replace("{recording_dir}", &recording_dir)
+        .replace("{name}", &name)
+        .replace("{preset}", &preset.name)
+        .replace("{date}", &format!("{:04}-{:02}-{:02}", y, mo, d))
+        .replace("{time}", &format!("{:02}{:02}{:02}", h, mi, s))
+        .replace("{ext}", &preset.format);
+    let mut path = PathBuf::from(expanded);
+    if path.extension().is_none() {
+        path.set_extension(&preset.format);
+    }
+    path
+}
+
+/// Appends -1, -2, ... to the file stem until the path does not exist.
+fn unique_output_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    (1..)
+        .map(|i| parent.join(format!("{}-{}{}", stem, i, ext)))
+        .find(|p| !p.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+#[tauri::command]
+pub async fn save_export_preset(
+    name: String,
+    settings: ExportPreset,
+) -> Result<Vec<ExportPreset>, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Preset name cannot be empty".to_string());
+    }
+    tokio::task::spawn_blocking(move || {
+        let _lock = EXPORT_PRESETS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut presets = load_export_presets();
+        let preset = ExportPreset { name: name.clone(), ..settings };
+        match presets.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = preset,
+            None => presets.push(preset),
+        }
+        save_export_presets(&presets)?;
+        Ok(presets)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+pub async fn list_export_presets() -> Result<Vec<ExportPreset>, String> {
+    tokio::task::spawn_blocking(load_export_presets)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_export_preset(name: String) -> Result<Vec<ExportPreset>, String> {
+    tokio::task::spawn_blocking(move || {
+        let _lock = EXPORT_PRESETS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut presets = load_export_presets();
+        let before = presets.len();
+        presets.retain(|p| p.name != name);
+        if presets.len() == before {
+            return Err(format!("Export preset '{}' not found", name));
+        }
+        save_export_presets(&presets)?;
+        Ok(presets)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn export_with_preset(
+    license: tauri::State<'_, SharedLicenseState>,
+    state: tauri::State<'_, SharedVideoEditorState>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    source_path: String,
+    segments: Vec<Segment>,
+    overlays: Vec<Overlay>,
+    preset_name: String,
+    project_path: Option<String>,
+) -> Result<String, String> {
+    crate::store::require_module(&license, "video-editor").await?;
+    let preset = tokio::task::spawn_blocking(load_export_presets)
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .into_iter()
+        .find(|p| p.name == preset_name)
+        .ok_or_else(|| format!("Export preset '{}' not found", preset_name))?;
+
+    let recording_dir = obs_state.read().await.record_settings.record_directory.clone();
+    let output = expand_destination_template(
+        &preset.destination_template,
+        &recording_dir,
+        &source_path,
+        &preset,
+    );
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create output folder: {}", e))?;
+    }
+    let output_path = output.to_string_lossy().to_string();
+
+    let request = ExportRequest {
+        source_path,
+        segments,
+        overlays,
+        output_path: output_path.clone(),
+        format: preset.format,
+        video_codec: preset.video_codec,
+        quality: preset.quality,
+        resolution: preset.resolution,
+        captions: None,
+        audio_narration: None,
+        audio_track_mode: preset.audio_track_mode,
+        audio_tracks: preset.audio_tracks,
+        aspect: None,
+        crop_keyframes: Vec::new(),
+        audio_normalize: None,
+        gif: None,
+        chapters: Vec::new(),
+        subtitle_path: None,
+    };
+    start_export(state.inner(), app_handle, request).await?;
+
+    // Reopening the project offers the same preset again.
+    if let Some(project_path) = project_path.filter(|p| !p.is_empty()) {
+        let recorded = tokio::task::spawn_blocking(move || {
+            let mut project = read_edit_project(&project_path)?;
+            project.last_export_preset = Some(preset_name);
+            write_edit_project(&project_path, &project)
+        })
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+        .and_then(|r| r);
+        if let Err(e) = recorded {
+            log::warn!("Failed to remember the export preset in the project: {}", e);
+        }
+    }
+    Ok(output_path)
+}
+
+/// Silent (start, end) ranges in the file's audio. A silence still open at
+/// the end of the file is only reported when `duration` is given.
+pub(crate) async fn detect_silence_ranges(
+    ffmpeg: &Path,
+    path: &str,
+    noise_db: f64,
+    min_duration: f64,
+    duration: Option<f64>,
+) -> Result<Vec<(f64, f64)>, String> {
+    let filter = format!("silencedetect=noise={:.1}dB:d={:.2}", noise_db, min_duration);
+    let result = tokio::process::Command::new(ffmpeg)
+        .args(["-hide_banner", "-nostats", "-i", path, "-vn", "-af", &filter, "-f", "null", "-"])
+        .output()
+        .await
+        .map_err(|e| format!("FFmpeg failed to start: {}", e))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(format!("Silence detection failed: {}", stderr));
+    }
+
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    let value_after = |line: &str, key: &str| -> Option<f64> {
+        let rest = &line[line.find(key)? + key.len()..];
+        rest.split_whitespace().next()?.parse().ok()
+    };
+    let mut ranges = Vec::new();
+    let mut open_start: Option<f64> = None;
+    for line in stderr.lines() {
+        if let Some(start) = value_after(line, "silence_start:") {
+            open_start = Some(start.max(0.0));
+        } else if let Some(end) = value_after(line, "silence_end:") {
+            if let Some(start) = open_start.take() {
+                ranges.push((start, end));
+            }
+        }
+    }
+    if let (Some(start), Some(duration)) = (open_start, duration) {
+        ranges.push((start, duration));
+    }
+    Ok(ranges)
+}
+
+// ---- Caption / Subtitle Generation ----
+
+fn hex_to_ass_color(hex: &str) -> String {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() >= 6 {
+        let r = &hex[0..2];
+        let g = &hex[2..4];
+        let b = &hex[4..6];
+        let a = if hex.len() >= 8 { &hex[6..8] } else { "00" };
+        format!("&H{}{}{}{}", a.to_uppercase(), b.to_uppercase(), g.to_uppercase(), r.to_uppercase())
+    } else {
+        "&H00FFFFFF".to_string()
+    }
+}
+
+fn format_ass_time(seconds: f64) -> String {
+    let total_cs = (seconds * 100.0).round() as u64;
+    let cs = total_cs % 100;
+    let total_s = total_cs / 100;
+    let s = total_s % 60;
+    let total_m = total_s / 60;
+    let m = total_m % 60;
+    let h = total_m / 60;
+    format!("{}:{:02}:{:02}.{:02}", h, m, s, cs)
+}
+
+fn generate_ass_content(req: &CaptionExportRequest) -> String {
+    let style = &req.style;
+    let mut ass = String::new();
+
+    ass.push_str("[Script Info]\r\n");
+    ass.push_str("ScriptType: v4.00+\r\n");
+    ass.push_str(&format!("PlayResX: {}\r\n", req.video_width));
+    ass.push_str(&format!("PlayResY: {}\r\n", req.video_height));
+    ass.push_str("WrapStyle: 0\r\n");
+    ass.push_str("ScaledBorderAndShadow: yes\r\n");
+    ass.push_str("\r\n");
+
+    ass.push_str("[V4+ Styles]\r\n");
+    ass.push_str("Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\r\n");
+    let bold_flag = if style.bold { -1 } else { 0 };
+    let italic_flag = if style.italic { -1 } else { 0 };
+    ass.push_str(&format!(
+        "Style: Default,{},{},{},{},{},{},{},{},0,0,100,100,0,0,1,{},{},{},20,20,{},1\r\n",
+        style.font_name,
+        style.font_size,
+        hex_to_ass_color(&style.primary_color),
+        "&H00FFFFFF",
+        hex_to_ass_color(&style.outline_color),
+        hex_to_ass_color(&style.shadow_color),
+        bold_flag,
+        italic_flag,
+        style.outline_width,
+        style.shadow_depth,
+        style.alignment,
+        style.margin_v,
+    ));
+    ass.push_str("\r\n");
+
+    ass.push_str("[Events]\r\n");
+    ass.push_str("Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\r\n");
+    for cap in &req.captions {
+        let start = format_ass_time(cap.video_start);
+        let end = format_ass_time(cap.video_end);
+        let text = cap.text.replace('\n', "\\N");
+        ass.push_str(&format!(
+            "Dialogue: 0,{},{},Default,,0,0,0,,{}\r\n",
+            start, end, text
+        ));
+    }
+
+    ass
+}
+
+fn format_srt_time(seconds: f64) -> String {
+    let total_ms = (seconds * 1000.0).round() as u64;
+    let ms = total_ms % 1000;
+    let total_s = total_ms / 1000;
+    let s = total_s % 60;
+    let total_m = total_s / 60;
+    let m = total_m % 60;
+    let h = total_m / 60;
+    format!("{:02}:{:02}:{:02},{:03}", h, m, s, ms)
+}
+
+#[tauri::command]
+pub async fn generate_ass_file(
+    state: tauri::State<'_, SharedVideoEditorState>,
+    request: CaptionExportRequest,
+) -> Result<String, String> {
+    let content = generate_ass_content(&request);
+    let s = state.lock().await;
+    let ass_path = s.temp_dir.join("captions.ass");
+    drop(s);
     std::fs::write(&ass_path, &content)
         .map_err(|e| format!("Write ASS failed: {}", e))?;
     Ok(ass_path.to_string_lossy().to_string())
@@ -1726,6 +2746,7 @@ if ($dlg.ShowDialog() -eq 'OK') {{ $dlg.FileName }} else {{ '' }}
 #[tauri::command]
 pub async fn set_ffmpeg_path(
     state: tauri::State<'_, SharedVideoEditorState>,
+    settings: tauri::State<'_, SharedSettings>,
     path: String,
 ) -> Result<FfmpegStatus, String> {
     let ffmpeg = PathBuf::from(&path);
@@ -1738,6 +2759,8 @@ pub async fn set_ffmpeg_path(
         .map(|d| d.join("ffprobe.exe"))
         .filter(|p| p.exists());
 
+    settings::update_section(&settings, |s| s.video_editor.ffmpeg_path = Some(path)).await?;
+
     let mut s = state.lock().await;
     s.ffmpeg_path = Some(ffmpeg.clone());
     s.ffprobe_path = ffprobe.clone();
@@ -1748,3 +2771,2089 @@ pub async fn set_ffmpeg_path(
         ffprobe_path: ffprobe.map(|p| p.to_string_lossy().to_string()),
     })
 }
+
+// ---- Auto Remux on Record Stop ----
+
+pub(crate) fn spawn_auto_remux(app: tauri::AppHandle, output_path: String) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = auto_remux_recording(&app, &output_path).await {
+            log::warn!("Auto-remux failed: {}", e);
+            emit_remux_progress(&app, &output_path, "", "error", 0.0, Some(e));
+        }
+    });
+}
+
+fn emit_remux_progress(
+    app: &tauri::AppHandle,
+    source: &str,
+    output: &str,
+    status: &str,
+    percent: f64,
+    error: Option<String>,
+) {
+    let _ = tauri::Emitter::emit(
+        app,
+        "recorder://remux-progress",
+        serde_json::json!({
+            "sourcePath": source,
+            "outputPath": output,
+            "status": status,
+            "percent": percent,
+            "error": error,
+        }),
+    );
+}
+
+async fn resolve_finished_recording(app: &tauri::AppHandle, output_path: &str) -> Result<PathBuf, String> {
+    use tauri::Manager;
+    if !output_path.is_empty() && Path::new(output_path).exists() {
+        return Ok(PathBuf::from(output_path));
+    }
+    // Older obs-websocket builds omit outputPath; fall back to the newest file
+    // written in the record directory within the last minute.
+    let dir = app
+        .state::<SharedObsState>()
+        .read()
+        .await
+        .record_settings
+        .record_directory
+        .clone();
+    if dir.is_empty() {
+        return Err("Recording path unknown and no record directory configured".into());
+    }
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    tokio::task::spawn_blocking(move || list_video_files(&dir))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??
+        .into_iter()
+        .find(|f| now.saturating_sub(f.modified) <= 60)
+        .map(|f| PathBuf::from(f.path))
+        .ok_or_else(|| "Could not find the finished recording".to_string())
+}
+
+async fn wait_for_stable_size(path: &Path) -> Result<(), String> {
+    let mut last_size = None;
+    let mut stable_checks = 0;
+    for _ in 0..120 {
+        let size = tokio::fs::metadata(path)
+            .await
+            .map(|m| m.len())
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        if Some(size) == last_size && size > 0 {
+            stable_checks += 1;
+            if stable_checks >= 3 {
+                return Ok(());
+            }
+        } else {
+            stable_checks = 0;
+        }
+        last_size = Some(size);
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+    Err(format!("{} is still being written", path.display()))
+}
+
+async fn auto_remux_recording(app: &tauri::AppHandle, output_path: &str) -> Result<(), String> {
+    use tauri::Manager;
+    let config = app
+        .state::<SharedSettings>()
+        .read()
+        .await
+        .recording
+        .clone();
+    if !config.auto_remux_on_record_stop {
+        return Ok(());
+    }
+
+    let src = resolve_finished_recording(app, output_path).await?;
+    let is_mp4 = src
+        .extension()
+        .map(|e| e.to_string_lossy().eq_ignore_ascii_case("mp4"))
+        .unwrap_or(false);
+    if is_mp4 {
+        return Ok(());
+    }
+
+    let editor = app.state::<SharedVideoEditorState>().inner().clone();
+    let (ffmpeg, ffprobe) = {
+        let mut s = editor.lock().await;
+        let ffmpeg = s.ffmpeg_path.clone().ok_or("FFmpeg not found")?;
+        if !s.remux_in_flight.insert(src.clone()) {
+            log::info!("Skipping auto-remux, {} is already being remuxed", src.display());
+            return Ok(());
+        }
+        (ffmpeg, s.ffprobe_path.clone())
+    };
+
+    let result = remux_recording(app, &ffmpeg, ffprobe.as_deref(), &src, &config).await;
+    editor.lock().await.remux_in_flight.remove(&src);
+    let output = result?;
+
+    if config.delete_original_after_remux {
+        let out_size = std::fs::metadata(&output).map(|m| m.len()).unwrap_or(0);
+        if out_size > 0 {
+            delete_recording(src.to_string_lossy().to_string()).await?;
+        } else {
+            log::warn!("Remuxed file is empty, keeping original {}", src.display());
+        }
+    }
+    Ok(())
+}
+
+async fn remux_recording(
+    app: &tauri::AppHandle,
+    ffmpeg: &Path,
+    ffprobe: Option<&Path>,
+    src: &Path,
+    config: &settings::RecordingSettings,
+) -> Result<PathBuf, String> {
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    let source_str = src.to_string_lossy().to_string();
+    emit_remux_progress(app, &source_str, "", "waiting", 0.0, None);
+    wait_for_stable_size(src).await?;
+
+    let dest_dir = match config.remux_destination.as_deref().filter(|d| !d.is_empty()) {
+        Some(d) => PathBuf::from(d),
+        None => src.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    std::fs::create_dir_all(&dest_dir).map_err(|e| format!("Failed to create remux folder: {}", e))?;
+    let stem = src
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "recording".to_string());
+    let output = unique_output_path(&dest_dir.join(format!("{}.mp4", stem)));
+    let output_str = output.to_string_lossy().to_string();
+
+    let duration = match ffprobe {
+        Some(p) => probe_duration(p, &source_str).await.unwrap_or(0.0),
+        None => 0.0,
+    };
+
+    let mut child = tokio::process::Command::new(ffmpeg)
+        .args([
+            "-y",
+            "-nostats",
+            "-progress",
+            "pipe:1",
+            "-i",
+            &source_str,
+            "-map",
+            "0",
+            "-c",
+            "copy",
+            "-movflags",
+            "+faststart",
+            &output_str,
+        ])
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|e| format!("FFmpeg failed to start: {}", e))?;
+
+    if let Some(stdout) = child.stdout.take() {
+        let mut reader = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = reader.next_line().await {
+            if let Some(us) = line.strip_prefix("out_time_us=").and_then(|v| v.parse::<f64>().ok()) {
+                if duration > 0.0 {
+                    let percent = (us / 1_000_000.0 / duration * 100.0).clamp(0.0, 99.9);
+                    emit_remux_progress(app, &source_str, &output_str, "remuxing", percent, None);
+                }
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("FFmpeg wait failed: {}", e))?;
+    if !status.success() {
+        let _ = std::fs::remove_file(&output);
+        return Err(format!("Remux of {} failed", src.display()));
+    }
+
+    emit_remux_progress(app, &source_str, &output_str, "done", 100.0, None);
+    log::info!("Auto-remuxed {} -> {}", src.display(), output.display());
+    Ok(output)
+}
+
+// ---- Phase 7: Clips ----
+
+pub(crate) async fn probe_duration(ffprobe: &Path, path: &str) -> Result<f64, String> {
+    let result = tokio::process::Command::new(ffprobe)
+        .args(["-v", "quiet", "-print_format", "json", "-show_format", path])
+        .output()
+        .await
+        .map_err(|e| format!("ffprobe failed: {}", e))?;
+    let json: Value = serde_json::from_slice(&result.stdout)
+        .map_err(|e| format!("Invalid ffprobe output: {}", e))?;
+    json["format"]["duration"]
+        .as_str()
+        .and_then(|d| d.parse::<f64>().ok())
+        .ok_or_else(|| format!("Could not read duration of {}", path))
+}
+
+/// The first video stream's codec and every audio stream's, as ffprobe names them.
+async fn probe_codecs(ffprobe: &Path, path: &str) -> Result<(String, Vec<String>), String> {
+    let result = tokio::process::Command::new(ffprobe)
+        .args(["-v", "quiet", "-print_format", "json", "-show_entries", "stream=codec_type,codec_name", path])
+        .output()
+        .await
+        .map_err(|e| format!("ffprobe failed: {}", e))?;
+    let json: Value =
+        serde_json::from_slice(&result.stdout).map_err(|e| format!("Invalid ffprobe output: {}", e))?;
+    let mut video = String::new();
+    let mut audio = Vec::new();
+    for stream in json["streams"].as_array().into_iter().flatten() {
+        let codec = stream["codec_name"].as_str().unwrap_or("").to_string();
+        match stream["codec_type"].as_str() {
+            Some("video") if video.is_empty() => video = codec,
+            Some("audio") => audio.push(codec),
+            _ => {}
+        }
+    }
+    Ok((video, audio))
+}
+
+async fn next_keyframe_after(ffprobe: &Path, path: &str, start: f64, end: f64) -> Option<f64> {
+    let result = tokio::process::Command::new(ffprobe)
+        .args([
+            "-v",
+            "quiet",
+            "-select_streams",
+            "v:0",
+            "-skip_frame",
+            "nokey",
+            "-show_entries",
+            "frame=pts_time",
+            "-read_intervals",
+            &format!("{:.3}%{:.3}", start, end),
+            "-of",
+            "csv=p=0",
+            path,
+        ])
+        .output()
+        .await
+        .ok()?;
+    String::from_utf8_lossy(&result.stdout)
+        .lines()
+        .filter_map(|l| l.trim().trim_end_matches(',').parse::<f64>().ok())
+        .find(|t| *t >= start && *t < end)
+}
+
+async fn run_ffmpeg(ffmpeg: &Path, args: &[String]) -> Result<(), String> {
+    let result = tokio::process::Command::new(ffmpeg)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("FFmpeg failed to start: {}", e))?;
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(format!("FFmpeg error: {}", stderr));
+    }
+    Ok(())
+}
+
+fn cut_args(source: &str, start: f64, end: f64, codec_args: &[&str], output: &Path) -> Vec<String> {
+    let mut args: Vec<String> = vec![
+        "-y".into(),
+        "-ss".into(),
+        format!("{:.3}", start),
+        "-to".into(),
+        format!("{:.3}", end),
+        "-i".into(),
+        source.into(),
+        "-map".into(),
+        "0".into(),
+    ];
+    args.extend(codec_args.iter().map(|a| a.to_string()));
+    args.push(output.to_string_lossy().to_string());
+    args
+}
+
+async fn resolve_clip_source(obs_state: &SharedObsState, source_path: &str) -> Result<String, String> {
+    match source_path {
+        "latest-recording" => {
+            let dir = obs_state.read().await.record_settings.record_directory.clone();
+            if dir.is_empty() {
+                return Err("No recording directory configured in OBS".to_string());
+            }
+            let files = tokio::task::spawn_blocking(move || list_video_files(&dir))
+                .await
+                .map_err(|e| format!("Task failed: {}", e))??;
+            files
+                .into_iter()
+                .next()
+                .map(|f| f.path)
+                .ok_or_else(|| "No recordings found".to_string())
+        }
+        "latest-replay" => obs_state
+            .read()
+            .await
+            .last_replay_path
+            .clone()
+            .ok_or_else(|| "No replay buffer has been saved this session".to_string()),
+        path => Ok(path.to_string()),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_clip(
+    license: tauri::State<'_, SharedLicenseState>,
+    state: tauri::State<'_, SharedVideoEditorState>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    app_handle: tauri::AppHandle,
+    source_path: String,
+    center_timestamp: f64,
+    pre_seconds: f64,
+    post_seconds: f64,
+    destination: Option<String>,
+    accurate: Option<bool>,
+) -> Result<VideoFileInfo, String> {
+    crate::store::require_module(&license, "video-editor").await?;
+    let source = resolve_clip_source(&obs_state, &source_path).await?;
+    let src = PathBuf::from(&source);
+    if !src.exists() {
+        return Err(format!("File not found: {}", source));
+    }
+
+    let s = state.lock().await;
+    let ffmpeg = s
+        .ffmpeg_path
+        .clone()
+        .ok_or("FFmpeg not found. Run detect_ffmpeg first.")?;
+    let ffprobe = s
+        .ffprobe_path
+        .clone()
+        .ok_or("ffprobe not found. Run detect_ffmpeg first.")?;
+    let temp_dir = s.temp_dir.clone();
+    drop(s);
+
+    let duration = probe_duration(&ffprobe, &source).await?;
+    let start = (center_timestamp - pre_seconds.max(0.0)).clamp(0.0, duration);
+    let end = (center_timestamp + post_seconds.max(0.0)).clamp(0.0, duration);
+    if end - start < 0.1 {
+        return Err("Clip range is empty after clamping to the file bounds".to_string());
+    }
+
+    let ext = src
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| "mp4".to_string());
+    let stem = src
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "clip".to_string());
+    let clip_name = format!("{}_clip_{:.0}s.{}", stem, center_timestamp.max(0.0), ext);
+
+    let output = match destination.filter(|d| !d.is_empty()) {
+        Some(d) => {
+            let p = PathBuf::from(d);
+            let is_file = p
+                .extension()
+                .map(|e| VIDEO_EXTENSIONS.contains(&e.to_string_lossy().to_lowercase().as_str()))
+                .unwrap_or(false);
+            if is_file { p } else { unique_output_path(&p.join(&clip_name)) }
+        }
+        None => {
+            let dir = obs_state.read().await.record_settings.record_directory.clone();
+            let base = if dir.is_empty() {
+                src.parent().map(Path::to_path_buf).unwrap_or_default()
+            } else {
+                PathBuf::from(dir)
+            };
+            unique_output_path(&base.join("Clips").join(&clip_name))
+        }
+    };
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create clip folder: {}", e))?;
+    }
+
+    let (_job_id, job_dir) = new_job_dir(&temp_dir)?;
+    let result = cut_clip(
+        &ffmpeg,
+        &ffprobe,
+        &job_dir,
+        &source,
+        start,
+        end,
+        &ext,
+        &output,
+        accurate.unwrap_or(false),
+    )
+    .await;
+    let _ = tokio::fs::remove_dir_all(&job_dir).await;
+    result?;
+
+    let info = video_file_info(&output).ok_or("Clip was not written")?;
+    let _ = tauri::Emitter::emit(&app_handle, "clips://created", &info);
+    Ok(info)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn cut_clip(
+    ffmpeg: &Path,
+    ffprobe: &Path,
+    job_dir: &Path,
+    source: &str,
+    start: f64,
+    end: f64,
+    ext: &str,
+    output: &Path,
+    accurate: bool,
+) -> Result<(), String> {
+    let copy = ["-c", "copy", "-avoid_negative_ts", "make_zero"];
+    if !accurate {
+        return run_ffmpeg(ffmpeg, &cut_args(source, start, end, &copy, output)).await;
+    }
+
+    let encode = ["-c:v", "libx264", "-preset", "veryfast", "-crf", "18", "-c:a", "aac"];
+    let keyframe = match next_keyframe_after(ffprobe, source, start, end).await {
+        Some(k) if k - start > 0.001 => k,
+        Some(_) => return run_ffmpeg(ffmpeg, &cut_args(source, start, end, &copy, output)).await,
+        None => return run_ffmpeg(ffmpeg, &cut_args(source, start, end, &encode, output)).await,
+    };
+
+    // The re-encoded head is joined to the stream-copied tail, so both must
+    // share codecs; the head is always H.264 with AAC audio.
+    let (video_codec, audio_codecs) = probe_codecs(ffprobe, source).await?;
+    if video_codec != "h264" {
+        return Err(format!(
+            "Frame-accurate clips need an H.264 recording, but this one is {}. Turn off accurate cutting to cut at keyframes instead.",
+            if video_codec.is_empty() { "not video" } else { video_codec.as_str() }
+        ));
+    }
+    if let Some(other) = audio_codecs.iter().find(|c| *c != "aac") {
+        return Err(format!(
+            "Frame-accurate clips need AAC audio, but this recording has {}. Turn off accurate cutting to cut at keyframes instead.",
+            other
+        ));
+    }
+
+    // Re-encode only up to the first keyframe, then stream copy the rest.
+    let head = job_dir.join(format!("head.{}", ext));
+    let tail = job_dir.join(format!("tail.{}", ext));
+    run_ffmpeg(ffmpeg, &cut_args(source, start, keyframe, &encode, &head)).await?;
+    run_ffmpeg(ffmpeg, &cut_args(source, keyframe, end, &copy, &tail)).await?;
+
+    let concat_file = job_dir.join("concat.txt");
+    let list = [&head, &tail]
+        .iter()
+        .map(|p| format!("file '{}'", p.to_string_lossy().replace('\'', "'\\''")))
+        .collect::<Vec<_>>()
+        .join("\n");
+    std::fs::write(&concat_file, list).map_err(|e| format!("Failed to write concat list: {}", e))?;
+
+    let args: Vec<String> = vec![
+        "-y".into(),
+        "-f".into(),
+        "concat".into(),
+        "-safe".into(),
+        "0".into(),
+        "-i".into(),
+        concat_file.to_string_lossy().to_string(),
+        "-map".into(),
+        "0".into(),
+        "-c".into(),
+        "copy".into(),
+        output.to_string_lossy().to_string(),
+    ];
+    run_ffmpeg(ffmpeg, &args).await
+}
+
+// ---- Phase 8: Silence Detection ----
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceDetection {
+    pub duration: f64,
+    /// Silent regions are pre-marked deleted; times are source seconds.
+    pub segments: Vec<Segment>,
+    pub warning: Option<String>,
+}
+
+/// Gaps shorter than this between two silences are folded into them.
+const SILENCE_MERGE_GAP: f64 = 0.05;
+
+async fn has_audio_stream(ffprobe: &Path, path: &str) -> Result<bool, String> {
+    let result = tokio::process::Command::new(ffprobe)
+        .args(["-v", "quiet", "-select_streams", "a", "-show_entries", "stream=index", "-of", "csv=p=0", path])
+        .output()
+        .await
+        .map_err(|e| format!("ffprobe failed: {}", e))?;
+    Ok(!String::from_utf8_lossy(&result.stdout).trim().is_empty())
+}
+
+/// Alternating kept/deleted segments covering 0..duration.
+fn silence_segments(silences: &[(f64, f64)], duration: f64) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    for &(start, end) in silences {
+        let cursor = segments.last().map_or(0.0, |s| s.end);
+        let start = start.clamp(cursor, duration);
+        let end = end.min(duration);
+        if end <= start {
+            continue;
+        }
+        if start - cursor >= SILENCE_MERGE_GAP {
+            segments.push(Segment { start: cursor, end: start, deleted: false });
+        }
+        // Back-to-back silences, or ones split by a blip, become one cut.
+        match segments.last_mut() {
+            Some(last) if last.deleted => last.end = end,
+            last => {
+                let from = last.map_or(0.0, |s| s.end);
+                segments.push(Segment { start: from, end, deleted: true });
+            }
+        }
+    }
+    let cursor = segments.last().map_or(0.0, |s| s.end);
+    if duration - cursor >= SILENCE_MERGE_GAP || segments.is_empty() {
+        segments.push(Segment { start: cursor, end: duration, deleted: false });
+    } else if let Some(last) = segments.last_mut() {
+        last.end = duration;
+    }
+    segments
+}
+
+/// Runs ffmpeg's silencedetect over the first audio track and turns the
+/// result into cut segments for preview_edit/export_video.
+#[tauri::command]
+pub async fn detect_silence(
+    license: tauri::State<'_, SharedLicenseState>,
+    state: tauri::State<'_, SharedVideoEditorState>,
+    path: String,
+    noise_db: Option<f64>,
+    min_duration: Option<f64>,
+) -> Result<SilenceDetection, String> {
+    crate::store::require_module(&license, "video-editor").await?;
+    let s = state.lock().await;
+    let ffmpeg = s
+        .ffmpeg_path
+        .clone()
+        .ok_or("FFmpeg not found. Run detect_ffmpeg first.")?;
+    let ffprobe = s
+        .ffprobe_path
+        .clone()
+        .ok_or("ffprobe not found. Run detect_ffmpeg first.")?;
+    drop(s);
+
+    let duration = probe_duration(&ffprobe, &path).await?;
+    let whole = vec![Segment { start: 0.0, end: duration, deleted: false }];
+    if !has_audio_stream(&ffprobe, &path).await? {
+        return Ok(SilenceDetection {
+            duration,
+            segments: whole,
+            warning: Some("This file has no audio track, so nothing was marked as silent".to_string()),
+        });
+    }
+
+    let noise_db = noise_db.unwrap_or(-35.0).min(0.0);
+    let min_duration = min_duration.unwrap_or(1.0).max(0.1);
+    let silences = detect_silence_ranges(&ffmpeg, &path, noise_db, min_duration, Some(duration)).await?;
+    let warning = if silences.is_empty() {
+        Some(format!("No silence quieter than {} dB lasting {}s was found", noise_db, min_duration))
+    } else {
+        None
+    };
+    Ok(SilenceDetection {
+        duration,
+        segments: silence_segments(&silences, duration),
+        warning,
+    })
+}
+
+// ---- Phase 9: Multi-Clip Export ----
+
+/// Expands `{stem}` and `{n}` (1-based); the export format's extension is
+/// added when the pattern has none.
+fn clip_file_name(pattern: &str, stem: &str, n: usize, format: &str) -> String {
+    let name = pattern.replace("{stem}", stem).replace("{n}", &n.to_string());
+    if Path::new(&name).extension().is_some() {
+        name
+    } else {
+        format!("{}.{}", name, format)
+    }
+}
+
+/// Overlays and captions are timed against the whole edit; shift the ones
+/// that fall inside this clip onto its own timeline.
+fn clip_request(request: &ExportRequest, segment: &Segment, offset: f64, output: &Path) -> ExportRequest {
+    let length = segment.end - segment.start;
+    let within = |start: f64, end: f64| end > offset && start < offset + length;
+    let overlays = request
+        .overlays
+        .iter()
+        .filter(|o| within(o.start_time, o.end_time))
+        .map(|o| Overlay {
+            start_time: (o.start_time - offset).max(0.0),
+            end_time: (o.end_time - offset).min(length),
+            ..o.clone()
+        })
+        .collect();
+    let captions = request.captions.as_ref().map(|c| CaptionExportRequest {
+        captions: c
+            .captions
+            .iter()
+            .filter(|cap| within(cap.video_start, cap.video_end))
+            .map(|cap| CaptionSegment {
+                video_start: (cap.video_start - offset).max(0.0),
+                video_end: (cap.video_end - offset).min(length),
+                ..cap.clone()
+            })
+            .collect(),
+        ..c.clone()
+    });
+    // Narration takes are placed on the full timeline; only muting carries over.
+    let audio_narration = request
+        .audio_narration
+        .as_ref()
+        .filter(|a| a.audio_mode == "mute_all")
+        .cloned();
+    let crop_keyframes = request
+        .crop_keyframes
+        .iter()
+        .map(|k| CropKeyframe { time: k.time - offset, x: k.x })
+        .collect();
+    ExportRequest {
+        segments: vec![segment.clone()],
+        overlays,
+        output_path: output.to_string_lossy().to_string(),
+        captions,
+        audio_narration,
+        crop_keyframes,
+        ..request.clone()
+    }
+}
+
+/// Writes every kept segment to its own file in `output_dir`. Runs in the
+/// background like export_video and returns the planned paths.
+#[tauri::command]
+pub async fn export_clips(
+    license: tauri::State<'_, SharedLicenseState>,
+    state: tauri::State<'_, SharedVideoEditorState>,
+    app_handle: tauri::AppHandle,
+    request: ExportRequest,
+    output_dir: String,
+    name_pattern: Option<String>,
+) -> Result<Vec<String>, String> {
+    crate::store::require_module(&license, "video-editor").await?;
+    let s = state.lock().await;
+    let ffmpeg = s
+        .ffmpeg_path
+        .clone()
+        .ok_or("FFmpeg not found")?;
+    let ffprobe = s.ffprobe_path.clone();
+    let temp_dir = s.temp_dir.clone();
+    let cancel_flag = s.export_cancel.clone();
+    drop(s);
+
+    let kept: Vec<Segment> = request.segments.iter().filter(|s| !s.deleted).cloned().collect();
+    if kept.is_empty() {
+        return Err("No segments to export".to_string());
+    }
+    if request
+        .audio_narration
+        .as_ref()
+        .map_or(false, |a| a.audio_mode != "mute_all")
+    {
+        log::warn!("Clip export ignores narration; each clip keeps its source audio");
+    }
+    let format = ExportFormat::parse(&request.format)?;
+    let dir = PathBuf::from(&output_dir);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create output folder: {}", e))?;
+
+    let stem = Path::new(&request.source_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "clip".to_string());
+    let pattern = name_pattern
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "{stem}_clip_{n}".to_string());
+    let mut offset = 0.0;
+    let clips: Vec<ExportRequest> = kept
+        .iter()
+        .enumerate()
+        .map(|(i, seg)| {
+            let output = dir.join(clip_file_name(&pattern, &stem, i + 1, format.extension()));
+            let clip = clip_request(&request, seg, offset, &output);
+            offset += seg.end - seg.start;
+            clip
+        })
+        .collect();
+    let paths: Vec<String> = clips.iter().map(|c| c.output_path.clone()).collect();
+    let mut reframes = Vec::with_capacity(clips.len());
+    for clip in &clips {
+        reframes.push(Reframe::for_request(ffprobe.as_deref(), clip).await?);
+    }
+    let mut warnings: Vec<String> = Vec::new();
+    for (clip, reframe) in clips.iter().zip(&reframes) {
+        for w in reframe.as_ref().map(|r| r.overlay_warnings(&clip.overlays)).unwrap_or_default() {
+            if !warnings.contains(&w) {
+                warnings.push(w);
+            }
+        }
+    }
+
+    cancel_flag.store(false, Ordering::SeqCst);
+    state.lock().await.export_progress = ExportProgress {
+        status: "starting".to_string(),
+        clip_count: clips.len(),
+        warnings,
+        ..Default::default()
+    };
+
+    let state_clone = state.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let started = std::time::Instant::now();
+        let mut result = Ok(());
+        for (i, (clip, reframe)) in clips.iter().zip(&reframes).enumerate() {
+            if cancel_flag.load(Ordering::SeqCst) {
+                result = Err("Export cancelled".to_string());
+                break;
+            }
+            {
+                let mut s = state_clone.lock().await;
+                s.export_progress.clip_index = i + 1;
+                s.export_progress.clip_percent = 0.0;
+            }
+            let job_dir = match new_job_dir(&temp_dir) {
+                Ok((_, dir)) => dir,
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            };
+            let clip_result = run_export(
+                &ffmpeg,
+                &job_dir,
+                clip,
+                &clip.segments,
+                reframe.as_ref(),
+                &state_clone,
+                &cancel_flag,
+                &app_handle,
+                started,
+            )
+            .await;
+            let _ = tokio::fs::remove_dir_all(&job_dir).await;
+            if let Err(e) = clip_result {
+                let _ = tokio::fs::remove_file(&clip.output_path).await;
+                result = Err(e);
+                break;
+            }
+            report_export_progress(&state_clone, &app_handle, &ExportStage::FINALIZE, 100.0, started).await;
+        }
+
+        let mut s = state_clone.lock().await;
+        match result {
+            Ok(()) => {
+                s.export_progress.percent = 100.0;
+                s.export_progress.status = "done".to_string();
+            }
+            Err(e) => {
+                if cancel_flag.load(Ordering::SeqCst) {
+                    s.export_progress.status = "cancelled".to_string();
+                } else {
+                    s.export_progress.status = "error".to_string();
+                    s.export_progress.error = Some(e);
+                }
+            }
+        }
+        let progress = s.export_progress.clone();
+        drop(s);
+        let _ = tauri::Emitter::emit(&app_handle, "video-editor://export-progress", &progress);
+    });
+
+    Ok(paths)
+}
+
+// ---- Phase 10: Vertical Reframe ----
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CropKeyframe {
+    /// Seconds on the edited timeline.
+    pub time: f64,
+    /// Horizontal center of the crop window, in source pixels.
+    pub x: f64,
+}
+
+const VERTICAL_WIDTH: u32 = 1080;
+const VERTICAL_HEIGHT: u32 = 1920;
+
+/// A 9:16 crop of the full source height, panned between keyframes and
+/// scaled to 1080x1920.
+pub(crate) struct Reframe {
+    src_height: f64,
+    crop_width: f64,
+    /// (time, left edge of the crop), sorted by time.
+    lefts: Vec<(f64, f64)>,
+}
+
+impl Reframe {
+    pub(crate) async fn for_request(ffprobe: Option<&Path>, request: &ExportRequest) -> Result<Option<Self>, String> {
+        match request.aspect.as_deref() {
+            None | Some("") | Some("source") => return Ok(None),
+            Some("9:16") => {}
+            Some(other) => return Err(format!("Unsupported export aspect '{}'", other)),
+        }
+        let ffprobe = ffprobe.ok_or("ffprobe not found. Run detect_ffmpeg first.")?;
+        let (width, height) = probe_dimensions(ffprobe, &request.source_path).await?;
+        let src_width = width as f64;
+        let src_height = height as f64;
+        let crop_width = (src_height * 9.0 / 16.0).min(src_width).floor();
+        let clamp_left = |center: f64| (center - crop_width / 2.0).clamp(0.0, src_width - crop_width);
+        let mut lefts: Vec<(f64, f64)> = request
+            .crop_keyframes
+            .iter()
+            .map(|k| (k.time, clamp_left(k.x)))
+            .collect();
+        lefts.sort_by(|a, b| a.0.total_cmp(&b.0));
+        if lefts.is_empty() {
+            lefts.push((0.0, clamp_left(src_width / 2.0)));
+        }
+        Ok(Some(Self { src_height, crop_width, lefts }))
+    }
+
+    fn left_at(&self, t: f64) -> f64 {
+        let first = self.lefts[0];
+        if t <= first.0 {
+            return first.1;
+        }
+        for pair in self.lefts.windows(2) {
+            let ((t0, l0), (t1, l1)) = (pair[0], pair[1]);
+            if t < t1 {
+                return l0 + (l1 - l0) * (t - t0) / (t1 - t0).max(0.001);
+            }
+        }
+        self.lefts[self.lefts.len() - 1].1
+    }
+
+    /// The crop x expression: constant before the first and after the last
+    /// keyframe, linear in between.
+    fn x_expr(&self) -> String {
+        let (_, last) = self.lefts[self.lefts.len() - 1];
+        let mut expr = format!("{:.1}", last);
+        for pair in self.lefts.windows(2).rev() {
+            let ((t0, l0), (t1, l1)) = (pair[0], pair[1]);
+            let span = (t1 - t0).max(0.001);
+            expr = format!("if(lt(t,{t1:.3}),{l0:.1}+({:.1})*(t-{t0:.3})/{span:.3},{expr})", l1 - l0);
+        }
+        let (t0, l0) = self.lefts[0];
+        format!("if(lt(t,{t0:.3}),{l0:.1},{expr})")
+    }
+
+    pub(crate) fn filter(&self) -> String {
+        format!(
+            "crop=w={:.0}:h={:.0}:x='{}':y=0,scale={}:{},setsar=1",
+            self.crop_width,
+            self.src_height,
+            self.x_expr(),
+            VERTICAL_WIDTH,
+            VERTICAL_HEIGHT
+        )
+    }
+
+    /// Moves an overlay from source coordinates onto the vertical canvas,
+    /// using where the crop sits when the overlay appears.
+    fn map_overlay(&self, overlay: &Overlay) -> Overlay {
+        let scale = VERTICAL_HEIGHT as f64 / self.src_height;
+        let left = self.left_at(overlay.start_time);
+        let mut mapped = overlay.clone();
+        mapped.x = (overlay.x - left) * scale;
+        mapped.y = overlay.y * scale;
+        mapped.width = overlay.width * scale;
+        mapped.height = overlay.height * scale;
+        mapped.style.font_size = ((overlay.style.font_size as f64) * scale).round().max(1.0) as u32;
+        mapped
+    }
+
+    pub(crate) fn map_overlays(&self, overlays: &[Overlay]) -> Vec<Overlay> {
+        overlays.iter().map(|o| self.map_overlay(o)).collect()
+    }
+
+    /// One warning per overlay the crop hides entirely or in part.
+    pub(crate) fn overlay_warnings(&self, overlays: &[Overlay]) -> Vec<String> {
+        let canvas = VERTICAL_WIDTH as f64;
+        self.map_overlays(overlays)
+            .iter()
+            .filter_map(|o| {
+                let label = if o.overlay_type == "text" { &o.content } else { &o.id };
+                if o.x >= canvas || o.x + o.width <= 0.0 {
+                    Some(format!("Overlay '{}' is outside the 9:16 crop and won't be visible", label))
+                } else if o.x < 0.0 || o.x + o.width > canvas {
+                    Some(format!("Overlay '{}' is partly outside the 9:16 crop", label))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+async fn probe_dimensions(ffprobe: &Path, path: &str) -> Result<(u32, u32), String> {
+    let result = tokio::process::Command::new(ffprobe)
+        .args([
+            "-v",
+            "quiet",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "stream=width,height",
+            "-print_format",
+            "json",
+            path,
+        ])
+        .output()
+        .await
+        .map_err(|e| format!("ffprobe failed: {}", e))?;
+    let json: Value = serde_json::from_slice(&result.stdout)
+        .map_err(|e| format!("Invalid ffprobe output: {}", e))?;
+    let stream = &json["streams"][0];
+    match (stream["width"].as_u64(), stream["height"].as_u64()) {
+        (Some(w), Some(h)) if w > 0 && h > 0 => Ok((w as u32, h as u32)),
+        _ => Err(format!("Could not read the video size of {}", path)),
+    }
+}
+
+// ---- Phase 11: Loudness Normalization ----
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AudioNormalize {
+    /// Integrated loudness to aim for, in LUFS.
+    pub target_lufs: f64,
+    /// True-peak ceiling, in dBTP.
+    pub true_peak: f64,
+}
+
+impl Default for AudioNormalize {
+    fn default() -> Self {
+        Self {
+            target_lufs: -14.0,
+            true_peak: -1.0,
+        }
+    }
+}
+
+/// loudnorm's own default loudness range.
+const NORMALIZE_LRA: f64 = 11.0;
+
+/// What loudnorm's measuring pass prints with print_format=json.
+#[derive(Debug, Clone, Copy)]
+struct LoudnessStats {
+    input_i: f64,
+    input_tp: f64,
+    input_lra: f64,
+    input_thresh: f64,
+    target_offset: f64,
+}
+
+impl AudioNormalize {
+    fn filter(&self, measured: Option<&LoudnessStats>) -> String {
+        let mut f = format!(
+            "loudnorm=I={:.1}:TP={:.1}:LRA={:.1}",
+            self.target_lufs, self.true_peak, NORMALIZE_LRA
+        );
+        if let Some(m) = measured {
+            f.push_str(&format!(
+                ":measured_I={:.2}:measured_TP={:.2}:measured_LRA={:.2}:measured_thresh={:.2}:offset={:.2}:linear=true",
+                m.input_i, m.input_tp, m.input_lra, m.input_thresh, m.target_offset
+            ));
+        }
+        f
+    }
+}
+
+/// The stats block is the last JSON object on stderr. Values are strings
+/// and come out as "-inf" for silent input, which a second pass can't use.
+fn parse_loudnorm_stats(stderr: &str) -> Option<LoudnessStats> {
+    let start = stderr.rfind('{')?;
+    let end = start + stderr[start..].find('}')?;
+    let v: serde_json::Value = serde_json::from_str(&stderr[start..=end]).ok()?;
+    let field = |key: &str| -> Option<f64> {
+        v[key].as_str()?.trim().parse::<f64>().ok().filter(|x| x.is_finite())
+    };
+    Some(LoudnessStats {
+        input_i: field("input_i")?,
+        input_tp: field("input_tp")?,
+        input_lra: field("input_lra")?,
+        input_thresh: field("input_thresh")?,
+        target_offset: field("target_offset")?,
+    })
+}
+
+/// Runs the whole edit once through loudnorm to measure it. Video has to be
+/// mapped too or ffmpeg rejects the graph's unconnected [vfinal].
+#[allow(clippy::too_many_arguments)]
+async fn measure_loudness(
+    ffmpeg: &Path,
+    normalize: &AudioNormalize,
+    inputs: &[String],
+    filter: &str,
+    duration: f64,
+    started: std::time::Instant,
+    state: &SharedVideoEditorState,
+    cancel: &AtomicBool,
+    app_handle: &tauri::AppHandle,
+) -> Result<LoudnessStats, String> {
+    let graph = format!(
+        "{}; [afinal]{}:print_format=json[anorm]",
+        filter,
+        normalize.filter(None)
+    );
+    let mut cmd = tokio::process::Command::new(ffmpeg);
+    cmd.args(["-y", "-nostats", "-progress", "pipe:1"]);
+    cmd.args(inputs);
+    cmd.args(["-filter_complex", &graph, "-map", "[vfinal]", "-map", "[anorm]", "-f", "null", "-"]);
+    let stderr = run_ffmpeg_capturing(cmd, ProgressSlot::Export(&ExportStage::measure(duration)), started, state, cancel, app_handle).await?;
+    parse_loudnorm_stats(&stderr).ok_or_else(|| "loudnorm reported no usable stats".to_string())
+}
+
+/// Appends loudnorm to the [afinal] chain, with measured values when the
+/// first pass works and single-pass otherwise. Returns the label to map.
+#[allow(clippy::too_many_arguments)]
+async fn apply_normalize(
+    ffmpeg: &Path,
+    normalize: &AudioNormalize,
+    inputs: &[String],
+    filter: &mut String,
+    duration: f64,
+    started: std::time::Instant,
+    state: &SharedVideoEditorState,
+    cancel: &AtomicBool,
+    app_handle: &tauri::AppHandle,
+) -> Result<&'static str, String> {
+    let measured = match measure_loudness(ffmpeg, normalize, inputs, filter, duration, started, state, cancel, app_handle).await {
+        Ok(stats) => Some(stats),
+        Err(_) if cancel.load(Ordering::SeqCst) => return Err("Export cancelled".to_string()),
+        Err(e) => {
+            log::warn!("Loudness measuring pass failed, normalizing in one pass: {}", e);
+            None
+        }
+    };
+    {
+        let mut s = state.lock().await;
+        s.export_progress.input_lufs = measured.map(|m| m.input_i);
+        s.export_progress.target_lufs = Some(normalize.target_lufs);
+    }
+    // loudnorm outputs 192 kHz; bring it back down for the encoder.
+    filter.push_str(&format!("; [afinal]{},aresample=48000[anorm]", normalize.filter(measured.as_ref())));
+    Ok("[anorm]")
+}
+
+// ---- Phase 12: Output Formats ----
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Mp4,
+    Mkv,
+    Mov,
+    WebM,
+    Gif,
+}
+
+impl ExportFormat {
+    fn parse(format: &str) -> Result<Self, String> {
+        match format.to_ascii_lowercase().as_str() {
+            "mp4" => Ok(Self::Mp4),
+            "mkv" => Ok(Self::Mkv),
+            "mov" => Ok(Self::Mov),
+            "webm" => Ok(Self::WebM),
+            "gif" => Ok(Self::Gif),
+            other => Err(format!("Unsupported export format '{}'", other)),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Mkv => "mkv",
+            Self::Mov => "mov",
+            Self::WebM => "webm",
+            Self::Gif => "gif",
+        }
+    }
+
+    fn has_audio(self) -> bool {
+        self != Self::Gif
+    }
+
+    /// Whether the source's H.264/AAC can be copied straight into it.
+    fn can_stream_copy(self) -> bool {
+        matches!(self, Self::Mp4 | Self::Mkv | Self::Mov)
+    }
+
+    fn video_args<'a>(self, codec: &'a str, quality: &str) -> Vec<&'a str> {
+        match self {
+            Self::WebM => vec!["-c:v", "libvpx-vp9", "-crf", vp9_crf(quality), "-b:v", "0", "-row-mt", "1"],
+            Self::Gif => Vec::new(),
+            _ => vec!["-c:v", codec, "-crf", x264_crf(quality)],
+        }
+    }
+
+    fn audio_args(self) -> &'static [&'static str] {
+        match self {
+            Self::WebM => &["-c:a", "libopus", "-b:a", "128k"],
+            Self::Gif => &["-an"],
+            _ => &["-c:a", "aac"],
+        }
+    }
+
+    /// +faststart is an MP4/MOV muxer flag; the WebM and GIF muxers reject it.
+    fn mux_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Mp4 | Self::Mov => &["-movflags", "+faststart"],
+            _ => &[],
+        }
+    }
+}
+
+fn x264_crf(quality: &str) -> &'static str {
+    match quality {
+        "high" => "18",
+        "low" => "28",
+        _ => "23",
+    }
+}
+
+/// VP9's CRF scale runs to 63; these land near the x264 presets visually.
+fn vp9_crf(quality: &str) -> &'static str {
+    match quality {
+        "high" => "24",
+        "low" => "37",
+        _ => "31",
+    }
+}
+
+/// Swaps the output extension for the format's when they disagree.
+/// Returns the corrected path, if it changed.
+fn correct_extension(output_path: &str, format: ExportFormat) -> Option<String> {
+    let path = Path::new(output_path);
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    if ext.eq_ignore_ascii_case(format.extension()) {
+        return None;
+    }
+    Some(path.with_extension(format.extension()).to_string_lossy().to_string())
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GifOptions {
+    pub fps: u32,
+    /// Wider sources are scaled down to this; narrower ones keep their size.
+    pub max_width: u32,
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        Self { fps: 15, max_width: 480 }
+    }
+}
+
+impl GifOptions {
+    fn scale_filter(&self) -> String {
+        format!(
+            "fps={},scale='min(iw,{})':-1:flags=lanczos",
+            self.fps.clamp(1, 50),
+            self.max_width.max(16)
+        )
+    }
+}
+
+/// palettegen over the whole edit, then paletteuse with that palette. Audio
+/// from the graph goes to anullsink so [afinal] isn't left unconnected.
+#[allow(clippy::too_many_arguments)]
+async fn run_gif_export(
+    ffmpeg: &Path,
+    temp_dir: &Path,
+    output_path: &str,
+    inputs: &[String],
+    filter: &str,
+    options: &GifOptions,
+    encode_stage: &ExportStage,
+    started: std::time::Instant,
+    state: &SharedVideoEditorState,
+    cancel: &AtomicBool,
+    app_handle: &tauri::AppHandle,
+) -> Result<(), String> {
+    let palette = temp_dir.join("export_palette.png");
+    let palette_str = palette.to_string_lossy().to_string();
+    let scale = options.scale_filter();
+
+    let mut cmd = tokio::process::Command::new(ffmpeg);
+    cmd.args(["-y", "-progress", "pipe:1"]);
+    cmd.args(inputs);
+    cmd.args([
+        "-filter_complex",
+        &format!("{filter}; [afinal]anullsink; [vfinal]{scale},palettegen[pal]"),
+        "-map",
+        "[pal]",
+        "-update",
+        "1",
+        &palette_str,
+    ]);
+    let palette_stage = ExportStage::palette(encode_stage.duration);
+    run_ffmpeg_with_progress(cmd, &palette_stage, started, state, cancel, app_handle).await?;
+
+    // Inputs are "-i path" pairs, so the palette is the next input index.
+    let palette_idx = inputs.len() / 2;
+    let mut cmd = tokio::process::Command::new(ffmpeg);
+    cmd.args(["-y", "-progress", "pipe:1"]);
+    cmd.args(inputs);
+    cmd.args([
+        "-i",
+        &palette_str,
+        "-filter_complex",
+        &format!(
+            "{filter}; [afinal]anullsink; [vfinal]{scale}[gv]; [gv][{palette_idx}:v]paletteuse=dither=sierra2_4a[gif]"
+        ),
+        "-map",
+        "[gif]",
+        "-loop",
+        "0",
+        output_path,
+    ]);
+    let result = run_ffmpeg_with_progress(cmd, encode_stage, started, state, cancel, app_handle).await;
+    let _ = std::fs::remove_file(&palette);
+    result
+}
+
+// ---- Phase 13: Chapters ----
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub title: String,
+    /// Seconds in source time.
+    pub time: f64,
+}
+
+/// Source time to edited time. A time in a cut moves to the start of the
+/// next kept segment; past the last one there's nowhere for it to go.
+fn remap_time(time: f64, kept: &[&Segment]) -> Option<f64> {
+    let mut offset = 0.0;
+    for seg in kept {
+        if time < seg.start {
+            return Some(offset);
+        }
+        if time < seg.end {
+            return Some(offset + time - seg.start);
+        }
+        offset += seg.end - seg.start;
+    }
+    None
+}
+
+/// Chapters on the edited timeline, in order. When several land on the same
+/// time (they were all cut), the last one wins since its section is what
+/// actually plays there.
+fn remap_chapters(chapters: &[Chapter], segments: &[Segment]) -> Vec<Chapter> {
+    let mut kept: Vec<&Segment> = segments.iter().filter(|s| !s.deleted).collect();
+    kept.sort_by(|a, b| a.start.total_cmp(&b.start));
+    let mut sorted: Vec<&Chapter> = chapters.iter().collect();
+    sorted.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let mut out: Vec<Chapter> = Vec::new();
+    for chapter in sorted {
+        let Some(time) = remap_time(chapter.time, &kept) else {
+            continue;
+        };
+        let remapped = Chapter {
+            title: chapter.title.clone(),
+            time,
+        };
+        match out.last_mut() {
+            Some(last) if (time - last.time).abs() < 0.001 => *last = remapped,
+            _ => out.push(remapped),
+        }
+    }
+    out
+}
+
+/// ffmetadata treats '=', ';', '#', '\' and newlines specially.
+fn escape_ffmetadata(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '=' | ';' | '#' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' | '\r' => out.push(' '),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Each chapter runs to the next one; the last runs to the end.
+fn ffmetadata(chapters: &[Chapter], duration: f64) -> String {
+    let mut out = String::from(";FFMETADATA1\n");
+    for (i, chapter) in chapters.iter().enumerate() {
+        let end = chapters.get(i + 1).map_or(duration, |next| next.time);
+        out.push_str(&format!(
+            "[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            (chapter.time * 1000.0).round() as u64,
+            (end.max(chapter.time) * 1000.0).round() as u64,
+            escape_ffmetadata(&chapter.title)
+        ));
+    }
+    out
+}
+
+fn youtube_timestamp(seconds: f64) -> String {
+    let s = seconds.max(0.0) as u64;
+    if s >= 3600 {
+        format!("{}:{:02}:{:02}", s / 3600, s % 3600 / 60, s % 60)
+    } else {
+        format!("{:02}:{:02}", s / 60, s % 60)
+    }
+}
+
+/// YouTube only recognises chapters when the first starts at 00:00, so the
+/// first one is written there whatever its time.
+fn youtube_chapters(chapters: &[Chapter]) -> String {
+    chapters
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let time = if i == 0 { 0.0 } else { c.time };
+            format!("{} {}", youtube_timestamp(time), c.title.replace(['\n', '\r'], " "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Adds chapters to the finished export with a stream-copy remux, so every
+/// encode path gets them without threading metadata through each one.
+#[allow(clippy::too_many_arguments)]
+async fn embed_chapters(
+    ffmpeg: &Path,
+    temp_dir: &Path,
+    request: &ExportRequest,
+    chapters: &[Chapter],
+    duration: f64,
+    started: std::time::Instant,
+    state: &SharedVideoEditorState,
+    cancel: &AtomicBool,
+    app_handle: &tauri::AppHandle,
+) -> Result<(), String> {
+    let format = ExportFormat::parse(&request.format)?;
+    if chapters.is_empty() {
+        return Ok(());
+    }
+    if format == ExportFormat::Gif {
+        log::warn!("GIF exports can't carry chapters; skipping them");
+        return Ok(());
+    }
+    let meta = temp_dir.join("export_chapters.txt");
+    std::fs::write(&meta, ffmetadata(chapters, duration))
+        .map_err(|e| format!("Write chapters failed: {}", e))?;
+    let output = Path::new(&request.output_path);
+    let remuxed = output.with_extension(format!("chapters.{}", format.extension()));
+    let remuxed_str = remuxed.to_string_lossy().to_string();
+
+    let mut cmd = tokio::process::Command::new(ffmpeg);
+    cmd.args([
+        "-y",
+        "-progress",
+        "pipe:1",
+        "-i",
+        &request.output_path,
+        "-i",
+        &meta.to_string_lossy(),
+        "-map",
+        "0",
+        "-map_metadata",
+        "1",
+        "-map_chapters",
+        "1",
+        "-c",
+        "copy",
+    ]);
+    cmd.args(format.mux_args());
+    cmd.arg(&remuxed_str);
+    let result = run_ffmpeg_with_progress(cmd, &ExportStage::FINALIZE, started, state, cancel, app_handle).await;
+    let _ = std::fs::remove_file(&meta);
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&remuxed);
+        return Err(e);
+    }
+    std::fs::rename(&remuxed, output).map_err(|e| format!("Replace export failed: {}", e))
+}
+
+/// Writes the edit's chapters as YouTube description lines ("00:00 Intro")
+/// and returns the text.
+#[tauri::command]
+pub async fn export_chapters_file(
+    license: tauri::State<'_, SharedLicenseState>,
+    segments: Vec<Segment>,
+    chapters: Vec<Chapter>,
+    path: String,
+) -> Result<String, String> {
+    crate::store::require_module(&license, "video-editor").await?;
+    let chapters = remap_chapters(&chapters, &segments);
+    if chapters.is_empty() {
+        return Err("No chapters fall inside the kept segments".to_string());
+    }
+    let text = youtube_chapters(&chapters);
+    std::fs::write(&path, &text).map_err(|e| format!("Write failed: {}", e))?;
+    Ok(text)
+}
+
+// ---- Phase 14: Proxy Previews ----
+
+/// Proxies past this total are evicted, least recently used first.
+const PROXY_CACHE_CAP_BYTES: u64 = 20 * 1024 * 1024 * 1024;
+const PROXY_HEIGHTS: [u32; 2] = [720, 480];
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyStatus {
+    pub source_path: String,
+    /// "none", "generating", "ready", "error" or "cancelled".
+    pub status: String,
+    pub percent: f64,
+    pub height: u32,
+    pub proxy_path: Option<String>,
+    pub error: Option<String>,
+}
+
+pub struct ProxyJob {
+    status: ProxyStatus,
+    cancel: Arc<AtomicBool>,
+}
+
+/// Proxies are named by source path hash and mtime, so an edited source
+/// misses the cache and the stale proxy is easy to find and remove.
+fn proxy_prefix(source: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let hash = Sha256::digest(source.as_bytes());
+    hash.iter().take(8).map(|b| format!("{:02x}", b)).collect()
+}
+
+fn proxy_file(proxy_dir: &Path, source: &str, height: u32) -> Result<PathBuf, String> {
+    let modified = std::fs::metadata(source)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("Cannot read {}: {}", source, e))?
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Ok(proxy_dir.join(format!("{}_{}_{}p.mp4", proxy_prefix(source), modified, height)))
+}
+
+/// Marks a proxy as just used for LRU eviction.
+fn touch(path: &Path) {
+    if let Ok(file) = std::fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(std::time::SystemTime::now());
+    }
+}
+
+/// Removes the oldest proxies until the directory fits the cap, never
+/// `keep`.
+fn evict_proxies(proxy_dir: &Path, keep: &Path) {
+    let Ok(entries) = std::fs::read_dir(proxy_dir) else {
+        return;
+    };
+    let mut files: Vec<(PathBuf, u64, std::time::SystemTime)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let meta = e.metadata().ok()?;
+            meta.is_file().then(|| (e.path(), meta.len(), meta.modified().unwrap_or(std::time::UNIX_EPOCH)))
+        })
+        .collect();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    files.sort_by_key(|(_, _, modified)| *modified);
+    for (path, len, _) in files {
+        if total <= PROXY_CACHE_CAP_BYTES {
+            break;
+        }
+        if path == keep {
+            continue;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(len);
+        }
+    }
+}
+
+fn proxy_dir(state: &VideoEditorState) -> PathBuf {
+    state.temp_dir.join("proxies")
+}
+
+async fn report_proxy_progress(
+    state: &SharedVideoEditorState,
+    app_handle: &tauri::AppHandle,
+    source: &str,
+    percent: f64,
+) {
+    let mut s = state.lock().await;
+    let Some(job) = s.proxy_jobs.get_mut(source) else {
+        return;
+    };
+    job.status.percent = job.status.percent.max(percent).min(99.9);
+    let status = job.status.clone();
+    drop(s);
+    let _ = tauri::Emitter::emit(app_handle, "video-editor://proxy-progress", &status);
+}
+
+async fn finish_proxy(state: &SharedVideoEditorState, app_handle: &tauri::AppHandle, status: ProxyStatus) {
+    let mut s = state.lock().await;
+    if let Some(job) = s.proxy_jobs.get_mut(&status.source_path) {
+        job.status = status.clone();
+    }
+    drop(s);
+    let _ = tauri::Emitter::emit(app_handle, "video-editor://proxy-progress", &status);
+}
+
+/// Re-encoded rather than copied: segment times stay in source time, so
+/// the proxy has to run exactly as long as the source. The short GOP is
+/// what makes scrubbing cheap.
+#[allow(clippy::too_many_arguments)]
+async fn encode_proxy(
+    ffmpeg: &Path,
+    source: &str,
+    output: &Path,
+    height: u32,
+    duration: f64,
+    state: &SharedVideoEditorState,
+    cancel: &AtomicBool,
+    app_handle: &tauri::AppHandle,
+) -> Result<(), String> {
+    let partial = output.with_extension("part.mp4");
+    let partial_str = partial.to_string_lossy().to_string();
+    let mut cmd = tokio::process::Command::new(ffmpeg);
+    cmd.args([
+        "-y",
+        "-progress",
+        "pipe:1",
+        "-i",
+        source,
+        "-map",
+        "0:v:0",
+        "-map",
+        "0:a:0?",
+        "-vf",
+        &format!("scale=-2:{}", height),
+        "-c:v",
+        "libx264",
+        "-preset",
+        "veryfast",
+        "-crf",
+        "26",
+        "-g",
+        "15",
+        "-pix_fmt",
+        "yuv420p",
+        "-c:a",
+        "aac",
+        "-b:a",
+        "128k",
+        "-movflags",
+        "+faststart",
+        &partial_str,
+    ]);
+    let slot = ProgressSlot::Proxy { source, duration };
+    let started = std::time::Instant::now();
+    if let Err(e) = run_ffmpeg_capturing(cmd, slot, started, state, cancel, app_handle).await {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e);
+    }
+    std::fs::rename(&partial, output).map_err(|e| format!("Save proxy failed: {}", e))
+}
+
+/// Starts a low-res proxy of `path` for playback, or returns the cached one.
+#[tauri::command]
+pub async fn generate_proxy(
+    license: tauri::State<'_, SharedLicenseState>,
+    state: tauri::State<'_, SharedVideoEditorState>,
+    app_handle: tauri::AppHandle,
+    path: String,
+    height: u32,
+) -> Result<ProxyStatus, String> {
+    crate::store::require_module(&license, "video-editor").await?;
+    if !PROXY_HEIGHTS.contains(&height) {
+        return Err(format!("Proxy height must be 480 or 720, not {}", height));
+    }
+
+    let mut s = state.lock().await;
+    let ffmpeg = s
+        .ffmpeg_path
+        .clone()
+        .ok_or("FFmpeg not found. Run detect_ffmpeg first.")?;
+    let ffprobe = s.ffprobe_path.clone();
+    let dir = proxy_dir(&s);
+    let output = proxy_file(&dir, &path, height)?;
+
+    if output.exists() {
+        touch(&output);
+        return Ok(ProxyStatus {
+            source_path: path,
+            status: "ready".to_string(),
+            percent: 100.0,
+            height,
+            proxy_path: Some(output.to_string_lossy().to_string()),
+            error: None,
+        });
+    }
+    if let Some(job) = s.proxy_jobs.get(&path) {
+        if job.status.status == "generating" {
+            return Ok(job.status.clone());
+        }
+    }
+    let status = ProxyStatus {
+        source_path: path.clone(),
+        status: "generating".to_string(),
+        height,
+        ..Default::default()
+    };
+    let cancel = Arc::new(AtomicBool::new(false));
+    s.proxy_jobs.insert(
+        path.clone(),
+        ProxyJob {
+            status: status.clone(),
+            cancel: cancel.clone(),
+        },
+    );
+    drop(s);
+
+    let state = state.inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let duration = match ffprobe {
+            Some(ref p) => probe_duration(p, &path).await.unwrap_or(0.0),
+            None => 0.0,
+        };
+        let result = encode_proxy(&ffmpeg, &path, &output, height, duration, &state, &cancel, &app_handle).await;
+        let mut done = ProxyStatus {
+            source_path: path.clone(),
+            height,
+            ..Default::default()
+        };
+        match result {
+            Ok(()) => {
+                evict_proxies(&dir, &output);
+                done.status = "ready".to_string();
+                done.percent = 100.0;
+                done.proxy_path = Some(output.to_string_lossy().to_string());
+            }
+            Err(_) if cancel.load(Ordering::SeqCst) => done.status = "cancelled".to_string(),
+            Err(e) => {
+                log::warn!("Proxy for {} failed: {}", path, e);
+                done.status = "error".to_string();
+                done.error = Some(e);
+            }
+        }
+        finish_proxy(&state, &app_handle, done).await;
+    });
+
+    Ok(status)
+}
+
+/// A running or failed job for `path`, else the largest cached proxy for
+/// the source as it is now.
+#[tauri::command]
+pub async fn get_proxy_status(
+    state: tauri::State<'_, SharedVideoEditorState>,
+    path: String,
+) -> Result<ProxyStatus, String> {
+    let s = state.lock().await;
+    if let Some(job) = s.proxy_jobs.get(&path) {
+        if job.status.status != "ready" {
+            return Ok(job.status.clone());
+        }
+    }
+    let dir = proxy_dir(&s);
+    drop(s);
+    for height in PROXY_HEIGHTS {
+        let Ok(file) = proxy_file(&dir, &path, height) else {
+            break;
+        };
+        if file.exists() {
+            return Ok(ProxyStatus {
+                source_path: path,
+                status: "ready".to_string(),
+                percent: 100.0,
+                height,
+                proxy_path: Some(file.to_string_lossy().to_string()),
+                error: None,
+            });
+        }
+    }
+    Ok(ProxyStatus {
+        source_path: path,
+        status: "none".to_string(),
+        ..Default::default()
+    })
+}
+
+/// Cancels and deletes the proxies for `path`, or every proxy when it's
+/// None. Returns the bytes freed.
+#[tauri::command]
+pub async fn delete_proxies(
+    state: tauri::State<'_, SharedVideoEditorState>,
+    path: Option<String>,
+) -> Result<u64, String> {
+    let mut s = state.lock().await;
+    s.proxy_jobs.retain(|source, job| {
+        let matches = path.as_deref().map_or(true, |p| p == source);
+        if matches {
+            job.cancel.store(true, Ordering::SeqCst);
+        }
+        !matches
+    });
+    let dir = proxy_dir(&s);
+    drop(s);
+
+    let prefix = path.as_deref().map(proxy_prefix);
+    let entries = std::fs::read_dir(&dir).map_err(|e| format!("Read proxy dir failed: {}", e))?;
+    let mut freed = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if prefix.as_deref().map_or(false, |p| !name.starts_with(p)) {
+            continue;
+        }
+        let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if std::fs::remove_file(entry.path()).is_ok() {
+            freed += len;
+        }
+    }
+    Ok(freed)
+}
+
+// ---- Phase 15: Subtitle Burn-In ----
+
+/// A cue's spans on the edited timeline, one per kept segment it overlaps,
+/// so a cue that straddles a cut is split at the cut rather than stretched
+/// across it. Empty when the cue falls entirely in cuts.
+fn remap_span(start: f64, end: f64, kept: &[&Segment]) -> Vec<(f64, f64)> {
+    let mut offset = 0.0;
+    let mut spans = Vec::new();
+    for seg in kept {
+        let a = start.max(seg.start);
+        let b = end.min(seg.end);
+        if b > a {
+            spans.push((offset + a - seg.start, offset + b - seg.start));
+        }
+        offset += seg.end - seg.start;
+    }
+    spans
+}
+
+/// "H:MM:SS.cc" (ASS) or "HH:MM:SS,mmm" (SRT).
+fn parse_subtitle_time(s: &str) -> Option<f64> {
+    let mut parts = s.trim().rsplitn(3, ':');
+    let secs: f64 = parts.next()?.replace(',', ".").parse().ok()?;
+    let mins: f64 = parts.next()?.parse().ok()?;
+    let hours: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + mins * 60.0 + secs)
+}
+
+/// Rewrites every cue's times, renumbering and dropping cues that fall
+/// entirely in cuts.
+fn remap_srt(content: &str, kept: &[&Segment]) -> String {
+    let normalized = content.replace("\r\n", "\n");
+    let mut out = String::new();
+    let mut n = 0;
+    for block in normalized.split("\n\n") {
+        let lines: Vec<&str> = block.lines().filter(|l| !l.trim().is_empty()).collect();
+        let Some(timing) = lines.iter().position(|l| l.contains("-->")) else {
+            continue;
+        };
+        let mut times = lines[timing].split("-->");
+        let start = times.next().and_then(parse_subtitle_time);
+        // Anything after the end time (positioning) is dropped.
+        let end = times
+            .next()
+            .and_then(|t| t.split_whitespace().next())
+            .and_then(parse_subtitle_time);
+        let (Some(start), Some(end)) = (start, end) else {
+            continue;
+        };
+        for (start, end) in remap_span(start, end, kept) {
+            n += 1;
+            out.push_str(&format!(
+                "{}\r\n{} --> {}\r\n",
+                n,
+                format_srt_time(start),
+                format_srt_time(end)
+            ));
+            for line in &lines[timing + 1..] {
+                out.push_str(line);
+                out.push_str("\r\n");
+            }
+            out.push_str("\r\n");
+        }
+    }
+    out
+}
+
+/// Rewrites the Start/End of each Dialogue line, using the [Events] Format
+/// line to find them, and leaves the rest of the script alone.
+fn remap_ass(content: &str, kept: &[&Segment]) -> String {
+    let mut start_idx = 1;
+    let mut end_idx = 2;
+    let mut field_count = 10;
+    let mut out = String::new();
+    for line in content.lines() {
+        if let Some(format) = line.strip_prefix("Format:") {
+            let fields: Vec<&str> = format.split(',').map(str::trim).collect();
+            if let (Some(s), Some(e)) = (
+                fields.iter().position(|f| *f == "Start"),
+                fields.iter().position(|f| *f == "End"),
+            ) {
+                start_idx = s;
+                end_idx = e;
+                field_count = fields.len();
+            }
+        } else if let Some(rest) = line.strip_prefix("Dialogue:") {
+            // Text is the last field and may contain commas itself.
+            let mut fields: Vec<String> = rest.splitn(field_count, ',').map(String::from).collect();
+            let times = fields
+                .get(start_idx)
+                .and_then(|s| parse_subtitle_time(s))
+                .zip(fields.get(end_idx).and_then(|e| parse_subtitle_time(e)));
+            let Some((start, end)) = times else {
+                continue;
+            };
+            for (start, end) in remap_span(start, end, kept) {
+                fields[start_idx] = format_ass_time(start);
+                fields[end_idx] = format_ass_time(end);
+                out.push_str(&format!("Dialogue: {}\r\n", fields.join(",").trim_start()));
+            }
+            continue;
+        }
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Writes a copy of the subtitle file remapped to the edit and returns the
+/// filter that burns it in.
+fn prepare_subtitles(path: &str, segments: &[Segment], temp_dir: &Path) -> Result<String, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Read subtitles failed: {}", e))?;
+    let content = content.trim_start_matches('\u{feff}');
+    let mut kept: Vec<&Segment> = segments.iter().filter(|s| !s.deleted).collect();
+    kept.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+    let ext = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let (remapped, name, filter) = match ext.as_str() {
+        "ass" | "ssa" => (remap_ass(content, &kept), "export_subtitles.ass", "ass"),
+        "srt" => (remap_srt(content, &kept), "export_subtitles.srt", "subtitles"),
+        _ => return Err(format!("Unsupported subtitle file '{}'; use .ass or .srt", path)),
+    };
+    let out = temp_dir.join(name);
+    std::fs::write(&out, remapped).map_err(|e| format!("Write subtitles failed: {}", e))?;
+    Ok(format!("{}='{}'", filter, escape_ass_path_for_ffmpeg(&out)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(template: &str) -> ExportPreset {
+        ExportPreset {
+            name: "YouTube".into(),
+            format: "mp4".into(),
+            video_codec: "libx264".into(),
+            quality: "high".into(),
+            resolution: None,
+            audio_track_mode: None,
+            audio_tracks: Vec::new(),
+            destination_template: template.into(),
+        }
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    /// Reports 0, 10, ... 100% of each stage in turn, checking every step of
+    /// the bar; returns the bar at each stage's start and end.
+    fn run_stages(stages: &[ExportStage]) -> Vec<(f64, f64)> {
+        let mut progress = ExportProgress::default();
+        let mut bounds = Vec::new();
+        for stage in stages {
+            let mut start = None;
+            for step in 0..=10 {
+                let (percent, _) = export_percent(&progress, stage, step as f64 * 10.0);
+                assert!(percent >= progress.percent, "{} went back at {}0%", stage.name, step);
+                assert!(percent < 100.0);
+                start.get_or_insert(percent);
+                progress.percent = percent;
+            }
+            bounds.push((start.unwrap(), progress.percent));
+        }
+        bounds
+    }
+
+    #[test]
+    fn export_stages_meet_end_to_end() {
+        let (splits, concat) = ExportStage::split_and_concat(&[seg(0.0, 30.0), seg(50.0, 60.0)]);
+        let mut stages = splits.clone();
+        stages.extend([concat, ExportStage::FINALIZE]);
+        for pair in stages.windows(2) {
+            assert!(close(pair[0].overall(100.0), pair[1].overall(0.0)), "{} -> {}", pair[0].name, pair[1].name);
+        }
+        // Splits share what concat and finalize leave, by segment length.
+        assert!(close(splits[0].overall(100.0), 66.0));
+        assert!(close(concat.overall(0.0), 88.0));
+        assert!(close(ExportStage::FINALIZE.overall(0.0), 98.0));
+        assert!(close(ExportStage::FINALIZE.overall(100.0), 100.0));
+
+        assert!(close(ExportStage::encode(60.0).overall(100.0), ExportStage::FINALIZE.overall(0.0)));
+        assert!(close(ExportStage::measure(60.0).overall(100.0), ExportStage::encode_after_measure(60.0).overall(0.0)));
+        assert!(close(ExportStage::encode_after_measure(60.0).overall(100.0), 98.0));
+        assert!(close(ExportStage::palette(60.0).overall(100.0), 0.0));
+    }
+
+    #[test]
+    fn export_percent_at_stage_edges() {
+        let progress = ExportProgress::default();
+        let encode = ExportStage::encode(60.0);
+        assert_eq!(export_percent(&progress, &encode, 0.0).0, 0.0);
+        assert!(close(export_percent(&progress, &encode, 100.0).0, 98.0));
+        // Out-of-range stage percents are clamped to the stage.
+        assert!(close(export_percent(&progress, &encode, 140.0).0, 98.0));
+        assert_eq!(export_percent(&progress, &encode, -5.0).0, 0.0);
+        assert!(close(export_percent(&progress, &ExportStage::FINALIZE, 0.0).0, 98.0));
+        // Only the finished export reports 100.
+        assert_eq!(export_percent(&progress, &ExportStage::FINALIZE, 100.0).0, 99.9);
+    }
+
+    #[test]
+    fn export_percent_never_goes_backwards() {
+        let (splits, concat) = ExportStage::split_and_concat(&[seg(0.0, 30.0), seg(50.0, 60.0), seg(70.0, 75.0)]);
+        let mut stages = splits;
+        stages.extend([concat, ExportStage::FINALIZE]);
+        let bounds = run_stages(&stages);
+        assert_eq!(bounds[0].0, 0.0);
+        assert_eq!(bounds.last().unwrap().1, 99.9);
+
+        let bounds = run_stages(&[ExportStage::encode(60.0), ExportStage::FINALIZE]);
+        assert!(close(bounds[0].1, 98.0) && close(bounds[1].0, 98.0));
+
+        // ffmpeg's out_time stepping back doesn't pull the bar down.
+        let progress = ExportProgress {
+            percent: 50.0,
+            ..Default::default()
+        };
+        assert_eq!(export_percent(&progress, &ExportStage::encode(60.0), 20.0).0, 50.0);
+    }
+
+    #[test]
+    fn export_percent_spreads_a_batch_over_one_bar() {
+        let progress = ExportProgress {
+            clip_index: 2,
+            clip_count: 4,
+            ..Default::default()
+        };
+        let (overall, clip) = export_percent(&progress, &ExportStage::encode(60.0), 50.0);
+        assert!(close(clip, 49.0));
+        assert!(close(overall, (100.0 + 49.0) / 4.0));
+    }
+
+    // 2023-11-14 22:13:20 UTC
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn destination_template_expands_every_token() {
+        let template = "{recording_dir}/{preset}/{name}_{date}_{time}.{ext}";
+        let path = fill_destination_template(template, "/rec", "/clips/stream.mkv", &preset(template), NOW);
+        assert_eq!(path, PathBuf::from("/rec/YouTube/stream_2023-11-14_221320.mp4"));
+    }
+
+    #[test]
+    fn destination_template_defaults() {
+        // No template and no recording dir: next to the source, under exports/.
+        let path = fill_destination_template("", "", "/clips/stream.mkv", &preset(""), NOW);
+        assert_eq!(path, PathBuf::from("/clips/exports/stream_2023-11-14.mp4"));
+        // A template without an extension gets the preset's format.
+        let path = fill_destination_template("/out/{name}", "", "/clips/stream.mkv", &preset(""), NOW);
+        assert_eq!(path, PathBuf::from("/out/stream.mp4"));
+    }
+
+    #[test]
+    fn civil_from_unix_is_utc() {
+        assert_eq!(civil_from_unix(0), (1970, 1, 1, 0, 0, 0));
+        assert_eq!(civil_from_unix(NOW), (2023, 11, 14, 22, 13, 20));
+        // Leap day.
+        assert_eq!(civil_from_unix(1_709_164_800), (2024, 2, 29, 0, 0, 0));
+    }
+
+    #[test]
+    fn unique_output_path_skips_taken_names() {
+        let dir = std::env::temp_dir().join(format!("observe-unique-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("out.mp4");
+        assert_eq!(unique_output_path(&out), out);
+        std::fs::write(&out, b"").unwrap();
+        std::fs::write(dir.join("out-1.mp4"), b"").unwrap();
+        assert_eq!(unique_output_path(&out), dir.join("out-2.mp4"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn seg(start: f64, end: f64) -> Segment {
+        Segment {
+            start,
+            end,
+            deleted: false,
+        }
+    }
+
+    #[test]
+    fn remap_span_shifts_cues_past_cuts() {
+        // Keeps 0-10 and 20-30; 10-20 is cut.
+        let (a, b) = (seg(0.0, 10.0), seg(20.0, 30.0));
+        let kept = [&a, &b];
+        assert_eq!(remap_span(2.0, 4.0, &kept), vec![(2.0, 4.0)]);
+        assert_eq!(remap_span(22.0, 25.0, &kept), vec![(12.0, 15.0)]);
+        assert!(remap_span(12.0, 18.0, &kept).is_empty());
+        // Trimmed where it runs into the cut.
+        assert_eq!(remap_span(8.0, 15.0, &kept), vec![(8.0, 10.0)]);
+    }
+
+    #[test]
+    fn remap_span_splits_a_cue_straddling_two_segments() {
+        let (a, b) = (seg(0.0, 10.0), seg(20.0, 30.0));
+        let kept = [&a, &b];
+        assert_eq!(remap_span(8.0, 23.0, &kept), vec![(8.0, 10.0), (10.0, 13.0)]);
+    }
+
+    #[test]
+    fn remap_srt_renumbers_and_splits() {
+        let (a, b) = (seg(0.0, 10.0), seg(20.0, 30.0));
+        let kept = [&a, &b];
+        let srt = "1\n00:00:01,000 --> 00:00:02,000\nHello\n\n2\n00:00:12,000 --> 00:00:15,000\nCut\n\n3\n00:00:09,000 --> 00:00:21,500\nAcross\n";
+        assert_eq!(
+            remap_srt(srt, &kept),
+            "1\r\n00:00:01,000 --> 00:00:02,000\r\nHello\r\n\r\n\
+             2\r\n00:00:09,000 --> 00:00:10,000\r\nAcross\r\n\r\n\
+             3\r\n00:00:10,000 --> 00:00:11,500\r\nAcross\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn remap_ass_rewrites_dialogue_times() {
+        let (a, b) = (seg(0.0, 10.0), seg(20.0, 30.0));
+        let kept = [&a, &b];
+        let ass = "[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+                   Dialogue: 0,0:00:12.00,0:00:14.00,Default,,0,0,0,,Cut\n\
+                   Dialogue: 0,0:00:09.00,0:00:21.00,Default,,0,0,0,,Across, with commas\n";
+        assert_eq!(
+            remap_ass(ass, &kept),
+            "[Events]\r\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\r\n\
+             Dialogue: 0,0:00:09.00,0:00:10.00,Default,,0,0,0,,Across, with commas\r\n\
+             Dialogue: 0,0:00:10.00,0:00:11.00,Default,,0,0,0,,Across, with commas\r\n"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use crate::chat_provider::{self, AiProviderKind};
+use crate::i18n::t;
+use crate::settings::{SharedSettings, VoiceSettings};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::sync::Mutex;
+
+pub type SharedVoiceCapture = Arc<Mutex<Option<VoiceCapture>>>;
+
+/// Capture stops by itself at this length.
+const MAX_DURATION: Duration = Duration::from_secs(60);
+const MIN_DURATION_SECS: f64 = 0.3;
+/// A peak below this means nothing was said.
+const SILENCE_PEAK_DB: f64 = -45.0;
+/// What both STT backends work best with; also keeps uploads small.
+const CLIP_SAMPLE_RATE: u32 = 16_000;
+const START_TIMEOUT: Duration = Duration::from_secs(3);
+const WHISPER_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub struct VoiceCapture {
+    stop: Arc<AtomicBool>,
+    thread: std::thread::JoinHandle<Result<VoiceClip, String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceClip {
+    pub path: String,
+    pub duration_secs: f64,
+    pub peak_db: f64,
+    /// Hit the 60s limit before stop_voice_capture was called.
+    pub truncated: bool,
+}
+
+fn voice_dir() -> PathBuf {
+    std::env::temp_dir().join("observe-voice")
+}
+
+/// Deletes the recording when dropped, whichever way transcription ends.
+struct RemoveOnDrop<'a>(&'a Path);
+
+impl Drop for RemoveOnDrop<'_> {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(self.0);
+    }
+}
+
+/// Starts recording the configured mic, or the default one, to a temp WAV.
+#[tauri::command]
+pub async fn start_voice_capture(
+    capture: tauri::State<'_, SharedVoiceCapture>,
+    settings: tauri::State<'_, SharedSettings>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    let mut capture = capture.lock().await;
+    if capture.is_some() {
+        return Err("Voice capture is already running".into());
+    }
+    let device_id = settings.read().await.voice.device_id.clone();
+    let dir = voice_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create dir: {}", e))?;
+    let path = dir.join(format!("ptt_{}.wav", uuid::Uuid::new_v4()));
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let (ready_tx, ready_rx) = mpsc::channel();
+    let thread = std::thread::spawn(move || {
+        let result = record(&device_id, &path, &thread_stop, &ready_tx);
+        if let Err(e) = &result {
+            let _ = ready_tx.send(Err(e.clone()));
+        }
+        if result.as_ref().is_ok_and(|clip| clip.truncated) {
+            let _ = app_handle.emit("voice://capture-limit", ());
+        }
+        result
+    });
+
+    let started = tokio::task::spawn_blocking(move || {
+        ready_rx
+            .recv_timeout(START_TIMEOUT)
+            .unwrap_or_else(|_| Err("Microphone did not start".to_string()))
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    if let Err(e) = started {
+        stop.store(true, Ordering::Relaxed);
+        return Err(e);
+    }
+    *capture = Some(VoiceCapture { stop, thread });
+    Ok(())
+}
+
+/// Stops recording. Too-short and near-silent clips are deleted and
+/// reported as errors so they never reach a transcription backend.
+#[tauri::command]
+pub async fn stop_voice_capture(capture: tauri::State<'_, SharedVoiceCapture>) -> Result<VoiceClip, String> {
+    let active = capture
+        .lock()
+        .await
+        .take()
+        .ok_or_else(|| "Voice capture isn't running".to_string())?;
+    active.stop.store(true, Ordering::Relaxed);
+    let clip = tokio::task::spawn_blocking(move || active.thread.join())
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+        .map_err(|_| "Voice capture thread panicked".to_string())??;
+
+    let problem = if clip.duration_secs < MIN_DURATION_SECS {
+        Some(t("voice.too_short", &[]))
+    } else if clip.peak_db < SILENCE_PEAK_DB {
+        Some(t("voice.silent", &[]))
+    } else {
+        None
+    };
+    if let Some(problem) = problem {
+        let _ = std::fs::remove_file(&clip.path);
+        return Err(problem);
+    }
+    Ok(clip)
+}
+
+/// Transcribes a clip from stop_voice_capture with whisper.cpp when its
+/// path is configured, otherwise with Gemini. The clip is deleted once
+/// it's transcribed.
+#[tauri::command]
+pub async fn transcribe_voice(
+    settings: tauri::State<'_, SharedSettings>,
+    path: String,
+) -> Result<String, String> {
+    let path = PathBuf::from(path);
+    if path.parent() != Some(voice_dir().as_path()) || !path.is_file() {
+        return Err("Not a voice recording".into());
+    }
+    let _cleanup = RemoveOnDrop(&path);
+    let voice = settings.read().await.voice.clone();
+    let text = if voice.whisper_path.trim().is_empty() {
+        transcribe_with_gemini(&path, &voice.language).await?
+    } else {
+        transcribe_with_whisper(&voice, &path).await?
+    };
+
+    let text = text.trim().to_string();
+    if text.is_empty() {
+        return Err(t("voice.no_speech", &[]));
+    }
+    Ok(text)
+}
+
+async fn transcribe_with_gemini(path: &Path, language: &str) -> Result<String, String> {
+    // Works whichever chat provider is selected, as long as a Gemini key exists.
+    let (api_key, _) = tokio::task::spawn_blocking(|| chat_provider::stored_key(AiProviderKind::Gemini))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))??
+        .ok_or_else(|| t("error.gemini_key_missing", &[]))?;
+    let wav = tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read recording: {}", e))?;
+    crate::gemini::transcribe_audio(&api_key, &wav, language).await
+}
+
+async fn transcribe_with_whisper(voice: &VoiceSettings, path: &Path) -> Result<String, String> {
+    let mut cmd = tokio::process::Command::new(voice.whisper_path.trim());
+    if !voice.whisper_model.trim().is_empty() {
+        cmd.arg("-m").arg(voice.whisper_model.trim());
+    }
+    if !voice.language.trim().is_empty() {
+        cmd.arg("-l").arg(voice.language.trim());
+    }
+    // No timestamps, no progress output: stdout is just the transcript.
+    cmd.arg("-nt").arg("-np").arg("-f").arg(path).kill_on_drop(true);
+
+    let output = tokio::time::timeout(WHISPER_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| "whisper.cpp timed out".to_string())?
+        .map_err(|e| format!("Failed to run whisper.cpp: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("no output");
+        return Err(format!("whisper.cpp failed: {}", reason.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+/// Linear resample of a mono signal.
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let step = from as f64 / to as f64;
+    let out_len = (samples.len() as f64 / step) as usize;
+    (0..out_len)
+        .map(|i| {
+            let pos = i as f64 * step;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = samples[idx];
+            let b = samples.get(idx + 1).copied().unwrap_or(a);
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Records until `stop` is set or MAX_DURATION passes, then writes a mono
+/// 16 kHz WAV. `ready` gets Ok once the mic is running.
+#[cfg(windows)]
+fn record(
+    device_id: &str,
+    path: &Path,
+    stop: &AtomicBool,
+    ready: &mpsc::Sender<Result<(), String>>,
+) -> Result<VoiceClip, String> {
+    use windows::Win32::System::Com::*;
+
+    unsafe {
+        CoInitializeEx(None, COINIT_MULTITHREADED)
+            .ok()
+            .map_err(|e| format!("COM init failed: {}", e))?;
+    }
+    let result = capture_mic(device_id, stop, ready).and_then(|(mono, sample_rate, truncated)| {
+        let clip = resample(&mono, sample_rate, CLIP_SAMPLE_RATE);
+        let peak = clip.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        crate::narration_capture::write_wav(&path.to_path_buf(), &clip, 1, CLIP_SAMPLE_RATE)?;
+        Ok(VoiceClip {
+            path: path.to_string_lossy().to_string(),
+            duration_secs: clip.len() as f64 / CLIP_SAMPLE_RATE as f64,
+            peak_db: if peak > 0.0 { 20.0 * (peak as f64).log10() } else { -100.0 },
+            truncated,
+        })
+    });
+    unsafe {
+        CoUninitialize();
+    }
+    result
+}
+
+/// The captured audio downmixed to mono, its sample rate, and whether the
+/// length limit cut it off.
+#[cfg(windows)]
+fn capture_mic(
+    device_id: &str,
+    stop: &AtomicBool,
+    ready: &mpsc::Sender<Result<(), String>>,
+) -> Result<(Vec<f32>, u32, bool), String> {
+    use windows::core::PCWSTR;
+    use windows::Win32::Media::Audio::*;
+    use windows::Win32::System::Com::*;
+
+    let enumerator: IMMDeviceEnumerator = unsafe {
+        CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+            .map_err(|e| format!("Device enumerator failed: {}", e))?
+    };
+    let device = unsafe {
+        if device_id.is_empty() {
+            enumerator
+                .GetDefaultAudioEndpoint(eCapture, eConsole)
+                .map_err(|e| format!("No default microphone: {}", e))?
+        } else {
+            let wide: Vec<u16> = device_id.encode_utf16().chain(std::iter::once(0)).collect();
+            enumerator
+                .GetDevice(PCWSTR(wide.as_ptr()))
+                .map_err(|e| format!("Microphone not found: {}", e))?
+        }
+    };
+    let audio_client: IAudioClient = unsafe {
+        device
+            .Activate(CLSCTX_ALL, None)
+            .map_err(|e| format!("Activate IAudioClient: {}", e))?
+    };
+    let mix_format = unsafe {
+        audio_client
+            .GetMixFormat()
+            .map_err(|e| format!("GetMixFormat: {}", e))?
+    };
+    let fmt = unsafe { *mix_format };
+    let channels = fmt.nChannels as usize;
+    let sample_rate = fmt.nSamplesPerSec;
+    let bits_per_sample = fmt.wBitsPerSample;
+    let block_align = fmt.nBlockAlign as usize;
+
+    let initialized =
+        unsafe { audio_client.Initialize(AUDCLNT_SHAREMODE_SHARED, 0, 2_000_000, 0, mix_format, None) };
+    unsafe { CoTaskMemFree(Some(mix_format as *const _)) };
+    initialized.map_err(|e| format!("Initialize: {}", e))?;
+    let capture_client: IAudioCaptureClient = unsafe {
+        audio_client
+            .GetService()
+            .map_err(|e| format!("GetService IAudioCaptureClient: {}", e))?
+    };
+    unsafe {
+        audio_client.Start().map_err(|e| format!("Start: {}", e))?;
+    }
+    let _ = ready.send(Ok(()));
+
+    let max_frames = sample_rate as usize * MAX_DURATION.as_secs() as usize;
+    let mut mono: Vec<f32> = Vec::with_capacity(max_frames);
+    let mut truncated = false;
+    while !stop.load(Ordering::Relaxed) {
+        std::thread::sleep(Duration::from_millis(10));
+        loop {
+            let packet_size = unsafe { capture_client.GetNextPacketSize().unwrap_or(0) };
+            if packet_size == 0 {
+                break;
+            }
+            let mut buffer_ptr = std::ptr::null_mut();
+            let mut num_frames = 0u32;
+            let mut flags = 0u32;
+            if unsafe { capture_client.GetBuffer(&mut buffer_ptr, &mut num_frames, &mut flags, None, None) }.is_err() {
+                break;
+            }
+            let frame_count = num_frames as usize;
+            if flags & 0x2 != 0 {
+                mono.extend(std::iter::repeat(0.0f32).take(frame_count));
+            } else if frame_count > 0 {
+                let samples = crate::spectrum::extract_samples(
+                    buffer_ptr,
+                    frame_count,
+                    channels,
+                    bits_per_sample,
+                    block_align,
+                );
+                mono.extend(
+                    samples
+                        .chunks(channels.max(1))
+                        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32),
+                );
+            }
+            let _ = unsafe { capture_client.ReleaseBuffer(num_frames) };
+        }
+        if mono.len() >= max_frames {
+            mono.truncate(max_frames);
+            truncated = true;
+            break;
+        }
+    }
+    unsafe {
+        let _ = audio_client.Stop();
+    }
+    Ok((mono, sample_rate, truncated))
+}
+
+#[cfg(not(windows))]
+fn record(
+    _device_id: &str,
+    _path: &Path,
+    _stop: &AtomicBool,
+    _ready: &mpsc::Sender<Result<(), String>>,
+) -> Result<VoiceClip, String> {
+    Err("Voice capture is only supported on Windows".into())
+}
@@ -0,0 +1,374 @@
+use crate::obs_websocket::ObsHandle;
+use crate::obs_state::SharedObsState;
+use crate::settings::{self, SharedSettings};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+pub type SharedVolumeGuardState = Arc<Mutex<GuardRuntime>>;
+
+const REAPPLY_DEBOUNCE: Duration = Duration::from_millis(400);
+const CLIP_PEAK: f64 = 0.95;
+const CLIP_SUSTAIN: Duration = Duration::from_secs(2);
+// Meter frames between peaks can dip below the threshold; only a gap longer
+// than this ends a clipping episode.
+const CLIP_GAP: Duration = Duration::from_millis(500);
+const RESCUE_STEP_DB: f64 = 3.0;
+const TOLERANCE_DB: f64 = 0.05;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VolumeGuard {
+    pub max_db: f64,
+    pub enforce: bool,
+    pub auto_rescue: bool,
+}
+
+impl Default for VolumeGuard {
+    fn default() -> Self {
+        Self {
+            max_db: 0.0,
+            enforce: true,
+            auto_rescue: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeGuardStatus {
+    pub input_name: String,
+    #[serde(flatten)]
+    pub guard: VolumeGuard,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClampResult {
+    pub input_name: String,
+    pub requested_db: f64,
+    pub applied_db: f64,
+    pub clamped: bool,
+}
+
+struct ClipEpisode {
+    started: Instant,
+    last_seen: Instant,
+    rescued: bool,
+}
+
+#[derive(Default)]
+pub struct GuardRuntime {
+    reapply_generation: HashMap<String, u64>,
+    clipping: HashMap<String, ClipEpisode>,
+}
+
+impl GuardRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn bump(&mut self, input_name: &str) -> u64 {
+        let gen = self.reapply_generation.entry(input_name.to_string()).or_insert(0);
+        *gen += 1;
+        *gen
+    }
+
+    fn is_current(&self, input_name: &str, gen: u64) -> bool {
+        self.reapply_generation.get(input_name) == Some(&gen)
+    }
+
+    /// Tracks a meter frame; returns true once per episode when the input has
+    /// been clipping for longer than CLIP_SUSTAIN.
+    fn observe_peak(&mut self, input_name: &str, peak: f64) -> bool {
+        let now = Instant::now();
+        if peak < CLIP_PEAK {
+            let ended = self
+                .clipping
+                .get(input_name)
+                .map(|e| now.duration_since(e.last_seen) > CLIP_GAP)
+                .unwrap_or(false);
+            if ended {
+                self.clipping.remove(input_name);
+            }
+            return false;
+        }
+        let episode = self.clipping.entry(input_name.to_string()).or_insert(ClipEpisode {
+            started: now,
+            last_seen: now,
+            rescued: false,
+        });
+        if now.duration_since(episode.last_seen) > CLIP_GAP {
+            episode.started = now;
+            episode.rescued = false;
+        }
+        episode.last_seen = now;
+        if !episode.rescued && now.duration_since(episode.started) >= CLIP_SUSTAIN {
+            episode.rescued = true;
+            return true;
+        }
+        false
+    }
+}
+
+pub fn clamp(guards: &HashMap<String, VolumeGuard>, input_name: &str, volume_db: f64) -> ClampResult {
+    let applied_db = match guards.get(input_name) {
+        Some(g) if g.enforce && volume_db > g.max_db => g.max_db,
+        _ => volume_db,
+    };
+    ClampResult {
+        input_name: input_name.to_string(),
+        requested_db: volume_db,
+        applied_db,
+        clamped: applied_db != volume_db,
+    }
+}
+
+async fn set_volume(conn: &ObsHandle, input_name: &str, volume_db: f64) -> Result<(), String> {
+    conn.send_request(
+        "SetInputVolume",
+        Some(json!({
+            "inputName": input_name,
+            "inputVolumeDb": volume_db,
+        })),
+    )
+    .await?;
+    Ok(())
+}
+
+/// Called for every InputVolumeChanged. A change above an enforced cap that
+/// didn't come through our own commands (OBS mixer, hotkeys, plugins) is pulled
+/// back to the cap once the value has settled.
+pub async fn on_volume_changed(app: &tauri::AppHandle, input_name: &str, volume_db: f64) {
+    let Some(settings) = app.try_state::<SharedSettings>() else {
+        return;
+    };
+    let guard = match settings.read().await.volume_guards.get(input_name) {
+        Some(g) if g.enforce && volume_db > g.max_db + TOLERANCE_DB => g.clone(),
+        _ => return,
+    };
+    let Some(runtime) = app.try_state::<SharedVolumeGuardState>() else {
+        return;
+    };
+    let runtime = runtime.inner().clone();
+    let gen = runtime.lock().unwrap_or_else(|e| e.into_inner()).bump(input_name);
+
+    let app = app.clone();
+    let input_name = input_name.to_string();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(REAPPLY_DEBOUNCE).await;
+        if !runtime.lock().unwrap_or_else(|e| e.into_inner()).is_current(&input_name, gen) {
+            return;
+        }
+        let current = app
+            .state::<SharedObsState>()
+            .read()
+            .await
+            .inputs
+            .get(&input_name)
+            .map(|i| i.volume_db);
+        if !current.map(|db| db > guard.max_db + TOLERANCE_DB).unwrap_or(false) {
+            return;
+        }
+        let conn = app.state::<ObsHandle>().inner().clone();
+        match set_volume(&conn, &input_name, guard.max_db).await {
+            Ok(()) => {
+                log::info!(
+                    "Volume guard pulled '{}' back from {:.1} dB to {:.1} dB",
+                    input_name,
+                    current.unwrap_or(0.0),
+                    guard.max_db
+                );
+                let _ = app.emit(
+                    "audio://volume-guard-triggered",
+                    json!({
+                        "inputName": input_name,
+                        "reason": "cap",
+                        "fromDb": current,
+                        "toDb": guard.max_db,
+                    }),
+                );
+            }
+            Err(e) => log::warn!("Volume guard failed to re-apply cap on '{}': {}", input_name, e),
+        }
+    });
+}
+
+/// Called once per InputVolumeMeters frame with the loudest channel peak
+/// (linear) of each input.
+pub async fn on_meter_frame(app: &tauri::AppHandle, peaks: &[(String, f64)]) {
+    let Some(settings) = app.try_state::<SharedSettings>() else {
+        return;
+    };
+    let rescue: Vec<&(String, f64)> = {
+        let s = settings.read().await;
+        peaks
+            .iter()
+            .filter(|(name, _)| s.volume_guards.get(name).map(|g| g.auto_rescue).unwrap_or(false))
+            .collect()
+    };
+    if rescue.is_empty() {
+        return;
+    }
+    let Some(runtime) = app.try_state::<SharedVolumeGuardState>() else {
+        return;
+    };
+    let triggered: Vec<String> = {
+        let mut rt = runtime.lock().unwrap_or_else(|e| e.into_inner());
+        rescue
+            .into_iter()
+            .filter(|(name, peak)| rt.observe_peak(name, *peak))
+            .map(|(name, _)| name.clone())
+            .collect()
+    };
+    for input_name in triggered {
+        rescue_clipping(app.clone(), input_name);
+    }
+}
+
+fn rescue_clipping(app: tauri::AppHandle, input_name: String) {
+    tauri::async_runtime::spawn(async move {
+        let Some(current) = app
+            .state::<SharedObsState>()
+            .read()
+            .await
+            .inputs
+            .get(&input_name)
+            .map(|i| i.volume_db)
+        else {
+            return;
+        };
+        let target = (current - RESCUE_STEP_DB).max(-100.0);
+        let conn = app.state::<ObsHandle>().inner().clone();
+        match set_volume(&conn, &input_name, target).await {
+            Ok(()) => {
+                log::warn!(
+                    "'{}' clipped for over {}s, lowered {:.1} dB -> {:.1} dB",
+                    input_name,
+                    CLIP_SUSTAIN.as_secs(),
+                    current,
+                    target
+                );
+                let _ = app.emit(
+                    "audio://volume-guard-triggered",
+                    json!({
+                        "inputName": input_name,
+                        "reason": "clipping",
+                        "fromDb": current,
+                        "toDb": target,
+                    }),
+                );
+                crate::alerts::notify(&app, "clipping", &[("input", &input_name)]);
+            }
+            Err(e) => log::warn!("Clipping rescue failed on '{}': {}", input_name, e),
+        }
+    });
+}
+
+/// Clipping rescue needs per-input meters from OBS even when the mixer is
+/// hidden.
+pub fn meter_flags(guards: &HashMap<String, VolumeGuard>) -> u64 {
+    if guards.values().any(|g| g.auto_rescue) {
+        crate::event_subscriptions::INPUT_VOLUME_METERS
+    } else {
+        0
+    }
+}
+
+async fn sync_meter_subscription(settings: &SharedSettings, conn: &ObsHandle) {
+    let flags = meter_flags(&settings.read().await.volume_guards);
+    if let Err(e) = crate::event_subscriptions::declare(conn, "volume-guard", flags).await {
+        log::warn!("Failed to update meter subscription: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn set_volume_guard(
+    settings: tauri::State<'_, SharedSettings>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    obs_state: tauri::State<'_, SharedObsState>,
+    input_name: String,
+    max_db: f64,
+    enforce: bool,
+    auto_rescue: Option<bool>,
+) -> Result<VolumeGuardStatus, String> {
+    if !(-100.0..=26.0).contains(&max_db) {
+        return Err(format!("Max volume must be between -100 and 26 dB, got {}", max_db));
+    }
+    let guard = VolumeGuard {
+        max_db,
+        enforce,
+        auto_rescue: auto_rescue.unwrap_or(false),
+    };
+    let stored = guard.clone();
+    let key = input_name.clone();
+    settings::update_section(&settings, move |s| {
+        s.volume_guards.insert(key, stored);
+    })
+    .await?;
+    sync_meter_subscription(&settings, &conn_state).await;
+
+    // Bring an input that is already above its new cap into line right away.
+    let current = obs_state.read().await.inputs.get(&input_name).map(|i| i.volume_db);
+    if guard.enforce && current.map(|db| db > max_db + TOLERANCE_DB).unwrap_or(false) {
+        set_volume(&conn_state, &input_name, max_db).await?;
+    }
+
+    Ok(VolumeGuardStatus { input_name, guard })
+}
+
+/// Moves a guard to an input's new name.
+pub async fn on_input_renamed(app: tauri::AppHandle, old_name: String, new_name: String) -> Vec<String> {
+    let Some(settings) = app.try_state::<SharedSettings>() else {
+        return Vec::new();
+    };
+    if !settings.read().await.volume_guards.contains_key(&old_name) {
+        return Vec::new();
+    }
+    let result = settings::update_section(&settings, |s| {
+        if let Some(guard) = s.volume_guards.remove(&old_name) {
+            s.volume_guards.insert(new_name, guard);
+        }
+    })
+    .await;
+    if let Err(e) = result {
+        log::warn!("Failed to save volume guard after rename: {}", e);
+        return Vec::new();
+    }
+    vec!["volume guard".to_string()]
+}
+
+#[tauri::command]
+pub async fn remove_volume_guard(
+    settings: tauri::State<'_, SharedSettings>,
+    conn_state: tauri::State<'_, ObsHandle>,
+    input_name: String,
+) -> Result<(), String> {
+    settings::update_section(&settings, move |s| {
+        s.volume_guards.remove(&input_name);
+    })
+    .await?;
+    sync_meter_subscription(&settings, &conn_state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_volume_guards(
+    settings: tauri::State<'_, SharedSettings>,
+) -> Result<Vec<VolumeGuardStatus>, String> {
+    let mut guards: Vec<VolumeGuardStatus> = settings
+        .read()
+        .await
+        .volume_guards
+        .iter()
+        .map(|(name, guard)| VolumeGuardStatus {
+            input_name: name.clone(),
+            guard: guard.clone(),
+        })
+        .collect();
+    guards.sort_by(|a, b| a.input_name.cmp(&b.input_name));
+    Ok(guards)
+}
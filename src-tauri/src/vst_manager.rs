@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::Manager;
 
 const BUNDLED_VSTS: &[&str] = &[
@@ -22,6 +24,16 @@ const BUNDLED_VSTS: &[&str] = &[
     "Verbity.dll",
 ];
 
+/// Where VST2 installers put 64-bit plugins by default.
+const STANDARD_VST2_DIRS: &[&str] = &[
+    r"C:\Program Files\VSTPlugins",
+    r"C:\Program Files\Steinberg\VSTPlugins",
+    r"C:\Program Files\Common Files\VST2",
+    r"C:\Program Files\Common Files\Steinberg\VST2",
+];
+/// Plugin folders are often grouped by vendor; don't walk deeper than this.
+const MAX_SCAN_DEPTH: usize = 4;
+
 const VST_DOWNLOAD_BASE: &str = "https://observe-api.smythmyke.workers.dev/vst";
 
 pub struct VstCatalogEntry {
@@ -30,6 +42,18 @@ pub struct VstCatalogEntry {
     pub description: &'static str,
     pub category: &'static str,
     pub size_bytes: u64,
+    /// Pinned for the bundled plugins. Downloaded ones without a pin are
+    /// checked against the hash recorded when they were installed.
+    pub sha256: Option<&'static str>,
+}
+
+/// Whether an installed DLL matches its expected hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginState {
+    Valid,
+    Corrupt,
+    Missing,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -41,76 +65,91 @@ pub struct VstCatalogWithStatus {
     pub category: String,
     pub size_bytes: u64,
     pub installed: bool,
+    pub state: PluginState,
     pub bundled: bool,
+    /// "bundled", "catalog" or "user".
+    pub source: String,
+    /// Set for user plugins, which live outside the install folder.
+    pub full_path: Option<String>,
+}
+
+/// A VST2 plugin found by scan_user_vsts.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserVst {
+    pub name: String,
+    pub dll_name: String,
+    pub path: String,
+    pub size_bytes: u64,
 }
 
 const VST_CATALOG: &[VstCatalogEntry] = &[
     // --- Dynamics ---
-    VstCatalogEntry { name: "Pressure4", dll_name: "Pressure4.dll", description: "Pressure-style compressor with speed control", category: "Dynamics", size_bytes: 200_000 },
-    VstCatalogEntry { name: "BlockParty", dll_name: "BlockParty.dll", description: "Loudness limiter for streaming", category: "Dynamics", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Surge", dll_name: "Surge.dll", description: "Compressor with a surge/release character", category: "Dynamics", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Thunder", dll_name: "Thunder.dll", description: "Fat bass-filtered compressor", category: "Dynamics", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Pop", dll_name: "Pop.dll", description: "Bright punchy compressor", category: "Dynamics", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Logical4", dll_name: "Logical4.dll", description: "SSL-style bus compressor", category: "Dynamics", size_bytes: 200_000 },
-    VstCatalogEntry { name: "ButterComp2", dll_name: "ButterComp2.dll", description: "Smooth transparent compressor", category: "Dynamics", size_bytes: 200_000 },
+    VstCatalogEntry { name: "Pressure4", dll_name: "Pressure4.dll", description: "Pressure-style compressor with speed control", category: "Dynamics", size_bytes: 200_000, sha256: Some("1766ecf952724becb30526982a54cc1846429f593feaa9e06875668d8285ceb5") },
+    VstCatalogEntry { name: "BlockParty", dll_name: "BlockParty.dll", description: "Loudness limiter for streaming", category: "Dynamics", size_bytes: 200_000, sha256: Some("5297e895b044538597a9ca7f5981d9195c062eefdd56eca165ba5af805a1bbd3") },
+    VstCatalogEntry { name: "Surge", dll_name: "Surge.dll", description: "Compressor with a surge/release character", category: "Dynamics", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Thunder", dll_name: "Thunder.dll", description: "Fat bass-filtered compressor", category: "Dynamics", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Pop", dll_name: "Pop.dll", description: "Bright punchy compressor", category: "Dynamics", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Logical4", dll_name: "Logical4.dll", description: "SSL-style bus compressor", category: "Dynamics", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "ButterComp2", dll_name: "ButterComp2.dll", description: "Smooth transparent compressor", category: "Dynamics", size_bytes: 200_000, sha256: None },
 
     // --- EQ & Tone ---
-    VstCatalogEntry { name: "Air", dll_name: "Air.dll", description: "Tilt EQ for brightness and warmth", category: "EQ & Tone", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Capacitor", dll_name: "Capacitor.dll", description: "High and low pass filter pair", category: "EQ & Tone", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Baxandall", dll_name: "Baxandall.dll", description: "Classic Baxandall tone control", category: "EQ & Tone", size_bytes: 200_000 },
-    VstCatalogEntry { name: "ToneSlant", dll_name: "ToneSlant.dll", description: "Fixed-pointed tilt EQ", category: "EQ & Tone", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Weight", dll_name: "Weight.dll", description: "Low-frequency shelf boost", category: "EQ & Tone", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Hermepass", dll_name: "Hermepass.dll", description: "Steep highpass filter", category: "EQ & Tone", size_bytes: 200_000 },
+    VstCatalogEntry { name: "Air", dll_name: "Air.dll", description: "Tilt EQ for brightness and warmth", category: "EQ & Tone", size_bytes: 200_000, sha256: Some("f2074e3453ae2ce33b23165e714882b95d2f86f7a941e3852474924964655281") },
+    VstCatalogEntry { name: "Capacitor", dll_name: "Capacitor.dll", description: "High and low pass filter pair", category: "EQ & Tone", size_bytes: 200_000, sha256: Some("34ffb0e82300eacb8bd5b4ec868fabd03704579faf0003cd7e5e9fbbb19d0231") },
+    VstCatalogEntry { name: "Baxandall", dll_name: "Baxandall.dll", description: "Classic Baxandall tone control", category: "EQ & Tone", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "ToneSlant", dll_name: "ToneSlant.dll", description: "Fixed-pointed tilt EQ", category: "EQ & Tone", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Weight", dll_name: "Weight.dll", description: "Low-frequency shelf boost", category: "EQ & Tone", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Hermepass", dll_name: "Hermepass.dll", description: "Steep highpass filter", category: "EQ & Tone", size_bytes: 200_000, sha256: None },
 
     // --- Saturation ---
-    VstCatalogEntry { name: "PurestDrive", dll_name: "PurestDrive.dll", description: "Ultra-clean saturation stage", category: "Saturation", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Tape", dll_name: "Tape.dll", description: "Analog tape warmth and saturation", category: "Saturation", size_bytes: 200_000 },
-    VstCatalogEntry { name: "NC17", dll_name: "NC17.dll", description: "Harsh pointed distortion", category: "Saturation", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Drive", dll_name: "Drive.dll", description: "General purpose overdrive", category: "Saturation", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Distortion", dll_name: "Distortion.dll", description: "Aggressive distortion effect", category: "Saturation", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Mojo", dll_name: "Mojo.dll", description: "Subtle analog warmth", category: "Saturation", size_bytes: 200_000 },
+    VstCatalogEntry { name: "PurestDrive", dll_name: "PurestDrive.dll", description: "Ultra-clean saturation stage", category: "Saturation", size_bytes: 200_000, sha256: Some("b2e59b8e729aa3c8cece9e4c80d89e6a192f64e2ce49a4c164b454731c5e0a32") },
+    VstCatalogEntry { name: "Tape", dll_name: "Tape.dll", description: "Analog tape warmth and saturation", category: "Saturation", size_bytes: 200_000, sha256: Some("929c2ccf3ae4be5880c0ded31cf930fdb6f0d5f372342435de9f06af4b32da22") },
+    VstCatalogEntry { name: "NC17", dll_name: "NC17.dll", description: "Harsh pointed distortion", category: "Saturation", size_bytes: 200_000, sha256: Some("3c2761283ca4ce78f38febc4d96748aa0b124204393edac2cffae7c3ba43f0c1") },
+    VstCatalogEntry { name: "Drive", dll_name: "Drive.dll", description: "General purpose overdrive", category: "Saturation", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Distortion", dll_name: "Distortion.dll", description: "Aggressive distortion effect", category: "Saturation", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Mojo", dll_name: "Mojo.dll", description: "Subtle analog warmth", category: "Saturation", size_bytes: 200_000, sha256: None },
 
     // --- Gate & Expand ---
-    VstCatalogEntry { name: "Gatelope", dll_name: "Gatelope.dll", description: "Gate with lowpass envelope shaping", category: "Gate & Expand", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Pyewacket", dll_name: "Pyewacket.dll", description: "Old-school compressor character", category: "Dynamics", size_bytes: 200_000 },
-    VstCatalogEntry { name: "SoftGate", dll_name: "SoftGate.dll", description: "Gentle noise gate with soft knee", category: "Gate & Expand", size_bytes: 200_000 },
+    VstCatalogEntry { name: "Gatelope", dll_name: "Gatelope.dll", description: "Gate with lowpass envelope shaping", category: "Gate & Expand", size_bytes: 200_000, sha256: Some("a8647b4c9dccf048bfa60cbc71d61d13765f65922019e3abb587ab1eb1e58fff") },
+    VstCatalogEntry { name: "Pyewacket", dll_name: "Pyewacket.dll", description: "Old-school compressor character", category: "Dynamics", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "SoftGate", dll_name: "SoftGate.dll", description: "Gentle noise gate with soft knee", category: "Gate & Expand", size_bytes: 200_000, sha256: None },
 
     // --- De-Ess & Clean ---
-    VstCatalogEntry { name: "DeEss", dll_name: "DeEss.dll", description: "Sibilance reducer for vocals", category: "De-Ess & Clean", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Acceleration", dll_name: "Acceleration.dll", description: "Slew-rate limiter for harshness", category: "De-Ess & Clean", size_bytes: 200_000 },
-    VstCatalogEntry { name: "PurestConsoleChannel", dll_name: "PurestConsoleChannel.dll", description: "Ultra-clean console channel strip", category: "De-Ess & Clean", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Noise", dll_name: "Noise.dll", description: "Noise removal utility", category: "De-Ess & Clean", size_bytes: 200_000 },
+    VstCatalogEntry { name: "DeEss", dll_name: "DeEss.dll", description: "Sibilance reducer for vocals", category: "De-Ess & Clean", size_bytes: 200_000, sha256: Some("4e06dde462f1cff805b0c5f117057d8115b201f96cde11764435805f2b404688") },
+    VstCatalogEntry { name: "Acceleration", dll_name: "Acceleration.dll", description: "Slew-rate limiter for harshness", category: "De-Ess & Clean", size_bytes: 200_000, sha256: Some("65de0e5509608566af0faf209146a1e1eda750f40d189c08e9a6da8839f6517f") },
+    VstCatalogEntry { name: "PurestConsoleChannel", dll_name: "PurestConsoleChannel.dll", description: "Ultra-clean console channel strip", category: "De-Ess & Clean", size_bytes: 200_000, sha256: Some("5787c4bfbcdf57384adf0a6b6478643da87ceb149b181e6164ad69aa4ee76c62") },
+    VstCatalogEntry { name: "Noise", dll_name: "Noise.dll", description: "Noise removal utility", category: "De-Ess & Clean", size_bytes: 200_000, sha256: None },
 
     // --- Stereo & Space ---
-    VstCatalogEntry { name: "Verbity", dll_name: "Verbity.dll", description: "Lush stereo reverb", category: "Stereo & Space", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Chamber", dll_name: "Chamber.dll", description: "Small room reverb", category: "Stereo & Space", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Galactic", dll_name: "Galactic.dll", description: "Super-long ambient reverb", category: "Stereo & Space", size_bytes: 200_000 },
-    VstCatalogEntry { name: "StereoFX", dll_name: "StereoFX.dll", description: "Stereo widening and narrowing", category: "Stereo & Space", size_bytes: 200_000 },
-    VstCatalogEntry { name: "ToVinyl4", dll_name: "ToVinyl4.dll", description: "Vinyl mastering EQ and stereo", category: "Stereo & Space", size_bytes: 200_000 },
-    VstCatalogEntry { name: "BrightAmbience3", dll_name: "BrightAmbience3.dll", description: "Bright artificial ambience", category: "Stereo & Space", size_bytes: 200_000 },
+    VstCatalogEntry { name: "Verbity", dll_name: "Verbity.dll", description: "Lush stereo reverb", category: "Stereo & Space", size_bytes: 200_000, sha256: Some("5cacd4612dab764e7de2c172878628dec624cad21558139127129357fee41879") },
+    VstCatalogEntry { name: "Chamber", dll_name: "Chamber.dll", description: "Small room reverb", category: "Stereo & Space", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Galactic", dll_name: "Galactic.dll", description: "Super-long ambient reverb", category: "Stereo & Space", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "StereoFX", dll_name: "StereoFX.dll", description: "Stereo widening and narrowing", category: "Stereo & Space", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "ToVinyl4", dll_name: "ToVinyl4.dll", description: "Vinyl mastering EQ and stereo", category: "Stereo & Space", size_bytes: 200_000, sha256: Some("120913dd3847444a3be531fe5dc7ec9b7e3f51a58782a4701dd8ee17cd813b7b") },
+    VstCatalogEntry { name: "BrightAmbience3", dll_name: "BrightAmbience3.dll", description: "Bright artificial ambience", category: "Stereo & Space", size_bytes: 200_000, sha256: None },
 
     // --- Channel Strip ---
-    VstCatalogEntry { name: "CStrip", dll_name: "CStrip.dll", description: "Full channel strip processor", category: "Channel Strip", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Console7Channel", dll_name: "Console7Channel.dll", description: "Console7 channel emulation", category: "Channel Strip", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Density", dll_name: "Density.dll", description: "Color saturation compressor", category: "Channel Strip", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Compresaturator", dll_name: "Compresaturator.dll", description: "Compression plus saturation", category: "Channel Strip", size_bytes: 200_000 },
+    VstCatalogEntry { name: "CStrip", dll_name: "CStrip.dll", description: "Full channel strip processor", category: "Channel Strip", size_bytes: 200_000, sha256: Some("7a50e2c86ead445c13d18bb57d13e269dbdde475dae0a5d9eaec3a341fa855d6") },
+    VstCatalogEntry { name: "Console7Channel", dll_name: "Console7Channel.dll", description: "Console7 channel emulation", category: "Channel Strip", size_bytes: 200_000, sha256: Some("f70689b755812b07b792c2be0e5e13528620b8984961e94c38537ee1e33ad8c7") },
+    VstCatalogEntry { name: "Density", dll_name: "Density.dll", description: "Color saturation compressor", category: "Channel Strip", size_bytes: 200_000, sha256: Some("ae3a96b6429947f11ba3f5d232c45f2ac7587959172ad39a6f543be18eee5b49") },
+    VstCatalogEntry { name: "Compresaturator", dll_name: "Compresaturator.dll", description: "Compression plus saturation", category: "Channel Strip", size_bytes: 200_000, sha256: None },
 
     // --- Creative FX ---
-    VstCatalogEntry { name: "Vibrato", dll_name: "Vibrato.dll", description: "Classic pitch vibrato effect", category: "Creative FX", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Chorus", dll_name: "Chorus.dll", description: "Stereo chorus effect", category: "Creative FX", size_bytes: 200_000 },
-    VstCatalogEntry { name: "PitchDelay", dll_name: "PitchDelay.dll", description: "Pitch-shifted delay effect", category: "Creative FX", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Spiral", dll_name: "Spiral.dll", description: "Soft-clip spiral saturation", category: "Creative FX", size_bytes: 200_000 },
-    VstCatalogEntry { name: "PhaseNudge", dll_name: "PhaseNudge.dll", description: "Subtle phase shift effect", category: "Creative FX", size_bytes: 200_000 },
-    VstCatalogEntry { name: "ChorusEnsemble", dll_name: "ChorusEnsemble.dll", description: "Rich ensemble chorus", category: "Creative FX", size_bytes: 200_000 },
-    VstCatalogEntry { name: "TapeDelay", dll_name: "TapeDelay.dll", description: "Analog tape delay emulation", category: "Creative FX", size_bytes: 200_000 },
+    VstCatalogEntry { name: "Vibrato", dll_name: "Vibrato.dll", description: "Classic pitch vibrato effect", category: "Creative FX", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Chorus", dll_name: "Chorus.dll", description: "Stereo chorus effect", category: "Creative FX", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "PitchDelay", dll_name: "PitchDelay.dll", description: "Pitch-shifted delay effect", category: "Creative FX", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Spiral", dll_name: "Spiral.dll", description: "Soft-clip spiral saturation", category: "Creative FX", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "PhaseNudge", dll_name: "PhaseNudge.dll", description: "Subtle phase shift effect", category: "Creative FX", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "ChorusEnsemble", dll_name: "ChorusEnsemble.dll", description: "Rich ensemble chorus", category: "Creative FX", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "TapeDelay", dll_name: "TapeDelay.dll", description: "Analog tape delay emulation", category: "Creative FX", size_bytes: 200_000, sha256: None },
 
     // --- Utility ---
-    VstCatalogEntry { name: "PurestGain", dll_name: "PurestGain.dll", description: "Ultra-clean gain staging utility", category: "Utility", size_bytes: 200_000 },
-    VstCatalogEntry { name: "BitShiftGain", dll_name: "BitShiftGain.dll", description: "Bit-perfect gain in 6dB steps", category: "Utility", size_bytes: 200_000 },
-    VstCatalogEntry { name: "Monitoring", dll_name: "Monitoring.dll", description: "Monitoring utility with dim/mono", category: "Utility", size_bytes: 200_000 },
-    VstCatalogEntry { name: "ClipOnly2", dll_name: "ClipOnly2.dll", description: "Final stage safety clipper", category: "Utility", size_bytes: 200_000 },
-    VstCatalogEntry { name: "PeaksOnly", dll_name: "PeaksOnly.dll", description: "Shows only peaks of audio", category: "Utility", size_bytes: 200_000 },
-    VstCatalogEntry { name: "SlewOnly", dll_name: "SlewOnly.dll", description: "Shows only slew of audio signal", category: "Utility", size_bytes: 200_000 },
+    VstCatalogEntry { name: "PurestGain", dll_name: "PurestGain.dll", description: "Ultra-clean gain staging utility", category: "Utility", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "BitShiftGain", dll_name: "BitShiftGain.dll", description: "Bit-perfect gain in 6dB steps", category: "Utility", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "Monitoring", dll_name: "Monitoring.dll", description: "Monitoring utility with dim/mono", category: "Utility", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "ClipOnly2", dll_name: "ClipOnly2.dll", description: "Final stage safety clipper", category: "Utility", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "PeaksOnly", dll_name: "PeaksOnly.dll", description: "Shows only peaks of audio", category: "Utility", size_bytes: 200_000, sha256: None },
+    VstCatalogEntry { name: "SlewOnly", dll_name: "SlewOnly.dll", description: "Shows only slew of audio signal", category: "Utility", size_bytes: 200_000, sha256: None },
 ];
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -127,9 +166,19 @@ pub struct VstPluginInfo {
     pub name: String,
     pub dll_name: String,
     pub installed: bool,
+    pub state: PluginState,
     pub full_path: String,
 }
 
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VstRepairResult {
+    pub repaired: Vec<String>,
+    /// "Plugin: reason" for each plugin that's still broken.
+    pub failed: Vec<String>,
+    pub status: VstStatus,
+}
+
 fn vst_install_dir() -> PathBuf {
     dirs::data_local_dir()
         .unwrap_or_else(|| PathBuf::from("."))
@@ -137,23 +186,172 @@ fn vst_install_dir() -> PathBuf {
         .join("vst")
 }
 
+/// Hashes recorded for downloaded plugins that have no pinned hash.
+fn manifest_path() -> PathBuf {
+    vst_install_dir().join("manifest.json")
+}
+
+fn user_registry_path() -> PathBuf {
+    vst_install_dir().join("user_plugins.json")
+}
+
+fn load_user_vsts() -> Vec<UserVst> {
+    let Ok(content) = fs::read_to_string(user_registry_path()) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("Failed to parse user VST registry: {}", e);
+        Vec::new()
+    })
+}
+
+fn collect_dlls(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            if depth < MAX_SCAN_DEPTH {
+                collect_dlls(&path, depth + 1, out);
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("dll"))
+        {
+            out.push(path);
+        }
+    }
+}
+
+/// Walks the standard VST2 folders and `extra_dirs`, and saves what it
+/// finds as the user plugin registry. DLLs named like a catalog plugin are
+/// skipped so {vst:Name} keeps resolving to Airwindows; among the rest the
+/// first folder searched wins, comparing DLL names case-insensitively.
+pub fn scan_user_vsts(extra_dirs: &[String]) -> Result<Vec<UserVst>, String> {
+    let dirs = STANDARD_VST2_DIRS
+        .iter()
+        .map(PathBuf::from)
+        .chain(extra_dirs.iter().map(PathBuf::from));
+    let mut found: Vec<UserVst> = Vec::new();
+    for dir in dirs {
+        let mut dlls = Vec::new();
+        collect_dlls(&dir, 0, &mut dlls);
+        dlls.sort();
+        for path in dlls {
+            let Some(dll_name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let taken = VST_CATALOG.iter().any(|e| e.dll_name.eq_ignore_ascii_case(&dll_name))
+                || found.iter().any(|u| u.dll_name.eq_ignore_ascii_case(&dll_name));
+            if taken {
+                continue;
+            }
+            found.push(UserVst {
+                name: dll_name[..dll_name.len() - ".dll".len()].to_string(),
+                size_bytes: fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                path: path.to_string_lossy().to_string(),
+                dll_name,
+            });
+        }
+    }
+    found.sort_by_key(|u| u.name.to_lowercase());
+
+    fs::create_dir_all(vst_install_dir())
+        .map_err(|e| format!("Failed to create VST directory: {}", e))?;
+    let json = serde_json::to_string_pretty(&found)
+        .map_err(|e| format!("Failed to serialize user VSTs: {}", e))?;
+    fs::write(user_registry_path(), json).map_err(|e| format!("Failed to save user VSTs: {}", e))?;
+    log::info!("VST scan found {} user plugins", found.len());
+    Ok(found)
+}
+
+fn load_manifest() -> HashMap<String, String> {
+    let Ok(content) = fs::read_to_string(manifest_path()) else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log::warn!("Failed to parse VST manifest: {}", e);
+        HashMap::new()
+    })
+}
+
+fn record_hash(dll_name: &str, hash: &str) {
+    let mut manifest = load_manifest();
+    manifest.insert(dll_name.to_string(), hash.to_string());
+    let result = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(manifest_path(), json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to save VST manifest: {}", e);
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Whether `bytes` look like a 64-bit Windows DLL: an MZ stub pointing at a
+/// PE header for x64, as OBS is 64-bit only.
+fn is_x64_pe(bytes: &[u8]) -> bool {
+    const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+    if bytes.len() < 0x40 || !bytes.starts_with(b"MZ") {
+        return false;
+    }
+    let pe_offset = u32::from_le_bytes([bytes[0x3C], bytes[0x3D], bytes[0x3E], bytes[0x3F]]) as usize;
+    let Some(header) = bytes.get(pe_offset..pe_offset.saturating_add(6)) else {
+        return false;
+    };
+    header.starts_with(b"PE\0\0") && u16::from_le_bytes([header[4], header[5]]) == IMAGE_FILE_MACHINE_AMD64
+}
+
+fn catalog_entry(dll_name: &str) -> Option<&'static VstCatalogEntry> {
+    VST_CATALOG.iter().find(|e| e.dll_name == dll_name)
+}
+
+fn expected_hash(dll_name: &str, manifest: &HashMap<String, String>) -> Option<String> {
+    catalog_entry(dll_name)
+        .and_then(|e| e.sha256)
+        .map(String::from)
+        .or_else(|| manifest.get(dll_name).cloned())
+}
+
+/// A DLL with no known hash (placed by hand, say) counts as valid.
+fn file_state(path: &Path, expected: Option<&str>) -> PluginState {
+    let Ok(bytes) = fs::read(path) else {
+        return PluginState::Missing;
+    };
+    match expected {
+        Some(hash) if !sha256_hex(&bytes).eq_ignore_ascii_case(hash) => PluginState::Corrupt,
+        _ => PluginState::Valid,
+    }
+}
+
+/// The state of an installed plugin by catalog name.
+pub fn plugin_state(plugin_name: &str) -> PluginState {
+    let dll_name = format!("{}.dll", plugin_name);
+    let expected = expected_hash(&dll_name, &load_manifest());
+    file_state(&vst_install_dir().join(&dll_name), expected.as_deref())
+}
+
 pub fn get_vst_status() -> VstStatus {
     let install_dir = vst_install_dir();
+    let manifest = load_manifest();
     let plugins: Vec<VstPluginInfo> = BUNDLED_VSTS
         .iter()
         .map(|dll| {
             let full_path = install_dir.join(dll);
-            let installed = full_path.exists();
+            let state = file_state(&full_path, expected_hash(dll, &manifest).as_deref());
             VstPluginInfo {
                 name: dll.trim_end_matches(".dll").to_string(),
                 dll_name: dll.to_string(),
-                installed,
+                installed: state != PluginState::Missing,
+                state,
                 full_path: full_path.to_string_lossy().to_string(),
             }
         })
         .collect();
 
-    let all_installed = plugins.iter().all(|p| p.installed);
+    let all_installed = plugins.iter().all(|p| p.state == PluginState::Valid);
 
     VstStatus {
         installed: all_installed,
@@ -175,27 +373,12 @@ pub fn install_vsts(app_handle: &tauri::AppHandle) -> Result<VstStatus, String>
     let vst_resource_dir = resource_dir.join("resources").join("vst");
 
     for dll in BUNDLED_VSTS {
-        let src = vst_resource_dir.join(dll);
         let dst = install_dir.join(dll);
-
-        if !src.exists() {
-            log::warn!("VST resource not found: {}", src.display());
+        let expected = catalog_entry(dll).and_then(|e| e.sha256);
+        if file_state(&dst, expected) == PluginState::Valid {
             continue;
         }
-
-        let should_copy = if dst.exists() {
-            let src_len = fs::metadata(&src).map(|m| m.len()).unwrap_or(0);
-            let dst_len = fs::metadata(&dst).map(|m| m.len()).unwrap_or(0);
-            src_len != dst_len
-        } else {
-            true
-        };
-
-        if should_copy {
-            fs::copy(&src, &dst)
-                .map_err(|e| format!("Failed to copy {}: {}", dll, e))?;
-            log::info!("Installed VST: {} -> {}", dll, dst.display());
-        }
+        copy_bundled(&vst_resource_dir, &install_dir, dll)?;
     }
 
     // Also copy the license file
@@ -208,36 +391,139 @@ pub fn install_vsts(app_handle: &tauri::AppHandle) -> Result<VstStatus, String>
     Ok(get_vst_status())
 }
 
-pub fn get_vst_path(plugin_name: &str) -> Option<String> {
+/// Copies a bundled DLL into place, refusing a resource that doesn't match
+/// its pinned hash.
+fn copy_bundled(vst_resource_dir: &Path, install_dir: &Path, dll: &str) -> Result<(), String> {
+    let src = vst_resource_dir.join(dll);
+    let expected = catalog_entry(dll).and_then(|e| e.sha256);
+    match file_state(&src, expected) {
+        PluginState::Valid => {}
+        PluginState::Missing => {
+            log::warn!("VST resource not found: {}", src.display());
+            return Ok(());
+        }
+        PluginState::Corrupt => return Err(format!("Bundled {} failed verification", dll)),
+    }
+    let dst = install_dir.join(dll);
+    fs::copy(&src, &dst).map_err(|e| format!("Failed to copy {}: {}", dll, e))?;
+    log::info!("Installed VST: {} -> {}", dll, dst.display());
+    Ok(())
+}
+
+/// Re-copies bundled plugins and re-downloads catalog plugins that are
+/// missing or fail verification. Downloaded plugins that were never
+/// installed are left alone.
+pub async fn repair_vsts(app_handle: &tauri::AppHandle) -> Result<VstRepairResult, String> {
     let install_dir = vst_install_dir();
+    fs::create_dir_all(&install_dir)
+        .map_err(|e| format!("Failed to create VST directory: {}", e))?;
+    let vst_resource_dir = app_handle
+        .path()
+        .resource_dir()
+        .map_err(|e| format!("Failed to get resource dir: {}", e))?
+        .join("resources")
+        .join("vst");
+
+    let manifest = load_manifest();
+    let mut repaired = Vec::new();
+    let mut failed = Vec::new();
+    for entry in VST_CATALOG {
+        let bundled = BUNDLED_VSTS.contains(&entry.dll_name);
+        let dst = install_dir.join(entry.dll_name);
+        let state = file_state(&dst, expected_hash(entry.dll_name, &manifest).as_deref());
+        if state == PluginState::Valid || (state == PluginState::Missing && !bundled) {
+            continue;
+        }
+        let result = if bundled {
+            copy_bundled(&vst_resource_dir, &install_dir, entry.dll_name)
+        } else {
+            let _ = fs::remove_file(&dst);
+            download_and_install_vst(entry.name).await.map(|_| ())
+        };
+        match result {
+            Ok(()) => repaired.push(entry.name.to_string()),
+            Err(e) => {
+                log::warn!("VST repair failed for {}: {}", entry.name, e);
+                failed.push(format!("{}: {}", entry.name, e));
+            }
+        }
+    }
+
+    Ok(VstRepairResult {
+        repaired,
+        failed,
+        status: get_vst_status(),
+    })
+}
+
+/// Catalog plugins resolve to the install folder, and only when they pass
+/// verification; other names are looked up among the scanned user plugins.
+pub fn get_vst_path(plugin_name: &str) -> Option<String> {
     let dll_name = format!("{}.dll", plugin_name);
-    let full_path = install_dir.join(&dll_name);
-    if full_path.exists() {
-        Some(full_path.to_string_lossy().to_string())
-    } else {
-        None
+    if VST_CATALOG.iter().any(|e| e.dll_name.eq_ignore_ascii_case(&dll_name)) {
+        if plugin_state(plugin_name) != PluginState::Valid {
+            return None;
+        }
+        let full_path = vst_install_dir().join(dll_name);
+        return Some(full_path.to_string_lossy().to_string());
     }
+    load_user_vsts()
+        .into_iter()
+        .find(|u| u.dll_name.eq_ignore_ascii_case(&dll_name))
+        .filter(|u| Path::new(&u.path).is_file())
+        .map(|u| u.path)
+}
+
+/// The catalog name of the plugin a `plugin_path` points at, matched on the
+/// DLL file name so it works wherever the plugin was installed.
+pub fn catalog_name_for_path(path: &str) -> Option<&'static str> {
+    let file_name = path.rsplit(['/', '\\']).next()?;
+    VST_CATALOG
+        .iter()
+        .find(|e| e.dll_name.eq_ignore_ascii_case(file_name))
+        .map(|e| e.name)
 }
 
 pub fn get_vst_catalog() -> Vec<VstCatalogWithStatus> {
     let install_dir = vst_install_dir();
+    let manifest = load_manifest();
     let bundled_set: std::collections::HashSet<&str> = BUNDLED_VSTS.iter().copied().collect();
 
-    VST_CATALOG
+    let catalog = VST_CATALOG
         .iter()
         .map(|entry| {
             let full_path = install_dir.join(entry.dll_name);
+            let state = file_state(&full_path, expected_hash(entry.dll_name, &manifest).as_deref());
+            let bundled = bundled_set.contains(entry.dll_name);
             VstCatalogWithStatus {
                 name: entry.name.to_string(),
                 dll_name: entry.dll_name.to_string(),
                 description: entry.description.to_string(),
                 category: entry.category.to_string(),
                 size_bytes: entry.size_bytes,
-                installed: full_path.exists(),
-                bundled: bundled_set.contains(entry.dll_name),
+                installed: state != PluginState::Missing,
+                state,
+                bundled,
+                source: if bundled { "bundled" } else { "catalog" }.to_string(),
+                full_path: None,
             }
-        })
-        .collect()
+        });
+    let user = load_user_vsts().into_iter().map(|u| {
+        let exists = Path::new(&u.path).is_file();
+        VstCatalogWithStatus {
+            name: u.name,
+            dll_name: u.dll_name,
+            description: String::new(),
+            category: "User".to_string(),
+            size_bytes: u.size_bytes,
+            installed: exists,
+            state: if exists { PluginState::Valid } else { PluginState::Missing },
+            bundled: false,
+            source: "user".to_string(),
+            full_path: Some(u.path),
+        }
+    });
+    catalog.chain(user).collect()
 }
 
 pub async fn download_and_install_vst(name: &str) -> Result<VstPluginInfo, String> {
@@ -251,13 +537,18 @@ pub async fn download_and_install_vst(name: &str) -> Result<VstPluginInfo, Strin
         .map_err(|e| format!("Failed to create VST directory: {}", e))?;
 
     let dst = install_dir.join(entry.dll_name);
-    if dst.exists() {
-        return Ok(VstPluginInfo {
-            name: entry.name.to_string(),
-            dll_name: entry.dll_name.to_string(),
-            installed: true,
-            full_path: dst.to_string_lossy().to_string(),
-        });
+    match plugin_state(entry.name) {
+        PluginState::Valid => {
+            return Ok(VstPluginInfo {
+                name: entry.name.to_string(),
+                dll_name: entry.dll_name.to_string(),
+                installed: true,
+                state: PluginState::Valid,
+                full_path: dst.to_string_lossy().to_string(),
+            });
+        }
+        PluginState::Corrupt => log::warn!("Replacing corrupt VST: {}", entry.name),
+        PluginState::Missing => {}
     }
 
     let url = format!("{}/{}", VST_DOWNLOAD_BASE, entry.dll_name);
@@ -274,6 +565,7 @@ pub async fn download_and_install_vst(name: &str) -> Result<VstPluginInfo, Strin
         ));
     }
 
+    let expected_len = response.content_length();
     let bytes = response
         .bytes()
         .await
@@ -282,6 +574,28 @@ pub async fn download_and_install_vst(name: &str) -> Result<VstPluginInfo, Strin
     if bytes.is_empty() {
         return Err("Downloaded file is empty".to_string());
     }
+    // A cut-off transfer or an error page served with 200 must never be
+    // installed, and for unpinned plugins never have its hash trusted.
+    if let Some(len) = expected_len {
+        if bytes.len() as u64 != len {
+            return Err(format!(
+                "Download of {} was incomplete ({} of {} bytes)",
+                entry.name,
+                bytes.len(),
+                len
+            ));
+        }
+    }
+    if !is_x64_pe(&bytes) {
+        return Err(format!("Downloaded {} is not a 64-bit Windows plugin", entry.name));
+    }
+
+    let hash = sha256_hex(&bytes);
+    if let Some(expected) = entry.sha256 {
+        if !hash.eq_ignore_ascii_case(expected) {
+            return Err(format!("Downloaded {} failed verification", entry.name));
+        }
+    }
 
     let tmp_path = install_dir.join(format!("{}.tmp", entry.dll_name));
     fs::write(&tmp_path, &bytes)
@@ -290,6 +604,10 @@ pub async fn download_and_install_vst(name: &str) -> Result<VstPluginInfo, Strin
         let _ = fs::remove_file(&tmp_path);
         format!("Failed to install plugin: {}", e)
     })?;
+    if entry.sha256.is_none() {
+        // No pin: remember this hash so later corruption is caught.
+        record_hash(entry.dll_name, &hash);
+    }
 
     log::info!(
         "Installed VST: {} ({} bytes) -> {}",
@@ -302,6 +620,7 @@ pub async fn download_and_install_vst(name: &str) -> Result<VstPluginInfo, Strin
         name: entry.name.to_string(),
         dll_name: entry.dll_name.to_string(),
         installed: true,
+        state: PluginState::Valid,
         full_path: dst.to_string_lossy().to_string(),
     })
 }
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::Emitter;
+use tokio::sync::Mutex;
+
+pub type SharedWsLog = Arc<Mutex<WsLog>>;
+
+const DEFAULT_MAX_ENTRIES: usize = 2000;
+const DEFAULT_PAYLOAD_LIMIT: usize = 4096;
+/// Field names are lowercased and stripped of '_' and '-' before matching,
+/// so streamKey, stream_key and STREAM-KEY are all caught.
+const SECRET_KEY_PARTS: &[&str] = &["password", "passwd", "secret", "token", "auth"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WsLogEntry {
+    pub timestamp_ms: u64,
+    pub direction: String,
+    pub op: u64,
+    pub name: String,
+    pub request_id: Option<String>,
+    pub success: Option<bool>,
+    pub latency_ms: Option<u64>,
+    pub truncated: bool,
+    pub data: Value,
+}
+
+pub struct WsLog {
+    enabled: bool,
+    max_entries: usize,
+    payload_limit: usize,
+    entries: VecDeque<WsLogEntry>,
+    in_flight: HashMap<String, Instant>,
+    app_handle: Option<tauri::AppHandle>,
+}
+
+impl WsLog {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            max_entries: DEFAULT_MAX_ENTRIES,
+            payload_limit: DEFAULT_PAYLOAD_LIMIT,
+            entries: VecDeque::new(),
+            in_flight: HashMap::new(),
+            app_handle: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn configure(&mut self, enabled: bool, max_entries: Option<usize>, payload_limit: Option<usize>) {
+        self.enabled = enabled;
+        if let Some(max) = max_entries {
+            self.max_entries = max.max(1);
+        }
+        if let Some(limit) = payload_limit {
+            self.payload_limit = limit.max(64);
+        }
+        if !enabled {
+            self.in_flight.clear();
+        }
+        while self.entries.len() > self.max_entries {
+            self.entries.pop_front();
+        }
+    }
+
+    pub fn set_app_handle(&mut self, app_handle: tauri::AppHandle) {
+        self.app_handle = Some(app_handle);
+    }
+
+    pub fn entries(&self) -> Vec<WsLogEntry> {
+        self.entries.iter().cloned().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.in_flight.clear();
+    }
+
+    fn log_outgoing(&mut self, message: &Value) -> Option<WsLogEntry> {
+        if !self.enabled {
+            return None;
+        }
+        let op = message["op"].as_u64().unwrap_or(0);
+        let d = &message["d"];
+        let request_id = d["requestId"].as_str().map(String::from);
+        if let Some(id) = &request_id {
+            self.in_flight.insert(id.clone(), Instant::now());
+        }
+        let name = match op {
+            1 => "Identify".to_string(),
+            _ => d["requestType"].as_str().unwrap_or("").to_string(),
+        };
+        Some(self.push(op, "out", name, request_id, None, None, d))
+    }
+
+    fn log_incoming(&mut self, message: &Value) -> Option<WsLogEntry> {
+        if !self.enabled {
+            return None;
+        }
+        let op = message["op"].as_u64().unwrap_or(0);
+        let d = &message["d"];
+        let entry = match op {
+            5 => {
+                let name = d["eventType"].as_str().unwrap_or("").to_string();
+                self.push(op, "event", name, None, None, None, d)
+            }
+            7 => {
+                let request_id = d["requestId"].as_str().map(String::from);
+                let latency_ms = request_id
+                    .as_ref()
+                    .and_then(|id| self.in_flight.remove(id))
+                    .map(|start| start.elapsed().as_millis() as u64);
+                let success = d["requestStatus"]["result"].as_bool();
+                let name = d["requestType"].as_str().unwrap_or("").to_string();
+                self.push(op, "in", name, request_id, success, latency_ms, d)
+            }
+            _ => {
+                let name = match op {
+                    0 => "Hello",
+                    2 => "Identified",
+                    _ => "",
+                };
+                self.push(op, "in", name.to_string(), None, None, None, d)
+            }
+        };
+        Some(entry)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn push(
+        &mut self,
+        op: u64,
+        direction: &str,
+        name: String,
+        request_id: Option<String>,
+        success: Option<bool>,
+        latency_ms: Option<u64>,
+        data: &Value,
+    ) -> WsLogEntry {
+        let mut data = data.clone();
+        redact(&mut data);
+        let serialized = data.to_string();
+        let truncated = serialized.len() > self.payload_limit;
+        if truncated {
+            let mut end = self.payload_limit;
+            while !serialized.is_char_boundary(end) {
+                end -= 1;
+            }
+            data = Value::String(serialized[..end].to_string());
+        }
+
+        let entry = WsLogEntry {
+            timestamp_ms: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            direction: direction.to_string(),
+            op,
+            name,
+            request_id,
+            success,
+            latency_ms,
+            truncated,
+            data,
+        };
+
+        self.entries.push_back(entry.clone());
+        while self.entries.len() > self.max_entries {
+            self.entries.pop_front();
+        }
+        entry
+    }
+
+    pub fn to_replay(&self) -> Value {
+        let start = self.entries.front().map(|e| e.timestamp_ms).unwrap_or(0);
+        let messages: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|e| {
+                json!({
+                    "offsetMs": e.timestamp_ms.saturating_sub(start),
+                    "direction": if e.direction == "out" { "client" } else { "server" },
+                    "truncated": e.truncated,
+                    "message": {"op": e.op, "d": e.data},
+                })
+            })
+            .collect();
+        json!({
+            "format": "observe-ws-replay",
+            "version": 1,
+            "recordedAt": start,
+            "messages": messages,
+        })
+    }
+}
+
+/// Records an outgoing message and emits it to the UI once the log is
+/// unlocked again.
+pub async fn log_outgoing(log: &SharedWsLog, message: &Value) {
+    let recorded = {
+        let mut log = log.lock().await;
+        log.log_outgoing(message).zip(log.app_handle.clone())
+    };
+    emit(recorded);
+}
+
+pub async fn log_incoming(log: &SharedWsLog, message: &Value) {
+    let recorded = {
+        let mut log = log.lock().await;
+        log.log_incoming(message).zip(log.app_handle.clone())
+    };
+    emit(recorded);
+}
+
+fn emit(recorded: Option<(WsLogEntry, tauri::AppHandle)>) {
+    if let Some((entry, app)) = recorded {
+        let _ = app.emit("obs://ws-log-entry", &entry);
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key: String = key
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
+    key.ends_with("key") || SECRET_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if is_secret_key(k) && !v.is_null() && !v.is_boolean() {
+                    *v = json!("[redacted]");
+                } else {
+                    redact(v);
+                }
+            }
+        }
+        Value::Array(arr) => {
+            for v in arr.iter_mut() {
+                redact(v);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_secret_fields_at_any_depth() {
+        let mut data = json!({
+            "requestType": "SetStreamServiceSettings",
+            "requestData": {
+                "streamServiceSettings": {
+                    "server": "rtmp://live.example.com/app",
+                    "key": "live_123",
+                    "bearer_token": "abc",
+                    "password": "hunter2",
+                    "use_auth": true,
+                    "username": "me"
+                }
+            },
+            "authentication": "c2VjcmV0",
+            "outputs": [{"streamKey": "live_456", "accessToken": "t", "X-Api-Key": "k"}]
+        });
+        redact(&mut data);
+        let settings = &data["requestData"]["streamServiceSettings"];
+        assert_eq!(settings["key"], "[redacted]");
+        assert_eq!(settings["bearer_token"], "[redacted]");
+        assert_eq!(settings["password"], "[redacted]");
+        assert_eq!(data["authentication"], "[redacted]");
+        assert_eq!(data["outputs"][0]["streamKey"], "[redacted]");
+        assert_eq!(data["outputs"][0]["accessToken"], "[redacted]");
+        assert_eq!(data["outputs"][0]["X-Api-Key"], "[redacted]");
+        // Flags and ordinary fields stay readable.
+        assert_eq!(settings["use_auth"], true);
+        assert_eq!(settings["username"], "me");
+        assert_eq!(settings["server"], "rtmp://live.example.com/app");
+    }
+
+    #[test]
+    fn leaves_ordinary_fields_alone() {
+        for key in ["inputName", "inputKind", "hotkeyName", "keyModifiers", "sceneItemId"] {
+            assert!(!is_secret_key(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn records_redacted_entries_with_latency() {
+        let mut log = WsLog::new();
+        log.configure(true, Some(10), None);
+        let sent = log
+            .log_outgoing(&json!({"op": 6, "d": {"requestType": "GetVersion", "requestId": "1", "requestData": {"password": "x"}}}))
+            .unwrap();
+        assert_eq!(sent.name, "GetVersion");
+        assert_eq!(sent.data["requestData"]["password"], "[redacted]");
+        let received = log
+            .log_incoming(&json!({"op": 7, "d": {"requestType": "GetVersion", "requestId": "1", "requestStatus": {"result": true}}}))
+            .unwrap();
+        assert_eq!(received.success, Some(true));
+        assert!(received.latency_ms.is_some());
+        assert_eq!(log.entries().len(), 2);
+    }
+
+    #[test]
+    fn records_nothing_while_disabled() {
+        let mut log = WsLog::new();
+        assert!(log.log_outgoing(&json!({"op": 6, "d": {}})).is_none());
+        assert!(log.entries().is_empty());
+    }
+}
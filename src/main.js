@@ -342,6 +342,12 @@ function setupEventListeners() {
     renderActiveCaptures();
   });
 
+  listen('obs://input-loaded', (e) => {
+    if (!obsState) return;
+    obsState.inputs[e.payload.name] = e.payload;
+    renderAudioMixer();
+  });
+
   listen('obs://input-removed', (e) => {
     if (obsState) delete obsState.inputs[e.payload.inputName];
     renderAudioMixer();
@@ -385,6 +391,11 @@ function setupEventListeners() {
     setDisconnectedUI();
   });
 
+  listen('obs://reconnect-failed', (ev) => {
+    const badge = $('#connection-badge');
+    if (badge) badge.title = ev?.payload?.reason || '';
+  });
+
   listen('obs://filters-changed', (ev) => {
     scLog('obs://filters-changed event received:', ev?.payload);
     refreshFullState();
@@ -414,6 +425,25 @@ function setupEventListeners() {
     showFrameDropAlert(`Dropped frames: ${parts.join(', ')} in last 5s`);
   });
 
+  // A dangerous finish action is never run unattended; offer it here.
+  listen('timer://finished', (e) => {
+    const { name, actionResults } = e.payload;
+    for (const result of actionResults || []) {
+      if (result.status === 'pending_confirmation' && result.pendingAction) {
+        showToastWithAction(`Timer "${name}" finished: ${result.description}`, 'Run', async () => {
+          try {
+            const res = await invoke('confirm_dangerous_action', { action: result.pendingAction });
+            if (res.status !== 'executed') showFrameDropAlert('Action failed: ' + (res.error || res.status));
+          } catch (err) {
+            showFrameDropAlert('Action failed: ' + err);
+          }
+        });
+      } else if (result.status !== 'executed') {
+        showFrameDropAlert(`Timer "${name}": ${result.description} ${result.status}` + (result.error ? ': ' + result.error : ''));
+      }
+    }
+  });
+
   listen('audio://peak-levels', (e) => {
     const { levels } = e.payload;
     for (const { deviceId, peak } of levels) {
@@ -478,6 +508,17 @@ function setupEventListeners() {
   listen('voice://ptt-stop', () => {
     if (pttActive) { pttActive = false; stopListening(); }
   });
+
+  // First close with no saved preference: ask whether to hide or quit.
+  listen('app://close-requested', async () => {
+    const toTray = confirm('Keep OBServe running in the system tray?\n\nOK hides the window to the tray. Cancel quits OBServe.');
+    const remember = confirm('Remember this choice? You can change it later in Settings.');
+    try {
+      await invoke('resolve_close_request', { action: toTray ? 'tray' : 'quit', remember });
+    } catch (e) {
+      scErr('resolve_close_request failed:', e);
+    }
+  });
 }
 
 async function refreshFullState() {
@@ -578,18 +619,21 @@ async function refreshSceneThumbnails(force) {
   if (!obsState || !isConnected) return;
   const panel = $('#scenes-panel');
   if (panel && panel.hidden) return;
-  const scenes = obsState.scenes || [];
+  let shots;
+  try {
+    shots = await invoke('get_all_scene_screenshots', { width: 160, height: 90, maxAgeMs: force ? 0 : null });
+  } catch (e) {
+    console.warn('[Thumbnail] Screenshots failed:', e);
+    return;
+  }
 
-  for (const s of scenes) {
-    const thumbId = 'scene-thumb-' + s.name.replace(/[^a-zA-Z0-9]/g, '_');
+  for (const shot of shots) {
+    const thumbId = 'scene-thumb-' + shot.sceneName.replace(/[^a-zA-Z0-9]/g, '_');
     const img = document.getElementById(thumbId);
     if (!img) continue;
-    try {
-      const dataUri = await invoke('get_scene_screenshot', { sceneName: s.name, width: 160, height: 90 });
-      if (dataUri) img.src = dataUri;
-    } catch (e) {
-      console.warn('[Thumbnail] Screenshot failed for scene "' + s.name + '":', e);
-    }
+    if (shot.imageData) img.src = shot.imageData;
+    img.classList.toggle('stale', !!shot.error);
+    if (shot.error) console.warn('[Thumbnail] Screenshot failed for scene "' + shot.sceneName + '":', shot.error);
   }
 }
 
@@ -1501,7 +1545,7 @@ function esc(str) {
 // --- Settings Persistence ---
 
 const SETTINGS_KEY = 'observe-settings';
-const DEFAULTS = { host: 'localhost', port: 4455, password: '', autoLaunchObs: false, geminiApiKey: '', enableVoiceInput: true };
+const DEFAULTS = { host: 'localhost', port: 4455, password: '', autoLaunchObs: false, enableVoiceInput: true };
 
 function loadSettings() {
   try {
@@ -1515,12 +1559,30 @@ function saveSettings(settings) {
   localStorage.setItem(SETTINGS_KEY, JSON.stringify(settings));
 }
 
+// Older versions kept the Gemini key in localStorage. Hand it to the
+// backend's key store once, then remove it.
+async function migrateLegacyApiKey() {
+  const raw = loadSettings();
+  if (!('geminiApiKey' in raw)) return;
+  const { geminiApiKey, ...rest } = raw;
+  try {
+    // Don't replace a provider the backend already has configured.
+    const status = await invoke('check_ai_status');
+    if (geminiApiKey && !status.ready) {
+      await invoke('set_ai_provider', { provider: 'gemini', apiKey: geminiApiKey });
+    }
+  } catch (e) {
+    console.warn('Gemini key migration failed, will retry next launch:', e);
+    return;
+  }
+  saveSettings(rest);
+}
+
 function populateSettingsForm(settings) {
   $('#obs-host').value = settings.host;
   $('#obs-port').value = settings.port;
   $('#obs-password').value = settings.password;
   $('#auto-launch-obs').checked = settings.autoLaunchObs;
-  $('#gemini-api-key').value = settings.geminiApiKey || '';
   $('#enable-voice-input').checked = settings.enableVoiceInput !== false;
 }
 
@@ -1531,6 +1593,7 @@ function setConnectedUI(status) {
   const badge = $('#connection-badge');
   badge.textContent = 'Connected';
   badge.className = 'badge connected';
+  badge.title = '';
   const led = $('#connection-led');
   if (led) led.className = 'led led-green';
   $('#btn-connect').disabled = true;
@@ -3456,10 +3519,33 @@ $('#btn-disconnect').addEventListener('click', async () => {
   setDisconnectedUI();
 });
 
+// The provider, model and base URL as last saved, to tell whether Save
+// changed them.
+let savedAiConfig = null;
+
+async function loadAiSettingsForm() {
+  try {
+    const { ai } = await invoke('get_settings');
+    savedAiConfig = { provider: ai.provider, model: ai.model, baseUrl: ai.baseUrl };
+    $('#ai-provider').value = ai.provider;
+    $('#ai-model').value = ai.model;
+    $('#ai-base-url').value = ai.baseUrl;
+    $('#ai-base-url-row').hidden = ai.provider !== 'openai';
+  } catch (_) {}
+}
+
 $('#btn-settings').addEventListener('click', (e) => {
   e.stopPropagation();
   $('#hamburger-dropdown').classList.remove('open');
-  $('#settings-dropdown').classList.toggle('open');
+  const open = $('#settings-dropdown').classList.toggle('open');
+  if (open) loadAiSettingsForm();
+});
+
+$('#ai-provider').addEventListener('change', () => {
+  const provider = $('#ai-provider').value;
+  $('#ai-base-url-row').hidden = provider !== 'openai';
+  // Model names don't carry over between providers.
+  if (savedAiConfig && provider !== savedAiConfig.provider) $('#ai-model').value = '';
 });
 
 $('#btn-hamburger').addEventListener('click', (e) => {
@@ -3494,17 +3580,28 @@ $('#btn-save-settings').addEventListener('click', async () => {
     port: parseInt($('#obs-port').value) || 4455,
     password: $('#obs-password').value,
     autoLaunchObs: $('#auto-launch-obs').checked,
-    geminiApiKey: newKey,
     enableVoiceInput: $('#enable-voice-input').checked,
   };
   saveSettings(settings);
   $('#settings-dropdown').classList.remove('open');
 
-  if (newKey) {
+  const provider = $('#ai-provider').value;
+  const model = $('#ai-model').value.trim();
+  const baseUrl = $('#ai-base-url').value.trim();
+  const aiChanged = !savedAiConfig
+    || provider !== savedAiConfig.provider
+    || model !== savedAiConfig.model
+    || baseUrl !== savedAiConfig.baseUrl;
+  if (newKey || aiChanged) {
     try {
-      await invoke('set_gemini_api_key', { apiKey: newKey });
+      await invoke('set_ai_provider', { provider, apiKey: newKey || null, baseUrl, model });
+      savedAiConfig = { provider, model, baseUrl };
+      // The backend keeps the key now; don't leave it in the form.
+      $('#gemini-api-key').value = '';
       await checkAiReady();
-    } catch (_) {}
+    } catch (e) {
+      showToast('AI provider not saved: ' + e, 'error');
+    }
   }
 });
 
@@ -3907,7 +4004,7 @@ let aiReady = false;
 
 async function checkAiReady() {
   try {
-    aiReady = await invoke('check_ai_status');
+    aiReady = (await invoke('check_ai_status')).ready;
   } catch (_) {
     aiReady = false;
   }
@@ -4248,7 +4345,7 @@ async function ensurePresetsLoaded() {
   try {
     cachedPresets = await invoke('get_smart_presets');
   } catch (e) {
-    if (String(e).includes('not purchased')) {
+    if (!isModuleOwned('presets')) {
       cachedPresets = [];
     } else {
       showFrameDropAlert('Failed to load presets: ' + e);
@@ -4766,6 +4863,7 @@ const PANEL_MODULE_MAP = {
 };
 
 function isModuleOwned(moduleId) {
+  if ((licenseState.trials || []).some(t => t.module_id === moduleId && t.state === 'active')) return true;
   return licenseState.owned_modules &&
     (Array.isArray(licenseState.owned_modules)
       ? licenseState.owned_modules.includes(moduleId)
@@ -11386,14 +11484,44 @@ async function veToggleFileBrowser() {
   if (ve.fileBrowserOpen) await veRefreshFiles();
 }
 
+// Thumbnail elements waiting on video-editor://thumbnail-ready, keyed by
+// path and timestamp.
+const veThumbTargets = new Map();
+const VE_BROWSER_THUMB_TS = 2.0;
+
+function veShowThumb(thumb, src) {
+  thumb.innerHTML = '';
+  const img = document.createElement('img');
+  img.src = src;
+  thumb.appendChild(img);
+}
+
+listen('video-editor://thumbnail-ready', (event) => {
+  const { path, timestamp, thumbnail } = event.payload;
+  const key = `${path}|${timestamp}`;
+  const thumb = veThumbTargets.get(key);
+  if (!thumb) return;
+  veThumbTargets.delete(key);
+  if (thumbnail) {
+    veShowThumb(thumb, thumbnail);
+  } else {
+    thumb.innerHTML = '<span class="ve-thumb-placeholder">No preview</span>';
+  }
+});
+
 async function veRefreshFiles() {
   const list = $('#ve-file-list');
   if (!list) return;
   list.innerHTML = '<div class="ve-hint">Loading...</div>';
 
+  // Thumbnails still queued for the old list are no longer wanted.
+  veThumbTargets.clear();
+  invoke('cancel_thumbnail_requests').catch(() => {});
+
   try {
     const files = await invoke('list_recordings', { dir: null });
     list.innerHTML = '';
+    const batch = [];
     if (files.length === 0) {
       list.innerHTML = '<div class="ve-hint">No recordings found</div>';
       return;
@@ -11428,26 +11556,32 @@ async function veRefreshFiles() {
       });
       list.appendChild(card);
 
+      if (ve.ffmpegReady) {
+        // Rendered by the backend's worker pool, in list order.
+        veThumbTargets.set(`${file.path}|${VE_BROWSER_THUMB_TS}`, thumb);
+        batch.push([file.path, VE_BROWSER_THUMB_TS]);
+        continue;
+      }
       (async () => {
         try {
-          if (ve.ffmpegReady) {
-            const thumbData = await invoke('get_video_thumbnail', { path: file.path, timestamp: 2.0 });
-            thumb.innerHTML = '';
-            const img = document.createElement('img');
-            img.src = thumbData;
-            thumb.appendChild(img);
-          } else {
-            const dataUrl = await veGenerateThumbnailHtml5(file.path);
-            thumb.innerHTML = '';
-            const img = document.createElement('img');
-            img.src = dataUrl;
-            thumb.appendChild(img);
-          }
+          veShowThumb(thumb, await veGenerateThumbnailHtml5(file.path));
         } catch (_) {
           thumb.innerHTML = '<span class="ve-thumb-placeholder">No preview</span>';
         }
       })();
     }
+    if (batch.length > 0) {
+      try {
+        await invoke('request_thumbnails', { batch });
+      } catch (_) {
+        for (const [path] of batch) {
+          const key = `${path}|${VE_BROWSER_THUMB_TS}`;
+          const thumb = veThumbTargets.get(key);
+          veThumbTargets.delete(key);
+          if (thumb) thumb.innerHTML = '<span class="ve-thumb-placeholder">No preview</span>';
+        }
+      }
+    }
   } catch (e) {
     list.innerHTML = `<div class="ve-hint">Error: ${e}</div>`;
   }
@@ -11491,7 +11625,7 @@ function veShowExportModal() {
 async function veExportBrowse() {
   const format = $('#export-format').value || 'mp4';
   const srcName = ve.sourcePath.split(/[/\\]/).pop().replace(/\.[^.]+$/, '');
-  const filterMap = { mp4: 'MP4 Video (*.mp4)|*.mp4', mkv: 'MKV Video (*.mkv)|*.mkv', mov: 'MOV Video (*.mov)|*.mov' };
+  const filterMap = { mp4: 'MP4 Video (*.mp4)|*.mp4', mkv: 'MKV Video (*.mkv)|*.mkv', mov: 'MOV Video (*.mov)|*.mov', webm: 'WebM Video (*.webm)|*.webm', gif: 'GIF Image (*.gif)|*.gif' };
   try {
     const path = await invoke('browse_save_location', {
       defaultName: `${srcName} - Edited.${format}`,
@@ -11964,6 +12098,11 @@ function updateStoreLicenseInfo() {
     if (licenseState.email) emailEl.textContent = licenseState.email;
     else emailEl.textContent = '';
     countEl.textContent = `${count} module${count !== 1 ? 's' : ''} owned`;
+    if (licenseState.needs_refresh) countEl.textContent += ' · license needs refresh — connect to the internet';
+  } else if ((licenseState.expired_modules || []).length > 0) {
+    infoEl.hidden = false;
+    emailEl.textContent = licenseState.email || '';
+    countEl.textContent = 'License expired — connect to the internet to refresh it';
   } else {
     infoEl.hidden = true;
   }
@@ -12141,7 +12280,7 @@ async function deactivateLicense() {
     } catch (_) { /* fingerprint or key read failed — proceed with local deactivation */ }
 
     await invoke('deactivate_license');
-    licenseState = { owned_modules: [], email: null, activated_at: null };
+    licenseState = await invoke('get_license_state');
     cachedPresets = null;
     vstStatus = null;
     applyPanelVisibility();
@@ -12429,11 +12568,7 @@ initStore();
 
 (async () => {
   await loadLicenseState();
-  if (initialSettings.geminiApiKey) {
-    try {
-      await invoke('set_gemini_api_key', { apiKey: initialSettings.geminiApiKey });
-    } catch (_) {}
-  }
+  await migrateLegacyApiKey();
   await checkAiReady();
   await ensurePresetsLoaded();
 